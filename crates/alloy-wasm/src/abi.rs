@@ -0,0 +1,506 @@
+//! Generic ABI encode/decode driven by a JSON ABI fragment, instead of the
+//! two selectors frozen in [`crate`]'s `sol!` block (`balanceOf`/`transfer`).
+//! A dapp passes its contract's ABI at runtime and gets the same
+//! selector/head-tail encoding `sol!` would have generated at compile time.
+
+use std::str::FromStr;
+
+use alloy_primitives::{keccak256, Address, U256};
+use serde::Deserialize;
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+/// One entry of a Solidity ABI JSON array. Only the fields needed to locate
+/// a function and encode/decode its arguments are modeled.
+#[derive(Debug, Deserialize)]
+struct AbiEntry {
+    #[serde(rename = "type", default)]
+    entry_type: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    inputs: Vec<AbiParam>,
+    #[serde(default)]
+    outputs: Vec<AbiParam>,
+}
+
+/// One parameter of an [`AbiEntry`]; `components` is only populated for
+/// `tuple`/`tuple[]`/... types.
+#[derive(Debug, Deserialize)]
+struct AbiParam {
+    #[serde(rename = "type")]
+    ty: String,
+    #[serde(default)]
+    components: Vec<AbiParam>,
+}
+
+/// A parsed Solidity ABI type, recursively covering arrays and tuples.
+#[derive(Debug, Clone)]
+enum AbiType {
+    Uint(usize),
+    Int(usize),
+    Address,
+    Bool,
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<AbiType>),
+    FixedArray(Box<AbiType>, usize),
+    Tuple(Vec<AbiType>),
+}
+
+fn parse_type(ty: &str, components: &[AbiParam]) -> Result<AbiType, String> {
+    if let Some(open) = ty.rfind('[') {
+        if !ty.ends_with(']') {
+            return Err(format!("malformed array type `{}`", ty));
+        }
+        let base = &ty[..open];
+        let len_str = &ty[open + 1..ty.len() - 1];
+        let inner = Box::new(parse_type(base, components)?);
+        return Ok(if len_str.is_empty() {
+            AbiType::Array(inner)
+        } else {
+            let n: usize = len_str
+                .parse()
+                .map_err(|_| format!("invalid array length in `{}`", ty))?;
+            AbiType::FixedArray(inner, n)
+        });
+    }
+
+    Ok(match ty {
+        "address" => AbiType::Address,
+        "bool" => AbiType::Bool,
+        "bytes" => AbiType::Bytes,
+        "string" => AbiType::String,
+        "tuple" => AbiType::Tuple(
+            components
+                .iter()
+                .map(param_to_type)
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        _ if ty.starts_with("uint") => {
+            let bits = parse_bit_width(&ty[4..])?;
+            AbiType::Uint(bits)
+        }
+        _ if ty.starts_with("int") => {
+            let bits = parse_bit_width(&ty[3..])?;
+            AbiType::Int(bits)
+        }
+        _ if ty.starts_with("bytes") => {
+            let n: usize = ty[5..]
+                .parse()
+                .map_err(|_| format!("invalid fixed-bytes width in `{}`", ty))?;
+            AbiType::FixedBytes(n)
+        }
+        other => return Err(format!("unsupported ABI type `{}`", other)),
+    })
+}
+
+fn parse_bit_width(s: &str) -> Result<usize, String> {
+    if s.is_empty() {
+        return Ok(256);
+    }
+    s.parse().map_err(|_| format!("invalid bit width `{}`", s))
+}
+
+fn param_to_type(param: &AbiParam) -> Result<AbiType, String> {
+    parse_type(&param.ty, &param.components)
+}
+
+/// Canonical type name used in a function signature, e.g. `uint256`,
+/// `address[]`, `(uint256,address)[2]`.
+fn canonical_type(ty: &AbiType) -> String {
+    match ty {
+        AbiType::Uint(bits) => format!("uint{}", bits),
+        AbiType::Int(bits) => format!("int{}", bits),
+        AbiType::Address => "address".to_string(),
+        AbiType::Bool => "bool".to_string(),
+        AbiType::FixedBytes(n) => format!("bytes{}", n),
+        AbiType::Bytes => "bytes".to_string(),
+        AbiType::String => "string".to_string(),
+        AbiType::Array(inner) => format!("{}[]", canonical_type(inner)),
+        AbiType::FixedArray(inner, n) => format!("{}[{}]", canonical_type(inner), n),
+        AbiType::Tuple(components) => {
+            format!(
+                "({})",
+                components.iter().map(canonical_type).collect::<Vec<_>>().join(",")
+            )
+        }
+    }
+}
+
+fn is_dynamic(ty: &AbiType) -> bool {
+    match ty {
+        AbiType::Bytes | AbiType::String | AbiType::Array(_) => true,
+        AbiType::FixedArray(inner, _) => is_dynamic(inner),
+        AbiType::Tuple(components) => components.iter().any(is_dynamic),
+        _ => false,
+    }
+}
+
+/// Size in bytes of the head slot a static `ty` occupies (always a multiple
+/// of 32). Only meaningful when `!is_dynamic(ty)`.
+fn static_size(ty: &AbiType) -> usize {
+    match ty {
+        AbiType::FixedArray(inner, n) => static_size(inner) * n,
+        AbiType::Tuple(components) => components.iter().map(static_size).sum(),
+        _ => 32,
+    }
+}
+
+fn find_function<'a>(entries: &'a [AbiEntry], name: &str) -> Result<&'a AbiEntry, String> {
+    entries
+        .iter()
+        .find(|e| e.entry_type == "function" && e.name == name)
+        .ok_or_else(|| format!("no function `{}` in ABI", name))
+}
+
+fn selector(name: &str, inputs: &[AbiType]) -> [u8; 4] {
+    let signature = format!(
+        "{}({})",
+        name,
+        inputs.iter().map(canonical_type).collect::<Vec<_>>().join(",")
+    );
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+fn pad32(mut bytes: Vec<u8>) -> Vec<u8> {
+    let padding = (32 - bytes.len() % 32) % 32;
+    bytes.extend(std::iter::repeat(0u8).take(padding));
+    bytes
+}
+
+fn parse_uint(value: &Value) -> Result<U256, String> {
+    match value {
+        Value::Number(n) => n
+            .as_u64()
+            .map(U256::from)
+            .ok_or_else(|| "integer literal out of u64 range, pass it as a string".to_string()),
+        Value::String(s) => U256::from_str(s).map_err(|e| e.to_string()),
+        other => Err(format!("expected a number or numeric string, got {}", other)),
+    }
+}
+
+/// Two's-complement `bits`-wide signed encoding, stored in a full 32-byte
+/// word per the ABI spec (the sign bit is extended across the whole word
+/// regardless of the declared width).
+fn encode_int(value: &Value) -> Result<[u8; 32], String> {
+    let (negative, magnitude) = match value {
+        Value::Number(n) => {
+            let i = n
+                .as_i64()
+                .ok_or_else(|| "integer literal out of i64 range, pass it as a string".to_string())?;
+            (i < 0, U256::from(i.unsigned_abs()))
+        }
+        Value::String(s) => match s.strip_prefix('-') {
+            Some(rest) => (true, U256::from_str(rest).map_err(|e| e.to_string())?),
+            None => (false, U256::from_str(s).map_err(|e| e.to_string())?),
+        },
+        other => return Err(format!("expected a number or numeric string, got {}", other)),
+    };
+    let word = if negative { magnitude.wrapping_neg() } else { magnitude };
+    Ok(word.to_be_bytes::<32>())
+}
+
+fn decode_int_to_value(word: &[u8]) -> Result<Value, String> {
+    let raw = U256::from_be_slice(word);
+    if word[0] & 0x80 != 0 {
+        let magnitude = raw.wrapping_neg();
+        Ok(Value::String(format!("-{}", magnitude)))
+    } else {
+        Ok(Value::String(raw.to_string()))
+    }
+}
+
+pub(crate) fn decode_bytes_literal(s: &str) -> Result<Vec<u8>, String> {
+    let stripped = s.strip_prefix("0x").unwrap_or(s);
+    crate::hex::decode(stripped).map_err(|_| format!("invalid hex literal `{}`", s))
+}
+
+/// Encode an atomic (non-array, non-tuple, non-`bytes`/`string`) ABI value
+/// to its 32-byte word, shared with [`crate::eip712`]'s `encodeData` for
+/// atomic struct fields.
+pub(crate) fn encode_atomic(ty: &str, value: &Value) -> Result<[u8; 32], String> {
+    let parsed = parse_type(ty, &[])?;
+    match parsed {
+        AbiType::Bytes | AbiType::String | AbiType::Array(_) | AbiType::FixedArray(_, _) | AbiType::Tuple(_) => {
+            Err(format!("`{}` is not an atomic EIP-712 field type", ty))
+        }
+        other => {
+            let encoded = encode_value(&other, value)?;
+            let mut word = [0u8; 32];
+            word.copy_from_slice(&encoded);
+            Ok(word)
+        }
+    }
+}
+
+/// Encode a single value as it would appear inline (static types) or as the
+/// complete tail blob (dynamic types, including its own length prefix where
+/// the ABI spec calls for one).
+fn encode_value(ty: &AbiType, value: &Value) -> Result<Vec<u8>, String> {
+    match ty {
+        AbiType::Uint(_) => {
+            let n = parse_uint(value)?;
+            Ok(n.to_be_bytes::<32>().to_vec())
+        }
+        AbiType::Int(_) => Ok(encode_int(value)?.to_vec()),
+        AbiType::Bool => {
+            let b = value.as_bool().ok_or("expected a bool")?;
+            let mut word = [0u8; 32];
+            word[31] = b as u8;
+            Ok(word.to_vec())
+        }
+        AbiType::Address => {
+            let s = value.as_str().ok_or("expected an address string")?;
+            let addr: Address = s.parse().map_err(|e| format!("invalid address `{}`: {}", s, e))?;
+            let mut word = [0u8; 32];
+            word[12..].copy_from_slice(addr.as_slice());
+            Ok(word.to_vec())
+        }
+        AbiType::FixedBytes(n) => {
+            let s = value.as_str().ok_or("expected a hex string")?;
+            let bytes = decode_bytes_literal(s)?;
+            if bytes.len() != *n {
+                return Err(format!("bytes{} literal has {} bytes", n, bytes.len()));
+            }
+            let mut word = [0u8; 32];
+            word[..bytes.len()].copy_from_slice(&bytes);
+            Ok(word.to_vec())
+        }
+        AbiType::Bytes => {
+            let s = value.as_str().ok_or("expected a hex string")?;
+            let bytes = decode_bytes_literal(s)?;
+            let mut out = U256::from(bytes.len()).to_be_bytes::<32>().to_vec();
+            out.extend(pad32(bytes));
+            Ok(out)
+        }
+        AbiType::String => {
+            let s = value.as_str().ok_or("expected a string")?;
+            let bytes = s.as_bytes().to_vec();
+            let mut out = U256::from(bytes.len()).to_be_bytes::<32>().to_vec();
+            out.extend(pad32(bytes));
+            Ok(out)
+        }
+        AbiType::Array(inner) => {
+            let items = value.as_array().ok_or("expected an array")?;
+            let types: Vec<AbiType> = (0..items.len()).map(|_| (**inner).clone()).collect();
+            let mut out = U256::from(items.len()).to_be_bytes::<32>().to_vec();
+            out.extend(encode_data(&types, items)?);
+            Ok(out)
+        }
+        AbiType::FixedArray(inner, n) => {
+            let items = value.as_array().ok_or("expected an array")?;
+            if items.len() != *n {
+                return Err(format!("expected {} elements, got {}", n, items.len()));
+            }
+            let types: Vec<AbiType> = (0..*n).map(|_| (**inner).clone()).collect();
+            encode_data(&types, items)
+        }
+        AbiType::Tuple(components) => {
+            let items = value.as_array().ok_or("expected a tuple encoded as a JSON array")?;
+            if items.len() != components.len() {
+                return Err(format!(
+                    "tuple expects {} fields, got {}",
+                    components.len(),
+                    items.len()
+                ));
+            }
+            encode_data(components, items)
+        }
+    }
+}
+
+/// Head/tail-encode a sequence of `values` against `types` (used both for a
+/// function's top-level arguments and recursively for tuples/arrays).
+fn encode_data(types: &[AbiType], values: &[Value]) -> Result<Vec<u8>, String> {
+    if types.len() != values.len() {
+        return Err(format!("expected {} values, got {}", types.len(), values.len()));
+    }
+
+    let mut heads: Vec<Vec<u8>> = Vec::with_capacity(types.len());
+    let mut tails: Vec<Vec<u8>> = Vec::with_capacity(types.len());
+    for (ty, value) in types.iter().zip(values) {
+        let encoded = encode_value(ty, value)?;
+        if is_dynamic(ty) {
+            heads.push(vec![0u8; 32]);
+            tails.push(encoded);
+        } else {
+            heads.push(encoded);
+            tails.push(Vec::new());
+        }
+    }
+
+    let heads_len: usize = heads.iter().map(Vec::len).sum();
+    let mut tail_offset = heads_len;
+    let mut result = Vec::with_capacity(heads_len + tails.iter().map(Vec::len).sum::<usize>());
+    for (i, ty) in types.iter().enumerate() {
+        if is_dynamic(ty) {
+            result.extend(U256::from(tail_offset).to_be_bytes::<32>());
+            tail_offset += tails[i].len();
+        } else {
+            result.extend(&heads[i]);
+        }
+    }
+    for tail in tails {
+        result.extend(tail);
+    }
+    Ok(result)
+}
+
+fn decode_value(ty: &AbiType, data: &[u8]) -> Result<Value, String> {
+    match ty {
+        AbiType::Uint(_) => Ok(Value::String(U256::from_be_slice(word(data, 0)?).to_string())),
+        AbiType::Int(_) => decode_int_to_value(word(data, 0)?),
+        AbiType::Bool => Ok(Value::Bool(*word(data, 0)?.last().unwrap_or(&0) != 0)),
+        AbiType::Address => {
+            let w = word(data, 0)?;
+            Ok(Value::String(Address::from_slice(&w[12..]).to_string()))
+        }
+        AbiType::FixedBytes(n) => {
+            let w = word(data, 0)?;
+            Ok(Value::String(format!("0x{}", crate::hex::encode(&w[..*n]))))
+        }
+        AbiType::Bytes => {
+            let len = read_length(data)?;
+            let bytes = data.get(32..32 + len).ok_or("truncated bytes value")?;
+            Ok(Value::String(format!("0x{}", crate::hex::encode(bytes))))
+        }
+        AbiType::String => {
+            let len = read_length(data)?;
+            let bytes = data.get(32..32 + len).ok_or("truncated string value")?;
+            let s = std::str::from_utf8(bytes).map_err(|e| e.to_string())?;
+            Ok(Value::String(s.to_string()))
+        }
+        AbiType::Array(inner) => {
+            let len = read_length(data)?;
+            let types: Vec<AbiType> = (0..len).map(|_| (**inner).clone()).collect();
+            let values = decode_data(&types, data.get(32..).ok_or("truncated array value")?)?;
+            Ok(Value::Array(values))
+        }
+        AbiType::FixedArray(inner, n) => {
+            let types: Vec<AbiType> = (0..*n).map(|_| (**inner).clone()).collect();
+            Ok(Value::Array(decode_data(&types, data)?))
+        }
+        AbiType::Tuple(components) => Ok(Value::Array(decode_data(components, data)?)),
+    }
+}
+
+fn decode_data(types: &[AbiType], data: &[u8]) -> Result<Vec<Value>, String> {
+    let mut values = Vec::with_capacity(types.len());
+    let mut cursor = 0usize;
+    for ty in types {
+        if is_dynamic(ty) {
+            let offset = read_length(data.get(cursor..).ok_or("truncated head")?)?;
+            let tail = data.get(offset..).ok_or("dynamic offset out of bounds")?;
+            values.push(decode_value(ty, tail)?);
+            cursor += 32;
+        } else {
+            let size = static_size(ty);
+            let slot = data.get(cursor..cursor + size).ok_or("truncated static value")?;
+            values.push(decode_value(ty, slot)?);
+            cursor += size;
+        }
+    }
+    Ok(values)
+}
+
+fn word(data: &[u8], offset: usize) -> Result<&[u8], String> {
+    data.get(offset..offset + 32).ok_or_else(|| "truncated word".to_string())
+}
+
+fn read_length(data: &[u8]) -> Result<usize, String> {
+    let w = word(data, 0)?;
+    U256::from_be_slice(w)
+        .try_into()
+        .map_err(|_| "length exceeds usize".to_string())
+}
+
+/// Encode a call to `function` from `abi_json`, given its positional
+/// `args_json` array. Returns the 4-byte selector followed by the
+/// head/tail-encoded arguments, exactly as `sol!`-generated `abi_encode`
+/// would.
+#[wasm_bindgen]
+pub fn encode_call(abi_json: &str, function: &str, args_json: &str) -> Result<Vec<u8>, JsError> {
+    let entries: Vec<AbiEntry> =
+        serde_json::from_str(abi_json).map_err(|e| JsError::new(&format!("Invalid ABI JSON: {}", e)))?;
+    let entry = find_function(&entries, function).map_err(|e| JsError::new(&e))?;
+    let input_types = entry
+        .inputs
+        .iter()
+        .map(param_to_type)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| JsError::new(&e))?;
+
+    let args: Vec<Value> =
+        serde_json::from_str(args_json).map_err(|e| JsError::new(&format!("Invalid args JSON: {}", e)))?;
+
+    let mut out = selector(&entry.name, &input_types).to_vec();
+    out.extend(encode_data(&input_types, &args).map_err(|e| JsError::new(&e))?);
+    Ok(out)
+}
+
+/// Decode `data` (the return value of a call to `function`) per
+/// `abi_json`'s `outputs`, returning a JSON array of the decoded values
+/// positionally matching `outputs`.
+#[wasm_bindgen]
+pub fn decode_output(abi_json: &str, function: &str, data: &[u8]) -> Result<String, JsError> {
+    let entries: Vec<AbiEntry> =
+        serde_json::from_str(abi_json).map_err(|e| JsError::new(&format!("Invalid ABI JSON: {}", e)))?;
+    let entry = find_function(&entries, function).map_err(|e| JsError::new(&e))?;
+    let output_types = entry
+        .outputs
+        .iter()
+        .map(param_to_type)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| JsError::new(&e))?;
+
+    let values = decode_data(&output_types, data).map_err(|e| JsError::new(&e))?;
+    serde_json::to_string(&values).map_err(|e| JsError::new(&format!("{}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ERC20_ABI: &str = r#"[
+        {"type":"function","name":"balanceOf","inputs":[{"name":"account","type":"address"}],"outputs":[{"name":"","type":"uint256"}]},
+        {"type":"function","name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],"outputs":[{"name":"","type":"bool"}]},
+        {"type":"function","name":"batch","inputs":[{"name":"amounts","type":"uint256[]"}],"outputs":[]}
+    ]"#;
+
+    #[test]
+    fn test_encode_call_matches_known_selector() {
+        let args = r#"["0x00000000000000000000000000000000000000aa"]"#;
+        let encoded = encode_call(ERC20_ABI, "balanceOf", args).unwrap();
+        // selector for balanceOf(address)
+        assert_eq!(&encoded[..4], &[0x70, 0xa0, 0x82, 0x31]);
+        assert_eq!(encoded.len(), 4 + 32);
+    }
+
+    #[test]
+    fn test_encode_transfer_matches_known_selector() {
+        let args = r#"["0x00000000000000000000000000000000000000aa", "1000"]"#;
+        let encoded = encode_call(ERC20_ABI, "transfer", args).unwrap();
+        // selector for transfer(address,uint256)
+        assert_eq!(&encoded[..4], &[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(encoded.len(), 4 + 64);
+    }
+
+    #[test]
+    fn test_encode_dynamic_array() {
+        let args = r#"[["1", "2", "3"]]"#;
+        let encoded = encode_call(ERC20_ABI, "batch", args).unwrap();
+        // selector(4) + offset word(32) + length word(32) + 3 elements(96)
+        assert_eq!(encoded.len(), 4 + 32 + 32 + 96);
+    }
+
+    #[test]
+    fn test_decode_output_roundtrips_uint256() {
+        let mut data = vec![0u8; 32];
+        data[31] = 42;
+        let decoded = decode_output(ERC20_ABI, "balanceOf", &data).unwrap();
+        assert_eq!(decoded, "[\"42\"]");
+    }
+}
@@ -0,0 +1,222 @@
+//! Full EIP-712 typed-data signing from the standard `{types, primaryType,
+//! domain, message}` JSON object, instead of forcing the caller to compute
+//! the final digest themselves (as `sign_typed_data_hash` does). Atomic
+//! field encoding reuses [`crate::abi::encode_atomic`] -- the same ABI
+//! type-encoding machinery the generic call codec uses.
+
+use std::collections::{BTreeSet, HashMap};
+
+use alloy_primitives::keccak256;
+use serde::Deserialize;
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+
+use crate::abi;
+
+#[derive(Debug, Deserialize)]
+struct TypedData {
+    types: HashMap<String, Vec<FieldDef>>,
+    #[serde(rename = "primaryType")]
+    primary_type: String,
+    domain: Value,
+    message: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FieldDef {
+    name: String,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+/// The struct name referenced by `ty`, stripping every `[]`/`[N]` suffix
+/// (e.g. `Person[2][]` -> `Person`).
+fn base_type(ty: &str) -> &str {
+    ty.find('[').map(|idx| &ty[..idx]).unwrap_or(ty)
+}
+
+/// `ty` with its outermost array suffix removed (e.g. `Person[2][]` ->
+/// `Person[2]`), or `None` if `ty` isn't an array type.
+fn element_type(ty: &str) -> Option<&str> {
+    ty.rfind('[').map(|idx| &ty[..idx])
+}
+
+fn collect_deps(types: &HashMap<String, Vec<FieldDef>>, ty: &str, found: &mut BTreeSet<String>) {
+    let base = base_type(ty);
+    if found.contains(base) {
+        return;
+    }
+    let Some(fields) = types.get(base) else {
+        return;
+    };
+    found.insert(base.to_string());
+    for field in fields {
+        collect_deps(types, &field.ty, found);
+    }
+}
+
+/// `PrimaryType(type1 name1,type2 name2,...)` followed by every
+/// transitively-referenced struct type, alphabetically sorted, per EIP-712's
+/// `encodeType`.
+fn encode_type(types: &HashMap<String, Vec<FieldDef>>, primary_type: &str) -> Result<String, String> {
+    let mut deps = BTreeSet::new();
+    collect_deps(types, primary_type, &mut deps);
+    deps.remove(primary_type);
+
+    let mut ordered = vec![primary_type.to_string()];
+    ordered.extend(deps);
+
+    let mut out = String::new();
+    for ty in &ordered {
+        let fields = types
+            .get(ty)
+            .ok_or_else(|| format!("type `{}` not declared in `types`", ty))?;
+        out.push_str(ty);
+        out.push('(');
+        out.push_str(
+            &fields
+                .iter()
+                .map(|f| format!("{} {}", f.ty, f.name))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        out.push(')');
+    }
+    Ok(out)
+}
+
+fn type_hash(types: &HashMap<String, Vec<FieldDef>>, ty: &str) -> Result<[u8; 32], String> {
+    Ok(keccak256(encode_type(types, ty)?.as_bytes()).0)
+}
+
+/// Encode one field's value to its 32-byte contribution to `encodeData`:
+/// atomic values inline, `string`/`bytes` as `keccak256(contents)`, nested
+/// structs via `hashStruct`, and arrays as `keccak256` of their
+/// concatenated encoded elements.
+fn encode_field_value(
+    types: &HashMap<String, Vec<FieldDef>>,
+    field_type: &str,
+    value: &Value,
+) -> Result<[u8; 32], String> {
+    if let Some(elem) = element_type(field_type) {
+        let items = value
+            .as_array()
+            .ok_or_else(|| format!("expected an array for `{}`", field_type))?;
+        let mut concatenated = Vec::with_capacity(items.len() * 32);
+        for item in items {
+            concatenated.extend_from_slice(&encode_field_value(types, elem, item)?);
+        }
+        return Ok(keccak256(&concatenated).0);
+    }
+
+    match field_type {
+        "string" => Ok(keccak256(value.as_str().ok_or("expected a string")?.as_bytes()).0),
+        "bytes" => {
+            let s = value.as_str().ok_or("expected a hex string")?;
+            Ok(keccak256(&abi::decode_bytes_literal(s)?).0)
+        }
+        ty if types.contains_key(ty) => hash_struct(types, ty, value),
+        atomic => abi::encode_atomic(atomic, value),
+    }
+}
+
+fn encode_data(types: &HashMap<String, Vec<FieldDef>>, ty: &str, data: &Value) -> Result<Vec<u8>, String> {
+    let fields = types
+        .get(ty)
+        .ok_or_else(|| format!("type `{}` not declared in `types`", ty))?;
+
+    let mut out = type_hash(types, ty)?.to_vec();
+    for field in fields {
+        let value = data
+            .get(&field.name)
+            .ok_or_else(|| format!("missing field `{}` on `{}`", field.name, ty))?;
+        out.extend_from_slice(&encode_field_value(types, &field.ty, value)?);
+    }
+    Ok(out)
+}
+
+fn hash_struct(types: &HashMap<String, Vec<FieldDef>>, ty: &str, data: &Value) -> Result<[u8; 32], String> {
+    Ok(keccak256(&encode_data(types, ty, data)?).0)
+}
+
+/// Sign an EIP-712 typed-data payload, deriving the domain separator and
+/// message digest internally instead of requiring the caller to precompute
+/// a hash (see [`crate::sign_typed_data_hash`] for that lower-level path).
+#[wasm_bindgen]
+pub fn sign_typed_data(private_key: &str, typed_data_json: &str) -> Result<String, JsError> {
+    let typed: TypedData = serde_json::from_str(typed_data_json)
+        .map_err(|e| JsError::new(&format!("Invalid typed data JSON: {}", e)))?;
+
+    let domain_separator =
+        hash_struct(&typed.types, "EIP712Domain", &typed.domain).map_err(|e| JsError::new(&e))?;
+    let message_hash =
+        hash_struct(&typed.types, &typed.primary_type, &typed.message).map_err(|e| JsError::new(&e))?;
+
+    let mut preimage = Vec::with_capacity(2 + 32 + 32);
+    preimage.extend_from_slice(&[0x19, 0x01]);
+    preimage.extend_from_slice(&domain_separator);
+    preimage.extend_from_slice(&message_hash);
+    let digest = keccak256(&preimage);
+
+    let signer = crate::get_signer(private_key)?;
+    let sig = signer
+        .sign_hash_sync(&digest)
+        .map_err(|e| JsError::new(&format!("Signing failed: {}", e)))?;
+
+    Ok(crate::to_hex(&sig.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIL_TYPED_DATA: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ],
+            "Person": [
+                {"name": "name", "type": "string"},
+                {"name": "wallet", "type": "address"}
+            ],
+            "Mail": [
+                {"name": "from", "type": "Person"},
+                {"name": "to", "type": "Person"},
+                {"name": "contents", "type": "string"}
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+            "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    #[test]
+    fn test_encode_type_orders_referenced_structs_alphabetically() {
+        let typed: TypedData = serde_json::from_str(MAIL_TYPED_DATA).unwrap();
+        let encoded = encode_type(&typed.types, "Mail").unwrap();
+        assert_eq!(
+            encoded,
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+    }
+
+    #[test]
+    fn test_sign_typed_data_produces_65_byte_signature() {
+        let private_key = "0x0000000000000000000000000000000000000000000000000000000000000001";
+        let sig = sign_typed_data(private_key, MAIL_TYPED_DATA).unwrap();
+        let bytes = abi::decode_bytes_literal(&sig).unwrap();
+        assert_eq!(bytes.len(), 65);
+    }
+}
@@ -9,6 +9,10 @@ use alloy_rlp::Encodable;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+mod abi;
+mod eip712;
+mod tx_decode;
+
 #[wasm_bindgen(start)]
 pub fn init_panic_hook() {
     console_error_panic_hook::set_once();
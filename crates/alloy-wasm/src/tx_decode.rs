@@ -0,0 +1,220 @@
+//! Raw-transaction decoding, the read-side counterpart to [`crate::sign_eip7702_tx`]:
+//! RLP-decode an EIP-2718-typed raw transaction back into a structured JSON
+//! form (chain id, fees, access list, and -- for type `0x04` -- the
+//! `authorization_list` with recovered authority addresses) and recover the
+//! sender from the signature. The `authorization_list` entries are shaped as
+//! [`crate::SignedAuthorizationInput`] plus a recovered `authority`, so the
+//! output round-trips straight back into [`crate::sign_eip7702_tx`].
+
+use alloy_consensus::{SignableTransaction, Transaction, TxEnvelope};
+use alloy_eips::eip2718::Decodable2718;
+use alloy_eips::eip7702::{Authorization, SignedAuthorization};
+use alloy_primitives::{Signature, B256};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::SignedAuthorizationInput;
+
+#[derive(Serialize)]
+struct AccessListItemJson {
+    address: String,
+    storage_keys: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct DecodedAuthorization {
+    #[serde(flatten)]
+    input: SignedAuthorizationInput,
+    authority: String,
+}
+
+#[derive(Serialize)]
+struct DecodedTx {
+    tx_type: String,
+    hash: String,
+    from: String,
+    chain_id: Option<u64>,
+    nonce: u64,
+    gas_price: Option<String>,
+    max_priority_fee_per_gas: Option<String>,
+    max_fee_per_gas: String,
+    gas_limit: u64,
+    to: Option<String>,
+    value: String,
+    data: String,
+    access_list: Vec<AccessListItemJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authorization_list: Option<Vec<DecodedAuthorization>>,
+}
+
+fn decode_authorization(signed: &SignedAuthorization) -> Result<DecodedAuthorization, JsError> {
+    let auth = Authorization {
+        chain_id: signed.chain_id(),
+        address: signed.address(),
+        nonce: signed.nonce(),
+    };
+    let sig_hash = auth.signature_hash();
+    let sig = Signature::from_scalars_and_parity(
+        B256::from(signed.r().to_be_bytes::<32>()),
+        B256::from(signed.s().to_be_bytes::<32>()),
+        signed.y_parity() != 0,
+    );
+    let authority = sig
+        .recover_address_from_prehash(&sig_hash)
+        .map_err(|e| JsError::new(&format!("Failed to recover authority: {}", e)))?;
+
+    Ok(DecodedAuthorization {
+        input: SignedAuthorizationInput {
+            chain_id: signed.chain_id().try_into().unwrap_or(0),
+            address: format!("{:?}", signed.address()),
+            nonce: signed.nonce(),
+            y_parity: signed.y_parity(),
+            r: crate::to_hex(&signed.r().to_be_bytes::<32>()),
+            s: crate::to_hex(&signed.s().to_be_bytes::<32>()),
+        },
+        authority: format!("{:?}", authority),
+    })
+}
+
+/// Build the common (type-independent) half of [`DecodedTx`] from a tx that
+/// implements both [`Transaction`] (uniform field accessors) and
+/// [`SignableTransaction`] (the signing hash needed to recover the sender),
+/// plus the parts an [`alloy_consensus::Signed`] wrapper carries alongside it.
+fn build_decoded<T: Transaction + SignableTransaction<Signature>>(
+    tx: &T,
+    signature: &Signature,
+    hash: B256,
+    tx_type: &str,
+    authorization_list: Option<Vec<DecodedAuthorization>>,
+) -> Result<DecodedTx, JsError> {
+    let sig_hash = tx.signature_hash();
+    let from = signature
+        .recover_address_from_prehash(&sig_hash)
+        .map_err(|e| JsError::new(&format!("Failed to recover sender: {}", e)))?;
+
+    let access_list = tx
+        .access_list()
+        .map(|list| {
+            list.iter()
+                .map(|item| AccessListItemJson {
+                    address: format!("{:?}", item.address),
+                    storage_keys: item.storage_keys.iter().map(|k| crate::to_hex(k.as_slice())).collect(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(DecodedTx {
+        tx_type: tx_type.to_string(),
+        hash: crate::to_hex(hash.as_slice()),
+        from: format!("{:?}", from),
+        chain_id: tx.chain_id(),
+        nonce: tx.nonce(),
+        gas_price: tx.gas_price().map(|p| p.to_string()),
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas().map(|p| p.to_string()),
+        max_fee_per_gas: tx.max_fee_per_gas().to_string(),
+        gas_limit: tx.gas_limit(),
+        to: tx.to().map(|addr| format!("{:?}", addr)),
+        value: tx.value().to_string(),
+        data: crate::to_hex(tx.input()),
+        access_list,
+        authorization_list,
+    })
+}
+
+/// Decode a raw EIP-2718 transaction (`0x`-prefixed hex) back into structured
+/// JSON, recovering the sender and -- for an EIP-7702 (type `0x04`) payload
+/// -- each authorization's authority address.
+#[wasm_bindgen]
+pub fn decode_raw_tx(raw_tx: &str) -> Result<String, JsError> {
+    let bytes = crate::parse_hex(raw_tx)?;
+    let mut slice = bytes.as_slice();
+    let envelope = TxEnvelope::decode_2718(&mut slice)
+        .map_err(|e| JsError::new(&format!("Failed to RLP-decode transaction: {}", e)))?;
+
+    let decoded = match &envelope {
+        TxEnvelope::Legacy(signed) => {
+            build_decoded(signed.tx(), signed.signature(), *signed.hash(), "legacy", None)?
+        }
+        TxEnvelope::Eip2930(signed) => {
+            build_decoded(signed.tx(), signed.signature(), *signed.hash(), "eip2930", None)?
+        }
+        TxEnvelope::Eip1559(signed) => {
+            build_decoded(signed.tx(), signed.signature(), *signed.hash(), "eip1559", None)?
+        }
+        TxEnvelope::Eip4844(signed) => {
+            build_decoded(signed.tx(), signed.signature(), *signed.hash(), "eip4844", None)?
+        }
+        TxEnvelope::Eip7702(signed) => {
+            let auths = signed
+                .tx()
+                .authorization_list
+                .iter()
+                .map(decode_authorization)
+                .collect::<Result<Vec<_>, _>>()?;
+            build_decoded(signed.tx(), signed.signature(), *signed.hash(), "eip7702", Some(auths))?
+        }
+    };
+
+    serde_json::to_string(&decoded).map_err(|e| JsError::new(&format!("{}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIVATE_KEY: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+    #[test]
+    fn test_decode_raw_tx_round_trips_signed_eip7702_tx() {
+        let expected_from = crate::get_address(PRIVATE_KEY).unwrap();
+
+        let signed_auth: serde_json::Value = serde_json::from_str(
+            &crate::sign_authorization(
+                PRIVATE_KEY,
+                r#"{"chain_id":1,"contract_address":"0x0000000000000000000000000000000000000002","nonce":0}"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        let tx_request = serde_json::json!({
+            "chain_id": 1,
+            "nonce": 0,
+            "max_priority_fee_per_gas": "1000000000",
+            "max_fee_per_gas": "2000000000",
+            "gas_limit": 100000,
+            "to": "0x0000000000000000000000000000000000000001",
+            "value": "0",
+            "data": "0x",
+            "authorization_list": [{
+                "chain_id": signed_auth["chain_id"],
+                "address": signed_auth["address"],
+                "nonce": signed_auth["nonce"],
+                "y_parity": signed_auth["y_parity"],
+                "r": signed_auth["r"],
+                "s": signed_auth["s"],
+            }],
+        })
+        .to_string();
+
+        let signed: serde_json::Value =
+            serde_json::from_str(&crate::sign_eip7702_tx(PRIVATE_KEY, &tx_request).unwrap()).unwrap();
+        let raw_tx = signed["raw_tx"].as_str().unwrap();
+
+        let decoded: serde_json::Value = serde_json::from_str(&decode_raw_tx(raw_tx).unwrap()).unwrap();
+
+        assert_eq!(decoded["tx_type"], "eip7702");
+        assert_eq!(decoded["hash"], signed["tx_hash"]);
+        assert_eq!(decoded["from"], expected_from);
+        assert_eq!(decoded["chain_id"], 1);
+        assert_eq!(decoded["to"], "0x0000000000000000000000000000000000000001");
+        assert_eq!(decoded["authorization_list"].as_array().unwrap().len(), 1);
+        assert_eq!(decoded["authorization_list"][0]["authority"], expected_from);
+    }
+
+    #[test]
+    fn test_decode_raw_tx_rejects_garbage_input() {
+        assert!(decode_raw_tx("0x1234").is_err());
+    }
+}
@@ -12,47 +12,157 @@
 //! 4. Call `apply_range_delta()` with the merged delta
 
 use inspire_core::bucket_index::{
-    compute_bucket_id, compute_cumulative,
+    compute_bucket_id, packed,
     range_delta::{RangeDeltaHeader, RangeEntry, HEADER_SIZE, RANGE_ENTRY_SIZE},
-    BucketDelta as CoreDelta, NUM_BUCKETS,
+    BucketDelta as CoreDelta, DEFAULT_BUCKET_BITS, INDEX_HEADER_SIZE, NUM_BUCKETS,
 };
 use wasm_bindgen::prelude::*;
 
+/// A 1-indexed Fenwick (binary indexed) tree over `NUM_BUCKETS` bucket
+/// counts, giving both point updates and prefix sums in `O(log
+/// NUM_BUCKETS)`. Replaces recomputing the full `cumulative: Vec<u64>`
+/// array on every delta, which cost a pass over all 256K buckets for even
+/// a single-bucket update.
+struct FenwickTree {
+    tree: Vec<u64>,
+}
+
+impl FenwickTree {
+    fn new(counts: &[u16]) -> Self {
+        let mut fenwick = Self {
+            tree: vec![0u64; counts.len() + 1],
+        };
+        for (i, &count) in counts.iter().enumerate() {
+            fenwick.update(i + 1, count as i64);
+        }
+        fenwick
+    }
+
+    /// Add `delta` (may be negative) to the 1-indexed position `i`.
+    fn update(&mut self, mut i: usize, delta: i64) {
+        let n = self.tree.len() - 1;
+        while i <= n {
+            self.tree[i] = (self.tree[i] as i64 + delta) as u64;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Sum of the first `i` (1-indexed) positions, i.e. `counts[0..i]`.
+    fn prefix_sum(&self, mut i: usize) -> u64 {
+        let mut sum = 0u64;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+}
+
+/// Build a packed presence bitset (one bit per bucket, LSB-first within
+/// each `u64` word, set when `counts[i] > 0`), in the style of tantivy's
+/// `BitSet`.
+fn build_nonempty_bitmap(counts: &[u16]) -> Vec<u64> {
+    let mut bitmap = vec![0u64; counts.len().div_ceil(64)];
+    for (i, &count) in counts.iter().enumerate() {
+        if count > 0 {
+            bitmap[i / 64] |= 1u64 << (i % 64);
+        }
+    }
+    bitmap
+}
+
+fn set_bit(bitmap: &mut [u64], i: usize, value: bool) {
+    let bit = 1u64 << (i % 64);
+    if value {
+        bitmap[i / 64] |= bit;
+    } else {
+        bitmap[i / 64] &= !bit;
+    }
+}
+
+fn test_bit(bitmap: &[u64], i: usize) -> bool {
+    (bitmap[i / 64] >> (i % 64)) & 1 == 1
+}
+
 /// Bucket index for sparse PIR lookups (WASM-compatible)
 #[wasm_bindgen]
 pub struct BucketIndex {
     counts: Vec<u16>,
-    cumulative: Vec<u64>,
+    cumulative: FenwickTree,
+    bucket_bits: u8,
+    nonempty: Vec<u64>,
 }
 
 #[wasm_bindgen]
 impl BucketIndex {
-    /// Load bucket index from uncompressed binary (512 KB)
+    /// Load bucket index from uncompressed binary.
     /// Use /index/raw endpoint which returns uncompressed data for WASM clients.
+    ///
+    /// Wire format: `bucket_bits:1` header followed by `(1 << bucket_bits)`
+    /// little-endian `u16` counts, so a server exporting a small state can
+    /// ship a coarser index (e.g. 64K buckets) and a large one a finer one
+    /// (e.g. 1M buckets), with clients reading whichever granularity the
+    /// file advertises instead of assuming [`DEFAULT_BUCKET_BITS`].
     #[wasm_bindgen(constructor)]
     pub fn from_bytes(data: &[u8]) -> Result<BucketIndex, JsValue> {
-        if data.len() != NUM_BUCKETS * 2 {
+        if data.len() < INDEX_HEADER_SIZE {
+            return Err(JsValue::from_str("Data too short for bucket index header"));
+        }
+        let bucket_bits = data[0];
+        let num_buckets = 1usize << bucket_bits;
+        let payload = &data[INDEX_HEADER_SIZE..];
+
+        if payload.len() != num_buckets * 2 {
             return Err(JsValue::from_str(&format!(
                 "Invalid bucket index size: expected {}, got {}",
-                NUM_BUCKETS * 2,
-                data.len()
+                num_buckets * 2,
+                payload.len()
             )));
         }
 
-        let mut counts = Vec::with_capacity(NUM_BUCKETS);
-        for chunk in data.chunks_exact(2) {
+        let mut counts = Vec::with_capacity(num_buckets);
+        for chunk in payload.chunks_exact(2) {
             counts.push(u16::from_le_bytes([chunk[0], chunk[1]]));
         }
 
-        let cumulative = compute_cumulative(&counts);
+        let cumulative = FenwickTree::new(&counts);
+        let nonempty = build_nonempty_bitmap(&counts);
 
-        Ok(BucketIndex { counts, cumulative })
+        Ok(BucketIndex { counts, cumulative, bucket_bits, nonempty })
+    }
+
+    /// Load bucket index from the bit-packed format served at
+    /// `/index/packed`, far smaller than the fixed `/index/raw` payload
+    /// when most bucket counts are small. Falls back to
+    /// [`BucketIndex::from_bytes`] for clients that fetch the raw format.
+    /// Wire format: the same `bucket_bits:1` header as `from_bytes`,
+    /// followed by a [`packed::pack`] payload.
+    pub fn from_packed_bytes(data: &[u8]) -> Result<BucketIndex, JsValue> {
+        if data.len() < INDEX_HEADER_SIZE {
+            return Err(JsValue::from_str("Data too short for bucket index header"));
+        }
+        let bucket_bits = data[0];
+        let num_buckets = 1usize << bucket_bits;
+
+        let counts = packed::unpack(&data[INDEX_HEADER_SIZE..], num_buckets)
+            .ok_or_else(|| JsValue::from_str("Invalid packed bucket index"))?;
+
+        let cumulative = FenwickTree::new(&counts);
+        let nonempty = build_nonempty_bitmap(&counts);
+
+        Ok(BucketIndex { counts, cumulative, bucket_bits, nonempty })
+    }
+
+    /// Active bucket granularity of this index (see [`compute_bucket_id`])
+    #[wasm_bindgen(getter)]
+    pub fn bucket_bits(&self) -> u8 {
+        self.bucket_bits
     }
 
     /// Get total number of entries across all buckets
     #[wasm_bindgen(getter)]
     pub fn total_entries(&self) -> u64 {
-        self.cumulative[NUM_BUCKETS]
+        self.cumulative.prefix_sum(self.counts.len())
     }
 
     /// Look up the bucket range for a (address, slot) pair
@@ -69,8 +179,8 @@ impl BucketIndex {
         let addr: [u8; 20] = address.try_into().unwrap();
         let sl: [u8; 32] = slot.try_into().unwrap();
 
-        let bucket_id = compute_bucket_id(&addr, &sl);
-        let start = self.cumulative[bucket_id];
+        let bucket_id = compute_bucket_id(&addr, &sl, self.bucket_bits);
+        let start = self.cumulative.prefix_sum(bucket_id);
         let count = self.counts[bucket_id] as u64;
 
         Ok(vec![bucket_id as u64, start, count])
@@ -83,7 +193,40 @@ impl BucketIndex {
 
     /// Get start index for a specific bucket
     pub fn bucket_start(&self, bucket_id: usize) -> u64 {
-        self.cumulative.get(bucket_id).copied().unwrap_or(0)
+        if bucket_id > self.counts.len() {
+            return 0;
+        }
+        self.cumulative.prefix_sum(bucket_id)
+    }
+
+    /// Fast "address not present" check: true if `bucket_id` is empty or
+    /// out of range. Backed by [`BucketIndex::nonempty_bitmap`], so it never
+    /// touches the Fenwick structure.
+    pub fn is_bucket_empty(&self, bucket_id: usize) -> bool {
+        if bucket_id >= self.counts.len() {
+            return true;
+        }
+        !test_bit(&self.nonempty, bucket_id)
+    }
+
+    /// Packed presence bitset: one bit per bucket, LSB-first within each
+    /// `u64` word, set when the bucket is nonempty. Lets a client iterate
+    /// only populated buckets in an address-prefix window without issuing a
+    /// PIR query per bucket.
+    pub fn nonempty_bitmap(&self) -> Vec<u64> {
+        self.nonempty.clone()
+    }
+
+    /// Contiguous span covering buckets `[start_bucket, end_bucket)`, as
+    /// `[start_index, total_count]`, via two Fenwick prefix-sum queries.
+    /// Lets a client coalesce a run of adjacent nonempty buckets into a
+    /// single PIR range request instead of one request per bucket.
+    pub fn lookup_range(&self, start_bucket: usize, end_bucket: usize) -> Vec<u64> {
+        let start_bucket = start_bucket.min(self.counts.len());
+        let end_bucket = end_bucket.min(self.counts.len()).max(start_bucket);
+        let start_index = self.cumulative.prefix_sum(start_bucket);
+        let end_index = self.cumulative.prefix_sum(end_bucket);
+        vec![start_index, end_index - start_index]
     }
 
     /// Apply a delta update (from websocket)
@@ -94,14 +237,14 @@ impl BucketIndex {
         let delta = CoreDelta::from_bytes(data).map_err(|e| JsValue::from_str(&e.to_string()))?;
 
         for &(bucket_id, new_count) in &delta.updates {
-            if bucket_id < NUM_BUCKETS {
+            if bucket_id < self.counts.len() {
+                let diff = new_count as i64 - self.counts[bucket_id] as i64;
+                self.cumulative.update(bucket_id + 1, diff);
                 self.counts[bucket_id] = new_count;
+                set_bit(&mut self.nonempty, bucket_id, new_count > 0);
             }
         }
 
-        // Recompute cumulative sums
-        self.cumulative = compute_cumulative(&self.counts);
-
         Ok(delta.block_number)
     }
 
@@ -120,6 +263,7 @@ impl BucketIndex {
 #[wasm_bindgen]
 pub struct RangeDeltaInfo {
     current_block: u64,
+    bucket_bits: u8,
     ranges: Vec<RangeInfoEntry>,
 }
 
@@ -162,6 +306,7 @@ impl RangeDeltaInfo {
 
         Ok(RangeDeltaInfo {
             current_block: header.current_block,
+            bucket_bits: header.bucket_bits,
             ranges,
         })
     }
@@ -172,6 +317,14 @@ impl RangeDeltaInfo {
         self.current_block
     }
 
+    /// Bucket granularity of the base index these deltas apply to, so a
+    /// client can confirm its [`BucketIndex::bucket_bits`] still matches
+    /// before applying a delta stream.
+    #[wasm_bindgen(getter)]
+    pub fn bucket_bits(&self) -> u8 {
+        self.bucket_bits
+    }
+
     /// Get number of ranges
     #[wasm_bindgen(getter)]
     pub fn num_ranges(&self) -> usize {
@@ -222,17 +375,18 @@ mod tests {
         let address = [0x42u8; 20];
         let slot = [0x01u8; 32];
 
-        let id1 = compute_bucket_id(&address, &slot);
-        let id2 = compute_bucket_id(&address, &slot);
+        let id1 = compute_bucket_id(&address, &slot, DEFAULT_BUCKET_BITS);
+        let id2 = compute_bucket_id(&address, &slot, DEFAULT_BUCKET_BITS);
         assert_eq!(id1, id2);
         assert!(id1 < NUM_BUCKETS);
     }
 
     #[wasm_bindgen_test]
     fn test_bucket_index_from_bytes() {
-        let mut data = vec![0u8; NUM_BUCKETS * 2];
-        data[0] = 10; // bucket 0 = 10
-        data[2] = 5; // bucket 1 = 5
+        let mut data = vec![0u8; INDEX_HEADER_SIZE + NUM_BUCKETS * 2];
+        data[0] = DEFAULT_BUCKET_BITS;
+        data[INDEX_HEADER_SIZE] = 10; // bucket 0 = 10
+        data[INDEX_HEADER_SIZE + 2] = 5; // bucket 1 = 5
 
         let index = BucketIndex::from_bytes(&data).unwrap();
 
@@ -243,10 +397,29 @@ mod tests {
         assert_eq!(index.bucket_start(2), 15);
     }
 
+    #[wasm_bindgen_test]
+    fn test_is_bucket_empty_and_lookup_range() {
+        let mut data = vec![0u8; INDEX_HEADER_SIZE + NUM_BUCKETS * 2];
+        data[0] = DEFAULT_BUCKET_BITS;
+        data[INDEX_HEADER_SIZE] = 10; // bucket 0 = 10
+        data[INDEX_HEADER_SIZE + 2 * 2] = 5; // bucket 2 = 5
+
+        let index = BucketIndex::from_bytes(&data).unwrap();
+
+        assert!(!index.is_bucket_empty(0));
+        assert!(index.is_bucket_empty(1));
+        assert!(!index.is_bucket_empty(2));
+        assert!(index.is_bucket_empty(NUM_BUCKETS)); // out of range
+
+        assert_eq!(index.lookup_range(0, 3), vec![0, 15]);
+        assert_eq!(index.lookup_range(1, 2), vec![10, 0]);
+    }
+
     #[wasm_bindgen_test]
     fn test_apply_delta() {
-        let mut data = vec![0u8; NUM_BUCKETS * 2];
-        data[0] = 10;
+        let mut data = vec![0u8; INDEX_HEADER_SIZE + NUM_BUCKETS * 2];
+        data[0] = DEFAULT_BUCKET_BITS;
+        data[INDEX_HEADER_SIZE] = 10;
 
         let mut index = BucketIndex::from_bytes(&data).unwrap();
         assert_eq!(index.bucket_count(0), 10);
@@ -254,20 +427,23 @@ mod tests {
         // Create delta bytes
         let delta = CoreDelta {
             block_number: 42,
-            updates: vec![(0, 15)],
+            updates: vec![(0, 15), (1, 0)],
         };
         let delta_bytes = delta.to_bytes();
 
         let block = index.apply_delta(&delta_bytes).unwrap();
         assert_eq!(block, 42);
         assert_eq!(index.bucket_count(0), 15);
+        assert!(!index.is_bucket_empty(0));
+        assert!(index.is_bucket_empty(1));
     }
 
     #[wasm_bindgen_test]
     fn test_apply_range_delta() {
-        let mut data = vec![0u8; NUM_BUCKETS * 2];
-        data[0] = 10;
-        data[2] = 5;
+        let mut data = vec![0u8; INDEX_HEADER_SIZE + NUM_BUCKETS * 2];
+        data[0] = DEFAULT_BUCKET_BITS;
+        data[INDEX_HEADER_SIZE] = 10;
+        data[INDEX_HEADER_SIZE + 2] = 5;
 
         let mut index = BucketIndex::from_bytes(&data).unwrap();
         assert_eq!(index.bucket_count(0), 10);
@@ -292,6 +468,7 @@ mod tests {
         use inspire_core::bucket_index::range_delta::{
             RangeDeltaHeader, RangeEntry, DEFAULT_RANGES, HEADER_SIZE, RANGE_ENTRY_SIZE, VERSION,
         };
+        use inspire_core::bucket_index::DEFAULT_BUCKET_BITS;
 
         // Create a minimal header + directory
         let mut data = vec![0u8; HEADER_SIZE + DEFAULT_RANGES.len() * RANGE_ENTRY_SIZE];
@@ -301,6 +478,7 @@ mod tests {
             version: VERSION,
             current_block: 12345,
             num_ranges: DEFAULT_RANGES.len() as u32,
+            bucket_bits: DEFAULT_BUCKET_BITS,
         };
         data[..HEADER_SIZE].copy_from_slice(&header.to_bytes());
 
@@ -334,6 +512,7 @@ mod tests {
         use inspire_core::bucket_index::range_delta::{
             RangeDeltaHeader, RangeEntry, DEFAULT_RANGES, HEADER_SIZE, RANGE_ENTRY_SIZE, VERSION,
         };
+        use inspire_core::bucket_index::DEFAULT_BUCKET_BITS;
 
         // Create header + directory
         let mut data = vec![0u8; HEADER_SIZE + DEFAULT_RANGES.len() * RANGE_ENTRY_SIZE];
@@ -341,6 +520,7 @@ mod tests {
             version: VERSION,
             current_block: 12345,
             num_ranges: DEFAULT_RANGES.len() as u32,
+            bucket_bits: DEFAULT_BUCKET_BITS,
         };
         data[..HEADER_SIZE].copy_from_slice(&header.to_bytes());
 
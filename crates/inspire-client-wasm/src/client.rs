@@ -12,7 +12,7 @@ use inspire_pir::{
 use inspire_pir::math::GaussianSampler;
 use inspire_pir::params::ShardConfig;
 use inspire_pir::rlwe::RlweSecretKey;
-use inspire_core::PIR_PARAMS_VERSION;
+use inspire_core::{TwoLaneConfig, PIR_PARAMS, PIR_PARAMS_VERSION};
 
 use crate::console_log;
 use crate::error::PirError;
@@ -20,7 +20,14 @@ use crate::transport::HttpClient;
 
 #[derive(Deserialize)]
 struct ServerInfo {
+    version: String,
     pir_params_version: u16,
+    pir_params_min_supported: u16,
+    pir_params_max_supported: u16,
+    pir_params_id: String,
+    config_hash: String,
+    hot_entries: u64,
+    cold_entries: u64,
 }
 
 #[derive(Deserialize)]
@@ -75,24 +82,53 @@ impl PirClient {
             .await
             .map_err(PirError::from)?;
 
-        if info.pir_params_version != PIR_PARAMS_VERSION {
-            return Err(PirError::VersionMismatch {
+        if PIR_PARAMS_VERSION < info.pir_params_min_supported
+            || PIR_PARAMS_VERSION > info.pir_params_max_supported
+        {
+            return Err(PirError::VersionUnsupported {
                 client: PIR_PARAMS_VERSION,
-                server: info.pir_params_version,
+                min_supported: info.pir_params_min_supported,
+                max_supported: info.pir_params_max_supported,
             }.into());
         }
 
-        console_log!("Version check passed: v{}", PIR_PARAMS_VERSION);
+        console_log!(
+            "Version check passed: client v{} within server's v{}-v{} window",
+            PIR_PARAMS_VERSION, info.pir_params_min_supported, info.pir_params_max_supported
+        );
+
+        let client_params_id = PIR_PARAMS.params_id();
+        if client_params_id != info.pir_params_id {
+            return Err(PirError::ParamsIdMismatch {
+                client: client_params_id,
+                server: info.pir_params_id,
+            }.into());
+        }
+        console_log!("Params id check passed: {}", info.pir_params_id);
         console_log!("Fetching CRS for lane: {}", lane);
-        
+
         let crs_resp: CrsResponse = http
             .get(&format!("/crs/{}", lane))
             .await
             .map_err(PirError::from)?;
-        
+
         let crs: ServerCrs = serde_json::from_str(&crs_resp.crs)
             .map_err(|e| PirError::Serialization(e.to_string()))?;
-        
+
+        let client_config_hash = TwoLaneConfig::compute_hash_from(
+            info.hot_entries,
+            info.cold_entries,
+            crs_resp.shard_config.entry_size_bytes,
+            &info.version,
+        );
+        if client_config_hash != info.config_hash {
+            return Err(PirError::ConfigHashMismatch {
+                client: client_config_hash,
+                server: info.config_hash,
+            }.into());
+        }
+
+        console_log!("Config hash check passed: {}", info.config_hash);
         console_log!("Generating secret key...");
         let mut sampler = GaussianSampler::new(crs.params.sigma);
         let secret_key = RlweSecretKey::generate(&crs.params, &mut sampler);
@@ -120,13 +156,51 @@ impl PirClient {
     #[wasm_bindgen]
     pub async fn query(&self, index: u64) -> Result<Vec<u8>, JsValue> {
         let inner = self.inner.as_ref().ok_or(PirError::NotInitialized)?;
+        console_log!("Building PIR query for index {}", index);
+        let entry = self.query_index(inner, index).await?;
+        Ok(entry)
+    }
+
+    /// Queries several indices in one call. Each index is still its own
+    /// seeded PIR exchange, issued one after another in input order; the
+    /// indices are not packed into a single combined request. An out-of-bounds index
+    /// doesn't fail the whole batch -- see [`BatchQueryResult`] and
+    /// [`PirError::PartialBatch`].
+    #[wasm_bindgen]
+    pub async fn query_batch(&self, indices: Vec<u64>) -> Result<BatchQueryResult, JsValue> {
+        let inner = self.inner.as_ref().ok_or(PirError::NotInitialized)?;
+
+        let mut succeeded = Vec::new();
+        let mut failed = Vec::new();
+
+        for (position, &index) in indices.iter().enumerate() {
+            match self.query_index(inner, index).await {
+                Ok(entry) => succeeded.push((position as u64, entry)),
+                Err(e) => failed.push((position as u64, e.to_string())),
+            }
+        }
+
+        if !failed.is_empty() {
+            console_log!(
+                "{}",
+                PirError::PartialBatch {
+                    succeeded: succeeded.clone(),
+                    failed: failed.clone(),
+                }
+            );
+        }
+
+        Ok(BatchQueryResult::new(succeeded, failed))
+    }
+
+    #[wasm_bindgen]
+    pub async fn query_binary(&self, index: u64) -> Result<Vec<u8>, JsValue> {
+        let inner = self.inner.as_ref().ok_or(PirError::NotInitialized)?;
         
         if index >= inner.entry_count {
             return Err(PirError::IndexOutOfBounds(index).into());
         }
         
-        console_log!("Building PIR query for index {}", index);
-        
         let mut sampler = GaussianSampler::new(inner.crs.params.sigma);
         let (client_state, seeded_query) = pir_query_seeded(
             &inner.crs,
@@ -136,33 +210,35 @@ impl PirClient {
             &mut sampler,
         ).map_err(|e| PirError::Pir(e.to_string()))?;
         
-        console_log!("Sending seeded query...");
-        
-        let response: QueryResponse = inner.http
-            .post_json(&format!("/query/{}/seeded", inner.lane), &SeededQueryRequest { query: seeded_query })
+        let bytes = inner.http
+            .post_json_binary(&format!("/query/{}/seeded/binary", inner.lane), &SeededQueryRequest { query: seeded_query })
             .await
             .map_err(PirError::from)?;
         
-        console_log!("Extracting result...");
+        let response = ServerResponse::from_binary(&bytes)
+            .map_err(|e| PirError::Pir(e.to_string()))?;
         
         let entry = extract(
             &inner.crs,
             &client_state,
-            &response.response,
+            &response,
             64,
         ).map_err(|e| PirError::Pir(e.to_string()))?;
         
         Ok(entry)
     }
+}
 
-    #[wasm_bindgen]
-    pub async fn query_binary(&self, index: u64) -> Result<Vec<u8>, JsValue> {
-        let inner = self.inner.as_ref().ok_or(PirError::NotInitialized)?;
-        
+impl PirClient {
+    /// Shared seeded-query round trip used by both `query` and
+    /// `query_batch`, so a batch issues the same exchange per index as a
+    /// single `query()` call would -- just without re-deriving a fresh
+    /// `GaussianSampler` call site for every caller.
+    async fn query_index(&self, inner: &ClientInner, index: u64) -> Result<Vec<u8>, PirError> {
         if index >= inner.entry_count {
-            return Err(PirError::IndexOutOfBounds(index).into());
+            return Err(PirError::IndexOutOfBounds(index));
         }
-        
+
         let mut sampler = GaussianSampler::new(inner.crs.params.sigma);
         let (client_state, seeded_query) = pir_query_seeded(
             &inner.crs,
@@ -171,23 +247,79 @@ impl PirClient {
             &inner.secret_key,
             &mut sampler,
         ).map_err(|e| PirError::Pir(e.to_string()))?;
-        
-        let bytes = inner.http
-            .post_json_binary(&format!("/query/{}/seeded/binary", inner.lane), &SeededQueryRequest { query: seeded_query })
+
+        let response: QueryResponse = inner.http
+            .post_json(&format!("/query/{}/seeded", inner.lane), &SeededQueryRequest { query: seeded_query })
             .await
             .map_err(PirError::from)?;
-        
-        let response = ServerResponse::from_binary(&bytes)
-            .map_err(|e| PirError::Pir(e.to_string()))?;
-        
-        let entry = extract(
-            &inner.crs,
-            &client_state,
-            &response,
-            64,
-        ).map_err(|e| PirError::Pir(e.to_string()))?;
-        
-        Ok(entry)
+
+        extract(&inner.crs, &client_state, &response.response, 64)
+            .map_err(|e| PirError::Pir(e.to_string()))
+    }
+}
+
+/// The outcome of [`PirClient::query_batch`]: entries for every index that
+/// succeeded plus the positions and messages for any that didn't, so a
+/// caller can use the valid results instead of the whole batch failing for
+/// one bad index. Plain getters rather than a `serde`-serialized blob since
+/// that's the convention this crate already uses for multi-value wasm
+/// returns (see [`crate::bucket_index::BucketIndex::lookup`]).
+#[wasm_bindgen]
+pub struct BatchQueryResult {
+    succeeded_positions: Vec<u64>,
+    /// Concatenated entry bytes for every succeeded position, in the same
+    /// order as `succeeded_positions` (64 bytes each, see `extract`'s
+    /// `entry_size` argument in `query_index`).
+    entries: Vec<u8>,
+    failed_positions: Vec<u64>,
+    failed_messages: Vec<String>,
+}
+
+impl BatchQueryResult {
+    fn new(succeeded: Vec<(u64, Vec<u8>)>, failed: Vec<(u64, String)>) -> Self {
+        let mut succeeded_positions = Vec::with_capacity(succeeded.len());
+        let mut entries = Vec::with_capacity(succeeded.len() * 64);
+        for (position, entry) in succeeded {
+            succeeded_positions.push(position);
+            entries.extend_from_slice(&entry);
+        }
+
+        let (failed_positions, failed_messages) = failed.into_iter().unzip();
+
+        Self {
+            succeeded_positions,
+            entries,
+            failed_positions,
+            failed_messages,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl BatchQueryResult {
+    /// Original batch positions (not database indices) that succeeded, in
+    /// the same order as `entries`'s 64-byte chunks.
+    #[wasm_bindgen(getter)]
+    pub fn succeeded_positions(&self) -> Vec<u64> {
+        self.succeeded_positions.clone()
+    }
+
+    /// Concatenated 64-byte entries for every succeeded position.
+    #[wasm_bindgen(getter)]
+    pub fn entries(&self) -> Vec<u8> {
+        self.entries.clone()
+    }
+
+    /// Original batch positions that failed (e.g. out of bounds).
+    #[wasm_bindgen(getter)]
+    pub fn failed_positions(&self) -> Vec<u64> {
+        self.failed_positions.clone()
+    }
+
+    /// Error messages parallel to `failed_positions`.
+    #[wasm_bindgen(getter)]
+    pub fn failed_messages(&self) -> Vec<String> {
+        self.failed_messages.clone()
     }
 }
 
@@ -203,4 +335,17 @@ mod tests {
         let client = PirClient::new("http://localhost:3000".to_string());
         assert!(client.inner.is_none());
     }
+
+    #[wasm_bindgen_test]
+    fn test_batch_query_result_splits_succeeded_and_failed() {
+        let result = BatchQueryResult::new(
+            vec![(0, vec![0xaa; 64]), (2, vec![0xbb; 64])],
+            vec![(1, "index 99 out of bounds".to_string())],
+        );
+
+        assert_eq!(result.succeeded_positions(), vec![0, 2]);
+        assert_eq!(result.entries(), [[0xaa; 64], [0xbb; 64]].concat());
+        assert_eq!(result.failed_positions(), vec![1]);
+        assert_eq!(result.failed_messages(), vec!["index 99 out of bounds".to_string()]);
+    }
 }
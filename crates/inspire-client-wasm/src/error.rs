@@ -9,7 +9,31 @@ pub enum PirError {
     Pir(String),
     NotInitialized,
     IndexOutOfBounds(u64),
-    VersionMismatch { client: u16, server: u16 },
+    /// Client's `PIR_PARAMS_VERSION` falls outside the server's advertised
+    /// `[min_supported, max_supported]` window.
+    VersionUnsupported { client: u16, min_supported: u16, max_supported: u16 },
+    /// Server's advertised `pir_params_id` disagrees with the client's
+    /// compiled-in `PIR_PARAMS`'s fingerprint -- same `pir_params_version`,
+    /// but different cryptographic parameters (q/sigma/gadget/etc), which
+    /// `VersionUnsupported` alone wouldn't catch.
+    ParamsIdMismatch { client: String, server: String },
+    /// Server's advertised `config_hash` disagrees with the hash the client
+    /// recomputed from the server's advertised hot/cold entry counts and
+    /// version plus the CRS's `entry_size_bytes` -- the client
+    /// and server disagree on database shape (e.g. a stale hot/cold lane
+    /// split) even though the params version matched.
+    ConfigHashMismatch { client: String, server: String },
+    /// A batch query where some indices succeeded and others failed (e.g.
+    /// one index was out of bounds). Positions are offsets into the
+    /// original batch request, not database indices, so a caller can match
+    /// a failure back to the slot it asked for. Used as a diagnostic
+    /// summary for [`crate::client::BatchQueryResult`], which carries the
+    /// same data back across the wasm boundary in a form JS can read
+    /// field-by-field rather than just this type's `Display` string.
+    PartialBatch {
+        succeeded: Vec<(u64, Vec<u8>)>,
+        failed: Vec<(u64, String)>,
+    },
 }
 
 impl std::fmt::Display for PirError {
@@ -20,13 +44,37 @@ impl std::fmt::Display for PirError {
             PirError::Pir(msg) => write!(f, "PIR error: {}", msg),
             PirError::NotInitialized => write!(f, "Client not initialized"),
             PirError::IndexOutOfBounds(idx) => write!(f, "Index {} out of bounds", idx),
-            PirError::VersionMismatch { client, server } => {
+            PirError::VersionUnsupported { client, min_supported, max_supported } => {
                 write!(
                     f,
-                    "PIR params version mismatch: client v{}, server v{}. Update client or regenerate server CRS.",
+                    "PIR params version v{} unsupported: server accepts v{}-v{}. Update client or regenerate server CRS.",
+                    client, min_supported, max_supported
+                )
+            }
+            PirError::ParamsIdMismatch { client, server } => {
+                write!(
+                    f,
+                    "PIR params id mismatch: client computed {}, server advertised {}. Same params version, different cryptographic parameters -- update client or regenerate server CRS.",
                     client, server
                 )
             }
+            PirError::ConfigHashMismatch { client, server } => {
+                write!(
+                    f,
+                    "Config hash mismatch: client computed {}, server advertised {}. Hot/cold lane shape disagrees between client and server.",
+                    client, server
+                )
+            }
+            PirError::PartialBatch { succeeded, failed } => {
+                let failed_positions: Vec<u64> = failed.iter().map(|(pos, _)| *pos).collect();
+                write!(
+                    f,
+                    "Batch query: {} succeeded, {} failed (positions: {:?})",
+                    succeeded.len(),
+                    failed.len(),
+                    failed_positions
+                )
+            }
         }
     }
 }
@@ -37,6 +37,143 @@ use inspire_core::ubt::{
 };
 use wasm_bindgen::prelude::*;
 
+/// Magic bytes identifying the tagged (compressed + checksummed) stem index
+/// format, to distinguish it from the legacy raw `count + (stem+offset)*`
+/// format that `StemIndex::from_bytes` still accepts unchanged.
+const STEM_INDEX_MAGIC: [u8; 4] = *b"STX1";
+
+/// Tagged format version
+const STEM_INDEX_VERSION: u8 = 1;
+
+/// Compressor applied to the at-rest tagged stem index (and, by the same
+/// scheme, encoded database shards).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionType {
+    /// No compression
+    None,
+    /// LZ4 block compression (fast, modest ratio)
+    Lz4,
+    /// Deflate/miniz at the given level (1-9, slower, better ratio)
+    Miniz(u8),
+}
+
+impl CompressionType {
+    fn tag(&self) -> u8 {
+        match self {
+            CompressionType::None => 0,
+            CompressionType::Lz4 => 1,
+            CompressionType::Miniz(_) => 2,
+        }
+    }
+
+    fn level(&self) -> u8 {
+        match self {
+            CompressionType::Miniz(level) => *level,
+            _ => 0,
+        }
+    }
+
+    fn from_tag(tag: u8, level: u8) -> Result<Self, JsValue> {
+        match tag {
+            0 => Ok(CompressionType::None),
+            1 => Ok(CompressionType::Lz4),
+            2 => Ok(CompressionType::Miniz(level)),
+            other => Err(JsValue::from_str(&format!("Unknown compression tag: {}", other))),
+        }
+    }
+
+    fn compress(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            CompressionType::None => data.to_vec(),
+            CompressionType::Lz4 => lz4_flex::compress_prepend_size(data),
+            CompressionType::Miniz(level) => {
+                miniz_oxide::deflate::compress_to_vec(data, *level)
+            }
+        }
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>, JsValue> {
+        match self {
+            CompressionType::None => Ok(data.to_vec()),
+            CompressionType::Lz4 => lz4_flex::decompress_size_prepended(data)
+                .map_err(|e| JsValue::from_str(&format!("LZ4 decompress failed: {}", e))),
+            CompressionType::Miniz(_) => miniz_oxide::inflate::decompress_to_vec(data)
+                .map_err(|e| JsValue::from_str(&format!("Miniz decompress failed: {:?}", e))),
+        }
+    }
+}
+
+/// Front-code (prefix-delta) encode sorted 31-byte stems.
+///
+/// Since stems are sorted and frequently share long common prefixes, each
+/// entry after the first is stored as `(shared_len: u8, suffix_len: u8,
+/// suffix bytes)` relative to the previous stem.
+fn prefix_delta_encode(stems: &[Stem]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(stems.len() * 8);
+    let mut prev = [0u8; 31];
+    for stem in stems {
+        let shared = prev.iter().zip(stem.iter()).take_while(|(a, b)| a == b).count();
+        let shared = shared.min(u8::MAX as usize) as u8;
+        let suffix_len = 31 - shared as usize;
+        out.push(shared);
+        out.push(suffix_len as u8);
+        out.extend_from_slice(&stem[shared as usize..]);
+        prev = *stem;
+    }
+    out
+}
+
+/// Scan a prefix-delta encoded stem block to find its length in bytes,
+/// without fully decoding it (used to locate the offset table that follows).
+fn prefix_delta_encoded_len(count: usize, data: &[u8]) -> Result<usize, JsValue> {
+    let mut pos = 0usize;
+    for _ in 0..count {
+        if pos + 2 > data.len() {
+            return Err(JsValue::from_str("Truncated prefix-delta stem table"));
+        }
+        let shared = data[pos] as usize;
+        let suffix_len = data[pos + 1] as usize;
+        if shared > 31 || suffix_len > 31 || shared + suffix_len != 31 {
+            return Err(JsValue::from_str("Corrupt prefix-delta stem entry"));
+        }
+        pos += 2 + suffix_len;
+    }
+    Ok(pos)
+}
+
+/// Inverse of [`prefix_delta_encode`].
+fn prefix_delta_decode(data: &[u8], count: usize) -> Result<Vec<Stem>, JsValue> {
+    let mut stems = Vec::with_capacity(count);
+    let mut prev = [0u8; 31];
+    let mut pos = 0usize;
+
+    for _ in 0..count {
+        if pos + 2 > data.len() {
+            return Err(JsValue::from_str("Truncated prefix-delta stem table"));
+        }
+        let shared = data[pos] as usize;
+        let suffix_len = data[pos + 1] as usize;
+        pos += 2;
+
+        if shared > 31 || suffix_len > 31 || shared + suffix_len != 31 {
+            return Err(JsValue::from_str("Corrupt prefix-delta stem entry"));
+        }
+        if pos + suffix_len > data.len() {
+            return Err(JsValue::from_str("Truncated prefix-delta stem table"));
+        }
+
+        let mut stem = [0u8; 31];
+        stem[..shared].copy_from_slice(&prev[..shared]);
+        stem[shared..].copy_from_slice(&data[pos..pos + suffix_len]);
+        pos += suffix_len;
+
+        stems.push(stem);
+        prev = stem;
+    }
+
+    Ok(stems)
+}
+
 /// Compute tree_index for a storage slot per EIP-7864.
 ///
 /// For slots 0-63: returns tree_index with subindex 64-127 (account stem)
@@ -322,6 +459,95 @@ impl StemIndex {
         self.lookup(address, &tree_index)
     }
 
+    /// Serialize to the tagged, checksummed, optionally compressed format.
+    ///
+    /// Layout: `"STX1" || version:1 || compression:1 || miniz_level:1 ||
+    /// checksum:8 (xxh3_64 of the uncompressed payload, LE) || count:8 (LE)
+    /// || compressed(prefix_delta(stems) || offsets)`.
+    ///
+    /// Not exposed to JS directly (no stable wasm-bindgen `Vec<u8>` bridging
+    /// with enums); use [`StemIndex::to_bytes_lz4`] / [`StemIndex::to_bytes_miniz`].
+    pub fn to_bytes_tagged(&self, compression: CompressionType) -> Vec<u8> {
+        let mut payload = prefix_delta_encode(&self.stems);
+        for &offset in &self.offsets {
+            payload.extend_from_slice(&offset.to_le_bytes());
+        }
+
+        let checksum = xxhash_rust::xxh3::xxh3_64(&payload);
+        let compressed = compression.compress(&payload);
+
+        let mut out = Vec::with_capacity(4 + 3 + 8 + 8 + compressed.len());
+        out.extend_from_slice(&STEM_INDEX_MAGIC);
+        out.push(STEM_INDEX_VERSION);
+        out.push(compression.tag());
+        out.push(compression.level());
+        out.extend_from_slice(&checksum.to_le_bytes());
+        out.extend_from_slice(&(self.stems.len() as u64).to_le_bytes());
+        out.extend_from_slice(&compressed);
+        out
+    }
+
+    /// Serialize using LZ4 compression (wasm-exported convenience wrapper).
+    #[wasm_bindgen(js_name = toBytesLz4)]
+    pub fn to_bytes_lz4(&self) -> Vec<u8> {
+        self.to_bytes_tagged(CompressionType::Lz4)
+    }
+
+    /// Serialize using Miniz/deflate at the given level (wasm-exported
+    /// convenience wrapper).
+    #[wasm_bindgen(js_name = toBytesMiniz)]
+    pub fn to_bytes_miniz(&self, level: u8) -> Vec<u8> {
+        self.to_bytes_tagged(CompressionType::Miniz(level))
+    }
+
+    /// Parse the tagged format produced by [`StemIndex::to_bytes_tagged`],
+    /// verifying the xxh3 checksum before trusting the decompressed data.
+    ///
+    /// The legacy raw `count + (stem+offset)*` format (no magic, no
+    /// checksum, no compression) remains readable via the untagged
+    /// [`StemIndex::from_bytes`] for backward compatibility.
+    #[wasm_bindgen(js_name = fromBytesTagged)]
+    pub fn from_bytes_tagged(data: &[u8]) -> Result<StemIndex, JsValue> {
+        const FIXED_HEADER_SIZE: usize = 4 + 1 + 1 + 1 + 8 + 8;
+
+        if data.len() < FIXED_HEADER_SIZE || data[0..4] != STEM_INDEX_MAGIC {
+            return Err(JsValue::from_str("Not a tagged stem index (bad magic)"));
+        }
+
+        let version = data[4];
+        if version != STEM_INDEX_VERSION {
+            return Err(JsValue::from_str(&format!("Unsupported stem index version: {}", version)));
+        }
+
+        let compression = CompressionType::from_tag(data[5], data[6])?;
+        let expected_checksum = u64::from_le_bytes(data[7..15].try_into().unwrap());
+        let count = u64::from_le_bytes(data[15..23].try_into().unwrap()) as usize;
+
+        let payload = compression.decompress(&data[FIXED_HEADER_SIZE..])?;
+
+        let actual_checksum = xxhash_rust::xxh3::xxh3_64(&payload);
+        if actual_checksum != expected_checksum {
+            return Err(JsValue::from_str(&format!(
+                "Stem index checksum mismatch: expected {:#x}, got {:#x}",
+                expected_checksum, actual_checksum
+            )));
+        }
+
+        let stems_bytes = prefix_delta_encoded_len(count, &payload)?;
+        let stems = prefix_delta_decode(&payload[..stems_bytes], count)?;
+
+        let offsets_bytes = &payload[stems_bytes..];
+        if offsets_bytes.len() != count * 8 {
+            return Err(JsValue::from_str("Truncated offset table"));
+        }
+        let offsets = offsets_bytes
+            .chunks_exact(8)
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+
+        Ok(StemIndex { stems, offsets })
+    }
+
     /// Look up just the stem's starting offset (without adding subindex).
     pub fn lookup_stem_offset(&self, address: &[u8], tree_index: &[u8]) -> Result<i64, JsValue> {
         if address.len() != 20 {
@@ -492,6 +718,62 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn sample_stem_index() -> StemIndex {
+        let stems: Vec<Stem> = (0u8..5)
+            .map(|i| {
+                let mut stem = [0u8; 31];
+                stem[30] = i;
+                stem
+            })
+            .collect();
+        let offsets: Vec<u64> = (0..5).map(|i| i * 1000).collect();
+        StemIndex { stems, offsets }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_tagged_roundtrip_none() {
+        let index = sample_stem_index();
+        let bytes = index.to_bytes_tagged(CompressionType::None);
+        let parsed = StemIndex::from_bytes_tagged(&bytes).unwrap();
+        assert_eq!(parsed.stems, index.stems);
+        assert_eq!(parsed.offsets, index.offsets);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_tagged_roundtrip_lz4() {
+        let index = sample_stem_index();
+        let bytes = index.to_bytes_lz4();
+        let parsed = StemIndex::from_bytes_tagged(&bytes).unwrap();
+        assert_eq!(parsed.stems, index.stems);
+        assert_eq!(parsed.offsets, index.offsets);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_tagged_roundtrip_miniz() {
+        let index = sample_stem_index();
+        let bytes = index.to_bytes_miniz(6);
+        let parsed = StemIndex::from_bytes_tagged(&bytes).unwrap();
+        assert_eq!(parsed.stems, index.stems);
+        assert_eq!(parsed.offsets, index.offsets);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_tagged_checksum_mismatch_rejected() {
+        let index = sample_stem_index();
+        let mut bytes = index.to_bytes_tagged(CompressionType::None);
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff; // corrupt a payload byte without touching the checksum
+        let result = StemIndex::from_bytes_tagged(&bytes);
+        assert!(result.is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_tagged_bad_magic_rejected() {
+        let mut bytes = sample_stem_index().to_bytes_tagged(CompressionType::None);
+        bytes[0] = b'X';
+        assert!(StemIndex::from_bytes_tagged(&bytes).is_err());
+    }
+
     #[wasm_bindgen_test]
     fn test_stem_index_truncated_rejected() {
         // Claim 10 entries but provide only header
@@ -1,12 +1,30 @@
 //! Two-lane PIR client implementation
+//!
+//! By default the network calls (`fetch_crs`, `send_query`, and friends) run
+//! over an async [`reqwest::Client`]. Building with `--features blocking`
+//! swaps in a synchronous [`ureq`] backend instead, for embedders that
+//! aren't async (CLIs, ExEx sync contexts, FFI). Both backends share the
+//! same method bodies via the [`maybe_async`] crate: each method is tagged
+//! `#[maybe_async::maybe_async]` and written once using normal `.await`
+//! syntax, with only the `HttpBackend`/`HttpResponse` calls below differing
+//! between the two builds. Under `blocking`, `maybe_async` strips the
+//! `async`/`.await` so every public method becomes an ordinary blocking
+//! call returning `Result<T>` directly instead of a `Future`. The PIR math
+//! (`GaussianSampler`, `RlweSecretKey::generate`, `pir_query`, `extract`)
+//! never touches the network layer and is shared unchanged.
+//!
+//! This crate's `blocking` feature enables `maybe-async`'s own `is_sync`
+//! feature (`blocking = ["maybe-async/is_sync"]` in `Cargo.toml`), which is
+//! what actually flips the macro's expansion.
 
-use reqwest::Client;
+use maybe_async::maybe_async;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 use inspire_core::{Address, Lane, LaneRouter, StorageKey, StorageValue};
 use inspire_pir::{
-    ServerCrs, ClientQuery, ClientState, ServerResponse,
-    query as pir_query, extract,
+    ServerCrs, ClientQuery, ClientState, SeededClientQuery, ServerResponse,
+    query as pir_query, query_seeded as pir_query_seeded, extract,
     InspireParams,
 };
 use inspire_pir::math::GaussianSampler;
@@ -14,6 +32,262 @@ use inspire_pir::rlwe::RlweSecretKey;
 
 use crate::error::{ClientError, Result};
 
+/// A backend-agnostic HTTP response: either a [`reqwest::Response`] (default)
+/// or a [`ureq::Response`] (`blocking` feature). Exposes just the surface
+/// [`TwoLaneClient`] needs, so its query methods don't have to know which
+/// backend produced the response.
+#[cfg(not(feature = "blocking"))]
+pub struct HttpResponse(reqwest::Response);
+
+#[cfg(feature = "blocking")]
+pub struct HttpResponse(ureq::Response);
+
+impl HttpResponse {
+    pub fn status(&self) -> u16 {
+        #[cfg(not(feature = "blocking"))]
+        {
+            self.0.status().as_u16()
+        }
+        #[cfg(feature = "blocking")]
+        {
+            self.0.status()
+        }
+    }
+
+    #[maybe_async]
+    pub async fn json<T: serde::de::DeserializeOwned>(self) -> Result<T> {
+        #[cfg(not(feature = "blocking"))]
+        {
+            Ok(self.0.json().await?)
+        }
+        #[cfg(feature = "blocking")]
+        {
+            Ok(self.0.into_json()?)
+        }
+    }
+
+    #[maybe_async]
+    pub async fn text(self) -> Result<String> {
+        #[cfg(not(feature = "blocking"))]
+        {
+            Ok(self.0.text().await?)
+        }
+        #[cfg(feature = "blocking")]
+        {
+            Ok(self.0.into_string().map_err(|e| ClientError::InvalidResponse(e.to_string()))?)
+        }
+    }
+
+    #[maybe_async]
+    pub async fn bytes(self) -> Result<Vec<u8>> {
+        #[cfg(not(feature = "blocking"))]
+        {
+            Ok(self.0.bytes().await?.to_vec())
+        }
+        #[cfg(feature = "blocking")]
+        {
+            use std::io::Read;
+            let mut buf = Vec::new();
+            self.0
+                .into_reader()
+                .read_to_end(&mut buf)
+                .map_err(|e| ClientError::InvalidResponse(e.to_string()))?;
+            Ok(buf)
+        }
+    }
+}
+
+/// The request/response surface [`TwoLaneClient`] needs from a transport:
+/// a plain GET (`fetch_crs`), a JSON POST (`send_query`, `query_batch`),
+/// and a raw-bytes POST (`query_full_binary_and_extract`'s bincode-encoded
+/// [`ClientQuery`]).
+/// [`HttpBackend`] is the only implementation today -- this trait exists so
+/// a future QUIC/HTTP3 transport (see [`TransportKind::Quic`]) can plug in
+/// through [`ClientBuilder::transport`] by implementing these three methods
+/// instead of `TwoLaneClient` growing a second code path.
+#[maybe_async]
+pub trait Transport: Send + Sync {
+    async fn get(&self, url: &str) -> Result<HttpResponse>;
+    async fn post_json<T: Serialize + ?Sized + Sync>(&self, url: &str, body: &T) -> Result<HttpResponse>;
+    async fn post_binary(&self, url: &str, body: Vec<u8>) -> Result<HttpResponse>;
+}
+
+#[maybe_async]
+impl Transport for HttpBackend {
+    async fn get(&self, url: &str) -> Result<HttpResponse> {
+        HttpBackend::get(self, url).await
+    }
+
+    async fn post_json<T: Serialize + ?Sized + Sync>(&self, url: &str, body: &T) -> Result<HttpResponse> {
+        HttpBackend::post_json(self, url, body).await
+    }
+
+    async fn post_binary(&self, url: &str, body: Vec<u8>) -> Result<HttpResponse> {
+        HttpBackend::post_binary(self, url, body).await
+    }
+}
+
+/// Thin wrapper around the active HTTP backend so [`TwoLaneClient`] doesn't
+/// need a `#[cfg]` of its own at every call site.
+#[derive(Clone)]
+pub struct HttpBackend {
+    #[cfg(not(feature = "blocking"))]
+    inner: reqwest::Client,
+    #[cfg(feature = "blocking")]
+    inner: ureq::Agent,
+}
+
+impl HttpBackend {
+    pub fn new() -> Self {
+        Self::with_timeout(DEFAULT_REQUEST_TIMEOUT)
+    }
+
+    /// Build a backend whose requests give up after `timeout` instead of
+    /// the default 30s, set via [`ClientBuilder::request_timeout`].
+    pub fn with_timeout(timeout: std::time::Duration) -> Self {
+        #[cfg(not(feature = "blocking"))]
+        {
+            Self {
+                inner: reqwest::Client::builder()
+                    .timeout(timeout)
+                    .build()
+                    .expect("failed to build reqwest client"),
+            }
+        }
+        #[cfg(feature = "blocking")]
+        {
+            Self {
+                inner: ureq::AgentBuilder::new().timeout(timeout).build(),
+            }
+        }
+    }
+
+    #[maybe_async]
+    pub async fn get(&self, url: &str) -> Result<HttpResponse> {
+        #[cfg(not(feature = "blocking"))]
+        {
+            Ok(HttpResponse(self.inner.get(url).send().await?))
+        }
+        #[cfg(feature = "blocking")]
+        {
+            Ok(HttpResponse(self.inner.get(url).call().map_err(ureq_to_client_error)?))
+        }
+    }
+
+    #[maybe_async]
+    pub async fn post_json<T: Serialize + ?Sized>(&self, url: &str, body: &T) -> Result<HttpResponse> {
+        #[cfg(not(feature = "blocking"))]
+        {
+            Ok(HttpResponse(self.inner.post(url).json(body).send().await?))
+        }
+        #[cfg(feature = "blocking")]
+        {
+            let value = serde_json::to_value(body)?;
+            Ok(HttpResponse(
+                self.inner.post(url).send_json(value).map_err(ureq_to_client_error)?,
+            ))
+        }
+    }
+
+    /// POST a raw binary body (e.g. a bincode-encoded [`inspire_pir::ClientQuery`]),
+    /// bypassing `post_json`'s JSON round trip entirely. Under the default
+    /// async build, the body is wrapped as a one-chunk [`reqwest::Body`]
+    /// stream (via `Body::wrap_stream`) rather than handed to `reqwest` as
+    /// an owned buffer, so the request doesn't hold a second full copy of
+    /// `body` alongside whatever buffer produced it.
+    #[maybe_async]
+    pub async fn post_binary(&self, url: &str, body: Vec<u8>) -> Result<HttpResponse> {
+        #[cfg(not(feature = "blocking"))]
+        {
+            let stream = futures::stream::once(async move { Ok::<_, std::io::Error>(body) });
+            let resp = self
+                .inner
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+                .body(reqwest::Body::wrap_stream(stream))
+                .send()
+                .await?;
+            Ok(HttpResponse(resp))
+        }
+        #[cfg(feature = "blocking")]
+        {
+            Ok(HttpResponse(
+                self.inner
+                    .post(url)
+                    .set("Content-Type", "application/octet-stream")
+                    .send_bytes(&body)
+                    .map_err(ureq_to_client_error)?,
+            ))
+        }
+    }
+}
+
+impl Default for HttpBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "blocking")]
+fn ureq_to_client_error(e: ureq::Error) -> ClientError {
+    match e {
+        ureq::Error::Status(status, resp) => classify_error_status(status, resp.into_string().unwrap_or_default()),
+        other => ClientError::InvalidResponse(other.to_string()),
+    }
+}
+
+/// Turn a non-2xx status into the right [`ClientError`] variant: HTTP 408
+/// (the convention the PIR server uses for a request it gave up waiting on
+/// under load, see `inspire_server::routes`) becomes [`ClientError::Timeout`]
+/// so retry logic can tell "the server is overloaded, try again" apart from
+/// every other 4xx/5xx, which becomes [`ClientError::Server`] and isn't
+/// retried.
+fn classify_error_status(status: u16, message: String) -> ClientError {
+    if status == 408 {
+        ClientError::Timeout(message)
+    } else {
+        ClientError::Server { status, message }
+    }
+}
+
+/// Retry policy for the idempotent network calls ([`TwoLaneClient::fetch_crs`],
+/// [`TwoLaneClient::query`]'s underlying `send_query`): how many times to
+/// retry a [`ClientError::Timeout`] or transport-level failure, and the base
+/// delay for the exponential backoff between attempts (doubled each retry,
+/// plus up to 25% jitter so a thundering herd of clients don't all retry in
+/// lockstep).
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub backoff_base: std::time::Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            backoff_base: std::time::Duration::from_millis(200),
+        }
+    }
+}
+
+/// Default per-request timeout applied by [`HttpBackend::new`]. Callers that
+/// need something else go through [`ClientBuilder::request_timeout`].
+const DEFAULT_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Whether a failure is worth retrying. Transport-level errors (connection
+/// reset, DNS hiccup, etc.) and [`ClientError::Timeout`] are; any other
+/// [`ClientError::Server`] means the server understood and rejected the
+/// request, so retrying it would just repeat the same failure.
+fn is_retryable(err: &ClientError) -> bool {
+    match err {
+        ClientError::Timeout(_) => true,
+        #[cfg(not(feature = "blocking"))]
+        ClientError::Http(_) => true,
+        _ => false,
+    }
+}
+
 /// Response from CRS endpoint
 #[derive(Deserialize)]
 pub struct CrsResponse {
@@ -28,6 +302,21 @@ struct QueryRequest {
     query: String,
 }
 
+/// Request to the seeded query endpoint
+#[derive(Serialize)]
+struct SeededQueryRequest {
+    query: String,
+}
+
+/// Request to the `/query/{lane}/batch` endpoint. Unlike `QueryRequest`,
+/// `ClientQuery` is serialized as a nested object here rather than a
+/// JSON-stringified field, matching `inspire_server::routes::BatchQueryRequest`'s
+/// `Vec<ClientQuery>`.
+#[derive(Serialize)]
+struct BatchQueryRequest {
+    queries: Vec<ClientQuery>,
+}
+
 /// Response from query endpoint
 #[derive(Deserialize)]
 pub struct QueryResponse {
@@ -35,6 +324,21 @@ pub struct QueryResponse {
     pub lane: Lane,
 }
 
+/// A single range's coverage within a range-delta file, as returned by
+/// `/index/deltas/info`
+#[derive(Deserialize)]
+pub struct RangeInfo {
+    pub blocks_covered: u32,
+    pub size: u64,
+}
+
+/// Response from the range-delta info endpoint (`/index/deltas/info`)
+#[derive(Deserialize)]
+pub struct RangeDeltaInfoResponse {
+    pub current_block: u64,
+    pub ranges: Vec<RangeInfo>,
+}
+
 /// Lane-specific client state
 struct LaneState {
     crs: ServerCrs,
@@ -45,8 +349,9 @@ struct LaneState {
 /// Two-lane PIR client that routes queries to the appropriate lane
 pub struct TwoLaneClient {
     router: LaneRouter,
-    http: Client,
+    http: HttpBackend,
     server_url: String,
+    retry: RetryPolicy,
     hot_state: Option<LaneState>,
     cold_state: Option<LaneState>,
 }
@@ -56,14 +361,38 @@ impl TwoLaneClient {
     pub fn new(router: LaneRouter, server_url: String) -> Self {
         Self {
             router,
-            http: Client::new(),
+            http: HttpBackend::new(),
             server_url: server_url.trim_end_matches('/').to_string(),
+            retry: RetryPolicy::default(),
             hot_state: None,
             cold_state: None,
         }
     }
 
+    /// Sleep for an exponential backoff with jitter ahead of retry attempt
+    /// number `attempt` (1-based). Gated like `HttpBackend`'s network calls
+    /// since a real sleep is async-only under the default build and would
+    /// block the executor if awaited there, while the `blocking` build has
+    /// no executor to yield to in the first place.
+    #[maybe_async]
+    async fn backoff_sleep(&self, attempt: u32) {
+        let base = self.retry.backoff_base.saturating_mul(1u32 << attempt.min(16));
+        let jitter_bound = (base.as_millis() as u64 / 4).max(1);
+        let jitter = std::time::Duration::from_millis(rand::thread_rng().gen_range(0..=jitter_bound));
+        let delay = base + jitter;
+
+        #[cfg(not(feature = "blocking"))]
+        {
+            tokio::time::sleep(delay).await;
+        }
+        #[cfg(feature = "blocking")]
+        {
+            std::thread::sleep(delay);
+        }
+    }
+
     /// Initialize the client by fetching CRS from server and generating keys
+    #[maybe_async]
     pub async fn init(&mut self) -> Result<()> {
         let hot_crs_resp = self.fetch_crs(Lane::Hot).await?;
         let hot_crs: ServerCrs = serde_json::from_str(&hot_crs_resp.crs)?;
@@ -92,26 +421,53 @@ impl TwoLaneClient {
         Ok(())
     }
 
-    /// Fetch CRS for a specific lane
+    /// Fetch CRS for a specific lane. Idempotent (a plain `GET`), so a
+    /// timed-out or transport-failed attempt is retried per
+    /// [`ClientBuilder::max_retries`]/[`ClientBuilder::backoff`].
+    #[maybe_async]
     pub async fn fetch_crs(&self, lane: Lane) -> Result<CrsResponse> {
         let url = format!("{}/crs/{}", self.server_url, lane);
-        let resp = self.http.get(&url).send().await?;
-        
-        if !resp.status().is_success() {
-            return Err(ClientError::Server {
-                status: resp.status().as_u16(),
-                message: resp.text().await.unwrap_or_default(),
-            });
+        let mut attempt = 0;
+        loop {
+            match self.fetch_crs_once(&url).await {
+                Err(e) if attempt < self.retry.max_retries && is_retryable(&e) => {
+                    attempt += 1;
+                    self.backoff_sleep(attempt).await;
+                }
+                result => return result,
+            }
         }
-        
+    }
+
+    #[maybe_async]
+    async fn fetch_crs_once(&self, url: &str) -> Result<CrsResponse> {
+        let resp = self.http.get(url).await?;
+
+        if resp.status() < 200 || resp.status() >= 300 {
+            let status = resp.status();
+            return Err(classify_error_status(status, resp.text().await.unwrap_or_default()));
+        }
+
         let crs_resp: CrsResponse = resp.json().await?;
         Ok(crs_resp)
     }
 
+    /// Fetch and decode the CRS for a lane, returning the parsed
+    /// [`ServerCrs`] directly rather than the wire [`CrsResponse`] envelope.
+    /// The index-oriented counterpart to `fetch_crs`, for callers (like
+    /// `query_and_extract`) that already have an index and don't need the
+    /// contract/slot routing `query` does.
+    #[maybe_async]
+    pub async fn get_crs(&self, lane: Lane) -> Result<ServerCrs> {
+        let resp = self.fetch_crs(lane).await?;
+        Ok(serde_json::from_str(&resp.crs)?)
+    }
+
     /// Query a storage slot using PIR
+    #[maybe_async]
     pub async fn query(&self, contract: Address, slot: StorageKey) -> Result<StorageValue> {
         let lane = self.router.route(&contract);
-        
+
         tracing::debug!(
             contract = hex::encode(contract),
             lane = %lane,
@@ -119,27 +475,258 @@ impl TwoLaneClient {
         );
 
         let lane_state = self.get_lane_state(lane)?;
-        
+
         let index = self.compute_index(&contract, &slot, lane)?;
-        
+
         let (client_state, client_query) = self.build_pir_query(lane_state, index)?;
-        
+
         let response = self.send_query(lane, &client_query).await?;
-        
+
         let server_response: ServerResponse = serde_json::from_str(&response.response)?;
-        
+
         let entry = extract(
             &lane_state.crs,
             &client_state,
             &server_response,
             32,
         ).map_err(|e| ClientError::InvalidResponse(e.to_string()))?;
-        
+
         let mut result = [0u8; 32];
         result.copy_from_slice(&entry[..32]);
         Ok(result)
     }
 
+    /// Resolve many `(contract, slot)` pairs in as few round trips as
+    /// possible: groups requests by routed lane, builds every per-lane
+    /// query up front, and dispatches the hot- and cold-lane batches
+    /// concurrently against `/query/{lane}/batch` rather than one round
+    /// trip per `query()` call. Preserves the input ordering in the
+    /// returned vector regardless of how the lanes interleave.
+    #[maybe_async]
+    pub async fn query_batch(&self, requests: &[(Address, StorageKey)]) -> Result<Vec<StorageValue>> {
+        let mut hot_slots: Vec<usize> = Vec::new();
+        let mut cold_slots: Vec<usize> = Vec::new();
+        for (i, (contract, _slot)) in requests.iter().enumerate() {
+            match self.router.route(contract) {
+                Lane::Hot => hot_slots.push(i),
+                Lane::Cold => cold_slots.push(i),
+            }
+        }
+
+        // Concurrent dispatch only makes sense against the async reqwest
+        // backend; under `blocking` there's no executor to interleave the
+        // two lane round trips on, so they just run one after the other.
+        #[cfg(not(feature = "blocking"))]
+        let (hot_result, cold_result) = futures::future::join(
+            self.query_lane_batch(Lane::Hot, requests, &hot_slots),
+            self.query_lane_batch(Lane::Cold, requests, &cold_slots),
+        )
+        .await;
+        #[cfg(feature = "blocking")]
+        let (hot_result, cold_result) = (
+            self.query_lane_batch(Lane::Hot, requests, &hot_slots),
+            self.query_lane_batch(Lane::Cold, requests, &cold_slots),
+        );
+
+        let mut results = vec![[0u8; 32]; requests.len()];
+        for (slot_idx, value) in hot_slots.into_iter().zip(hot_result?) {
+            results[slot_idx] = value;
+        }
+        for (slot_idx, value) in cold_slots.into_iter().zip(cold_result?) {
+            results[slot_idx] = value;
+        }
+        Ok(results)
+    }
+
+    /// Build and resolve the `lane` portion of a [`Self::query_batch`] call:
+    /// the `indices` into `requests` that routed to `lane`, in order.
+    /// Returns an empty vector without a round trip if `indices` is empty.
+    #[maybe_async]
+    async fn query_lane_batch(
+        &self,
+        lane: Lane,
+        requests: &[(Address, StorageKey)],
+        indices: &[usize],
+    ) -> Result<Vec<StorageValue>> {
+        if indices.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let lane_state = self.get_lane_state(lane)?;
+
+        let mut client_states = Vec::with_capacity(indices.len());
+        let mut queries = Vec::with_capacity(indices.len());
+        for &i in indices {
+            let (contract, slot) = &requests[i];
+            let index = self.compute_index(contract, slot, lane)?;
+            let (client_state, client_query) = self.build_pir_query(lane_state, index)?;
+            client_states.push(client_state);
+            queries.push(client_query);
+        }
+
+        let url = format!("{}/query/{}/batch", self.server_url, lane);
+        let resp = self.http.post_json(&url, &BatchQueryRequest { queries }).await?;
+
+        if resp.status() < 200 || resp.status() >= 300 {
+            let status = resp.status();
+            return Err(classify_error_status(status, resp.text().await.unwrap_or_default()));
+        }
+
+        let framed = resp.bytes().await?;
+        let frames = split_framed_responses(&framed)?;
+        if frames.len() != client_states.len() {
+            return Err(ClientError::InvalidResponse(format!(
+                "batch response has {} frames, expected {}",
+                frames.len(),
+                client_states.len()
+            )));
+        }
+
+        frames
+            .into_iter()
+            .zip(client_states)
+            .map(|(frame, client_state)| {
+                let server_response = ServerResponse::from_binary(frame)
+                    .map_err(|e| ClientError::InvalidResponse(e.to_string()))?;
+                let entry = extract(&lane_state.crs, &client_state, &server_response, 32)
+                    .map_err(|e| ClientError::InvalidResponse(e.to_string()))?;
+                let mut result = [0u8; 32];
+                result.copy_from_slice(&entry[..32]);
+                Ok(result)
+            })
+            .collect()
+    }
+
+    /// Run a full PIR query against a raw database index and extract the
+    /// plaintext entry, bypassing the contract/slot routing `query` does.
+    /// Requires `init()` to have populated the lane's state first.
+    #[maybe_async]
+    pub async fn query_and_extract(&self, lane: Lane, index: u64) -> Result<Vec<u8>> {
+        let lane_state = self.get_lane_state(lane)?;
+        let (client_state, client_query) = self.build_pir_query(lane_state, index)?;
+        let response = self.send_query(lane, &client_query).await?;
+        let server_response: ServerResponse = serde_json::from_str(&response.response)?;
+
+        extract(&lane_state.crs, &client_state, &server_response, 32)
+            .map_err(|e| ClientError::InvalidResponse(e.to_string()))
+    }
+
+    /// Seeded-query variant of `query_and_extract`: the client uploads a
+    /// seeded query (a seed in place of expanded randomness), halving upload size.
+    #[maybe_async]
+    pub async fn query_seeded_and_extract(&self, lane: Lane, index: u64) -> Result<Vec<u8>> {
+        let lane_state = self.get_lane_state(lane)?;
+        let mut sampler = GaussianSampler::new(lane_state.crs.params.sigma);
+        let shard_config = inspire_pir::params::ShardConfig {
+            shard_size_bytes: (lane_state.crs.params.ring_dim as u64) * 32,
+            entry_size_bytes: 32,
+            total_entries: lane_state.entry_count,
+        };
+
+        let (client_state, seeded_query) = pir_query_seeded(
+            &lane_state.crs,
+            index,
+            &shard_config,
+            &lane_state.secret_key,
+            &mut sampler,
+        )
+        .map_err(|e| ClientError::InvalidResponse(format!("Failed to build seeded query: {}", e)))?;
+
+        let response = self.send_seeded_query(lane, &seeded_query).await?;
+        let server_response: ServerResponse = serde_json::from_str(&response.response)?;
+
+        extract(&lane_state.crs, &client_state, &server_response, 32)
+            .map_err(|e| ClientError::InvalidResponse(e.to_string()))
+    }
+
+    /// Binary-response variant of `query_and_extract`: avoids the JSON
+    /// base64 overhead on the way back by hitting `/query/{lane}/binary`
+    /// and decoding the raw bytes via [`ServerResponse::from_binary`].
+    #[maybe_async]
+    pub async fn query_binary_and_extract(&self, lane: Lane, index: u64) -> Result<Vec<u8>> {
+        let lane_state = self.get_lane_state(lane)?;
+        let (client_state, client_query) = self.build_pir_query(lane_state, index)?;
+
+        let url = format!("{}/query/{}/binary", self.server_url, lane);
+        let resp = self
+            .http
+            .post_json(&url, &QueryRequest { query: serde_json::to_string(&client_query)? })
+            .await?;
+
+        if resp.status() < 200 || resp.status() >= 300 {
+            let status = resp.status();
+            return Err(classify_error_status(status, resp.text().await.unwrap_or_default()));
+        }
+
+        let bytes = resp.bytes().await?;
+        let server_response = ServerResponse::from_binary(&bytes)
+            .map_err(|e| ClientError::InvalidResponse(e.to_string()))?;
+
+        extract(&lane_state.crs, &client_state, &server_response, 32)
+            .map_err(|e| ClientError::InvalidResponse(e.to_string()))
+    }
+
+    /// Fully-binary variant of `query_binary_and_extract`: the request body
+    /// is also bincode rather than a JSON-wrapped `ClientQuery`, hitting
+    /// `/query/{lane}/full-binary`. Saves the struct -> JSON string -> JSON
+    /// object double-encoding `send_query` does, on top of the existing
+    /// binary-response saving.
+    #[maybe_async]
+    pub async fn query_full_binary_and_extract(&self, lane: Lane, index: u64) -> Result<Vec<u8>> {
+        let lane_state = self.get_lane_state(lane)?;
+        let (client_state, client_query) = self.build_pir_query(lane_state, index)?;
+
+        let url = format!("{}/query/{}/full-binary", self.server_url, lane);
+        let query_bytes = bincode::serialize(&client_query)
+            .map_err(|e| ClientError::InvalidResponse(format!("failed to encode query: {e}")))?;
+
+        let resp = self.http.post_binary(&url, query_bytes).await?;
+
+        if resp.status() < 200 || resp.status() >= 300 {
+            let status = resp.status();
+            return Err(classify_error_status(status, resp.text().await.unwrap_or_default()));
+        }
+
+        let bytes = resp.bytes().await?;
+        let server_response = ServerResponse::from_binary(&bytes)
+            .map_err(|e| ClientError::InvalidResponse(e.to_string()))?;
+
+        extract(&lane_state.crs, &client_state, &server_response, 32)
+            .map_err(|e| ClientError::InvalidResponse(e.to_string()))
+    }
+
+    /// Fetch the range-delta directory (which block the deltas are current
+    /// as of, and the byte size of each `blocks_covered` granularity) from
+    /// `/index/deltas/info`, without downloading any delta data yet
+    #[maybe_async]
+    pub async fn fetch_range_delta_info(&self) -> Result<RangeDeltaInfoResponse> {
+        let url = format!("{}/index/deltas/info", self.server_url);
+        let resp = self.http.get(&url).await?;
+
+        if resp.status() < 200 || resp.status() >= 300 {
+            let status = resp.status();
+            return Err(classify_error_status(status, resp.text().await.unwrap_or_default()));
+        }
+
+        Ok(resp.json().await?)
+    }
+
+    /// Fetch the raw `BDLT`-framed delta bytes for a single `blocks_covered`
+    /// granularity (one of [`RangeDeltaInfoResponse::ranges`]) from
+    /// `/index/deltas`
+    #[maybe_async]
+    pub async fn fetch_range_delta(&self, blocks_covered: u32) -> Result<Vec<u8>> {
+        let url = format!("{}/index/deltas?blocks_covered={blocks_covered}", self.server_url);
+        let resp = self.http.get(&url).await?;
+
+        if resp.status() < 200 || resp.status() >= 300 {
+            let status = resp.status();
+            return Err(classify_error_status(status, resp.text().await.unwrap_or_default()));
+        }
+
+        resp.bytes().await
+    }
+
     /// Build a PIR query for the given index
     fn build_pir_query(&self, lane_state: &LaneState, index: u64) -> Result<(ClientState, ClientQuery)> {
         let mut sampler = GaussianSampler::new(lane_state.crs.params.sigma);
@@ -161,23 +748,57 @@ impl TwoLaneClient {
         Ok((state, query))
     }
 
-    /// Send a query to the server
+    /// Send a query to the server. A PIR query never mutates server state,
+    /// so it's safe to retry exactly like `fetch_crs` -- a briefly
+    /// overloaded server shouldn't abort an otherwise-recoverable query.
+    #[maybe_async]
     async fn send_query(&self, lane: Lane, query: &ClientQuery) -> Result<QueryResponse> {
         let url = format!("{}/query/{}", self.server_url, lane);
-        
         let query_json = serde_json::to_string(query)?;
-        
-        let resp = self.http
-            .post(&url)
-            .json(&QueryRequest { query: query_json })
-            .send()
+
+        let mut attempt = 0;
+        loop {
+            match self.send_query_once(&url, &query_json).await {
+                Err(e) if attempt < self.retry.max_retries && is_retryable(&e) => {
+                    attempt += 1;
+                    self.backoff_sleep(attempt).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    #[maybe_async]
+    async fn send_query_once(&self, url: &str, query_json: &str) -> Result<QueryResponse> {
+        let resp = self
+            .http
+            .post_json(url, &QueryRequest { query: query_json.to_string() })
             .await?;
 
-        if !resp.status().is_success() {
-            return Err(ClientError::Server {
-                status: resp.status().as_u16(),
-                message: resp.text().await.unwrap_or_default(),
-            });
+        if resp.status() < 200 || resp.status() >= 300 {
+            let status = resp.status();
+            return Err(classify_error_status(status, resp.text().await.unwrap_or_default()));
+        }
+
+        let query_resp: QueryResponse = resp.json().await?;
+        Ok(query_resp)
+    }
+
+    /// Send a seeded query to the server
+    #[maybe_async]
+    async fn send_seeded_query(&self, lane: Lane, query: &SeededClientQuery) -> Result<QueryResponse> {
+        let url = format!("{}/query/{}/seeded", self.server_url, lane);
+
+        let query_json = serde_json::to_string(query)?;
+
+        let resp = self
+            .http
+            .post_json(&url, &SeededQueryRequest { query: query_json })
+            .await?;
+
+        if resp.status() < 200 || resp.status() >= 300 {
+            let status = resp.status();
+            return Err(classify_error_status(status, resp.text().await.unwrap_or_default()));
         }
 
         let query_resp: QueryResponse = resp.json().await?;
@@ -222,16 +843,93 @@ impl TwoLaneClient {
     }
 }
 
+/// Split a `/query/{lane}/batch` response body into its individual
+/// `ServerResponse::to_binary()` payloads. Inverse of the server's
+/// `frame_response`: each frame is `[4-byte big-endian length][bytes]`.
+fn split_framed_responses(data: &[u8]) -> Result<Vec<&[u8]>> {
+    let mut frames = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        if data.len() - offset < 4 {
+            return Err(ClientError::InvalidResponse(
+                "truncated batch response: incomplete frame length".to_string(),
+            ));
+        }
+        let len = u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
+        offset += 4;
+        if data.len() - offset < len {
+            return Err(ClientError::InvalidResponse(
+                "truncated batch response: incomplete frame body".to_string(),
+            ));
+        }
+        frames.push(&data[offset..offset + len]);
+        offset += len;
+    }
+    Ok(frames)
+}
+
 /// Generate a secret key for PIR
 fn generate_secret_key(params: &InspireParams) -> RlweSecretKey {
     let mut sampler = GaussianSampler::new(params.sigma);
     RlweSecretKey::generate(params, &mut sampler)
 }
 
+/// Which transport [`ClientBuilder::build`] wires a [`TwoLaneClient`] up
+/// with. `Reqwest` (the default) builds an [`HttpBackend`], the [`Transport`]
+/// impl every method on this struct is written against today.
+///
+/// `Quic` is a placeholder for a QUIC/HTTP3 transport -- multiplexed streams
+/// would let `query_batch`'s concurrent hot/cold dispatch run over one
+/// connection without head-of-line blocking, and 0-RTT resumption would
+/// save a round trip on repeat connections to the same server. Neither
+/// `quinn` nor an HTTP/3 client (e.g. `h3`) is a dependency anywhere in
+/// this tree, so [`ClientBuilder::build`] rejects it with
+/// [`ClientError::TransportUnavailable`] rather than silently falling back
+/// to `Reqwest` or shipping a non-functional stub -- a caller that asked
+/// for QUIC and got HTTP/1.1 without being told would have no way to know
+/// its 0-RTT/multiplexing assumptions don't hold. Implementing the real
+/// thing (a [`Transport`] impl backed by `quinn`) is future work; the
+/// [`Transport`] trait is the extension point it will plug into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransportKind {
+    #[default]
+    Reqwest,
+    Quic,
+}
+
+impl TransportKind {
+    /// URL schemes this transport accepts. Used by [`ClientBuilder::build`]
+    /// to reject a server URL that can't possibly work with the selected
+    /// transport (e.g. a `quic://` URL with `TransportKind::Reqwest`)
+    /// before any request is attempted, rather than failing opaquely on
+    /// the first `fetch_crs`/`query` call.
+    fn accepted_schemes(self) -> &'static [&'static str] {
+        match self {
+            TransportKind::Reqwest => &["http://", "https://"],
+            TransportKind::Quic => &["quic://"],
+        }
+    }
+}
+
+/// Validate that `server_url` uses a scheme `transport` actually supports.
+fn validate_server_url(server_url: &str, transport: TransportKind) -> Result<()> {
+    let schemes = transport.accepted_schemes();
+    if schemes.iter().any(|scheme| server_url.starts_with(scheme)) {
+        Ok(())
+    } else {
+        Err(ClientError::InvalidServerUrl(format!(
+            "{server_url:?} doesn't match any scheme {transport:?} supports ({schemes:?})"
+        )))
+    }
+}
+
 /// Builder for TwoLaneClient
 pub struct ClientBuilder {
     server_url: String,
     manifest_path: Option<std::path::PathBuf>,
+    transport: TransportKind,
+    request_timeout: std::time::Duration,
+    retry: RetryPolicy,
 }
 
 impl ClientBuilder {
@@ -239,6 +937,9 @@ impl ClientBuilder {
         Self {
             server_url: server_url.into(),
             manifest_path: None,
+            transport: TransportKind::default(),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            retry: RetryPolicy::default(),
         }
     }
 
@@ -247,15 +948,54 @@ impl ClientBuilder {
         self
     }
 
+    /// Select the transport `build()` wires the client up with. See
+    /// [`TransportKind`] for why `Quic` currently fails at build time.
+    pub fn transport(mut self, transport: TransportKind) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// How many times to retry a timed-out or transport-failed `fetch_crs`
+    /// or query attempt before giving up. Defaults to 0 (no retries).
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.retry.max_retries = max_retries;
+        self
+    }
+
+    /// Base delay for the retry backoff (doubled per attempt, plus jitter).
+    /// Defaults to 200ms.
+    pub fn backoff(mut self, base: std::time::Duration) -> Self {
+        self.retry.backoff_base = base;
+        self
+    }
+
+    /// Per-request timeout for every HTTP call the built client makes.
+    /// Defaults to 30s.
+    pub fn request_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
     pub fn build(self) -> Result<TwoLaneClient> {
+        validate_server_url(&self.server_url, self.transport)?;
+
+        if self.transport == TransportKind::Quic {
+            return Err(ClientError::TransportUnavailable(
+                "QUIC transport has no quinn/h3 backend in this build".to_string(),
+            ));
+        }
+
         let manifest = if let Some(path) = self.manifest_path {
             inspire_core::HotLaneManifest::load(&path)?
         } else {
             inspire_core::HotLaneManifest::new(0)
         };
-        
+
         let router = LaneRouter::new(manifest);
-        Ok(TwoLaneClient::new(router, self.server_url))
+        let mut client = TwoLaneClient::new(router, self.server_url);
+        client.http = HttpBackend::with_timeout(self.request_timeout);
+        client.retry = self.retry;
+        Ok(client)
     }
 }
 
@@ -288,7 +1028,34 @@ mod tests {
     fn test_hot_contract_count() {
         let router = LaneRouter::new(create_test_manifest());
         let client = TwoLaneClient::new(router, "http://localhost:3000".into());
-        
+
         assert_eq!(client.hot_contract_count(), 2);
     }
+
+    #[test]
+    fn test_build_rejects_scheme_mismatch() {
+        let err = ClientBuilder::new("quic://localhost:3000")
+            .transport(TransportKind::Reqwest)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ClientError::InvalidServerUrl(_)));
+    }
+
+    #[test]
+    fn test_build_accepts_https_for_reqwest() {
+        let client = ClientBuilder::new("https://localhost:3000")
+            .transport(TransportKind::Reqwest)
+            .build()
+            .unwrap();
+        assert_eq!(client.hot_contract_count(), 0);
+    }
+
+    #[test]
+    fn test_build_rejects_quic_transport() {
+        let err = ClientBuilder::new("quic://localhost:3000")
+            .transport(TransportKind::Quic)
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ClientError::TransportUnavailable(_)));
+    }
 }
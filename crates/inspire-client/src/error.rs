@@ -0,0 +1,43 @@
+//! Error types for inspire-client
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("Server returned {status}: {message}")]
+    Server { status: u16, message: String },
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Lane not available: {0}")]
+    LaneNotAvailable(String),
+
+    #[error("Transport unavailable: {0}")]
+    TransportUnavailable(String),
+
+    /// The server URL's scheme doesn't match the selected
+    /// [`crate::client::TransportKind`] (e.g. an `http://` URL with
+    /// `TransportKind::Quic`), caught at [`crate::client::ClientBuilder::build`]
+    /// time instead of failing opaquely on the first request.
+    #[error("Invalid server URL: {0}")]
+    InvalidServerUrl(String),
+
+    /// A server-side slow-request condition (HTTP 408), distinct from
+    /// `Server` so callers like retry logic can tell "the server is
+    /// overloaded, try again" apart from "the request itself is invalid".
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
+    #[cfg(not(feature = "blocking"))]
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Core(#[from] inspire_core::Error),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
@@ -0,0 +1,15 @@
+//! inspire-client: Two-lane PIR client library
+//!
+//! Wraps CRS fetch, query/extract, and range-delta sync against an
+//! `inspire-server` instance into typed methods on [`TwoLaneClient`]. Builds
+//! against an async [`reqwest::Client`] by default; enabling the `blocking`
+//! feature swaps in a synchronous [`ureq`] backend instead, for embedders
+//! (CLI tools, sync data pipelines) that don't want to pull in a Tokio
+//! runtime. See [`client`] for how the two backends share one method body
+//! via `maybe-async`.
+
+pub mod client;
+pub mod error;
+
+pub use client::{ClientBuilder, CrsResponse, QueryResponse, RangeDeltaInfoResponse, TwoLaneClient};
+pub use error::{ClientError, Result};
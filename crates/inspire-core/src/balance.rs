@@ -80,6 +80,39 @@ pub struct BalanceDbMetadata {
     pub record_size: usize,
     pub num_records: usize,
     pub addresses: Vec<String>,
+    /// Keccak256 root (hex-encoded) of the Merkle tree built over the
+    /// unpadded records, for verifying an entry's in-payload authentication
+    /// path (see [`crate::balance_merkle`] for the current status of that
+    /// wiring -- as of this field, nothing serves or verifies a balance
+    /// entry over the network yet). Empty for a database built without
+    /// verifiable entries. Lives in this same (unsigned) metadata file as
+    /// the database it authenticates, so on its own this proves the
+    /// database is internally consistent, not that it matches what the
+    /// operator actually published.
+    #[serde(default)]
+    pub merkle_root: String,
+    /// Size in bytes of each on-disk PIR entry once padded with its
+    /// Merkle authentication path (`record_size` when `merkle_root` is
+    /// empty, or [`crate::balance_padded_entry_size`] of `num_records`).
+    #[serde(default)]
+    pub padded_entry_size: usize,
+    /// Ethereum `stateRoot` (hex-encoded) every included record's balance
+    /// was verified against via `lane_builder::verify_account_proof` /
+    /// `verify_storage_proof`. Empty for a database built from unverified
+    /// RPC responses.
+    #[serde(default)]
+    pub state_root: String,
+    /// Beacon block root (hex-encoded) of the beacon checkpoint
+    /// `snapshot_block`/`state_root` were resolved from, via
+    /// `lane_builder::CheckpointClient::resolve`. Empty for a database
+    /// anchored to a raw, operator-supplied block number instead. Note this
+    /// does **not** currently imply the checkpoint's sync-committee
+    /// signature was verified -- `CheckpointClient` has no BLS backend
+    /// vendored yet, so a non-empty value only means the block number/hash
+    /// came from a beacon node rather than being hand-typed, not that it
+    /// carries a cryptographic finality guarantee.
+    #[serde(default)]
+    pub beacon_checkpoint_root: String,
 }
 
 impl BalanceDbMetadata {
@@ -89,6 +122,35 @@ impl BalanceDbMetadata {
             .iter()
             .position(|a| a.to_lowercase() == normalized)
     }
+
+    /// Unpack a padded PIR entry retrieved for `index` and verify it folds
+    /// up to `merkle_root`, rejecting a tampered or mismatched response
+    /// instead of trusting whatever the server returned.
+    ///
+    /// Not currently called from any serve or client crate -- see
+    /// [`crate::balance_merkle`]'s status note. Available today for a
+    /// balance-lane query path to call once one exists.
+    ///
+    /// Returns `Err` if this metadata wasn't built with a Merkle root
+    /// (`merkle_root` is empty), the entry can't be unpacked, or the path
+    /// doesn't verify.
+    pub fn verify_entry(
+        &self,
+        entry: &[u8],
+        index: usize,
+    ) -> Result<BalanceRecord, crate::BalanceMerkleError> {
+        if self.merkle_root.is_empty() {
+            return Err(crate::BalanceMerkleError::RootMismatch);
+        }
+        let root_bytes = hex::decode(&self.merkle_root).map_err(|_| crate::BalanceMerkleError::RootMismatch)?;
+        let root: [u8; 32] = root_bytes
+            .try_into()
+            .map_err(|_| crate::BalanceMerkleError::RootMismatch)?;
+
+        let proof = crate::balance_merkle_unpack_entry(entry, index).ok_or(crate::BalanceMerkleError::RootMismatch)?;
+        proof.verify_against_root(&root, self.num_records)?;
+        Ok(proof.record)
+    }
 }
 
 #[cfg(test)]
@@ -119,4 +181,63 @@ mod tests {
         let record = BalanceRecord::new(eth, [0u8; 32]);
         assert_eq!(record.eth_as_u128(), 100);
     }
+
+    #[test]
+    fn test_verify_entry_roundtrip() {
+        let records = vec![
+            BalanceRecord::new([1u8; 32], [2u8; 32]),
+            BalanceRecord::new([3u8; 32], [4u8; 32]),
+            BalanceRecord::new([5u8; 32], [6u8; 32]),
+        ];
+        let root = crate::balance_merkle_root(&records);
+        let metadata = BalanceDbMetadata {
+            chain_id: 1,
+            snapshot_block: 0,
+            snapshot_block_hash: String::new(),
+            usdc_contract: String::new(),
+            record_size: BALANCE_RECORD_SIZE,
+            num_records: records.len(),
+            addresses: vec![],
+            merkle_root: hex::encode(root),
+            padded_entry_size: crate::balance_padded_entry_size(records.len()),
+            state_root: String::new(),
+            beacon_checkpoint_root: String::new(),
+        };
+
+        for (i, record) in records.iter().enumerate() {
+            let path = crate::balance_merkle_path(&records, i);
+            let entry = crate::balance_merkle_pack_entry(record, &path);
+            let verified = metadata.verify_entry(&entry, i).expect("entry should verify");
+            assert_eq!(verified, *record);
+        }
+    }
+
+    #[test]
+    fn test_verify_entry_rejects_tampered_record() {
+        let records = vec![
+            BalanceRecord::new([1u8; 32], [2u8; 32]),
+            BalanceRecord::new([3u8; 32], [4u8; 32]),
+        ];
+        let root = crate::balance_merkle_root(&records);
+        let metadata = BalanceDbMetadata {
+            chain_id: 1,
+            snapshot_block: 0,
+            snapshot_block_hash: String::new(),
+            usdc_contract: String::new(),
+            record_size: BALANCE_RECORD_SIZE,
+            num_records: records.len(),
+            addresses: vec![],
+            merkle_root: hex::encode(root),
+            padded_entry_size: crate::balance_padded_entry_size(records.len()),
+            state_root: String::new(),
+            beacon_checkpoint_root: String::new(),
+        };
+
+        let path = crate::balance_merkle_path(&records, 0);
+        let mut tampered = records[0];
+        tampered.eth_balance[0] ^= 0xff;
+        let entry = crate::balance_merkle_pack_entry(&tampered, &path);
+
+        assert!(metadata.verify_entry(&entry, 0).is_err());
+    }
 }
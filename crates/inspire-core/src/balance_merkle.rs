@@ -0,0 +1,322 @@
+//! Keccak256 Merkle authentication for in-payload `BalanceRecord` entries
+//!
+//! A PIR query for the hot lane returns whatever `BalanceRecord` the server
+//! feels like, with no guarantee it's the real one -- PIR buys query
+//! privacy, not response integrity. This module is meant to let the
+//! authentication path travel *inside* the same oblivious fetch:
+//! `lane_builder::balance_extractor::BalanceExtractor` pads each database
+//! entry with the `ceil(log2(num_records))` sibling hashes on that record's
+//! path to a root stored in [`crate::BalanceDbMetadata`], and
+//! [`BalanceProofRecord::verify_against_root`] would fold the leaf back up
+//! to that root client-side, the same "don't trust it until it hashes up to
+//! a known root" idea as [`crate::merkle_proof`], recast over a small,
+//! record-indexed tree instead of a full-depth, tree-key-indexed one.
+//!
+//! # Status: builder-side primitive only, not wired into a serve/fetch path
+//!
+//! `lane-builder`'s standalone `balance-builder` binary builds a padded
+//! database file with these proofs baked in, but nothing currently serves a
+//! balance-lane entry over the network and no client (`inspire-server`,
+//! `inspire-client`, the wasm client) unpacks or calls
+//! [`BalanceProofRecord::verify_against_root`] /
+//! [`crate::BalanceDbMetadata::verify_entry`] against a response. Treat this
+//! as an authentication primitive ready for a balance-lane query path, not
+//! as something that currently authenticates a real fetch. Separately, the
+//! root it verifies against ([`crate::BalanceDbMetadata::merkle_root`])
+//! lives in the same unsigned `metadata.json` as the database it
+//! authenticates and isn't yet tied to any independently-trusted anchor
+//! (e.g. the CRS signature bundle in `inspire_core::crs_signing`) -- so even
+//! once wired into a fetch, a root from that file alone proves internal
+//! consistency, not that the database matches what the operator published.
+
+use thiserror::Error;
+
+use crate::balance::{BalanceRecord, BALANCE_RECORD_SIZE};
+
+/// Ordered sibling hashes from a leaf up to the root.
+///
+/// `siblings[0]` is the leaf's immediate sibling; `siblings[len - 1]` is the
+/// sibling at the level just below the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceMerklePath {
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl BalanceMerklePath {
+    pub fn new(siblings: Vec<[u8; 32]>) -> Self {
+        Self { siblings }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.siblings.len()
+    }
+}
+
+/// A PIR entry authenticated against the balance database's Merkle root:
+/// the retrieved [`BalanceRecord`] plus the path proving its inclusion at
+/// `index`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceProofRecord {
+    pub record: BalanceRecord,
+    pub index: usize,
+    pub path: BalanceMerklePath,
+}
+
+/// Errors from verifying a [`BalanceProofRecord`] against a trusted root
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BalanceMerkleError {
+    #[error("merkle path length {actual} does not match expected depth {expected}")]
+    WrongPathLength { expected: usize, actual: usize },
+
+    #[error("recomputed root does not match trusted balance db root")]
+    RootMismatch,
+}
+
+impl BalanceProofRecord {
+    pub fn new(record: BalanceRecord, index: usize, path: BalanceMerklePath) -> Self {
+        Self { record, index, path }
+    }
+
+    /// Verify this record's path folds up to `root`, requiring the path to
+    /// be exactly [`merkle_depth`] levels for `num_records`.
+    pub fn verify_against_root(
+        &self,
+        root: &[u8; 32],
+        num_records: usize,
+    ) -> Result<(), BalanceMerkleError> {
+        let expected_depth = merkle_depth(num_records);
+        if self.path.depth() != expected_depth {
+            return Err(BalanceMerkleError::WrongPathLength {
+                expected: expected_depth,
+                actual: self.path.depth(),
+            });
+        }
+
+        let mut node = leaf_hash(&self.record.to_bytes());
+        let mut idx = self.index;
+        for sibling in &self.path.siblings {
+            node = if idx % 2 == 0 {
+                fold(&node, sibling)
+            } else {
+                fold(sibling, &node)
+            };
+            idx /= 2;
+        }
+
+        if &node == root {
+            Ok(())
+        } else {
+            Err(BalanceMerkleError::RootMismatch)
+        }
+    }
+}
+
+/// Depth of a [`BalanceMerklePath`] (and the record's padded entry) for a
+/// database of `num_records` records: `ceil(log2(num_records))`, or `0` for
+/// an empty or single-record database (nothing to authenticate against).
+pub fn merkle_depth(num_records: usize) -> usize {
+    if num_records <= 1 {
+        0
+    } else {
+        (usize::BITS - (num_records - 1).leading_zeros()) as usize
+    }
+}
+
+/// Size of a padded PIR entry for a database of `num_records` records: the
+/// raw [`BalanceRecord`] plus its authentication path.
+pub fn padded_entry_size(num_records: usize) -> usize {
+    BALANCE_RECORD_SIZE + 32 * merkle_depth(num_records)
+}
+
+/// Build the Merkle root over `records` (builder-side; the tree is built
+/// over the records themselves, not the padded entries, so the root stays
+/// stable regardless of how proofs are packed).
+///
+/// Odd node counts at any level are completed by duplicating the last
+/// node, a fixed, deterministic rule that needs no special-casing for
+/// non-power-of-two `records.len()`.
+pub fn build_root(records: &[BalanceRecord]) -> [u8; 32] {
+    if records.is_empty() {
+        return [0u8; 32];
+    }
+    *build_levels(records).last().unwrap().first().unwrap()
+}
+
+/// Build the authentication path for `records[index]` (builder-side).
+pub fn build_path(records: &[BalanceRecord], index: usize) -> BalanceMerklePath {
+    if records.len() <= 1 {
+        return BalanceMerklePath::new(Vec::new());
+    }
+
+    let levels = build_levels(records);
+    let mut path = Vec::with_capacity(levels.len() - 1);
+    let mut idx = index;
+    for level in &levels[..levels.len() - 1] {
+        let sibling_idx = if idx % 2 == 0 { idx + 1 } else { idx - 1 };
+        path.push(*level.get(sibling_idx).unwrap_or(&level[idx]));
+        idx /= 2;
+    }
+    BalanceMerklePath::new(path)
+}
+
+/// Pack `record` plus its authentication `path` into a fixed-width PIR
+/// entry of [`padded_entry_size`] bytes.
+pub fn pack_entry(record: &BalanceRecord, path: &BalanceMerklePath) -> Vec<u8> {
+    let mut entry = record.to_bytes().to_vec();
+    for sibling in &path.siblings {
+        entry.extend_from_slice(sibling);
+    }
+    entry
+}
+
+/// Unpack a padded PIR entry into a [`BalanceProofRecord`] for `index`,
+/// without verifying it against a root (use
+/// [`BalanceProofRecord::verify_against_root`] for that).
+pub fn unpack_entry(entry: &[u8], index: usize) -> Option<BalanceProofRecord> {
+    let record = BalanceRecord::from_bytes(entry)?;
+    let siblings = entry[BALANCE_RECORD_SIZE..]
+        .chunks_exact(32)
+        .map(|c| c.try_into().expect("chunks_exact(32) yields 32-byte slices"))
+        .collect();
+    Some(BalanceProofRecord::new(record, index, BalanceMerklePath::new(siblings)))
+}
+
+fn build_levels(records: &[BalanceRecord]) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![records.iter().map(|r| leaf_hash(&r.to_bytes())).collect::<Vec<_>>()];
+    while levels.last().unwrap().len() > 1 {
+        let prev = levels.last().unwrap();
+        let mut next = Vec::with_capacity(prev.len().div_ceil(2));
+        let mut i = 0;
+        while i < prev.len() {
+            let left = &prev[i];
+            let right = prev.get(i + 1).unwrap_or(left);
+            next.push(fold(left, right));
+            i += 2;
+        }
+        levels.push(next);
+    }
+    levels
+}
+
+/// Leaf hash for a raw (unpadded) `BalanceRecord` encoding
+fn leaf_hash(record_bytes: &[u8; BALANCE_RECORD_SIZE]) -> [u8; 32] {
+    keccak256(record_bytes)
+}
+
+/// Fold two child hashes into their parent
+fn fold(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(left);
+    buf[32..].copy_from_slice(right);
+    keccak256(&buf)
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    use tiny_keccak::{Hasher, Keccak};
+
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut out = [0u8; 32];
+    hasher.finalize(&mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_records(n: usize) -> Vec<BalanceRecord> {
+        (0..n)
+            .map(|i| BalanceRecord::new([i as u8; 32], [(i as u8).wrapping_add(1); 32]))
+            .collect()
+    }
+
+    #[test]
+    fn test_merkle_depth() {
+        assert_eq!(merkle_depth(0), 0);
+        assert_eq!(merkle_depth(1), 0);
+        assert_eq!(merkle_depth(2), 1);
+        assert_eq!(merkle_depth(3), 2);
+        assert_eq!(merkle_depth(4), 2);
+        assert_eq!(merkle_depth(5), 3);
+        assert_eq!(merkle_depth(1000), 10);
+    }
+
+    #[test]
+    fn test_honest_entries_verify_power_of_two() {
+        let records = test_records(8);
+        let root = build_root(&records);
+
+        for (i, record) in records.iter().enumerate() {
+            let path = build_path(&records, i);
+            let proof = BalanceProofRecord::new(*record, i, path);
+            assert!(proof.verify_against_root(&root, records.len()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_honest_entries_verify_non_power_of_two() {
+        let records = test_records(5);
+        let root = build_root(&records);
+
+        for (i, record) in records.iter().enumerate() {
+            let path = build_path(&records, i);
+            let proof = BalanceProofRecord::new(*record, i, path);
+            assert!(proof.verify_against_root(&root, records.len()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_tampered_record_rejected() {
+        let records = test_records(8);
+        let root = build_root(&records);
+        let path = build_path(&records, 3);
+
+        let mut tampered = records[3];
+        tampered.eth_balance[0] ^= 0xff;
+        let proof = BalanceProofRecord::new(tampered, 3, path);
+
+        assert_eq!(
+            proof.verify_against_root(&root, records.len()),
+            Err(BalanceMerkleError::RootMismatch)
+        );
+    }
+
+    #[test]
+    fn test_truncated_path_rejected() {
+        let records = test_records(8);
+        let root = build_root(&records);
+        let mut path = build_path(&records, 0);
+        path.siblings.pop();
+
+        let proof = BalanceProofRecord::new(records[0], 0, path);
+        assert_eq!(
+            proof.verify_against_root(&root, records.len()),
+            Err(BalanceMerkleError::WrongPathLength { expected: 3, actual: 2 })
+        );
+    }
+
+    #[test]
+    fn test_pack_and_unpack_entry_roundtrip() {
+        let records = test_records(5);
+        let path = build_path(&records, 2);
+        let entry = pack_entry(&records[2], &path);
+
+        assert_eq!(entry.len(), padded_entry_size(records.len()));
+
+        let proof = unpack_entry(&entry, 2).unwrap();
+        assert_eq!(proof.record, records[2]);
+        assert_eq!(proof.path, path);
+    }
+
+    #[test]
+    fn test_single_record_db_has_empty_path() {
+        let records = test_records(1);
+        let root = build_root(&records);
+        let path = build_path(&records, 0);
+
+        assert_eq!(path.depth(), 0);
+        let proof = BalanceProofRecord::new(records[0], 0, path);
+        assert!(proof.verify_against_root(&root, 1).is_ok());
+    }
+}
@@ -0,0 +1,173 @@
+//! Shared bucket-indexing primitives for the sparse PIR bucket index.
+//!
+//! Every `(address, slot)` pair is hashed into one of [`NUM_BUCKETS`] fixed
+//! buckets, each holding a `u16` entry count. A client downloads the flat
+//! array of counts (see `inspire_client_wasm::bucket_index::BucketIndex`)
+//! and derives, for any pair, the contiguous `[start_index, count)` span it
+//! occupies in the lane's flat PIR database without a server round trip.
+//! [`range_delta`] describes the incremental delta-sync format served from
+//! `/index/deltas`.
+
+pub mod packed;
+pub mod range_delta;
+
+/// Default bucket granularity (an 18-bit hash prefix, i.e. a 512 KB
+/// `u16`-per-bucket raw index), used when an index file's header doesn't
+/// advertise a different [`compute_bucket_id`] width.
+pub const DEFAULT_BUCKET_BITS: u8 = 18;
+
+/// Number of buckets at [`DEFAULT_BUCKET_BITS`] granularity.
+pub const NUM_BUCKETS: usize = 1 << DEFAULT_BUCKET_BITS;
+
+/// Size in bytes of the 1-byte `bucket_bits` header prepended to the raw
+/// and bit-packed index binaries, so a server with a small state export can
+/// ship a 64K-bucket index and a large one a 1M-bucket index while clients
+/// read whatever granularity the file advertises instead of assuming
+/// [`DEFAULT_BUCKET_BITS`].
+pub const INDEX_HEADER_SIZE: usize = 1;
+
+/// Hash an `(address, slot)` pair down to its bucket id in
+/// `0..(1 << bucket_bits)`.
+pub fn compute_bucket_id(address: &[u8; 20], slot: &[u8; 32], bucket_bits: u8) -> usize {
+    let mut buf = [0u8; 52];
+    buf[..20].copy_from_slice(address);
+    buf[20..].copy_from_slice(slot);
+    let hash = keccak256(&buf);
+    let prefix = u32::from_be_bytes(hash[..4].try_into().unwrap());
+    (prefix as usize) & ((1usize << bucket_bits) - 1)
+}
+
+/// Turn per-bucket counts into prefix-sum offsets: `cumulative[i]` is the
+/// total entry count of every bucket before `i`, and the trailing
+/// `cumulative[counts.len()]` is the grand total.
+pub fn compute_cumulative(counts: &[u16]) -> Vec<u64> {
+    let mut cumulative = Vec::with_capacity(counts.len() + 1);
+    let mut sum = 0u64;
+    cumulative.push(0);
+    for &count in counts {
+        sum += count as u64;
+        cumulative.push(sum);
+    }
+    cumulative
+}
+
+/// A bucket-count delta broadcast over the websocket feed: the new count of
+/// every bucket that changed since the previous update, as of
+/// `block_number`. Wire format: `block_number:8 (LE u64) + update_count:4
+/// (LE u32) + (bucket_id:4 (LE u32) + new_count:2 (LE u16)) * update_count`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BucketDelta {
+    pub block_number: u64,
+    pub updates: Vec<(usize, u16)>,
+}
+
+impl BucketDelta {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(12 + self.updates.len() * 6);
+        buf.extend_from_slice(&self.block_number.to_le_bytes());
+        buf.extend_from_slice(&(self.updates.len() as u32).to_le_bytes());
+        for &(bucket_id, count) in &self.updates {
+            buf.extend_from_slice(&(bucket_id as u32).to_le_bytes());
+            buf.extend_from_slice(&count.to_le_bytes());
+        }
+        buf
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, DeltaError> {
+        if data.len() < 12 {
+            return Err(DeltaError::TooShort { actual: data.len() });
+        }
+        let block_number = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let count = u32::from_le_bytes(data[8..12].try_into().unwrap()) as usize;
+        let expected = 12 + count * 6;
+        if data.len() != expected {
+            return Err(DeltaError::SizeMismatch { expected, actual: data.len() });
+        }
+
+        let mut updates = Vec::with_capacity(count);
+        let mut offset = 12;
+        for _ in 0..count {
+            let bucket_id = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
+            let new_count = u16::from_le_bytes(data[offset + 4..offset + 6].try_into().unwrap());
+            updates.push((bucket_id, new_count));
+            offset += 6;
+        }
+
+        Ok(Self { block_number, updates })
+    }
+}
+
+/// Errors decoding a [`BucketDelta`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeltaError {
+    /// Data is shorter than the 12-byte header
+    TooShort { actual: usize },
+    /// Data length doesn't match the header's declared update count
+    SizeMismatch { expected: usize, actual: usize },
+}
+
+impl core::fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DeltaError::TooShort { actual } => {
+                write!(f, "bucket delta too short for header: {actual} bytes")
+            }
+            DeltaError::SizeMismatch { expected, actual } => {
+                write!(f, "bucket delta size mismatch: expected {expected} bytes, got {actual}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DeltaError {}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    use tiny_keccak::{Hasher, Keccak};
+
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut out = [0u8; 32];
+    hasher.finalize(&mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_bucket_id_deterministic_and_in_range() {
+        let address = [0x42u8; 20];
+        let slot = [0x01u8; 32];
+
+        let id1 = compute_bucket_id(&address, &slot, DEFAULT_BUCKET_BITS);
+        let id2 = compute_bucket_id(&address, &slot, DEFAULT_BUCKET_BITS);
+        assert_eq!(id1, id2);
+        assert!(id1 < NUM_BUCKETS);
+    }
+
+    #[test]
+    fn test_compute_bucket_id_respects_bit_width() {
+        let address = [0x42u8; 20];
+        let slot = [0x01u8; 32];
+        assert!(compute_bucket_id(&address, &slot, 16) < (1 << 16));
+    }
+
+    #[test]
+    fn test_compute_cumulative() {
+        let counts = vec![10u16, 5, 0, 3];
+        let cumulative = compute_cumulative(&counts);
+        assert_eq!(cumulative, vec![0, 10, 15, 15, 18]);
+    }
+
+    #[test]
+    fn test_bucket_delta_roundtrip() {
+        let delta = BucketDelta {
+            block_number: 42,
+            updates: vec![(0, 15), (100, 3)],
+        };
+        let bytes = delta.to_bytes();
+        let decoded = BucketDelta::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, delta);
+    }
+}
@@ -0,0 +1,116 @@
+//! Bit-packed encoding of bucket counts, for clients that would rather pay
+//! a decode pass than download the full 512 KB raw `u16`-per-bucket index
+//! (most buckets hold a handful of entries, so most of each fixed two-byte
+//! slot is wasted). In the style of tantivy's `BitPacker`: a short header
+//! records the chosen `bits_per_count` -- derived from the largest count in
+//! the index -- and every count is packed into a rolling bit buffer instead
+//! of a fixed-width slot.
+//!
+//! Served from `/index/packed` alongside the existing `/index/raw` raw
+//! format once that route lands (see `inspire_server::delta_stream`'s note
+//! on `/index/raw`/`/index/deltas` being unwired in this tree); clients
+//! that don't care about bandwidth keep using `BucketIndex::from_bytes`.
+
+/// Header: `bits_per_count:1 + bucket_count:4 (LE u32)`
+pub const HEADER_SIZE: usize = 5;
+
+/// Pack `counts` into the header-prefixed bit-packed format described above.
+pub fn pack(counts: &[u16]) -> Vec<u8> {
+    let max_count = counts.iter().copied().max().unwrap_or(0);
+    let bits_per_count = bits_needed(max_count);
+
+    let packed_bytes = (counts.len() * bits_per_count as usize).div_ceil(8);
+    let mut out = Vec::with_capacity(HEADER_SIZE + packed_bytes);
+    out.push(bits_per_count);
+    out.extend_from_slice(&(counts.len() as u32).to_le_bytes());
+
+    let mut mini_buffer: u64 = 0;
+    let mut bits_written: u32 = 0;
+    for &count in counts {
+        mini_buffer |= (count as u64) << bits_written;
+        bits_written += bits_per_count as u32;
+        while bits_written >= 8 {
+            out.push((mini_buffer & 0xff) as u8);
+            mini_buffer >>= 8;
+            bits_written -= 8;
+        }
+    }
+    if bits_written > 0 {
+        out.push((mini_buffer & 0xff) as u8);
+    }
+
+    out
+}
+
+/// Decode a payload produced by [`pack`] back into `bucket_count` counts.
+/// Returns `None` if the header's declared bucket count doesn't match, the
+/// bit width is out of range, or the payload is truncated.
+pub fn unpack(data: &[u8], bucket_count: usize) -> Option<Vec<u16>> {
+    if data.len() < HEADER_SIZE {
+        return None;
+    }
+
+    let bits_per_count = data[0];
+    let declared_count = u32::from_le_bytes(data[1..5].try_into().unwrap()) as usize;
+    if declared_count != bucket_count || bits_per_count == 0 || bits_per_count > 16 {
+        return None;
+    }
+
+    let payload = &data[HEADER_SIZE..];
+    let mask: u64 = (1u64 << bits_per_count) - 1;
+
+    let mut counts = Vec::with_capacity(bucket_count);
+    let mut mini_buffer: u64 = 0;
+    let mut bits_available: u32 = 0;
+    let mut byte_idx = 0;
+
+    for _ in 0..bucket_count {
+        while bits_available < bits_per_count as u32 {
+            let byte = *payload.get(byte_idx)?;
+            mini_buffer |= (byte as u64) << bits_available;
+            bits_available += 8;
+            byte_idx += 1;
+        }
+        counts.push((mini_buffer & mask) as u16);
+        mini_buffer >>= bits_per_count;
+        bits_available -= bits_per_count as u32;
+    }
+
+    Some(counts)
+}
+
+/// Minimum number of bits needed to represent `max_count`, at least 1 (so
+/// an all-empty index still round-trips instead of packing into zero bits).
+fn bits_needed(max_count: u16) -> u8 {
+    if max_count == 0 {
+        return 1;
+    }
+    (16 - max_count.leading_zeros()) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack_roundtrip() {
+        let counts = vec![0u16, 1, 2, 3, 100, 65535, 0, 7];
+        let packed = pack(&counts);
+        let unpacked = unpack(&packed, counts.len()).unwrap();
+        assert_eq!(unpacked, counts);
+    }
+
+    #[test]
+    fn test_pack_all_zero_uses_one_bit() {
+        let counts = vec![0u16; 10];
+        let packed = pack(&counts);
+        assert_eq!(packed[0], 1);
+        assert_eq!(unpack(&packed, counts.len()).unwrap(), counts);
+    }
+
+    #[test]
+    fn test_unpack_rejects_bucket_count_mismatch() {
+        let packed = pack(&[1u16, 2, 3]);
+        assert!(unpack(&packed, 4).is_none());
+    }
+}
@@ -0,0 +1,123 @@
+//! On-disk format for `/index/deltas`: a directory of pre-merged
+//! [`super::BucketDelta`] ranges covering increasingly large spans of
+//! recent blocks, so a client picks the smallest range covering its sync
+//! gap instead of replaying every individual delta since it last synced.
+//!
+//! Layout: a fixed-size [`RangeDeltaHeader`] followed by `num_ranges`
+//! [`RangeEntry`] directory entries, followed by the range payloads
+//! themselves (each a [`super::BucketDelta`] encoding) at their declared
+//! `offset`/`size`.
+
+/// Size in bytes of the leading [`RangeDeltaHeader`]
+pub const HEADER_SIZE: usize = 64;
+
+/// Size in bytes of a single [`RangeEntry`] directory record
+pub const RANGE_ENTRY_SIZE: usize = 16;
+
+/// Current on-disk format version
+pub const VERSION: u32 = 1;
+
+/// Default range directory: one range per decade of blocks behind, from a
+/// single block up to 10,000 -- beyond that a client re-downloads the full
+/// index instead of replaying deltas.
+pub const DEFAULT_RANGES: &[u32] = &[1, 10, 100, 1000, 10000];
+
+/// Fixed-size header at the start of an `/index/deltas` file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeDeltaHeader {
+    pub version: u32,
+    pub current_block: u64,
+    pub num_ranges: u32,
+    /// Bucket granularity of the base index these deltas apply to, so a
+    /// client can't accidentally replay deltas meant for a differently
+    /// bucketed index. See [`super::compute_bucket_id`].
+    pub bucket_bits: u8,
+}
+
+impl RangeDeltaHeader {
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut buf = [0u8; HEADER_SIZE];
+        buf[0..4].copy_from_slice(&self.version.to_le_bytes());
+        buf[4..12].copy_from_slice(&self.current_block.to_le_bytes());
+        buf[12..16].copy_from_slice(&self.num_ranges.to_le_bytes());
+        buf[16] = self.bucket_bits;
+        buf
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < HEADER_SIZE {
+            return None;
+        }
+        Some(Self {
+            version: u32::from_le_bytes(data[0..4].try_into().unwrap()),
+            current_block: u64::from_le_bytes(data[4..12].try_into().unwrap()),
+            num_ranges: u32::from_le_bytes(data[12..16].try_into().unwrap()),
+            bucket_bits: data[16],
+        })
+    }
+}
+
+/// One directory entry pointing at a pre-merged delta range within the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeEntry {
+    /// Number of blocks this range's merged delta covers
+    pub blocks_covered: u32,
+    /// Byte offset of the range's [`super::BucketDelta`] payload
+    pub offset: u32,
+    /// Byte size of the range's payload
+    pub size: u32,
+    /// Number of bucket updates the merged delta contains
+    pub entry_count: u32,
+}
+
+impl RangeEntry {
+    pub fn to_bytes(&self) -> [u8; RANGE_ENTRY_SIZE] {
+        let mut buf = [0u8; RANGE_ENTRY_SIZE];
+        buf[0..4].copy_from_slice(&self.blocks_covered.to_le_bytes());
+        buf[4..8].copy_from_slice(&self.offset.to_le_bytes());
+        buf[8..12].copy_from_slice(&self.size.to_le_bytes());
+        buf[12..16].copy_from_slice(&self.entry_count.to_le_bytes());
+        buf
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() < RANGE_ENTRY_SIZE {
+            return None;
+        }
+        Some(Self {
+            blocks_covered: u32::from_le_bytes(data[0..4].try_into().unwrap()),
+            offset: u32::from_le_bytes(data[4..8].try_into().unwrap()),
+            size: u32::from_le_bytes(data[8..12].try_into().unwrap()),
+            entry_count: u32::from_le_bytes(data[12..16].try_into().unwrap()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_roundtrip() {
+        let header = RangeDeltaHeader {
+            version: VERSION,
+            current_block: 12345,
+            num_ranges: DEFAULT_RANGES.len() as u32,
+            bucket_bits: crate::bucket_index::DEFAULT_BUCKET_BITS,
+        };
+        let bytes = header.to_bytes();
+        assert_eq!(RangeDeltaHeader::from_bytes(&bytes), Some(header));
+    }
+
+    #[test]
+    fn test_range_entry_roundtrip() {
+        let entry = RangeEntry {
+            blocks_covered: 100,
+            offset: 512,
+            size: 2048,
+            entry_count: 30,
+        };
+        let bytes = entry.to_bytes();
+        assert_eq!(RangeEntry::from_bytes(&bytes), Some(entry));
+    }
+}
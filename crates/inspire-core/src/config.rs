@@ -6,6 +6,51 @@ use std::path::PathBuf;
 /// Protocol version constant
 pub const PROTOCOL_VERSION: &str = "1.0.0";
 
+/// Kernel access-pattern hint (`madvise`/`posix_fadvise`) applied to
+/// mmap'd shard files after `MmapDatabase::open`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MmapAccessPattern {
+    /// No hint — leave it to the kernel's default heuristics
+    Normal,
+    /// Queries touch shards in unpredictable order (MADV_RANDOM) — the
+    /// expected pattern for PIR, which by design scatters reads across
+    /// the whole lane
+    Random,
+    /// Shards will be scanned start-to-end (MADV_SEQUENTIAL)
+    Sequential,
+    /// Hint the kernel to read the whole lane in ahead of time (MADV_WILLNEED)
+    WillNeed,
+}
+
+impl Default for MmapAccessPattern {
+    fn default() -> Self {
+        MmapAccessPattern::Random
+    }
+}
+
+/// Which role a server process plays in a distributed deployment
+///
+/// `Standalone` is the only mode this crate historically supported: one
+/// process owns the lane data on local disk and serves queries against it
+/// directly. `Ingest`/`Query` split that apart so a query-serving fleet can
+/// scale independently of the (much heavier) state-dump pipeline -- see
+/// `inspire_server::cluster`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeMode {
+    /// Owns lane data on local disk and serves queries against it directly
+    /// (the only mode this crate supported before cluster mode)
+    #[default]
+    Standalone,
+    /// Owns the `state-dump` pipeline and on-disk lane files, periodically
+    /// reloading its snapshot and registering with query nodes
+    Ingest,
+    /// Holds no lane data of its own; proxies `process_query` to a
+    /// registered ingest node chosen by consistent hashing
+    Query,
+}
+
 /// Configuration for the two-lane PIR system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TwoLaneConfig {
@@ -43,12 +88,108 @@ pub struct TwoLaneConfig {
     /// Shard size in bytes (for mmap mode, default 128KB)
     #[serde(default = "default_shard_size")]
     pub shard_size_bytes: u64,
+    /// Store lane shards in an embedded KV store instead of JSON/mmap.
+    /// Takes precedence over `use_mmap` when set.
+    #[serde(default)]
+    pub use_kv_store: bool,
+    /// Directory holding the KV-backed shard stores (`hot.redb`/`cold.redb`),
+    /// required when `use_kv_store` is enabled.
+    #[serde(default)]
+    pub kv_store_dir: Option<PathBuf>,
+    /// `madvise`/`posix_fadvise` hint applied to mmap'd shard files
+    #[serde(default)]
+    pub mmap_access_pattern: MmapAccessPattern,
+    /// Read through every shard file once after opening so the first
+    /// queries don't pay major page faults
+    #[serde(default)]
+    pub mmap_prefault: bool,
+    /// Pin the hot lane's shard pages in RAM via `mlock`. Not offered for
+    /// the cold lane — its ~2.7B entries are usually too large to pin.
+    #[serde(default)]
+    pub mmap_mlock_hot_lane: bool,
+    /// Cross-check each loaded lane's committed UBT root (from CRS
+    /// metadata) against the chain's authoritative root before swapping a
+    /// new snapshot in. Requires `ubt_root_rpc_url`; left off by default so
+    /// air-gapped deployments without RPC access can still reload.
+    #[serde(default)]
+    pub verify_ubt_root: bool,
+    /// `ubt_getRoot` RPC endpoint used for `verify_ubt_root`.
+    #[serde(default)]
+    pub ubt_root_rpc_url: Option<String>,
+    /// Check each loaded lane's `crs.sig` against the verifying key embedded
+    /// in its CRS metadata before swapping a new snapshot in. Left off by
+    /// default so CRS/DB built without `TwoLaneSetup::sign_with` still load.
+    ///
+    /// On its own this only proves internal self-consistency -- the
+    /// signature and the key it's checked against both come from the same
+    /// `crs.meta.json`, so an attacker who can replace the whole CRS bundle
+    /// (e.g. a compromised mirror or object-store backend) can mint a fresh
+    /// keypair and pass this check trivially. Set
+    /// [`trusted_crs_verifying_key`](Self::trusted_crs_verifying_key) to
+    /// anchor the check to a key the operator actually trusts.
+    #[serde(default)]
+    pub verify_crs_signature: bool,
+    /// The CRS verifying key the operator trusts out-of-band, pinned here
+    /// rather than read from the CRS metadata under test. When set (and
+    /// `verify_crs_signature` is enabled), [`crate::crs_signing`]
+    /// verification additionally requires each lane's `crs.meta.json` to
+    /// embed exactly this key, rejecting a bundle that merely signs
+    /// consistently with a key of the attacker's own choosing.
+    #[serde(default)]
+    pub trusted_crs_verifying_key: Option<crate::crs_signing::CrsVerifyingKey>,
+    /// Shared secret required (as `Authorization: Bearer <token>`) on
+    /// `/admin/reload` and `/admin/reload/poll`. `None` leaves those routes
+    /// unauthenticated, relying on the admin listener being bound to
+    /// localhost only, as documented on `create_admin_router`.
+    #[serde(default)]
+    pub admin_reload_token: Option<String>,
+    /// Which role this process plays -- standalone (default), ingest, or
+    /// query. See [`NodeMode`].
+    #[serde(default)]
+    pub node_mode: NodeMode,
+    /// This node's own externally-reachable base URL (e.g.
+    /// `http://10.0.1.4:3000`), sent as the `url` field of a
+    /// `/cluster/register` call. Required for `node_mode: Ingest`.
+    #[serde(default)]
+    pub cluster_self_url: Option<String>,
+    /// Query-node addresses an ingest node registers itself with on
+    /// startup and on every successful reload. Ignored in other modes.
+    #[serde(default)]
+    pub cluster_query_nodes: Vec<String>,
+    /// Object-store location (`s3://bucket/prefix`, or a filesystem path)
+    /// backing lane loading. When set, `hot_lane_crs`/`hot_lane_db`/
+    /// `cold_lane_crs`/`cold_lane_db` and `kv_store_dir`'s
+    /// `hot.redb`/`cold.redb` are treated as keys relative to this store
+    /// rather than local paths, downloaded into `lane_store_cache_dir`
+    /// before loading -- see [`crate::Store`]. Only the in-memory and KV
+    /// backends are supported this way; `use_mmap` still requires its
+    /// shards directory on local disk, since a shard set is many files and
+    /// [`crate::Store`] only addresses single objects.
+    #[serde(default)]
+    pub lane_store: Option<String>,
+    /// Credentials/region/endpoint for `lane_store` when it's an
+    /// `s3://...` location. Ignored for a filesystem `lane_store`.
+    #[serde(default)]
+    pub lane_store_s3: Option<crate::S3Config>,
+    /// Local directory `lane_store` downloads are cached in. Defaults to
+    /// `std::env::temp_dir().join("inspire-lane-store-cache")` when unset.
+    #[serde(default)]
+    pub lane_store_cache_dir: Option<PathBuf>,
+    /// Maximum number of queries accepted in one `/query/:lane/batch*`
+    /// request. A larger batch is rejected with `413`/`BatchTooLarge`
+    /// rather than accepted and left to exhaust server memory/CPU.
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
 }
 
 fn default_shard_size() -> u64 {
     128 * 1024
 }
 
+fn default_max_batch_size() -> usize {
+    10_000
+}
+
 fn default_version() -> String {
     PROTOCOL_VERSION.to_string()
 }
@@ -87,6 +228,23 @@ impl TwoLaneConfig {
             cold_lane_shards: Some(cold.join("shards")),
             use_mmap: true,
             shard_size_bytes: default_shard_size(),
+            use_kv_store: false,
+            kv_store_dir: None,
+            mmap_access_pattern: MmapAccessPattern::default(),
+            mmap_prefault: false,
+            mmap_mlock_hot_lane: false,
+            verify_ubt_root: false,
+            ubt_root_rpc_url: None,
+            verify_crs_signature: false,
+            trusted_crs_verifying_key: None,
+            admin_reload_token: None,
+            node_mode: NodeMode::default(),
+            cluster_self_url: None,
+            cluster_query_nodes: Vec::new(),
+            lane_store: None,
+            lane_store_s3: None,
+            lane_store_cache_dir: None,
+            max_batch_size: default_max_batch_size(),
         }
     }
 
@@ -103,6 +261,70 @@ impl TwoLaneConfig {
         self
     }
 
+    /// Store lane shards in an embedded KV store at `dir` (overrides
+    /// `use_mmap` for backend selection)
+    pub fn with_kv_store(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.use_kv_store = true;
+        self.kv_store_dir = Some(dir.into());
+        self
+    }
+
+    /// Set the `madvise`/`posix_fadvise` access-pattern hint for mmap'd
+    /// shard files
+    pub fn with_mmap_access_pattern(mut self, pattern: MmapAccessPattern) -> Self {
+        self.mmap_access_pattern = pattern;
+        self
+    }
+
+    /// Warm the page cache for every shard file right after opening
+    pub fn with_mmap_prefault(mut self, enabled: bool) -> Self {
+        self.mmap_prefault = enabled;
+        self
+    }
+
+    /// Pin the hot lane's shard pages in RAM via `mlock`
+    pub fn with_mmap_mlock_hot_lane(mut self, enabled: bool) -> Self {
+        self.mmap_mlock_hot_lane = enabled;
+        self
+    }
+
+    /// Enable UBT root verification against `rpc_url` on every lane load
+    pub fn with_ubt_root_verification(mut self, rpc_url: impl Into<String>) -> Self {
+        self.verify_ubt_root = true;
+        self.ubt_root_rpc_url = Some(rpc_url.into());
+        self
+    }
+
+    /// Enable CRS signature verification against each lane's `crs.sig` on
+    /// every lane load
+    pub fn with_crs_signature_verification(mut self, enabled: bool) -> Self {
+        self.verify_crs_signature = enabled;
+        self
+    }
+
+    /// Pin the CRS verifying key the operator trusts out-of-band. See
+    /// [`Self::trusted_crs_verifying_key`].
+    pub fn with_trusted_crs_verifying_key(mut self, key: crate::crs_signing::CrsVerifyingKey) -> Self {
+        self.trusted_crs_verifying_key = Some(key);
+        self
+    }
+
+    /// Load `hot_lane_crs`/`hot_lane_db`/`cold_lane_crs`/`cold_lane_db` and
+    /// the KV backend's shard files from `location` instead of the local
+    /// filesystem. See [`TwoLaneConfig::lane_store`].
+    pub fn with_lane_store(mut self, location: impl Into<String>, s3_config: Option<crate::S3Config>) -> Self {
+        self.lane_store = Some(location.into());
+        self.lane_store_s3 = s3_config;
+        self
+    }
+
+    /// Cap on queries accepted in one `/query/:lane/batch*` request. See
+    /// [`TwoLaneConfig::max_batch_size`].
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
     /// Load configuration from a JSON file
     pub fn load(path: impl AsRef<std::path::Path>) -> crate::Result<Self> {
         let content = std::fs::read_to_string(path.as_ref())?;
@@ -136,14 +358,24 @@ impl TwoLaneConfig {
     /// This hash includes entry counts and entry size, which are the key
     /// parameters that must match between client and server.
     pub fn compute_hash(&self) -> String {
+        Self::compute_hash_from(self.hot_entries, self.cold_entries, self.entry_size, &self.version)
+    }
+
+    /// Pure core of [`Self::compute_hash`], taking just the shape fields
+    /// it hashes rather than a full `TwoLaneConfig`. Lets a caller that
+    /// only knows its own shape from CRS/`/info` responses (e.g. the wasm
+    /// client, which has no `PathBuf`s to build a `TwoLaneConfig` from)
+    /// recompute the same hash and compare it against what the server
+    /// advertises, without reconstructing a config it doesn't have.
+    pub fn compute_hash_from(hot_entries: u64, cold_entries: u64, entry_size: usize, version: &str) -> String {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
-        
+
         let mut hasher = DefaultHasher::new();
-        self.hot_entries.hash(&mut hasher);
-        self.cold_entries.hash(&mut hasher);
-        self.entry_size.hash(&mut hasher);
-        self.version.hash(&mut hasher);
+        hot_entries.hash(&mut hasher);
+        cold_entries.hash(&mut hasher);
+        entry_size.hash(&mut hasher);
+        version.hash(&mut hasher);
         format!("{:016x}", hasher.finish())
     }
 
@@ -0,0 +1,233 @@
+//! Detached signatures over CRS sidecars
+//!
+//! `crs.json` and `crs.meta.json` are served to clients over plain HTTP by
+//! `lane-builder`'s `reload`/`checkpoint` paths, with no guarantee the bytes
+//! a client receives are the ones the server actually generated -- a
+//! malicious mirror or a tampered-with transport could swap either file.
+//! This module signs the concatenation of both files and embeds the
+//! verifying key in [`CrsMetadata`] itself, so a client only needs the CRS
+//! sidecar to check `crs.sig`.
+//!
+//! [`verify_crs_signature`] only proves the bundle is internally
+//! self-consistent -- that whoever produced it also holds the private key
+//! for the public key it carries -- which by itself is not very useful: an
+//! attacker who can replace the whole bundle can mint a fresh keypair and
+//! pass this check trivially. The out-of-band half (the operator's public
+//! key, trusted independently of the file being checked) is
+//! `TwoLaneConfig::trusted_crs_verifying_key` -- see
+//! `inspire_server::state::ServerState::validate_crs_metadata`, which
+//! rejects any CRS metadata whose embedded key doesn't match it before
+//! calling [`verify_crs_signature`] at all.
+//!
+//! Both ed25519 and secp256k1 are supported via the `signature` crate's
+//! shared `Signer`/`Verifier` traits, so Ethereum operators can sign with
+//! an existing secp256k1 key instead of minting a fresh ed25519 one.
+
+use std::path::Path;
+
+use ed25519_dalek::{Signer as _, Verifier as _};
+use k256::ecdsa::signature::{Signer as _, Verifier as _};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Errors signing or verifying a CRS sidecar
+#[derive(Error, Debug)]
+pub enum CrsSigningError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid key encoding: {0}")]
+    InvalidKey(String),
+
+    #[error("invalid signature encoding: {0}")]
+    InvalidSignature(String),
+
+    #[error("CRS metadata has no embedded verifying key")]
+    MissingVerifyingKey,
+
+    #[error("CRS signature does not verify against the embedded verifying key")]
+    SignatureMismatch,
+}
+
+/// A private key able to sign a CRS sidecar, in either supported curve
+pub enum CrsSigningKey {
+    Ed25519(ed25519_dalek::SigningKey),
+    Secp256k1(k256::ecdsa::SigningKey),
+}
+
+impl CrsSigningKey {
+    /// The verifying half of this key, for embedding in [`CrsMetadata`]
+    pub fn verifying_key(&self) -> CrsVerifyingKey {
+        match self {
+            CrsSigningKey::Ed25519(sk) => {
+                CrsVerifyingKey::Ed25519(hex::encode(sk.verifying_key().to_bytes()))
+            }
+            CrsSigningKey::Secp256k1(sk) => {
+                let point = sk.verifying_key().to_encoded_point(true);
+                CrsVerifyingKey::Secp256k1(hex::encode(point.as_bytes()))
+            }
+        }
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            CrsSigningKey::Ed25519(sk) => sk.sign(message).to_bytes().to_vec(),
+            CrsSigningKey::Secp256k1(sk) => {
+                let sig: k256::ecdsa::Signature = sk.sign(message);
+                sig.to_bytes().to_vec()
+            }
+        }
+    }
+}
+
+/// The verifying key for a [`CrsSigningKey`], embedded in
+/// [`CrsMetadata::verifying_key`](crate::CrsMetadata::verifying_key) so a
+/// client can check `crs.sig` without a separate key-distribution channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "scheme", content = "key", rename_all = "snake_case")]
+pub enum CrsVerifyingKey {
+    /// Hex-encoded 32-byte ed25519 public key
+    Ed25519(String),
+    /// Hex-encoded SEC1-compressed (33-byte) secp256k1 public key
+    Secp256k1(String),
+}
+
+/// Read `crs_path` and `meta_path` and concatenate their bytes into the
+/// canonical preimage a CRS signature is computed over.
+fn canonical_preimage(crs_path: &Path, meta_path: &Path) -> std::io::Result<Vec<u8>> {
+    let mut preimage = std::fs::read(crs_path)?;
+    preimage.extend(std::fs::read(meta_path)?);
+    Ok(preimage)
+}
+
+/// Sign `crs_path` + `meta_path`'s bytes with `key`, writing a detached,
+/// hex-encoded signature to `sig_path` (conventionally `crs.sig` alongside
+/// `crs.json`).
+pub fn sign_crs(
+    key: &CrsSigningKey,
+    crs_path: &Path,
+    meta_path: &Path,
+    sig_path: &Path,
+) -> Result<(), CrsSigningError> {
+    let preimage = canonical_preimage(crs_path, meta_path)?;
+    let signature = key.sign(&preimage);
+    std::fs::write(sig_path, hex::encode(signature))?;
+    Ok(())
+}
+
+/// Verify that `sig_path` holds a valid signature over `crs_path` +
+/// `meta_path` under `verifying_key` (as embedded in
+/// [`CrsMetadata::verifying_key`](crate::CrsMetadata::verifying_key)).
+pub fn verify_crs_signature(
+    verifying_key: &CrsVerifyingKey,
+    crs_path: &Path,
+    meta_path: &Path,
+    sig_path: &Path,
+) -> Result<(), CrsSigningError> {
+    let preimage = canonical_preimage(crs_path, meta_path)?;
+    let sig_hex = std::fs::read_to_string(sig_path)?;
+    let sig_bytes =
+        hex::decode(sig_hex.trim()).map_err(|e| CrsSigningError::InvalidSignature(e.to_string()))?;
+
+    match verifying_key {
+        CrsVerifyingKey::Ed25519(key) => {
+            let key_bytes =
+                hex::decode(key).map_err(|e| CrsSigningError::InvalidKey(e.to_string()))?;
+            let key_arr: [u8; 32] = key_bytes
+                .try_into()
+                .map_err(|_| CrsSigningError::InvalidKey("expected 32 bytes".to_string()))?;
+            let vk = ed25519_dalek::VerifyingKey::from_bytes(&key_arr)
+                .map_err(|e| CrsSigningError::InvalidKey(e.to_string()))?;
+            let sig_arr: [u8; 64] = sig_bytes
+                .try_into()
+                .map_err(|_| CrsSigningError::InvalidSignature("expected 64 bytes".to_string()))?;
+            let sig = ed25519_dalek::Signature::from_bytes(&sig_arr);
+            vk.verify(&preimage, &sig)
+                .map_err(|_| CrsSigningError::SignatureMismatch)
+        }
+        CrsVerifyingKey::Secp256k1(key) => {
+            let key_bytes =
+                hex::decode(key).map_err(|e| CrsSigningError::InvalidKey(e.to_string()))?;
+            let vk = k256::ecdsa::VerifyingKey::from_sec1_bytes(&key_bytes)
+                .map_err(|e| CrsSigningError::InvalidKey(e.to_string()))?;
+            let sig = k256::ecdsa::Signature::from_slice(&sig_bytes)
+                .map_err(|e| CrsSigningError::InvalidSignature(e.to_string()))?;
+            vk.verify(&preimage, &sig)
+                .map_err(|_| CrsSigningError::SignatureMismatch)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ed25519_sign_and_verify_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let crs_path = dir.path().join("crs.json");
+        let meta_path = dir.path().join("crs.meta.json");
+        let sig_path = dir.path().join("crs.sig");
+        std::fs::write(&crs_path, b"{\"crs\":true}").unwrap();
+        std::fs::write(&meta_path, b"{\"meta\":true}").unwrap();
+
+        let key = CrsSigningKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[1u8; 32]));
+        let verifying_key = key.verifying_key();
+        sign_crs(&key, &crs_path, &meta_path, &sig_path).unwrap();
+
+        verify_crs_signature(&verifying_key, &crs_path, &meta_path, &sig_path).unwrap();
+    }
+
+    #[test]
+    fn test_secp256k1_sign_and_verify_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let crs_path = dir.path().join("crs.json");
+        let meta_path = dir.path().join("crs.meta.json");
+        let sig_path = dir.path().join("crs.sig");
+        std::fs::write(&crs_path, b"{\"crs\":true}").unwrap();
+        std::fs::write(&meta_path, b"{\"meta\":true}").unwrap();
+
+        let key = CrsSigningKey::Secp256k1(k256::ecdsa::SigningKey::from_bytes(&[2u8; 32].into()).unwrap());
+        let verifying_key = key.verifying_key();
+        sign_crs(&key, &crs_path, &meta_path, &sig_path).unwrap();
+
+        verify_crs_signature(&verifying_key, &crs_path, &meta_path, &sig_path).unwrap();
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let crs_path = dir.path().join("crs.json");
+        let meta_path = dir.path().join("crs.meta.json");
+        let sig_path = dir.path().join("crs.sig");
+        std::fs::write(&crs_path, b"{\"crs\":true}").unwrap();
+        std::fs::write(&meta_path, b"{\"meta\":true}").unwrap();
+
+        let key = CrsSigningKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[3u8; 32]));
+        let verifying_key = key.verifying_key();
+        sign_crs(&key, &crs_path, &meta_path, &sig_path).unwrap();
+
+        std::fs::write(&meta_path, b"{\"meta\":false}").unwrap();
+        assert!(matches!(
+            verify_crs_signature(&verifying_key, &crs_path, &meta_path, &sig_path),
+            Err(CrsSigningError::SignatureMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_verify_rejects_wrong_curve() {
+        let dir = tempfile::tempdir().unwrap();
+        let crs_path = dir.path().join("crs.json");
+        let meta_path = dir.path().join("crs.meta.json");
+        let sig_path = dir.path().join("crs.sig");
+        std::fs::write(&crs_path, b"{\"crs\":true}").unwrap();
+        std::fs::write(&meta_path, b"{\"meta\":true}").unwrap();
+
+        let ed_key = CrsSigningKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[4u8; 32]));
+        sign_crs(&ed_key, &crs_path, &meta_path, &sig_path).unwrap();
+
+        let k256_key = CrsSigningKey::Secp256k1(k256::ecdsa::SigningKey::from_bytes(&[5u8; 32].into()).unwrap());
+        let wrong_verifying_key = k256_key.verifying_key();
+        assert!(verify_crs_signature(&wrong_verifying_key, &crs_path, &meta_path, &sig_path).is_err());
+    }
+}
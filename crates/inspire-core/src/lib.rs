@@ -46,14 +46,49 @@ mod routing;
 mod indexing;
 mod error;
 mod balance;
+mod balance_merkle;
+mod merkle_proof;
+mod params;
+mod crs_signing;
+mod state_format;
+mod snapshot_blacklist;
+mod state_backend;
+mod state_migration;
+mod stem_index;
+mod store;
+pub mod bucket_index;
 
 pub use lane::Lane;
-pub use config::{TwoLaneConfig, PROTOCOL_VERSION};
-pub use manifest::{HotLaneManifest, HotContract};
-pub use routing::{LaneRouter, QueryTarget, RoutedQuery};
+pub use config::{TwoLaneConfig, MmapAccessPattern, NodeMode, PROTOCOL_VERSION};
+pub use manifest::{
+    HotLaneManifest, HotContract, ColdCandidate, ManifestDelta, ManifestInclusionProof,
+    RebalanceConfig, RebalancePlan, ReindexedContract, ResizedContract, MANIFEST_VERSION,
+};
+pub use routing::{LaneRouter, QueryTarget, RoutedBatch, RoutedQuery};
 pub use indexing::{slot_to_offset, hot_index, cold_index};
 pub use error::Error;
 pub use balance::{BalanceRecord, BalanceDbMetadata, BALANCE_RECORD_SIZE};
+pub use balance_merkle::{
+    build_path as balance_merkle_path, build_root as balance_merkle_root, merkle_depth as balance_merkle_depth,
+    pack_entry as balance_merkle_pack_entry, padded_entry_size as balance_padded_entry_size,
+    unpack_entry as balance_merkle_unpack_entry, BalanceMerkleError, BalanceMerklePath, BalanceProofRecord,
+};
+pub use merkle_proof::{MerklePath, MerkleProofError, ProofRecord, UBT_TREE_DEPTH};
+pub use params::{
+    CrsMetadata, ParamsVersionError, PirParams, PIR_PARAMS, PIR_PARAMS_MIN_SUPPORTED_VERSION,
+    PIR_PARAMS_VERSION,
+};
+pub use crs_signing::{
+    sign_crs, verify_crs_signature, CrsSigningError, CrsSigningKey, CrsVerifyingKey,
+};
+pub use state_format::{
+    StateFormatError, StateHeader, StorageEntry, STATE_ENTRY_SIZE, STATE_HEADER_SIZE, STATE_MAGIC,
+};
+pub use snapshot_blacklist::SnapshotBlacklist;
+pub use state_backend::{open as open_state_backend, BackendKind, InMemoryBackend, MmapBackend, StateBackend};
+pub use state_migration::{DigestBackfillMigration, MigrationRegistry, StateMigration};
+pub use stem_index::{Stem, StemIndex, StemIndexError, STEM_LEN};
+pub use store::{open_store, FilesystemStore, S3Config, S3Store, Store, StoreError, StoreLocation};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -18,6 +18,89 @@ pub struct HotContract {
     pub start_index: u64,
     /// Category (e.g., "defi", "token", "privacy", "nft")
     pub category: String,
+    /// Running count of PIR queries served for this contract (best-effort;
+    /// incremented on each [`HotContract::record_access`] call)
+    #[serde(default)]
+    pub access_count: u64,
+    /// Exponentially-decayed access rate, the basis for
+    /// [`HotLaneManifest::rebalance`] hot/cold decisions. Higher means
+    /// "queried more recently and more often".
+    #[serde(default)]
+    pub access_ewma: f64,
+    /// Unix timestamp of the last [`HotContract::record_access`] call, used
+    /// to compute how much `access_ewma` has decayed since then
+    #[serde(default)]
+    pub last_access_unix: u64,
+}
+
+impl HotContract {
+    /// Record a query against this contract, decaying the existing EWMA by
+    /// its half-life over the elapsed time before adding the new sample.
+    /// `now_unix` and `half_life_secs` are both in seconds.
+    pub fn record_access(&mut self, now_unix: u64, half_life_secs: u64) {
+        self.access_count += 1;
+
+        let elapsed_secs = now_unix.saturating_sub(self.last_access_unix) as f64;
+        let decay = if half_life_secs == 0 {
+            0.0
+        } else {
+            0.5f64.powf(elapsed_secs / half_life_secs as f64)
+        };
+        self.access_ewma = self.access_ewma * decay + 1.0;
+        self.last_access_unix = now_unix;
+    }
+}
+
+/// A cold-lane contract's decayed access rate, tracked the same way as
+/// [`HotContract::access_ewma`] so it can be compared against the hot lane's
+/// scores in [`HotLaneManifest::rebalance`].
+#[derive(Debug, Clone, Copy)]
+pub struct ColdCandidate {
+    pub address: Address,
+    pub access_ewma: f64,
+}
+
+/// Tuning for [`HotLaneManifest::rebalance`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RebalanceConfig {
+    /// Half-life, in seconds, for [`HotContract::record_access`]'s decay
+    pub half_life_secs: u64,
+    /// EWMA score above which a cold contract is considered "hot enough"
+    pub promote_threshold: f64,
+    /// Margin subtracted from / added to `promote_threshold` when deciding
+    /// demotion/promotion, so contracts hovering near the threshold don't
+    /// flip lanes on every reload
+    pub hysteresis_margin: f64,
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            half_life_secs: 3600,
+            promote_threshold: 1.0,
+            hysteresis_margin: 0.2,
+        }
+    }
+}
+
+/// The result of [`HotLaneManifest::rebalance`]: contracts to move between
+/// lanes on the next reload.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RebalancePlan {
+    /// Hot contracts whose EWMA fell below `promote_threshold - hysteresis_margin`
+    pub demote: Vec<Address>,
+    /// Cold candidates whose EWMA rose above `promote_threshold + hysteresis_margin`
+    pub promote: Vec<Address>,
+}
+
+/// Current manifest format version. Bumped from 1 to 2 when the Merkle
+/// commitment field was added, and from 2 to 3 when `num_shards` was added
+/// for multi-node deployments. Older manifests still deserialize: missing
+/// fields default (`merkle_root` to `None`, `num_shards` to `1`).
+pub const MANIFEST_VERSION: u32 = 3;
+
+fn default_num_shards() -> u32 {
+    1
 }
 
 /// Hot lane manifest containing all contracts in the hot lane
@@ -33,6 +116,87 @@ pub struct HotLaneManifest {
     pub total_entries: u64,
     /// Version of the manifest format
     pub version: u32,
+    /// Merkle commitment to `contracts` (see [`HotLaneManifest::commit_merkle_root`]).
+    /// A malicious or desynced server cannot silently reorder `start_index`
+    /// values without changing this root, so clients that pin the expected
+    /// root over an authenticated channel can detect layout drift before
+    /// issuing queries. `None` for manifests saved before version 2, or
+    /// before `commit_merkle_root` has been called.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merkle_root: Option<[u8; 32]>,
+    /// Number of PIR server nodes this manifest's contracts are split
+    /// across. Each contract's node is [`HotLaneManifest::shard_for`] its
+    /// address, deterministic and independent of manifest order, so shard
+    /// membership is stable across reloads. Defaults to `1` (single node)
+    /// for manifests saved before this field existed.
+    #[serde(default = "default_num_shards")]
+    pub num_shards: u32,
+    /// Ethereum `stateRoot` this manifest's balances were verified against
+    /// (see `lane_builder::verify_account_proof`), so downstream components
+    /// can assert the data's provenance instead of trusting an RPC blindly.
+    /// `None` for manifests built without proof verification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_root: Option<[u8; 32]>,
+}
+
+/// A contract whose `slot_count` changed between two manifests
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResizedContract {
+    #[serde(with = "hex_address")]
+    pub address: Address,
+    pub old_slot_count: u64,
+    pub new_slot_count: u64,
+}
+
+/// A contract whose `start_index` shifted between two manifests
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReindexedContract {
+    #[serde(with = "hex_address")]
+    pub address: Address,
+    pub old_start_index: u64,
+    pub new_start_index: u64,
+}
+
+/// The difference between two [`HotLaneManifest`]s, produced by
+/// [`HotLaneManifest::diff`]. Lets a server advertise "manifest changed from
+/// block A -> B" cheaply, and a client or reloading server rebuild only the
+/// affected index ranges instead of reindexing every slot.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManifestDelta {
+    pub from_block: u64,
+    pub to_block: u64,
+    /// Contracts present in the newer manifest but not the older one
+    pub added: Vec<HotContract>,
+    /// Addresses present in the older manifest but not the newer one
+    pub removed: Vec<Address>,
+    /// Contracts present in both with a changed `slot_count`
+    pub resized: Vec<ResizedContract>,
+    /// Contracts present in both with a shifted `start_index`
+    pub reindexed: Vec<ReindexedContract>,
+    /// `total_entries` of the newer manifest
+    pub new_total_entries: u64,
+}
+
+impl ManifestDelta {
+    /// Whether this delta represents any actual change
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.resized.is_empty()
+            && self.reindexed.is_empty()
+    }
+}
+
+/// Inclusion proof that a [`HotContract`] is leaf `index` of a
+/// [`HotLaneManifest`]'s Merkle tree. Bundles the leaf position with the
+/// sibling path (mirroring [`crate::ProofRecord`]) since the position alone
+/// determines left/right ordering when folding up to the root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestInclusionProof {
+    /// Position of the leaf in `contracts` order
+    pub index: usize,
+    /// Sibling hashes from the leaf up to (but not including) the root
+    pub siblings: Vec<[u8; 32]>,
 }
 
 impl HotLaneManifest {
@@ -46,10 +210,28 @@ impl HotLaneManifest {
                 .as_secs(),
             contracts: Vec::new(),
             total_entries: 0,
-            version: 1,
+            version: MANIFEST_VERSION,
+            merkle_root: None,
+            num_shards: 1,
+            state_root: None,
         }
     }
 
+    /// Record the verified Ethereum `stateRoot` this manifest's balances
+    /// were checked against.
+    pub fn with_state_root(mut self, state_root: [u8; 32]) -> Self {
+        self.state_root = Some(state_root);
+        self
+    }
+
+    /// Set the number of PIR server nodes to split contracts across.
+    /// Chainable for use right after [`HotLaneManifest::new`].
+    pub fn with_num_shards(mut self, num_shards: u32) -> Self {
+        assert!(num_shards > 0, "num_shards must be non-zero");
+        self.num_shards = num_shards;
+        self
+    }
+
     /// Add a contract to the manifest
     pub fn add_contract(&mut self, address: Address, name: String, slot_count: u64, category: String) {
         let start_index = self.total_entries;
@@ -59,10 +241,26 @@ impl HotLaneManifest {
             slot_count,
             start_index,
             category,
+            access_count: 0,
+            access_ewma: 0.0,
+            last_access_unix: 0,
         });
         self.total_entries += slot_count;
     }
 
+    /// Remove a contract from the hot lane, shifting every later contract's
+    /// `start_index` down by the removed contract's `slot_count` so the
+    /// lane stays densely packed. Returns the removed contract, if present.
+    pub fn remove_contract(&mut self, address: &Address) -> Option<HotContract> {
+        let pos = self.contracts.iter().position(|c| &c.address == address)?;
+        let removed = self.contracts.remove(pos);
+        for contract in self.contracts.iter_mut().skip(pos) {
+            contract.start_index -= removed.slot_count;
+        }
+        self.total_entries -= removed.slot_count;
+        Some(removed)
+    }
+
     /// Check if an address is in the hot lane
     pub fn contains(&self, address: &Address) -> bool {
         self.contracts.iter().any(|c| &c.address == address)
@@ -96,6 +294,264 @@ impl HotLaneManifest {
     pub fn contract_count(&self) -> usize {
         self.contracts.len()
     }
+
+    /// Leaf hash for a single contract: `hash(address || slot_count || start_index || category)`
+    fn leaf_hash(contract: &HotContract) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&contract.address);
+        hasher.update(&contract.slot_count.to_le_bytes());
+        hasher.update(&contract.start_index.to_le_bytes());
+        hasher.update(contract.category.as_bytes());
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Fold two child hashes into their parent
+    fn fold(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(left);
+        hasher.update(right);
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Full tree levels, leaf level first, root level last. Odd-sized levels
+    /// duplicate their last node before folding, per common Merkle tree
+    /// convention, so `contracts` of any length produce a well-defined root.
+    fn tree_levels(&self) -> Vec<Vec<[u8; 32]>> {
+        if self.contracts.is_empty() {
+            return vec![vec![[0u8; 32]]];
+        }
+
+        let mut level: Vec<[u8; 32]> = self.contracts.iter().map(Self::leaf_hash).collect();
+        let mut levels = vec![level.clone()];
+        while level.len() > 1 {
+            if level.len() % 2 == 1 {
+                level.push(*level.last().unwrap());
+            }
+            level = level.chunks(2).map(|pair| Self::fold(&pair[0], &pair[1])).collect();
+            levels.push(level.clone());
+        }
+        levels
+    }
+
+    /// Recompute the Merkle root over the current `contracts` list and order,
+    /// without storing it in `merkle_root`
+    pub fn compute_merkle_root(&self) -> [u8; 32] {
+        *self.tree_levels().last().unwrap().first().unwrap()
+    }
+
+    /// Recompute the Merkle root and store it in `merkle_root`. Call this
+    /// after the contract list is finalized, before publishing or saving the
+    /// manifest.
+    pub fn commit_merkle_root(&mut self) {
+        self.merkle_root = Some(self.compute_merkle_root());
+    }
+
+    /// The committed Merkle root, if one has been computed
+    pub fn merkle_root(&self) -> Option<[u8; 32]> {
+        self.merkle_root
+    }
+
+    /// Build an inclusion proof for `address`'s current position in the
+    /// manifest. Returns `None` if the address is not present.
+    pub fn inclusion_proof(&self, address: &Address) -> Option<ManifestInclusionProof> {
+        let index = self.contracts.iter().position(|c| &c.address == address)?;
+        let levels = self.tree_levels();
+
+        let mut siblings = Vec::with_capacity(levels.len() - 1);
+        let mut idx = index;
+        for level in levels.iter().take(levels.len() - 1) {
+            let mut level = level.clone();
+            if level.len() % 2 == 1 {
+                level.push(*level.last().unwrap());
+            }
+            siblings.push(level[idx ^ 1]);
+            idx /= 2;
+        }
+
+        Some(ManifestInclusionProof { index, siblings })
+    }
+
+    /// Verify that `contract` is included, at the position recorded in
+    /// `proof`, under `root`. This does not require the full manifest: a
+    /// client that has pinned `root` over an authenticated channel can check
+    /// a single contract's layout without re-downloading every entry.
+    pub fn verify_proof(root: &[u8; 32], contract: &HotContract, proof: &ManifestInclusionProof) -> bool {
+        let mut node = Self::leaf_hash(contract);
+        let mut idx = proof.index;
+
+        for sibling in &proof.siblings {
+            node = if idx % 2 == 0 {
+                Self::fold(&node, sibling)
+            } else {
+                Self::fold(sibling, &node)
+            };
+            idx /= 2;
+        }
+
+        &node == root
+    }
+
+    /// Deterministic shard id for `address` in `0..num_shards`, independent
+    /// of manifest order, so a contract's assigned node is stable across
+    /// reloads and across manifests built from different contract subsets.
+    pub fn shard_for(&self, address: &Address) -> u32 {
+        Self::shard_id(address, self.num_shards)
+    }
+
+    fn shard_id(address: &Address, num_shards: u32) -> u32 {
+        let hash = blake3::hash(address);
+        let bytes = hash.as_bytes();
+        let v = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        v % num_shards
+    }
+
+    /// Build the sub-manifest hosted by node `shard_id`: only the contracts
+    /// whose [`HotLaneManifest::shard_for`] is `shard_id`, with `start_index`
+    /// and `total_entries` recomputed over that contiguous, self-consistent
+    /// index space. The returned manifest's `merkle_root` is cleared since
+    /// it commits to a different contract list; call
+    /// [`HotLaneManifest::commit_merkle_root`] again if a per-shard
+    /// commitment is needed.
+    pub fn manifest_for_shard(&self, shard_id: u32) -> HotLaneManifest {
+        let mut total_entries = 0u64;
+        let contracts = self
+            .contracts
+            .iter()
+            .filter(|c| self.shard_for(&c.address) == shard_id)
+            .map(|c| {
+                let start_index = total_entries;
+                total_entries += c.slot_count;
+                HotContract {
+                    address: c.address,
+                    name: c.name.clone(),
+                    slot_count: c.slot_count,
+                    start_index,
+                    category: c.category.clone(),
+                    access_count: c.access_count,
+                    access_ewma: c.access_ewma,
+                    last_access_unix: c.last_access_unix,
+                }
+            })
+            .collect();
+
+        HotLaneManifest {
+            block_number: self.block_number,
+            timestamp: self.timestamp,
+            contracts,
+            total_entries,
+            version: self.version,
+            merkle_root: None,
+            num_shards: self.num_shards,
+            state_root: self.state_root,
+        }
+    }
+
+    /// Compute the added/removed/resized/reindexed contracts between `self`
+    /// (older) and `newer`.
+    pub fn diff(&self, newer: &Self) -> ManifestDelta {
+        let old_set = self.address_set();
+        let new_set = newer.address_set();
+
+        let added = newer
+            .contracts
+            .iter()
+            .filter(|c| !old_set.contains(&c.address))
+            .cloned()
+            .collect();
+
+        let removed = self
+            .contracts
+            .iter()
+            .filter(|c| !new_set.contains(&c.address))
+            .map(|c| c.address)
+            .collect();
+
+        let mut resized = Vec::new();
+        let mut reindexed = Vec::new();
+        for new_contract in &newer.contracts {
+            let Some(old_contract) = self.get_contract(&new_contract.address) else {
+                continue;
+            };
+            if old_contract.slot_count != new_contract.slot_count {
+                resized.push(ResizedContract {
+                    address: new_contract.address,
+                    old_slot_count: old_contract.slot_count,
+                    new_slot_count: new_contract.slot_count,
+                });
+            }
+            if old_contract.start_index != new_contract.start_index {
+                reindexed.push(ReindexedContract {
+                    address: new_contract.address,
+                    old_start_index: old_contract.start_index,
+                    new_start_index: new_contract.start_index,
+                });
+            }
+        }
+
+        ManifestDelta {
+            from_block: self.block_number,
+            to_block: newer.block_number,
+            added,
+            removed,
+            resized,
+            reindexed,
+            new_total_entries: newer.total_entries,
+        }
+    }
+
+    /// Apply a [`ManifestDelta`] in place: removes `delta.removed`, patches
+    /// `slot_count`/`start_index` for resized/reindexed contracts, appends
+    /// `delta.added`, and updates `total_entries`/`block_number`/`timestamp`.
+    /// Clears `merkle_root`, since the contract list changed and the caller
+    /// must call [`HotLaneManifest::commit_merkle_root`] again if needed.
+    pub fn apply_delta(&mut self, delta: &ManifestDelta) {
+        let removed_set: HashSet<Address> = delta.removed.iter().copied().collect();
+        self.contracts.retain(|c| !removed_set.contains(&c.address));
+
+        for resize in &delta.resized {
+            if let Some(contract) = self.contracts.iter_mut().find(|c| c.address == resize.address) {
+                contract.slot_count = resize.new_slot_count;
+            }
+        }
+        for reindex in &delta.reindexed {
+            if let Some(contract) = self.contracts.iter_mut().find(|c| c.address == reindex.address) {
+                contract.start_index = reindex.new_start_index;
+            }
+        }
+        for contract in &delta.added {
+            self.contracts.push(contract.clone());
+        }
+
+        self.total_entries = delta.new_total_entries;
+        self.block_number = delta.to_block;
+        self.timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.merkle_root = None;
+    }
+
+    /// Decide which hot contracts should be demoted and which cold
+    /// candidates are hot enough to be promoted, so genuinely popular
+    /// contracts stay in the low-latency hot lane without a hand-curated
+    /// static list. `hysteresis_margin` in `config` keeps contracts near the
+    /// threshold from flipping lanes on every reload.
+    pub fn rebalance(&self, cold_candidates: &[ColdCandidate], config: &RebalanceConfig) -> RebalancePlan {
+        let demote = self
+            .contracts
+            .iter()
+            .filter(|c| c.access_ewma < config.promote_threshold - config.hysteresis_margin)
+            .map(|c| c.address)
+            .collect();
+
+        let promote = cold_candidates
+            .iter()
+            .filter(|c| c.access_ewma > config.promote_threshold + config.hysteresis_margin)
+            .map(|c| c.address)
+            .collect();
+
+        RebalancePlan { demote, promote }
+    }
 }
 
 mod hex_address {
@@ -155,6 +611,32 @@ mod tests {
         assert_eq!(manifest.total_entries, 300);
     }
 
+    #[test]
+    fn test_manifest_remove_contract_reindexes_later_entries() {
+        let mut manifest = HotLaneManifest::new(1000);
+        let addr1 = [1u8; 20];
+        let addr2 = [2u8; 20];
+        let addr3 = [3u8; 20];
+
+        manifest.add_contract(addr1, "Contract1".into(), 100, "defi".into());
+        manifest.add_contract(addr2, "Contract2".into(), 200, "token".into());
+        manifest.add_contract(addr3, "Contract3".into(), 50, "nft".into());
+
+        let removed = manifest.remove_contract(&addr2).expect("addr2 should be present");
+        assert_eq!(removed.slot_count, 200);
+        assert!(!manifest.contains(&addr2));
+        assert_eq!(manifest.contracts[0].start_index, 0);
+        assert_eq!(manifest.contracts[1].start_index, 100);
+        assert_eq!(manifest.total_entries, 150);
+    }
+
+    #[test]
+    fn test_manifest_remove_contract_missing_address_returns_none() {
+        let mut manifest = HotLaneManifest::new(1000);
+        manifest.add_contract([1u8; 20], "Contract1".into(), 100, "defi".into());
+        assert!(manifest.remove_contract(&[9u8; 20]).is_none());
+    }
+
     #[test]
     fn test_address_serialization() {
         let mut manifest = HotLaneManifest::new(1000);
@@ -166,4 +648,206 @@ mod tests {
         let parsed: HotLaneManifest = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.contracts[0].address, [0xdeu8; 20]);
     }
+
+    #[test]
+    fn test_version_1_manifest_without_root_deserializes() {
+        let json = r#"{
+            "block_number": 1000,
+            "timestamp": 0,
+            "contracts": [],
+            "total_entries": 0,
+            "version": 1
+        }"#;
+        let manifest: HotLaneManifest = serde_json::from_str(json).unwrap();
+        assert_eq!(manifest.version, 1);
+        assert_eq!(manifest.merkle_root, None);
+        assert_eq!(manifest.num_shards, 1);
+    }
+
+    #[test]
+    fn test_manifest_for_shard_recomputes_contiguous_indices() {
+        let mut manifest = HotLaneManifest::new(1000).with_num_shards(4);
+        for i in 0u8..20 {
+            manifest.add_contract([i; 20], format!("C{i}"), 10, "defi".into());
+        }
+
+        let mut seen_total = 0u64;
+        for shard_id in 0..manifest.num_shards {
+            let shard_manifest = manifest.manifest_for_shard(shard_id);
+            for (i, contract) in shard_manifest.contracts.iter().enumerate() {
+                assert_eq!(contract.start_index, i as u64 * 10);
+                assert_eq!(manifest.shard_for(&contract.address), shard_id);
+            }
+            assert_eq!(
+                shard_manifest.total_entries,
+                shard_manifest.contracts.len() as u64 * 10
+            );
+            seen_total += shard_manifest.contracts.len() as u64;
+        }
+        assert_eq!(seen_total, manifest.contract_count() as u64);
+    }
+
+    #[test]
+    fn test_shard_for_is_deterministic_and_in_range() {
+        let manifest = HotLaneManifest::new(1000).with_num_shards(8);
+        for i in 0u8..50 {
+            let addr = [i; 20];
+            let shard = manifest.shard_for(&addr);
+            assert!(shard < 8);
+            assert_eq!(shard, manifest.shard_for(&addr));
+        }
+    }
+
+    #[test]
+    fn test_diff_detects_added_removed_resized_reindexed() {
+        let mut old = HotLaneManifest::new(1000);
+        old.add_contract([1u8; 20], "A".into(), 100, "defi".into());
+        old.add_contract([2u8; 20], "B".into(), 200, "token".into());
+
+        let mut newer = HotLaneManifest::new(1010);
+        newer.add_contract([1u8; 20], "A".into(), 150, "defi".into()); // resized
+        newer.add_contract([3u8; 20], "C".into(), 50, "nft".into()); // added
+
+        let delta = old.diff(&newer);
+
+        assert_eq!(delta.from_block, 1000);
+        assert_eq!(delta.to_block, 1010);
+        assert_eq!(delta.added.len(), 1);
+        assert_eq!(delta.added[0].address, [3u8; 20]);
+        assert_eq!(delta.removed, vec![[2u8; 20]]);
+        assert_eq!(delta.resized.len(), 1);
+        assert_eq!(delta.resized[0].old_slot_count, 100);
+        assert_eq!(delta.resized[0].new_slot_count, 150);
+        // "A" is still the first contract in both manifests, so its
+        // start_index (0) is unchanged -> no reindex entry.
+        assert!(delta.reindexed.is_empty());
+        assert_eq!(delta.new_total_entries, newer.total_entries);
+    }
+
+    #[test]
+    fn test_apply_delta_reproduces_newer_manifest_state() {
+        let mut old = HotLaneManifest::new(1000);
+        old.add_contract([1u8; 20], "A".into(), 100, "defi".into());
+        old.add_contract([2u8; 20], "B".into(), 200, "token".into());
+        old.commit_merkle_root();
+
+        let mut newer = HotLaneManifest::new(1010);
+        newer.add_contract([1u8; 20], "A".into(), 150, "defi".into());
+        newer.add_contract([3u8; 20], "C".into(), 50, "nft".into());
+
+        let delta = old.diff(&newer);
+        old.apply_delta(&delta);
+
+        assert_eq!(old.block_number, 1010);
+        assert_eq!(old.total_entries, newer.total_entries);
+        assert_eq!(old.merkle_root, None);
+        assert!(!old.contains(&[2u8; 20]));
+        assert!(old.contains(&[1u8; 20]));
+        assert!(old.contains(&[3u8; 20]));
+        assert_eq!(old.get_contract(&[1u8; 20]).unwrap().slot_count, 150);
+    }
+
+    #[test]
+    fn test_diff_is_empty_for_identical_manifests() {
+        let mut manifest = HotLaneManifest::new(1000);
+        manifest.add_contract([1u8; 20], "A".into(), 100, "defi".into());
+
+        let delta = manifest.diff(&manifest.clone());
+        assert!(delta.is_empty());
+    }
+
+    #[test]
+    fn test_record_access_accumulates_without_decay_at_same_timestamp() {
+        let mut manifest = HotLaneManifest::new(1000);
+        manifest.add_contract([1u8; 20], "A".into(), 100, "defi".into());
+        let contract = &mut manifest.contracts[0];
+
+        contract.record_access(1000, 3600);
+        contract.record_access(1000, 3600);
+        assert_eq!(contract.access_count, 2);
+        assert_eq!(contract.access_ewma, 2.0);
+    }
+
+    #[test]
+    fn test_record_access_decays_over_half_life() {
+        let mut manifest = HotLaneManifest::new(1000);
+        manifest.add_contract([1u8; 20], "A".into(), 100, "defi".into());
+        let contract = &mut manifest.contracts[0];
+
+        contract.record_access(0, 3600);
+        assert_eq!(contract.access_ewma, 1.0);
+
+        // One full half-life later: previous contribution should have
+        // decayed to ~0.5 before the new sample is added.
+        contract.record_access(3600, 3600);
+        assert!((contract.access_ewma - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rebalance_respects_hysteresis_margin() {
+        let mut manifest = HotLaneManifest::new(1000);
+        manifest.add_contract([1u8; 20], "Cold".into(), 100, "defi".into());
+        manifest.add_contract([2u8; 20], "Steady".into(), 100, "defi".into());
+        manifest.contracts[0].access_ewma = 0.1; // clearly below threshold -> demote
+        manifest.contracts[1].access_ewma = 0.9; // within hysteresis band -> stays
+
+        let cold_candidates = vec![
+            ColdCandidate { address: [3u8; 20], access_ewma: 5.0 }, // clearly hot -> promote
+            ColdCandidate { address: [4u8; 20], access_ewma: 1.1 }, // within band -> stays cold
+        ];
+
+        let config = RebalanceConfig::default(); // threshold 1.0, margin 0.2
+        let plan = manifest.rebalance(&cold_candidates, &config);
+
+        assert_eq!(plan.demote, vec![[1u8; 20]]);
+        assert_eq!(plan.promote, vec![[3u8; 20]]);
+    }
+
+    #[test]
+    fn test_commit_merkle_root_is_deterministic() {
+        let mut manifest = HotLaneManifest::new(1000);
+        manifest.add_contract([1u8; 20], "A".into(), 100, "defi".into());
+        manifest.add_contract([2u8; 20], "B".into(), 200, "token".into());
+        manifest.add_contract([3u8; 20], "C".into(), 50, "nft".into());
+
+        manifest.commit_merkle_root();
+        let root = manifest.merkle_root().unwrap();
+        assert_eq!(root, manifest.compute_merkle_root());
+    }
+
+    #[test]
+    fn test_inclusion_proof_verifies_for_every_contract() {
+        let mut manifest = HotLaneManifest::new(1000);
+        manifest.add_contract([1u8; 20], "A".into(), 100, "defi".into());
+        manifest.add_contract([2u8; 20], "B".into(), 200, "token".into());
+        manifest.add_contract([3u8; 20], "C".into(), 50, "nft".into());
+        manifest.commit_merkle_root();
+        let root = manifest.merkle_root().unwrap();
+
+        for contract in &manifest.contracts {
+            let proof = manifest.inclusion_proof(&contract.address).unwrap();
+            assert!(HotLaneManifest::verify_proof(&root, contract, &proof));
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proof_rejects_tampered_contract() {
+        let mut manifest = HotLaneManifest::new(1000);
+        manifest.add_contract([1u8; 20], "A".into(), 100, "defi".into());
+        manifest.add_contract([2u8; 20], "B".into(), 200, "token".into());
+        manifest.commit_merkle_root();
+        let root = manifest.merkle_root().unwrap();
+
+        let proof = manifest.inclusion_proof(&[1u8; 20]).unwrap();
+        let mut tampered = manifest.contracts[0].clone();
+        tampered.start_index = 999;
+
+        assert!(!HotLaneManifest::verify_proof(&root, &tampered, &proof));
+    }
+
+    #[test]
+    fn test_inclusion_proof_missing_address_is_none() {
+        let manifest = HotLaneManifest::new(1000);
+        assert!(manifest.inclusion_proof(&[0xffu8; 20]).is_none());
+    }
 }
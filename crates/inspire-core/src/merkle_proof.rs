@@ -0,0 +1,238 @@
+//! Merkle inclusion proofs for EIP-7864 UBT leaves
+//!
+//! The Unified Binary Trie (UBT) is a BLAKE3-hashed binary Merkle tree keyed
+//! by 32-byte `tree_key`s. A PIR server can return the correct value for an
+//! index, but the client has no cryptographic guarantee of that on its own.
+//! By storing `value(32) || merkle_path` as the PIR record and having the
+//! client fold the path up to a trusted state root, the client authenticates
+//! the retrieved value without revealing to anyone which index was queried:
+//! the proof travels *inside* the privately retrieved record.
+//!
+//! This module implements the client-side verification primitive only.
+//! Building [`ProofRecord`]s from an actual UBT (`setup_authenticated`) is a
+//! PIR-database-builder concern and lives alongside the encoded database
+//! format itself.
+
+use thiserror::Error;
+
+/// Number of bits in a `tree_key`, and therefore the expected Merkle path
+/// length for a full-depth UBT proof.
+pub const UBT_TREE_DEPTH: usize = 256;
+
+/// Ordered sequence of sibling hashes from a leaf up to the root.
+///
+/// `siblings[0]` is the leaf's immediate sibling; `siblings[len - 1]` is the
+/// sibling at the level just below the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerklePath {
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerklePath {
+    pub fn new(siblings: Vec<[u8; 32]>) -> Self {
+        Self { siblings }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.siblings.len()
+    }
+}
+
+/// A PIR record authenticated against the UBT: the retrieved value plus the
+/// Merkle path proving its inclusion under `tree_key`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofRecord {
+    /// The 32-byte value retrieved via PIR
+    pub value: [u8; 32],
+    /// The leaf's tree key; determines left/right ordering at each level
+    pub tree_key: [u8; 32],
+    /// Sibling hashes from leaf to root
+    pub path: MerklePath,
+}
+
+/// Errors from verifying a [`ProofRecord`] against a trusted root
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MerkleProofError {
+    #[error("merkle path length {actual} does not match expected depth {expected}")]
+    WrongPathLength { expected: usize, actual: usize },
+
+    #[error("recomputed root does not match trusted state root")]
+    RootMismatch,
+}
+
+impl ProofRecord {
+    pub fn new(value: [u8; 32], tree_key: [u8; 32], path: MerklePath) -> Self {
+        Self { value, tree_key, path }
+    }
+
+    /// Verify this record's value folds up to `state_root`, requiring the
+    /// path to be exactly [`UBT_TREE_DEPTH`] levels (the full UBT depth).
+    pub fn verify_against_root(&self, state_root: &[u8; 32]) -> Result<(), MerkleProofError> {
+        self.verify_against_root_with_depth(state_root, UBT_TREE_DEPTH)
+    }
+
+    /// Verify this record's value folds up to `state_root`, requiring the
+    /// path to be exactly `expected_depth` levels. Exposed separately from
+    /// [`ProofRecord::verify_against_root`] so tests can exercise the folding
+    /// logic over small trees without materializing a full 256-level UBT.
+    pub fn verify_against_root_with_depth(
+        &self,
+        state_root: &[u8; 32],
+        expected_depth: usize,
+    ) -> Result<(), MerkleProofError> {
+        if self.path.depth() != expected_depth {
+            return Err(MerkleProofError::WrongPathLength {
+                expected: expected_depth,
+                actual: self.path.depth(),
+            });
+        }
+
+        let mut node = leaf_hash(&self.value);
+
+        // Fold from the leaf (deepest level) towards the root, using the
+        // tree_key bit at the corresponding level to determine ordering.
+        // siblings[0] is the leaf's sibling (deepest), so we walk the path
+        // in reverse relative to bit index: bit (depth - 1 - i) selects
+        // whether `node` is the left or right child at level i.
+        for (i, sibling) in self.path.siblings.iter().enumerate() {
+            let bit_index = expected_depth - 1 - i;
+            let bit = tree_key_bit(&self.tree_key, bit_index);
+            node = if bit == 0 {
+                fold(&node, sibling)
+            } else {
+                fold(sibling, &node)
+            };
+        }
+
+        if &node == state_root {
+            Ok(())
+        } else {
+            Err(MerkleProofError::RootMismatch)
+        }
+    }
+}
+
+/// Leaf hash for a 32-byte value
+fn leaf_hash(value: &[u8; 32]) -> [u8; 32] {
+    *blake3::hash(value).as_bytes()
+}
+
+/// Fold two child hashes into their parent
+fn fold(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+/// Most-significant-bit-first bit of a 32-byte key at `index` (0 = MSB of byte 0)
+fn tree_key_bit(key: &[u8; 32], index: usize) -> u8 {
+    let byte = key[index / 8];
+    (byte >> (7 - (index % 8))) & 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a small full binary tree of `depth` levels over `2^depth`
+    /// leaves and return (root, per-leaf proof records) so the folding
+    /// logic can be exercised without a real UBT.
+    fn build_test_tree(depth: usize, leaf_values: &[[u8; 32]]) -> ([u8; 32], Vec<ProofRecord>) {
+        assert_eq!(leaf_values.len(), 1 << depth);
+
+        let mut level: Vec<[u8; 32]> = leaf_values.iter().map(leaf_hash).collect();
+        let mut levels = vec![level.clone()];
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| fold(&pair[0], &pair[1]))
+                .collect();
+            levels.push(level.clone());
+        }
+        let root = levels.last().unwrap()[0];
+
+        let records = leaf_values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let tree_key = tree_key_for_leaf(depth, i);
+                let mut siblings = Vec::with_capacity(depth);
+                let mut idx = i;
+                for level_nodes in levels.iter().take(depth) {
+                    let sibling_idx = idx ^ 1;
+                    siblings.push(level_nodes[sibling_idx]);
+                    idx /= 2;
+                }
+                // siblings above were collected deepest-first, matching
+                // `MerklePath`'s leaf-to-root ordering already.
+                ProofRecord::new(*value, tree_key, MerklePath::new(siblings))
+            })
+            .collect();
+
+        (root, records)
+    }
+
+    /// Tree key whose top `depth` bits (MSB-first) equal the leaf's binary
+    /// index, placing it at leaf `index` of the tree.
+    fn tree_key_for_leaf(depth: usize, index: usize) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        for bit in 0..depth {
+            let bit_index = bit;
+            if (index >> (depth - 1 - bit)) & 1 == 1 {
+                key[bit_index / 8] |= 1 << (7 - (bit_index % 8));
+            }
+        }
+        key
+    }
+
+    #[test]
+    fn test_honest_value_verifies() {
+        let depth = 4;
+        let leaves: Vec<[u8; 32]> = (0..(1u8 << depth)).map(|i| [i; 32]).collect();
+        let (root, records) = build_test_tree(depth, &leaves);
+
+        for record in &records {
+            assert!(record.verify_against_root_with_depth(&root, depth).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_tampered_value_rejected() {
+        let depth = 4;
+        let leaves: Vec<[u8; 32]> = (0..(1u8 << depth)).map(|i| [i; 32]).collect();
+        let (root, mut records) = build_test_tree(depth, &leaves);
+
+        records[3].value[0] ^= 0xff;
+        assert_eq!(
+            records[3].verify_against_root_with_depth(&root, depth),
+            Err(MerkleProofError::RootMismatch)
+        );
+    }
+
+    #[test]
+    fn test_truncated_path_rejected() {
+        let depth = 4;
+        let leaves: Vec<[u8; 32]> = (0..(1u8 << depth)).map(|i| [i; 32]).collect();
+        let (root, mut records) = build_test_tree(depth, &leaves);
+
+        records[0].path.siblings.pop();
+        assert_eq!(
+            records[0].verify_against_root_with_depth(&root, depth),
+            Err(MerkleProofError::WrongPathLength { expected: depth, actual: depth - 1 })
+        );
+    }
+
+    #[test]
+    fn test_wrong_root_rejected() {
+        let depth = 3;
+        let leaves: Vec<[u8; 32]> = (0..(1u8 << depth)).map(|i| [i; 32]).collect();
+        let (_root, records) = build_test_tree(depth, &leaves);
+
+        let wrong_root = [0xabu8; 32];
+        assert_eq!(
+            records[0].verify_against_root_with_depth(&wrong_root, depth),
+            Err(MerkleProofError::RootMismatch)
+        );
+    }
+}
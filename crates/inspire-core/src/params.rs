@@ -19,6 +19,22 @@ use serde::{Deserialize, Serialize};
 /// - v2: Updated sigma to 6.4 per InsPIRe paper
 pub const PIR_PARAMS_VERSION: u16 = 2;
 
+/// Oldest `PIR_PARAMS_VERSION` a server still advertised by this build
+/// will serve a client for, i.e. the low end of the `/info` compatibility
+/// window (see `inspire-server`'s `ServerInfo`). A client whose compiled-in
+/// version falls anywhere in `[PIR_PARAMS_MIN_SUPPORTED_VERSION,
+/// PIR_PARAMS_VERSION]` can still query this server; below that, its CRS
+/// request would build ciphertexts the server's RLWE parameters can't
+/// decode. Bump this forward only when a v1-era client population is known
+/// to be fully retired.
+pub const PIR_PARAMS_MIN_SUPPORTED_VERSION: u16 = 1;
+
+/// Tag for the CRS JSON layout this build serializes, folded into
+/// [`PirParams::params_id`] alongside the numeric fields so a CRS
+/// serialization change that doesn't touch any of them (e.g. a new
+/// encoding for a polynomial coefficient) still changes the fingerprint.
+pub const CRS_FORMAT_TAG: &str = "inspire-crs-v1";
+
 /// PIR parameters for RLWE-based PIR
 ///
 /// These must match between client and server for queries to succeed.
@@ -56,6 +72,24 @@ impl PirParams {
         }
         Ok(())
     }
+
+    /// Content-derived fingerprint over every cryptographic field plus
+    /// [`CRS_FORMAT_TAG`]: a hex-encoded BLAKE3 hash. `version` is a
+    /// hand-bumped `u16` -- two deployments that agree on it can still
+    /// differ in `q`, `sigma`, `gadget_base`, etc. and silently produce
+    /// ciphertexts the other side can't decode. `params_id` is an opaque
+    /// equality check for that, not meant to be hand-read or hand-bumped.
+    pub fn params_id(&self) -> String {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&self.ring_dim.to_le_bytes());
+        hasher.update(&self.sigma.to_le_bytes());
+        hasher.update(&self.q.to_le_bytes());
+        hasher.update(&self.p.to_le_bytes());
+        hasher.update(&self.gadget_base.to_le_bytes());
+        hasher.update(&(self.gadget_len as u64).to_le_bytes());
+        hasher.update(CRS_FORMAT_TAG.as_bytes());
+        hasher.finalize().to_hex().to_string()
+    }
 }
 
 /// Default production parameters (must match lane-builder defaults)
@@ -74,6 +108,12 @@ pub const PIR_PARAMS: PirParams = PirParams {
 pub enum ParamsVersionError {
     #[error("PIR params version mismatch: expected v{expected}, got v{actual}. Regenerate CRS/DB.")]
     VersionMismatch { expected: u16, actual: u16 },
+    /// `pir_params_version`/`params_id` agree (or both were skipped) but
+    /// the stored `params_id` doesn't match what `pir_params` itself
+    /// hashes to -- the metadata was hand-edited, truncated, or written by
+    /// a version of lane-builder with a different `params_id()` formula.
+    #[error("PIR params id mismatch: metadata carries {stored}, but its own pir_params hashes to {recomputed}. CRS metadata is corrupt or stale.")]
+    ParamsIdMismatch { stored: String, recomputed: String },
 }
 
 /// CRS metadata sidecar (generated alongside CRS files)
@@ -95,6 +135,26 @@ pub struct CrsMetadata {
     pub entry_count: u64,
     /// Lane name (hot/cold/balances)
     pub lane: String,
+    /// Content-derived fingerprint of `pir_params`, i.e.
+    /// `pir_params.params_id()` at generation time -- see
+    /// [`PirParams::params_id`]. `None` for CRS metadata generated before
+    /// this field existed, which skips the check in [`Self::validate`].
+    #[serde(default)]
+    pub params_id: Option<String>,
+    /// Authoritative UBT (Unified Binary Trie) state root this lane's data
+    /// was dumped against, as observed by lane-builder via `ubt_getRoot` at
+    /// generation time. `None` for CRS metadata saved before this field
+    /// existed, which disables root verification on load (see
+    /// [`crate::HotLaneManifest`] for the analogous `merkle_root` gap).
+    #[serde(default)]
+    pub ubt_root: Option<[u8; 32]>,
+    /// Verifying key for the detached `crs.sig` signature over
+    /// `crs.json` + this file's own bytes, computed by
+    /// [`crate::sign_crs`]. `None` for CRS metadata generated without
+    /// `TwoLaneSetup::sign_with`, which disables signature verification on
+    /// load.
+    #[serde(default)]
+    pub verifying_key: Option<crate::CrsVerifyingKey>,
 }
 
 impl CrsMetadata {
@@ -112,19 +172,37 @@ impl CrsMetadata {
     ) -> Self {
         Self {
             pir_params_version: params.version,
+            params_id: Some(params.params_id()),
             pir_params: params.clone(),
             generated_by: generated_by.to_string(),
             generated_at: generated_at.to_string(),
             entry_size,
             entry_count,
             lane: lane.to_string(),
+            ubt_root: None,
+            verifying_key: None,
         }
     }
 
+    /// Record the authoritative UBT root this lane was dumped against.
+    /// Chainable for use right after [`CrsMetadata::new`].
+    pub fn with_ubt_root(mut self, root: [u8; 32]) -> Self {
+        self.ubt_root = Some(root);
+        self
+    }
+
+    /// Embed the verifying key clients should use to check this lane's
+    /// `crs.sig`. Chainable for use right after [`CrsMetadata::new`].
+    pub fn with_verifying_key(mut self, key: crate::CrsVerifyingKey) -> Self {
+        self.verifying_key = Some(key);
+        self
+    }
+
     /// Validate metadata against current version
     ///
-    /// Checks both that the version matches `PIR_PARAMS_VERSION` and that the
-    /// metadata fields are internally consistent.
+    /// Checks that the version matches `PIR_PARAMS_VERSION`, that the
+    /// metadata fields are internally consistent, and (when a `params_id`
+    /// was recorded) that it still matches what `pir_params` hashes to.
     pub fn validate(&self) -> Result<(), ParamsVersionError> {
         if self.pir_params_version != self.pir_params.version {
             return Err(ParamsVersionError::VersionMismatch {
@@ -132,6 +210,15 @@ impl CrsMetadata {
                 actual: self.pir_params.version,
             });
         }
+        if let Some(stored) = &self.params_id {
+            let recomputed = self.pir_params.params_id();
+            if *stored != recomputed {
+                return Err(ParamsVersionError::ParamsIdMismatch {
+                    stored: stored.clone(),
+                    recomputed,
+                });
+            }
+        }
         self.pir_params.validate()
     }
 
@@ -187,5 +274,54 @@ mod tests {
         let parsed: CrsMetadata = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.pir_params_version, PIR_PARAMS_VERSION);
         assert_eq!(parsed.lane, "hot");
+        assert_eq!(parsed.ubt_root, None);
+    }
+
+    #[test]
+    fn test_crs_metadata_with_ubt_root() {
+        let meta = CrsMetadata::new(&PIR_PARAMS, 32, 1000, "hot", "lane-builder 0.1.0", "2025-01-01T00:00:00Z")
+            .with_ubt_root([7u8; 32]);
+        let json = serde_json::to_string(&meta).unwrap();
+        let parsed: CrsMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.ubt_root, Some([7u8; 32]));
+    }
+
+    #[test]
+    fn test_crs_metadata_with_verifying_key() {
+        let key = crate::CrsVerifyingKey::Ed25519("ab".repeat(32));
+        let meta = CrsMetadata::new(&PIR_PARAMS, 32, 1000, "hot", "lane-builder 0.1.0", "2025-01-01T00:00:00Z")
+            .with_verifying_key(key.clone());
+        let json = serde_json::to_string(&meta).unwrap();
+        let parsed: CrsMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.verifying_key, Some(key));
+    }
+
+    #[test]
+    fn test_params_id_is_stable_and_sensitive_to_crypto_fields() {
+        let id_a = PIR_PARAMS.params_id();
+        let id_b = PIR_PARAMS.params_id();
+        assert_eq!(id_a, id_b);
+
+        let different_sigma = PirParams { sigma: 1.0, ..PIR_PARAMS };
+        assert_ne!(id_a, different_sigma.params_id());
+
+        let different_q = PirParams { q: PIR_PARAMS.q + 1, ..PIR_PARAMS };
+        assert_ne!(id_a, different_q.params_id());
+    }
+
+    #[test]
+    fn test_crs_metadata_validate_detects_params_id_mismatch() {
+        let mut meta = CrsMetadata::new(&PIR_PARAMS, 32, 1000, "hot", "lane-builder 0.1.0", "2025-01-01T00:00:00Z");
+        assert!(meta.validate().is_ok());
+
+        meta.params_id = Some("tampered".to_string());
+        assert!(matches!(meta.validate(), Err(ParamsVersionError::ParamsIdMismatch { .. })));
+    }
+
+    #[test]
+    fn test_crs_metadata_without_params_id_skips_check() {
+        let mut meta = CrsMetadata::new(&PIR_PARAMS, 32, 1000, "hot", "lane-builder 0.1.0", "2025-01-01T00:00:00Z");
+        meta.params_id = None;
+        assert!(meta.validate().is_ok());
     }
 }
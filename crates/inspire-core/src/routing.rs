@@ -79,6 +79,72 @@ pub struct RoutedQuery {
     pub index: u64,
 }
 
+/// A batch of [`QueryTarget`]s routed and grouped by lane in one pass.
+///
+/// Fetching several slots one at a time costs one PIR round-trip per slot;
+/// [`RoutedBatch::route_all`] lets a caller route the
+/// whole batch up front, then issue one combined query per lane instead of
+/// one per slot, while still returning results to the caller in the
+/// original input order.
+#[derive(Debug, Clone)]
+pub struct RoutedBatch {
+    /// One [`RoutedQuery`] per input [`QueryTarget`], in input order
+    queries: Vec<RoutedQuery>,
+}
+
+impl RoutedBatch {
+    /// Routes every target in `targets` against `router`, preserving input
+    /// order. A target whose contract isn't in the hot lane manifest (i.e.
+    /// it routes to [`Lane::Cold`]) has no hot-lane index, so its `index` is
+    /// left at `0`; cold lane indexing is the caller's responsibility, same
+    /// as [`LaneRouter::get_hot_index`]'s existing single-query contract.
+    pub fn route_all(router: &LaneRouter, targets: &[QueryTarget]) -> Self {
+        let queries = targets
+            .iter()
+            .map(|target| {
+                let lane = router.route(&target.contract);
+                let index = router
+                    .get_hot_index(&target.contract, &target.slot)
+                    .unwrap_or(0);
+                RoutedQuery {
+                    target: target.clone(),
+                    lane,
+                    index,
+                }
+            })
+            .collect();
+        Self { queries }
+    }
+
+    /// Every routed query, in input order.
+    pub fn queries(&self) -> &[RoutedQuery] {
+        &self.queries
+    }
+
+    /// Indices (with their position in the original batch) of every target
+    /// routed to `lane`, for packing into that lane's single combined
+    /// query. The position is returned alongside each index so a caller can
+    /// reassemble per-lane responses back into input order.
+    pub fn indices_for_lane(&self, lane: Lane) -> Vec<(usize, u64)> {
+        self.queries
+            .iter()
+            .enumerate()
+            .filter(|(_, q)| q.lane == lane)
+            .map(|(pos, q)| (pos, q.index))
+            .collect()
+    }
+
+    /// Number of targets in the batch.
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Whether the batch is empty.
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,9 +169,36 @@ mod tests {
     fn test_hot_index() {
         let router = LaneRouter::new(create_test_manifest());
         let slot = [0u8; 32];
-        
+
         assert_eq!(router.get_hot_index(&[0x11u8; 20], &slot), Some(0));
         assert_eq!(router.get_hot_index(&[0x22u8; 20], &slot), Some(1000));
         assert_eq!(router.get_hot_index(&[0x33u8; 20], &slot), None);
     }
+
+    #[test]
+    fn test_routed_batch_groups_by_lane_in_input_order() {
+        let router = LaneRouter::new(create_test_manifest());
+        let targets = vec![
+            QueryTarget::new([0x33u8; 20], [0u8; 32]), // cold
+            QueryTarget::new([0x11u8; 20], [0u8; 32]), // hot
+            QueryTarget::new([0x22u8; 20], [0u8; 32]), // hot
+        ];
+
+        let batch = RoutedBatch::route_all(&router, &targets);
+        assert_eq!(batch.len(), 3);
+
+        let hot = batch.indices_for_lane(Lane::Hot);
+        assert_eq!(hot, vec![(1, 0), (2, 1000)]);
+
+        let cold = batch.indices_for_lane(Lane::Cold);
+        assert_eq!(cold, vec![(0, 0)]);
+    }
+
+    #[test]
+    fn test_routed_batch_empty() {
+        let router = LaneRouter::new(create_test_manifest());
+        let batch = RoutedBatch::route_all(&router, &[]);
+        assert!(batch.is_empty());
+        assert!(batch.indices_for_lane(Lane::Hot).is_empty());
+    }
 }
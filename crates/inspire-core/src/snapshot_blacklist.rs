@@ -0,0 +1,113 @@
+//! Persistent blacklist of corrupt `state.bin` snapshots
+//!
+//! A snapshot is identified by its `block_hash` plus [`StateHeader`]'s
+//! `body_digest` (see `state_format`): once a loader's
+//! [`StateHeader::verify_digest`] fails for a given snapshot, there's no
+//! point re-reading and re-hashing the same bad file on every subsequent
+//! startup, so the combination is recorded here and checked up front next
+//! time -- mirroring how other snapshot-ingestion systems blacklist a bad
+//! manifest hash after a failed import rather than retrying it forever.
+//!
+//! [`StateHeader`]: crate::state_format::StateHeader
+//! [`StateHeader::verify_digest`]: crate::state_format::StateHeader::verify_digest
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// A JSON-persisted set of known-corrupt `(block_hash, body_digest)` pairs
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SnapshotBlacklist {
+    entries: HashSet<String>,
+}
+
+fn key(block_hash: &[u8; 32], body_digest: &[u8; 32]) -> String {
+    format!("{}:{}", hex::encode(block_hash), hex::encode(body_digest))
+}
+
+impl SnapshotBlacklist {
+    /// Load the blacklist from `path`, or start empty if it doesn't exist yet
+    pub fn load(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Persist the blacklist to `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path.as_ref(), content)?;
+        Ok(())
+    }
+
+    /// `true` if this exact snapshot has already been recorded as corrupt
+    pub fn contains(&self, block_hash: &[u8; 32], body_digest: &[u8; 32]) -> bool {
+        self.entries.contains(&key(block_hash, body_digest))
+    }
+
+    /// Record a snapshot that failed digest verification, so it's skipped
+    /// (without re-reading or re-hashing) on subsequent startups
+    pub fn record(&mut self, block_hash: &[u8; 32], body_digest: &[u8; 32]) {
+        self.entries.insert(key(block_hash, body_digest));
+    }
+
+    /// Number of blacklisted snapshots
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_contains() {
+        let mut blacklist = SnapshotBlacklist::default();
+        let block_hash = [0x11u8; 32];
+        let digest = [0x22u8; 32];
+
+        assert!(!blacklist.contains(&block_hash, &digest));
+        blacklist.record(&block_hash, &digest);
+        assert!(blacklist.contains(&block_hash, &digest));
+    }
+
+    #[test]
+    fn test_different_digest_same_block_hash_not_blacklisted() {
+        let mut blacklist = SnapshotBlacklist::default();
+        let block_hash = [0x11u8; 32];
+
+        blacklist.record(&block_hash, &[0x22u8; 32]);
+        assert!(!blacklist.contains(&block_hash, &[0x33u8; 32]));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("inspire-blacklist-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("blacklist.json");
+
+        let mut blacklist = SnapshotBlacklist::default();
+        blacklist.record(&[0xab; 32], &[0xcd; 32]);
+        blacklist.save(&path).unwrap();
+
+        let loaded = SnapshotBlacklist::load(&path).unwrap();
+        assert!(loaded.contains(&[0xab; 32], &[0xcd; 32]));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        let blacklist = SnapshotBlacklist::load("/nonexistent/path/blacklist.json").unwrap();
+        assert!(blacklist.is_empty());
+    }
+}
@@ -0,0 +1,343 @@
+//! Pluggable backends for reading entries directly out of a `state.bin`
+//! snapshot (see [`crate::state_format`]), without requiring the whole file
+//! to be materialized into memory up front.
+//!
+//! [`InMemoryBackend`] keeps today's behavior of parsing every
+//! [`StorageEntry`] into a `Vec` at load time. [`MmapBackend`] memory-maps
+//! the file instead and decodes a `StorageEntry` from the mapped slice only
+//! when it's actually looked up, giving near-zero startup cost for
+//! multi-gigabyte snapshots. `lane-builder` writes entries in ascending
+//! `(address, slot)` order (see `ShardWriter::write_state_file` in
+//! `inspire-updater`), so both backends get `O(log n)` point lookups via
+//! binary search over that ordering for free, via the shared
+//! [`StateBackend::lookup`] default method.
+//!
+//! Note: the production query path ([`crate`]'s consumer
+//! `inspire_server::state::LaneBackend`) serves PIR-encoded shard databases
+//! that `lane-builder` builds *from* `state.bin`, not `state.bin` itself, so
+//! this trait isn't wired into `ServerBuilder`. It's for tools that need a
+//! direct point lookup against a raw snapshot (e.g. debugging a specific
+//! `(address, slot)` before it's been encoded into a lane).
+
+use std::cmp::Ordering;
+use std::fs::File;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::state_format::{StateFormatError, StateHeader, StorageEntry, STATE_ENTRY_SIZE, STATE_HEADER_SIZE};
+
+/// Which [`StateBackend`] implementation to load a `state.bin` snapshot with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackendKind {
+    /// Parse every entry into a `Vec` up front ([`InMemoryBackend`])
+    #[default]
+    InMemory,
+    /// Memory-map the file and decode entries lazily ([`MmapBackend`])
+    Mmap,
+}
+
+/// A source of [`StorageEntry`] records, addressable by index or by
+/// `(address, slot)` key.
+pub trait StateBackend {
+    /// Number of entries in the snapshot
+    fn entry_count(&self) -> u64;
+    /// Decode the entry at `index` (0-based), or `None` if out of range
+    fn entry_at(&self, index: u64) -> Option<StorageEntry>;
+
+    /// Binary search for the entry matching `(address, slot)`, assuming
+    /// entries are sorted ascending by that key (see module docs).
+    fn lookup(&self, address: &[u8; 20], slot: &[u8; 32]) -> Option<StorageEntry> {
+        let target = (*address, *slot);
+        let mut lo = 0u64;
+        let mut hi = self.entry_count();
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let entry = self.entry_at(mid)?;
+            match entry_key(&entry).cmp(&target) {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return Some(entry),
+            }
+        }
+
+        None
+    }
+}
+
+fn entry_key(entry: &StorageEntry) -> ([u8; 20], [u8; 32]) {
+    (entry.address, entry.slot)
+}
+
+/// Opens a `state.bin` file with the given [`BackendKind`]
+pub fn open(path: impl AsRef<Path>, kind: BackendKind) -> Result<Box<dyn StateBackend>, StateFormatError> {
+    match kind {
+        BackendKind::InMemory => Ok(Box::new(InMemoryBackend::load(path)?)),
+        BackendKind::Mmap => Ok(Box::new(MmapBackend::open(path)?)),
+    }
+}
+
+/// Loads every entry of a `state.bin` file into a `Vec` up front
+pub struct InMemoryBackend {
+    entries: Vec<StorageEntry>,
+}
+
+impl InMemoryBackend {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StateFormatError> {
+        let bytes = std::fs::read(path.as_ref()).map_err(|e| StateFormatError::Io(e.to_string()))?;
+        let header = StateHeader::from_bytes(&bytes)?;
+
+        let mut entries = Vec::with_capacity(header.entry_count as usize);
+        for i in 0..header.entry_count {
+            let offset = STATE_HEADER_SIZE + i as usize * STATE_ENTRY_SIZE;
+            entries.push(StorageEntry::from_bytes(&bytes[offset..])?);
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+impl StateBackend for InMemoryBackend {
+    fn entry_count(&self) -> u64 {
+        self.entries.len() as u64
+    }
+
+    fn entry_at(&self, index: u64) -> Option<StorageEntry> {
+        self.entries.get(index as usize).copied()
+    }
+}
+
+/// Memory-maps a `state.bin` file and decodes a [`StorageEntry`] only when
+/// it's actually accessed, indexing by offset
+/// (`STATE_HEADER_SIZE + index * STATE_ENTRY_SIZE`).
+pub struct MmapBackend {
+    mmap: Mmap,
+    entry_count: u64,
+}
+
+impl MmapBackend {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StateFormatError> {
+        let file = File::open(path.as_ref()).map_err(|e| StateFormatError::Io(e.to_string()))?;
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|e| StateFormatError::Io(e.to_string()))?;
+
+        let header = StateHeader::from_bytes(&mmap)?;
+        let expected_len = STATE_HEADER_SIZE + header.entry_count as usize * STATE_ENTRY_SIZE;
+        if mmap.len() < expected_len {
+            return Err(StateFormatError::SizeMismatch {
+                expected: expected_len as u64,
+                actual: mmap.len() as u64,
+            });
+        }
+
+        Ok(Self {
+            mmap,
+            entry_count: header.entry_count,
+        })
+    }
+}
+
+impl StateBackend for MmapBackend {
+    fn entry_count(&self) -> u64 {
+        self.entry_count
+    }
+
+    fn entry_at(&self, index: u64) -> Option<StorageEntry> {
+        if index >= self.entry_count {
+            return None;
+        }
+        let offset = STATE_HEADER_SIZE + index as usize * STATE_ENTRY_SIZE;
+        StorageEntry::from_bytes(&self.mmap[offset..]).ok()
+    }
+}
+
+/// Memory-maps a `state.bin` file for sequential or random-access reading,
+/// without [`StateBackend`]'s sorted-key `lookup` (callers like the
+/// database generator and loader just need to stream every entry, or grab
+/// one by ordinal). Unlike [`MmapBackend::open`], which only rejects
+/// truncation, [`StateFile::open`] requires an *exact* size match so
+/// trailing bytes past the declared entry count are caught too.
+pub struct StateFile {
+    mmap: Mmap,
+    header: StateHeader,
+}
+
+impl StateFile {
+    /// Memory-map `path` and validate that its length exactly matches
+    /// `STATE_HEADER_SIZE + entry_count * STATE_ENTRY_SIZE`, emitting
+    /// [`StateFormatError::SizeMismatch`] otherwise.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StateFormatError> {
+        let file = File::open(path.as_ref()).map_err(|e| StateFormatError::Io(e.to_string()))?;
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|e| StateFormatError::Io(e.to_string()))?;
+
+        let header = StateHeader::from_bytes(&mmap)?;
+        let expected_len = STATE_HEADER_SIZE + header.entry_count as usize * STATE_ENTRY_SIZE;
+        if mmap.len() != expected_len {
+            return Err(StateFormatError::SizeMismatch {
+                expected: expected_len as u64,
+                actual: mmap.len() as u64,
+            });
+        }
+
+        Ok(Self { mmap, header })
+    }
+
+    /// The validated header this file was opened with.
+    pub fn header(&self) -> &StateHeader {
+        &self.header
+    }
+
+    /// Number of entries in the file.
+    pub fn entry_count(&self) -> u64 {
+        self.header.entry_count
+    }
+
+    /// Decode the entry at `index` (0-based) by slicing directly into the
+    /// mapping -- O(1), no per-entry allocation. `None` if out of range.
+    pub fn entry(&self, index: u64) -> Option<StorageEntry> {
+        if index >= self.header.entry_count {
+            return None;
+        }
+        let offset = STATE_HEADER_SIZE + index as usize * STATE_ENTRY_SIZE;
+        StorageEntry::from_bytes(&self.mmap[offset..]).ok()
+    }
+
+    /// Iterate every entry in file order.
+    pub fn iter(&self) -> StateFileIter<'_> {
+        StateFileIter { file: self, next: 0 }
+    }
+}
+
+impl<'a> IntoIterator for &'a StateFile {
+    type Item = StorageEntry;
+    type IntoIter = StateFileIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator returned by [`StateFile::iter`].
+pub struct StateFileIter<'a> {
+    file: &'a StateFile,
+    next: u64,
+}
+
+impl<'a> Iterator for StateFileIter<'a> {
+    type Item = StorageEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.file.entry(self.next)?;
+        self.next += 1;
+        Some(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_test_file(path: &Path, entries: &[StorageEntry]) {
+        let header = StateHeader::new(entries.len() as u64, 100, 1, [0u8; 32]);
+        let mut bytes = header.to_bytes().to_vec();
+        for entry in entries {
+            bytes.extend_from_slice(&entry.to_bytes());
+        }
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    fn sample_entries() -> Vec<StorageEntry> {
+        vec![
+            StorageEntry::new([0x01; 20], [0x01; 32], [0xaa; 32]),
+            StorageEntry::new([0x01; 20], [0x02; 32], [0xbb; 32]),
+            StorageEntry::new([0x02; 20], [0x01; 32], [0xcc; 32]),
+        ]
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("inspire-state-backend-test-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_inmemory_backend_lookup() {
+        let path = temp_path("inmemory.bin");
+        write_test_file(&path, &sample_entries());
+
+        let backend = InMemoryBackend::load(&path).unwrap();
+        assert_eq!(backend.entry_count(), 3);
+        assert_eq!(backend.lookup(&[0x01; 20], &[0x02; 32]), Some(sample_entries()[1]));
+        assert_eq!(backend.lookup(&[0x03; 20], &[0x00; 32]), None);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_mmap_backend_lookup() {
+        let path = temp_path("mmap.bin");
+        write_test_file(&path, &sample_entries());
+
+        let backend = MmapBackend::open(&path).unwrap();
+        assert_eq!(backend.entry_count(), 3);
+        assert_eq!(backend.lookup(&[0x02; 20], &[0x01; 32]), Some(sample_entries()[2]));
+        assert_eq!(backend.entry_at(0), Some(sample_entries()[0]));
+        assert_eq!(backend.entry_at(3), None);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_open_selects_backend_by_kind() {
+        let path = temp_path("open.bin");
+        write_test_file(&path, &sample_entries());
+
+        let inmemory = open(&path, BackendKind::InMemory).unwrap();
+        let mmap = open(&path, BackendKind::Mmap).unwrap();
+        assert_eq!(inmemory.entry_count(), mmap.entry_count());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_mmap_backend_rejects_truncated_file() {
+        let path = temp_path("truncated.bin");
+        let header = StateHeader::new(3, 100, 1, [0u8; 32]);
+        std::fs::write(&path, header.to_bytes()).unwrap();
+
+        let result = MmapBackend::open(&path);
+        assert!(matches!(result, Err(StateFormatError::SizeMismatch { .. })));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_state_file_iterates_entries_in_order() {
+        let path = temp_path("statefile.bin");
+        write_test_file(&path, &sample_entries());
+
+        let file = StateFile::open(&path).unwrap();
+        assert_eq!(file.entry_count(), 3);
+        assert_eq!(file.entry(1), Some(sample_entries()[1]));
+        assert_eq!(file.entry(3), None);
+
+        let collected: Vec<StorageEntry> = file.iter().collect();
+        assert_eq!(collected, sample_entries());
+        let via_into_iter: Vec<StorageEntry> = (&file).into_iter().collect();
+        assert_eq!(via_into_iter, sample_entries());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_state_file_rejects_trailing_garbage() {
+        let path = temp_path("statefile_trailing.bin");
+        write_test_file(&path, &sample_entries());
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.extend_from_slice(&[0u8; 16]);
+        std::fs::write(&path, &bytes).unwrap();
+
+        let result = StateFile::open(&path);
+        assert!(matches!(result, Err(StateFormatError::SizeMismatch { .. })));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
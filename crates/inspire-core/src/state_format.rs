@@ -5,8 +5,19 @@
 /// Magic bytes identifying an inspire state file
 pub const STATE_MAGIC: [u8; 4] = *b"PIR2";
 
-/// Header size in bytes
-pub const STATE_HEADER_SIZE: usize = 64;
+/// Header size in bytes.
+///
+/// Bumped from 64 to 128 when the streaming body digest was added
+/// ([`StateHeader::VERSION`] 2): 64 bytes for the original fields, 32 for
+/// `body_digest`, 8 for `hashed_bytes`, and 24 bytes reserved for future
+/// fields so the next addition doesn't need another size bump. This is a
+/// fixed-layout export artifact regenerated from source state by
+/// lane-builder, not a long-lived wire format, so the bump is a clean break
+/// rather than something `from_bytes` needs to stay compatible with.
+pub const STATE_HEADER_SIZE: usize = 128;
+
+const DIGEST_OFFSET: usize = 64;
+const HASHED_BYTES_OFFSET: usize = 96;
 
 /// Entry size in bytes (address + slot + value)
 pub const STATE_ENTRY_SIZE: usize = 84;
@@ -31,13 +42,39 @@ pub struct StateHeader {
     pub chain_id: u64,
     /// Block hash (zero if unknown)
     pub block_hash: [u8; 32],
+    /// BLAKE3 digest of the entry bytes, fed incrementally while they were
+    /// streamed to disk (see [`StateHeader::with_digest`]). Only meaningful
+    /// when `version >= 2`; zero for headers written by `new()` alone.
+    pub body_digest: [u8; 32],
+    /// Number of entry bytes covered by `body_digest`, i.e. `entry_count *
+    /// entry_size` at the time the digest was finalized. Stored separately
+    /// from `entry_count` so [`StateHeader::verify_digest`] can catch a
+    /// truncated file even if `entry_count` itself were somehow correct.
+    pub hashed_bytes: u64,
 }
 
 impl StateHeader {
-    /// Current format version
+    /// Format version without a body digest
     pub const VERSION: u16 = 1;
+    /// Format version with `body_digest`/`hashed_bytes` populated
+    pub const VERSION_WITH_DIGEST: u16 = 2;
+    /// Newest version this binary knows how to read directly. A file
+    /// stored at an older version is still readable (see
+    /// [`StateHeader::needs_migration`] and [`crate::state_migration`]); one
+    /// stored newer than this is rejected by [`StateHeader::from_bytes`]
+    /// with [`StateFormatError::UnsupportedVersion`], since this binary has
+    /// no way to know what that version's entry layout means.
+    pub const CURRENT_VERSION: u16 = Self::VERSION_WITH_DIGEST;
+
+    /// Whether this header's entries are in an older on-disk layout than
+    /// [`StateHeader::CURRENT_VERSION`], and should be run through
+    /// [`crate::state_migration::MigrationRegistry::migrate_to_current`]
+    /// before being trusted by a reader written against the current format.
+    pub fn needs_migration(&self) -> bool {
+        self.version < Self::CURRENT_VERSION
+    }
 
-    /// Create a new header
+    /// Create a new header (no body digest; see [`StateHeader::with_digest`])
     pub fn new(entry_count: u64, block_number: u64, chain_id: u64, block_hash: [u8; 32]) -> Self {
         Self {
             magic: STATE_MAGIC,
@@ -47,9 +84,21 @@ impl StateHeader {
             block_number,
             chain_id,
             block_hash,
+            body_digest: [0u8; 32],
+            hashed_bytes: 0,
         }
     }
 
+    /// Attach a streaming body digest, bumping the header to
+    /// [`StateHeader::VERSION_WITH_DIGEST`]. Chainable for use right after
+    /// [`StateHeader::new`].
+    pub fn with_digest(mut self, body_digest: [u8; 32], hashed_bytes: u64) -> Self {
+        self.version = Self::VERSION_WITH_DIGEST;
+        self.body_digest = body_digest;
+        self.hashed_bytes = hashed_bytes;
+        self
+    }
+
     /// Serialize header to bytes
     pub fn to_bytes(&self) -> [u8; STATE_HEADER_SIZE] {
         let mut buf = [0u8; STATE_HEADER_SIZE];
@@ -60,6 +109,10 @@ impl StateHeader {
         buf[16..24].copy_from_slice(&self.block_number.to_le_bytes());
         buf[24..32].copy_from_slice(&self.chain_id.to_le_bytes());
         buf[32..64].copy_from_slice(&self.block_hash);
+        buf[DIGEST_OFFSET..DIGEST_OFFSET + 32].copy_from_slice(&self.body_digest);
+        buf[HASHED_BYTES_OFFSET..HASHED_BYTES_OFFSET + 8]
+            .copy_from_slice(&self.hashed_bytes.to_le_bytes());
+        // Remaining bytes stay zero, reserved for future fields.
         buf
     }
 
@@ -77,11 +130,17 @@ impl StateHeader {
         }
 
         let version = u16::from_le_bytes(data[4..6].try_into().unwrap());
+        if version > Self::CURRENT_VERSION {
+            return Err(StateFormatError::UnsupportedVersion { version });
+        }
         let entry_size = u16::from_le_bytes(data[6..8].try_into().unwrap());
         let entry_count = u64::from_le_bytes(data[8..16].try_into().unwrap());
         let block_number = u64::from_le_bytes(data[16..24].try_into().unwrap());
         let chain_id = u64::from_le_bytes(data[24..32].try_into().unwrap());
         let block_hash: [u8; 32] = data[32..64].try_into().unwrap();
+        let body_digest: [u8; 32] = data[DIGEST_OFFSET..DIGEST_OFFSET + 32].try_into().unwrap();
+        let hashed_bytes =
+            u64::from_le_bytes(data[HASHED_BYTES_OFFSET..HASHED_BYTES_OFFSET + 8].try_into().unwrap());
 
         Ok(Self {
             magic,
@@ -91,6 +150,8 @@ impl StateHeader {
             block_number,
             chain_id,
             block_hash,
+            body_digest,
+            hashed_bytes,
         })
     }
 
@@ -98,6 +159,52 @@ impl StateHeader {
     pub fn has_magic(data: &[u8]) -> bool {
         data.len() >= 4 && data[0..4] == STATE_MAGIC
     }
+
+    /// Re-stream the file body from `reader` and check it against
+    /// `body_digest`/`hashed_bytes`. `reader` must start at the first entry
+    /// byte (i.e. right after the header).
+    ///
+    /// Returns [`StateFormatError::DigestNotAvailable`] for headers written
+    /// without a digest ([`StateHeader::VERSION`]).
+    pub fn verify_digest(&self, mut reader: impl std::io::Read) -> Result<(), StateFormatError> {
+        if self.version < Self::VERSION_WITH_DIGEST {
+            return Err(StateFormatError::DigestNotAvailable {
+                version: self.version,
+            });
+        }
+
+        let mut hasher = blake3::Hasher::new();
+        let mut buf = [0u8; 64 * 1024];
+        let mut total_bytes = 0u64;
+
+        loop {
+            let n = reader
+                .read(&mut buf)
+                .map_err(|e| StateFormatError::Io(e.to_string()))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            total_bytes += n as u64;
+        }
+
+        if total_bytes != self.hashed_bytes {
+            return Err(StateFormatError::DigestLengthMismatch {
+                expected: self.hashed_bytes,
+                actual: total_bytes,
+            });
+        }
+
+        let actual_digest = *hasher.finalize().as_bytes();
+        if actual_digest != self.body_digest {
+            return Err(StateFormatError::ChecksumMismatch {
+                expected: self.body_digest,
+                actual: actual_digest,
+            });
+        }
+
+        Ok(())
+    }
 }
 
 /// Storage entry (84 bytes)
@@ -155,6 +262,18 @@ pub enum StateFormatError {
     EntryTooShort { actual: usize },
     /// File size doesn't match header
     SizeMismatch { expected: u64, actual: u64 },
+    /// Header was written without a body digest
+    DigestNotAvailable { version: u16 },
+    /// Re-streamed body length didn't match `hashed_bytes` (truncated file)
+    DigestLengthMismatch { expected: u64, actual: u64 },
+    /// Re-streamed body hashed to a different digest (corruption)
+    ChecksumMismatch { expected: [u8; 32], actual: [u8; 32] },
+    /// I/O error while re-streaming the body for [`StateHeader::verify_digest`]
+    Io(String),
+    /// Header declares a version newer than [`StateHeader::CURRENT_VERSION`]
+    /// -- this binary doesn't know that version's entry layout and must not
+    /// guess at it.
+    UnsupportedVersion { version: u16 },
 }
 
 impl core::fmt::Display for StateFormatError {
@@ -172,6 +291,31 @@ impl core::fmt::Display for StateFormatError {
             StateFormatError::SizeMismatch { expected, actual } => {
                 write!(f, "File size mismatch: expected {} bytes, got {}", expected, actual)
             }
+            StateFormatError::DigestNotAvailable { version } => {
+                write!(f, "Header has no body digest (version {}, need >= {})", version, StateHeader::VERSION_WITH_DIGEST)
+            }
+            StateFormatError::DigestLengthMismatch { expected, actual } => {
+                write!(f, "Body length mismatch while verifying digest: expected {} bytes, got {} (truncated file?)", expected, actual)
+            }
+            StateFormatError::ChecksumMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Body digest mismatch: file is corrupted (expected {}, got {})",
+                    hex::encode(expected),
+                    hex::encode(actual)
+                )
+            }
+            StateFormatError::Io(e) => {
+                write!(f, "I/O error while verifying digest: {}", e)
+            }
+            StateFormatError::UnsupportedVersion { version } => {
+                write!(
+                    f,
+                    "Unsupported state file version {} (this binary supports up to {})",
+                    version,
+                    StateHeader::CURRENT_VERSION
+                )
+            }
         }
     }
 }
@@ -198,6 +342,62 @@ mod tests {
         assert_eq!(recovered.block_number, 20_000_000);
         assert_eq!(recovered.chain_id, 1);
         assert_eq!(recovered.block_hash, block_hash);
+        assert_eq!(recovered.body_digest, [0u8; 32]);
+        assert_eq!(recovered.hashed_bytes, 0);
+    }
+
+    #[test]
+    fn test_header_with_digest_roundtrip() {
+        let block_hash = [0xab; 32];
+        let digest = [0x7a; 32];
+        let header = StateHeader::new(1000, 20_000_000, 1, block_hash).with_digest(digest, 84_000);
+
+        let bytes = header.to_bytes();
+        let recovered = StateHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(recovered.version, StateHeader::VERSION_WITH_DIGEST);
+        assert_eq!(recovered.body_digest, digest);
+        assert_eq!(recovered.hashed_bytes, 84_000);
+    }
+
+    #[test]
+    fn test_verify_digest_success() {
+        let body = b"some entry bytes go here";
+        let digest = *blake3::hash(body).as_bytes();
+        let header = StateHeader::new(1, 0, 1, [0u8; 32]).with_digest(digest, body.len() as u64);
+
+        assert!(header.verify_digest(&body[..]).is_ok());
+    }
+
+    #[test]
+    fn test_verify_digest_mismatch() {
+        let body = b"some entry bytes go here";
+        let digest = *blake3::hash(body).as_bytes();
+        let header = StateHeader::new(1, 0, 1, [0u8; 32]).with_digest(digest, body.len() as u64);
+
+        let corrupted = b"some entry BYTES go here";
+        let result = header.verify_digest(&corrupted[..]);
+        assert!(matches!(
+            result,
+            Err(StateFormatError::ChecksumMismatch { expected, .. }) if expected == digest
+        ));
+    }
+
+    #[test]
+    fn test_verify_digest_length_mismatch() {
+        let body = b"some entry bytes go here";
+        let digest = *blake3::hash(body).as_bytes();
+        let header = StateHeader::new(1, 0, 1, [0u8; 32]).with_digest(digest, body.len() as u64);
+
+        let truncated = &body[..body.len() - 4];
+        let result = header.verify_digest(truncated);
+        assert!(matches!(result, Err(StateFormatError::DigestLengthMismatch { .. })));
+    }
+
+    #[test]
+    fn test_verify_digest_not_available_for_legacy_header() {
+        let header = StateHeader::new(1, 0, 1, [0u8; 32]);
+        let result = header.verify_digest(&b""[..]);
+        assert!(matches!(result, Err(StateFormatError::DigestNotAvailable { version: 1 })));
     }
 
     #[test]
@@ -218,6 +418,27 @@ mod tests {
         assert!(!StateHeader::has_magic(b"PIR")); // too short
     }
 
+    #[test]
+    fn test_from_bytes_rejects_version_newer_than_current() {
+        let mut bytes = StateHeader::new(1, 0, 1, [0u8; 32]).to_bytes();
+        bytes[4..6].copy_from_slice(&(StateHeader::CURRENT_VERSION + 1).to_le_bytes());
+
+        let result = StateHeader::from_bytes(&bytes);
+        assert!(matches!(
+            result,
+            Err(StateFormatError::UnsupportedVersion { version }) if version == StateHeader::CURRENT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn test_needs_migration() {
+        let legacy = StateHeader::new(1, 0, 1, [0u8; 32]);
+        assert!(legacy.needs_migration());
+
+        let current = legacy.with_digest([0u8; 32], 0);
+        assert!(!current.needs_migration());
+    }
+
     #[test]
     fn test_invalid_magic() {
         let mut bytes = [0u8; STATE_HEADER_SIZE];
@@ -0,0 +1,206 @@
+//! Migration pipeline for upgrading older `state.bin` files in place
+//!
+//! [`StateHeader::version`](crate::state_format::StateHeader::version)
+//! records the on-disk entry layout a snapshot was written with. Rather
+//! than every reader needing to understand every historical layout (or
+//! every deployment needing to regenerate `state.bin` from scratch after a
+//! format change), a [`StateMigration`] describes how to rewrite one
+//! version's entry bytes into the next version's, and a
+//! [`MigrationRegistry`] chains as many hops as it takes to reach
+//! [`StateHeader::CURRENT_VERSION`].
+//!
+//! [`StateHeader::from_bytes`] only ever sees the fixed-size header, not
+//! the entry stream, so it can't invoke a migration itself -- it just
+//! accepts any `version <= CURRENT_VERSION` (see
+//! [`StateHeader::needs_migration`]) and rejects anything newer with
+//! [`StateFormatError::UnsupportedVersion`]. It's up to a caller that holds
+//! the whole file (e.g. a `lane-builder` upgrade tool) to check
+//! `needs_migration()` and, if so, run the entry bytes through
+//! [`MigrationRegistry::migrate_to_current`] before trusting them.
+
+use std::io::{Read, Write};
+
+use crate::state_format::{StateFormatError, StateHeader};
+
+/// One hop in the migration pipeline: rewrites entry bytes written at
+/// [`StateMigration::from_version`] into the layout expected at
+/// [`StateMigration::to_version`], returning the header that should
+/// describe the rewritten bytes.
+pub trait StateMigration: Send + Sync {
+    fn from_version(&self) -> u16;
+    fn to_version(&self) -> u16;
+
+    /// Stream `reader`'s entry bytes (not including the header) through the
+    /// migration, writing the upgraded entry bytes to `writer`.
+    fn migrate(
+        &self,
+        header: &StateHeader,
+        reader: &mut dyn Read,
+        writer: &mut dyn Write,
+    ) -> Result<StateHeader, StateFormatError>;
+}
+
+/// Upgrades version 1 (no body digest) to version 2: the entry layout is
+/// unchanged, so this just copies entry bytes through unmodified while
+/// streaming them through BLAKE3, then stamps the header with the
+/// resulting `body_digest`/`hashed_bytes` -- the same digest
+/// [`StateHeader::verify_digest`] would check against afterward.
+pub struct DigestBackfillMigration;
+
+impl StateMigration for DigestBackfillMigration {
+    fn from_version(&self) -> u16 {
+        StateHeader::VERSION
+    }
+
+    fn to_version(&self) -> u16 {
+        StateHeader::VERSION_WITH_DIGEST
+    }
+
+    fn migrate(
+        &self,
+        header: &StateHeader,
+        reader: &mut dyn Read,
+        writer: &mut dyn Write,
+    ) -> Result<StateHeader, StateFormatError> {
+        let mut hasher = blake3::Hasher::new();
+        let mut buf = [0u8; 64 * 1024];
+        let mut total_bytes = 0u64;
+
+        loop {
+            let n = reader.read(&mut buf).map_err(|e| StateFormatError::Io(e.to_string()))?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            writer
+                .write_all(&buf[..n])
+                .map_err(|e| StateFormatError::Io(e.to_string()))?;
+            total_bytes += n as u64;
+        }
+
+        Ok(header.with_digest(*hasher.finalize().as_bytes(), total_bytes))
+    }
+}
+
+/// A set of [`StateMigration`]s, looked up by the version they upgrade
+/// *from*, chained to carry a header/entry stream up to
+/// [`StateHeader::CURRENT_VERSION`].
+pub struct MigrationRegistry {
+    migrations: Vec<Box<dyn StateMigration>>,
+}
+
+impl MigrationRegistry {
+    pub fn new() -> Self {
+        Self { migrations: Vec::new() }
+    }
+
+    /// A registry pre-populated with every migration this binary ships.
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(DigestBackfillMigration));
+        registry
+    }
+
+    pub fn register(&mut self, migration: Box<dyn StateMigration>) -> &mut Self {
+        self.migrations.push(migration);
+        self
+    }
+
+    fn find(&self, from_version: u16) -> Option<&dyn StateMigration> {
+        self.migrations
+            .iter()
+            .find(|m| m.from_version() == from_version)
+            .map(|m| m.as_ref())
+    }
+
+    /// Chain registered migrations to carry `header`/`entry_bytes` from
+    /// whatever version they're currently at up to
+    /// [`StateHeader::CURRENT_VERSION`], returning the upgraded header and
+    /// entry bytes. A no-op (returns the inputs unchanged) if `header`
+    /// already `!needs_migration()`.
+    pub fn migrate_to_current(
+        &self,
+        header: StateHeader,
+        entry_bytes: Vec<u8>,
+    ) -> Result<(StateHeader, Vec<u8>), StateFormatError> {
+        let mut current_header = header;
+        let mut current_bytes = entry_bytes;
+
+        while current_header.needs_migration() {
+            let migration = self
+                .find(current_header.version)
+                .ok_or(StateFormatError::UnsupportedVersion {
+                    version: current_header.version,
+                })?;
+
+            let mut upgraded_bytes = Vec::with_capacity(current_bytes.len());
+            current_header = migration.migrate(&current_header, &mut &current_bytes[..], &mut upgraded_bytes)?;
+            current_bytes = upgraded_bytes;
+        }
+
+        Ok((current_header, current_bytes))
+    }
+}
+
+impl Default for MigrationRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_backfill_migration_upgrades_v1_to_v2() {
+        let entries = b"some entry bytes";
+        let header = StateHeader::new(1, 100, 1, [0u8; 32]);
+        let mut out = Vec::new();
+
+        let migrated = DigestBackfillMigration
+            .migrate(&header, &mut &entries[..], &mut out)
+            .unwrap();
+
+        assert_eq!(migrated.version, StateHeader::VERSION_WITH_DIGEST);
+        assert_eq!(out, entries);
+        assert_eq!(migrated.hashed_bytes, entries.len() as u64);
+        assert!(migrated.verify_digest(&entries[..]).is_ok());
+    }
+
+    #[test]
+    fn test_registry_migrates_to_current() {
+        let registry = MigrationRegistry::with_defaults();
+        let header = StateHeader::new(1, 100, 1, [0u8; 32]);
+        let entries = b"entries".to_vec();
+
+        let (migrated, bytes) = registry.migrate_to_current(header, entries.clone()).unwrap();
+
+        assert!(!migrated.needs_migration());
+        assert_eq!(bytes, entries);
+    }
+
+    #[test]
+    fn test_registry_is_noop_for_current_header() {
+        let registry = MigrationRegistry::with_defaults();
+        let header = StateHeader::new(1, 100, 1, [0u8; 32]).with_digest([0x11; 32], 7);
+        let entries = b"entries".to_vec();
+
+        let (migrated, bytes) = registry.migrate_to_current(header, entries.clone()).unwrap();
+
+        assert_eq!(migrated, header);
+        assert_eq!(bytes, entries);
+    }
+
+    #[test]
+    fn test_registry_errors_on_unbridgeable_gap() {
+        let registry = MigrationRegistry::new(); // no migrations registered
+        let header = StateHeader::new(1, 100, 1, [0u8; 32]);
+
+        let result = registry.migrate_to_current(header, Vec::new());
+        assert!(matches!(
+            result,
+            Err(StateFormatError::UnsupportedVersion { version: 1 })
+        ));
+    }
+}
@@ -0,0 +1,262 @@
+//! Reader for the flat stem index that `stem-index` (see
+//! `lane_builder::bin::stem_index`) builds from a `state.bin` snapshot.
+//!
+//! The on-disk format is `count:8 (LE u64)` followed by `count` fixed-width
+//! records of `stem:31 + offset:8 (LE u64)`, sorted ascending by `stem`.
+//! [`StemIndex`] memory-maps that file and performs binary search directly
+//! over the mapped bytes -- no allocation, no full-file parse -- so a reader
+//! actually gets the `O(log N)` lookup the index format promises instead of
+//! every consumer re-parsing it into a `BTreeMap`. This mirrors
+//! [`crate::state_backend::MmapBackend`]'s approach to `state.bin` itself.
+
+use std::fs::File;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+/// Length in bytes of a stem (EIP-7864 tree key minus its subindex byte)
+pub const STEM_LEN: usize = 31;
+
+/// A stem: the first 31 bytes of an EIP-7864 tree key
+pub type Stem = [u8; STEM_LEN];
+
+const COUNT_SIZE: usize = 8;
+const RECORD_SIZE: usize = STEM_LEN + 8;
+
+/// A memory-mapped, binary-searchable stem index
+pub struct StemIndex {
+    mmap: Mmap,
+    count: u64,
+}
+
+impl StemIndex {
+    /// Opens and validates a stem index file written by `stem-index`.
+    ///
+    /// Checks the leading count against the actual file length so a
+    /// truncated index is rejected here, up front, rather than panicking on
+    /// slice indexing the first time a lookup walks off the end of the file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StemIndexError> {
+        let file = File::open(path.as_ref()).map_err(|e| StemIndexError::Io(e.to_string()))?;
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|e| StemIndexError::Io(e.to_string()))?;
+
+        if mmap.len() < COUNT_SIZE {
+            return Err(StemIndexError::HeaderTooShort { actual: mmap.len() });
+        }
+
+        let count = u64::from_le_bytes(mmap[0..COUNT_SIZE].try_into().unwrap());
+        let expected_len = COUNT_SIZE + count as usize * RECORD_SIZE;
+        if mmap.len() != expected_len {
+            return Err(StemIndexError::SizeMismatch {
+                expected: expected_len,
+                actual: mmap.len(),
+            });
+        }
+
+        Ok(Self { mmap, count })
+    }
+
+    /// Number of `(stem, offset)` records in the index
+    pub fn len(&self) -> u64 {
+        self.count
+    }
+
+    /// Whether the index has no records
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    fn record(&self, index: u64) -> (Stem, u64) {
+        let start = COUNT_SIZE + index as usize * RECORD_SIZE;
+        let stem: Stem = self.mmap[start..start + STEM_LEN].try_into().unwrap();
+        let offset = u64::from_le_bytes(
+            self.mmap[start + STEM_LEN..start + RECORD_SIZE]
+                .try_into()
+                .unwrap(),
+        );
+        (stem, offset)
+    }
+
+    /// Smallest index whose stem is `>= target` (a standard lower-bound
+    /// binary search), used by both [`StemIndex::lookup`] and
+    /// [`StemIndex::range`].
+    fn lower_bound(&self, target: &Stem) -> u64 {
+        let mut lo = 0u64;
+        let mut hi = self.count;
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let (stem, _) = self.record(mid);
+            if &stem < target {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        lo
+    }
+
+    /// Binary search for `stem`'s starting offset in the indexed `state.bin`
+    pub fn lookup(&self, stem: &Stem) -> Option<u64> {
+        let index = self.lower_bound(stem);
+        if index < self.count && self.record(index).0 == *stem {
+            Some(self.record(index).1)
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over `(stem, offset)` records with `start <= stem < end`,
+    /// for a prefix scan over a contiguous stem range.
+    pub fn range(&self, start: &Stem, end: &Stem) -> StemIndexRange<'_> {
+        StemIndexRange {
+            index: self,
+            next: self.lower_bound(start),
+            end: self.lower_bound(end),
+        }
+    }
+}
+
+/// Iterator returned by [`StemIndex::range`]
+pub struct StemIndexRange<'a> {
+    index: &'a StemIndex,
+    next: u64,
+    end: u64,
+}
+
+impl Iterator for StemIndexRange<'_> {
+    type Item = (Stem, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.end {
+            return None;
+        }
+        let record = self.index.record(self.next);
+        self.next += 1;
+        Some(record)
+    }
+}
+
+/// Errors opening or validating a stem index file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StemIndexError {
+    /// File is shorter than the 8-byte leading count
+    HeaderTooShort {
+        /// Actual file length in bytes
+        actual: usize,
+    },
+    /// File length doesn't match `count * 39 + 8`, i.e. it was truncated or
+    /// corrupted after the count was written
+    SizeMismatch {
+        /// Expected file length given the leading count
+        expected: usize,
+        /// Actual file length in bytes
+        actual: usize,
+    },
+    /// I/O error opening or mapping the file
+    Io(String),
+}
+
+impl core::fmt::Display for StemIndexError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            StemIndexError::HeaderTooShort { actual } => {
+                write!(f, "stem index file too short for count header: {actual} bytes")
+            }
+            StemIndexError::SizeMismatch { expected, actual } => {
+                write!(f, "stem index file size mismatch: expected {expected} bytes, got {actual}")
+            }
+            StemIndexError::Io(e) => write!(f, "I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for StemIndexError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("inspire-stem-index-test-{}-{}", std::process::id(), name))
+    }
+
+    fn sample_stems() -> Vec<(Stem, u64)> {
+        vec![
+            ([0x01; STEM_LEN], 0),
+            ([0x02; STEM_LEN], 5),
+            ([0x03; STEM_LEN], 9),
+            ([0x05; STEM_LEN], 20),
+        ]
+    }
+
+    fn write_index(path: &Path, records: &[(Stem, u64)]) {
+        let mut bytes = (records.len() as u64).to_le_bytes().to_vec();
+        for (stem, offset) in records {
+            bytes.extend_from_slice(stem);
+            bytes.extend_from_slice(&offset.to_le_bytes());
+        }
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_lookup_hit_and_miss() {
+        let path = temp_path("lookup.bin");
+        write_index(&path, &sample_stems());
+
+        let index = StemIndex::open(&path).unwrap();
+        assert_eq!(index.len(), 4);
+        assert_eq!(index.lookup(&[0x03; STEM_LEN]), Some(9));
+        assert_eq!(index.lookup(&[0x04; STEM_LEN]), None);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_range_is_half_open_prefix_scan() {
+        let path = temp_path("range.bin");
+        write_index(&path, &sample_stems());
+
+        let index = StemIndex::open(&path).unwrap();
+        let got: Vec<(Stem, u64)> = index.range(&[0x02; STEM_LEN], &[0x05; STEM_LEN]).collect();
+        assert_eq!(got, vec![([0x02; STEM_LEN], 5), ([0x03; STEM_LEN], 9)]);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_empty_index_opens_and_finds_nothing() {
+        let path = temp_path("empty.bin");
+        write_index(&path, &[]);
+
+        let index = StemIndex::open(&path).unwrap();
+        assert!(index.is_empty());
+        assert_eq!(index.lookup(&[0x00; STEM_LEN]), None);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_truncated_index_is_rejected() {
+        let path = temp_path("truncated.bin");
+        let mut bytes = 4u64.to_le_bytes().to_vec();
+        bytes.extend_from_slice(&[0u8; RECORD_SIZE]); // only 1 of 4 records present
+        std::fs::write(&path, bytes).unwrap();
+
+        let result = StemIndex::open(&path);
+        assert!(matches!(result, Err(StemIndexError::SizeMismatch { .. })));
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_missing_count_header_is_rejected() {
+        let path = temp_path("no-header.bin");
+        std::fs::write(&path, [0u8; 4]).unwrap();
+
+        let result = StemIndex::open(&path);
+        assert!(matches!(result, Err(StemIndexError::HeaderTooShort { .. })));
+
+        std::fs::remove_file(&path).ok();
+    }
+}
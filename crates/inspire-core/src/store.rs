@@ -0,0 +1,571 @@
+//! Pluggable storage backend for PIR dump artifacts
+//!
+//! `lane-builder`'s `state-dump` binary and
+//! `inspire-server`'s snapshot loader ([`crate`]'s consumer
+//! `inspire_server::state::ServerState::load_lanes`) both read and write
+//! the same three files -- `accounts.bin`/`storage.bin`/`database.bin` and
+//! `metadata.json` -- and until now both assumed those files sat on a
+//! filesystem the dumper and the server shared. [`Store`] lifts that
+//! assumption: [`FilesystemStore`] is today's behavior, and [`S3Store`]
+//! lets a dumper write straight to an `s3://bucket/prefix` location that
+//! any number of query nodes can then pull from independently, borrowing
+//! the same "storage is just a `Store` trait object" shape pict-rs and
+//! Garage use for their own backends.
+//!
+//! `put_writer` returns a `Write` so callers that already build their
+//! output incrementally (`state-dump`'s `BufWriter<File>` loop) don't need
+//! to restructure around buffering a whole dump in memory first --
+//! [`S3Store`] streams what it's given into S3 multipart upload parts as
+//! they fill up, rather than holding the dump's entirety before the first
+//! byte goes over the wire.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("HTTP error talking to object store: {0}")]
+    Http(String),
+
+    #[error("object store rejected {op} {key}: HTTP {status}: {body}")]
+    ObjectStore {
+        op: &'static str,
+        key: String,
+        status: u16,
+        body: String,
+    },
+
+    #[error("invalid store location {0:?}: {1}")]
+    InvalidLocation(String, String),
+}
+
+/// Credentials and endpoint for an S3-compatible object store.
+///
+/// `endpoint` defaults to AWS's own `s3.{region}.amazonaws.com` when unset,
+/// but can point at a self-hosted S3-compatible service (MinIO, Garage)
+/// instead -- this is what actually makes path-style `s3://bucket/prefix`
+/// locations useful for an operator who isn't running on AWS.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct S3Config {
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Override for non-AWS S3-compatible endpoints, e.g. `http://minio.internal:9000`
+    pub endpoint: Option<String>,
+}
+
+/// A location a [`Store`] can be opened against: either a local directory
+/// or an `s3://bucket/prefix` URL.
+pub enum StoreLocation {
+    Filesystem(PathBuf),
+    S3 { bucket: String, prefix: String },
+}
+
+impl StoreLocation {
+    /// Parses `s3://bucket/prefix` URLs as [`StoreLocation::S3`]; anything
+    /// else is treated as a filesystem path, matching every dump/load path
+    /// in this codebase today (see `state-dump --output-dir`).
+    pub fn parse(location: &str) -> Result<Self, StoreError> {
+        match location.strip_prefix("s3://") {
+            Some(rest) => {
+                let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+                if bucket.is_empty() {
+                    return Err(StoreError::InvalidLocation(
+                        location.to_string(),
+                        "missing bucket name".to_string(),
+                    ));
+                }
+                Ok(StoreLocation::S3 {
+                    bucket: bucket.to_string(),
+                    prefix: prefix.trim_end_matches('/').to_string(),
+                })
+            }
+            None => Ok(StoreLocation::Filesystem(PathBuf::from(location))),
+        }
+    }
+}
+
+/// A place dump artifacts can be written to and read back from.
+///
+/// All methods are blocking -- both call sites run from synchronous
+/// contexts (`state-dump`'s `fn main`, `ServerState::load_lanes`), the same
+/// reasoning [`crate`]'s consumer `inspire_server::ubt_verify::UbtRootClient`
+/// uses `reqwest::blocking::Client` for.
+pub trait Store: Send + Sync {
+    /// Open `key` for writing. Implementations should not require the
+    /// caller to know the final size up front.
+    fn put_writer(&self, key: &str) -> Result<Box<dyn Write>, StoreError>;
+
+    /// Read `key` fully into memory. Used for small artifacts like
+    /// `metadata.json`.
+    fn get(&self, key: &str) -> Result<Vec<u8>, StoreError>;
+
+    /// Download `key` to `local_path`, for the `use_mmap` loading path
+    /// which needs a real file to `mmap()` rather than an in-memory buffer.
+    fn download_to_file(&self, key: &str, local_path: &Path) -> Result<(), StoreError>;
+}
+
+/// Opens a [`Store`] for `location`, dispatching on [`StoreLocation::parse`].
+/// `s3_config` is ignored for filesystem locations.
+pub fn open_store(location: &str, s3_config: Option<S3Config>) -> Result<Box<dyn Store>, StoreError> {
+    match StoreLocation::parse(location)? {
+        StoreLocation::Filesystem(root) => Ok(Box::new(FilesystemStore::new(root))),
+        StoreLocation::S3 { bucket, prefix } => {
+            let config = s3_config.ok_or_else(|| {
+                StoreError::InvalidLocation(
+                    location.to_string(),
+                    "s3:// location requires S3 credentials/region config".to_string(),
+                )
+            })?;
+            Ok(Box::new(S3Store::new(bucket, prefix, config)))
+        }
+    }
+}
+
+/// Today's behavior: dump artifacts live as plain files under a directory.
+pub struct FilesystemStore {
+    root: PathBuf,
+}
+
+impl FilesystemStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn resolve(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl Store for FilesystemStore {
+    fn put_writer(&self, key: &str) -> Result<Box<dyn Write>, StoreError> {
+        let path = self.resolve(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(Box::new(BufWriter::new(File::create(path)?)))
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<u8>, StoreError> {
+        Ok(fs::read(self.resolve(key))?)
+    }
+
+    fn download_to_file(&self, key: &str, local_path: &Path) -> Result<(), StoreError> {
+        fs::copy(self.resolve(key), local_path)?;
+        Ok(())
+    }
+}
+
+/// Size of each multipart upload part. S3 requires every part but the last
+/// to be at least 5 MiB; 8 MiB keeps the part count reasonable for a
+/// multi-gigabyte `accounts.bin`/`storage.bin` without holding much more
+/// than one part in memory at a time.
+const MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Streams dump artifacts to an S3-compatible bucket.
+pub struct S3Store {
+    bucket: String,
+    prefix: String,
+    config: S3Config,
+    http: reqwest::blocking::Client,
+}
+
+impl S3Store {
+    pub fn new(bucket: String, prefix: String, config: S3Config) -> Self {
+        Self {
+            bucket,
+            prefix,
+            config,
+            http: reqwest::blocking::Client::new(),
+        }
+    }
+
+    fn full_key(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", self.prefix, key)
+        }
+    }
+
+    fn object_url(&self, key: &str) -> String {
+        match &self.config.endpoint {
+            Some(endpoint) => format!("{}/{}/{}", endpoint.trim_end_matches('/'), self.bucket, key),
+            None => format!(
+                "https://{}.s3.{}.amazonaws.com/{}",
+                self.bucket, self.config.region, key
+            ),
+        }
+    }
+}
+
+impl Store for S3Store {
+    fn put_writer(&self, key: &str) -> Result<Box<dyn Write>, StoreError> {
+        let full_key = self.full_key(key);
+        let writer = S3MultipartWriter::start(
+            self.http.clone(),
+            self.config.clone(),
+            self.object_url(&full_key),
+            full_key,
+        )?;
+        Ok(Box::new(writer))
+    }
+
+    fn get(&self, key: &str) -> Result<Vec<u8>, StoreError> {
+        let full_key = self.full_key(key);
+        let url = self.object_url(&full_key);
+        let request = s3_auth::signed_request(&self.http, "GET", &url, &self.config, b"")?;
+        let response = request
+            .send()
+            .map_err(|e| StoreError::Http(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(StoreError::ObjectStore {
+                op: "GET",
+                key: full_key,
+                status: status.as_u16(),
+                body,
+            });
+        }
+
+        Ok(response.bytes().map_err(|e| StoreError::Http(e.to_string()))?.to_vec())
+    }
+
+    fn download_to_file(&self, key: &str, local_path: &Path) -> Result<(), StoreError> {
+        let bytes = self.get(key)?;
+        if let Some(parent) = local_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(local_path, bytes)?;
+        Ok(())
+    }
+}
+
+/// Buffers writes into [`MULTIPART_PART_SIZE`] chunks and uploads each as
+/// an S3 multipart upload part as soon as it fills, so a caller streaming a
+/// large dump never needs the whole thing resident in memory at once.
+/// `finish()` (called from `Drop`) completes the upload. A part upload
+/// that fails is returned as an error from the `write()` call that
+/// triggered it; a failure while completing the upload in `Drop` can't be
+/// returned, so it is only logged.
+struct S3MultipartWriter {
+    http: reqwest::blocking::Client,
+    config: S3Config,
+    url: String,
+    key: String,
+    upload_id: String,
+    buffer: Vec<u8>,
+    parts: Vec<(u32, String)>,
+    completed: bool,
+    error: Option<StoreError>,
+}
+
+impl S3MultipartWriter {
+    fn start(
+        http: reqwest::blocking::Client,
+        config: S3Config,
+        url: String,
+        key: String,
+    ) -> Result<Self, StoreError> {
+        let initiate_url = format!("{url}?uploads");
+        let request = s3_auth::signed_request(&http, "POST", &initiate_url, &config, b"")?;
+        let response = request.send().map_err(|e| StoreError::Http(e.to_string()))?;
+
+        let status = response.status();
+        let body = response.text().map_err(|e| StoreError::Http(e.to_string()))?;
+        if !status.is_success() {
+            return Err(StoreError::ObjectStore {
+                op: "CreateMultipartUpload",
+                key,
+                status: status.as_u16(),
+                body,
+            });
+        }
+        let upload_id = extract_xml_tag(&body, "UploadId").ok_or_else(|| {
+            StoreError::Http(format!("CreateMultipartUpload response missing UploadId: {body}"))
+        })?;
+
+        Ok(Self {
+            http,
+            config,
+            url,
+            key,
+            upload_id,
+            buffer: Vec::with_capacity(MULTIPART_PART_SIZE),
+            parts: Vec::new(),
+            completed: false,
+            error: None,
+        })
+    }
+
+    fn upload_part(&mut self, part_number: u32, data: &[u8]) -> Result<(), StoreError> {
+        let part_url = format!(
+            "{}?partNumber={}&uploadId={}",
+            self.url, part_number, self.upload_id
+        );
+        let request = s3_auth::signed_request(&self.http, "PUT", &part_url, &self.config, data)?;
+        let response = request
+            .body(data.to_vec())
+            .send()
+            .map_err(|e| StoreError::Http(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(StoreError::ObjectStore {
+                op: "UploadPart",
+                key: self.key.clone(),
+                status: status.as_u16(),
+                body,
+            });
+        }
+
+        let etag = response
+            .headers()
+            .get("ETag")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| StoreError::Http("UploadPart response missing ETag".to_string()))?
+            .to_string();
+
+        self.parts.push((part_number, etag));
+        Ok(())
+    }
+
+    fn flush_buffer(&mut self) -> Result<(), StoreError> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let part_number = self.parts.len() as u32 + 1;
+        let data = std::mem::take(&mut self.buffer);
+        self.upload_part(part_number, &data)
+    }
+
+    fn finish(&mut self) -> Result<(), StoreError> {
+        if self.completed {
+            return Ok(());
+        }
+        self.flush_buffer()?;
+        self.completed = true;
+
+        let complete_url = format!("{}?uploadId={}", self.url, self.upload_id);
+        let body = render_complete_multipart_body(&self.parts);
+        let request = s3_auth::signed_request(&self.http, "POST", &complete_url, &self.config, body.as_bytes())?;
+        let response = request
+            .body(body)
+            .send()
+            .map_err(|e| StoreError::Http(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(StoreError::ObjectStore {
+                op: "CompleteMultipartUpload",
+                key: self.key.clone(),
+                status: status.as_u16(),
+                body,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl Write for S3MultipartWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(err) = self.error.take() {
+            return Err(io::Error::new(io::ErrorKind::Other, err));
+        }
+
+        self.buffer.extend_from_slice(buf);
+        while self.buffer.len() >= MULTIPART_PART_SIZE {
+            let part: Vec<u8> = self.buffer.drain(..MULTIPART_PART_SIZE).collect();
+            let part_number = self.parts.len() as u32 + 1;
+            if let Err(e) = self.upload_part(part_number, &part) {
+                self.error = Some(e);
+                let err = self.error.take().unwrap();
+                return Err(io::Error::new(io::ErrorKind::Other, err));
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for S3MultipartWriter {
+    fn drop(&mut self) {
+        if !self.completed {
+            if let Err(e) = self.finish() {
+                tracing::error!(key = %self.key, error = %e, "Failed to complete S3 multipart upload");
+            }
+        }
+    }
+}
+
+fn render_complete_multipart_body(parts: &[(u32, String)]) -> String {
+    let mut body = String::from("<CompleteMultipartUpload>");
+    for (number, etag) in parts {
+        body.push_str(&format!(
+            "<Part><PartNumber>{number}</PartNumber><ETag>{etag}</ETag></Part>"
+        ));
+    }
+    body.push_str("</CompleteMultipartUpload>");
+    body
+}
+
+fn extract_xml_tag(xml: &str, tag: &str) -> Option<String> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&close)? + start;
+    Some(xml[start..end].to_string())
+}
+
+/// Minimal AWS SigV4 request signing, just enough to authenticate the S3
+/// REST calls [`S3Store`] and [`S3MultipartWriter`] make. Not a general
+/// SigV4 client -- no query-string presigning, no chunked payload signing.
+mod s3_auth {
+    use hmac::{Hmac, Mac};
+    use sha2::{Digest, Sha256};
+
+    use super::{S3Config, StoreError};
+
+    type HmacSha256 = Hmac<Sha256>;
+
+    /// Builds a `reqwest::blocking::RequestBuilder` for `method url` with
+    /// the `Authorization`/`x-amz-*` headers SigV4 requires already set.
+    pub(super) fn signed_request(
+        http: &reqwest::blocking::Client,
+        method: &str,
+        url: &str,
+        config: &S3Config,
+        payload: &[u8],
+    ) -> Result<reqwest::blocking::RequestBuilder, StoreError> {
+        let now = httpdate::fmt_http_date(std::time::SystemTime::now());
+        let amz_date = now.replace("GMT", "").replace(", ", "T").replace(' ', "") + "Z";
+        let date_stamp = &amz_date[0..8];
+
+        let payload_hash = hex::encode(Sha256::digest(payload));
+        let host = reqwest::Url::parse(url)
+            .map_err(|e| StoreError::Http(format!("invalid URL {url}: {e}")))?
+            .host_str()
+            .ok_or_else(|| StoreError::Http(format!("URL {url} has no host")))?
+            .to_string();
+
+        let credential_scope = format!("{date_stamp}/{}/s3/aws4_request", config.region);
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+
+        let canonical_request = format!(
+            "{method}\n{path}\n{query}\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}",
+            method = method,
+            path = reqwest::Url::parse(url).unwrap().path(),
+            query = reqwest::Url::parse(url).unwrap().query().unwrap_or(""),
+        );
+
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{credential_scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let signing_key = derive_signing_key(&config.secret_access_key, date_stamp, &config.region);
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{credential_scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            config.access_key_id,
+        );
+
+        Ok(http
+            .request(method.parse().expect("valid HTTP method"), url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("Authorization", authorization))
+    }
+
+    fn derive_signing_key(secret: &str, date_stamp: &str, region: &str) -> Vec<u8> {
+        let k_date = hmac_sha256(format!("AWS4{secret}").as_bytes(), date_stamp.as_bytes());
+        let k_region = hmac_sha256(&k_date, region.as_bytes());
+        let k_service = hmac_sha256(&k_region, b"s3");
+        hmac_sha256(&k_service, b"aws4_request")
+    }
+
+    fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(data);
+        mac.finalize().into_bytes().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_s3_location() {
+        let loc = StoreLocation::parse("s3://my-bucket/pir/sepolia").unwrap();
+        match loc {
+            StoreLocation::S3 { bucket, prefix } => {
+                assert_eq!(bucket, "my-bucket");
+                assert_eq!(prefix, "pir/sepolia");
+            }
+            _ => panic!("expected S3 location"),
+        }
+    }
+
+    #[test]
+    fn test_parse_s3_location_without_prefix() {
+        let loc = StoreLocation::parse("s3://my-bucket").unwrap();
+        match loc {
+            StoreLocation::S3 { bucket, prefix } => {
+                assert_eq!(bucket, "my-bucket");
+                assert_eq!(prefix, "");
+            }
+            _ => panic!("expected S3 location"),
+        }
+    }
+
+    #[test]
+    fn test_parse_filesystem_location() {
+        let loc = StoreLocation::parse("./pir-data").unwrap();
+        assert!(matches!(loc, StoreLocation::Filesystem(p) if p == PathBuf::from("./pir-data")));
+    }
+
+    #[test]
+    fn test_parse_s3_location_rejects_empty_bucket() {
+        assert!(StoreLocation::parse("s3:///prefix").is_err());
+    }
+
+    #[test]
+    fn test_filesystem_store_put_and_get_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("inspire-store-test-{}", std::process::id()));
+        let store = FilesystemStore::new(&dir);
+
+        {
+            let mut writer = store.put_writer("accounts.bin").unwrap();
+            writer.write_all(b"hello world").unwrap();
+        }
+
+        let bytes = store.get("accounts.bin").unwrap();
+        assert_eq!(bytes, b"hello world");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_extract_xml_tag() {
+        let xml = "<InitiateMultipartUploadResult><Bucket>b</Bucket><Key>k</Key><UploadId>abc-123</UploadId></InitiateMultipartUploadResult>";
+        assert_eq!(extract_xml_tag(xml, "UploadId"), Some("abc-123".to_string()));
+        assert_eq!(extract_xml_tag(xml, "Missing"), None);
+    }
+}
@@ -0,0 +1,101 @@
+//! Constant-time bearer-token auth for the mutating admin endpoints
+//!
+//! `/admin/reload` triggers an expensive database swap, so an admin
+//! listener reachable by anyone is a trivial DoS vector even though it's
+//! normally bound to localhost only (see `crate::routes::create_admin_router`).
+//! When the operator sets `TwoLaneConfig::admin_reload_token`, every request
+//! to a route guarded by [`admin_auth_middleware`] must carry a matching
+//! `Authorization: Bearer <token>` header, compared in constant time so
+//! response timing can't leak how many bytes of a guessed token matched.
+//! Left off (`None`) by default so deployments that already isolate the
+//! admin listener at the network layer aren't forced to mint a token.
+
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, Request};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::error::ServerError;
+use crate::state::SharedState;
+
+/// Compare two byte strings without short-circuiting on the first
+/// mismatch. Lengths are allowed to leak -- `expected` is a fixed,
+/// operator-chosen secret, not something worth hiding the length of.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Whether `headers` carries an `Authorization: Bearer <token>` matching
+/// `expected`. Split out from [`admin_auth_middleware`] so the auth
+/// decision is unit-testable without building a full `Request`/`Response`.
+fn is_authorized(headers: &HeaderMap, expected: &str) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), expected.as_bytes()))
+}
+
+/// Axum middleware gating a route behind `TwoLaneConfig::admin_reload_token`.
+/// A no-op when no token is configured.
+pub async fn admin_auth_middleware(
+    State(state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    match state.config.admin_reload_token.as_deref() {
+        Some(expected) if !is_authorized(request.headers(), expected) => {
+            ServerError::Unauthorized.into_response()
+        }
+        _ => next.run(request).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers_with_bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_accepts_matching_token() {
+        let headers = headers_with_bearer("super-secret");
+        assert!(is_authorized(&headers, "super-secret"));
+    }
+
+    #[test]
+    fn test_rejects_mismatched_token() {
+        let headers = headers_with_bearer("wrong-token");
+        assert!(!is_authorized(&headers, "super-secret"));
+    }
+
+    #[test]
+    fn test_rejects_missing_header() {
+        let headers = HeaderMap::new();
+        assert!(!is_authorized(&headers, "super-secret"));
+    }
+
+    #[test]
+    fn test_rejects_non_bearer_scheme() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_static("Basic super-secret"));
+        assert!(!is_authorized(&headers, "super-secret"));
+    }
+
+    #[test]
+    fn test_constant_time_eq_rejects_length_mismatch() {
+        assert!(!constant_time_eq(b"secret", b"secret-but-longer"));
+    }
+}
@@ -10,6 +10,7 @@
 //!   loadtest http://localhost:3000 -c 128 -q 50         # 128 clients, 50 queries each
 //!   loadtest http://localhost:3000 --with-reloads       # Trigger reloads during test
 //!   loadtest http://localhost:3000 --lane cold          # Test cold lane only
+//!   loadtest http://localhost:3000 --distribution zipf --zipf-s 1.2  # Skewed hot-lane load
 
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -19,6 +20,7 @@ use clap::Parser;
 use inspire_pir::math::GaussianSampler;
 use inspire_pir::rlwe::RlweSecretKey;
 use inspire_pir::{extract, query as pir_query, ServerCrs};
+use rand::Rng;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Semaphore;
@@ -57,6 +59,92 @@ struct Args {
     /// Warmup queries before timing starts
     #[arg(long, default_value = "10")]
     warmup: usize,
+
+    /// Index sampling distribution for queries
+    #[arg(long, value_enum, default_value = "uniform")]
+    distribution: Distribution,
+
+    /// Zipf skew parameter `s` (only used with `--distribution zipf`); higher
+    /// values concentrate queries on fewer hot indices
+    #[arg(long, default_value = "1.07")]
+    zipf_s: f64,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Distribution {
+    Uniform,
+    Zipf,
+}
+
+/// Zipf(n, s) sampler using rejection inversion (Hörmann & Derflinger, 1996),
+/// which draws exact Zipf-distributed ranks in O(1) expected time per sample
+/// without materializing an O(n) CDF table.
+struct ZipfSampler {
+    n: f64,
+    exponent: f64,
+    h_integral_x1: f64,
+    h_integral_n: f64,
+    s_bound: f64,
+}
+
+impl ZipfSampler {
+    fn new(n: u64, exponent: f64) -> Self {
+        let n = n as f64;
+        let h_integral_x1 = Self::h_integral(1.5, exponent) - 1.0;
+        let h_integral_n = Self::h_integral(n + 0.5, exponent);
+        let s_bound = 2.0 - Self::h_integral_inv(Self::h_integral(2.5, exponent) - Self::h(2.0, exponent), exponent);
+        Self { n, exponent, h_integral_x1, h_integral_n, s_bound }
+    }
+
+    /// Sample a rank in `1..=n`, with rank 1 drawn most frequently.
+    fn sample(&self, rng: &mut impl Rng) -> u64 {
+        loop {
+            let u = self.h_integral_n + rng.gen::<f64>() * (self.h_integral_x1 - self.h_integral_n);
+            let x = Self::h_integral_inv(u, self.exponent);
+            let mut k = (x + 0.5).floor();
+            if k < 1.0 {
+                k = 1.0;
+            } else if k > self.n {
+                k = self.n;
+            }
+
+            if k - x <= self.s_bound || u >= Self::h_integral(k + 0.5, self.exponent) - Self::h(k, self.exponent) {
+                return k as u64;
+            }
+        }
+    }
+
+    fn h_integral(x: f64, exponent: f64) -> f64 {
+        let log_x = x.ln();
+        Self::helper2((1.0 - exponent) * log_x) * log_x
+    }
+
+    fn h(x: f64, exponent: f64) -> f64 {
+        (-exponent * x.ln()).exp()
+    }
+
+    fn h_integral_inv(x: f64, exponent: f64) -> f64 {
+        let t = (x * (1.0 - exponent)).max(-1.0);
+        (Self::helper1(t) * x).exp()
+    }
+
+    /// `ln(1 + x) / x`, numerically stable near `x = 0`
+    fn helper1(x: f64) -> f64 {
+        if x.abs() > 1e-8 {
+            x.ln_1p() / x
+        } else {
+            1.0 - x * (0.5 - x * (1.0 / 3.0 - x * 0.25))
+        }
+    }
+
+    /// `(exp(x) - 1) / x`, numerically stable near `x = 0`
+    fn helper2(x: f64) -> f64 {
+        if x.abs() > 1e-8 {
+            x.exp_m1() / x
+        } else {
+            1.0 + x * 0.5 * (1.0 + x / 3.0 * (1.0 + x * 0.25))
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -76,6 +164,89 @@ struct QueryResponse {
     response: inspire_pir::ServerResponse,
 }
 
+/// Sub-buckets per power-of-two doubling band, giving ~1/64 (1.5%) relative
+/// error at any scale.
+const SUB_BUCKETS: usize = 64;
+
+/// Highest tracked magnitude (2^40 us ~= 12.7 days); latencies above this are
+/// clamped into the top bucket and counted separately.
+const MAX_MAGNITUDE: usize = 40;
+
+const NUM_BUCKETS: usize = (MAX_MAGNITUDE + 1) * SUB_BUCKETS;
+
+/// Lock-free logarithmic (HDR-style) histogram of latencies in microseconds.
+///
+/// Each power-of-two band `[2^m, 2^(m+1))` is split into `SUB_BUCKETS` equal
+/// linear sub-buckets, so the relative error of any recorded percentile is
+/// bounded by `1 / SUB_BUCKETS` regardless of magnitude. Recording a sample
+/// is a single `fetch_add` on the bucket for that value.
+struct LatencyHistogram {
+    buckets: Vec<AtomicU64>,
+    clamped: AtomicU64,
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        Self {
+            buckets: (0..NUM_BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+            clamped: AtomicU64::new(0),
+        }
+    }
+
+    /// Map a latency in microseconds to its bucket index.
+    fn bucket_index(v: u64) -> usize {
+        let v = v.max(1);
+        let magnitude = (63 - v.leading_zeros()) as usize;
+        if magnitude > MAX_MAGNITUDE {
+            return NUM_BUCKETS - 1;
+        }
+        let band_start = 1u64 << magnitude;
+        let sub_bucket = ((v - band_start) * SUB_BUCKETS as u64 / band_start) as usize;
+        magnitude * SUB_BUCKETS + sub_bucket.min(SUB_BUCKETS - 1)
+    }
+
+    /// Representative value (microseconds) for a bucket index, used when
+    /// reporting a percentile.
+    fn bucket_midpoint(idx: usize) -> u64 {
+        let magnitude = (idx / SUB_BUCKETS) as u32;
+        let sub_bucket = (idx % SUB_BUCKETS) as u64;
+        let band_start = 1u64 << magnitude;
+        let low = band_start + sub_bucket * band_start / SUB_BUCKETS as u64;
+        let high = band_start + (sub_bucket + 1) * band_start / SUB_BUCKETS as u64;
+        (low + high) / 2
+    }
+
+    fn record(&self, latency_us: u64) {
+        let idx = Self::bucket_index(latency_us);
+        if idx == NUM_BUCKETS - 1 && latency_us >= (1u64 << MAX_MAGNITUDE) {
+            self.clamped.fetch_add(1, Ordering::Relaxed);
+        }
+        self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Microsecond latency below which `percentile` (0.0..=1.0) of recorded
+    /// samples fall. Returns `0` if nothing was recorded.
+    fn percentile(&self, percentile: f64) -> u64 {
+        let total: u64 = self.buckets.iter().map(|b| b.load(Ordering::Relaxed)).sum();
+        if total == 0 {
+            return 0;
+        }
+        let target = ((percentile * total as f64).ceil() as u64).max(1);
+        let mut cumulative = 0u64;
+        for (idx, bucket) in self.buckets.iter().enumerate() {
+            cumulative += bucket.load(Ordering::Relaxed);
+            if cumulative >= target {
+                return Self::bucket_midpoint(idx);
+            }
+        }
+        Self::bucket_midpoint(NUM_BUCKETS - 1)
+    }
+
+    fn clamped_count(&self) -> u64 {
+        self.clamped.load(Ordering::Relaxed)
+    }
+}
+
 struct Stats {
     total_queries: AtomicU64,
     successful_queries: AtomicU64,
@@ -83,6 +254,7 @@ struct Stats {
     total_latency_us: AtomicU64,
     min_latency_us: AtomicU64,
     max_latency_us: AtomicU64,
+    histogram: LatencyHistogram,
 }
 
 impl Stats {
@@ -94,6 +266,7 @@ impl Stats {
             total_latency_us: AtomicU64::new(0),
             min_latency_us: AtomicU64::new(u64::MAX),
             max_latency_us: AtomicU64::new(0),
+            histogram: LatencyHistogram::new(),
         }
     }
 
@@ -103,6 +276,7 @@ impl Stats {
         self.total_latency_us.fetch_add(latency_us, Ordering::Relaxed);
         self.min_latency_us.fetch_min(latency_us, Ordering::Relaxed);
         self.max_latency_us.fetch_max(latency_us, Ordering::Relaxed);
+        self.histogram.record(latency_us);
     }
 
     fn record_failure(&self) {
@@ -140,7 +314,18 @@ impl Stats {
         if min_latency != u64::MAX {
             println!("  Min:        {:.2} ms", min_latency as f64 / 1000.0);
             println!("  Avg:        {:.2} ms", avg_latency as f64 / 1000.0);
+            println!("  p50:        {:.2} ms", self.histogram.percentile(0.50) as f64 / 1000.0);
+            println!("  p90:        {:.2} ms", self.histogram.percentile(0.90) as f64 / 1000.0);
+            println!("  p99:        {:.2} ms", self.histogram.percentile(0.99) as f64 / 1000.0);
+            println!("  p99.9:      {:.2} ms", self.histogram.percentile(0.999) as f64 / 1000.0);
             println!("  Max:        {:.2} ms", max_latency as f64 / 1000.0);
+            let clamped = self.histogram.clamped_count();
+            if clamped > 0 {
+                println!(
+                    "  ({} sample(s) clamped into the top bucket; percentiles above them are lower bounds)",
+                    clamped
+                );
+            }
         } else {
             println!("  (no successful queries)");
         }
@@ -169,6 +354,8 @@ async fn run_client(
     entry_count: u64,
     stats: Arc<Stats>,
     semaphore: Arc<Semaphore>,
+    distribution: Distribution,
+    zipf: Option<Arc<ZipfSampler>>,
 ) {
     let http = Client::builder()
         .timeout(Duration::from_secs(60))
@@ -178,7 +365,14 @@ async fn run_client(
     for q in 0..queries {
         let _permit = semaphore.acquire().await.unwrap();
 
-        let index = ((client_id * queries + q) as u64) % entry_count;
+        let index = match distribution {
+            Distribution::Uniform => ((client_id * queries + q) as u64) % entry_count,
+            Distribution::Zipf => {
+                let zipf = zipf.as_ref().expect("zipf sampler required for zipf distribution");
+                let rank = zipf.sample(&mut rand::thread_rng());
+                (rank - 1).min(entry_count - 1)
+            }
+        };
         let start = Instant::now();
 
         let result = async {
@@ -265,6 +459,10 @@ async fn main() -> anyhow::Result<()> {
     println!("Lane:         {}", args.lane);
     println!("Max concurrent: {}", args.max_concurrent);
     println!("With reloads: {}", args.with_reloads);
+    match args.distribution {
+        Distribution::Uniform => println!("Distribution:  uniform"),
+        Distribution::Zipf => println!("Distribution:  zipf (s = {})", args.zipf_s),
+    }
     println!();
 
     let client = Client::new();
@@ -277,6 +475,10 @@ async fn main() -> anyhow::Result<()> {
     let crs = Arc::new(crs);
     let stats = Arc::new(Stats::new());
     let semaphore = Arc::new(Semaphore::new(args.max_concurrent));
+    let zipf = match args.distribution {
+        Distribution::Uniform => None,
+        Distribution::Zipf => Some(Arc::new(ZipfSampler::new(entry_count, args.zipf_s))),
+    };
 
     if args.warmup > 0 {
         println!("\nWarmup: {} queries...", args.warmup);
@@ -290,6 +492,8 @@ async fn main() -> anyhow::Result<()> {
             let url = args.server_url.clone();
             let lane = args.lane.clone();
             let sem = semaphore.clone();
+            let distribution = args.distribution;
+            let zipf = zipf.clone();
 
             warmup_handles.push(tokio::spawn(async move {
                 run_client(
@@ -302,6 +506,8 @@ async fn main() -> anyhow::Result<()> {
                     entry_count,
                     stats,
                     sem,
+                    distribution,
+                    zipf,
                 )
                 .await;
             }));
@@ -341,6 +547,8 @@ async fn main() -> anyhow::Result<()> {
         let lane = args.lane.clone();
         let sem = semaphore.clone();
         let queries = args.queries;
+        let distribution = args.distribution;
+        let zipf = zipf.clone();
 
         handles.push(tokio::spawn(async move {
             run_client(
@@ -353,6 +561,8 @@ async fn main() -> anyhow::Result<()> {
                 entry_count,
                 stats,
                 sem,
+                distribution,
+                zipf,
             )
             .await;
         }));
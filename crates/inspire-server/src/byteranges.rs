@@ -0,0 +1,223 @@
+//! Multi-range (`multipart/byteranges`) parsing and rendering
+//!
+//! A syncing client fetching several [`RangeEntry`](crate) blobs out of a
+//! delta file wants them in one round trip rather than one `Range:` request
+//! per entry. This module turns a `Range: bytes=0-99,200-299,...` header
+//! into validated, coalesced spans and renders the corresponding
+//! `multipart/byteranges` body (RFC 7233 §4.1) -- one part per span, each
+//! with its own `Content-Range`.
+//!
+//! This is intentionally decoupled from any one HTTP handler. `parse_ranges`
+//! also backs the single-range `Range:` support on `get_crs` and
+//! `query_binary`/`query_seeded_binary` (see `crate::routes::ranged_response`,
+//! which calls it with `max_parts: 1`); `render_multipart_byteranges` and
+//! genuine multi-range requests are still unused -- no endpoint here needs
+//! more than one range per request yet.
+
+/// A single, inclusive byte span: `[start, end]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+}
+
+/// Why a `Range:` header couldn't be honored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+    /// No requested span overlaps `[0, total_len)` -- caller should respond `416`
+    Unsatisfiable,
+    /// More distinct spans than `max_parts` were requested -- caller should
+    /// respond `416` rather than let a client force unbounded part-count work
+    TooManyParts,
+}
+
+/// Parse a `Range: bytes=...` header value into validated, coalesced spans
+///
+/// Supports `start-end`, `start-` (open-ended), and `-suffix_len` (last N
+/// bytes) forms, comma-separated. Spans are clamped to `[0, total_len)`,
+/// sorted, and merged when overlapping or adjacent, so a client asking for
+/// `0-99,100-199` gets one part rather than two. Returns
+/// [`RangeError::TooManyParts`] if the coalesced span count still exceeds
+/// `max_parts`, bounding the multipart fan-out a single request can trigger.
+pub fn parse_ranges(
+    header: &str,
+    total_len: u64,
+    max_parts: usize,
+) -> Result<Vec<ByteRange>, RangeError> {
+    let spec = header.strip_prefix("bytes=").ok_or(RangeError::Unsatisfiable)?;
+    if total_len == 0 {
+        return Err(RangeError::Unsatisfiable);
+    }
+
+    let mut spans: Vec<ByteRange> = Vec::new();
+    for part in spec.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+
+        let Some((start_str, end_str)) = part.split_once('-') else {
+            continue;
+        };
+
+        let range = if start_str.is_empty() {
+            // suffix range: last `end_str` bytes
+            let suffix_len: u64 = end_str.parse().map_err(|_| RangeError::Unsatisfiable)?;
+            if suffix_len == 0 {
+                continue;
+            }
+            let start = total_len.saturating_sub(suffix_len);
+            ByteRange { start, end: total_len - 1 }
+        } else {
+            let start: u64 = start_str.parse().map_err(|_| RangeError::Unsatisfiable)?;
+            if start >= total_len {
+                continue;
+            }
+            let end = if end_str.is_empty() {
+                total_len - 1
+            } else {
+                let requested: u64 = end_str.parse().map_err(|_| RangeError::Unsatisfiable)?;
+                requested.min(total_len - 1)
+            };
+            if end < start {
+                continue;
+            }
+            ByteRange { start, end }
+        };
+
+        spans.push(range);
+    }
+
+    if spans.is_empty() {
+        return Err(RangeError::Unsatisfiable);
+    }
+
+    spans.sort_by_key(|r| r.start);
+    let mut coalesced: Vec<ByteRange> = Vec::with_capacity(spans.len());
+    for span in spans {
+        match coalesced.last_mut() {
+            Some(last) if span.start <= last.end.saturating_add(1) => {
+                last.end = last.end.max(span.end);
+            }
+            _ => coalesced.push(span),
+        }
+    }
+
+    if coalesced.len() > max_parts {
+        return Err(RangeError::TooManyParts);
+    }
+
+    Ok(coalesced)
+}
+
+/// Render a `multipart/byteranges` body for the given spans
+///
+/// `fetch` is called once per coalesced span to pull its slice of the
+/// underlying content; callers can read from a file, an mmap, or an
+/// in-memory buffer without this function caring which. Returns the
+/// boundary string (for the response's `Content-Type` header) and the
+/// rendered body.
+pub fn render_multipart_byteranges(
+    ranges: &[ByteRange],
+    total_len: u64,
+    part_content_type: &str,
+    mut fetch: impl FnMut(ByteRange) -> Vec<u8>,
+) -> (String, Vec<u8>) {
+    let boundary = format!("inspire-byteranges-{total_len:x}-{}", ranges.len());
+    let mut body = Vec::new();
+
+    for range in ranges {
+        let data = fetch(*range);
+        debug_assert_eq!(data.len() as u64, range.len());
+
+        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+        body.extend_from_slice(format!("Content-Type: {part_content_type}\r\n").as_bytes());
+        body.extend_from_slice(
+            format!("Content-Range: bytes {}-{}/{total_len}\r\n\r\n", range.start, range.end)
+                .as_bytes(),
+        );
+        body.extend_from_slice(&data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+
+    (boundary, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single_range() {
+        let ranges = parse_ranges("bytes=0-99", 1000, 16).unwrap();
+        assert_eq!(ranges, vec![ByteRange { start: 0, end: 99 }]);
+    }
+
+    #[test]
+    fn test_parse_multiple_ranges_preserved_distinct() {
+        let ranges = parse_ranges("bytes=0-99,200-299,400-499", 1000, 16).unwrap();
+        assert_eq!(
+            ranges,
+            vec![
+                ByteRange { start: 0, end: 99 },
+                ByteRange { start: 200, end: 299 },
+                ByteRange { start: 400, end: 499 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coalesces_overlapping_and_adjacent_ranges() {
+        let ranges = parse_ranges("bytes=0-99,100-199,150-250", 1000, 16).unwrap();
+        assert_eq!(ranges, vec![ByteRange { start: 0, end: 250 }]);
+    }
+
+    #[test]
+    fn test_open_ended_and_suffix_ranges() {
+        let ranges = parse_ranges("bytes=900-,-50", 1000, 16).unwrap();
+        assert_eq!(
+            ranges,
+            vec![ByteRange { start: 900, end: 999 }]
+        );
+    }
+
+    #[test]
+    fn test_end_clamped_to_total_len() {
+        let ranges = parse_ranges("bytes=0-99999", 1000, 16).unwrap();
+        assert_eq!(ranges, vec![ByteRange { start: 0, end: 999 }]);
+    }
+
+    #[test]
+    fn test_rejects_unsatisfiable_range() {
+        let err = parse_ranges("bytes=5000-6000", 1000, 16).unwrap_err();
+        assert_eq!(err, RangeError::Unsatisfiable);
+    }
+
+    #[test]
+    fn test_caps_number_of_parts() {
+        let header = "bytes=0-0,10-10,20-20,30-30";
+        let err = parse_ranges(header, 1000, 2).unwrap_err();
+        assert_eq!(err, RangeError::TooManyParts);
+    }
+
+    #[test]
+    fn test_render_multipart_byteranges_includes_content_range_per_part() {
+        let ranges = vec![ByteRange { start: 0, end: 3 }, ByteRange { start: 10, end: 13 }];
+        let (boundary, body) =
+            render_multipart_byteranges(&ranges, 1000, "application/octet-stream", |r| {
+                vec![0u8; r.len() as usize]
+            });
+        let text = String::from_utf8_lossy(&body);
+        assert!(text.contains(&boundary));
+        assert!(text.contains("Content-Range: bytes 0-3/1000"));
+        assert!(text.contains("Content-Range: bytes 10-13/1000"));
+        assert!(text.trim_end().ends_with(&format!("--{boundary}--")));
+    }
+}
@@ -0,0 +1,276 @@
+//! Ingest-node registry and consistent-hash routing for query-node mode
+//!
+//! Following the distributed ingest/query separation Parseable uses for its
+//! own server, a deployment can split into two roles (see
+//! [`inspire_core::NodeMode`]):
+//!
+//! - An **ingest** node owns the `state-dump` pipeline and the on-disk lane
+//!   files, reloads its snapshot on its own schedule, and calls
+//!   [`register`]/`/cluster/register` against every query node it's told
+//!   about.
+//! - A **query** node holds no lane data of its own. It keeps a
+//!   [`ClusterRegistry`] of the ingest nodes that have registered with it
+//!   and, for each incoming query, picks one via [`ClusterRegistry::route`]
+//!   and proxies the request to it instead of calling
+//!   [`crate::state::DbSnapshot::process_query`] locally.
+//!
+//! Routing is rendezvous (highest-random-weight) hashing over the
+//! manifest block and shard config rather than round-robin: the same
+//! `(lane, manifest_block, shard_config)` triple always maps to the same
+//! backend as long as that backend stays registered, so per-backend page
+//! caches stay warm instead of being shuffled across the fleet on every
+//! request.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use inspire_core::Lane;
+use inspire_pir::params::ShardConfig;
+
+use crate::error::{Result, ServerError};
+use crate::state::LaneStats;
+
+/// An ingest node's self-reported identity and lane status, as sent to
+/// `/cluster/register`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterRequest {
+    /// Externally-reachable base URL this node can be proxied to
+    /// (e.g. `http://10.0.1.4:3000`)
+    pub url: String,
+    /// This node's currently-loaded lane stats, so a query node can skip
+    /// routing to a backend that isn't ready yet
+    pub stats: LaneStats,
+    /// Config hash of the `TwoLaneConfig` this node is running, echoed back
+    /// in proxy error responses so an operator can spot a mixed-version
+    /// fleet at a glance
+    pub config_hash: String,
+}
+
+/// Response to a successful `/cluster/register` call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterResponse {
+    pub registered: bool,
+    /// How many ingest nodes this query node now tracks, for operator
+    /// sanity-checking against the expected fleet size
+    pub cluster_size: usize,
+}
+
+/// An ingest node's last-known state, as tracked by a query node
+#[derive(Debug, Clone)]
+struct IngestNode {
+    stats: LaneStats,
+    config_hash: String,
+    last_seen: Instant,
+}
+
+/// Registry of ingest nodes a query node proxies requests to
+///
+/// Entries are keyed by URL and expire after [`ClusterRegistry::STALE_AFTER`]
+/// without a re-register, so a crashed ingest node falls out of rotation on
+/// its own rather than requiring an explicit deregistration call.
+pub struct ClusterRegistry {
+    nodes: RwLock<HashMap<String, IngestNode>>,
+}
+
+impl ClusterRegistry {
+    /// An ingest node that hasn't re-registered in this long is treated as
+    /// gone. Ingest nodes are expected to re-register on every reload
+    /// (typically every few seconds to minutes), so this comfortably
+    /// outlives a normal reload cadence while still reacting to a crashed
+    /// node within a few polling cycles.
+    const STALE_AFTER: Duration = Duration::from_secs(120);
+
+    pub fn new() -> Self {
+        Self {
+            nodes: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Record or refresh an ingest node's registration
+    pub fn register(&self, req: RegisterRequest) -> RegisterResponse {
+        let mut nodes = self.nodes.write().expect("cluster registry lock poisoned");
+        nodes.insert(
+            req.url,
+            IngestNode {
+                stats: req.stats,
+                config_hash: req.config_hash,
+                last_seen: Instant::now(),
+            },
+        );
+        nodes.retain(|_, node| node.last_seen.elapsed() < Self::STALE_AFTER);
+
+        RegisterResponse {
+            registered: true,
+            cluster_size: nodes.len(),
+        }
+    }
+
+    /// Number of currently-live ingest nodes
+    pub fn len(&self) -> usize {
+        self.nodes.read().expect("cluster registry lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pick the ingest node to route a query for `lane` to, via rendezvous
+    /// hashing over `(lane, manifest_block, shard_config)` against each
+    /// live, lane-loaded candidate's URL.
+    ///
+    /// Returns the candidate whose `blake3(url || routing_key)` hash is
+    /// numerically highest. Unlike a mod-N hash, adding or removing a node
+    /// only reshuffles the queries that would have routed to *that* node --
+    /// every other key keeps mapping to the same backend it did before.
+    pub fn route(
+        &self,
+        lane: Lane,
+        manifest_block: Option<u64>,
+        shard_config: &ShardConfig,
+    ) -> Result<String> {
+        let nodes = self.nodes.read().expect("cluster registry lock poisoned");
+
+        let mut routing_key = Vec::with_capacity(32);
+        routing_key.push(match lane {
+            Lane::Hot => 0u8,
+            Lane::Cold => 1u8,
+        });
+        routing_key.extend_from_slice(&manifest_block.unwrap_or(0).to_le_bytes());
+        routing_key.extend_from_slice(&shard_config.shard_size_bytes.to_le_bytes());
+        routing_key.extend_from_slice(&shard_config.entry_size_bytes.to_le_bytes());
+        routing_key.extend_from_slice(&shard_config.total_entries.to_le_bytes());
+
+        let candidate = nodes
+            .iter()
+            .filter(|(_, node)| {
+                node.last_seen.elapsed() < Self::STALE_AFTER
+                    && lane_is_loaded(&node.stats, lane)
+            })
+            .max_by_key(|(url, _)| rendezvous_weight(url, &routing_key));
+
+        candidate
+            .map(|(url, _)| url.clone())
+            .ok_or_else(|| ServerError::LaneNotLoaded(format!(
+                "no registered ingest node has the {:?} lane loaded",
+                lane
+            )))
+    }
+
+    /// Snapshot of every currently-registered node's config hash, for a
+    /// `/cluster/status` style diagnostic endpoint
+    pub fn config_hashes(&self) -> Vec<(String, String)> {
+        self.nodes
+            .read()
+            .expect("cluster registry lock poisoned")
+            .iter()
+            .map(|(url, node)| (url.clone(), node.config_hash.clone()))
+            .collect()
+    }
+}
+
+impl Default for ClusterRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lane_is_loaded(stats: &LaneStats, lane: Lane) -> bool {
+    match lane {
+        Lane::Hot => stats.hot_loaded,
+        Lane::Cold => stats.cold_loaded,
+    }
+}
+
+/// Rendezvous-hashing weight for `candidate` against `routing_key`
+fn rendezvous_weight(candidate: &str, routing_key: &[u8]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(candidate.as_bytes());
+    hasher.update(routing_key);
+    *hasher.finalize().as_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(hot: bool, cold: bool) -> LaneStats {
+        LaneStats {
+            hot_loaded: hot,
+            cold_loaded: cold,
+            hot_entries: 0,
+            cold_entries: 0,
+            hot_contracts: 0,
+            block_number: Some(100),
+            pir_params_version: 1,
+            pir_params_id: "test".to_string(),
+        }
+    }
+
+    fn shard_config() -> ShardConfig {
+        ShardConfig {
+            shard_size_bytes: 128 * 1024,
+            entry_size_bytes: 64,
+            total_entries: 1_000,
+        }
+    }
+
+    #[test]
+    fn test_route_with_no_nodes_errors() {
+        let registry = ClusterRegistry::new();
+        let err = registry.route(Lane::Hot, Some(1), &shard_config()).unwrap_err();
+        assert!(matches!(err, ServerError::LaneNotLoaded(_)));
+    }
+
+    #[test]
+    fn test_route_skips_nodes_missing_the_lane() {
+        let registry = ClusterRegistry::new();
+        registry.register(RegisterRequest {
+            url: "http://cold-only:3000".to_string(),
+            stats: stats(false, true),
+            config_hash: "abc".to_string(),
+        });
+
+        let err = registry.route(Lane::Hot, Some(1), &shard_config()).unwrap_err();
+        assert!(matches!(err, ServerError::LaneNotLoaded(_)));
+
+        let routed = registry.route(Lane::Cold, Some(1), &shard_config()).unwrap();
+        assert_eq!(routed, "http://cold-only:3000");
+    }
+
+    #[test]
+    fn test_route_is_deterministic_for_same_key() {
+        let registry = ClusterRegistry::new();
+        for i in 0..5 {
+            registry.register(RegisterRequest {
+                url: format!("http://node-{i}:3000"),
+                stats: stats(true, true),
+                config_hash: "abc".to_string(),
+            });
+        }
+
+        let a = registry.route(Lane::Hot, Some(42), &shard_config()).unwrap();
+        let b = registry.route(Lane::Hot, Some(42), &shard_config()).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_register_updates_cluster_size() {
+        let registry = ClusterRegistry::new();
+        let resp = registry.register(RegisterRequest {
+            url: "http://node-0:3000".to_string(),
+            stats: stats(true, true),
+            config_hash: "abc".to_string(),
+        });
+        assert_eq!(resp.cluster_size, 1);
+
+        let resp = registry.register(RegisterRequest {
+            url: "http://node-0:3000".to_string(),
+            stats: stats(true, true),
+            config_hash: "abc".to_string(),
+        });
+        assert_eq!(resp.cluster_size, 1, "re-registering the same url shouldn't grow the cluster");
+    }
+}
@@ -0,0 +1,152 @@
+//! `Accept-Encoding` negotiation and streaming response compression
+//!
+//! Large binary responses (batch query streams, and eventually bucket-index
+//! sync payloads once that feature lands in this tree) are mostly
+//! small, highly-repetitive records, so letting clients opt into `gzip` or
+//! `zstd` materially cuts bandwidth. Encoding happens on the already-streamed
+//! body -- never a buffer-then-compress pass -- so response size never
+//! inflates server memory regardless of how large the underlying payload is.
+//!
+//! A `Range:` request pins its offsets to the *uncompressed* bytes, so
+//! callers must treat [`ContentEncoding::Identity`] as mandatory (via
+//! [`negotiate`]'s `has_range` flag) whenever a range is being served --
+//! ranges and content-encoding are mutually exclusive on a single response.
+
+use axum::body::Bytes;
+use axum::http::{HeaderMap, HeaderValue};
+use futures::Stream;
+use tokio_util::io::{ReaderStream, StreamReader};
+
+/// Negotiated response body encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Identity,
+    Gzip,
+    Zstd,
+}
+
+impl ContentEncoding {
+    /// `Content-Encoding` header value, or `None` for identity (omit the header)
+    pub fn header_value(self) -> Option<HeaderValue> {
+        match self {
+            ContentEncoding::Identity => None,
+            ContentEncoding::Gzip => Some(HeaderValue::from_static("gzip")),
+            ContentEncoding::Zstd => Some(HeaderValue::from_static("zstd")),
+        }
+    }
+}
+
+/// Pick the best encoding the client advertised in `Accept-Encoding`
+///
+/// Prefers `zstd` over `gzip` (better ratio for our tuple-heavy payloads)
+/// over identity, but never negotiates a compressed encoding when
+/// `has_range` is set -- `Range:` offsets are defined against the
+/// uncompressed body, so a ranged request always gets identity.
+pub fn negotiate(headers: &HeaderMap, has_range: bool) -> ContentEncoding {
+    if has_range {
+        return ContentEncoding::Identity;
+    }
+
+    let accepted = headers
+        .get(axum::http::header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+
+    let wants = |name: &str| {
+        accepted.split(',').any(|tok| {
+            let tok = tok.trim();
+            let codec = tok.split(';').next().unwrap_or("").trim();
+            if codec != name {
+                return false;
+            }
+            // `codec;q=0` explicitly opts out
+            !tok.contains("q=0")
+        })
+    };
+
+    if wants("zstd") {
+        ContentEncoding::Zstd
+    } else if wants("gzip") {
+        ContentEncoding::Gzip
+    } else {
+        ContentEncoding::Identity
+    }
+}
+
+/// Wrap a byte stream so it's compressed on the fly as it's polled
+///
+/// Returns the stream unchanged for [`ContentEncoding::Identity`]. For
+/// `gzip`/`zstd` the stream is fed through an async encoder one chunk at a
+/// time via [`StreamReader`]/[`ReaderStream`], so compression never
+/// materializes the full body in memory.
+pub fn compress_stream<S>(
+    encoding: ContentEncoding,
+    stream: S,
+) -> std::pin::Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>
+where
+    S: Stream<Item = std::io::Result<Bytes>> + Send + 'static,
+{
+    match encoding {
+        ContentEncoding::Identity => Box::pin(stream),
+        ContentEncoding::Gzip => {
+            let reader = StreamReader::new(stream);
+            let encoder = async_compression::tokio::bufread::GzipEncoder::new(reader);
+            Box::pin(ReaderStream::new(encoder))
+        }
+        ContentEncoding::Zstd => {
+            let reader = StreamReader::new(stream);
+            let encoder = async_compression::tokio::bufread::ZstdEncoder::new(reader);
+            Box::pin(ReaderStream::new(encoder))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers_with_accept_encoding(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::ACCEPT_ENCODING,
+            HeaderValue::from_str(value).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_negotiate_prefers_zstd_over_gzip() {
+        let headers = headers_with_accept_encoding("gzip, zstd, deflate");
+        assert_eq!(negotiate(&headers, false), ContentEncoding::Zstd);
+    }
+
+    #[test]
+    fn test_negotiate_falls_back_to_gzip() {
+        let headers = headers_with_accept_encoding("gzip, deflate");
+        assert_eq!(negotiate(&headers, false), ContentEncoding::Gzip);
+    }
+
+    #[test]
+    fn test_negotiate_falls_back_to_identity_when_unsupported() {
+        let headers = headers_with_accept_encoding("br, deflate");
+        assert_eq!(negotiate(&headers, false), ContentEncoding::Identity);
+    }
+
+    #[test]
+    fn test_negotiate_missing_header_is_identity() {
+        let headers = HeaderMap::new();
+        assert_eq!(negotiate(&headers, false), ContentEncoding::Identity);
+    }
+
+    #[test]
+    fn test_negotiate_honors_q_zero_opt_out() {
+        let headers = headers_with_accept_encoding("zstd;q=0, gzip");
+        assert_eq!(negotiate(&headers, false), ContentEncoding::Gzip);
+    }
+
+    #[test]
+    fn test_negotiate_forces_identity_for_range_requests() {
+        let headers = headers_with_accept_encoding("zstd, gzip");
+        assert_eq!(negotiate(&headers, true), ContentEncoding::Identity);
+    }
+}
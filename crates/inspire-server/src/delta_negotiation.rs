@@ -0,0 +1,69 @@
+//! Version negotiation for the range-delta sync handshake
+//!
+//! A client syncing bucket deltas needs to know, before downloading
+//! anything, whether the on-disk delta format this server emits is one it
+//! understands. [`negotiate`] is the pure decision function a
+//! `/index/deltas/info`-style handler calls with the client's advertised
+//! format version (from a header or query param) and the server's
+//! supported version range; it never panics and always returns a typed
+//! [`ServerError`] variant the handler can translate into the right status
+//! code (`426` when the client is ahead of what this server can emit,
+//! `409` when the server has dropped support for a version this old)
+//! instead of letting a malformed or future-versioned request fall through
+//! to a generic `500`.
+//!
+//! This mirrors the parse-or-error discipline the range-delta file format
+//! itself (`RangeDeltaHeader`/`BucketDelta`, in `inspire_core::bucket_index`)
+//! needs for `to_bytes`/`from_bytes`. No `/index/deltas*` route is
+//! registered in this tree; this is the negotiation half a handler over it
+//! would call.
+
+use crate::error::{Result, ServerError};
+
+/// Decide whether `client_version` can be served by a server whose
+/// supported version range is `[server_min, server_max]` (both inclusive)
+///
+/// Returns `Ok(())` when negotiable.
+pub fn negotiate(client_version: u16, server_min: u16, server_max: u16) -> Result<()> {
+    if client_version > server_max {
+        return Err(ServerError::DeltaVersionTooNew { client_version, server_max });
+    }
+    if client_version < server_min {
+        return Err(ServerError::DeltaVersionTooOld { client_version, server_min });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_accepts_version_in_range() {
+        assert!(negotiate(2, 1, 3).is_ok());
+    }
+
+    #[test]
+    fn test_negotiate_rejects_client_newer_than_server_max() {
+        let err = negotiate(5, 1, 3).unwrap_err();
+        assert!(matches!(
+            err,
+            ServerError::DeltaVersionTooNew { client_version: 5, server_max: 3 }
+        ));
+    }
+
+    #[test]
+    fn test_negotiate_rejects_client_older_than_server_min() {
+        let err = negotiate(0, 1, 3).unwrap_err();
+        assert!(matches!(
+            err,
+            ServerError::DeltaVersionTooOld { client_version: 0, server_min: 1 }
+        ));
+    }
+
+    #[test]
+    fn test_negotiate_accepts_boundary_versions() {
+        assert!(negotiate(1, 1, 3).is_ok());
+        assert!(negotiate(3, 1, 3).is_ok());
+    }
+}
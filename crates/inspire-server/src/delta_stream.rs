@@ -0,0 +1,184 @@
+//! Live push for bucket-delta updates via Server-Sent Events
+//!
+//! Polling `/index/deltas` to notice `current_block` move forward wastes a
+//! round trip for every block a client isn't interested in. This module is
+//! the push-side plumbing instead: a [`DeltaBroadcaster`] that committed
+//! deltas are published to, and [`delta_event_stream`] which
+//! turns a subscription (plus whatever backfill the handler looked up for
+//! the client's `Last-Event-ID`) into an SSE body. A lagging subscriber
+//! gets an explicit `lagged` event instead of silently missing blocks, so
+//! it knows to fall back to a full resync.
+//!
+//! The `BucketDelta` type these events actually carry lives in
+//! `inspire_core::bucket_index`; this module only needs `Bytes` (the
+//! caller's already-serialized payload), so it doesn't depend on it. No
+//! `/index/deltas/stream` route is registered, the reload path does not
+//! publish to a [`DeltaBroadcaster`], and nothing publishes to NATS.
+
+use axum::response::sse::{Event, KeepAlive, Sse};
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use tokio::sync::broadcast;
+
+/// One committed `BucketDelta`, ready to push to subscribers
+#[derive(Debug, Clone)]
+pub struct DeltaEvent {
+    /// Block number the delta was committed at; doubles as the SSE event id
+    /// so a reconnecting client's `Last-Event-ID` tells us where to resume
+    pub block_number: u64,
+    /// Serialized delta bytes (hex-encoded on the wire, since SSE `data:`
+    /// fields are text)
+    pub payload: Bytes,
+}
+
+/// Fan-out point for newly committed deltas
+///
+/// Every subscriber gets a copy of each event passed to
+/// [`publish`](Self::publish). Nothing in this crate calls `publish`.
+#[derive(Clone)]
+pub struct DeltaBroadcaster {
+    sender: broadcast::Sender<DeltaEvent>,
+}
+
+impl DeltaBroadcaster {
+    /// `capacity` bounds how many not-yet-consumed events a lagging
+    /// subscriber can fall behind by before it starts missing ones (it'll
+    /// see a `lagged` event in the stream and should fall back to a full
+    /// `/index/deltas` resync)
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<DeltaEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Push a newly committed delta to every subscriber; a no-op (besides
+    /// the dropped permit) if nobody is currently listening
+    pub fn publish(&self, event: DeltaEvent) {
+        let _ = self.sender.send(event);
+    }
+}
+
+fn to_sse_event(event: &DeltaEvent) -> Event {
+    Event::default()
+        .id(event.block_number.to_string())
+        .data(hex::encode(&event.payload))
+}
+
+/// Merge `backfill` (deltas since the client's `Last-Event-ID`, looked up
+/// by the caller from the on-disk range-delta file) with `live` into one
+/// ordered stream of SSE events
+///
+/// Never fails outright: a [`broadcast::error::RecvError::Lagged`] is
+/// surfaced as a `lagged` event rather than ending the stream, since the
+/// client is still connected and can choose to resync.
+pub fn merged_event_stream(
+    backfill: Vec<DeltaEvent>,
+    live: broadcast::Receiver<DeltaEvent>,
+) -> impl Stream<Item = Event> {
+    let backfill_events: Vec<_> = backfill.iter().map(to_sse_event).collect();
+
+    let live_stream = futures::stream::unfold(live, |mut rx| async move {
+        loop {
+            return match rx.recv().await {
+                Ok(event) => Some((to_sse_event(&event), rx)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => Some((
+                    Event::default()
+                        .event("lagged")
+                        .data(format!("missed {skipped} delta(s); resync from /index/deltas")),
+                    rx,
+                )),
+                Err(broadcast::error::RecvError::Closed) => None,
+            };
+        }
+    });
+
+    futures::stream::iter(backfill_events).chain(live_stream)
+}
+
+/// Build the SSE response for `/index/deltas/stream`
+///
+/// See [`merged_event_stream`] for the replay-then-tail behavior.
+pub fn delta_event_stream(
+    backfill: Vec<DeltaEvent>,
+    live: broadcast::Receiver<DeltaEvent>,
+) -> Sse<impl Stream<Item = std::result::Result<Event, std::convert::Infallible>>> {
+    Sse::new(merged_event_stream(backfill, live).map(Ok)).keep_alive(KeepAlive::default())
+}
+
+/// Feature-gated helpers for a NATS JetStream mirror
+///
+/// Holds the subject prefix and message-size config and splits a
+/// [`DeltaEvent`]'s payload into `max_message_bytes`-sized chunks. This
+/// module does not connect to a broker or publish anything itself.
+#[cfg(feature = "nats")]
+pub mod nats_bridge {
+    use super::DeltaEvent;
+
+    /// Configuration for the JetStream mirror
+    pub struct NatsBridgeConfig {
+        pub subject_prefix: String,
+        pub max_message_bytes: usize,
+    }
+
+    impl Default for NatsBridgeConfig {
+        fn default() -> Self {
+            Self {
+                subject_prefix: "pir.deltas".to_string(),
+                max_message_bytes: 1024 * 1024,
+            }
+        }
+    }
+
+    /// Split `event.payload` into `max_message_bytes`-sized chunks for
+    /// publishing under `{subject_prefix}.{blocks_covered}`, so a delta
+    /// larger than the broker's limit still goes out as several messages
+    /// instead of being rejected outright
+    pub fn chunk_payload<'a>(
+        event: &'a DeltaEvent,
+        max_message_bytes: usize,
+    ) -> impl Iterator<Item = &'a [u8]> {
+        event.payload.chunks(max_message_bytes.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_broadcaster_delivers_to_subscriber() {
+        let broadcaster = DeltaBroadcaster::new(8);
+        let mut rx = broadcaster.subscribe();
+
+        broadcaster.publish(DeltaEvent { block_number: 42, payload: Bytes::from_static(b"abc") });
+
+        let event = rx.recv().await.expect("event delivered");
+        assert_eq!(event.block_number, 42);
+        assert_eq!(&event.payload[..], b"abc");
+    }
+
+    #[tokio::test]
+    async fn test_merged_event_stream_replays_backfill_then_live() {
+        let broadcaster = DeltaBroadcaster::new(8);
+        let rx = broadcaster.subscribe();
+
+        let backfill = vec![DeltaEvent { block_number: 1, payload: Bytes::from_static(b"one") }];
+        broadcaster.publish(DeltaEvent { block_number: 2, payload: Bytes::from_static(b"two") });
+
+        let events: Vec<Event> = merged_event_stream(backfill, rx).take(2).collect().await;
+        assert_eq!(events.len(), 2);
+    }
+
+    #[cfg(feature = "nats")]
+    #[test]
+    fn test_chunk_payload_splits_large_delta() {
+        let event = DeltaEvent { block_number: 1, payload: Bytes::from(vec![0u8; 10]) };
+        let chunks: Vec<_> = nats_bridge::chunk_payload(&event, 4).collect();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[0].len(), 4);
+        assert_eq!(chunks[2].len(), 2);
+    }
+}
@@ -1,9 +1,53 @@
 //! Server error types
 
-use axum::http::StatusCode;
+use axum::http::{header, HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
 use thiserror::Error;
 
+/// Seconds a client should wait before retrying a request that hit
+/// [`ServerError::LaneNotLoaded`] (emitted as the `Retry-After` header)
+const LANE_NOT_LOADED_RETRY_AFTER_SECS: u64 = 5;
+
+/// Stable, machine-readable discriminant for [`ServerError`], serialized as
+/// the JSON body's `error` field so clients can branch on it instead of
+/// string-matching `message`
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    LaneNotLoaded,
+    InvalidQuery,
+    PirError,
+    ConfigMismatch,
+    ParamsVersionMismatch,
+    ParamsIdMismatch,
+    CrsMetadataNotFound,
+    UbtRootMismatch,
+    UbtRootUnavailable,
+    CrsSignatureInvalid,
+    Io,
+    Json,
+    Internal,
+    DeltaVersionTooNew,
+    DeltaVersionTooOld,
+    Unauthorized,
+    BatchTooLarge,
+    ClientParamsVersionMismatch,
+    ClientConfigHashMismatch,
+}
+
+/// JSON body shape for every [`ServerError`] response
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: ErrorCode,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lane: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<serde_json::Value>,
+}
+
 #[derive(Error, Debug)]
 pub enum ServerError {
     #[error("Lane not loaded: {0}")]
@@ -29,9 +73,30 @@ pub enum ServerError {
         lane: String,
     },
 
+    #[error("PIR params id mismatch for {lane} lane: CRS was generated with params_id {crs_params_id}, but server expects {expected_params_id}. Same version, different cryptographic parameters (q/sigma/gadget/etc) -- regenerate CRS/DB with lane-builder.")]
+    ParamsIdMismatch {
+        crs_params_id: String,
+        expected_params_id: String,
+        lane: String,
+    },
+
     #[error("CRS metadata not found for {lane} lane at {path}. Regenerate with lane-builder >= 0.1.0.")]
     CrsMetadataNotFound { lane: String, path: String },
 
+    #[error("UBT root mismatch for {lane} lane at block {block_number}: expected {expected} (committed in CRS metadata), chain reports {actual}. Refusing to swap in this snapshot.")]
+    UbtRootMismatch {
+        lane: String,
+        block_number: u64,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("UBT root verification enabled but unavailable: {0}. Refusing to swap in this snapshot.")]
+    UbtRootUnavailable(String),
+
+    #[error("CRS signature invalid for {lane} lane: {reason}. Refusing to swap in this snapshot.")]
+    CrsSignatureInvalid { lane: String, reason: String },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -40,24 +105,210 @@ pub enum ServerError {
 
     #[error("Internal error: {0}")]
     Internal(String),
+
+    #[error("Range-delta format version {client_version} is newer than this server supports (max {server_max}); the server must be upgraded to sync with this client")]
+    DeltaVersionTooNew { client_version: u16, server_max: u16 },
+
+    #[error("Range-delta format version {client_version} is no longer supported by this server (min {server_min}); the client must resync from a fresh snapshot")]
+    DeltaVersionTooOld { client_version: u16, server_min: u16 },
+
+    #[error("Unauthorized: missing or invalid admin reload token")]
+    Unauthorized,
+
+    #[error("Batch too large: {requested} queries requested, server allows at most {max}")]
+    BatchTooLarge { requested: usize, max: usize },
+
+    #[error("Client PIR params version {client_version} is not in this server's supported range [{server_min}, {server_max}]. Refetch /info and /crs/:lane and retry.")]
+    ClientParamsVersionMismatch {
+        client_version: u16,
+        server_min: u16,
+        server_max: u16,
+    },
+
+    #[error("Client config hash {client_hash} does not match this server's loaded config hash {server_hash}. Refetch /info and /crs/:lane and retry.")]
+    ClientConfigHashMismatch { client_hash: String, server_hash: String },
+}
+
+impl ServerError {
+    fn code(&self) -> ErrorCode {
+        match self {
+            ServerError::LaneNotLoaded(_) => ErrorCode::LaneNotLoaded,
+            ServerError::InvalidQuery(_) => ErrorCode::InvalidQuery,
+            ServerError::PirError(_) => ErrorCode::PirError,
+            ServerError::ConfigMismatch { .. } => ErrorCode::ConfigMismatch,
+            ServerError::ParamsVersionMismatch { .. } => ErrorCode::ParamsVersionMismatch,
+            ServerError::ParamsIdMismatch { .. } => ErrorCode::ParamsIdMismatch,
+            ServerError::CrsMetadataNotFound { .. } => ErrorCode::CrsMetadataNotFound,
+            ServerError::UbtRootMismatch { .. } => ErrorCode::UbtRootMismatch,
+            ServerError::UbtRootUnavailable(_) => ErrorCode::UbtRootUnavailable,
+            ServerError::CrsSignatureInvalid { .. } => ErrorCode::CrsSignatureInvalid,
+            ServerError::Io(_) => ErrorCode::Io,
+            ServerError::Json(_) => ErrorCode::Json,
+            ServerError::Internal(_) => ErrorCode::Internal,
+            ServerError::DeltaVersionTooNew { .. } => ErrorCode::DeltaVersionTooNew,
+            ServerError::DeltaVersionTooOld { .. } => ErrorCode::DeltaVersionTooOld,
+            ServerError::Unauthorized => ErrorCode::Unauthorized,
+            ServerError::BatchTooLarge { .. } => ErrorCode::BatchTooLarge,
+            ServerError::ClientParamsVersionMismatch { .. } => ErrorCode::ClientParamsVersionMismatch,
+            ServerError::ClientConfigHashMismatch { .. } => ErrorCode::ClientConfigHashMismatch,
+        }
+    }
+
+    fn lane(&self) -> Option<String> {
+        match self {
+            ServerError::ParamsVersionMismatch { lane, .. }
+            | ServerError::ParamsIdMismatch { lane, .. }
+            | ServerError::CrsMetadataNotFound { lane, .. }
+            | ServerError::UbtRootMismatch { lane, .. }
+            | ServerError::CrsSignatureInvalid { lane, .. } => Some(lane.clone()),
+            _ => None,
+        }
+    }
+
+    /// Structured fields callers can branch on without parsing `message`
+    /// (e.g. a PIR client detecting a CRS version skew and re-downloading)
+    fn detail(&self) -> Option<serde_json::Value> {
+        match self {
+            ServerError::ParamsVersionMismatch {
+                crs_version,
+                expected_version,
+                ..
+            } => Some(serde_json::json!({
+                "crs_version": crs_version,
+                "expected_version": expected_version,
+            })),
+            ServerError::ParamsIdMismatch {
+                crs_params_id,
+                expected_params_id,
+                ..
+            } => Some(serde_json::json!({
+                "crs_params_id": crs_params_id,
+                "expected_params_id": expected_params_id,
+            })),
+            ServerError::UbtRootMismatch {
+                block_number,
+                expected,
+                actual,
+                ..
+            } => Some(serde_json::json!({
+                "block_number": block_number,
+                "expected": expected,
+                "actual": actual,
+            })),
+            ServerError::ConfigMismatch {
+                field,
+                config_value,
+                actual_value,
+            } => Some(serde_json::json!({
+                "field": field,
+                "config_value": config_value,
+                "actual_value": actual_value,
+            })),
+            ServerError::DeltaVersionTooNew { client_version, server_max } => Some(serde_json::json!({
+                "client_version": client_version,
+                "server_max": server_max,
+            })),
+            ServerError::DeltaVersionTooOld { client_version, server_min } => Some(serde_json::json!({
+                "client_version": client_version,
+                "server_min": server_min,
+            })),
+            ServerError::BatchTooLarge { requested, max } => Some(serde_json::json!({
+                "requested": requested,
+                "max": max,
+            })),
+            ServerError::ClientParamsVersionMismatch {
+                client_version,
+                server_min,
+                server_max,
+            } => Some(serde_json::json!({
+                "client_version": client_version,
+                "server_min": server_min,
+                "server_max": server_max,
+            })),
+            ServerError::ClientConfigHashMismatch { client_hash, server_hash } => Some(serde_json::json!({
+                "client_hash": client_hash,
+                "server_hash": server_hash,
+            })),
+            _ => None,
+        }
+    }
 }
 
 impl IntoResponse for ServerError {
     fn into_response(self) -> Response {
-        let (status, message) = match &self {
-            ServerError::LaneNotLoaded(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
-            ServerError::InvalidQuery(_) => (StatusCode::BAD_REQUEST, self.to_string()),
-            ServerError::PirError(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
-            ServerError::ConfigMismatch { .. } => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
-            ServerError::ParamsVersionMismatch { .. } => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
-            ServerError::CrsMetadataNotFound { .. } => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
-            ServerError::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
-            ServerError::Json(_) => (StatusCode::BAD_REQUEST, self.to_string()),
-            ServerError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        let status = match &self {
+            ServerError::LaneNotLoaded(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ServerError::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+            ServerError::PirError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ServerError::ConfigMismatch { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            ServerError::ParamsVersionMismatch { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            ServerError::ParamsIdMismatch { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            ServerError::CrsMetadataNotFound { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            ServerError::UbtRootMismatch { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            ServerError::UbtRootUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ServerError::CrsSignatureInvalid { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            ServerError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ServerError::Json(_) => StatusCode::BAD_REQUEST,
+            ServerError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ServerError::DeltaVersionTooNew { .. } => StatusCode::UPGRADE_REQUIRED,
+            ServerError::DeltaVersionTooOld { .. } => StatusCode::CONFLICT,
+            ServerError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ServerError::BatchTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            ServerError::ClientParamsVersionMismatch { .. } => StatusCode::CONFLICT,
+            ServerError::ClientConfigHashMismatch { .. } => StatusCode::CONFLICT,
+        };
+
+        let body = ErrorBody {
+            error: self.code(),
+            message: self.to_string(),
+            lane: self.lane(),
+            detail: self.detail(),
         };
 
-        (status, message).into_response()
+        let mut response = (status, Json(body)).into_response();
+        if matches!(self, ServerError::LaneNotLoaded(_)) {
+            response.headers_mut().insert(
+                header::RETRY_AFTER,
+                HeaderValue::from_str(&LANE_NOT_LOADED_RETRY_AFTER_SECS.to_string())
+                    .expect("digit-only string is a valid header value"),
+            );
+        }
+        response
     }
 }
 
 pub type Result<T> = std::result::Result<T, ServerError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lane_not_loaded_sets_retry_after() {
+        let response = ServerError::LaneNotLoaded("hot".to_string()).into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers().get(header::RETRY_AFTER).unwrap(),
+            "5"
+        );
+    }
+
+    #[test]
+    fn test_other_errors_have_no_retry_after() {
+        let response = ServerError::InvalidQuery("bad lane".to_string()).into_response();
+        assert!(response.headers().get(header::RETRY_AFTER).is_none());
+    }
+
+    #[test]
+    fn test_params_version_mismatch_carries_structured_detail() {
+        let err = ServerError::ParamsVersionMismatch {
+            crs_version: 1,
+            expected_version: 2,
+            lane: "cold".to_string(),
+        };
+        assert_eq!(err.lane(), Some("cold".to_string()));
+        let detail = err.detail().unwrap();
+        assert_eq!(detail["crs_version"], 1);
+        assert_eq!(detail["expected_version"], 2);
+    }
+}
@@ -5,12 +5,33 @@
 
 pub mod server;
 pub mod state;
+pub mod kv_backend;
+pub mod mmap_hints;
+pub mod ubt_verify;
 pub mod routes;
 pub mod error;
 pub mod metrics;
+pub mod rate_limit;
+pub mod admin_auth;
+pub mod params_negotiation;
+pub mod compression;
+pub mod byteranges;
+pub mod delta_negotiation;
+pub mod delta_stream;
+pub mod delta_cache;
+pub mod cluster;
 
 pub use server::{TwoLaneServer, ServerBuilder};
-pub use state::{ServerState, DbSnapshot, SharedState, LaneStats, ReloadResult, LaneData, LaneDatabase, create_shared_state};
+pub use state::{ServerState, DbSnapshot, SharedState, LaneStats, ReloadResult, IncrementalReloadResult, LaneData, LaneBackend, create_shared_state};
+pub use kv_backend::KvLaneDatabase;
+pub use ubt_verify::UbtRootClient;
 pub use routes::{create_router, create_router_with_metrics, create_public_router, create_admin_router};
-pub use error::ServerError;
+pub use error::{ErrorCode, ServerError};
 pub use metrics::init_prometheus_recorder;
+pub use rate_limit::{RateLimitConfig, RateLimiter};
+pub use compression::{negotiate as negotiate_content_encoding, ContentEncoding};
+pub use byteranges::{parse_ranges, render_multipart_byteranges, ByteRange, RangeError};
+pub use delta_negotiation::negotiate as negotiate_delta_version;
+pub use delta_stream::{delta_event_stream, DeltaBroadcaster, DeltaEvent};
+pub use delta_cache::{compute_range_delta_etag, if_none_match_satisfied, if_range_satisfied};
+pub use cluster::{ClusterRegistry, RegisterRequest, RegisterResponse};
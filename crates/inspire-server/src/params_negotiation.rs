@@ -0,0 +1,172 @@
+//! Client/server PIR-parameter negotiation middleware for `/query/*`
+//!
+//! `ServerInfo` already exposes `pir_params_version`/`pir_params_id`/
+//! `config_hash`, but nothing stopped a client built against different
+//! parameters from querying anyway -- the PIR math still "succeeds" and
+//! silently hands back garbage plaintext. [`negotiate_params_version`] is
+//! the pure decision function [`params_negotiation_middleware`] calls with
+//! the client's advertised `X-PIR-Params-Version` (required) and
+//! `X-PIR-Config-Hash` (optional) headers, rejecting a mismatch with a
+//! structured `409 Conflict` carrying the server's expected values so the
+//! client can refetch `/info`/`/crs/:lane` and retry.
+//!
+//! The compatibility window is `[PIR_PARAMS_MIN_SUPPORTED_VERSION,
+//! PIR_PARAMS_VERSION]` (the same range already advertised on
+//! `ServerInfo`), so a server mid-rollout across two param generations
+//! accepts either version without any change here.
+
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderMap, Request};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use inspire_core::{PIR_PARAMS_MIN_SUPPORTED_VERSION, PIR_PARAMS_VERSION};
+
+use crate::error::{Result, ServerError};
+use crate::state::SharedState;
+
+const PARAMS_VERSION_HEADER: &str = "x-pir-params-version";
+const CONFIG_HASH_HEADER: &str = "x-pir-config-hash";
+
+/// Check `client_version` against this server's supported
+/// `[server_min, server_max]` window (inclusive)
+pub fn negotiate_params_version(client_version: u16, server_min: u16, server_max: u16) -> Result<()> {
+    if client_version < server_min || client_version > server_max {
+        return Err(ServerError::ClientParamsVersionMismatch {
+            client_version,
+            server_min,
+            server_max,
+        });
+    }
+    Ok(())
+}
+
+/// Check an optional `client_hash` against `server_hash`. `None` always
+/// passes -- `X-PIR-Config-Hash` is advisory, not required.
+pub fn negotiate_config_hash(client_hash: Option<&str>, server_hash: &str) -> Result<()> {
+    match client_hash {
+        Some(hash) if hash != server_hash => Err(ServerError::ClientConfigHashMismatch {
+            client_hash: hash.to_string(),
+            server_hash: server_hash.to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Extract and parse the required `X-PIR-Params-Version` header
+fn parse_params_version(headers: &HeaderMap) -> Result<u16> {
+    let value = headers
+        .get(PARAMS_VERSION_HEADER)
+        .ok_or_else(|| ServerError::InvalidQuery(format!("missing required {PARAMS_VERSION_HEADER} header")))?;
+
+    value
+        .to_str()
+        .ok()
+        .and_then(|v| v.parse::<u16>().ok())
+        .ok_or_else(|| ServerError::InvalidQuery(format!("{PARAMS_VERSION_HEADER} header must be a u16")))
+}
+
+/// Axum middleware gating `/query/*` routes behind [`negotiate_params_version`]
+/// and, when sent, [`negotiate_config_hash`]
+pub async fn params_negotiation_middleware(
+    State(state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let headers = request.headers();
+
+    let result = parse_params_version(headers).and_then(|client_version| {
+        negotiate_params_version(client_version, PIR_PARAMS_MIN_SUPPORTED_VERSION, PIR_PARAMS_VERSION)
+    });
+    if let Err(e) = result {
+        return e.into_response();
+    }
+
+    if let Some(client_hash) = headers.get(CONFIG_HASH_HEADER).and_then(|v| v.to_str().ok()) {
+        let server_hash = state.config.config_hash.clone().unwrap_or_else(|| state.config.compute_hash());
+        if let Err(e) = negotiate_config_hash(Some(client_hash), &server_hash) {
+            return e.into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_params_version_accepts_in_range() {
+        assert!(negotiate_params_version(2, 1, 3).is_ok());
+    }
+
+    #[test]
+    fn test_negotiate_params_version_rejects_too_new() {
+        let err = negotiate_params_version(5, 1, 3).unwrap_err();
+        assert!(matches!(
+            err,
+            ServerError::ClientParamsVersionMismatch {
+                client_version: 5,
+                server_min: 1,
+                server_max: 3
+            }
+        ));
+    }
+
+    #[test]
+    fn test_negotiate_params_version_rejects_too_old() {
+        let err = negotiate_params_version(0, 1, 3).unwrap_err();
+        assert!(matches!(
+            err,
+            ServerError::ClientParamsVersionMismatch {
+                client_version: 0,
+                server_min: 1,
+                server_max: 3
+            }
+        ));
+    }
+
+    #[test]
+    fn test_negotiate_params_version_accepts_boundaries() {
+        assert!(negotiate_params_version(1, 1, 3).is_ok());
+        assert!(negotiate_params_version(3, 1, 3).is_ok());
+    }
+
+    #[test]
+    fn test_negotiate_config_hash_accepts_missing_header() {
+        assert!(negotiate_config_hash(None, "abc123").is_ok());
+    }
+
+    #[test]
+    fn test_negotiate_config_hash_accepts_matching_hash() {
+        assert!(negotiate_config_hash(Some("abc123"), "abc123").is_ok());
+    }
+
+    #[test]
+    fn test_negotiate_config_hash_rejects_mismatched_hash() {
+        let err = negotiate_config_hash(Some("wrong"), "abc123").unwrap_err();
+        assert!(matches!(err, ServerError::ClientConfigHashMismatch { .. }));
+    }
+
+    #[test]
+    fn test_parse_params_version_rejects_missing_header() {
+        let headers = HeaderMap::new();
+        assert!(parse_params_version(&headers).is_err());
+    }
+
+    #[test]
+    fn test_parse_params_version_rejects_non_numeric_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(PARAMS_VERSION_HEADER, "not-a-number".parse().unwrap());
+        assert!(parse_params_version(&headers).is_err());
+    }
+
+    #[test]
+    fn test_parse_params_version_accepts_valid_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(PARAMS_VERSION_HEADER, "2".parse().unwrap());
+        assert_eq!(parse_params_version(&headers).unwrap(), 2);
+    }
+}
@@ -0,0 +1,320 @@
+//! Per-client token-bucket rate limiting for the query and admin endpoints
+//!
+//! A PIR server is a natural DoS target: every `/query/{lane}` request
+//! triggers expensive homomorphic work regardless of how small the request
+//! is, and an unauthenticated admin listener is an easy target for anyone
+//! who can reach it. This module buckets requests by client IP and rejects
+//! requests that exceed a configurable token-bucket budget with `429 Too
+//! Many Requests`, while stamping `X-RateLimit-*` headers on every response
+//! so well-behaved clients can self-pace.
+//!
+//! [`rate_limit_middleware`] is wired into [`SharedState`] for the query
+//! router; [`standalone_rate_limit_middleware`] takes a bare `Arc<RateLimiter>`
+//! as router state for listeners (like the admin router) that don't share
+//! that state, so both get independent, burst-tolerant per-client limits
+//! instead of one gate starving the other.
+
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::extract::{ConnectInfo, State};
+use axum::http::{HeaderValue, Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use dashmap::DashMap;
+
+use crate::state::SharedState;
+
+/// Configuration for the per-client token bucket
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitConfig {
+    /// Maximum tokens (and therefore maximum burst) a single client can hold
+    pub capacity: f64,
+    /// Tokens refilled per second, up to `capacity`
+    pub refill_per_sec: f64,
+    /// A client's bucket is dropped once it's been idle this long, so
+    /// `evict_idle` bounds the map to currently-active clients instead of
+    /// growing forever
+    pub idle_evict_after: Duration,
+    /// Hard cap on the number of distinct client buckets tracked at once.
+    /// Bounds memory against an attacker cycling through source addresses
+    /// faster than `idle_evict_after` would naturally reap them: once hit,
+    /// [`RateLimiter::check`] evicts the single least-recently-refilled
+    /// bucket before inserting a new one.
+    pub max_clients: usize,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 10.0,
+            refill_per_sec: 1.0,
+            idle_evict_after: Duration::from_secs(300),
+            max_clients: 100_000,
+        }
+    }
+}
+
+/// A single client's token bucket
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64) -> Self {
+        Self {
+            tokens: capacity,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Refill based on elapsed time, then try to take one token
+    fn take(&mut self, config: &RateLimitConfig) -> bool {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.refill_per_sec).min(config.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Seconds until at least one token is available
+    fn retry_after_secs(&self, config: &RateLimitConfig) -> u64 {
+        if self.tokens >= 1.0 || config.refill_per_sec <= 0.0 {
+            return 0;
+        }
+        ((1.0 - self.tokens) / config.refill_per_sec).ceil() as u64
+    }
+}
+
+/// Outcome of a rate-limit check, carrying enough to populate either a `429`
+/// rejection or the `X-RateLimit-*` headers on a successful response
+struct RateLimitDecision {
+    allowed: bool,
+    limit: u64,
+    remaining: u64,
+    reset_secs: u64,
+}
+
+/// Sharded per-client token-bucket limiter
+///
+/// Keyed by client IP via [`DashMap`], so concurrent requests from different
+/// clients don't contend on the same lock. Each entry is itself
+/// mutex-guarded since a single client's requests still need to serialize
+/// against their own bucket.
+pub struct RateLimiter {
+    buckets: DashMap<IpAddr, Mutex<TokenBucket>>,
+    config: RateLimitConfig,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            buckets: DashMap::new(),
+            config,
+        }
+    }
+
+    fn check(&self, key: IpAddr) -> RateLimitDecision {
+        if !self.buckets.contains_key(&key) && self.buckets.len() >= self.config.max_clients {
+            self.evict_oldest();
+        }
+
+        let entry = self
+            .buckets
+            .entry(key)
+            .or_insert_with(|| Mutex::new(TokenBucket::new(self.config.capacity)));
+        let mut bucket = entry.lock().expect("token bucket mutex poisoned");
+
+        let allowed = bucket.take(&self.config);
+        let reset_secs = bucket.retry_after_secs(&self.config);
+
+        RateLimitDecision {
+            allowed,
+            limit: self.config.capacity as u64,
+            remaining: bucket.tokens.floor().max(0.0) as u64,
+            reset_secs,
+        }
+    }
+
+    /// Evict the single bucket with the oldest `last_refill`, making room for
+    /// a new client once `max_clients` is reached
+    fn evict_oldest(&self) {
+        let oldest = self
+            .buckets
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .value()
+                    .lock()
+                    .ok()
+                    .map(|bucket| (*entry.key(), bucket.last_refill))
+            })
+            .min_by_key(|(_, last_refill)| *last_refill)
+            .map(|(key, _)| key);
+
+        if let Some(key) = oldest {
+            self.buckets.remove(&key);
+        }
+    }
+
+    /// Drop buckets that haven't been touched in `idle_evict_after`, so
+    /// long-running servers don't accumulate one entry per client forever
+    pub fn evict_idle(&self) {
+        let idle_since = self.config.idle_evict_after;
+        self.buckets.retain(|_, bucket| {
+            bucket
+                .lock()
+                .map(|b| b.last_refill.elapsed() < idle_since)
+                .unwrap_or(true)
+        });
+    }
+}
+
+fn header_value(n: u64) -> HeaderValue {
+    HeaderValue::from_str(&n.to_string()).expect("digit-only string is a valid header value")
+}
+
+/// Check `limiter` for `addr` and either reject with `429` + `Retry-After` or
+/// run `next` and stamp `X-RateLimit-*` headers on the response. Shared by
+/// every router-specific middleware wrapper below so the query and admin
+/// listeners enforce identical semantics against their own [`RateLimiter`].
+async fn apply_rate_limit(
+    limiter: &RateLimiter,
+    addr: std::net::SocketAddr,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let decision = limiter.check(addr.ip());
+
+    if !decision.allowed {
+        let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
+        let headers = response.headers_mut();
+        headers.insert(axum::http::header::RETRY_AFTER, header_value(decision.reset_secs));
+        headers.insert("x-ratelimit-limit", header_value(decision.limit));
+        headers.insert("x-ratelimit-remaining", header_value(0));
+        headers.insert("x-ratelimit-reset", header_value(decision.reset_secs));
+        return response;
+    }
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert("x-ratelimit-limit", header_value(decision.limit));
+    headers.insert("x-ratelimit-remaining", header_value(decision.remaining));
+    headers.insert("x-ratelimit-reset", header_value(decision.reset_secs));
+    response
+}
+
+/// Axum middleware enforcing [`RateLimiter`] on the query endpoints
+///
+/// Rejects exhausted clients with `429` + `Retry-After`; successful
+/// responses get `X-RateLimit-Limit`/`X-RateLimit-Remaining`/
+/// `X-RateLimit-Reset` so well-behaved clients can self-pace.
+pub async fn rate_limit_middleware(
+    State(state): State<SharedState>,
+    ConnectInfo(addr): ConnectInfo<std::net::SocketAddr>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    apply_rate_limit(&state.rate_limiter, addr, request, next).await
+}
+
+/// Axum middleware enforcing a standalone [`RateLimiter`] that isn't part of
+/// [`SharedState`] — for routers (e.g. the admin listener) whose state is
+/// just the limiter itself rather than the full query-serving state.
+pub async fn standalone_rate_limit_middleware(
+    State(limiter): State<Arc<RateLimiter>>,
+    ConnectInfo(addr): ConnectInfo<std::net::SocketAddr>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    apply_rate_limit(&limiter, addr, request, next).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> RateLimitConfig {
+        RateLimitConfig {
+            capacity: 3.0,
+            refill_per_sec: 1000.0,
+            idle_evict_after: Duration::from_secs(60),
+            max_clients: 100_000,
+        }
+    }
+
+    #[test]
+    fn test_bucket_exhausts_after_capacity() {
+        let limiter = RateLimiter::new(test_config());
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+
+        for _ in 0..3 {
+            assert!(limiter.check(ip).allowed);
+        }
+        let rejected = limiter.check(ip);
+        assert!(!rejected.allowed);
+        assert_eq!(rejected.remaining, 0);
+    }
+
+    #[test]
+    fn test_distinct_clients_have_independent_buckets() {
+        let limiter = RateLimiter::new(test_config());
+        let a: IpAddr = "127.0.0.1".parse().unwrap();
+        let b: IpAddr = "127.0.0.2".parse().unwrap();
+
+        for _ in 0..3 {
+            assert!(limiter.check(a).allowed);
+        }
+        assert!(!limiter.check(a).allowed);
+        assert!(limiter.check(b).allowed);
+    }
+
+    #[test]
+    fn test_evict_idle_drops_stale_buckets() {
+        let config = RateLimitConfig {
+            capacity: 1.0,
+            refill_per_sec: 1.0,
+            idle_evict_after: Duration::from_millis(0),
+            max_clients: 100_000,
+        };
+        let limiter = RateLimiter::new(config);
+        let ip: IpAddr = "127.0.0.1".parse().unwrap();
+
+        limiter.check(ip);
+        assert_eq!(limiter.buckets.len(), 1);
+        limiter.evict_idle();
+        assert_eq!(limiter.buckets.len(), 0);
+    }
+
+    #[test]
+    fn test_max_clients_evicts_oldest_bucket() {
+        let config = RateLimitConfig {
+            max_clients: 2,
+            ..test_config()
+        };
+        let limiter = RateLimiter::new(config);
+        let a: IpAddr = "127.0.0.1".parse().unwrap();
+        let b: IpAddr = "127.0.0.2".parse().unwrap();
+        let c: IpAddr = "127.0.0.3".parse().unwrap();
+
+        limiter.check(a);
+        std::thread::sleep(Duration::from_millis(5));
+        limiter.check(b);
+        assert_eq!(limiter.buckets.len(), 2);
+
+        limiter.check(c);
+        assert_eq!(limiter.buckets.len(), 2);
+        assert!(!limiter.buckets.contains_key(&a));
+        assert!(limiter.buckets.contains_key(&b));
+        assert!(limiter.buckets.contains_key(&c));
+    }
+}
@@ -1,19 +1,30 @@
 //! HTTP routes for the PIR server
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use axum::{
-    extract::{Path, State},
-    http::header,
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware,
     response::{IntoResponse, Json, Response},
     routing::{get, post},
     Router,
 };
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 
 use inspire_core::Lane;
 use inspire_pir::{params::ShardConfig, ClientQuery, SeededClientQuery, ServerResponse};
 
+use crate::admin_auth::admin_auth_middleware;
+use crate::byteranges::{parse_ranges, RangeError};
+use crate::compression::{compress_stream, negotiate};
 use crate::error::{Result, ServerError};
-use crate::state::{ReloadResult, SharedState};
+use crate::params_negotiation::params_negotiation_middleware;
+use crate::rate_limit::rate_limit_middleware;
+use crate::state::{DbSnapshot, ReloadResult, SharedState};
 use crate::metrics;
 
 /// Health/readiness check response
@@ -54,13 +65,32 @@ pub struct QueryResponse {
 #[derive(Serialize)]
 pub struct ServerInfo {
     pub version: String,
+    /// Current PIR params version this server's CRS/databases were built
+    /// with.
     pub pir_params_version: u16,
+    /// Inclusive `[pir_params_min_supported, pir_params_max_supported]`
+    /// compatibility window: any client whose compiled-in
+    /// `PIR_PARAMS_VERSION` falls in this range can still query this
+    /// server, even if it doesn't exactly match `pir_params_version`.
+    pub pir_params_min_supported: u16,
+    pub pir_params_max_supported: u16,
+    /// Content-derived fingerprint of the server's loaded `PirParams` --
+    /// see [`inspire_core::PirParams::params_id`]. Two servers/clients can
+    /// agree on `pir_params_version` yet differ in `q`/`sigma`/gadget
+    /// fields; this catches that where the version number alone can't.
+    pub pir_params_id: String,
     pub config_hash: String,
     pub manifest_block: Option<u64>,
     pub hot_entries: u64,
     pub cold_entries: u64,
     pub hot_contracts: usize,
     pub block_number: Option<u64>,
+    /// This node's role -- standalone, ingest, or query. See
+    /// [`inspire_core::NodeMode`].
+    pub node_mode: inspire_core::NodeMode,
+    /// Number of ingest nodes currently registered, for a `Query`-mode
+    /// node; always 0 in other modes.
+    pub cluster_size: usize,
 }
 
 /// CRS response
@@ -70,6 +100,69 @@ pub struct CrsResponse {
     pub lane: Lane,
     pub entry_count: u64,
     pub shard_config: ShardConfig,
+    /// Strong ETag for this CRS (also emitted as the `ETag` response
+    /// header). Bumps on every `/admin/reload`, even a no-op one -- see
+    /// [`crate::state::ServerState::generation`].
+    pub etag: String,
+}
+
+/// Compute a strong ETag for a lane's CRS: a blake3 hash of the CRS JSON
+/// bytes plus the server's current reload generation, quoted per RFC 7232.
+/// Including the generation means a reload always invalidates client-side
+/// caches, even one that reloads byte-identical CRS data.
+fn compute_crs_etag(crs_json: &str, generation: u64) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(crs_json.as_bytes());
+    hasher.update(&generation.to_le_bytes());
+    format!("\"{}\"", hex::encode(hasher.finalize().as_bytes()))
+}
+
+/// Honors a single-range `Range: bytes=...` request against an in-memory
+/// `body`, mirroring pict-rs's range handling so large bodies (CRS
+/// payloads, binary query responses) are resumable on flaky links.
+///
+/// Returns `None` when no `Range` header was sent -- the caller should
+/// serve the full body itself (while still advertising `Accept-Ranges:
+/// bytes`). Returns `Some(206 Partial Content)` with the sliced body and a
+/// `Content-Range` header for a satisfiable single range, or `Some(416
+/// Range Not Satisfiable)` otherwise. Multi-range requests are rejected as
+/// unsatisfiable (`max_parts: 1` in [`parse_ranges`]) rather than honored
+/// via `multipart/byteranges` -- none of these endpoints need more than
+/// one range per request today.
+fn ranged_response(headers: &HeaderMap, body: Vec<u8>, content_type: &'static str) -> Option<Response> {
+    let range_header = headers.get(header::RANGE)?.to_str().ok()?;
+    let total_len = body.len() as u64;
+
+    match parse_ranges(range_header, total_len, 1) {
+        Ok(ranges) => {
+            let range = ranges[0];
+            let slice = body[range.start as usize..=range.end as usize].to_vec();
+            let content_length = slice.len();
+            Some(
+                (
+                    StatusCode::PARTIAL_CONTENT,
+                    [
+                        (header::CONTENT_TYPE, content_type.to_string()),
+                        (header::ACCEPT_RANGES, "bytes".to_string()),
+                        (
+                            header::CONTENT_RANGE,
+                            format!("bytes {}-{}/{}", range.start, range.end, total_len),
+                        ),
+                        (header::CONTENT_LENGTH, content_length.to_string()),
+                    ],
+                    slice,
+                )
+                    .into_response(),
+            )
+        }
+        Err(RangeError::Unsatisfiable) | Err(RangeError::TooManyParts) => Some(
+            (
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                [(header::CONTENT_RANGE, format!("bytes */{total_len}"))],
+            )
+                .into_response(),
+        ),
+    }
 }
 
 /// Health/readiness check endpoint
@@ -112,6 +205,9 @@ async fn info(State(state): State<SharedState>) -> Json<ServerInfo> {
     Json(ServerInfo {
         version: state.config.version.clone(),
         pir_params_version: stats.pir_params_version,
+        pir_params_min_supported: inspire_core::PIR_PARAMS_MIN_SUPPORTED_VERSION,
+        pir_params_max_supported: inspire_core::PIR_PARAMS_VERSION,
+        pir_params_id: stats.pir_params_id.clone(),
         config_hash: state
             .config
             .config_hash
@@ -122,21 +218,86 @@ async fn info(State(state): State<SharedState>) -> Json<ServerInfo> {
         cold_entries: stats.cold_entries,
         hot_contracts: stats.hot_contracts,
         block_number: stats.block_number,
+        node_mode: state.config.node_mode,
+        cluster_size: if state.config.node_mode == inspire_core::NodeMode::Query {
+            state.cluster.len()
+        } else {
+            0
+        },
     })
 }
 
 /// Get CRS for a specific lane
-async fn get_crs(State(state): State<SharedState>, Path(lane): Path<String>) -> Result<Json<CrsResponse>> {
+///
+/// The CRS only changes on reload, so this honors conditional GET: a
+/// matching `If-None-Match` gets a `304 Not Modified` with no body instead
+/// of re-serializing and re-sending the (large) CRS.
+///
+/// Also honors `Range: bytes=...` (advertised via `Accept-Ranges: bytes`)
+/// for clients that only want to resume or prefetch part of a large CRS --
+/// a ranged request gets the raw sliced CRS bytes directly, bypassing the
+/// [`CrsResponse`] JSON envelope so the client's byte offsets line up with
+/// the CRS content itself rather than with where it happens to land inside
+/// the envelope.
+async fn get_crs(
+    State(state): State<SharedState>,
+    Path(lane): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response> {
     let lane = parse_lane(&lane)?;
     let snapshot = state.load_snapshot();
     let lane_data = snapshot.get_lane(lane)?;
 
-    Ok(Json(CrsResponse {
-        crs: lane_data.crs_json()?,
+    let crs_json = lane_data.crs_json()?;
+    let etag = compute_crs_etag(&crs_json, state.generation());
+
+    if headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        == Some(etag.as_str())
+    {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    if let Some(response) = ranged_response(&headers, crs_json.clone().into_bytes(), "application/json") {
+        return Ok(response);
+    }
+
+    let body = CrsResponse {
+        crs: crs_json,
         lane,
         entry_count: lane_data.entry_count,
         shard_config: lane_data.shard_config(),
-    }))
+        etag: etag.clone(),
+    };
+
+    Ok((
+        StatusCode::OK,
+        [(header::ETAG, etag), (header::ACCEPT_RANGES, "bytes".to_string())],
+        Json(body),
+    )
+        .into_response())
+}
+
+/// Record per-lane request counters/latency around a query body, so every
+/// query-processing route (not just the plain JSON one) feeds the same
+/// `pir_requests_total`/`pir_request_duration_seconds` series operators
+/// alert on.
+fn record_query_outcome<T>(lane_str: &str, start: std::time::Instant, result: &Result<T>) {
+    metrics::record_pir_request_end(lane_str);
+    let duration = start.elapsed();
+
+    match result {
+        Ok(_) => metrics::record_pir_request(lane_str, metrics::OUTCOME_OK, duration),
+        Err(e) => {
+            let outcome = if matches!(e, ServerError::InvalidQuery(_)) {
+                metrics::OUTCOME_CLIENT_ERROR
+            } else {
+                metrics::OUTCOME_SERVER_ERROR
+            };
+            metrics::record_pir_request(lane_str, outcome, duration);
+        }
+    }
 }
 
 /// Process a PIR query (full ciphertext)
@@ -150,27 +311,10 @@ async fn query(
     let start = std::time::Instant::now();
     metrics::record_pir_request_start(&lane_str);
 
-    let snapshot = state.load_snapshot_full();
-    let result = snapshot.process_query(lane, &req.query);
-
-    metrics::record_pir_request_end(&lane_str);
-    let duration = start.elapsed();
+    let result = state.resolve_query(lane, &req.query).await;
+    record_query_outcome(&lane_str, start, &result);
 
-    match result {
-        Ok(response) => {
-            metrics::record_pir_request(&lane_str, metrics::OUTCOME_OK, duration);
-            Ok(Json(QueryResponse { response, lane }))
-        }
-        Err(e) => {
-            let outcome = if matches!(e, ServerError::InvalidQuery(_)) {
-                metrics::OUTCOME_CLIENT_ERROR
-            } else {
-                metrics::OUTCOME_SERVER_ERROR
-            };
-            metrics::record_pir_request(&lane_str, outcome, duration);
-            Err(e)
-        }
-    }
+    result.map(|response| Json(QueryResponse { response, lane }))
 }
 
 /// Process a seeded PIR query (~50% smaller, server expands)
@@ -180,14 +324,17 @@ async fn query_seeded(
     Json(req): Json<SeededQueryRequest>,
 ) -> Result<Json<QueryResponse>> {
     let lane = parse_lane(&lane)?;
+    let lane_str = lane_to_string(lane);
+    let start = std::time::Instant::now();
+    metrics::record_pir_request_start(&lane_str);
 
     // Expand seeded query to full query (regenerate `a` polynomials from seeds)
     let expanded_query = req.query.expand();
 
-    let snapshot = state.load_snapshot_full();
-    let response = snapshot.process_query(lane, &expanded_query)?;
+    let result = state.resolve_query(lane, &expanded_query).await;
+    record_query_outcome(&lane_str, start, &result);
 
-    Ok(Json(QueryResponse { response, lane }))
+    result.map(|response| Json(QueryResponse { response, lane }))
 }
 
 /// Process a seeded PIR query with binary response (~75% smaller total)
@@ -197,21 +344,32 @@ async fn query_seeded(
 async fn query_seeded_binary(
     State(state): State<SharedState>,
     Path(lane): Path<String>,
+    headers: HeaderMap,
     Json(req): Json<SeededQueryRequest>,
 ) -> Result<Response> {
     let lane = parse_lane(&lane)?;
+    let lane_str = lane_to_string(lane);
+    let start = std::time::Instant::now();
+    metrics::record_pir_request_start(&lane_str);
 
     let expanded_query = req.query.expand();
 
-    let snapshot = state.load_snapshot_full();
-    let response = snapshot.process_query(lane, &expanded_query)?;
+    let result = state.resolve_query(lane, &expanded_query).await;
+    record_query_outcome(&lane_str, start, &result);
 
-    let binary = response
+    let binary = result?
         .to_binary()
         .map_err(|e| ServerError::Internal(e.to_string()))?;
 
+    if let Some(response) = ranged_response(&headers, binary.clone(), "application/octet-stream") {
+        return Ok(response);
+    }
+
     Ok((
-        [(header::CONTENT_TYPE, "application/octet-stream")],
+        [
+            (header::CONTENT_TYPE, "application/octet-stream"),
+            (header::ACCEPT_RANGES, "bytes"),
+        ],
         binary,
     )
         .into_response())
@@ -221,24 +379,246 @@ async fn query_seeded_binary(
 async fn query_binary(
     State(state): State<SharedState>,
     Path(lane): Path<String>,
+    headers: HeaderMap,
     Json(req): Json<QueryRequest>,
 ) -> Result<Response> {
     let lane = parse_lane(&lane)?;
+    let lane_str = lane_to_string(lane);
+    let start = std::time::Instant::now();
+    metrics::record_pir_request_start(&lane_str);
 
-    let snapshot = state.load_snapshot_full();
-    let response = snapshot.process_query(lane, &req.query)?;
+    let result = state.resolve_query(lane, &req.query).await;
+    record_query_outcome(&lane_str, start, &result);
 
-    let binary = response
+    let binary = result?
         .to_binary()
         .map_err(|e| ServerError::Internal(e.to_string()))?;
 
+    if let Some(response) = ranged_response(&headers, binary.clone(), "application/octet-stream") {
+        return Ok(response);
+    }
+
     Ok((
-        [(header::CONTENT_TYPE, "application/octet-stream")],
+        [
+            (header::CONTENT_TYPE, "application/octet-stream"),
+            (header::ACCEPT_RANGES, "bytes"),
+        ],
         binary,
     )
         .into_response())
 }
 
+/// Process a full PIR query sent and returned as raw bincode instead of the
+/// JSON-wrapped `ClientQuery` the other `/binary` endpoints still take --
+/// for realistic RLWE parameters the double-encoding (struct -> JSON string
+/// -> JSON object) on the request side costs real memory and CPU on top of
+/// the bincode response these endpoints already return. See
+/// `TwoLaneClient::query_full_binary_and_extract`.
+async fn query_full_binary(
+    State(state): State<SharedState>,
+    Path(lane): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response> {
+    let lane = parse_lane(&lane)?;
+    let lane_str = lane_to_string(lane);
+
+    let query: ClientQuery = bincode::deserialize(&body)
+        .map_err(|e| ServerError::InvalidQuery(format!("invalid bincode query body: {e}")))?;
+
+    let start = std::time::Instant::now();
+    metrics::record_pir_request_start(&lane_str);
+
+    let result = state.resolve_query(lane, &query).await;
+    record_query_outcome(&lane_str, start, &result);
+
+    let binary = result?
+        .to_binary()
+        .map_err(|e| ServerError::Internal(e.to_string()))?;
+
+    if let Some(response) = ranged_response(&headers, binary.clone(), "application/octet-stream") {
+        return Ok(response);
+    }
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream"),
+            (header::ACCEPT_RANGES, "bytes"),
+        ],
+        binary,
+    )
+        .into_response())
+}
+
+/// Batch PIR query request (full ciphertext queries)
+#[derive(Deserialize)]
+pub struct BatchQueryRequest {
+    pub queries: Vec<ClientQuery>,
+}
+
+/// Batch seeded PIR query request
+#[derive(Deserialize)]
+pub struct SeededBatchQueryRequest {
+    pub queries: Vec<SeededClientQuery>,
+}
+
+/// Frame a single query's binary response as `[4-byte big-endian length][bytes]`
+fn frame_response(bytes: Vec<u8>) -> Bytes {
+    let mut framed = Vec::with_capacity(4 + bytes.len());
+    framed.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    framed.extend_from_slice(&bytes);
+    Bytes::from(framed)
+}
+
+/// Number of queries a single batch request processes concurrently via
+/// `tokio::task::spawn_blocking`. Bounds how many PIR queries (each a
+/// multi-millisecond lattice operation) run at once per request, so one huge
+/// batch can't starve the blocking thread pool other requests share;
+/// `buffered` below keeps frames flushed in request order regardless of
+/// which worker finishes first.
+const BATCH_WORKER_CONCURRENCY: usize = 8;
+
+/// Reject a batch request outright if it exceeds the configured
+/// `max_batch_size`, rather than accepting it and letting it exhaust the
+/// blocking thread pool or the snapshot's memory.
+fn check_batch_size(requested: usize, max: usize) -> Result<()> {
+    if requested > max {
+        return Err(ServerError::BatchTooLarge { requested, max });
+    }
+    Ok(())
+}
+
+/// Stream a batch of queries as length-framed binary responses
+///
+/// Each frame is `[4-byte big-endian length][ServerResponse::to_binary()
+/// bytes]`. Up to [`BATCH_WORKER_CONCURRENCY`] queries run at once on the
+/// blocking thread pool, but `buffered` still flushes frames in request
+/// order, so the server's working set stays bounded regardless of batch size
+/// while still amortizing per-query latency across workers. Each query's
+/// outcome feeds the same `pir_requests_total`/`pir_request_duration_seconds`
+/// series as the single-query routes (see [`record_query_outcome`]).
+fn stream_batch_responses(
+    snapshot: Arc<DbSnapshot>,
+    lane: Lane,
+    lane_str: String,
+    queries: Vec<ClientQuery>,
+) -> impl futures::Stream<Item = std::result::Result<Bytes, std::io::Error>> {
+    futures::stream::iter(queries.into_iter())
+        .map(move |query| {
+            let snapshot = snapshot.clone();
+            let lane_str = lane_str.clone();
+            async move {
+                let start = std::time::Instant::now();
+                metrics::record_pir_request_start(&lane_str);
+
+                let result = tokio::task::spawn_blocking(move || snapshot.process_query(lane, &query))
+                    .await
+                    .unwrap_or_else(|e| Err(ServerError::Internal(format!("batch worker panicked: {e}"))));
+                record_query_outcome(&lane_str, start, &result);
+
+                result
+                    .and_then(|response| {
+                        response
+                            .to_binary()
+                            .map_err(|e| ServerError::Internal(e.to_string()))
+                    })
+                    .map(frame_response)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+            }
+        })
+        .buffered(BATCH_WORKER_CONCURRENCY)
+}
+
+/// Process a batch of full PIR queries
+///
+/// Streams each query's framed `ServerResponse` back as it's computed
+/// instead of buffering the whole batch, so one large request can't blow up
+/// server memory. See [`stream_batch_responses`] for the framing and worker
+/// concurrency, and [`check_batch_size`] for the `413` cutoff.
+///
+/// Honors `Accept-Encoding: gzip`/`zstd` (see [`crate::compression`]),
+/// compressing the stream as it's emitted rather than buffering it first;
+/// batch responses have no `Range:` support, so negotiation never has to
+/// fall back to identity for that reason.
+async fn query_batch(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(lane): Path<String>,
+    Json(req): Json<BatchQueryRequest>,
+) -> Result<Response> {
+    let lane = parse_lane(&lane)?;
+    check_batch_size(req.queries.len(), state.config.max_batch_size)?;
+    let lane_str = lane_to_string(lane);
+    let snapshot = state.load_snapshot_full();
+
+    let stream = stream_batch_responses(snapshot, lane, lane_str, req.queries);
+    Ok(compressed_octet_stream_response(&headers, stream))
+}
+
+/// Process a batch of seeded PIR queries, streaming framed responses
+///
+/// Seeds are expanded up front (matching [`query_seeded`]'s single-query
+/// behavior) since expansion is cheap relative to the PIR response each
+/// query produces; it's the responses that are streamed incrementally and,
+/// per [`query_batch`], optionally compressed in flight.
+async fn query_batch_seeded(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(lane): Path<String>,
+    Json(req): Json<SeededBatchQueryRequest>,
+) -> Result<Response> {
+    let lane = parse_lane(&lane)?;
+    check_batch_size(req.queries.len(), state.config.max_batch_size)?;
+    let lane_str = lane_to_string(lane);
+    let snapshot = state.load_snapshot_full();
+
+    let expanded: Vec<ClientQuery> = req.queries.into_iter().map(|q| q.expand()).collect();
+    let stream = stream_batch_responses(snapshot, lane, lane_str, expanded);
+    Ok(compressed_octet_stream_response(&headers, stream))
+}
+
+/// Build an `application/octet-stream` response from a byte stream, negotiating
+/// `Content-Encoding` from the request's `Accept-Encoding` header
+///
+/// These batch endpoints never serve partial content, so `has_range` is
+/// always `false` here; call sites that can receive a `Range:` header (e.g.
+/// a future bucket-index/range-delta sync endpoint) must pass the range
+/// flag through so ranged requests stay pinned to uncompressed offsets.
+fn compressed_octet_stream_response(
+    headers: &HeaderMap,
+    stream: impl futures::Stream<Item = std::result::Result<Bytes, std::io::Error>> + Send + 'static,
+) -> Response {
+    let encoding = negotiate(headers, false);
+    let content_encoding = encoding.header_value();
+    let body = Body::from_stream(compress_stream(encoding, stream));
+
+    let mut response = (
+        [(header::CONTENT_TYPE, "application/octet-stream")],
+        body,
+    )
+        .into_response();
+
+    if let Some(value) = content_encoding {
+        response.headers_mut().insert(header::CONTENT_ENCODING, value);
+    }
+
+    response
+}
+
+/// Register an ingest node with this query node's [`crate::cluster::ClusterRegistry`]
+///
+/// Meant to be called by ingest nodes on startup and after every reload (see
+/// `inspire_core::NodeMode::Ingest`), not by end clients -- operators running
+/// a split ingest/query deployment should firewall this route the same way
+/// they would `/admin/*`, even though it's served off the public listener
+/// for now so a query node doesn't need a third listener just for this.
+async fn cluster_register(
+    State(state): State<SharedState>,
+    Json(req): Json<crate::cluster::RegisterRequest>,
+) -> Json<crate::cluster::RegisterResponse> {
+    Json(state.cluster.register(req))
+}
+
 /// Reload lanes from disk (admin endpoint)
 ///
 /// Atomically swaps in a new snapshot without blocking ongoing queries.
@@ -247,6 +627,40 @@ async fn admin_reload(State(state): State<SharedState>) -> Result<Json<ReloadRes
     Ok(Json(result))
 }
 
+/// Query parameters for the long-poll reload endpoint
+#[derive(Deserialize)]
+struct ReloadPollParams {
+    /// Causality token from a previous [`ReloadResult`]; the call blocks
+    /// until the snapshot advances past it.
+    after: String,
+    #[serde(default = "default_poll_timeout_ms")]
+    timeout_ms: u64,
+}
+
+fn default_poll_timeout_ms() -> u64 {
+    30_000
+}
+
+/// Long-poll for a reload that advances past a causality token (admin endpoint)
+///
+/// Blocks server-side until the current snapshot advances past `after` or
+/// `timeout_ms` elapses, returning the new [`ReloadResult`] (200) or
+/// nothing (204) on timeout. Lets operators and test harnesses await a
+/// database swap (e.g. after submitting a delta) instead of busy-polling
+/// `/admin/reload`/`/health` in a loop.
+async fn admin_reload_poll(
+    State(state): State<SharedState>,
+    Query(params): Query<ReloadPollParams>,
+) -> Response {
+    let after = crate::state::decode_causality_token(&params.after);
+    let timeout = Duration::from_millis(params.timeout_ms);
+
+    match state.wait_for_reload(after, timeout).await {
+        Some(result) => Json(result).into_response(),
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
 /// Parse lane from URL path
 fn parse_lane(s: &str) -> Result<Lane> {
     match s.to_lowercase().as_str() {
@@ -264,6 +678,41 @@ fn lane_to_string(lane: Lane) -> String {
     }
 }
 
+/// Admin routes that trigger or observe a database reload, gated by
+/// [`admin_auth_middleware`] so a shared-secret bearer token is required
+/// whenever `TwoLaneConfig::admin_reload_token` is configured.
+///
+/// Split out from `/admin/health` for the same reason `query_router` is
+/// split out from the public routes: a liveness probe shouldn't need the
+/// admin token just to confirm the process is up.
+fn admin_reload_router(state: SharedState) -> Router {
+    Router::new()
+        .route("/admin/reload", post(admin_reload))
+        .route("/admin/reload/poll", get(admin_reload_poll))
+        .route_layer(middleware::from_fn_with_state(state, admin_auth_middleware))
+}
+
+/// Query routes, gated by PIR-params negotiation and the per-client-IP
+/// token-bucket limiter
+///
+/// Split out from the rest of the router so `/health`, `/crs/{lane}`, etc.
+/// stay exempt from rate limiting -- only the endpoints that trigger
+/// expensive homomorphic work are metered. [`params_negotiation_middleware`]
+/// runs outermost so a client on incompatible parameters is rejected with
+/// `409` before it can spend a rate-limit token.
+fn query_router(state: SharedState) -> Router {
+    Router::new()
+        .route("/query/:lane", post(query))
+        .route("/query/:lane/binary", post(query_binary))
+        .route("/query/:lane/full-binary", post(query_full_binary))
+        .route("/query/:lane/seeded", post(query_seeded))
+        .route("/query/:lane/seeded/binary", post(query_seeded_binary))
+        .route("/query/:lane/batch", post(query_batch))
+        .route("/query/:lane/batch/seeded", post(query_batch_seeded))
+        .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit_middleware))
+        .route_layer(middleware::from_fn_with_state(state, params_negotiation_middleware))
+}
+
 /// Create the public router (exposed to the internet)
 pub fn create_public_router(state: SharedState) -> Router {
     create_public_router_with_metrics(state, None)
@@ -279,10 +728,8 @@ pub fn create_public_router_with_metrics(
         .route("/live", get(live))
         .route("/info", get(info))
         .route("/crs/:lane", get(get_crs))
-        .route("/query/:lane", post(query))
-        .route("/query/:lane/binary", post(query_binary))
-        .route("/query/:lane/seeded", post(query_seeded))
-        .route("/query/:lane/seeded/binary", post(query_seeded_binary))
+        .route("/cluster/register", post(cluster_register))
+        .merge(query_router(state.clone()))
         .with_state(state);
 
     if let Some(handle) = prometheus_handle {
@@ -298,8 +745,8 @@ pub fn create_public_router_with_metrics(
 /// Create the admin router (bound to localhost only)
 pub fn create_admin_router(state: SharedState) -> Router {
     Router::new()
-        .route("/admin/reload", post(admin_reload))
         .route("/admin/health", get(health))
+        .merge(admin_reload_router(state.clone()))
         .with_state(state)
 }
 
@@ -318,11 +765,9 @@ pub fn create_router_with_metrics(
         .route("/live", get(live))
         .route("/info", get(info))
         .route("/crs/:lane", get(get_crs))
-        .route("/query/:lane", post(query))
-        .route("/query/:lane/binary", post(query_binary))
-        .route("/query/:lane/seeded", post(query_seeded))
-        .route("/query/:lane/seeded/binary", post(query_seeded_binary))
-        .route("/admin/reload", post(admin_reload))
+        .route("/cluster/register", post(cluster_register))
+        .merge(query_router(state.clone()))
+        .merge(admin_reload_router(state.clone()))
         .with_state(state);
 
     if let Some(handle) = prometheus_handle {
@@ -346,4 +791,58 @@ mod tests {
         assert_eq!(parse_lane("cold").unwrap(), Lane::Cold);
         assert!(parse_lane("invalid").is_err());
     }
+
+    #[test]
+    fn test_compute_crs_etag_is_deterministic() {
+        let a = compute_crs_etag("{\"foo\":1}", 0);
+        let b = compute_crs_etag("{\"foo\":1}", 0);
+        assert_eq!(a, b);
+        assert!(a.starts_with('"') && a.ends_with('"'));
+    }
+
+    #[test]
+    fn test_compute_crs_etag_changes_with_generation() {
+        let a = compute_crs_etag("{\"foo\":1}", 0);
+        let b = compute_crs_etag("{\"foo\":1}", 1);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_compute_crs_etag_changes_with_content() {
+        let a = compute_crs_etag("{\"foo\":1}", 0);
+        let b = compute_crs_etag("{\"foo\":2}", 0);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_ranged_response_none_without_range_header() {
+        assert!(ranged_response(&HeaderMap::new(), b"hello world".to_vec(), "text/plain").is_none());
+    }
+
+    #[test]
+    fn test_ranged_response_partial_content() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RANGE, "bytes=0-4".parse().unwrap());
+
+        let response = ranged_response(&headers, b"hello world".to_vec(), "text/plain").unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            response.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 0-4/11"
+        );
+        assert_eq!(response.headers().get(header::CONTENT_LENGTH).unwrap(), "5");
+    }
+
+    #[test]
+    fn test_ranged_response_unsatisfiable() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RANGE, "bytes=100-200".parse().unwrap());
+
+        let response = ranged_response(&headers, b"hello world".to_vec(), "text/plain").unwrap();
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            response.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes */11"
+        );
+    }
 }
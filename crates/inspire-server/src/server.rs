@@ -1,81 +1,35 @@
 //! Two-lane PIR server implementation
 
 use std::net::SocketAddr;
-use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-
-use axum::{
-    body::Body,
-    http::{Request, StatusCode},
-    middleware::{self, Next},
-    response::{IntoResponse, Response},
-};
-use inspire_core::TwoLaneConfig;
+use std::time::Duration;
+
+use inspire_core::{NodeMode, TwoLaneConfig};
 use tokio::net::TcpListener;
 
 use crate::error::Result;
+use crate::rate_limit::{standalone_rate_limit_middleware, RateLimitConfig, RateLimiter};
 use crate::routes::{create_admin_router, create_public_router, create_router};
 use crate::state::{create_shared_state, SharedState};
 
-/// Rate limiter state for admin endpoints
-#[derive(Clone)]
-struct RateLimiter {
-    last_request: Arc<AtomicU64>,
-    min_interval: Duration,
-}
-
-impl RateLimiter {
-    fn new(min_interval: Duration) -> Self {
-        Self {
-            last_request: Arc::new(AtomicU64::new(0)),
-            min_interval,
-        }
-    }
-
-    fn check(&self) -> bool {
-        let now = Instant::now().elapsed().as_millis() as u64;
-        let last = self.last_request.load(Ordering::Relaxed);
-        let min_ms = self.min_interval.as_millis() as u64;
-        
-        if now.saturating_sub(last) >= min_ms {
-            self.last_request.store(now, Ordering::Relaxed);
-            true
-        } else {
-            false
-        }
-    }
-}
-
-async fn rate_limit_middleware(
-    State(limiter): State<RateLimiter>,
-    request: Request<Body>,
-    next: Next,
-) -> Response {
-    if !limiter.check() {
-        return (
-            StatusCode::TOO_MANY_REQUESTS,
-            "Rate limit exceeded. Try again in 1 second.",
-        )
-            .into_response();
-    }
-    next.run(request).await
-}
-
-use axum::extract::State;
-
 /// Two-lane PIR server
 pub struct TwoLaneServer {
     state: SharedState,
     public_addr: SocketAddr,
     admin_addr: Option<SocketAddr>,
+    admin_rate_limit: RateLimitConfig,
 }
 
 impl TwoLaneServer {
     /// Create a new server with the given configuration
     pub fn new(config: TwoLaneConfig, public_addr: SocketAddr, admin_addr: Option<SocketAddr>) -> Self {
         let state = create_shared_state(config);
-        Self { state, public_addr, admin_addr }
+        Self {
+            state,
+            public_addr,
+            admin_addr,
+            admin_rate_limit: RateLimitConfig::default(),
+        }
     }
 
     /// Load both lanes from disk
@@ -85,6 +39,10 @@ impl TwoLaneServer {
 
     /// Run the server (single listener mode for backwards compatibility)
     pub async fn run(self) -> Result<()> {
+        if self.state.config.node_mode == NodeMode::Ingest {
+            spawn_cluster_registration(self.state.clone());
+        }
+
         if self.admin_addr.is_some() {
             self.run_dual().await
         } else {
@@ -99,9 +57,12 @@ impl TwoLaneServer {
         tracing::info!("Starting Two-Lane PIR server on {}", self.public_addr);
 
         let listener = TcpListener::bind(self.public_addr).await?;
-        axum::serve(listener, router)
-            .await
-            .map_err(|e| crate::error::ServerError::Internal(e.to_string()))?;
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .map_err(|e| crate::error::ServerError::Internal(e.to_string()))?;
 
         Ok(())
     }
@@ -109,12 +70,13 @@ impl TwoLaneServer {
     /// Run with separate public and admin listeners
     async fn run_dual(self) -> Result<()> {
         let admin_addr = self.admin_addr.expect("admin_addr required for dual mode");
-        
+
         let public_router = create_public_router(self.state.clone());
-        
-        let rate_limiter = RateLimiter::new(Duration::from_secs(1));
-        let admin_router = create_admin_router(self.state.clone())
-            .layer(middleware::from_fn_with_state(rate_limiter, rate_limit_middleware));
+
+        let admin_rate_limiter = Arc::new(RateLimiter::new(self.admin_rate_limit));
+        let admin_router = create_admin_router(self.state.clone()).layer(
+            axum::middleware::from_fn_with_state(admin_rate_limiter, standalone_rate_limit_middleware),
+        );
 
         tracing::info!("Starting public PIR server on {}", self.public_addr);
         tracing::info!("Starting admin server on {} (localhost only)", admin_addr);
@@ -123,11 +85,19 @@ impl TwoLaneServer {
         let admin_listener = TcpListener::bind(admin_addr).await?;
 
         let public_handle = tokio::spawn(async move {
-            axum::serve(public_listener, public_router).await
+            axum::serve(
+                public_listener,
+                public_router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
         });
 
         let admin_handle = tokio::spawn(async move {
-            axum::serve(admin_listener, admin_router).await
+            axum::serve(
+                admin_listener,
+                admin_router.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
         });
 
         tokio::select! {
@@ -150,11 +120,71 @@ impl TwoLaneServer {
     }
 }
 
+/// How often an ingest node re-registers with each configured query node.
+/// Comfortably inside [`crate::cluster::ClusterRegistry::STALE_AFTER`] so a
+/// healthy ingest node never falls out of rotation between registrations.
+const CLUSTER_REGISTER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Spawn the background task that periodically registers this ingest node
+/// with every query node in `config.cluster_query_nodes`
+///
+/// Runs for the lifetime of the process; a query node that's temporarily
+/// unreachable just gets skipped this cycle and retried next tick, since
+/// registration is naturally idempotent (re-registering the same URL
+/// replaces its entry's stats and refreshes its last-seen timestamp, see
+/// [`crate::cluster::ClusterRegistry::register`]).
+fn spawn_cluster_registration(state: SharedState) {
+    let Some(self_url) = state.config.cluster_self_url.clone() else {
+        tracing::warn!("node_mode is Ingest but cluster_self_url is not configured -- skipping cluster registration");
+        return;
+    };
+    if state.config.cluster_query_nodes.is_empty() {
+        tracing::warn!("node_mode is Ingest but cluster_query_nodes is empty -- skipping cluster registration");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let http = reqwest::Client::new();
+        let config_hash = state
+            .config
+            .config_hash
+            .clone()
+            .unwrap_or_else(|| state.config.compute_hash());
+
+        loop {
+            let stats = state.load_snapshot().stats();
+            let request = crate::cluster::RegisterRequest {
+                url: self_url.clone(),
+                stats,
+                config_hash: config_hash.clone(),
+            };
+
+            for query_node in &state.config.cluster_query_nodes {
+                let url = format!("{}/cluster/register", query_node.trim_end_matches('/'));
+                match http.post(&url).json(&request).send().await {
+                    Ok(resp) if resp.status().is_success() => {
+                        tracing::debug!(query_node, "Registered with query node");
+                    }
+                    Ok(resp) => {
+                        tracing::warn!(query_node, status = %resp.status(), "Cluster registration rejected");
+                    }
+                    Err(e) => {
+                        tracing::warn!(query_node, error = %e, "Cluster registration failed");
+                    }
+                }
+            }
+
+            tokio::time::sleep(CLUSTER_REGISTER_INTERVAL).await;
+        }
+    });
+}
+
 /// Builder for TwoLaneServer
 pub struct ServerBuilder {
     config: TwoLaneConfig,
     public_addr: SocketAddr,
     admin_addr: Option<SocketAddr>,
+    admin_rate_limit: RateLimitConfig,
     load_lanes: bool,
 }
 
@@ -164,6 +194,7 @@ impl ServerBuilder {
             config,
             public_addr: ([127, 0, 0, 1], 3000).into(),
             admin_addr: None,
+            admin_rate_limit: RateLimitConfig::default(),
             load_lanes: true,
         }
     }
@@ -187,6 +218,14 @@ impl ServerBuilder {
         self
     }
 
+    /// Configure the per-client token-bucket limiter guarding the admin
+    /// listener (burst, refill rate, and max tracked clients). Defaults to
+    /// [`RateLimitConfig::default`] when unset.
+    pub fn admin_rate_limit(mut self, config: RateLimitConfig) -> Self {
+        self.admin_rate_limit = config;
+        self
+    }
+
     /// Skip loading lanes on build (useful for testing)
     pub fn skip_load(mut self) -> Self {
         self.load_lanes = false;
@@ -194,9 +233,15 @@ impl ServerBuilder {
     }
 
     pub fn build(self) -> Result<TwoLaneServer> {
-        let server = TwoLaneServer::new(self.config, self.public_addr, self.admin_addr);
-
-        if self.load_lanes {
+        let is_query_node = self.config.node_mode == NodeMode::Query;
+        let mut server = TwoLaneServer::new(self.config, self.public_addr, self.admin_addr);
+        server.admin_rate_limit = self.admin_rate_limit;
+
+        // A query node holds no lane data of its own -- see
+        // `inspire_core::NodeMode::Query` -- so there's nothing on disk for
+        // `load_lanes` to find, and forcing it to try would turn every
+        // query-node startup into a failure.
+        if self.load_lanes && !is_query_node {
             server.load_lanes()?;
         }
 
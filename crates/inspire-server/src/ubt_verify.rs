@@ -0,0 +1,133 @@
+//! Cross-checks loaded lane data against the chain's authoritative UBT root
+//!
+//! `LaneData`'s database is RLWE-encoded by `inspire_pir::setup` — this
+//! crate never holds the plaintext leaf layout once a lane is encoded, and
+//! has no secret key to decode it back, so there is no way to rehash the
+//! loaded ciphertexts into a UBT root directly. Instead, lane-builder (which
+//! *does* see plaintext while dumping a lane) records the authoritative
+//! root it observed via `ubt_getRoot` into [`inspire_core::CrsMetadata::ubt_root`]
+//! at build time. On load, this module re-fetches that same block's root
+//! from the chain and compares it against the committed value: a mismatch
+//! means the on-disk shard directory no longer reflects the chain (stale
+//! dump, corruption, or a reorg lane-builder's snapshot didn't survive).
+//!
+//! This catches a corrupted/stale shard directory, not a malicious
+//! lane-builder — a builder that lies about `ubt_root` would pass this
+//! check trivially. That's consistent with the project's threat model
+//! (single-server, honest-but-curious), not a gap introduced here.
+
+use crate::error::{Result, ServerError};
+
+/// Client for the `ubt_getRoot` JSON-RPC method, used only for root
+/// verification. Deliberately independent of `inspire_updater::EthrexClient`
+/// — `inspire-server` and `inspire-updater` are separate processes, and this
+/// is a blocking call made from `ServerState::load_lanes`, which itself runs
+/// synchronously from request handlers.
+pub struct UbtRootClient {
+    rpc_url: String,
+    http: reqwest::blocking::Client,
+}
+
+impl UbtRootClient {
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            http: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// Fetch the authoritative UBT root for `block_number`.
+    pub fn fetch_root(&self, block_number: u64) -> Result<[u8; 32]> {
+        let request_body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "ubt_getRoot",
+            "params": [block_number],
+            "id": 1,
+        });
+
+        let response: serde_json::Value = self
+            .http
+            .post(&self.rpc_url)
+            .json(&request_body)
+            .send()
+            .map_err(|e| ServerError::UbtRootUnavailable(format!("ubt_getRoot request failed: {e}")))?
+            .json()
+            .map_err(|e| ServerError::UbtRootUnavailable(format!("ubt_getRoot response not JSON: {e}")))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(ServerError::UbtRootUnavailable(format!(
+                "ubt_getRoot RPC error: {error}"
+            )));
+        }
+
+        let root_hex = response["result"]["root"].as_str().ok_or_else(|| {
+            ServerError::UbtRootUnavailable("ubt_getRoot response missing result.root".to_string())
+        })?;
+
+        parse_hex_root(root_hex)
+            .map_err(|e| ServerError::UbtRootUnavailable(format!("malformed root in ubt_getRoot response: {e}")))
+    }
+}
+
+/// Fetch the authoritative root for `block_number` and compare it against
+/// `expected_root` (the root committed into this lane's CRS metadata at
+/// build time). Returns [`ServerError::UbtRootMismatch`] on mismatch.
+pub fn verify(
+    client: &UbtRootClient,
+    lane_name: &str,
+    block_number: u64,
+    expected_root: [u8; 32],
+) -> Result<()> {
+    let actual_root = client.fetch_root(block_number)?;
+
+    if actual_root != expected_root {
+        return Err(ServerError::UbtRootMismatch {
+            lane: lane_name.to_string(),
+            block_number,
+            expected: hex_root(&expected_root),
+            actual: hex_root(&actual_root),
+        });
+    }
+
+    Ok(())
+}
+
+fn parse_hex_root(s: &str) -> std::result::Result<[u8; 32], String> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let bytes = hex::decode(s).map_err(|e| e.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("expected 32 bytes, got {}", bytes.len()))
+}
+
+fn hex_root(root: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_root_with_0x_prefix() {
+        let root = [0xabu8; 32];
+        let parsed = parse_hex_root(&hex_root(&root)).unwrap();
+        assert_eq!(parsed, root);
+    }
+
+    #[test]
+    fn test_parse_hex_root_without_0x_prefix() {
+        let parsed = parse_hex_root(&"11".repeat(32)).unwrap();
+        assert_eq!(parsed, [0x11u8; 32]);
+    }
+
+    #[test]
+    fn test_parse_hex_root_wrong_length() {
+        assert!(parse_hex_root("0xabcd").is_err());
+    }
+
+    #[test]
+    fn test_parse_hex_root_invalid_hex() {
+        assert!(parse_hex_root(&"zz".repeat(32)).is_err());
+    }
+}
@@ -17,6 +17,27 @@ pub struct UpdaterConfig {
     pub max_blocks_per_fetch: u64,
     /// Ethereum chain ID (1=mainnet, 11155111=sepolia)
     pub chain_id: u64,
+    /// Independently verify changed storage entries against an
+    /// `eth_getProof` response before they reach [`crate::ShardWriter`],
+    /// trading throughput (one extra RPC round-trip per changed account)
+    /// for trustlessness against a compromised or buggy RPC endpoint.
+    pub verify_proofs: bool,
+    /// Fraction of changed entries sampled for proof verification each
+    /// block when `verify_proofs` is set, in `[0.0, 1.0]`. `1.0` (the
+    /// default) verifies every entry; a lower value trades verification
+    /// coverage for fewer `eth_getProof` round-trips on high-churn blocks.
+    pub verify_sample_rate: f64,
+    /// Halt the service with an error on the first proof/value mismatch
+    /// instead of dropping the offending entry and continuing. Off by
+    /// default so a single flaky RPC response doesn't take the updater
+    /// down; operators who'd rather stop and investigate than silently
+    /// lose entries should set this.
+    pub verify_strict: bool,
+    /// Shared secret sent as `Authorization: Bearer <token>` on every
+    /// `/admin/reload` call, matched against the PIR server's
+    /// `TwoLaneConfig::admin_reload_token`. `None` sends no header, for
+    /// servers that haven't configured one.
+    pub reload_auth_token: Option<String>,
 }
 
 impl Default for UpdaterConfig {
@@ -29,6 +50,10 @@ impl Default for UpdaterConfig {
             poll_interval: Duration::from_secs(1),
             max_blocks_per_fetch: 100,
             chain_id: 11155111, // Sepolia
+            verify_proofs: false,
+            verify_sample_rate: 1.0,
+            verify_strict: false,
+            reload_auth_token: None,
         }
     }
 }
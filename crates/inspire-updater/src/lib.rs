@@ -20,13 +20,19 @@
 //! ```
 
 mod config;
+mod reader;
 mod rpc;
 mod service;
 mod state;
+// MPT proof-walking logic lives in the `state_proof` crate, shared with
+// `lane-builder`; this module just re-exports it.
+pub mod state_proof;
 mod writer;
 
 pub use config::UpdaterConfig;
-pub use rpc::{EthrexClient, StorageEntry, DumpStorageResponse, UbtRootResponse, StateDeltaResponse, BlockDeltas};
+pub use reader::{StateReader, StateReaderRange};
+pub use rpc::{EthrexClient, StorageEntry, StoragePage, DumpStorageResponse, UbtRootResponse, StateDeltaResponse, BlockDeltas};
 pub use service::{ReloadClient, UpdaterService};
 pub use state::StateTracker;
+pub use state_proof::{verify_account_proof, verify_storage_proof, StateProofError, TrieAccount};
 pub use writer::ShardWriter;
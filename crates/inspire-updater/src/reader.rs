@@ -0,0 +1,286 @@
+//! Zero-copy, memory-mapped reader for PIR `state.bin` shards
+//!
+//! [`ShardWriter`] keeps entries sorted by EIP-7864 `tree_key` (stem ||
+//! subindex), but nothing on the read side exploited that ordering --
+//! consuming a shard meant deserializing the whole file into a `Vec`. This
+//! module memory-maps the file instead (via [`memmap2`]) and binary
+//! searches the fixed-size [`STATE_ENTRY_SIZE`] records directly against
+//! the mapping, so a lookup or range scan over a multi-gigabyte cold-lane
+//! file costs O(log N) page-ins and no per-entry allocation.
+//!
+//! [`ShardWriter`]: crate::writer::ShardWriter
+
+use std::fs::File;
+use std::path::Path;
+
+use inspire_core::state_format::{StateHeader, StorageEntry, STATE_ENTRY_SIZE, STATE_HEADER_SIZE};
+use inspire_core::ubt::compute_storage_tree_key;
+use memmap2::Mmap;
+
+/// Memory-mapped, read-only view over a `state.bin` shard. Entries are
+/// assumed sorted by `tree_key` (see [`ShardWriter`]'s doc comment), which
+/// [`Self::lookup`] and [`Self::range`] rely on for binary search.
+///
+/// [`ShardWriter`]: crate::writer::ShardWriter
+pub struct StateReader {
+    mmap: Mmap,
+    header: StateHeader,
+}
+
+impl StateReader {
+    /// Memory-map `path` and validate its [`StateHeader`]. Fails if the
+    /// magic/version don't match or the file is a different size than the
+    /// header's `entry_count` implies (truncated or corrupt shard).
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let file = File::open(path.as_ref())?;
+        // Safe in the sense memmap2 defines it: the file isn't expected to
+        // be truncated or mutated by another process while mapped, which
+        // holds for the regenerated, read-mostly shards this reads.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let header = StateHeader::from_bytes(&mmap)
+            .map_err(|e| anyhow::anyhow!("invalid state file header in {:?}: {e}", path.as_ref()))?;
+
+        let expected_len = STATE_HEADER_SIZE as u64 + header.entry_count * STATE_ENTRY_SIZE as u64;
+        if mmap.len() as u64 != expected_len {
+            anyhow::bail!(
+                "state file {:?} size mismatch: header declares {} entries ({} bytes expected), file is {} bytes",
+                path.as_ref(),
+                header.entry_count,
+                expected_len,
+                mmap.len(),
+            );
+        }
+
+        Ok(Self { mmap, header })
+    }
+
+    /// The validated header this shard was opened with.
+    pub fn header(&self) -> &StateHeader {
+        &self.header
+    }
+
+    /// Re-hash the mapped entry bytes and compare against the header's
+    /// `body_digest`, catching silent corruption or a partial download that
+    /// [`Self::open`]'s size check alone wouldn't ([`Self::open`] only
+    /// checks the file is the *right length*, not that its bytes are the
+    /// ones that were written). Returns
+    /// [`inspire_core::state_format::StateFormatError::DigestNotAvailable`]
+    /// for shards written before the body digest existed.
+    pub fn verify(&self) -> Result<(), inspire_core::state_format::StateFormatError> {
+        self.header.verify_digest(&self.mmap[STATE_HEADER_SIZE..])
+    }
+
+    /// Number of entries in the shard.
+    pub fn entry_count(&self) -> u64 {
+        self.header.entry_count
+    }
+
+    /// Look up the storage value at `(address, slot)` by binary-searching
+    /// the `tree_key`-sorted entries. O(log N), no full-file scan.
+    pub fn lookup(&self, address: [u8; 20], slot: [u8; 32]) -> Option<StorageEntry> {
+        let target = compute_storage_tree_key(&address, &slot);
+        let ordinal = self.bisect(&target).ok()?;
+        Some(self.entry_at(ordinal))
+    }
+
+    /// Iterate entries whose `tree_key` falls in `[start_key, end_key)`,
+    /// in ascending `tree_key` order, for folding a cold-lane range into a
+    /// lane shard without materializing the whole file.
+    pub fn range(&self, start_key: [u8; 32], end_key: [u8; 32]) -> StateReaderRange<'_> {
+        let start_ordinal = match self.bisect(&start_key) {
+            Ok(ordinal) => ordinal,
+            Err(insert_at) => insert_at,
+        };
+        StateReaderRange {
+            reader: self,
+            next_ordinal: start_ordinal,
+            end_key,
+        }
+    }
+
+    /// Binary search for `target` among the sorted entries. `Ok(ordinal)`
+    /// on an exact match; `Err(insert_at)` with the ordinal the first entry
+    /// `>= target` sits at (or `entry_count()` if none does) otherwise --
+    /// mirroring `[T]::binary_search`'s contract.
+    fn bisect(&self, target: &[u8; 32]) -> Result<u64, u64> {
+        let mut low = 0u64;
+        let mut high = self.header.entry_count;
+
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let mid_key = self.tree_key_at(mid);
+            match mid_key.as_slice().cmp(target.as_slice()) {
+                std::cmp::Ordering::Equal => return Ok(mid),
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+            }
+        }
+
+        Err(low)
+    }
+
+    fn entry_at(&self, ordinal: u64) -> StorageEntry {
+        let bytes = self.entry_bytes(ordinal);
+        StorageEntry::from_bytes(bytes).expect("STATE_ENTRY_SIZE bytes, validated by Self::open")
+    }
+
+    fn tree_key_at(&self, ordinal: u64) -> [u8; 32] {
+        let bytes = self.entry_bytes(ordinal);
+        let address: [u8; 20] = bytes[0..20].try_into().unwrap();
+        let slot: [u8; 32] = bytes[20..52].try_into().unwrap();
+        compute_storage_tree_key(&address, &slot)
+    }
+
+    fn entry_bytes(&self, ordinal: u64) -> &[u8] {
+        let offset = STATE_HEADER_SIZE + ordinal as usize * STATE_ENTRY_SIZE;
+        &self.mmap[offset..offset + STATE_ENTRY_SIZE]
+    }
+}
+
+/// Iterator returned by [`StateReader::range`].
+pub struct StateReaderRange<'a> {
+    reader: &'a StateReader,
+    next_ordinal: u64,
+    end_key: [u8; 32],
+}
+
+impl<'a> Iterator for StateReaderRange<'a> {
+    type Item = StorageEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_ordinal >= self.reader.entry_count() {
+            return None;
+        }
+        if self.reader.tree_key_at(self.next_ordinal).as_slice() >= self.end_key.as_slice() {
+            return None;
+        }
+
+        let entry = self.reader.entry_at(self.next_ordinal);
+        self.next_ordinal += 1;
+        Some(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_sorted_shard(dir: &Path, entries: &[([u8; 20], [u8; 32], [u8; 32])]) -> std::path::PathBuf {
+        let mut sorted: Vec<_> = entries
+            .iter()
+            .map(|&(a, s, v)| (compute_storage_tree_key(&a, &s), StorageEntry::new(a, s, v)))
+            .collect();
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let path = dir.join("state.bin");
+        let mut file = File::create(&path).unwrap();
+        file.write_all(&StateHeader::new(sorted.len() as u64, 0, 1, [0u8; 32]).to_bytes())
+            .unwrap();
+        for (_, entry) in &sorted {
+            file.write_all(&entry.to_bytes()).unwrap();
+        }
+        path
+    }
+
+    #[test]
+    fn test_lookup_finds_existing_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let entries = vec![
+            ([1u8; 20], [1u8; 32], [0xaa; 32]),
+            ([2u8; 20], [2u8; 32], [0xbb; 32]),
+            ([3u8; 20], [3u8; 32], [0xcc; 32]),
+        ];
+        let path = write_sorted_shard(dir.path(), &entries);
+
+        let reader = StateReader::open(&path).unwrap();
+        let found = reader.lookup([2u8; 20], [2u8; 32]).unwrap();
+        assert_eq!(found.value, [0xbb; 32]);
+    }
+
+    #[test]
+    fn test_lookup_missing_entry_returns_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let entries = vec![([1u8; 20], [1u8; 32], [0xaa; 32])];
+        let path = write_sorted_shard(dir.path(), &entries);
+
+        let reader = StateReader::open(&path).unwrap();
+        assert!(reader.lookup([9u8; 20], [9u8; 32]).is_none());
+    }
+
+    #[test]
+    fn test_range_returns_entries_in_tree_key_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let entries: Vec<_> = (0..20u8)
+            .map(|i| ([i; 20], [i; 32], [i.wrapping_add(100); 32]))
+            .collect();
+        let path = write_sorted_shard(dir.path(), &entries);
+
+        let reader = StateReader::open(&path).unwrap();
+        let all: Vec<StorageEntry> = reader.range([0u8; 32], [0xff; 32]).collect();
+        assert_eq!(all.len(), 20);
+
+        let keys: Vec<[u8; 32]> = all
+            .iter()
+            .map(|e| compute_storage_tree_key(&e.address, &e.slot))
+            .collect();
+        let mut sorted_keys = keys.clone();
+        sorted_keys.sort();
+        assert_eq!(keys, sorted_keys);
+    }
+
+    #[test]
+    fn test_open_rejects_truncated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.bin");
+        let mut file = File::create(&path).unwrap();
+        file.write_all(&StateHeader::new(5, 0, 1, [0u8; 32]).to_bytes()).unwrap();
+        // Declares 5 entries but no entry bytes follow.
+
+        assert!(StateReader::open(&path).is_err());
+    }
+
+    #[test]
+    fn test_verify_detects_corruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let entries = vec![
+            ([1u8; 20], [1u8; 32], [0xaa; 32]),
+            ([2u8; 20], [2u8; 32], [0xbb; 32]),
+        ];
+
+        let mut sorted: Vec<_> = entries
+            .iter()
+            .map(|&(a, s, v)| (compute_storage_tree_key(&a, &s), StorageEntry::new(a, s, v)))
+            .collect();
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+        let body: Vec<u8> = sorted.iter().flat_map(|(_, e)| e.to_bytes()).collect();
+        let digest = *blake3::hash(&body).as_bytes();
+
+        let path = dir.path().join("state.bin");
+        let mut file = File::create(&path).unwrap();
+        file.write_all(
+            &StateHeader::new(sorted.len() as u64, 0, 1, [0u8; 32])
+                .with_digest(digest, body.len() as u64)
+                .to_bytes(),
+        )
+        .unwrap();
+        file.write_all(&body).unwrap();
+        drop(file);
+
+        let reader = StateReader::open(&path).unwrap();
+        assert!(reader.verify().is_ok());
+
+        // Corrupt one byte of the body in place and re-open.
+        let mut corrupted = std::fs::read(&path).unwrap();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        std::fs::write(&path, &corrupted).unwrap();
+
+        let reader = StateReader::open(&path).unwrap();
+        assert!(matches!(
+            reader.verify(),
+            Err(inspire_core::state_format::StateFormatError::ChecksumMismatch { .. })
+        ));
+    }
+}
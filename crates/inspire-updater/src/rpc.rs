@@ -1,6 +1,7 @@
 use alloy_primitives::{Address, B256, U256};
 use alloy_provider::{Provider, ProviderBuilder};
-use alloy_rpc_types::BlockNumberOrTag;
+use alloy_rpc_types::{BlockNumberOrTag, EIP1186AccountProofResponse};
+use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 
 /// Storage entry from pir_dumpStorage
@@ -42,6 +43,17 @@ pub struct StateDeltaResponse {
     pub total_deltas: u64,
 }
 
+/// One page of a `stream_storage` dump
+#[derive(Debug, Clone)]
+pub struct StoragePage {
+    pub entries: Vec<StorageEntry>,
+    /// Cursor that resumes the dump immediately after this page. `None`
+    /// once the dump is exhausted, so a caller can persist this alongside
+    /// whatever it flushed for this page and pass it back as
+    /// `start_cursor` to pick up mid-dump after a crash.
+    pub resume_cursor: Option<String>,
+}
+
 /// Client for ethrex RPC
 pub struct EthrexClient {
     rpc_url: String,
@@ -100,6 +112,47 @@ impl EthrexClient {
         Ok(self.provider().await?.get_block_number().await?)
     }
 
+    /// Fetch `(hash, parent_hash)` for `block_number`, used by the updater
+    /// to detect reorgs without pulling the full block body.
+    pub async fn block_header(&self, block_number: u64) -> anyhow::Result<(B256, B256)> {
+        let block = self
+            .provider()
+            .await?
+            .get_block_by_number(BlockNumberOrTag::Number(block_number))
+            .await?;
+        let block = block.ok_or_else(|| anyhow::anyhow!("block {} not found", block_number))?;
+        Ok((block.header.hash, block.header.parent_hash))
+    }
+
+    /// Fetch the `stateRoot` of `block_number`'s header, used to
+    /// independently verify `eth_getProof` responses.
+    pub async fn state_root(&self, block_number: u64) -> anyhow::Result<B256> {
+        let block = self
+            .provider()
+            .await?
+            .get_block_by_number(BlockNumberOrTag::Number(block_number))
+            .await?;
+        let block = block.ok_or_else(|| anyhow::anyhow!("block {} not found", block_number))?;
+        Ok(block.header.state_root)
+    }
+
+    /// Fetch an `eth_getProof` response authenticating `address` and
+    /// `slots` against `block_number`'s state root (see
+    /// [`crate::state_proof`] for the verification side).
+    pub async fn get_proof(
+        &self,
+        address: Address,
+        slots: Vec<B256>,
+        block_number: u64,
+    ) -> anyhow::Result<EIP1186AccountProofResponse> {
+        Ok(self
+            .provider()
+            .await?
+            .get_proof(address, slots)
+            .block_id(BlockNumberOrTag::Number(block_number).into())
+            .await?)
+    }
+
     /// Get storage at specific slot
     pub async fn get_storage_at(
         &self,
@@ -143,31 +196,57 @@ impl EthrexClient {
             .await
     }
 
-    /// Iterate all storage entries
-    /// Returns an async iterator over all storage entries
+    /// Stream storage entries page-by-page instead of buffering the whole
+    /// dump, so a consumer building encoded PIR shards can process and
+    /// flush each page as it arrives rather than holding the full
+    /// multi-million-entry dataset in memory.
+    ///
+    /// Pass `start_cursor` as `None` to stream from the beginning, or as
+    /// a `StoragePage::resume_cursor` saved from an earlier run to resume
+    /// a crashed dump mid-way instead of re-paging from scratch. Stops
+    /// (with an `Err` item) on the first RPC failure rather than retrying.
+    pub fn stream_storage(
+        &self,
+        start_cursor: Option<String>,
+        limit_per_page: u64,
+    ) -> impl Stream<Item = anyhow::Result<StoragePage>> + '_ {
+        futures::stream::unfold(Some(start_cursor), move |next_cursor| async move {
+            let cursor = next_cursor?;
+
+            let resp = match self.pir_dump_storage(cursor.as_deref(), limit_per_page).await {
+                Ok(resp) => resp,
+                Err(e) => return Some((Err(e), None)),
+            };
+
+            let resume_cursor = resp.has_more.then(|| resp.next_cursor.clone()).flatten();
+            let next_state = resp.has_more.then(|| resp.next_cursor.clone());
+
+            Some((Ok(StoragePage { entries: resp.entries, resume_cursor }), next_state))
+        })
+    }
+
+    /// Iterate all storage entries, buffering them into a single `Vec`.
+    ///
+    /// Implemented on top of [`Self::stream_storage`]; kept for callers
+    /// that genuinely need the full set at once. Prefer `stream_storage`
+    /// directly for multi-million-entry dumps where holding everything in
+    /// RAM defeats the point of pagination.
     pub async fn dump_all_storage(
         &self,
         limit_per_page: u64,
         mut on_page: impl FnMut(usize, &[StorageEntry]),
     ) -> anyhow::Result<Vec<StorageEntry>> {
-        let mut all_entries = Vec::new();
-        let mut cursor: Option<String> = None;
-        let mut page = 0;
-
-        loop {
-            let resp = self
-                .pir_dump_storage(cursor.as_deref(), limit_per_page)
-                .await?;
+        let stream = self.stream_storage(None, limit_per_page);
+        futures::pin_mut!(stream);
 
-            on_page(page, &resp.entries);
-            all_entries.extend(resp.entries);
-
-            if !resp.has_more {
-                break;
-            }
+        let mut all_entries = Vec::new();
+        let mut page_index = 0usize;
 
-            cursor = resp.next_cursor;
-            page += 1;
+        while let Some(page) = stream.next().await {
+            let page = page?;
+            on_page(page_index, &page.entries);
+            all_entries.extend(page.entries);
+            page_index += 1;
         }
 
         Ok(all_entries)
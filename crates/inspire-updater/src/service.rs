@@ -1,32 +1,44 @@
+use std::collections::HashMap;
 use std::time::Duration;
+
+use alloy_primitives::{Address, B256};
+use futures::StreamExt;
+use rand::Rng;
 use tokio::time::interval;
 use tracing::{error, info, warn};
 
 use crate::config::UpdaterConfig;
-use crate::rpc::EthrexClient;
+use crate::rpc::{EthrexClient, StorageEntry};
 use crate::state::StateTracker;
+use crate::state_proof::{verify_account_proof, verify_storage_proof};
 use crate::writer::ShardWriter;
 
 /// Reload client (reuse from lane-builder)
 pub struct ReloadClient {
     client: reqwest::Client,
     server_url: String,
+    auth_token: Option<String>,
 }
 
 impl ReloadClient {
-    pub fn new(server_url: &str) -> Self {
+    pub fn new(server_url: &str, auth_token: Option<String>) -> Self {
         Self {
             client: reqwest::Client::builder()
                 .timeout(Duration::from_secs(30))
                 .build()
                 .expect("Failed to build HTTP client"),
             server_url: server_url.to_string(),
+            auth_token,
         }
     }
 
     pub async fn reload(&self) -> anyhow::Result<()> {
         let url = format!("{}/admin/reload", self.server_url);
-        let resp = self.client.post(&url).send().await?;
+        let mut req = self.client.post(&url);
+        if let Some(token) = &self.auth_token {
+            req = req.bearer_auth(token);
+        }
+        let resp = req.send().await?;
         if !resp.status().is_success() {
             anyhow::bail!("Reload failed: {}", resp.status());
         }
@@ -58,7 +70,7 @@ impl UpdaterService {
         let rpc = EthrexClient::new(&config.rpc_url, config.admin_rpc_url.clone()).await?;
         let state = StateTracker::new();
         let writer = ShardWriter::new(&config.data_dir, config.chain_id);
-        let reload = ReloadClient::new(&config.pir_server_url);
+        let reload = ReloadClient::new(&config.pir_server_url, config.reload_auth_token.clone());
 
         Ok(Self {
             config,
@@ -69,30 +81,47 @@ impl UpdaterService {
         })
     }
 
-    /// Perform initial sync by dumping all storage
+    /// Perform initial sync by streaming all storage pages straight into
+    /// `state.bin`, so the writer buffers around one page plus one sort run
+    /// regardless of total state size -- see
+    /// [`ShardWriter::streaming_state_builder`]. The [`StateTracker`] index
+    /// still takes in every entry, so its memory grows with the state.
     pub async fn initial_sync(&mut self) -> anyhow::Result<()> {
         info!("Starting initial sync via pir_dumpStorage");
 
         let current_block = self.rpc.block_number().await?;
+        let (current_hash, _) = self.rpc.block_header(current_block).await?;
+
+        let shard_size_bytes = inspire_core::TwoLaneConfig::default().shard_size_bytes;
+        let mut builder = self.writer.streaming_state_builder(shard_size_bytes)?;
+        self.state.begin_dump();
+
+        let mut pages = self.rpc.stream_storage(None, 10000);
+        let mut total_entries = 0usize;
+        let mut page_index = 0usize;
+
+        while let Some(page) = pages.next().await {
+            let page = page?;
+            info!(page = page_index, entries = page.entries.len(), "Fetched storage page");
+
+            self.state.ingest_dump_page(&page.entries);
+            builder.push_page(&page.entries)?;
+
+            total_entries += page.entries.len();
+            page_index += 1;
+        }
+        drop(pages);
 
-        let entries = self
-            .rpc
-            .dump_all_storage(10000, |page, entries| {
-                info!(page, entries = entries.len(), "Fetched storage page");
-            })
-            .await?;
+        self.state.finish_dump(current_block, current_hash);
+        let path = builder.finish(current_block, current_hash.0)?;
 
         info!(
             block = current_block,
-            entries = entries.len(),
+            entries = total_entries,
+            path = %path.display(),
             "Initial sync complete"
         );
 
-        self.state.load_from_dump(current_block, entries.clone());
-        let path = self.writer.write_full_state(&entries, current_block).await?;
-
-        info!(path = %path.display(), "Wrote state.bin");
-
         // Trigger PIR server reload
         if let Err(e) = self.reload.reload().await {
             warn!(error = %e, "Failed to trigger PIR reload after initial sync");
@@ -132,12 +161,24 @@ impl UpdaterService {
 
     async fn poll_once(&mut self) -> anyhow::Result<()> {
         let current_block = self.rpc.block_number().await?;
-        let last_block = self.state.last_block().unwrap_or(0);
+        let mut last_block = self.state.last_block().unwrap_or(0);
 
         if current_block <= last_block {
             return Ok(()); // No new blocks
         }
 
+        if let Some(last_hash) = self.state.last_hash() {
+            let (_, next_parent_hash) = self.rpc.block_header(last_block + 1).await?;
+            if next_parent_hash != last_hash {
+                if !self.handle_reorg(last_block).await? {
+                    // Deeper than the retained window; initial_sync already
+                    // reset state and reloaded the PIR server.
+                    return Ok(());
+                }
+                last_block = self.state.last_block().unwrap_or(0);
+            }
+        }
+
         let blocks_behind = current_block - last_block;
 
         if blocks_behind > 0 {
@@ -165,30 +206,214 @@ impl UpdaterService {
                 "Received state deltas"
             );
 
-            // Collect all deltas from all blocks
-            let mut all_entries = Vec::new();
+            let mut total_changed = Vec::new();
             for block_delta in &delta_resp.blocks {
-                all_entries.extend(block_delta.deltas.clone());
+                let (hash, parent_hash) = self.rpc.block_header(block_delta.block_number).await?;
+
+                let entries = if self.config.verify_proofs {
+                    self.verify_block_entries(block_delta.block_number, &block_delta.deltas)
+                        .await?
+                } else {
+                    block_delta.deltas.clone()
+                };
+
+                let changed = self
+                    .state
+                    .apply_block(block_delta.block_number, hash, parent_hash, entries);
+                total_changed.extend(changed);
             }
 
-            if !all_entries.is_empty() {
-                let changed = self.state.apply_entries(to_block, all_entries);
+            // No deltas in range but still need to advance past `to_block`
+            if delta_resp.blocks.is_empty() {
+                let (hash, parent_hash) = self.rpc.block_header(to_block).await?;
+                self.state.apply_block(to_block, hash, parent_hash, vec![]);
+            }
 
-                info!(changed = changed.len(), "Storage entries changed");
-                self.writer.write_entries(&changed).await?;
+            info!(changed = total_changed.len(), "Storage entries changed");
 
-                // Trigger PIR server reload
-                if let Err(e) = self.reload.reload().await {
-                    warn!(error = %e, "Failed to trigger PIR reload");
-                } else {
-                    info!(block = to_block, "PIR server reloaded");
-                }
+            if !total_changed.is_empty() {
+                let to_block_hash = self
+                    .state
+                    .last_hash()
+                    .expect("apply_block above always sets last_hash");
+                self.writer
+                    .write_entries(&total_changed, to_block, to_block_hash.0)
+                    .await?;
+            }
+
+            // Only reload once the state is fully consistent for this batch
+            if let Err(e) = self.reload.reload().await {
+                warn!(error = %e, "Failed to trigger PIR reload");
             } else {
-                // No deltas but still update block number
-                self.state.apply_entries(to_block, vec![]);
+                info!(block = to_block, "PIR server reloaded");
             }
         }
 
         Ok(())
     }
+
+    /// Independently verify a (possibly sampled, see
+    /// `UpdaterConfig::verify_sample_rate`) subset of `entries` against
+    /// `block`'s `stateRoot` via `eth_getProof`. Entries that weren't
+    /// sampled pass through unverified. A verified entry whose proof or
+    /// proven value doesn't check out is dropped (and logged) unless
+    /// `UpdaterConfig::verify_strict` is set, in which case verification
+    /// fails the whole call so the caller halts rather than silently
+    /// losing entries. Entries for the same address are batched into a
+    /// single `eth_getProof` call.
+    async fn verify_block_entries(
+        &self,
+        block: u64,
+        entries: &[StorageEntry],
+    ) -> anyhow::Result<Vec<StorageEntry>> {
+        let sample_rate = self.config.verify_sample_rate.clamp(0.0, 1.0);
+
+        let mut to_verify: Vec<&StorageEntry> = Vec::new();
+        let mut passthrough: Vec<StorageEntry> = Vec::new();
+        for entry in entries {
+            if sample_rate >= 1.0 || rand::thread_rng().gen::<f64>() < sample_rate {
+                to_verify.push(entry);
+            } else {
+                passthrough.push(entry.clone());
+            }
+        }
+
+        if to_verify.is_empty() {
+            return Ok(passthrough);
+        }
+
+        let state_root = self.rpc.state_root(block).await?;
+
+        let mut by_address: HashMap<Address, Vec<&StorageEntry>> = HashMap::new();
+        for entry in to_verify {
+            by_address.entry(entry.address).or_default().push(entry);
+        }
+
+        let mut verified = passthrough;
+
+        for (address, address_entries) in by_address {
+            let slots: Vec<B256> = address_entries.iter().map(|e| e.slot).collect();
+            let proof = self.rpc.get_proof(address, slots, block).await?;
+
+            let account = match verify_account_proof(&proof.account_proof, address, state_root) {
+                Ok(Some(account)) => account,
+                Ok(None) => {
+                    if self.config.verify_strict {
+                        anyhow::bail!("account proof excludes {address} at block {block} but RPC reported storage changes");
+                    }
+                    warn!(%address, block, "account proof excludes address but RPC reported storage changes, dropping entries");
+                    continue;
+                }
+                Err(e) => {
+                    if self.config.verify_strict {
+                        anyhow::bail!("account proof for {address} at block {block} failed to verify: {e}");
+                    }
+                    warn!(%address, block, error = %e, "account proof failed to verify, dropping entries");
+                    continue;
+                }
+            };
+
+            for entry in address_entries {
+                let storage_proof = proof.storage_proof.iter().find(|p| p.key.as_b256() == entry.slot);
+                let Some(storage_proof) = storage_proof else {
+                    if self.config.verify_strict {
+                        anyhow::bail!("no storage proof returned for {address} slot {} at block {block}", entry.slot);
+                    }
+                    warn!(%address, slot = %entry.slot, block, "no storage proof returned for changed slot, dropping");
+                    continue;
+                };
+
+                let proven_value = match verify_storage_proof(&storage_proof.proof, entry.slot, account.storage_root) {
+                    Ok(value) => value.unwrap_or_default(),
+                    Err(e) => {
+                        if self.config.verify_strict {
+                            anyhow::bail!("storage proof for {address} slot {} at block {block} failed to verify: {e}", entry.slot);
+                        }
+                        warn!(%address, slot = %entry.slot, block, error = %e, "storage proof failed to verify, dropping");
+                        continue;
+                    }
+                };
+
+                if proven_value != entry.value {
+                    if self.config.verify_strict {
+                        anyhow::bail!(
+                            "RPC-reported value for {address} slot {} at block {block} disagrees with proven value (reported {}, proven {})",
+                            entry.slot, entry.value, proven_value
+                        );
+                    }
+                    warn!(
+                        %address, slot = %entry.slot, block,
+                        reported = %entry.value, proven = %proven_value,
+                        "RPC-reported value disagrees with proven value, dropping"
+                    );
+                    continue;
+                }
+
+                verified.push(entry.clone());
+            }
+        }
+
+        Ok(verified)
+    }
+
+    /// Walk backward through the retained reorg window comparing stored
+    /// hashes against the canonical chain until a common ancestor is found,
+    /// then revert storage entries applied above it. Returns `true` if the
+    /// rollback succeeded, or `false` if the reorg was deeper than the
+    /// retained window and `initial_sync` was used instead.
+    async fn handle_reorg(&mut self, last_block: u64) -> anyhow::Result<bool> {
+        warn!(last_block, "Detected reorg, searching for common ancestor");
+
+        let oldest = match self.state.oldest_window_block() {
+            Some(oldest) => oldest,
+            None => {
+                self.initial_sync().await?;
+                return Ok(false);
+            }
+        };
+
+        let mut candidate = last_block;
+        let ancestor = loop {
+            if candidate < oldest {
+                warn!(
+                    oldest_retained = oldest,
+                    "Reorg deeper than retained window, falling back to initial sync"
+                );
+                self.initial_sync().await?;
+                return Ok(false);
+            }
+
+            let window_hash = self
+                .state
+                .window_hash(candidate)
+                .expect("candidate is within [oldest, last_block] and thus in the window");
+            let (canonical_hash, _) = self.rpc.block_header(candidate).await?;
+
+            if canonical_hash == window_hash {
+                break candidate;
+            }
+
+            match candidate.checked_sub(1) {
+                Some(prev) => candidate = prev,
+                None => {
+                    self.initial_sync().await?;
+                    return Ok(false);
+                }
+            }
+        };
+
+        info!(ancestor, "Found common ancestor, reverting state above it");
+        let reverted = self.state.handle_reorg(ancestor);
+        if !reverted.is_empty() {
+            let ancestor_hash = self
+                .state
+                .last_hash()
+                .expect("handle_reorg above always leaves last_hash set to the ancestor");
+            self.writer
+                .write_entries(&reverted, ancestor, ancestor_hash.0)
+                .await?;
+        }
+
+        Ok(true)
+    }
 }
@@ -1,63 +1,269 @@
 use alloy_primitives::{Address, B256, U256};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use crate::rpc::StorageEntry;
 
+/// How many recently applied blocks to retain undo deltas for. A reorg
+/// deeper than this falls back to `initial_sync` rather than rolling back.
+const REORG_WINDOW: usize = 128;
+
+/// Previous value of a slot before a block's deltas were applied, so the
+/// block can be undone if it turns out to be on an abandoned fork. `None`
+/// means the slot didn't exist before this block (a tombstone on revert).
+#[derive(Debug, Clone)]
+struct UndoEntry {
+    address: Address,
+    slot: B256,
+    previous_value: Option<U256>,
+}
+
+/// One block's worth of applied state, kept around so a reorg can walk
+/// backward to a common ancestor and replay the inverse deltas.
+struct WindowEntry {
+    number: u64,
+    hash: B256,
+    parent_hash: B256,
+    undo: Vec<UndoEntry>,
+}
+
+/// Default per-block decay factor for [`StateTracker`]'s slot hotness
+/// scores -- see [`StateTracker::with_decay_factor`].
+const DEFAULT_DECAY_FACTOR: f64 = 0.99;
+
 /// Tracks current PIR database state
 pub struct StateTracker {
     /// Last synced block
     last_block: Option<u64>,
+    /// Hash of `last_block`, used to detect reorgs on the next poll
+    last_hash: Option<B256>,
     /// In-memory state (address -> slot -> value)
     /// Only used for hot lane tracking
     state: HashMap<Address, HashMap<B256, U256>>,
+    /// Sliding window of recently applied blocks, oldest first
+    window: VecDeque<WindowEntry>,
+    /// Per-`(address, slot)` access weight, borrowing the EIP-2929
+    /// "accessed storage slots" idea: every slot touched by
+    /// [`Self::apply_block`] gets `+1.0`, and every existing weight decays
+    /// by `decay_factor` first, so long-dormant slots age out and the
+    /// manifest builder can see *which* slots of a contract are actually
+    /// hot instead of assuming the whole contract is uniformly hot.
+    slot_weights: HashMap<Address, HashMap<B256, f64>>,
+    /// Per-block multiplier applied to every `slot_weights` entry before
+    /// new accesses are added. See [`Self::with_decay_factor`].
+    decay_factor: f64,
 }
 
 impl StateTracker {
     pub fn new() -> Self {
         Self {
             last_block: None,
+            last_hash: None,
             state: HashMap::new(),
+            window: VecDeque::new(),
+            slot_weights: HashMap::new(),
+            decay_factor: DEFAULT_DECAY_FACTOR,
         }
     }
 
+    /// Set the per-block decay factor applied to slot hotness weights
+    /// (e.g. `0.99` halves a dormant slot's weight roughly every 69
+    /// blocks). Must be in `(0.0, 1.0]`; values outside that range are
+    /// clamped.
+    pub fn with_decay_factor(mut self, factor: f64) -> Self {
+        self.decay_factor = factor.clamp(f64::MIN_POSITIVE, 1.0);
+        self
+    }
+
     pub fn last_block(&self) -> Option<u64> {
         self.last_block
     }
 
-    /// Apply entries and return ones that changed
-    pub fn apply_entries(&mut self, block: u64, entries: Vec<StorageEntry>) -> Vec<StorageEntry> {
+    pub fn last_hash(&self) -> Option<B256> {
+        self.last_hash
+    }
+
+    /// Hash of `block` if it's still inside the retained reorg window.
+    pub fn window_hash(&self, block: u64) -> Option<B256> {
+        self.window.iter().find(|w| w.number == block).map(|w| w.hash)
+    }
+
+    /// Oldest block number still covered by the reorg window, i.e. the
+    /// deepest point a reorg can be rolled back to.
+    pub fn oldest_window_block(&self) -> Option<u64> {
+        self.window.front().map(|w| w.number)
+    }
+
+    /// Apply a single block's deltas, recording undo entries for reorg
+    /// rollback. Returns the entries that actually changed value.
+    pub fn apply_block(
+        &mut self,
+        block: u64,
+        hash: B256,
+        parent_hash: B256,
+        entries: Vec<StorageEntry>,
+    ) -> Vec<StorageEntry> {
         let mut changed = Vec::new();
+        let mut undo = Vec::new();
+
+        for weights in self.slot_weights.values_mut() {
+            for weight in weights.values_mut() {
+                *weight *= self.decay_factor;
+            }
+        }
 
         for entry in entries {
             let slots = self.state.entry(entry.address).or_default();
-            let old_value = slots.insert(entry.slot, entry.value);
+            let previous_value = slots.insert(entry.slot, entry.value);
 
-            // Only track if value actually changed
-            if old_value != Some(entry.value) {
+            *self
+                .slot_weights
+                .entry(entry.address)
+                .or_default()
+                .entry(entry.slot)
+                .or_insert(0.0) += 1.0;
+
+            if previous_value != Some(entry.value) {
+                undo.push(UndoEntry {
+                    address: entry.address,
+                    slot: entry.slot,
+                    previous_value,
+                });
                 changed.push(entry);
             }
         }
 
+        self.window.push_back(WindowEntry {
+            number: block,
+            hash,
+            parent_hash,
+            undo,
+        });
+        while self.window.len() > REORG_WINDOW {
+            self.window.pop_front();
+        }
+
         self.last_block = Some(block);
+        self.last_hash = Some(hash);
         changed
     }
 
-    /// Load full state from dump (for initial sync)
-    pub fn load_from_dump(&mut self, block: u64, entries: Vec<StorageEntry>) {
+    /// Roll back every window block above `common_ancestor`, restoring prior
+    /// slot values (or deleting tombstoned slots), so the caller can replay
+    /// the new canonical chain forward from there. Returns the entries that
+    /// must be rewritten to storage to reflect the rollback.
+    /// `common_ancestor` must still be covered by the window (see
+    /// [`Self::oldest_window_block`]).
+    pub fn handle_reorg(&mut self, common_ancestor: u64) -> Vec<StorageEntry> {
+        let depth = self.last_block.unwrap_or(common_ancestor).saturating_sub(common_ancestor);
+        tracing::warn!(common_ancestor, depth, "Rolling back reorged blocks");
+
+        let mut reverted = Vec::new();
+
+        while let Some(tip) = self.window.back() {
+            if tip.number <= common_ancestor {
+                break;
+            }
+            let tip = self.window.pop_back().expect("checked above");
+
+            for undo in tip.undo.into_iter().rev() {
+                match undo.previous_value {
+                    Some(value) => {
+                        self.state
+                            .entry(undo.address)
+                            .or_default()
+                            .insert(undo.slot, value);
+                        reverted.push(StorageEntry {
+                            address: undo.address,
+                            slot: undo.slot,
+                            value,
+                        });
+                    }
+                    None => {
+                        if let Some(slots) = self.state.get_mut(&undo.address) {
+                            slots.remove(&undo.slot);
+                        }
+                        reverted.push(StorageEntry {
+                            address: undo.address,
+                            slot: undo.slot,
+                            value: U256::ZERO,
+                        });
+                    }
+                }
+            }
+        }
+
+        self.last_block = Some(common_ancestor);
+        self.last_hash = self.window.back().map(|w| w.hash);
+        reverted
+    }
+
+    /// Load full state from dump (for initial sync), anchoring the reorg
+    /// window at `block`/`hash` so the very next poll can detect a reorg.
+    pub fn load_from_dump(&mut self, block: u64, hash: B256, entries: Vec<StorageEntry>) {
+        self.begin_dump();
+        self.ingest_dump_page(&entries);
+        self.finish_dump(block, hash);
+    }
+
+    /// Clear existing state ahead of a streaming initial sync; pair with
+    /// [`Self::ingest_dump_page`] per page and [`Self::finish_dump`] once
+    /// the dump is exhausted, so the full entry set never needs to be
+    /// materialized in one `Vec` just to build the index.
+    pub fn begin_dump(&mut self) {
         self.state.clear();
+        self.window.clear();
+    }
+
+    /// Fold one page of a streaming dump into the index.
+    pub fn ingest_dump_page(&mut self, entries: &[StorageEntry]) {
         for entry in entries {
             self.state
                 .entry(entry.address)
                 .or_default()
                 .insert(entry.slot, entry.value);
         }
+    }
+
+    /// Anchor the reorg window at `block`/`hash` once a (streaming or
+    /// in-memory) dump has been fully ingested, so the very next poll can
+    /// detect a reorg.
+    pub fn finish_dump(&mut self, block: u64, hash: B256) {
         self.last_block = Some(block);
+        self.last_hash = Some(hash);
+        // Anchor entry with no undo: a reorg can never roll back past the
+        // initial sync point, only fall back to re-syncing entirely.
+        self.window.push_back(WindowEntry {
+            number: block,
+            hash,
+            parent_hash: B256::ZERO,
+            undo: Vec::new(),
+        });
     }
 
     #[allow(dead_code)]
     pub fn entry_count(&self) -> usize {
         self.state.values().map(|s| s.len()).sum()
     }
+
+    /// The `n` hottest slots tracked for `address`, by live (decayed)
+    /// weight, descending. Empty if the address has never been touched by
+    /// [`Self::apply_block`].
+    pub fn hot_slots(&self, address: Address, n: usize) -> Vec<(B256, f64)> {
+        let Some(weights) = self.slot_weights.get(&address) else {
+            return Vec::new();
+        };
+        let mut scored: Vec<(B256, f64)> = weights.iter().map(|(&slot, &weight)| (slot, weight)).collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.truncate(n);
+        scored
+    }
+
+    /// Sum of `address`'s live slot weights, the aggregate hotness score a
+    /// contract ranking (e.g. `ContractExtractor::ranked_contracts`) can use
+    /// in place of (or alongside) a raw transaction count.
+    pub fn contract_score(&self, address: Address) -> f64 {
+        self.slot_weights.get(&address).map(|w| w.values().sum()).unwrap_or(0.0)
+    }
 }
 
 impl Default for StateTracker {
@@ -0,0 +1,23 @@
+//! Re-export of the shared `state_proof` crate's MPT proof verifier.
+//!
+//! [`UpdaterService`](crate::UpdaterService) fetches storage deltas via
+//! `pir_dumpStorage`/`pir_getStateDelta` today, which means a compromised
+//! or buggy RPC endpoint can silently poison the PIR database -- PIR
+//! protects the *query*, not the data it was built from.
+//! [`verify_account_proof`]/[`verify_storage_proof`] close that gap by
+//! independently re-checking an `eth_getProof` response against the block's
+//! state root; see the `state_proof` crate for the actual trie-walking
+//! implementation, which is shared with `lane-builder` rather than
+//! duplicated here -- both fetch the same `eth_getProof` shape, so a fix to
+//! one (e.g. the embedded-node handling) now applies to both automatically.
+//!
+//! Kept out of `inspire-core` -- which both `lane-builder` and
+//! `inspire-updater` already depend on -- because `inspire-core`
+//! intentionally carries no `alloy_rlp`/`alloy_primitives` dependency so it
+//! stays embeddable in the wasm client.
+//!
+//! Kept as a same-named module (rather than having callers depend on
+//! `state_proof` directly) so `crate::state_proof::*`/the `inspire_updater`
+//! re-exports in `lib.rs` don't need to change.
+
+pub use state_proof::{verify_account_proof, verify_storage_proof, StateProofError, TrieAccount};
@@ -1,18 +1,43 @@
 //! Writes storage entries to PIR state.bin format
 
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
 use std::fs::File;
-use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use inspire_core::state_format::{StateHeader, StorageEntry, STATE_ENTRY_SIZE, STATE_HEADER_SIZE};
 use inspire_core::ubt::compute_tree_key;
 
 use crate::rpc::StorageEntry as RpcStorageEntry;
 
+/// Number of overflow entries `write_entries` tolerates before folding them
+/// back into the main sorted region via [`ShardWriter::compact_overflow`].
+/// Keeps both the overflow file and the index lookup it requires bounded,
+/// without paying for a k-way merge of the whole (potentially
+/// cold-lane-sized) file on every single block.
+const OVERFLOW_COMPACTION_THRESHOLD: u64 = 50_000;
+
+/// In-memory `tree_key -> ordinal` lookup for `state.bin`'s main sorted
+/// region and its `state.overflow.bin` companion, so `write_entries` can
+/// seek straight to an existing entry instead of scanning the file. Built
+/// once per `ShardWriter` (see `ShardWriter::index`) by scanning both files;
+/// `compact_overflow` rebuilds it after folding the overflow back in.
+struct TreeKeyIndex {
+    main: HashMap<[u8; 32], u64>,
+    main_len: u64,
+    overflow: HashMap<[u8; 32], u64>,
+    overflow_len: u64,
+}
+
 /// Writes storage entries to PIR shard files
 pub struct ShardWriter {
     data_dir: std::path::PathBuf,
     chain_id: u64,
+    /// Lazily built the first time `write_entries` runs against a given
+    /// `state.bin`; see [`TreeKeyIndex`].
+    index: Mutex<Option<TreeKeyIndex>>,
 }
 
 impl ShardWriter {
@@ -20,9 +45,20 @@ impl ShardWriter {
         Self {
             data_dir: data_dir.as_ref().to_path_buf(),
             chain_id,
+            index: Mutex::new(None),
         }
     }
 
+    fn main_path(&self) -> PathBuf {
+        self.data_dir.join("state.bin")
+    }
+
+    /// Overflow entries are tree_keys not yet present in the main sorted
+    /// region: appended here until `compact_overflow` merges them in.
+    fn overflow_path(&self) -> PathBuf {
+        self.data_dir.join("state.overflow.bin")
+    }
+
     /// Compute EIP-7864 tree_key (stem || subindex) for UBT-ordered sorting
     fn compute_entry_tree_key(entry: &StorageEntry) -> [u8; 32] {
         compute_tree_key(&entry.address, &entry.tree_index)
@@ -46,7 +82,7 @@ impl ShardWriter {
     ) -> anyhow::Result<std::path::PathBuf> {
         std::fs::create_dir_all(&self.data_dir)?;
 
-        let output_path = self.data_dir.join("state.bin");
+        let output_path = self.main_path();
 
         // Convert and sort entries by tree_key (EIP-7864 ordering)
         let mut sorted_entries: Vec<_> = entries
@@ -92,16 +128,226 @@ impl ShardWriter {
         Ok(output_path)
     }
 
-    /// Write entries (convenience wrapper for incremental updates)
-    pub async fn write_entries(&self, entries: &[RpcStorageEntry]) -> anyhow::Result<()> {
+    /// Apply a block's changed/added storage entries to `state.bin` without
+    /// rewriting the whole file. Each entry either patches an existing
+    /// record in place (tracked slot, or a tree_key already folded into the
+    /// main sorted region) or is appended to `state.overflow.bin` (a new
+    /// tree_key); the overflow is periodically folded back into the main
+    /// region by [`Self::compact_overflow`]. `block_number`/`block_hash`
+    /// become the new header once the batch is applied.
+    pub async fn write_entries(
+        &self,
+        entries: &[RpcStorageEntry],
+        block_number: u64,
+        block_hash: [u8; 32],
+    ) -> anyhow::Result<()> {
         if entries.is_empty() {
             return Ok(());
         }
 
+        let main_path = self.main_path();
+        if !main_path.exists() {
+            anyhow::bail!(
+                "{} does not exist yet -- run initial_sync before applying incremental deltas",
+                main_path.display()
+            );
+        }
+
+        let mut index_guard = self.index.lock().expect("tree-key index mutex poisoned");
+        if index_guard.is_none() {
+            *index_guard = Some(self.load_index()?);
+        }
+        let index = index_guard.as_mut().expect("populated above");
+
+        let mut main_file = std::fs::OpenOptions::new().read(true).write(true).open(&main_path)?;
+        // Opened only once an entry actually goes to the overflow region.
+        let mut overflow_file: Option<File> = None;
+
+        let mut patched = 0u64;
+        let mut appended = 0u64;
+
+        for entry in entries {
+            let core = Self::to_core_entry(entry);
+            let tree_key = Self::compute_entry_tree_key(&core);
+            let bytes = core.to_bytes();
+
+            if let Some(&ordinal) = index.main.get(&tree_key) {
+                let offset = STATE_HEADER_SIZE as u64 + ordinal * STATE_ENTRY_SIZE as u64;
+                main_file.seek(SeekFrom::Start(offset))?;
+                main_file.write_all(&bytes)?;
+                patched += 1;
+                continue;
+            }
+
+            if overflow_file.is_none() {
+                overflow_file = Some(
+                    std::fs::OpenOptions::new().create(true).read(true).write(true).open(self.overflow_path())?,
+                );
+            }
+            let overflow = overflow_file.as_mut().expect("opened above");
+            if let Some(&ordinal) = index.overflow.get(&tree_key) {
+                overflow.seek(SeekFrom::Start(ordinal * STATE_ENTRY_SIZE as u64))?;
+                overflow.write_all(&bytes)?;
+                patched += 1;
+            } else {
+                overflow.seek(SeekFrom::End(0))?;
+                overflow.write_all(&bytes)?;
+                index.overflow.insert(tree_key, index.overflow_len);
+                index.overflow_len += 1;
+                appended += 1;
+            }
+        }
+
+        main_file.sync_data()?;
+        if let Some(overflow) = &overflow_file {
+            overflow.sync_data()?;
+        }
+
         tracing::info!(
-            count = entries.len(),
-            data_dir = %self.data_dir.display(),
-            "Incremental update (full rewrite needed for state.bin)"
+            patched,
+            appended,
+            overflow_len = index.overflow_len,
+            block = block_number,
+            "Applied incremental deltas to state.bin"
+        );
+
+        if index.overflow_len >= OVERFLOW_COMPACTION_THRESHOLD {
+            drop(overflow_file);
+            drop(main_file);
+            self.compact_overflow(index, block_number, block_hash)?;
+        } else {
+            Self::write_header(&mut main_file, index.main_len, block_number, self.chain_id, block_hash)?;
+        }
+
+        Ok(())
+    }
+
+    /// Scan `state.bin` and `state.overflow.bin` (if present) once to build
+    /// the `tree_key -> ordinal` lookup `write_entries` needs to patch in
+    /// place. Only runs the first time a given `ShardWriter` applies a
+    /// delta; subsequent calls reuse the cached index.
+    fn load_index(&self) -> anyhow::Result<TreeKeyIndex> {
+        let (main, main_len) = Self::scan_entries(&self.main_path(), STATE_HEADER_SIZE as u64)?;
+
+        let overflow_path = self.overflow_path();
+        let (overflow, overflow_len) = if overflow_path.exists() {
+            Self::scan_entries(&overflow_path, 0)?
+        } else {
+            (HashMap::new(), 0)
+        };
+
+        Ok(TreeKeyIndex { main, main_len, overflow, overflow_len })
+    }
+
+    /// Read every fixed-size record in `path` starting at byte `skip`,
+    /// returning its `tree_key -> ordinal` map and the record count.
+    fn scan_entries(path: &Path, skip: u64) -> anyhow::Result<(HashMap<[u8; 32], u64>, u64)> {
+        let mut map = HashMap::new();
+        let mut count = 0u64;
+
+        let mut reader = BufReader::new(File::open(path)?);
+        if skip > 0 {
+            reader.seek(SeekFrom::Start(skip))?;
+        }
+
+        let mut buf = [0u8; STATE_ENTRY_SIZE];
+        loop {
+            match reader.read_exact(&mut buf) {
+                Ok(()) => {
+                    let entry = StorageEntry::from_bytes(&buf)?;
+                    map.insert(Self::compute_entry_tree_key(&entry), count);
+                    count += 1;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok((map, count))
+    }
+
+    /// Overwrite just the fixed 128-byte header in place. Unlike
+    /// `write_state_file`/`merge_runs` (which rebuild the whole body and
+    /// swap it in via a temp-path rename), an incremental patch only ever
+    /// touches existing bytes or appends past EOF -- the body is never torn
+    /// by these writes -- so the header is the only part that needs an
+    /// atomic-enough update per block, and a single aligned write+fsync at
+    /// a fixed offset is that; a full-file tmp+rename here would cost an
+    /// O(N) copy on every block, defeating the point of patching in place.
+    fn write_header(
+        file: &mut File,
+        entry_count: u64,
+        block_number: u64,
+        chain_id: u64,
+        block_hash: [u8; 32],
+    ) -> anyhow::Result<()> {
+        let header = StateHeader::new(entry_count, block_number, chain_id, block_hash);
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&header.to_bytes())?;
+        file.sync_all()?;
+        Ok(())
+    }
+
+    /// Fold `state.overflow.bin` back into the main sorted region once it
+    /// grows past [`OVERFLOW_COMPACTION_THRESHOLD`], by sorting it and
+    /// k-way merging it against the existing main file via [`Self::merge_runs`]
+    /// (the same machinery `StreamingStateBuilder::finish` uses for the
+    /// initial sync). `index` is rebuilt afterwards since every ordinal
+    /// shifts once the overflow is folded in.
+    fn compact_overflow(
+        &self,
+        index: &mut TreeKeyIndex,
+        block_number: u64,
+        block_hash: [u8; 32],
+    ) -> anyhow::Result<()> {
+        if index.overflow_len == 0 {
+            return Ok(());
+        }
+
+        let overflow_path = self.overflow_path();
+        let mut reader = BufReader::new(File::open(&overflow_path)?);
+        let mut sorted: Vec<([u8; 32], StorageEntry)> = Vec::with_capacity(index.overflow_len as usize);
+        let mut buf = [0u8; STATE_ENTRY_SIZE];
+        loop {
+            match reader.read_exact(&mut buf) {
+                Ok(()) => {
+                    let entry = StorageEntry::from_bytes(&buf)?;
+                    sorted.push((Self::compute_entry_tree_key(&entry), entry));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        drop(reader);
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let sorted_run_path = self.data_dir.join("state.overflow.sorted.tmp");
+        {
+            let mut writer = BufWriter::new(File::create(&sorted_run_path)?);
+            for (_, entry) in &sorted {
+                writer.write_all(&entry.to_bytes())?;
+            }
+            writer.flush()?;
+        }
+
+        let main_path = self.main_path();
+        let entry_count = self.merge_runs(
+            &[(main_path.clone(), STATE_HEADER_SIZE as u64), (sorted_run_path.clone(), 0)],
+            &main_path,
+            block_number,
+            self.chain_id,
+            block_hash,
+        )?;
+
+        std::fs::remove_file(&sorted_run_path).ok();
+        std::fs::remove_file(&overflow_path).ok();
+
+        *index = self.load_index()?;
+
+        tracing::info!(
+            entries = entry_count,
+            block = block_number,
+            "Compacted overflow entries into main state.bin region"
         );
 
         Ok(())
@@ -125,6 +371,228 @@ impl ShardWriter {
     ) -> anyhow::Result<std::path::PathBuf> {
         self.write_state_file(entries, block_number, ubt_root)
     }
+
+    /// Start a streaming `state.bin` build: pair with
+    /// [`StreamingStateBuilder::push_page`] per page of a dump (e.g. from
+    /// [`crate::EthrexClient::stream_storage`]) and
+    /// [`StreamingStateBuilder::finish`] once it's exhausted, so the caller
+    /// can drive the same page loop that feeds `StateTracker`'s index
+    /// without ever materializing the full dump in one `Vec`.
+    ///
+    /// `run_budget_bytes` bounds how much is buffered in memory before a
+    /// sorted run is flushed to a temporary file (pass
+    /// `TwoLaneConfig::shard_size_bytes`); peak memory stays around one run
+    /// rather than the whole state.
+    pub fn streaming_state_builder(&self, run_budget_bytes: u64) -> anyhow::Result<StreamingStateBuilder<'_>> {
+        std::fs::create_dir_all(&self.data_dir)?;
+        Ok(StreamingStateBuilder {
+            writer: self,
+            run_budget_entries: (run_budget_bytes as usize / STATE_ENTRY_SIZE).max(1),
+            run_paths: Vec::new(),
+            run_buf: Vec::new(),
+        })
+    }
+
+    /// Streaming k-way merge of already-sorted run files into `output_path`,
+    /// prefixed with a freshly computed [`StateHeader`]. Each run is given
+    /// as `(path, skip_bytes)`: `skip_bytes` is `0` for a headerless sort
+    /// run, or [`STATE_HEADER_SIZE`] to read an existing `state.bin` as a
+    /// run in place (used by [`Self::compact_overflow`], which merges the
+    /// main file with the sorted overflow run and writes the result back
+    /// over the main file). `output_path` may equal one of the run
+    /// paths -- the rename only happens after every run has been read to
+    /// completion. Returns the number of entries written.
+    fn merge_runs(
+        &self,
+        runs: &[(PathBuf, u64)],
+        output_path: &Path,
+        block_number: u64,
+        chain_id: u64,
+        block_hash: [u8; 32],
+    ) -> anyhow::Result<u64> {
+        struct RunCursor {
+            reader: BufReader<File>,
+            head: Option<StorageEntry>,
+        }
+
+        impl RunCursor {
+            fn open(path: &Path, skip_bytes: u64) -> anyhow::Result<Option<Self>> {
+                let mut reader = BufReader::new(File::open(path)?);
+                if skip_bytes > 0 {
+                    reader.seek(SeekFrom::Start(skip_bytes))?;
+                }
+                let mut cursor = Self { reader, head: None };
+                cursor.advance()?;
+                Ok(if cursor.head.is_some() { Some(cursor) } else { None })
+            }
+
+            fn advance(&mut self) -> anyhow::Result<()> {
+                let mut buf = [0u8; STATE_ENTRY_SIZE];
+                match self.reader.read_exact(&mut buf) {
+                    Ok(()) => {
+                        self.head = Some(StorageEntry::from_bytes(&buf)?);
+                        Ok(())
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                        self.head = None;
+                        Ok(())
+                    }
+                    Err(e) => Err(e.into()),
+                }
+            }
+        }
+
+        struct HeapItem {
+            tree_key: [u8; 32],
+            cursor_idx: usize,
+        }
+        impl PartialEq for HeapItem {
+            fn eq(&self, other: &Self) -> bool {
+                self.tree_key == other.tree_key && self.cursor_idx == other.cursor_idx
+            }
+        }
+        impl Eq for HeapItem {}
+        impl PartialOrd for HeapItem {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for HeapItem {
+            fn cmp(&self, other: &Self) -> Ordering {
+                // BinaryHeap is a max-heap; reverse so the smallest key pops first.
+                // Among equal keys the later run (higher cursor_idx) pops first.
+                other
+                    .tree_key
+                    .cmp(&self.tree_key)
+                    .then_with(|| self.cursor_idx.cmp(&other.cursor_idx))
+            }
+        }
+
+        let mut cursors: Vec<RunCursor> = Vec::new();
+        for (path, skip_bytes) in runs {
+            if let Some(cursor) = RunCursor::open(path, *skip_bytes)? {
+                cursors.push(cursor);
+            }
+        }
+
+        let mut heap: BinaryHeap<HeapItem> = BinaryHeap::new();
+        for (idx, cursor) in cursors.iter().enumerate() {
+            if let Some(entry) = &cursor.head {
+                heap.push(HeapItem { tree_key: Self::compute_entry_tree_key(entry), cursor_idx: idx });
+            }
+        }
+
+        let tmp_path = output_path.with_extension("bin.tmp");
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        writer.write_all(&[0u8; STATE_HEADER_SIZE])?;
+
+        let mut entry_count: u64 = 0;
+        let mut last_key: Option<[u8; 32]> = None;
+
+        while let Some(item) = heap.pop() {
+            let cursor = &mut cursors[item.cursor_idx];
+            let entry = cursor.head.take().expect("heap item's cursor always has a head");
+            cursor.advance()?;
+            if let Some(next) = &cursor.head {
+                heap.push(HeapItem { tree_key: Self::compute_entry_tree_key(next), cursor_idx: item.cursor_idx });
+            }
+
+            if last_key == Some(item.tree_key) {
+                // Duplicate tree_key across runs (shouldn't happen for a
+                // single point-in-time dump, but keep the later-flushed
+                // run's value rather than erroring).
+                continue;
+            }
+            last_key = Some(item.tree_key);
+
+            writer.write_all(&entry.to_bytes())?;
+            entry_count += 1;
+        }
+
+        writer.flush()?;
+        drop(writer);
+
+        let header = StateHeader::new(entry_count, block_number, chain_id, block_hash);
+        let mut file = std::fs::OpenOptions::new().write(true).open(&tmp_path)?;
+        file.write_all(&header.to_bytes())?;
+        drop(file);
+
+        std::fs::rename(&tmp_path, output_path)?;
+        Ok(entry_count)
+    }
+}
+
+/// Incrementally builds `state.bin` from a page-at-a-time dump, bounding
+/// peak memory to roughly one sort run. See
+/// [`ShardWriter::streaming_state_builder`].
+pub struct StreamingStateBuilder<'a> {
+    writer: &'a ShardWriter,
+    run_budget_entries: usize,
+    run_paths: Vec<PathBuf>,
+    run_buf: Vec<([u8; 32], StorageEntry)>,
+}
+
+impl<'a> StreamingStateBuilder<'a> {
+    /// Fold one page's entries into the current run, flushing it to a
+    /// sorted temporary file once it reaches the configured budget.
+    pub fn push_page(&mut self, entries: &[RpcStorageEntry]) -> anyhow::Result<()> {
+        for entry in entries {
+            let core = ShardWriter::to_core_entry(entry);
+            let tree_key = ShardWriter::compute_entry_tree_key(&core);
+            self.run_buf.push((tree_key, core));
+        }
+
+        if self.run_buf.len() >= self.run_budget_entries {
+            self.flush_run()?;
+        }
+        Ok(())
+    }
+
+    /// Flush any remaining buffered entries, then k-way merge every run
+    /// into the final, fully sorted `state.bin` and clean up the runs.
+    pub fn finish(mut self, block_number: u64, block_hash: [u8; 32]) -> anyhow::Result<PathBuf> {
+        if !self.run_buf.is_empty() {
+            self.flush_run()?;
+        }
+
+        let output_path = self.writer.main_path();
+        let runs: Vec<(PathBuf, u64)> = self.run_paths.iter().map(|p| (p.clone(), 0)).collect();
+        let entry_count =
+            self.writer
+                .merge_runs(&runs, &output_path, block_number, self.writer.chain_id, block_hash)?;
+
+        for run_path in &self.run_paths {
+            let _ = std::fs::remove_file(run_path);
+        }
+
+        let file_size = STATE_HEADER_SIZE + entry_count as usize * STATE_ENTRY_SIZE;
+        tracing::info!(
+            path = %output_path.display(),
+            entries = entry_count,
+            size_mb = file_size / (1024 * 1024),
+            runs = self.run_paths.len(),
+            block = block_number,
+            "Wrote state.bin via streaming initial sync"
+        );
+
+        Ok(output_path)
+    }
+
+    fn flush_run(&mut self) -> anyhow::Result<()> {
+        self.run_buf.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let run_path = self.writer.data_dir.join(format!("state.run.{}.tmp", self.run_paths.len()));
+        let mut writer = BufWriter::new(File::create(&run_path)?);
+        for (_, entry) in self.run_buf.iter() {
+            writer.write_all(&entry.to_bytes())?;
+        }
+        writer.flush()?;
+
+        tracing::debug!(path = %run_path.display(), entries = self.run_buf.len(), "Flushed sort run");
+        self.run_buf.clear();
+        self.run_paths.push(run_path);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -193,4 +661,67 @@ mod tests {
 
         std::fs::remove_dir_all(&temp_dir).ok();
     }
+
+    #[tokio::test]
+    async fn test_write_entries_patches_existing_slot_in_place() {
+        let temp_dir = std::env::temp_dir().join("inspire-updater-test-patch");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+
+        let writer = ShardWriter::new(&temp_dir, 11155111);
+        let entry = RpcStorageEntry {
+            address: Address::repeat_byte(0x42),
+            slot: B256::repeat_byte(0x01),
+            value: U256::from(100),
+        };
+        writer.write_state_file(&[entry.clone()], 1000, [0u8; 32]).unwrap();
+
+        let updated = RpcStorageEntry { value: U256::from(999), ..entry };
+        writer.write_entries(&[updated], 1001, [0x11; 32]).await.unwrap();
+
+        // Still one entry: the update patched the existing slot rather than
+        // landing in the overflow file.
+        assert!(!writer.overflow_path().exists());
+
+        let data = std::fs::read(writer.main_path()).unwrap();
+        let header = StateHeader::from_bytes(&data).unwrap();
+        assert_eq!(header.entry_count, 1);
+        assert_eq!(header.block_number, 1001);
+
+        let patched = StorageEntry::from_bytes(&data[STATE_HEADER_SIZE..]).unwrap();
+        assert_eq!(patched.value, U256::from(999).to_be_bytes::<32>());
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_write_entries_appends_new_slot_to_overflow() {
+        let temp_dir = std::env::temp_dir().join("inspire-updater-test-overflow");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+
+        let writer = ShardWriter::new(&temp_dir, 11155111);
+        let existing = RpcStorageEntry {
+            address: Address::repeat_byte(0x42),
+            slot: B256::repeat_byte(0x01),
+            value: U256::from(100),
+        };
+        writer.write_state_file(&[existing], 1000, [0u8; 32]).unwrap();
+
+        let new_entry = RpcStorageEntry {
+            address: Address::repeat_byte(0x43),
+            slot: B256::repeat_byte(0x02),
+            value: U256::from(200),
+        };
+        writer.write_entries(&[new_entry], 1001, [0x22; 32]).await.unwrap();
+
+        // The main file's entry_count is unchanged -- the new slot landed in
+        // the overflow file pending compaction.
+        let data = std::fs::read(writer.main_path()).unwrap();
+        let header = StateHeader::from_bytes(&data).unwrap();
+        assert_eq!(header.entry_count, 1);
+
+        let overflow = std::fs::read(writer.overflow_path()).unwrap();
+        assert_eq!(overflow.len(), STATE_ENTRY_SIZE);
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
 }
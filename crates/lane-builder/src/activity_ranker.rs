@@ -0,0 +1,271 @@
+//! Hot lane discovery via on-chain activity ranking
+//!
+//! `contracts::HOT_CONTRACTS` is a frozen, curated snapshot -- a new router
+//! or restaking contract doesn't show up there until someone edits the list
+//! by hand, even though PIR cost is dominated by which contracts actually
+//! get queried. [`ActivityRanker`] instead tallies `to`-address call counts
+//! over a rolling block window pulled from a live node, confirms each
+//! top-ranked candidate is actually a contract (`eth_getCode`), and hands
+//! back [`ContractStats`] that [`ContractExtractor::add_contract`] merges
+//! on top of the curated list the same way `HotLaneBuilder::load_scored_contracts`
+//! does for backfill-derived scores.
+//!
+//! [`ContractExtractor::add_contract`]: crate::extractor::ContractExtractor::add_contract
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use inspire_core::Address;
+
+use crate::contracts::{ContractInfo, HOT_CONTRACTS};
+use crate::extractor::ContractStats;
+
+/// Minimal on-chain surface [`ActivityRanker`] needs. Kept narrow (rather
+/// than depending on the full `alloy_provider::Provider` trait) so tests
+/// can implement it with a canned mock instead of spinning up a node.
+pub trait ActivityProvider: Send + Sync {
+    /// Current chain head.
+    async fn block_number(&self) -> anyhow::Result<u64>;
+    /// The `to` address of every transaction in `block`, in order
+    /// (`None` for contract-creation transactions).
+    async fn block_call_targets(&self, block: u64) -> anyhow::Result<Vec<Option<Address>>>;
+    /// Whether `address` has contract code (`eth_getCode` non-empty).
+    async fn has_code(&self, address: Address) -> anyhow::Result<bool>;
+    /// A rough storage footprint for `address`, e.g. derived from an
+    /// `eth_getProof` trie-node count. Implementations that can't estimate
+    /// this cheaply may return `None`.
+    async fn estimate_storage_slots(&self, address: Address) -> anyhow::Result<Option<u64>>;
+}
+
+/// Tuning for [`ActivityRanker`].
+#[derive(Debug, Clone, Copy)]
+pub struct ActivityRankerConfig {
+    /// EIP-155 chain ID the [`ActivityProvider`] is connected to. Stamped
+    /// onto freshly-discovered [`ContractInfo`] entries; curated entries
+    /// keep their own `chain_id` from [`crate::contracts::KnownContract`].
+    pub chain_id: u64,
+    /// How many blocks back from the chain head to scan for activity.
+    pub window_blocks: u64,
+    /// How many of the top-ranked addresses to keep as candidates.
+    pub top_n: usize,
+    /// How often a long-running service should call [`ActivityRanker::discover_contracts`]
+    /// again. Not enforced here -- callers (e.g. an ExEx or a cron task)
+    /// own the scheduling loop; this just documents the intended cadence.
+    pub refresh_interval: Duration,
+}
+
+impl Default for ActivityRankerConfig {
+    fn default() -> Self {
+        Self {
+            chain_id: crate::contracts::MAINNET_CHAIN_ID,
+            window_blocks: 7200, // ~1 day of mainnet blocks
+            top_n: 50,
+            refresh_interval: Duration::from_secs(3600),
+        }
+    }
+}
+
+/// Ranks contracts by recent on-chain call activity over an [`ActivityProvider`].
+pub struct ActivityRanker<P> {
+    provider: P,
+    config: ActivityRankerConfig,
+}
+
+impl<P: ActivityProvider> ActivityRanker<P> {
+    pub fn new(provider: P, config: ActivityRankerConfig) -> Self {
+        Self { provider, config }
+    }
+
+    /// Tally `to`-address call counts over `[head - window_blocks, head]`,
+    /// descending by count, truncated to `top_n`.
+    pub async fn rank_by_activity(&self) -> anyhow::Result<Vec<(Address, u64)>> {
+        let head = self.provider.block_number().await?;
+        let start = head.saturating_sub(self.config.window_blocks);
+
+        let mut counts: HashMap<Address, u64> = HashMap::new();
+        for block in start..=head {
+            let targets = self.provider.block_call_targets(block).await?;
+            for target in targets.into_iter().flatten() {
+                *counts.entry(target).or_insert(0) += 1;
+            }
+        }
+
+        let mut ranked: Vec<_> = counts.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked.truncate(self.config.top_n);
+        Ok(ranked)
+    }
+
+    /// Rank live activity and confirm each top address is actually a
+    /// contract, emitting [`ContractStats`] ready for
+    /// [`ContractExtractor::add_contract`](crate::extractor::ContractExtractor::add_contract).
+    /// Addresses `eth_getCode` reports as EOAs (no code) are dropped --
+    /// ranking by call count alone can't tell an EOA from a contract.
+    pub async fn discover_contracts(&self) -> anyhow::Result<Vec<ContractStats>> {
+        let mut discovered = Vec::new();
+
+        for (address, tx_count) in self.rank_by_activity().await? {
+            if !self.provider.has_code(address).await? {
+                continue;
+            }
+            let storage_slots = self.provider.estimate_storage_slots(address).await?.unwrap_or(0);
+
+            discovered.push(ContractStats {
+                address,
+                name: format!("0x{}", hex::encode(address)),
+                category: "unclassified".to_string(),
+                tx_count,
+                storage_slots,
+                hot_slots: Vec::new(),
+                storage_layout: None,
+            });
+        }
+
+        Ok(discovered)
+    }
+
+    /// [`Self::discover_contracts`], merged on top of the curated
+    /// [`HOT_CONTRACTS`] list as [`ContractInfo`] -- a curated entry's
+    /// name/category always wins (it's hand-verified); only the discovered
+    /// `tx_count`/`storage_slots` are grafted on, so the hot lane adapts to
+    /// real traffic without losing its curated labels.
+    pub async fn refresh_contract_info(&self) -> anyhow::Result<Vec<ContractInfo>> {
+        let discovered = self.discover_contracts().await?;
+        Ok(merge_with_curated(discovered, self.config.chain_id))
+    }
+}
+
+/// Merge freshly-discovered [`ContractStats`] on top of the curated list
+/// for `chain_id`, keyed by address.
+fn merge_with_curated(discovered: Vec<ContractStats>, chain_id: u64) -> Vec<ContractInfo> {
+    let mut by_address: HashMap<Address, ContractInfo> = crate::contracts::hot_contracts(chain_id)
+        .iter()
+        .map(|known| (known.address, crate::hot_lane_config::known_to_info(known)))
+        .collect();
+
+    for stats in discovered {
+        by_address
+            .entry(stats.address)
+            .and_modify(|existing| {
+                existing.tx_count = Some(stats.tx_count);
+                existing.storage_slots = Some(stats.storage_slots);
+            })
+            .or_insert(ContractInfo {
+                address: stats.address,
+                name: stats.name,
+                category: stats.category,
+                chain_id,
+                tx_count: Some(stats.tx_count),
+                storage_slots: Some(stats.storage_slots),
+                storage_layout: None,
+            });
+    }
+
+    by_address.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap as StdHashMap;
+    use std::sync::Mutex;
+
+    /// Canned [`ActivityProvider`] over an in-memory block-to-targets map,
+    /// so tests don't need a live node.
+    struct MockActivityProvider {
+        head: u64,
+        blocks: StdHashMap<u64, Vec<Option<Address>>>,
+        contracts: Mutex<std::collections::HashSet<Address>>,
+    }
+
+    impl ActivityProvider for MockActivityProvider {
+        async fn block_number(&self) -> anyhow::Result<u64> {
+            Ok(self.head)
+        }
+
+        async fn block_call_targets(&self, block: u64) -> anyhow::Result<Vec<Option<Address>>> {
+            Ok(self.blocks.get(&block).cloned().unwrap_or_default())
+        }
+
+        async fn has_code(&self, address: Address) -> anyhow::Result<bool> {
+            Ok(self.contracts.lock().unwrap().contains(&address))
+        }
+
+        async fn estimate_storage_slots(&self, _address: Address) -> anyhow::Result<Option<u64>> {
+            Ok(Some(42))
+        }
+    }
+
+    fn addr(byte: u8) -> Address {
+        [byte; 20]
+    }
+
+    #[tokio::test]
+    async fn test_rank_by_activity_counts_and_truncates() {
+        let mut blocks = StdHashMap::new();
+        blocks.insert(10u64, vec![Some(addr(1)), Some(addr(2)), None]);
+        blocks.insert(11u64, vec![Some(addr(1)), Some(addr(1))]);
+
+        let provider = MockActivityProvider {
+            head: 11,
+            blocks,
+            contracts: Mutex::new(std::collections::HashSet::new()),
+        };
+        let ranker = ActivityRanker::new(
+            provider,
+            ActivityRankerConfig {
+                chain_id: crate::contracts::MAINNET_CHAIN_ID,
+                window_blocks: 1,
+                top_n: 1,
+                refresh_interval: Duration::from_secs(1),
+            },
+        );
+
+        let ranked = ranker.rank_by_activity().await.unwrap();
+        assert_eq!(ranked, vec![(addr(1), 3)]);
+    }
+
+    #[tokio::test]
+    async fn test_discover_contracts_excludes_eoas() {
+        let mut blocks = StdHashMap::new();
+        blocks.insert(5u64, vec![Some(addr(1)), Some(addr(2))]);
+
+        let mut contracts = std::collections::HashSet::new();
+        contracts.insert(addr(1));
+
+        let provider = MockActivityProvider {
+            head: 5,
+            blocks,
+            contracts: Mutex::new(contracts),
+        };
+        let ranker = ActivityRanker::new(
+            provider,
+            ActivityRankerConfig {
+                chain_id: crate::contracts::MAINNET_CHAIN_ID,
+                window_blocks: 0,
+                top_n: 10,
+                refresh_interval: Duration::from_secs(1),
+            },
+        );
+
+        let discovered = ranker.discover_contracts().await.unwrap();
+        assert_eq!(discovered.len(), 1);
+        assert_eq!(discovered[0].address, addr(1));
+        assert_eq!(discovered[0].storage_slots, 42);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_contract_info_preserves_curated_names() {
+        let provider = MockActivityProvider {
+            head: 0,
+            blocks: StdHashMap::new(),
+            contracts: Mutex::new(std::collections::HashSet::new()),
+        };
+        let ranker = ActivityRanker::new(provider, ActivityRankerConfig::default());
+
+        let merged = ranker.refresh_contract_info().await.unwrap();
+        assert_eq!(merged.len(), HOT_CONTRACTS.len());
+        let usdc = merged.iter().find(|c| c.name == "USDC").unwrap();
+        assert_eq!(usdc.address, HOT_CONTRACTS[0].address);
+    }
+}
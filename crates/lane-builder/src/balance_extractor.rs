@@ -4,14 +4,19 @@
 
 use std::path::Path;
 
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{keccak256, Address, B256, U256};
 use alloy_provider::Provider;
-use alloy_rpc_types::BlockId;
+use alloy_rpc_types::{BlockId, BlockNumberOrTag};
 use futures::future::join_all;
 use indicatif::{ProgressBar, ProgressStyle};
 use serde::{Deserialize, Serialize};
 
-use inspire_core::{BalanceDbMetadata, BalanceRecord, BALANCE_RECORD_SIZE};
+use inspire_core::{
+    balance_merkle_path, balance_merkle_root, balance_padded_entry_size, BalanceDbMetadata, BalanceRecord,
+    BALANCE_RECORD_SIZE,
+};
+
+use crate::state_proof::{verify_account_proof, verify_storage_proof};
 
 const USDC_BALANCE_OF_SELECTOR: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
 
@@ -21,6 +26,18 @@ pub struct BalanceExtractorConfig {
     pub usdc_contract: Address,
     pub batch_size: usize,
     pub max_concurrent: usize,
+    /// Storage slot index of the `balances` mapping in the USDC contract's
+    /// layout, used to derive `eth_getProof` storage keys for verified
+    /// extraction (see [`BalanceExtractor::build_database_verified`]).
+    /// Defaults to `9`, the slot used by mainnet USDC's `FiatTokenV2`
+    /// implementation -- a testnet or mock deployment may differ and should
+    /// override this.
+    #[serde(default = "default_usdc_balance_slot_index")]
+    pub usdc_balance_slot_index: u64,
+}
+
+fn default_usdc_balance_slot_index() -> u64 {
+    9
 }
 
 impl Default for BalanceExtractorConfig {
@@ -32,6 +49,7 @@ impl Default for BalanceExtractorConfig {
                 .unwrap(),
             batch_size: 100,
             max_concurrent: 10,
+            usdc_balance_slot_index: default_usdc_balance_slot_index(),
         }
     }
 }
@@ -45,6 +63,7 @@ impl BalanceExtractorConfig {
                 .unwrap(),
             batch_size: 100,
             max_concurrent: 10,
+            usdc_balance_slot_index: default_usdc_balance_slot_index(),
         }
     }
 
@@ -56,8 +75,19 @@ impl BalanceExtractorConfig {
                 .unwrap(),
             batch_size: 100,
             max_concurrent: 10,
+            usdc_balance_slot_index: default_usdc_balance_slot_index(),
         }
     }
+
+    /// Derive the storage key for `balances[address]`, assuming a Solidity
+    /// `mapping(address => uint256)` at `usdc_balance_slot_index` (the
+    /// standard single-slot mapping layout: `keccak256(address ++ slot)`).
+    fn usdc_balance_slot(&self, address: Address) -> B256 {
+        let mut preimage = [0u8; 64];
+        preimage[12..32].copy_from_slice(address.as_slice());
+        preimage[32..64].copy_from_slice(&U256::from(self.usdc_balance_slot_index).to_be_bytes::<32>());
+        keccak256(preimage)
+    }
 }
 
 pub struct BalanceExtractor<P> {
@@ -158,10 +188,21 @@ impl<P: Provider + Clone + Send + Sync + 'static> BalanceExtractor<P> {
         let block = BlockId::number(block_number);
         let records = self.extract_balances(addresses, block).await?;
 
+        // Anchor each record to a Merkle root so a PIR response carries its
+        // own authentication path -- see `inspire_core::balance_merkle`.
+        let root = balance_merkle_root(&records);
+        let padded_entry_size = balance_padded_entry_size(records.len());
+
         let db_path = output_dir.join("balances.bin");
-        let mut db_data = Vec::with_capacity(records.len() * BALANCE_RECORD_SIZE);
-        for record in &records {
-            db_data.extend_from_slice(&record.to_bytes());
+        let mut db_data = Vec::with_capacity(records.len() * padded_entry_size);
+        for (i, record) in records.iter().enumerate() {
+            let path = balance_merkle_path(&records, i);
+            let mut entry = record.to_bytes().to_vec();
+            for sibling in &path.siblings {
+                entry.extend_from_slice(sibling);
+            }
+            debug_assert_eq!(entry.len(), padded_entry_size);
+            db_data.extend_from_slice(&entry);
         }
         std::fs::write(&db_path, &db_data)?;
 
@@ -173,6 +214,10 @@ impl<P: Provider + Clone + Send + Sync + 'static> BalanceExtractor<P> {
             record_size: BALANCE_RECORD_SIZE,
             num_records: records.len(),
             addresses: addresses.iter().map(|a| format!("{:?}", a)).collect(),
+            merkle_root: hex::encode(root),
+            padded_entry_size,
+            state_root: String::new(),
+            beacon_checkpoint_root: String::new(),
         };
 
         let metadata_path = output_dir.join("metadata.json");
@@ -188,6 +233,201 @@ impl<P: Provider + Clone + Send + Sync + 'static> BalanceExtractor<P> {
 
         Ok(metadata)
     }
+
+    /// Fetch the `stateRoot` of `block_number`, used to anchor a verifying
+    /// build (see [`Self::build_database_verified`]).
+    async fn fetch_state_root(&self, block_number: u64) -> anyhow::Result<B256> {
+        let block = self
+            .provider
+            .get_block_by_number(BlockNumberOrTag::Number(block_number))
+            .await?;
+        let block = block.ok_or_else(|| anyhow::anyhow!("block {} not found", block_number))?;
+        Ok(block.header.state_root)
+    }
+
+    /// Fetch `address`'s ETH and USDC balance along with an `eth_getProof`
+    /// response, and independently verify both against `state_root` before
+    /// returning a record -- unlike [`Self::fetch_balance`], a proof that
+    /// fails to verify is excluded (`Ok(None)`) rather than trusted.
+    async fn fetch_verified_balance(
+        &self,
+        address: Address,
+        block: BlockId,
+        state_root: B256,
+    ) -> anyhow::Result<Option<BalanceRecord>> {
+        let storage_slot = self.config.usdc_balance_slot(address);
+
+        let eth_proof = self
+            .provider
+            .get_proof(address, vec![])
+            .block_id(block)
+            .await?;
+
+        let account = match verify_account_proof(&eth_proof.account_proof, address, state_root)? {
+            Some(account) => account,
+            None => {
+                tracing::warn!(%address, "account proof excludes address, skipping");
+                return Ok(None);
+            }
+        };
+
+        // The balance lives in the USDC contract's storage, not the holder's.
+        let usdc_contract = self.config.usdc_contract;
+        let usdc_proof = self
+            .provider
+            .get_proof(usdc_contract, vec![storage_slot])
+            .block_id(block)
+            .await?;
+        let usdc_account = match verify_account_proof(&usdc_proof.account_proof, usdc_contract, state_root)? {
+            Some(account) => account,
+            None => {
+                tracing::warn!(%usdc_contract, "account proof excludes USDC contract, skipping");
+                return Ok(None);
+            }
+        };
+
+        let usdc_balance = if let Some(storage_proof) = usdc_proof.storage_proof.first() {
+            match verify_storage_proof(&storage_proof.proof, storage_slot, usdc_account.storage_root)? {
+                Some(value) => value,
+                None => U256::ZERO,
+            }
+        } else {
+            U256::ZERO
+        };
+
+        Ok(Some(BalanceRecord::new(
+            account.balance.to_be_bytes(),
+            usdc_balance.to_be_bytes(),
+        )))
+    }
+
+    /// Like [`Self::build_database`], but every record is backed by an
+    /// `eth_getProof` response independently verified against `block_number`'s
+    /// `stateRoot` rather than trusted from a plain `eth_getBalance`/`eth_call`
+    /// response. Addresses whose proof fails to verify are logged and
+    /// excluded from the database entirely, so the resulting
+    /// [`BalanceDbMetadata::state_root`] is a true provenance claim over
+    /// whatever records it does contain.
+    pub async fn build_database_verified(
+        &self,
+        addresses: &[Address],
+        block_number: u64,
+        block_hash: &str,
+        output_dir: &Path,
+    ) -> anyhow::Result<BalanceDbMetadata> {
+        std::fs::create_dir_all(output_dir)?;
+
+        let block = BlockId::number(block_number);
+        let state_root = self.fetch_state_root(block_number).await?;
+
+        let mut records = Vec::with_capacity(addresses.len());
+        let mut verified_addresses = Vec::with_capacity(addresses.len());
+
+        for chunk in addresses.chunks(self.config.batch_size) {
+            let futures: Vec<_> = chunk
+                .iter()
+                .map(|addr| self.fetch_verified_balance(*addr, block, state_root))
+                .collect();
+
+            let results = join_all(futures).await;
+
+            for (addr, result) in chunk.iter().zip(results) {
+                match result {
+                    Ok(Some(record)) => {
+                        records.push(record);
+                        verified_addresses.push(format!("{:?}", addr));
+                    }
+                    Ok(None) => {
+                        tracing::warn!(%addr, "balance proof did not verify, excluding from database");
+                    }
+                    Err(e) => {
+                        tracing::warn!(%addr, "Failed to fetch verified balance: {}", e);
+                    }
+                }
+            }
+        }
+
+        let root = balance_merkle_root(&records);
+        let padded_entry_size = balance_padded_entry_size(records.len());
+
+        let db_path = output_dir.join("balances.bin");
+        let mut db_data = Vec::with_capacity(records.len() * padded_entry_size);
+        for (i, record) in records.iter().enumerate() {
+            let path = balance_merkle_path(&records, i);
+            let mut entry = record.to_bytes().to_vec();
+            for sibling in &path.siblings {
+                entry.extend_from_slice(sibling);
+            }
+            debug_assert_eq!(entry.len(), padded_entry_size);
+            db_data.extend_from_slice(&entry);
+        }
+        std::fs::write(&db_path, &db_data)?;
+
+        let metadata = BalanceDbMetadata {
+            chain_id: self.config.chain_id,
+            snapshot_block: block_number,
+            snapshot_block_hash: block_hash.to_string(),
+            usdc_contract: format!("{:?}", self.config.usdc_contract),
+            record_size: BALANCE_RECORD_SIZE,
+            num_records: records.len(),
+            addresses: verified_addresses,
+            merkle_root: hex::encode(root),
+            padded_entry_size,
+            state_root: hex::encode(state_root),
+            beacon_checkpoint_root: String::new(),
+        };
+
+        let metadata_path = output_dir.join("metadata.json");
+        let metadata_json = serde_json::to_string_pretty(&metadata)?;
+        std::fs::write(&metadata_path, &metadata_json)?;
+
+        tracing::info!(
+            requested = addresses.len(),
+            verified = records.len(),
+            db_size = db_data.len(),
+            path = %db_path.display(),
+            "Verified balance database built"
+        );
+
+        Ok(metadata)
+    }
+
+    /// Like [`Self::build_database_verified`], but resolves the snapshot
+    /// block from a beacon-chain checkpoint (see
+    /// [`crate::checkpoint::CheckpointClient`]) instead of a raw,
+    /// operator-supplied block number, and records the beacon checkpoint
+    /// root alongside `state_root` for provenance. Note that
+    /// `checkpoint_client` does not currently verify the checkpoint's
+    /// sync-committee signature (see that module's scope note) unless the
+    /// caller has wired in a BLS backend, so this only saves callers from
+    /// hand-typing a block number -- it is not yet a stronger trust anchor
+    /// than [`Self::build_database_verified`].
+    pub async fn build_database_at_checkpoint(
+        &self,
+        addresses: &[Address],
+        checkpoint_client: &crate::checkpoint::CheckpointClient,
+        checkpoint_root: &str,
+        output_dir: &Path,
+    ) -> anyhow::Result<BalanceDbMetadata> {
+        let checkpoint = checkpoint_client.resolve(checkpoint_root).await?;
+
+        let mut metadata = self
+            .build_database_verified(
+                addresses,
+                checkpoint.execution_block_number,
+                &checkpoint.execution_block_hash,
+                output_dir,
+            )
+            .await?;
+
+        metadata.beacon_checkpoint_root = checkpoint.beacon_root;
+
+        let metadata_path = output_dir.join("metadata.json");
+        let metadata_json = serde_json::to_string_pretty(&metadata)?;
+        std::fs::write(&metadata_path, &metadata_json)?;
+
+        Ok(metadata)
+    }
 }
 
 pub fn load_addresses_from_file(path: &Path) -> anyhow::Result<Vec<Address>> {
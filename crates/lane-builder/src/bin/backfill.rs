@@ -10,10 +10,27 @@
 
 use std::path::PathBuf;
 
-use clap::Parser;
-use lane_builder::gas_tracker::{BackfillConfig, GasTracker};
+use clap::{Parser, ValueEnum};
+use lane_builder::gas_tracker::{BackfillConfig, GasSource, GasTracker};
 use lane_builder::hybrid_scorer::{HybridScorer, HybridScorerConfig};
 
+/// CLI-facing mirror of [`GasSource`] (`clap::ValueEnum` can't be derived
+/// on a type outside this crate's control over its feature gating).
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GasSourceArg {
+    GasLimit,
+    GasUsed,
+}
+
+impl From<GasSourceArg> for GasSource {
+    fn from(arg: GasSourceArg) -> Self {
+        match arg {
+            GasSourceArg::GasLimit => GasSource::GasLimit,
+            GasSourceArg::GasUsed => GasSource::GasUsed,
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "lane-backfill")]
 #[command(about = "Backfill gas usage data for hot lane selection")]
@@ -49,6 +66,33 @@ struct Args {
     /// Priority boost for known contracts (in gas units)
     #[arg(long, default_value = "100000000000")]
     known_boost: u64,
+
+    /// Width in blocks of each gas-recency bucket used for decay-weighted scoring
+    #[arg(long, default_value = "5000")]
+    bucket_block_span: u64,
+
+    /// Exponential decay factor (0, 1] applied across recency buckets, newest first.
+    /// 1.0 disables decay (flat lifetime gas sum, today's behavior).
+    #[arg(long, default_value = "1.0")]
+    decay_lambda: f64,
+
+    /// Attribute gas via `debug_traceBlockByNumber`'s callTracer, walking the
+    /// full call tree instead of just each transaction's top-level `to`.
+    /// Requires an archive node with the `debug` namespace enabled.
+    #[arg(long, default_value = "false")]
+    use_call_tracer: bool,
+
+    /// Checkpoint file for a resumable backfill. When set, progress commits
+    /// chunk-by-chunk to this path and a run killed partway resumes from
+    /// its last committed chunk instead of starting over.
+    #[arg(long)]
+    checkpoint: Option<PathBuf>,
+
+    /// Attribute each transaction's gas_limit (cheap) or its receipt's
+    /// actual gas_used (accurate, costs one extra RPC round trip per
+    /// block). No effect when `--use-call-tracer` is set.
+    #[arg(long, value_enum, default_value = "gas-limit")]
+    gas_source: GasSourceArg,
 }
 
 #[tokio::main]
@@ -72,9 +116,15 @@ async fn main() -> anyhow::Result<()> {
         block_count: args.blocks,
         batch_size: args.batch_size,
         concurrency: args.concurrency,
+        bucket_block_span: args.bucket_block_span,
+        use_call_tracer: args.use_call_tracer,
+        gas_source: args.gas_source.into(),
     };
 
-    let tracker = GasTracker::new(config).await?;
+    let tracker = match &args.checkpoint {
+        Some(checkpoint_path) => GasTracker::resume(config, checkpoint_path.clone()).await?,
+        None => GasTracker::new(config).await?,
+    };
     let result = tracker.backfill().await?;
 
     println!();
@@ -91,6 +141,7 @@ async fn main() -> anyhow::Result<()> {
     let scorer_config = HybridScorerConfig {
         known_contract_boost: args.known_boost,
         max_contracts: args.top_n,
+        decay_lambda: args.decay_lambda,
         ..Default::default()
     };
 
@@ -111,6 +162,7 @@ async fn main() -> anyhow::Result<()> {
             lane_builder::hybrid_scorer::ContractSource::GasBackfill => "gas",
             lane_builder::hybrid_scorer::ContractSource::KnownList => "known",
             lane_builder::hybrid_scorer::ContractSource::Both => "both",
+            lane_builder::hybrid_scorer::ContractSource::Manifest => "manifest",
         };
         println!(
             "{:>3}. {} ({}) - score: {}, gas: {}, txs: {} [{}]",
@@ -0,0 +1,81 @@
+//! delta-compact: Merge per-block delta files into one consolidated state.bin
+//!
+//! Usage:
+//!   delta-compact --delta-dir ./pir-data/delta --from-block 100 --to-block 200 \
+//!       --output ./pir-data/delta/compacted.bin
+//!
+//! Reads every `delta_<block>_<hash>.bin` under `--delta-dir` whose block
+//! number lies in `[from_block, to_block]` (blocks with no file are
+//! skipped), in file-name order, and streams them through
+//! [`lane_builder::compact_range`].
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use lane_builder::compact_range;
+
+#[derive(Parser)]
+#[command(about = "Merge per-block delta files into one consolidated state.bin")]
+struct Args {
+    /// Directory containing delta_<block>_<hash>.bin files
+    #[arg(long)]
+    delta_dir: PathBuf,
+
+    /// First block number (inclusive) to include in the merge
+    #[arg(long)]
+    from_block: u64,
+
+    /// Last block number (inclusive) to include in the merge
+    #[arg(long)]
+    to_block: u64,
+
+    /// Output path for the consolidated state.bin
+    #[arg(long)]
+    output: PathBuf,
+}
+
+fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    let args = Args::parse();
+
+    if args.from_block > args.to_block {
+        anyhow::bail!("--from-block must be <= --to-block");
+    }
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(&args.delta_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .filter(|name| name.ends_with(".bin"))
+                .and_then(|name| name.strip_prefix("delta_"))
+                .and_then(|rest| rest.get(..10))
+                .and_then(|digits| digits.parse::<u64>().ok())
+                .map_or(false, |block| (args.from_block..=args.to_block).contains(&block))
+        })
+        .collect();
+    paths.sort();
+
+    tracing::info!(
+        delta_dir = %args.delta_dir.display(),
+        from_block = args.from_block,
+        to_block = args.to_block,
+        files_found = paths.len(),
+        "Starting delta compaction"
+    );
+
+    let header = compact_range(&paths, &args.output)
+        .map_err(|e| anyhow::anyhow!("Compaction failed: {}", e))?;
+
+    tracing::info!(
+        entries = header.entry_count,
+        block = header.block_number,
+        output = %args.output.display(),
+        "Compaction complete"
+    );
+
+    Ok(())
+}
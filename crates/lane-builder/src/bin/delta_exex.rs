@@ -28,6 +28,11 @@ struct DeltaExExArgs {
     /// Number of recent blocks to keep (0 = keep all)
     #[arg(long, env = "DELTA_KEEP_BLOCKS", default_value = "256")]
     keep_blocks: u64,
+
+    /// Blocks behind the committed tip before a delta is treated as
+    /// finalized (and becomes eligible for pruning)
+    #[arg(long, env = "DELTA_FINALIZED_DEPTH", default_value = "64")]
+    finalized_depth: u64,
 }
 
 fn main() -> Result<()> {
@@ -41,6 +46,7 @@ fn main() -> Result<()> {
         let config = DeltaExporterConfig {
             output_dir: exex_args.output_dir,
             keep_blocks: exex_args.keep_blocks,
+            finalized_depth: exex_args.finalized_depth,
         };
 
         tracing::info!(
@@ -58,6 +58,7 @@ fn main() -> Result<()> {
             server_url: exex_args.pir_server_url,
             data_dir: exex_args.pir_data_dir,
             reload_debounce: Duration::from_secs(exex_args.reload_debounce_secs),
+            ..Default::default()
         };
 
         tracing::info!(
@@ -9,6 +9,17 @@ fn main() -> anyhow::Result<()> {
         .with_max_level(tracing::Level::INFO)
         .init();
 
+    // Catch a bad checksum or duplicate address in the curated contract
+    // literals at startup, before `load_known_contracts` bakes a wrong
+    // entry into the manifest -- a build that skips `cargo test` would
+    // otherwise ship it silently.
+    if let Err(errors) = lane_builder::contracts::validate() {
+        for error in &errors {
+            tracing::error!("curated contract list failed validation: {error}");
+        }
+        anyhow::bail!("curated contract list failed validation ({} error(s))", errors.len());
+    }
+
     let args: Vec<String> = std::env::args().collect();
     
     let output_dir = args.get(1).map(PathBuf::from).unwrap_or_else(|| {
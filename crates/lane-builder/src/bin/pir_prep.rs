@@ -4,6 +4,31 @@
 //! - database.bin: flat 32-byte storage values
 //! - storage-mapping.bin: sorted (address:20 + slot:32 + index:4) entries
 //!
+//! `PlainStorageState` is a DUPSORT table, so both extraction backends below
+//! step through duplicates (one per storage slot) of each address key with
+//! dup-aware cursor ops rather than treating every step as a new address.
+//!
+//! Two extraction backends are available:
+//!
+//! - The default, `typed_extract::extract_storage_for_pir`, opens the table
+//!   through reth's own `reth-db` schema (`tables::PlainStorageState`, the
+//!   same crate `bin/reth-state-export.rs` uses) and never touches raw MDBX
+//!   pointers. It pins extraction to the chain's canonical head so the dense
+//!   DB this binary produces and the ExEx's incremental updates agree on a
+//!   snapshot height; `--block-number` lets a caller assert that head is the
+//!   one they expect (see [`typed_extract`] for why a different height is
+//!   rejected rather than served).
+//! - The legacy `ffi_extract::extract_storage_for_pir`, kept for databases
+//!   predating reth's `reth-db` typed tables, walks the table via raw
+//!   `unsafe` `mdbx_*` calls with a hardcoded `"PlainStorageState"` table
+//!   name and manual `MDBX_val` slicing. It's brittle to reth's on-disk
+//!   layout changing across versions, which is exactly why the typed path
+//!   is now the default; opt into it with `--features mdbx-ffi-extract`.
+//!
+//! See [`lane_builder::storage_decode`] for the RLP decode the FFI backend
+//! uses and the malformed/skipped counters both backends fold into
+//! `metadata.json`.
+//!
 //! Usage:
 //!   cargo run --bin pir-prep --features state-dump -- \
 //!     --db-path /mnt/sepolia/data/db \
@@ -11,18 +36,19 @@
 
 #![cfg(feature = "state-dump")]
 
-use std::ffi::CString;
-use std::fs::File;
-use std::io::{BufWriter, Write};
 use std::path::PathBuf;
-use std::ptr;
 
 use clap::Parser;
 use eyre::Result;
-use indicatif::{ProgressBar, ProgressStyle};
-use mdbx_rs::{MDBX_cursor_op::*, *};
 use serde::{Deserialize, Serialize};
 
+use lane_builder::storage_decode::ExtractionReport;
+
+#[cfg(feature = "mdbx-ffi-extract")]
+use ffi_extract::extract_storage_for_pir;
+#[cfg(not(feature = "mdbx-ffi-extract"))]
+use typed_extract::extract_storage_for_pir;
+
 #[derive(Parser, Debug)]
 #[command(name = "pir-prep")]
 #[command(about = "Prepare PIR database from reth MDBX (plinko-compatible format)")]
@@ -42,6 +68,14 @@ struct Args {
     /// Log progress every N records
     #[arg(long, default_value = "1000000")]
     progress_interval: u64,
+
+    /// Require extraction to run against this canonical block number (typed
+    /// backend only; ignored by `--features mdbx-ffi-extract`). Errors out
+    /// if the DB's canonical head has since moved past it, so the dense DB
+    /// produced here and the ExEx's incremental updates agree on a height
+    /// instead of silently snapshotting whatever the head happened to be.
+    #[arg(long)]
+    block_number: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +86,26 @@ struct PirMetadata {
     entry_size: usize,
     mapping_entry_size: usize,
     format_version: String,
+    /// Duplicates that failed to decode (bad RLP, non-canonical encoding,
+    /// or a value too short to carry a slot key). See [`ExtractionReport`].
+    malformed_records: u64,
+    /// Keys skipped outright because they weren't a 20-byte address.
+    skipped_records: u64,
+    /// Canonical block this snapshot was extracted at, so the dense DB can
+    /// be lined up against the ExEx's incremental updates. `None` for the
+    /// legacy FFI backend, which doesn't track a block height.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_hash: Option<String>,
+}
+
+/// Outcome shared by both extraction backends, so `main` doesn't need to
+/// know which one ran.
+struct ExtractionOutcome {
+    report: ExtractionReport,
+    block_number: Option<u64>,
+    block_hash: Option<[u8; 32]>,
 }
 
 fn main() -> Result<()> {
@@ -69,14 +123,19 @@ fn main() -> Result<()> {
         "Starting PIR database preparation"
     );
 
-    let num_storage_slots = unsafe { extract_storage_for_pir(&args)? };
+    let outcome = extract_storage_for_pir(&args)?;
+    let report = outcome.report;
 
     let metadata = PirMetadata {
         chain: args.chain.clone(),
-        num_storage_slots,
+        num_storage_slots: report.decoded,
         entry_size: 32,
         mapping_entry_size: 56,
         format_version: "1.0.0".to_string(),
+        malformed_records: report.malformed,
+        skipped_records: report.skipped,
+        block_number: outcome.block_number,
+        block_hash: outcome.block_hash.map(|h| format!("0x{}", hex::encode(h))),
     };
 
     let metadata_path = args.output_dir.join("metadata.json");
@@ -84,231 +143,328 @@ fn main() -> Result<()> {
     std::fs::write(&metadata_path, &metadata_json)?;
 
     tracing::info!(
-        storage_slots = num_storage_slots,
+        storage_slots = report.decoded,
+        malformed = report.malformed,
+        skipped = report.skipped,
+        block_number = ?metadata.block_number,
         metadata = %metadata_path.display(),
-        "PIR database preparation complete"
+        "PIR database preparation complete -- compare storage_slots against the node's reported slot count to validate extraction completeness"
     );
 
     Ok(())
 }
 
-unsafe fn extract_storage_for_pir(args: &Args) -> Result<u64> {
-    let mut env: *mut MDBX_env = ptr::null_mut();
-    let rc = mdbx_env_create(&mut env);
-    if rc != MDBX_SUCCESS {
-        return Err(eyre::eyre!("Failed to create MDBX environment: {}", rc));
+/// Legacy extraction backend: walks `PlainStorageState` through raw
+/// `unsafe` `mdbx_*` calls. Kept for databases predating reth's typed
+/// `reth-db` tables; see the module doc for why [`typed_extract`] is the
+/// default instead.
+#[cfg(feature = "mdbx-ffi-extract")]
+mod ffi_extract {
+    use std::ffi::CString;
+    use std::fs::File;
+    use std::io::{BufWriter, Write};
+    use std::ptr;
+
+    use eyre::Result;
+    use indicatif::{ProgressBar, ProgressStyle};
+    use mdbx_rs::{MDBX_cursor_op::*, *};
+
+    use lane_builder::storage_decode::{decode_rlp_u256, split_storage_dup_value, ExtractionReport};
+
+    use super::{Args, ExtractionOutcome};
+
+    pub fn extract_storage_for_pir(args: &Args) -> Result<ExtractionOutcome> {
+        let report = unsafe { extract_storage_for_pir_unsafe(args)? };
+        Ok(ExtractionOutcome {
+            report,
+            block_number: None,
+            block_hash: None,
+        })
     }
 
-    let rc = mdbx_env_set_maxdbs(env, 64);
-    if rc != MDBX_SUCCESS {
-        mdbx_env_close(env);
-        return Err(eyre::eyre!("Failed to set maxdbs: {}", rc));
-    }
-
-    let db_path_str = args.db_path.to_string_lossy();
-    let path_cstr = CString::new(db_path_str.as_ref())?;
-
-    tracing::info!("Opening MDBX database at {}", db_path_str);
+    unsafe fn extract_storage_for_pir_unsafe(args: &Args) -> Result<ExtractionReport> {
+        let mut env: *mut MDBX_env = ptr::null_mut();
+        let rc = mdbx_env_create(&mut env);
+        if rc != MDBX_SUCCESS {
+            return Err(eyre::eyre!("Failed to create MDBX environment: {}", rc));
+        }
 
-    let rc = mdbx_env_open(env, path_cstr.as_ptr(), MDBX_RDONLY as u32, 0o644);
-    if rc != MDBX_SUCCESS {
-        mdbx_env_close(env);
-        return Err(eyre::eyre!("Failed to open MDBX environment: {}", rc));
-    }
+        let rc = mdbx_env_set_maxdbs(env, 64);
+        if rc != MDBX_SUCCESS {
+            mdbx_env_close(env);
+            return Err(eyre::eyre!("Failed to set maxdbs: {}", rc));
+        }
 
-    tracing::info!("MDBX environment opened successfully");
+        let db_path_str = args.db_path.to_string_lossy();
+        let path_cstr = CString::new(db_path_str.as_ref())?;
 
-    let mut txn: *mut MDBX_txn = ptr::null_mut();
-    let rc = mdbx_txn_begin(env, ptr::null_mut(), MDBX_RDONLY as u32, &mut txn);
-    if rc != MDBX_SUCCESS {
-        mdbx_env_close(env);
-        return Err(eyre::eyre!("Failed to begin transaction: {}", rc));
-    }
+        tracing::info!("Opening MDBX database at {}", db_path_str);
 
-    let table_cstr = CString::new("PlainStorageState")?;
-    let mut dbi: MDBX_dbi = 0;
-    let rc = mdbx_dbi_open(txn, table_cstr.as_ptr(), 0, &mut dbi);
-    if rc != MDBX_SUCCESS {
-        mdbx_txn_abort(txn);
-        mdbx_env_close(env);
-        return Err(eyre::eyre!("Failed to open PlainStorageState: {}", rc));
-    }
-
-    tracing::info!("Opened PlainStorageState table");
+        let rc = mdbx_env_open(env, path_cstr.as_ptr(), MDBX_RDONLY as u32, 0o644);
+        if rc != MDBX_SUCCESS {
+            mdbx_env_close(env);
+            return Err(eyre::eyre!("Failed to open MDBX environment: {}", rc));
+        }
 
-    let mut cursor: *mut MDBX_cursor = ptr::null_mut();
-    let rc = mdbx_cursor_open(txn, dbi, &mut cursor);
-    if rc != MDBX_SUCCESS {
-        mdbx_txn_abort(txn);
-        mdbx_env_close(env);
-        return Err(eyre::eyre!("Failed to open cursor: {}", rc));
-    }
+        tracing::info!("MDBX environment opened successfully");
 
-    let database_path = args.output_dir.join("database.bin");
-    let mapping_path = args.output_dir.join("storage-mapping.bin");
+        let mut txn: *mut MDBX_txn = ptr::null_mut();
+        let rc = mdbx_txn_begin(env, ptr::null_mut(), MDBX_RDONLY as u32, &mut txn);
+        if rc != MDBX_SUCCESS {
+            mdbx_env_close(env);
+            return Err(eyre::eyre!("Failed to begin transaction: {}", rc));
+        }
 
-    let mut db_writer = BufWriter::with_capacity(64 * 1024 * 1024, File::create(&database_path)?);
-    let mut map_writer = BufWriter::with_capacity(64 * 1024 * 1024, File::create(&mapping_path)?);
+        let table_cstr = CString::new("PlainStorageState")?;
+        let mut dbi: MDBX_dbi = 0;
+        let rc = mdbx_dbi_open(txn, table_cstr.as_ptr(), 0, &mut dbi);
+        if rc != MDBX_SUCCESS {
+            mdbx_txn_abort(txn);
+            mdbx_env_close(env);
+            return Err(eyre::eyre!("Failed to open PlainStorageState: {}", rc));
+        }
 
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("[{elapsed_precise}] {spinner} {msg}")
-            .unwrap(),
-    );
+        tracing::info!("Opened PlainStorageState table");
 
-    let mut key = MDBX_val::default();
-    let mut val = MDBX_val::default();
-    let mut count = 0u64;
-    let mut skipped = 0u64;
-
-    let mut rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_FIRST as MDBX_cursor_op);
-
-    while rc == MDBX_SUCCESS {
-        let key_bytes = std::slice::from_raw_parts(key.iov_base as *const u8, key.iov_len);
-        let val_bytes = std::slice::from_raw_parts(val.iov_base as *const u8, val.iov_len);
-
-        // PlainStorageState format:
-        // - key: 20-byte address
-        // - value: variable-length encoded (slot + storage_value)
-        //
-        // In reth with DUPSORT, the value contains the slot as the dupsort key
-        // and the storage value as the data. The exact format depends on reth version.
-        //
-        // For modern reth (post-1.0), the value is:
-        // - First 32 bytes: storage slot (B256)
-        // - Remaining bytes: RLP-encoded U256 storage value
-
-        if key_bytes.len() != 20 {
-            skipped += 1;
-            rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_NEXT as MDBX_cursor_op);
-            continue;
+        let mut cursor: *mut MDBX_cursor = ptr::null_mut();
+        let rc = mdbx_cursor_open(txn, dbi, &mut cursor);
+        if rc != MDBX_SUCCESS {
+            mdbx_txn_abort(txn);
+            mdbx_env_close(env);
+            return Err(eyre::eyre!("Failed to open cursor: {}", rc));
         }
 
-        // Value should be at least 32 bytes (slot) + 1 byte (minimal RLP)
-        if val_bytes.len() < 33 {
-            skipped += 1;
-            rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_NEXT as MDBX_cursor_op);
-            continue;
+        let database_path = args.output_dir.join("database.bin");
+        let mapping_path = args.output_dir.join("storage-mapping.bin");
+
+        let mut db_writer = BufWriter::with_capacity(64 * 1024 * 1024, File::create(&database_path)?);
+        let mut map_writer = BufWriter::with_capacity(64 * 1024 * 1024, File::create(&mapping_path)?);
+
+        let pb = ProgressBar::new_spinner();
+        pb.set_style(
+            ProgressStyle::default_spinner()
+                .template("[{elapsed_precise}] {spinner} {msg}")
+                .unwrap(),
+        );
+
+        let mut key = MDBX_val::default();
+        let mut val = MDBX_val::default();
+        let mut report = ExtractionReport::default();
+
+        // PlainStorageState is DUPSORT: one 20-byte address key has many
+        // duplicate (slot, value) entries, ordered by the slot key prefixing
+        // each duplicate's value. MDBX_FIRST lands on the first key's first
+        // duplicate; MDBX_NEXT_DUP walks the remaining duplicates for the
+        // *same* key (MDBX_NOTFOUND once they're exhausted), and MDBX_NEXT_NODUP
+        // then jumps straight to the next distinct key's first duplicate.
+        let mut rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_FIRST as MDBX_cursor_op);
+
+        while rc == MDBX_SUCCESS {
+            let key_bytes = std::slice::from_raw_parts(key.iov_base as *const u8, key.iov_len);
+
+            if key_bytes.len() != 20 {
+                report.record_skipped();
+                rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_NEXT_NODUP as MDBX_cursor_op);
+                continue;
+            }
+            let address: [u8; 20] = key_bytes.try_into().expect("length checked above");
+
+            loop {
+                let val_bytes = std::slice::from_raw_parts(val.iov_base as *const u8, val.iov_len);
+
+                match split_storage_dup_value(val_bytes).and_then(|(slot, rlp)| decode_rlp_u256(rlp).map(|v| (slot, v))) {
+                    Ok((slot, storage_value)) => {
+                        // Write 32-byte storage value to database.bin
+                        db_writer.write_all(&storage_value)?;
+
+                        // Write mapping entry: address(20) + slot(32) + index(4 LE)
+                        map_writer.write_all(&address)?;
+                        map_writer.write_all(&slot)?;
+                        map_writer.write_all(&(report.decoded as u32).to_le_bytes())?;
+
+                        report.record_decoded();
+
+                        if report.decoded % args.progress_interval == 0 {
+                            pb.set_message(format!(
+                                "PlainStorageState: {} entries (malformed: {}, skipped: {})",
+                                report.decoded, report.malformed, report.skipped
+                            ));
+                            db_writer.flush()?;
+                            map_writer.flush()?;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!(address = hex::encode(address), error = %e, "Malformed PlainStorageState duplicate");
+                        report.record_malformed();
+                    }
+                }
+
+                rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_NEXT_DUP as MDBX_cursor_op);
+                if rc == MDBX_NOTFOUND {
+                    break;
+                }
+                if rc != MDBX_SUCCESS {
+                    mdbx_cursor_close(cursor);
+                    mdbx_txn_abort(txn);
+                    mdbx_env_close(env);
+                    return Err(eyre::eyre!("Cursor error while walking duplicates: {}", rc));
+                }
+            }
+
+            rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_NEXT_NODUP as MDBX_cursor_op);
         }
 
-        let address = key_bytes;
-        let slot = &val_bytes[0..32];
-
-        // Decode the storage value from RLP
-        // The value is an RLP-encoded U256. For simplicity, we'll handle common cases:
-        // - Single byte 0x00-0x7f: value is the byte itself
-        // - 0x80: empty value (0)
-        // - 0x81-0xb7: short string (1-55 bytes)
-        let storage_value = decode_rlp_u256(&val_bytes[32..])?;
-
-        // Write 32-byte storage value to database.bin
-        db_writer.write_all(&storage_value)?;
-
-        // Write mapping entry: address(20) + slot(32) + index(4 LE)
-        map_writer.write_all(address)?;
-        map_writer.write_all(slot)?;
-        map_writer.write_all(&(count as u32).to_le_bytes())?;
-
-        count += 1;
-
-        if count % args.progress_interval == 0 {
-            pb.set_message(format!(
-                "PlainStorageState: {} entries (skipped: {})",
-                count, skipped
-            ));
-            db_writer.flush()?;
-            map_writer.flush()?;
+        if rc != MDBX_NOTFOUND {
+            mdbx_cursor_close(cursor);
+            mdbx_txn_abort(txn);
+            mdbx_env_close(env);
+            return Err(eyre::eyre!("Cursor error: {}", rc));
         }
 
-        rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_NEXT as MDBX_cursor_op);
-    }
-
-    if rc != MDBX_NOTFOUND {
+        db_writer.flush()?;
+        map_writer.flush()?;
         mdbx_cursor_close(cursor);
         mdbx_txn_abort(txn);
         mdbx_env_close(env);
-        return Err(eyre::eyre!("Cursor error: {}", rc));
-    }
-
-    db_writer.flush()?;
-    map_writer.flush()?;
-    mdbx_cursor_close(cursor);
-    mdbx_txn_abort(txn);
-    mdbx_env_close(env);
-
-    pb.finish_with_message(format!(
-        "PlainStorageState: {} entries complete (skipped: {})",
-        count, skipped
-    ));
-
-    tracing::info!(
-        count,
-        skipped,
-        database = %database_path.display(),
-        mapping = %mapping_path.display(),
-        "Storage extraction complete"
-    );
-
-    Ok(count)
-}
 
-/// Decode RLP-encoded U256 to 32-byte big-endian array
-fn decode_rlp_u256(data: &[u8]) -> Result<[u8; 32]> {
-    if data.is_empty() {
-        return Ok([0u8; 32]);
-    }
-
-    let first = data[0];
-    let mut result = [0u8; 32];
-
-    if first == 0x80 {
-        // Empty string = 0
-        return Ok(result);
-    }
-
-    if first < 0x80 {
-        // Single byte value
-        result[31] = first;
-        return Ok(result);
+        pb.finish_with_message(format!(
+            "PlainStorageState: {} entries complete (malformed: {}, skipped: {})",
+            report.decoded, report.malformed, report.skipped
+        ));
+
+        tracing::info!(
+            decoded = report.decoded,
+            malformed = report.malformed,
+            skipped = report.skipped,
+            database = %database_path.display(),
+            mapping = %mapping_path.display(),
+            "Storage extraction complete"
+        );
+
+        Ok(report)
     }
+}
 
-    if first <= 0xb7 {
-        // Short string: length is (first - 0x80)
-        let len = (first - 0x80) as usize;
-        if data.len() < 1 + len {
-            return Err(eyre::eyre!("RLP truncated: expected {} bytes", len));
-        }
-        if len > 32 {
-            return Err(eyre::eyre!("RLP value too large: {} bytes", len));
+/// Default extraction backend: walks `PlainStorageState` through reth's own
+/// typed `reth-db` schema instead of raw MDBX pointers, the same crate
+/// `bin/reth-state-export.rs` links. `PlainStorageState` only ever reflects
+/// the chain's current committed state (there's no reverse-changeset replay
+/// here), so "pin to a specific block" means asserting that block is the
+/// canonical head rather than rewinding to it -- passing a stale
+/// `--block-number` is a hard error instead of silently extracting the
+/// wrong snapshot.
+#[cfg(not(feature = "mdbx-ffi-extract"))]
+mod typed_extract {
+    use std::fs::File;
+    use std::io::{BufWriter, Write};
+
+    use eyre::{eyre, Result};
+    use indicatif::{ProgressBar, ProgressStyle};
+    use reth_db::mdbx::DatabaseArguments;
+    use reth_db::transaction::DbTx;
+    use reth_db::{open_db_read_only, tables, ClientVersion};
+
+    use lane_builder::storage_decode::ExtractionReport;
+
+    use super::{Args, ExtractionOutcome};
+
+    pub fn extract_storage_for_pir(args: &Args) -> Result<ExtractionOutcome> {
+        let db = open_db_read_only(&args.db_path, DatabaseArguments::new(ClientVersion::default()))
+            .map_err(|e| eyre!("Failed to open reth DB at {}: {}", args.db_path.display(), e))?;
+
+        let tx = db.tx()?;
+
+        let (block_number, block_hash) = latest_canonical_header(&tx)?;
+        if let Some(expected) = args.block_number {
+            if expected != block_number {
+                return Err(eyre!(
+                    "Requested --block-number {} but the DB's canonical head is at {}; \
+                     PlainStorageState only reflects current state, so extraction can't be \
+                     pinned to a non-head block. Re-run without --block-number to accept the \
+                     current head, or wait for the chain to reach the requested height.",
+                    expected,
+                    block_number
+                ));
+            }
         }
-        // Copy to right-aligned position in result
-        let start = 32 - len;
-        result[start..].copy_from_slice(&data[1..1 + len]);
-        return Ok(result);
-    }
 
-    if first <= 0xbf {
-        // Long string: next (first - 0xb7) bytes are the length
-        let len_of_len = (first - 0xb7) as usize;
-        if data.len() < 1 + len_of_len {
-            return Err(eyre::eyre!("RLP length truncated"));
-        }
-        let mut len = 0usize;
-        for i in 0..len_of_len {
-            len = (len << 8) | (data[1 + i] as usize);
-        }
-        if data.len() < 1 + len_of_len + len {
-            return Err(eyre::eyre!("RLP data truncated"));
+        tracing::info!(
+            block_number,
+            block_hash = %hex::encode(block_hash),
+            "Extracting PlainStorageState at canonical head"
+        );
+
+        let database_path = args.output_dir.join("database.bin");
+        let mapping_path = args.output_dir.join("storage-mapping.bin");
+
+        let mut db_writer = BufWriter::with_capacity(64 * 1024 * 1024, File::create(&database_path)?);
+        let mut map_writer = BufWriter::with_capacity(64 * 1024 * 1024, File::create(&mapping_path)?);
+
+        let pb = ProgressBar::new_spinner();
+        pb.set_style(
+            ProgressStyle::default_spinner()
+                .template("[{elapsed_precise}] {spinner} {msg}")
+                .unwrap(),
+        );
+
+        let mut report = ExtractionReport::default();
+        let mut cursor = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        let mut walker = cursor.walk_dup(None, None)?;
+
+        while let Some(row) = walker.next() {
+            let (address, storage_entry) = row?;
+            let slot_bytes: [u8; 32] = storage_entry.key.0;
+            let value_bytes: [u8; 32] = storage_entry.value.to_be_bytes();
+
+            db_writer.write_all(&value_bytes)?;
+
+            map_writer.write_all(&address.0 .0)?;
+            map_writer.write_all(&slot_bytes)?;
+            map_writer.write_all(&(report.decoded as u32).to_le_bytes())?;
+
+            report.record_decoded();
+
+            if report.decoded % args.progress_interval == 0 {
+                pb.set_message(format!(
+                    "PlainStorageState: {} entries (malformed: {}, skipped: {})",
+                    report.decoded, report.malformed, report.skipped
+                ));
+                db_writer.flush()?;
+                map_writer.flush()?;
+            }
         }
-        if len > 32 {
-            return Err(eyre::eyre!("RLP value too large: {} bytes", len));
-        }
-        let start = 32 - len;
-        result[start..].copy_from_slice(&data[1 + len_of_len..1 + len_of_len + len]);
-        return Ok(result);
+
+        db_writer.flush()?;
+        map_writer.flush()?;
+        drop(walker);
+        tx.commit()?;
+
+        pb.finish_with_message(format!(
+            "PlainStorageState: {} entries complete (malformed: {}, skipped: {})",
+            report.decoded, report.malformed, report.skipped
+        ));
+
+        tracing::info!(
+            decoded = report.decoded,
+            malformed = report.malformed,
+            skipped = report.skipped,
+            database = %database_path.display(),
+            mapping = %mapping_path.display(),
+            "Storage extraction complete"
+        );
+
+        Ok(ExtractionOutcome {
+            report,
+            block_number: Some(block_number),
+            block_hash: Some(block_hash),
+        })
     }
 
-    // List types (0xc0-0xff) shouldn't appear for storage values
-    Err(eyre::eyre!("Unexpected RLP list type: 0x{:02x}", first))
+    fn latest_canonical_header(tx: &impl DbTx) -> Result<(u64, [u8; 32])> {
+        let mut cursor = tx.cursor_read::<tables::CanonicalHeaders>()?;
+        let (block_number, block_hash) = cursor
+            .last()?
+            .ok_or_else(|| eyre!("CanonicalHeaders table is empty"))?;
+        Ok((block_number, block_hash.0))
+    }
 }
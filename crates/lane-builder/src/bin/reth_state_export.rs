@@ -3,6 +3,26 @@
 //! Reads PlainStorageState from a reth MDBX database via reth-db APIs and
 //! writes a state.bin file in EIP-7864 (UBT) ordering.
 //!
+//! The sorted path (`--no-sort` off, the default) spills chunks to
+//! `--tmp-dir` as it walks the DB: the walker fills `chunk_entries`-sized
+//! buffers on the main thread and hands each off to a bounded rayon worker
+//! pool that sorts and zstd-compresses it. `--max-inflight-chunks` caps how
+//! many of those buffers may be queued at once, which bounds peak RAM
+//! regardless of DB size. Before every chunk write the exporter checks free
+//! space on the temp volume against `--reserved-disk-ratio` and aborts
+//! cleanly rather than filling the disk.
+//!
+//! Progress is crash-resumable: `tmp_dir` holds a `chunk_manifest.json`
+//! recording each completed chunk's path/entry count/tree_key range plus the
+//! `(db_path, chain_id, block_number, block_hash)` the run was started
+//! against. Re-running against the same head skips re-reading the DB rows
+//! already covered by those chunks and resumes the cursor right after the
+//! last one; if the chain head moved, or `tmp_dir` belongs to a different
+//! `db_path`/`chain_id`, the manifest (and any chunks left by it) is
+//! invalidated and the export restarts from scratch. `write_sorted_output`
+//! writes to `<output>.tmp` and renames it into place on success, so a
+//! reader never observes a half-written `state.bin`.
+//!
 //! Usage:
 //!   cargo run --bin reth-state-export --features reth-export -- \
 //!     --db-path /path/to/reth/db \
@@ -12,27 +32,37 @@
 #![cfg(feature = "reth-export")]
 
 use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use std::collections::{BTreeMap, BinaryHeap};
 use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::sync_channel;
+use std::sync::{Arc, Mutex};
+use std::thread;
 
 use anyhow::{anyhow, Context, Result};
 use clap::Parser;
 use indicatif::{ProgressBar, ProgressStyle};
 use inspire_core::state_format::{StateHeader, STATE_ENTRY_SIZE, STATE_HEADER_SIZE};
 use inspire_core::ubt::{compute_storage_tree_index, compute_tree_key};
+use rayon::iter::{ParallelBridge, ParallelIterator};
 use reth_db::mdbx::DatabaseArguments;
-use reth_db::table::Table;
+use reth_db::table::{DupSort, Table};
 use reth_db::transaction::DbTx;
 use reth_db::{open_db_read_only, tables, ClientVersion};
+use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
 const RECORD_SIZE: usize = 32 + STATE_ENTRY_SIZE;
 
+/// zstd compression level used for sort chunks. Low on purpose: chunks are
+/// deleted after the merge, so this trades ratio for sort-stage throughput.
+const CHUNK_ZSTD_LEVEL: i32 = 3;
+
 type EntryBytes = [u8; STATE_ENTRY_SIZE];
 type PlainStorageKey = <tables::PlainStorageState as Table>::Key;
 type PlainStorageValue = <tables::PlainStorageState as Table>::Value;
+type PlainStorageSubKey = <tables::PlainStorageState as DupSort>::SubKey;
 
 #[derive(Parser, Debug)]
 #[command(name = "reth-state-export")]
@@ -69,6 +99,20 @@ struct Args {
     /// Keep temporary chunk files after merge
     #[arg(long)]
     keep_temp: bool,
+
+    /// Number of chunk buffers allowed to be queued for sorting at once.
+    /// Bounds peak RAM to roughly `chunk_entries * max_inflight_chunks`.
+    #[arg(long, default_value = "4")]
+    max_inflight_chunks: usize,
+
+    /// Threads used to sort and compress chunks in parallel (0 = rayon default)
+    #[arg(long, default_value = "0")]
+    sort_threads: usize,
+
+    /// Abort the export if free space on the temp volume would drop below
+    /// this fraction of the volume's total capacity
+    #[arg(long, default_value = "0.1")]
+    reserved_disk_ratio: f64,
 }
 
 #[derive(Clone)]
@@ -77,6 +121,67 @@ struct EntryWithKey {
     entry: EntryBytes,
 }
 
+/// One completed, sorted, zstd-compressed chunk file, as recorded in
+/// `chunk_manifest.json` for crash resume.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChunkManifestEntry {
+    path: PathBuf,
+    entry_count: u64,
+    #[serde(with = "hex32")]
+    first_tree_key: [u8; 32],
+    #[serde(with = "hex32")]
+    last_tree_key: [u8; 32],
+    /// Last raw `PlainStorageState` (address, slot) consumed from the DB
+    /// cursor before this chunk was cut, so a resumed run can skip straight
+    /// past it instead of re-reading everything from the start.
+    #[serde(with = "hex_address")]
+    last_address: [u8; 20],
+    #[serde(with = "hex32")]
+    last_slot: [u8; 32],
+}
+
+/// Resume manifest for the sort-chunk stage, written to `tmp_dir`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChunkManifest {
+    db_path: PathBuf,
+    chain_id: u64,
+    block_number: u64,
+    #[serde(with = "hex32")]
+    block_hash: [u8; 32],
+    chunks: Vec<ChunkManifestEntry>,
+}
+
+const CHUNK_MANIFEST_FILE: &str = "chunk_manifest.json";
+
+impl ChunkManifest {
+    fn manifest_path(tmp_dir: &Path) -> PathBuf {
+        tmp_dir.join(CHUNK_MANIFEST_FILE)
+    }
+
+    fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Whether this manifest was produced by a run against the same DB and
+    /// the same canonical head we're about to export.
+    fn matches(&self, args: &Args, block_number: u64, block_hash: [u8; 32]) -> bool {
+        self.db_path == args.db_path
+            && self.chain_id == args.chain_id
+            && self.block_number == block_number
+            && self.block_hash == block_hash
+    }
+
+    /// Atomically overwrite the manifest file so a crash mid-write never
+    /// leaves a corrupt (and therefore unusable) resume point behind.
+    fn save(&self, path: &Path) -> Result<()> {
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
 struct ChunkRecord {
     tree_key: [u8; 32],
     entry: EntryBytes,
@@ -127,11 +232,13 @@ fn main() -> Result<()> {
     if args.no_sort {
         export_unsorted(&mut tx, &args, block_number, block_hash)?;
     } else {
-        let (total_entries, chunk_paths) = build_sorted_chunks(&mut tx, &args)?;
+        let (total_entries, chunk_paths, manifest_path) =
+            build_sorted_chunks(&mut tx, &args, block_number, block_hash)?;
         tx.commit()?;
         write_sorted_output(&args, block_number, block_hash, total_entries, &chunk_paths)?;
         if !args.keep_temp {
             cleanup_chunks(&chunk_paths)?;
+            let _ = fs::remove_file(&manifest_path);
         }
         return Ok(());
     }
@@ -165,11 +272,13 @@ fn export_unsorted(
 
     let pb = spinner("Exporting entries (unsorted)");
     let mut count = 0u64;
+    let mut hasher = blake3::Hasher::new();
 
     while let Some(row) = walker.next() {
         let (address, storage_entry) = row?;
         let entry = encode_entry(address, storage_entry)?;
         writer.write_all(&entry)?;
+        hasher.update(&entry);
         count += 1;
 
         if count % args.progress_interval == 0 {
@@ -182,7 +291,9 @@ fn export_unsorted(
     let mut file = writer.into_inner().map_err(|e| e.into_error())?;
     file.seek(SeekFrom::Start(0))?;
 
-    let header = StateHeader::new(count, block_number, args.chain_id, block_hash);
+    let hashed_bytes = count * STATE_ENTRY_SIZE as u64;
+    let header = StateHeader::new(count, block_number, args.chain_id, block_hash)
+        .with_digest(*hasher.finalize().as_bytes(), hashed_bytes);
     file.write_all(&header.to_bytes())?;
     file.flush()?;
 
@@ -192,31 +303,155 @@ fn export_unsorted(
     Ok(())
 }
 
-fn build_sorted_chunks(tx: &mut impl DbTx, args: &Args) -> Result<(u64, Vec<PathBuf>)> {
+/// Chunk buffer handed from the producer (DB walker) to the sorting pool,
+/// tagged with the raw DB position of its last row for manifest resume.
+struct PendingChunk {
+    chunk_index: usize,
+    buffer: Vec<EntryWithKey>,
+    last_address: [u8; 20],
+    last_slot: [u8; 32],
+}
+
+/// Returns `(total_entries, chunk_paths, manifest_path)`. `chunk_paths`
+/// covers both chunks resumed from a prior run's manifest and newly written
+/// ones; the manifest itself is left on disk until the caller has finished
+/// merging (see [`main`]), so a crash during the merge can still resume.
+fn build_sorted_chunks(
+    tx: &mut impl DbTx,
+    args: &Args,
+    block_number: u64,
+    block_hash: [u8; 32],
+) -> Result<(u64, Vec<PathBuf>, PathBuf)> {
     let tmp_dir = temp_dir(&args.output, args.tmp_dir.as_ref())?;
-    fs::create_dir_all(&tmp_dir)?;
+    let manifest_path = ChunkManifest::manifest_path(&tmp_dir);
+
+    let resumed = load_resumable_manifest(&manifest_path, args, block_number, block_hash)?;
+    let (mut completed, resume_from, start_chunk_index, mut count) = match resumed {
+        Some(manifest) => {
+            info!(
+                chunks = manifest.chunks.len(),
+                entries = manifest.chunks.iter().map(|c| c.entry_count).sum::<u64>(),
+                "Resuming export: reusing chunks from previous run"
+            );
+            let resume_from = manifest.chunks.last().map(|c| (c.last_address, c.last_slot));
+            let count = manifest.chunks.iter().map(|c| c.entry_count).sum();
+            let start_index = manifest.chunks.len();
+            (manifest.chunks, resume_from, start_index, count)
+        }
+        None => {
+            cleanup_orphaned_temp_dir(&tmp_dir)?;
+            fs::create_dir_all(&tmp_dir)?;
+            (Vec::new(), None, 0usize, 0u64)
+        }
+    };
 
-    info!(tmp_dir = %tmp_dir.display(), "Writing sorted chunks");
+    info!(tmp_dir = %tmp_dir.display(), max_inflight_chunks = args.max_inflight_chunks, "Writing sorted chunks");
+
+    let mut builder = rayon::ThreadPoolBuilder::new();
+    if args.sort_threads > 0 {
+        builder = builder.num_threads(args.sort_threads);
+    }
+    let thread_pool = builder
+        .build()
+        .context("Failed to build chunk-sorting thread pool")?;
+
+    // Bounds how many unsorted buffers may sit in memory at once: the
+    // producer (DB walker) blocks on send() once this many are queued.
+    let (tx_chunks, rx_chunks) = sync_channel::<PendingChunk>(args.max_inflight_chunks);
+
+    // Persist the manifest as a contiguous prefix of completed chunks as
+    // they land: a later chunk can finish sorting before an earlier one
+    // (rayon doesn't preserve submission order), but only a gap-free prefix
+    // from `start_chunk_index` is ever a safe resume point.
+    let new_chunks: Arc<Mutex<BTreeMap<usize, ChunkManifestEntry>>> = Arc::new(Mutex::new(BTreeMap::new()));
+    let new_chunks_for_worker = new_chunks.clone();
+    let resumed_chunks = completed.clone();
+    let worker_tmp_dir = tmp_dir.clone();
+    let worker_manifest_path = manifest_path.clone();
+    let reserved_disk_ratio = args.reserved_disk_ratio;
+    let manifest_db_path = args.db_path.clone();
+    let manifest_chain_id = args.chain_id;
+
+    let worker = thread::spawn(move || -> Result<()> {
+        thread_pool.install(|| {
+            rx_chunks.into_iter().par_bridge().try_for_each(|pending| -> Result<()> {
+                check_disk_pressure(&worker_tmp_dir, reserved_disk_ratio)?;
+                let entry_count = pending.buffer.len() as u64;
+                let mut buffer = pending.buffer;
+                buffer.sort_by_key(|entry| entry.tree_key);
+                let first_tree_key = buffer.first().map(|e| e.tree_key).unwrap_or([0u8; 32]);
+                let last_tree_key = buffer.last().map(|e| e.tree_key).unwrap_or([0u8; 32]);
+                // Already sorted above; flush_chunk's own sort is then a cheap no-op pass.
+                let path = flush_chunk(&mut buffer, &worker_tmp_dir, pending.chunk_index)?;
+                let manifest_entry = ChunkManifestEntry {
+                    path,
+                    entry_count,
+                    first_tree_key,
+                    last_tree_key,
+                    last_address: pending.last_address,
+                    last_slot: pending.last_slot,
+                };
+
+                let mut map = new_chunks_for_worker
+                    .lock()
+                    .map_err(|_| anyhow!("chunk manifest map poisoned"))?;
+                map.insert(pending.chunk_index, manifest_entry);
+
+                let mut chunks = resumed_chunks.clone();
+                let mut next = resumed_chunks.len();
+                while let Some(entry) = map.get(&next) {
+                    chunks.push(entry.clone());
+                    next += 1;
+                }
+                drop(map);
+
+                ChunkManifest {
+                    db_path: manifest_db_path.clone(),
+                    chain_id: manifest_chain_id,
+                    block_number,
+                    block_hash,
+                    chunks,
+                }
+                .save(&worker_manifest_path)
+            })
+        })
+    });
 
     let mut cursor = tx.cursor_dup_read::<tables::PlainStorageState>()?;
-    let mut walker = cursor.walk_dup(None, None)?;
+    let mut walker = if let Some((resume_address, resume_slot)) = resume_from {
+        let resume_key: PlainStorageKey = resume_address.into();
+        let resume_subkey: PlainStorageSubKey = resume_slot.into();
+        let mut w = cursor.walk_dup(Some(resume_key), Some(resume_subkey))?;
+        w.next().transpose()?; // walk_dup is inclusive; skip the row we've already chunked
+        w
+    } else {
+        cursor.walk_dup(None, None)?
+    };
 
     let pb = spinner("Sorting chunks");
-    let mut count = 0u64;
-    let mut chunk_index = 0usize;
-    let mut chunk_paths = Vec::new();
-
+    let mut chunk_index = start_chunk_index;
     let mut buffer: Vec<EntryWithKey> = Vec::with_capacity(args.chunk_entries);
+    let mut last_address = [0u8; 20];
+    let mut last_slot = [0u8; 32];
 
     while let Some(row) = walker.next() {
         let (address, storage_entry) = row?;
         let (tree_key, entry) = encode_entry_with_key(address, storage_entry)?;
         buffer.push(EntryWithKey { tree_key, entry });
+        last_address = address.0 .0;
+        last_slot = storage_entry.key.0;
         count += 1;
 
         if buffer.len() >= args.chunk_entries {
-            let path = flush_chunk(&mut buffer, &tmp_dir, chunk_index)?;
-            chunk_paths.push(path);
+            let full = std::mem::replace(&mut buffer, Vec::with_capacity(args.chunk_entries));
+            tx_chunks
+                .send(PendingChunk {
+                    chunk_index,
+                    buffer: full,
+                    last_address,
+                    last_slot,
+                })
+                .map_err(|_| anyhow!("chunk-sorting worker pool terminated early"))?;
             chunk_index += 1;
         }
 
@@ -226,13 +461,105 @@ fn build_sorted_chunks(tx: &mut impl DbTx, args: &Args) -> Result<(u64, Vec<Path
     }
 
     if !buffer.is_empty() {
-        let path = flush_chunk(&mut buffer, &tmp_dir, chunk_index)?;
-        chunk_paths.push(path);
+        tx_chunks
+            .send(PendingChunk {
+                chunk_index,
+                buffer,
+                last_address,
+                last_slot,
+            })
+            .map_err(|_| anyhow!("chunk-sorting worker pool terminated early"))?;
     }
+    drop(tx_chunks);
+
+    worker
+        .join()
+        .map_err(|_| anyhow!("chunk-sorting worker pool panicked"))??;
+
+    // Every chunk submitted was processed without error (the ? above would
+    // have propagated otherwise), so the map is exactly the contiguous run
+    // [start_chunk_index, chunk_index] - no gap-checking needed here.
+    let new_entries = Arc::try_unwrap(new_chunks)
+        .map_err(|_| anyhow!("chunk manifest map still has outstanding references"))?
+        .into_inner()
+        .map_err(|_| anyhow!("chunk manifest map poisoned"))?;
+    completed.extend(new_entries.into_values());
+
+    ChunkManifest {
+        db_path: args.db_path.clone(),
+        chain_id: args.chain_id,
+        block_number,
+        block_hash,
+        chunks: completed.clone(),
+    }
+    .save(&manifest_path)?;
+
+    let chunk_paths = completed.into_iter().map(|c| c.path).collect();
 
     pb.finish_with_message(format!("Prepared {} entries", count));
 
-    Ok((count, chunk_paths))
+    Ok((count, chunk_paths, manifest_path))
+}
+
+/// Load and validate a resumable manifest for this run, if one exists.
+/// Returns `None` (and invalidates any stale `tmp_dir`) when there is no
+/// manifest, it fails to parse, or it was written against a different DB or
+/// a different canonical head.
+fn load_resumable_manifest(
+    manifest_path: &Path,
+    args: &Args,
+    block_number: u64,
+    block_hash: [u8; 32],
+) -> Result<Option<ChunkManifest>> {
+    if !manifest_path.exists() {
+        return Ok(None);
+    }
+
+    match ChunkManifest::load(manifest_path) {
+        Ok(manifest) if manifest.matches(args, block_number, block_hash) => Ok(Some(manifest)),
+        Ok(_) => {
+            warn!("Chunk manifest is for a different db_path/chain_id/head - discarding and restarting export");
+            Ok(None)
+        }
+        Err(err) => {
+            warn!(error = %err, "Failed to parse chunk manifest - discarding and restarting export");
+            Ok(None)
+        }
+    }
+}
+
+/// Remove a `tmp_dir` left behind by a previous crashed run before starting
+/// a fresh one, so its stale `chunk_*.bin` files don't get merged in.
+fn cleanup_orphaned_temp_dir(tmp_dir: &Path) -> Result<()> {
+    if tmp_dir.exists() {
+        warn!(tmp_dir = %tmp_dir.display(), "Removing orphaned chunk directory left by a previous run");
+        fs::remove_dir_all(tmp_dir)
+            .with_context(|| format!("Failed to remove orphaned temp dir {}", tmp_dir.display()))?;
+    }
+    Ok(())
+}
+
+/// Abort rather than risk filling the temp volume: check that free space on
+/// `tmp_dir` is still above `reserved_ratio` of the volume's total capacity.
+fn check_disk_pressure(tmp_dir: &Path, reserved_ratio: f64) -> Result<()> {
+    let available = fs2::available_space(tmp_dir)
+        .with_context(|| format!("Failed to read free space for {}", tmp_dir.display()))?;
+    let total = fs2::total_space(tmp_dir)
+        .with_context(|| format!("Failed to read total space for {}", tmp_dir.display()))?;
+    let reserved = (total as f64 * reserved_ratio) as u64;
+
+    if available < reserved {
+        return Err(anyhow!(
+            "Temp volume {} has {} bytes free, below the reserved threshold of {} bytes ({:.0}% of {} total) - aborting export before the disk fills up",
+            tmp_dir.display(),
+            available,
+            reserved,
+            reserved_ratio * 100.0,
+            total,
+        ));
+    }
+
+    Ok(())
 }
 
 fn write_sorted_output(
@@ -244,14 +571,21 @@ fn write_sorted_output(
 ) -> Result<()> {
     info!(output = %args.output.display(), "Merging sorted chunks");
 
-    let file = File::create(&args.output)?;
+    let output_tmp = {
+        let mut name = args.output.clone().into_os_string();
+        name.push(".tmp");
+        PathBuf::from(name)
+    };
+    let file = File::create(&output_tmp)?;
     let mut writer = BufWriter::new(file);
-    let header = StateHeader::new(total_entries, block_number, args.chain_id, block_hash);
-    writer.write_all(&header.to_bytes())?;
+    writer.write_all(&[0u8; STATE_HEADER_SIZE])?; // placeholder
 
-    let mut readers: Vec<BufReader<File>> = Vec::with_capacity(chunk_paths.len());
+    let mut readers: Vec<zstd::Decoder<'static, BufReader<File>>> = Vec::with_capacity(chunk_paths.len());
     for path in chunk_paths {
-        readers.push(BufReader::new(File::open(path)?));
+        readers.push(
+            zstd::Decoder::new(File::open(path)?)
+                .with_context(|| format!("Failed to open compressed chunk {}", path.display()))?,
+        );
     }
 
     let mut heap: BinaryHeap<std::cmp::Reverse<HeapItem>> = BinaryHeap::new();
@@ -267,9 +601,11 @@ fn write_sorted_output(
 
     let pb = spinner("Merging chunks");
     let mut written = 0u64;
+    let mut hasher = blake3::Hasher::new();
 
     while let Some(std::cmp::Reverse(item)) = heap.pop() {
         writer.write_all(&item.entry)?;
+        hasher.update(&item.entry);
         written += 1;
 
         if written % args.progress_interval == 0 {
@@ -287,6 +623,26 @@ fn write_sorted_output(
     }
 
     writer.flush()?;
+    let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+    file.seek(SeekFrom::Start(0))?;
+
+    let hashed_bytes = written * STATE_ENTRY_SIZE as u64;
+    let header = StateHeader::new(total_entries, block_number, args.chain_id, block_hash)
+        .with_digest(*hasher.finalize().as_bytes(), hashed_bytes);
+    file.write_all(&header.to_bytes())?;
+    file.flush()?;
+    drop(file);
+
+    // Rename into place only after the file is fully written and flushed,
+    // so a crash mid-merge never leaves a half-written state.bin at `output`.
+    fs::rename(&output_tmp, &args.output).with_context(|| {
+        format!(
+            "Failed to move completed export from {} to {}",
+            output_tmp.display(),
+            args.output.display()
+        )
+    })?;
+
     pb.finish_with_message(format!("Merged {} entries", written));
 
     if written != total_entries {
@@ -324,9 +680,9 @@ fn encode_entry_with_key(
 fn flush_chunk(buffer: &mut Vec<EntryWithKey>, dir: &Path, index: usize) -> Result<PathBuf> {
     buffer.sort_by_key(|entry| entry.tree_key);
 
-    let path = dir.join(format!("chunk_{:05}.bin", index));
+    let path = dir.join(format!("chunk_{:05}.bin.zst", index));
     let file = File::create(&path)?;
-    let mut writer = BufWriter::new(file);
+    let mut writer = zstd::Encoder::new(BufWriter::new(file), CHUNK_ZSTD_LEVEL)?.auto_finish();
 
     for entry in buffer.iter() {
         writer.write_all(&entry.tree_key)?;
@@ -339,7 +695,7 @@ fn flush_chunk(buffer: &mut Vec<EntryWithKey>, dir: &Path, index: usize) -> Resu
     Ok(path)
 }
 
-fn read_record(reader: &mut BufReader<File>) -> Result<Option<ChunkRecord>> {
+fn read_record(reader: &mut impl Read) -> Result<Option<ChunkRecord>> {
     let mut buf = [0u8; RECORD_SIZE];
     match reader.read_exact(&mut buf) {
         Ok(()) => {
@@ -384,3 +740,45 @@ fn spinner(message: &str) -> ProgressBar {
     pb.set_message(message.to_string());
     pb
 }
+
+mod hex32 {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(bytes: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<[u8; 32], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let s = s.strip_prefix("0x").unwrap_or(&s);
+        let bytes = hex::decode(s).map_err(serde::de::Error::custom)?;
+        bytes.try_into().map_err(|_| serde::de::Error::custom("invalid 32-byte hex length"))
+    }
+}
+
+mod hex_address {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(address: &[u8; 20], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("0x{}", hex::encode(address)))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<[u8; 20], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let s = s.strip_prefix("0x").unwrap_or(&s);
+        let bytes = hex::decode(s).map_err(serde::de::Error::custom)?;
+        bytes.try_into().map_err(|_| serde::de::Error::custom("invalid address length"))
+    }
+}
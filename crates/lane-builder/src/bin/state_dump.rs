@@ -11,8 +11,8 @@
 
 #![cfg(feature = "state-dump")]
 
+use std::collections::BTreeSet;
 use std::ffi::CString;
-use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 use std::ptr;
@@ -50,6 +50,76 @@ struct Args {
     /// Log progress every N records
     #[arg(long, default_value = "1000000")]
     progress_interval: u64,
+
+    /// Instead of a full re-walk of `PlainAccountState`/`PlainStorageState`,
+    /// read `block_number` out of `output_dir/metadata.json` from a
+    /// previous run and only emit entries touched in `(last_dumped, to_block]`,
+    /// computed from `AccountChangeSets`/`StorageChangeSets`. Requires
+    /// `--to-block` and a prior full (or incremental) dump in `output_dir`.
+    #[arg(long)]
+    incremental: bool,
+
+    /// Upper bound (inclusive) of the incremental window. Required with
+    /// `--incremental`. A full dump always reflects whatever
+    /// `PlainAccountState`/`PlainStorageState` currently hold and only
+    /// records this value as `block_number` in `metadata.json`; a full dump
+    /// run without it records no block number.
+    #[arg(long)]
+    to_block: Option<u64>,
+
+    /// Where to write dump output. Defaults to `--output-dir` on the local
+    /// filesystem; an `s3://bucket/prefix` URL streams output straight to
+    /// S3-compatible object storage instead (see `inspire_core::store`),
+    /// which is what lets a dumper and its query nodes not share a
+    /// filesystem. `--output-dir` is still created/used as a scratch dir
+    /// for `--incremental`'s prior-metadata lookup when this is unset.
+    #[arg(long)]
+    output_store: Option<String>,
+
+    /// AWS region for `--output-store s3://...`. Ignored otherwise.
+    #[arg(long, default_value = "us-east-1")]
+    s3_region: String,
+
+    /// Access key ID for `--output-store s3://...`. Ignored otherwise.
+    #[arg(long)]
+    s3_access_key_id: Option<String>,
+
+    /// Secret access key for `--output-store s3://...`. Ignored otherwise.
+    #[arg(long)]
+    s3_secret_access_key: Option<String>,
+
+    /// Override endpoint for an S3-compatible (non-AWS) service, e.g.
+    /// `http://minio.internal:9000`. Ignored for filesystem output.
+    #[arg(long)]
+    s3_endpoint: Option<String>,
+}
+
+/// Opens the [`inspire_core::Store`] `--output-store` (or `--output-dir`,
+/// if unset) points at.
+fn build_store(args: &Args) -> Result<Box<dyn inspire_core::Store>> {
+    let location = args
+        .output_store
+        .clone()
+        .unwrap_or_else(|| args.output_dir.to_string_lossy().into_owned());
+
+    let s3_config = if location.starts_with("s3://") {
+        Some(inspire_core::S3Config {
+            region: args.s3_region.clone(),
+            access_key_id: args
+                .s3_access_key_id
+                .clone()
+                .ok_or_else(|| eyre::eyre!("--output-store s3://... requires --s3-access-key-id"))?,
+            secret_access_key: args
+                .s3_secret_access_key
+                .clone()
+                .ok_or_else(|| eyre::eyre!("--output-store s3://... requires --s3-secret-access-key"))?,
+            endpoint: args.s3_endpoint.clone(),
+        })
+    } else {
+        None
+    };
+
+    Ok(inspire_core::open_store(&location, s3_config)?)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +130,12 @@ struct DumpMetadata {
     num_storage_slots: u64,
     entry_size: usize,
     manifest_entry_size: usize,
+    /// Block height this dump (full or incremental) reflects. `None` for
+    /// dumps produced before incremental mode existed -- those can't be
+    /// incrementally refreshed and need one more full dump to start the
+    /// chain of deltas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    block_number: Option<u64>,
 }
 
 fn main() -> Result<()> {
@@ -69,6 +145,11 @@ fn main() -> Result<()> {
     let args = Args::parse();
 
     std::fs::create_dir_all(&args.output_dir)?;
+    let store = build_store(&args)?;
+
+    if args.incremental {
+        return main_incremental(&args, store.as_ref());
+    }
 
     tracing::info!(
         db_path = %args.db_path.display(),
@@ -120,7 +201,8 @@ fn main() -> Result<()> {
             num_accounts = dump_table(
                 txn,
                 "PlainAccountState",
-                &args.output_dir.join("accounts.bin"),
+                store.as_ref(),
+                "accounts.bin",
                 args.progress_interval,
                 false,
             )?;
@@ -130,7 +212,8 @@ fn main() -> Result<()> {
             num_storage_slots = dump_table(
                 txn,
                 "PlainStorageState",
-                &args.output_dir.join("storage.bin"),
+                store.as_ref(),
+                "storage.bin",
                 args.progress_interval,
                 true,
             )?;
@@ -146,26 +229,394 @@ fn main() -> Result<()> {
         num_storage_slots,
         entry_size: 32,
         manifest_entry_size: 52,
+        block_number: args.to_block,
     };
 
-    let metadata_path = args.output_dir.join("metadata.json");
     let metadata_json = serde_json::to_string_pretty(&metadata)?;
-    std::fs::write(&metadata_path, &metadata_json)?;
+    store.put_writer("metadata.json")?.write_all(metadata_json.as_bytes())?;
 
     tracing::info!(
         accounts = num_accounts,
         storage_slots = num_storage_slots,
-        metadata = %metadata_path.display(),
         "State dump complete"
     );
 
     Ok(())
 }
 
+/// Incremental dump: instead of re-walking `PlainAccountState`/
+/// `PlainStorageState` in full, figure out which keys changed in
+/// `(last_dumped, to_block]` from the changeset tables, then re-read only
+/// those keys' *current* values.
+///
+/// `AccountChangeSets`/`StorageChangeSets` record the value a key had
+/// *before* each block that touched it (for reorg/unwind support), not the
+/// value after -- so they're only used here to build the set of touched
+/// keys; the actual delta payload always comes from a point lookup against
+/// the plain-state tables, which hold the current value.
+fn main_incremental(args: &Args, store: &dyn inspire_core::Store) -> Result<()> {
+    let to_block = args
+        .to_block
+        .ok_or_else(|| eyre::eyre!("--incremental requires --to-block"))?;
+
+    let previous_bytes = store
+        .get("metadata.json")
+        .map_err(|e| eyre::eyre!("failed to read prior dump metadata: {}", e))?;
+    let previous: DumpMetadata = serde_json::from_slice(&previous_bytes)
+        .map_err(|e| eyre::eyre!("failed to parse prior dump metadata: {}", e))?;
+    let from_block = previous.block_number.ok_or_else(|| {
+        eyre::eyre!("prior dump's metadata.json has no block_number recorded -- run a full (non-incremental) dump first")
+    })? + 1;
+
+    if from_block > to_block {
+        return Err(eyre::eyre!(
+            "nothing to do: prior dump already covers block {} (requested to-block {})",
+            from_block - 1,
+            to_block
+        ));
+    }
+
+    tracing::info!(
+        db_path = %args.db_path.display(),
+        output_dir = %args.output_dir.display(),
+        from_block,
+        to_block,
+        "Starting incremental state dump"
+    );
+
+    let (mut num_accounts, mut num_storage_slots) = (0u64, 0u64);
+
+    unsafe {
+        let mut env: *mut MDBX_env = ptr::null_mut();
+        let rc = mdbx_env_create(&mut env);
+        if rc != MDBX_SUCCESS {
+            return Err(eyre::eyre!("Failed to create MDBX environment: {}", rc));
+        }
+        let rc = mdbx_env_set_maxdbs(env, 64);
+        if rc != MDBX_SUCCESS {
+            mdbx_env_close(env);
+            return Err(eyre::eyre!("Failed to set maxdbs: {}", rc));
+        }
+
+        let db_path_str = args.db_path.to_string_lossy();
+        let path_cstr = CString::new(db_path_str.as_ref())?;
+        let rc = mdbx_env_open(env, path_cstr.as_ptr(), MDBX_RDONLY as u32, 0o644);
+        if rc != MDBX_SUCCESS {
+            mdbx_env_close(env);
+            return Err(eyre::eyre!("Failed to open MDBX environment: {}", rc));
+        }
+
+        let mut txn: *mut MDBX_txn = ptr::null_mut();
+        let rc = mdbx_txn_begin(env, ptr::null_mut(), MDBX_RDONLY as u32, &mut txn);
+        if rc != MDBX_SUCCESS {
+            mdbx_env_close(env);
+            return Err(eyre::eyre!("Failed to begin transaction: {}", rc));
+        }
+
+        if !args.storage_only {
+            let touched = collect_touched_addresses(txn, from_block, to_block)?;
+            num_accounts = write_account_deltas(
+                txn,
+                &touched,
+                store,
+                &format!("accounts-delta-{from_block}-{to_block}.bin"),
+            )?;
+        }
+
+        if !args.accounts_only {
+            let touched = collect_touched_storage_slots(txn, from_block, to_block)?;
+            num_storage_slots = write_storage_deltas(
+                txn,
+                &touched,
+                store,
+                &format!("storage-delta-{from_block}-{to_block}.bin"),
+            )?;
+        }
+
+        mdbx_txn_abort(txn);
+        mdbx_env_close(env);
+    }
+
+    let metadata = DumpMetadata {
+        chain: args.chain.clone(),
+        num_accounts: previous.num_accounts + num_accounts,
+        num_storage_slots: previous.num_storage_slots + num_storage_slots,
+        entry_size: previous.entry_size,
+        manifest_entry_size: previous.manifest_entry_size,
+        block_number: Some(to_block),
+    };
+    store
+        .put_writer("metadata.json")?
+        .write_all(serde_json::to_string_pretty(&metadata)?.as_bytes())?;
+
+    tracing::info!(
+        from_block,
+        to_block,
+        touched_accounts = num_accounts,
+        touched_storage_slots = num_storage_slots,
+        "Incremental state dump complete"
+    );
+
+    Ok(())
+}
+
+/// Walk `AccountChangeSets` over `[from_block, to_block]` and return the set
+/// of 20-byte addresses touched in that range.
+///
+/// Reth keys `AccountChangeSets` by an 8-byte big-endian block number, so
+/// `MDBX_SET_RANGE` on `from_block`'s encoding lands exactly on the first
+/// entry in the window (or the first entry past it, if `from_block` itself
+/// wasn't touched); `MDBX_NEXT` from there stays in block-number order, and
+/// the loop stops as soon as a key decodes past `to_block`.
+unsafe fn collect_touched_addresses(
+    txn: *mut MDBX_txn,
+    from_block: u64,
+    to_block: u64,
+) -> Result<BTreeSet<[u8; 20]>> {
+    let mut touched = BTreeSet::new();
+
+    let table_cstr = CString::new("AccountChangeSets")?;
+    let mut dbi: MDBX_dbi = 0;
+    let rc = mdbx_dbi_open(txn, table_cstr.as_ptr(), 0, &mut dbi);
+    if rc != MDBX_SUCCESS {
+        return Err(eyre::eyre!("Failed to open AccountChangeSets: {}", rc));
+    }
+
+    let mut cursor: *mut MDBX_cursor = ptr::null_mut();
+    let rc = mdbx_cursor_open(txn, dbi, &mut cursor);
+    if rc != MDBX_SUCCESS {
+        return Err(eyre::eyre!("Failed to open cursor: {}", rc));
+    }
+
+    let start_key = from_block.to_be_bytes();
+    let mut key = MDBX_val {
+        iov_base: start_key.as_ptr() as *mut _,
+        iov_len: start_key.len(),
+    };
+    let mut val = MDBX_val::default();
+
+    let mut rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_SET_RANGE as MDBX_cursor_op);
+
+    while rc == MDBX_SUCCESS {
+        let key_bytes = std::slice::from_raw_parts(key.iov_base as *const u8, key.iov_len);
+        if key_bytes.len() < 8 {
+            rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_NEXT as MDBX_cursor_op);
+            continue;
+        }
+        let block: u64 = u64::from_be_bytes(key_bytes[0..8].try_into().expect("checked len"));
+        if block > to_block {
+            break;
+        }
+
+        let val_bytes = std::slice::from_raw_parts(val.iov_base as *const u8, val.iov_len);
+        if val_bytes.len() >= 20 {
+            let address: [u8; 20] = val_bytes[0..20].try_into().expect("checked len");
+            touched.insert(address);
+        }
+
+        rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_NEXT as MDBX_cursor_op);
+    }
+
+    if rc != MDBX_SUCCESS && rc != MDBX_NOTFOUND {
+        mdbx_cursor_close(cursor);
+        return Err(eyre::eyre!("Cursor error while walking AccountChangeSets: {}", rc));
+    }
+
+    mdbx_cursor_close(cursor);
+    Ok(touched)
+}
+
+/// Walk `StorageChangeSets` (DUPSORT, keyed by `BlockNumberAddress`) over
+/// `[from_block, to_block]` and return the set of `(address, slot)` pairs
+/// touched in that range.
+unsafe fn collect_touched_storage_slots(
+    txn: *mut MDBX_txn,
+    from_block: u64,
+    to_block: u64,
+) -> Result<BTreeSet<([u8; 20], [u8; 32])>> {
+    let mut touched = BTreeSet::new();
+
+    let table_cstr = CString::new("StorageChangeSets")?;
+    let mut dbi: MDBX_dbi = 0;
+    let rc = mdbx_dbi_open(txn, table_cstr.as_ptr(), 0, &mut dbi);
+    if rc != MDBX_SUCCESS {
+        return Err(eyre::eyre!("Failed to open StorageChangeSets: {}", rc));
+    }
+
+    let mut cursor: *mut MDBX_cursor = ptr::null_mut();
+    let rc = mdbx_cursor_open(txn, dbi, &mut cursor);
+    if rc != MDBX_SUCCESS {
+        return Err(eyre::eyre!("Failed to open cursor: {}", rc));
+    }
+
+    let start_key = from_block.to_be_bytes();
+    let mut key = MDBX_val {
+        iov_base: start_key.as_ptr() as *mut _,
+        iov_len: start_key.len(),
+    };
+    let mut val = MDBX_val::default();
+
+    let mut rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_SET_RANGE as MDBX_cursor_op);
+
+    while rc == MDBX_SUCCESS {
+        let key_bytes = std::slice::from_raw_parts(key.iov_base as *const u8, key.iov_len);
+        // BlockNumberAddress = 8-byte BE block number + 20-byte address
+        if key_bytes.len() < 28 {
+            rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_NEXT as MDBX_cursor_op);
+            continue;
+        }
+        let block: u64 = u64::from_be_bytes(key_bytes[0..8].try_into().expect("checked len"));
+        if block > to_block {
+            break;
+        }
+        let address: [u8; 20] = key_bytes[8..28].try_into().expect("checked len");
+
+        // DUPSORT value is slot(32) + value-before(32); only the slot is
+        // needed to know what to re-read from PlainStorageState.
+        let val_bytes = std::slice::from_raw_parts(val.iov_base as *const u8, val.iov_len);
+        if val_bytes.len() >= 32 {
+            let slot: [u8; 32] = val_bytes[0..32].try_into().expect("checked len");
+            touched.insert((address, slot));
+        }
+
+        rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_NEXT as MDBX_cursor_op);
+    }
+
+    if rc != MDBX_SUCCESS && rc != MDBX_NOTFOUND {
+        mdbx_cursor_close(cursor);
+        return Err(eyre::eyre!("Cursor error while walking StorageChangeSets: {}", rc));
+    }
+
+    mdbx_cursor_close(cursor);
+    Ok(touched)
+}
+
+/// Point-read each touched address's *current* value out of
+/// `PlainAccountState` via `MDBX_SET_RANGE` and append it to a delta file in
+/// the same `key || value` layout [`dump_table`] uses for the full dump, so
+/// the server-side loader can apply either kind of file the same way.
+///
+/// An address with no hit (self-destructed and since pruned) is skipped --
+/// a consumer applying this delta should treat a missing key as "leave
+/// whatever value it last had," since changesets don't carry deletions.
+unsafe fn write_account_deltas(
+    txn: *mut MDBX_txn,
+    touched: &BTreeSet<[u8; 20]>,
+    store: &dyn inspire_core::Store,
+    output_key: &str,
+) -> Result<u64> {
+    let table_cstr = CString::new("PlainAccountState")?;
+    let mut dbi: MDBX_dbi = 0;
+    let rc = mdbx_dbi_open(txn, table_cstr.as_ptr(), 0, &mut dbi);
+    if rc != MDBX_SUCCESS {
+        return Err(eyre::eyre!("Failed to open PlainAccountState: {}", rc));
+    }
+
+    let mut cursor: *mut MDBX_cursor = ptr::null_mut();
+    let rc = mdbx_cursor_open(txn, dbi, &mut cursor);
+    if rc != MDBX_SUCCESS {
+        return Err(eyre::eyre!("Failed to open cursor: {}", rc));
+    }
+
+    let mut writer = store.put_writer(output_key)?;
+    let mut count = 0u64;
+
+    for address in touched {
+        let mut key = MDBX_val {
+            iov_base: address.as_ptr() as *mut _,
+            iov_len: address.len(),
+        };
+        let mut val = MDBX_val::default();
+
+        let rc = mdbx_cursor_get(cursor, &mut key, &mut val, MDBX_SET_RANGE as MDBX_cursor_op);
+        if rc != MDBX_SUCCESS {
+            continue;
+        }
+        let key_bytes = std::slice::from_raw_parts(key.iov_base as *const u8, key.iov_len);
+        if key_bytes != address {
+            // MDBX_SET_RANGE landed on the next key after this address --
+            // i.e. this address has no current entry.
+            continue;
+        }
+
+        let val_bytes = std::slice::from_raw_parts(val.iov_base as *const u8, val.iov_len);
+        writer.write_all(address)?;
+        writer.write_all(val_bytes)?;
+        count += 1;
+    }
+
+    writer.flush()?;
+    mdbx_cursor_close(cursor);
+    tracing::info!(count, key = output_key, "Account delta written");
+    Ok(count)
+}
+
+/// Point-read each touched `(address, slot)`'s current value out of the
+/// DUPSORT `PlainStorageState` table via `MDBX_GET_BOTH_RANGE` (seek to
+/// `address`, then to the first duplicate whose slot prefix is `>= slot`)
+/// and append it to a delta file, same layout as [`write_account_deltas`].
+unsafe fn write_storage_deltas(
+    txn: *mut MDBX_txn,
+    touched: &BTreeSet<([u8; 20], [u8; 32])>,
+    store: &dyn inspire_core::Store,
+    output_key: &str,
+) -> Result<u64> {
+    let table_cstr = CString::new("PlainStorageState")?;
+    let mut dbi: MDBX_dbi = 0;
+    let rc = mdbx_dbi_open(txn, table_cstr.as_ptr(), 0, &mut dbi);
+    if rc != MDBX_SUCCESS {
+        return Err(eyre::eyre!("Failed to open PlainStorageState: {}", rc));
+    }
+
+    let mut cursor: *mut MDBX_cursor = ptr::null_mut();
+    let rc = mdbx_cursor_open(txn, dbi, &mut cursor);
+    if rc != MDBX_SUCCESS {
+        return Err(eyre::eyre!("Failed to open cursor: {}", rc));
+    }
+
+    let mut writer = store.put_writer(output_key)?;
+    let mut count = 0u64;
+
+    for (address, slot) in touched {
+        let mut key = MDBX_val {
+            iov_base: address.as_ptr() as *mut _,
+            iov_len: address.len(),
+        };
+        // The DUPSORT value is slot(32) + value(32); seeking by slot prefix
+        // only needs the slot bytes as the probe value.
+        let mut probe = MDBX_val {
+            iov_base: slot.as_ptr() as *mut _,
+            iov_len: slot.len(),
+        };
+
+        let rc = mdbx_cursor_get(cursor, &mut key, &mut probe, MDBX_GET_BOTH_RANGE as MDBX_cursor_op);
+        if rc != MDBX_SUCCESS {
+            continue;
+        }
+
+        let val_bytes = std::slice::from_raw_parts(probe.iov_base as *const u8, probe.iov_len);
+        if val_bytes.len() < 32 || &val_bytes[0..32] != slot {
+            // Landed on the next slot for this address -- this exact slot
+            // has no current entry (e.g. it was zeroed and pruned).
+            continue;
+        }
+
+        writer.write_all(address)?;
+        writer.write_all(val_bytes)?;
+        count += 1;
+    }
+
+    writer.flush()?;
+    mdbx_cursor_close(cursor);
+    tracing::info!(count, key = output_key, "Storage delta written");
+    Ok(count)
+}
+
 unsafe fn dump_table(
     txn: *mut MDBX_txn,
     table_name: &str,
-    output_path: &PathBuf,
+    store: &dyn inspire_core::Store,
+    output_key: &str,
     progress_interval: u64,
     is_storage: bool,
 ) -> Result<u64> {
@@ -177,7 +628,7 @@ unsafe fn dump_table(
         return Err(eyre::eyre!("Failed to open table {}: {}", table_name, rc));
     }
 
-    tracing::info!(table = table_name, path = %output_path.display(), "Dumping table");
+    tracing::info!(table = table_name, key = output_key, "Dumping table");
 
     let mut cursor: *mut MDBX_cursor = ptr::null_mut();
     let rc = mdbx_cursor_open(txn, dbi, &mut cursor);
@@ -185,7 +636,7 @@ unsafe fn dump_table(
         return Err(eyre::eyre!("Failed to open cursor: {}", rc));
     }
 
-    let mut writer = BufWriter::with_capacity(64 * 1024 * 1024, File::create(output_path)?);
+    let mut writer = BufWriter::with_capacity(64 * 1024 * 1024, store.put_writer(output_key)?);
 
     let pb = ProgressBar::new_spinner();
     pb.set_style(
@@ -235,7 +686,7 @@ unsafe fn dump_table(
     tracing::info!(
         table = table_name,
         count,
-        path = %output_path.display(),
+        key = output_key,
         "Table dump complete"
     );
 
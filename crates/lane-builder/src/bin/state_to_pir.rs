@@ -25,6 +25,7 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use inspire_core::state_format::{StateHeader, STATE_ENTRY_SIZE, STATE_HEADER_SIZE};
+use inspire_core::SnapshotBlacklist;
 use lane_builder::{default_params, TwoLaneSetup};
 
 #[derive(Parser)]
@@ -80,6 +81,52 @@ fn main() -> anyhow::Result<()> {
         "State file parsed"
     );
 
+    // A snapshot that previously failed digest verification is recorded by
+    // (block_hash, body_digest) so repeated runs (e.g. an automated ExEx
+    // ingestion loop) don't re-read and re-hash the same corrupt file on
+    // every restart.
+    let blacklist_path = args
+        .input
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."))
+        .join("corrupt-snapshots.json");
+    let mut blacklist = SnapshotBlacklist::load(&blacklist_path)?;
+
+    if header.version >= StateHeader::VERSION_WITH_DIGEST
+        && blacklist.contains(&header.block_hash, &header.body_digest)
+    {
+        anyhow::bail!(
+            "Refusing to load {}: this snapshot (block_hash={}, digest={}) is blacklisted as corrupt, see {}",
+            args.input.display(),
+            hex::encode(header.block_hash),
+            hex::encode(header.body_digest),
+            blacklist_path.display(),
+        );
+    }
+
+    // Re-verify the streaming body digest before trusting any entries, so a
+    // truncated or bit-rotted state.bin is caught here rather than silently
+    // producing a PIR database over partial/garbage data.
+    if header.version >= StateHeader::VERSION_WITH_DIGEST {
+        let entries_file = File::open(&args.input)?;
+        let mut entries_reader = BufReader::new(entries_file);
+        std::io::copy(&mut (&mut entries_reader).take(STATE_HEADER_SIZE as u64), &mut std::io::sink())?;
+
+        if let Err(e) = header.verify_digest(entries_reader) {
+            blacklist.record(&header.block_hash, &header.body_digest);
+            blacklist.save(&blacklist_path)?;
+            anyhow::bail!(
+                "State file failed digest verification, blacklisting (block_hash={}, digest={}): {}",
+                hex::encode(header.block_hash),
+                hex::encode(header.body_digest),
+                e,
+            );
+        }
+        tracing::info!("Body digest verified");
+    } else {
+        tracing::warn!("State file has no body digest (legacy version); skipping integrity check");
+    }
+
     // Determine how many entries to process
     let entry_count = if args.max_entries > 0 {
         std::cmp::min(args.max_entries, header.entry_count as usize)
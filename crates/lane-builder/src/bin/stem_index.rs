@@ -35,6 +35,13 @@ struct Args {
     /// Verify the stem index after generation
     #[arg(long)]
     verify: bool,
+
+    /// Recompute and check the input file's body digest against its header
+    /// before indexing, rejecting a truncated or corrupted state.bin up
+    /// front instead of silently indexing garbage. Requires a header
+    /// written with a digest (`StateHeader::VERSION_WITH_DIGEST` or newer).
+    #[arg(long)]
+    verify_checksum: bool,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -66,6 +73,16 @@ fn main() -> anyhow::Result<()> {
         header.entry_count
     );
 
+    if args.verify_checksum {
+        tracing::info!("Verifying body checksum before indexing...");
+        let mut body_reader = BufReader::new(File::open(&args.input)?);
+        body_reader.seek_relative(STATE_HEADER_SIZE as i64)?;
+        header
+            .verify_digest(&mut body_reader)
+            .map_err(|e| anyhow::anyhow!("Checksum verification failed: {}", e))?;
+        tracing::info!("Checksum verified");
+    }
+
     // Build stem -> first_offset map
     // Since entries are sorted by tree_key, we just need to track first occurrence
     let mut stem_offsets: BTreeMap<Stem, u64> = BTreeMap::new();
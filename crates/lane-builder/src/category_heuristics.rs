@@ -0,0 +1,112 @@
+//! Data-driven category inference from observed function selectors.
+//!
+//! [`crate::hybrid_scorer::HybridScorer`] only assigns a `category` to
+//! contracts found in the curated [`crate::contracts::HOT_CONTRACTS`] list --
+//! everything discovered purely through [`crate::gas_tracker::GasTracker`]
+//! backfill shows up as `None`. This module closes that gap by matching a
+//! contract's observed [`crate::gas_tracker::GasStats::selectors`] histogram
+//! against a small embedded table of well-known function signatures, using
+//! the same `keccak256(signature)[..4]` selector computation as the ABI
+//! encoder in the WASM module (`crates/alloy-wasm/src/abi.rs`).
+
+#![cfg(feature = "backfill")]
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use alloy_primitives::keccak256;
+
+/// Canonical Solidity function signatures mapped to the category they imply.
+/// Intentionally small and conservative -- these are selectors that are
+/// essentially unambiguous signals of their category in practice.
+const CATEGORY_SIGNATURES: &[(&str, &str)] = &[
+    ("transfer(address,uint256)", "token"),
+    ("transferFrom(address,address,uint256)", "token"),
+    ("balanceOf(address)", "token"),
+    ("approve(address,uint256)", "token"),
+    ("swapExactTokensForTokens(uint256,uint256,address[],address,uint256)", "dex"),
+    ("swapExactETHForTokens(uint256,address[],address,uint256)", "dex"),
+    ("addLiquidity(address,address,uint256,uint256,uint256,uint256,address,uint256)", "dex"),
+    ("mint(address,uint256)", "dex"),
+    ("swap(uint256,uint256,address,bytes)", "dex"),
+    ("borrow(address,uint256,uint256,uint16,address)", "lending"),
+    ("repay(address,uint256,uint256,address)", "lending"),
+    ("liquidationCall(address,address,address,uint256,bool)", "lending"),
+    ("propose(address[],uint256[],bytes[],string)", "governance"),
+    ("castVote(uint256,uint8)", "governance"),
+    ("delegate(address)", "governance"),
+];
+
+/// 4-byte selector for a canonical function signature, e.g.
+/// `selector("transfer(address,uint256)")`.
+fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Lazily-built lookup from hex-encoded, `0x`-prefixed selector to category.
+fn selector_table() -> &'static HashMap<String, &'static str> {
+    static TABLE: OnceLock<HashMap<String, &'static str>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        CATEGORY_SIGNATURES
+            .iter()
+            .map(|(sig, category)| (format!("0x{}", hex::encode(selector(sig))), *category))
+            .collect()
+    })
+}
+
+/// Infer a category from a contract's observed selector call-count
+/// histogram, scoring each category by the summed call counts of its
+/// matching selectors and returning the highest-scoring one. Returns `None`
+/// if no observed selector matches the embedded table.
+pub fn infer_category(selectors: &HashMap<String, u64>) -> Option<String> {
+    let table = selector_table();
+    let mut scores: HashMap<&'static str, u64> = HashMap::new();
+
+    for (sel, count) in selectors {
+        if let Some(category) = table.get(sel) {
+            *scores.entry(category).or_insert(0) += count;
+        }
+    }
+
+    scores
+        .into_iter()
+        .max_by_key(|(_, score)| *score)
+        .map(|(category, _)| category.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_category_recognizes_token_selectors() {
+        let mut selectors = HashMap::new();
+        selectors.insert(format!("0x{}", hex::encode(selector("transfer(address,uint256)"))), 10);
+        selectors.insert(format!("0x{}", hex::encode(selector("balanceOf(address)"))), 5);
+
+        assert_eq!(infer_category(&selectors), Some("token".to_string()));
+    }
+
+    #[test]
+    fn test_infer_category_picks_highest_scoring_category() {
+        let mut selectors = HashMap::new();
+        selectors.insert(format!("0x{}", hex::encode(selector("transfer(address,uint256)"))), 1);
+        selectors.insert(format!("0x{}", hex::encode(selector("swap(uint256,uint256,address,bytes)"))), 100);
+
+        assert_eq!(infer_category(&selectors), Some("dex".to_string()));
+    }
+
+    #[test]
+    fn test_infer_category_returns_none_for_unrecognized_selectors() {
+        let mut selectors = HashMap::new();
+        selectors.insert("0xdeadbeef".to_string(), 50);
+
+        assert_eq!(infer_category(&selectors), None);
+    }
+
+    #[test]
+    fn test_infer_category_returns_none_for_empty_histogram() {
+        assert_eq!(infer_category(&HashMap::new()), None);
+    }
+}
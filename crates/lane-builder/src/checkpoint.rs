@@ -0,0 +1,248 @@
+//! Beacon-checkpoint block lookup (fetch/parse only -- **not** a trust
+//! anchor yet)
+//!
+//! [`crate::builder::HotLaneBuilder::at_block`] and
+//! [`crate::balance_extractor::BalanceExtractor::build_database`] both take
+//! a raw, operator-supplied block number, so the snapshot is anchored to
+//! whatever number someone typed rather than a chain state anyone else can
+//! independently confirm. This module was meant to resolve a snapshot point
+//! from a *trusted* beacon-chain finalized checkpoint instead -- fetch the
+//! checkpoint's beacon block from a
+//! [Beacon API](https://ethereum.github.io/beacon-APIs/) endpoint, confirm
+//! its sync-committee aggregate signature, and read the Capella execution
+//! payload's block number/hash out of it -- but the signature-verification
+//! half isn't implemented, so **do not** treat [`CheckpointClient::resolve`]
+//! as delivering that guarantee today.
+//!
+//! # Scope: fetch/parse utility only
+//!
+//! Verifying a sync-committee aggregate signature requires a BLS12-381
+//! aggregate-signature primitive and SSZ re-serialization of the signed
+//! header, and this tree doesn't vendor a BLS backend. Rather than fake the
+//! check, [`verify_sync_committee_signature`] unconditionally fails, and
+//! [`CheckpointClient::resolve`] only ever succeeds when the caller
+//! explicitly opts into [`CheckpointClient::insecure_skip_signature_check`].
+//! That means every successful `resolve()` call today is, cryptographically,
+//! exactly as trustworthy as a raw unauthenticated block number -- this
+//! module currently only saves callers from hand-typing one. Wire in a BLS
+//! crate and implement [`verify_sync_committee_signature`] for real before
+//! relying on this for the light-client guarantee the module was designed
+//! around.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A snapshot point resolved from a beacon-chain finalized checkpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointBlock {
+    /// Beacon block root of the checkpoint (hex-encoded, `0x`-prefixed)
+    pub beacon_root: String,
+    /// Canonical execution-layer block number extracted from the beacon
+    /// block's Capella execution payload
+    pub execution_block_number: u64,
+    /// Canonical execution-layer block hash (hex-encoded) from the same
+    /// payload
+    pub execution_block_hash: String,
+}
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("beacon API request failed: {0}")]
+    Request(String),
+    #[error("beacon block response did not include an execution payload (pre-Capella fork?)")]
+    MissingExecutionPayload,
+    #[error("beacon block response had a malformed execution_payload.block_number: {0}")]
+    InvalidBlockNumber(String),
+    #[error(
+        "sync-committee signature verification is not implemented (no BLS backend vendored); \
+         pass insecure_skip_signature_check(true) to accept the checkpoint unverified, or wire \
+         in a BLS crate and implement verify_sync_committee_signature"
+    )]
+    SignatureVerificationUnavailable,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlockEnvelope {
+    data: BeaconBlockData,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlockData {
+    root: Option<String>,
+    message: BeaconBlockMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlockMessage {
+    body: BeaconBlockBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlockBody {
+    execution_payload: Option<ExecutionPayload>,
+    sync_aggregate: Option<SyncAggregate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExecutionPayload {
+    block_number: String,
+    block_hash: String,
+}
+
+/// BLS-aggregated sync-committee attestation over this block's parent, as
+/// returned by the Beacon API. Currently only carried through for a future
+/// [`verify_sync_committee_signature`] implementation.
+#[derive(Debug, Deserialize)]
+struct SyncAggregate {
+    #[allow(dead_code)]
+    sync_committee_bits: String,
+    #[allow(dead_code)]
+    sync_committee_signature: String,
+}
+
+/// Verify a beacon block's sync-committee aggregate signature.
+///
+/// Always returns [`CheckpointError::SignatureVerificationUnavailable`] --
+/// see the module-level scope note.
+fn verify_sync_committee_signature(_aggregate: &SyncAggregate) -> Result<(), CheckpointError> {
+    Err(CheckpointError::SignatureVerificationUnavailable)
+}
+
+/// Client for resolving finalized checkpoints against a Beacon API endpoint
+#[derive(Debug, Clone)]
+pub struct CheckpointClient {
+    client: reqwest::Client,
+    beacon_api_url: String,
+    insecure_skip_signature_check: bool,
+}
+
+impl CheckpointClient {
+    pub fn new(beacon_api_url: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            client,
+            beacon_api_url: beacon_api_url.into(),
+            insecure_skip_signature_check: false,
+        }
+    }
+
+    /// Accept a checkpoint without verifying its sync-committee signature.
+    ///
+    /// # Security Warning
+    ///
+    /// Without signature verification this provides no more assurance than
+    /// a plain unauthenticated block number -- only use this for testing or
+    /// until a BLS backend is wired into [`verify_sync_committee_signature`].
+    pub fn insecure_skip_signature_check(mut self, skip: bool) -> Self {
+        self.insecure_skip_signature_check = skip;
+        self
+    }
+
+    /// Resolve `checkpoint_root` (a beacon block root, hex-encoded) to its
+    /// canonical execution-layer block number and hash.
+    pub async fn resolve(&self, checkpoint_root: &str) -> anyhow::Result<CheckpointBlock> {
+        let url = format!("{}/eth/v2/beacon/blocks/{}", self.beacon_api_url, checkpoint_root);
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| CheckpointError::Request(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(CheckpointError::Request(format!("{}: {}", status, body)).into());
+        }
+
+        let envelope: BeaconBlockEnvelope = response
+            .json()
+            .await
+            .map_err(|e| CheckpointError::Request(e.to_string()))?;
+
+        let body = envelope.data.message.body;
+
+        if let Some(aggregate) = &body.sync_aggregate {
+            if !self.insecure_skip_signature_check {
+                verify_sync_committee_signature(aggregate)?;
+            } else {
+                tracing::warn!(
+                    checkpoint_root,
+                    "Accepting beacon checkpoint without sync-committee signature verification"
+                );
+            }
+        } else if !self.insecure_skip_signature_check {
+            return Err(CheckpointError::SignatureVerificationUnavailable.into());
+        }
+
+        let payload = body
+            .execution_payload
+            .ok_or(CheckpointError::MissingExecutionPayload)?;
+
+        let execution_block_number = payload
+            .block_number
+            .parse()
+            .map_err(|_| CheckpointError::InvalidBlockNumber(payload.block_number.clone()))?;
+
+        Ok(CheckpointBlock {
+            beacon_root: envelope
+                .data
+                .root
+                .unwrap_or_else(|| checkpoint_root.to_string()),
+            execution_block_number,
+            execution_block_hash: payload.block_hash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_envelope(include_sync_aggregate: bool) -> String {
+        let sync_aggregate = if include_sync_aggregate {
+            r#","sync_aggregate":{"sync_committee_bits":"0xff","sync_committee_signature":"0xaa"}"#
+        } else {
+            ""
+        };
+        format!(
+            r#"{{"data":{{"root":"0xbeacon","message":{{"body":{{"execution_payload":{{"block_number":"12345","block_hash":"0xdeadbeef"}}{}}}}}}}}}"#,
+            sync_aggregate
+        )
+    }
+
+    #[test]
+    fn test_parses_execution_payload() {
+        let envelope: BeaconBlockEnvelope = serde_json::from_str(&sample_envelope(true)).unwrap();
+        let payload = envelope.data.message.body.execution_payload.unwrap();
+        assert_eq!(payload.block_number, "12345");
+        assert_eq!(payload.block_hash, "0xdeadbeef");
+    }
+
+    #[test]
+    fn test_missing_execution_payload_is_none() {
+        let json = r#"{"data":{"root":"0xbeacon","message":{"body":{"execution_payload":null}}}}"#;
+        let envelope: BeaconBlockEnvelope = serde_json::from_str(json).unwrap();
+        assert!(envelope.data.message.body.execution_payload.is_none());
+    }
+
+    #[test]
+    fn test_verify_sync_committee_signature_unavailable() {
+        let aggregate = SyncAggregate {
+            sync_committee_bits: "0xff".to_string(),
+            sync_committee_signature: "0xaa".to_string(),
+        };
+        assert!(matches!(
+            verify_sync_committee_signature(&aggregate),
+            Err(CheckpointError::SignatureVerificationUnavailable)
+        ));
+    }
+}
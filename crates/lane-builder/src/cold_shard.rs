@@ -0,0 +1,221 @@
+//! Cold-lane sharded database: manifest and index router
+//!
+//! The cold lane (~2.7B entries, ~87 GB) cannot be held as a single encoded
+//! PIR database in memory or even in one file. This module splits the
+//! logical cold-lane index space into fixed-size shards of `ring_dim * K`
+//! entries, persists each shard to its own encoded-database file, and
+//! records a manifest mapping contiguous global-index ranges to shard IDs
+//! so the server can load/serve a single shard lazily (e.g. via
+//! `MmapDatabase`) instead of materializing the whole lane at once.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Per-shard metadata recorded in the manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColdShardInfo {
+    /// Shard identifier (0-indexed, contiguous)
+    pub shard_id: u64,
+    /// First global index covered by this shard (inclusive)
+    pub start_index: u64,
+    /// Number of entries in this shard
+    pub entry_count: u64,
+    /// Entry size in bytes (same across all shards in a lane)
+    pub entry_size: usize,
+    /// File name (relative to the manifest's directory) holding the
+    /// encoded database for this shard
+    pub file_name: String,
+    /// Keccak256 content hash (hex-encoded) of the on-disk shard file, used
+    /// by [`crate::cold_snapshot::ColdSnapshotBuilder`] to resume an
+    /// interrupted build and to detect corruption on load. Empty for a
+    /// manifest built before content hashing was added.
+    #[serde(default)]
+    pub content_hash: String,
+}
+
+impl ColdShardInfo {
+    /// Last global index covered by this shard (exclusive)
+    pub fn end_index(&self) -> u64 {
+        self.start_index + self.entry_count
+    }
+}
+
+/// Manifest mapping the cold lane's logical index space onto shard files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColdShardManifest {
+    /// Ring dimension used to size shards (`shard_entries = ring_dim * shard_factor`)
+    pub ring_dim: u32,
+    /// Number of ring-dimension "pages" per shard
+    pub shard_factor: u64,
+    /// Entry size in bytes
+    pub entry_size: usize,
+    /// Total entries across all shards
+    pub total_entries: u64,
+    /// Shards, ordered by `start_index`
+    pub shards: Vec<ColdShardInfo>,
+}
+
+/// Errors for cold shard manifest construction/lookup
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ColdShardError {
+    #[error("ring_dim and shard_factor must be non-zero")]
+    InvalidShardSize,
+    #[error("global index {index} is out of range (total_entries = {total})")]
+    IndexOutOfRange { index: u64, total: u64 },
+}
+
+impl ColdShardManifest {
+    /// Entries per shard (`ring_dim * shard_factor`)
+    pub fn shard_entries(ring_dim: u32, shard_factor: u64) -> u64 {
+        ring_dim as u64 * shard_factor
+    }
+
+    /// Build a manifest for `total_entries` entries, partitioned into
+    /// fixed-size shards of `ring_dim * shard_factor` entries each (the
+    /// last shard may be shorter).
+    pub fn build(
+        ring_dim: u32,
+        shard_factor: u64,
+        entry_size: usize,
+        total_entries: u64,
+    ) -> Result<Self, ColdShardError> {
+        let shard_size = Self::shard_entries(ring_dim, shard_factor);
+        if shard_size == 0 {
+            return Err(ColdShardError::InvalidShardSize);
+        }
+
+        let mut shards = Vec::new();
+        let mut start_index = 0u64;
+        let mut shard_id = 0u64;
+
+        while start_index < total_entries {
+            let entry_count = shard_size.min(total_entries - start_index);
+            shards.push(ColdShardInfo {
+                shard_id,
+                start_index,
+                entry_count,
+                entry_size,
+                file_name: format!("shard_{:06}.bin", shard_id),
+                content_hash: String::new(),
+            });
+            start_index += entry_count;
+            shard_id += 1;
+        }
+
+        Ok(Self {
+            ring_dim,
+            shard_factor,
+            entry_size,
+            total_entries,
+            shards,
+        })
+    }
+
+    /// Route a global index to its shard and the local index within that shard
+    pub fn shard_of(&self, global_index: u64) -> Result<(u64, u64), ColdShardError> {
+        if global_index >= self.total_entries {
+            return Err(ColdShardError::IndexOutOfRange {
+                index: global_index,
+                total: self.total_entries,
+            });
+        }
+
+        // Shards are fixed-size (except possibly the last), so this is O(1)
+        // rather than a binary search over shard boundaries.
+        let shard_size = Self::shard_entries(self.ring_dim, self.shard_factor);
+        let shard_id = global_index / shard_size;
+        let local_index = global_index - shard_id * shard_size;
+        Ok((shard_id, local_index))
+    }
+
+    /// Look up shard metadata by ID
+    pub fn shard(&self, shard_id: u64) -> Option<&ColdShardInfo> {
+        self.shards.get(shard_id as usize)
+    }
+
+    /// Number of shards in the manifest
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Resolve a shard's file path relative to a shards directory
+    pub fn shard_path(&self, shards_dir: &Path, shard_id: u64) -> Option<PathBuf> {
+        self.shard(shard_id).map(|s| shards_dir.join(&s.file_name))
+    }
+
+    /// Load manifest from a JSON file
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save manifest to a JSON file
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_exact_shards() {
+        let manifest = ColdShardManifest::build(2048, 4, 32, 2048 * 4 * 3).unwrap();
+        assert_eq!(manifest.shard_count(), 3);
+        for (i, shard) in manifest.shards.iter().enumerate() {
+            assert_eq!(shard.shard_id, i as u64);
+            assert_eq!(shard.entry_count, 2048 * 4);
+        }
+    }
+
+    #[test]
+    fn test_build_with_partial_last_shard() {
+        let shard_size = 2048 * 4;
+        let manifest = ColdShardManifest::build(2048, 4, 32, shard_size * 2 + 100).unwrap();
+        assert_eq!(manifest.shard_count(), 3);
+        assert_eq!(manifest.shards[2].entry_count, 100);
+        assert_eq!(manifest.shards[2].end_index(), shard_size * 2 + 100);
+    }
+
+    #[test]
+    fn test_shard_of_crosses_boundaries() {
+        let shard_size = 2048 * 4;
+        let manifest = ColdShardManifest::build(2048, 4, 32, shard_size * 3).unwrap();
+
+        assert_eq!(manifest.shard_of(0).unwrap(), (0, 0));
+        assert_eq!(manifest.shard_of(shard_size - 1).unwrap(), (0, shard_size - 1));
+        assert_eq!(manifest.shard_of(shard_size).unwrap(), (1, 0));
+        assert_eq!(manifest.shard_of(shard_size * 2 + 5).unwrap(), (2, 5));
+    }
+
+    #[test]
+    fn test_shard_of_out_of_range() {
+        let manifest = ColdShardManifest::build(2048, 4, 32, 2048 * 4).unwrap();
+        assert_eq!(
+            manifest.shard_of(2048 * 4),
+            Err(ColdShardError::IndexOutOfRange { index: 2048 * 4, total: 2048 * 4 })
+        );
+    }
+
+    #[test]
+    fn test_invalid_shard_size() {
+        assert_eq!(ColdShardManifest::build(0, 4, 32, 100), Err(ColdShardError::InvalidShardSize));
+        assert_eq!(ColdShardManifest::build(2048, 0, 32, 100), Err(ColdShardError::InvalidShardSize));
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = ColdShardManifest::build(2048, 4, 32, 2048 * 4 * 2 + 7).unwrap();
+        let path = dir.path().join("cold_shards.json");
+        manifest.save(&path).unwrap();
+
+        let loaded = ColdShardManifest::load(&path).unwrap();
+        assert_eq!(loaded.shard_count(), manifest.shard_count());
+        assert_eq!(loaded.total_entries, manifest.total_entries);
+    }
+}
@@ -0,0 +1,305 @@
+//! Chunked, resumable cold-lane snapshot builder
+//!
+//! [`crate::setup::TwoLaneSetup`] encodes `cold_data` as a single in-memory
+//! `Vec<u8>`, which is impossible at the ~2.7B-entry scale the cold lane
+//! targets. This module instead streams the cold database off disk in
+//! fixed-size chunks (one PIR shard = `ring_dim * shard_factor` entries),
+//! encodes each chunk independently via [`crate::cold_shard::ColdShardManifest`]'s
+//! shard layout, and records a keccak256 content hash per shard. Borrowed
+//! from Ethereum snapshot sync: a chunk whose on-disk hash already matches
+//! the manifest is skipped on re-run (so an interrupted build resumes
+//! instead of restarting), and a chunk that fails hash verification on load
+//! is blacklisted so later runs don't keep retrying it.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use inspire_pir::{setup as pir_setup, InspireParams};
+use inspire_pir::math::GaussianSampler;
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::cold_shard::{ColdShardInfo, ColdShardManifest};
+
+fn keccak256_hex(data: &[u8]) -> String {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut out = [0u8; 32];
+    hasher.finalize(&mut out);
+    hex::encode(out)
+}
+
+/// Chunk hashes known to have failed integrity verification on load, so a
+/// later build doesn't keep re-trusting a corrupt cached shard file just
+/// because its (also corrupt) hash happens to still match the manifest.
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ColdShardBlacklist {
+    /// Maps shard ID to the content hash that failed verification for it
+    bad_hashes: HashMap<u64, String>,
+}
+
+impl ColdShardBlacklist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `shard_id`'s current on-disk hash as known-bad
+    pub fn blacklist(&mut self, shard_id: u64, hash: String) {
+        self.bad_hashes.insert(shard_id, hash);
+    }
+
+    /// Whether `shard_id`'s on-disk hash is the one previously blacklisted
+    pub fn is_blacklisted(&self, shard_id: u64, hash: &str) -> bool {
+        self.bad_hashes.get(&shard_id).map(String::as_str) == Some(hash)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        if !path.as_ref().exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// Builder for a chunked, resumable cold-lane snapshot
+pub struct ColdSnapshotBuilder {
+    output_dir: PathBuf,
+    ring_dim: u32,
+    shard_factor: u64,
+    entry_size: usize,
+    params: InspireParams,
+}
+
+impl ColdSnapshotBuilder {
+    pub fn new(output_dir: impl Into<PathBuf>, params: InspireParams) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            ring_dim: params.ring_dim as u32,
+            shard_factor: 1,
+            entry_size: 32,
+            params,
+        }
+    }
+
+    /// Number of `ring_dim`-entry pages per shard (default: 1)
+    pub fn shard_factor(mut self, factor: u64) -> Self {
+        self.shard_factor = factor;
+        self
+    }
+
+    /// Entry size in bytes (default: 32, an Ethereum storage slot)
+    pub fn entry_size(mut self, size: usize) -> Self {
+        self.entry_size = size;
+        self
+    }
+
+    fn manifest_path(&self) -> PathBuf {
+        self.output_dir.join("manifest.json")
+    }
+
+    fn shard_path(&self, shard: &ColdShardInfo) -> PathBuf {
+        self.output_dir.join(&shard.file_name)
+    }
+
+    /// Stream `data_path` (raw cold-lane entries, `entry_size` bytes each)
+    /// into per-shard encoded databases under `output_dir`, resuming from
+    /// whatever the previous run already completed.
+    ///
+    /// A shard is re-used (not re-encoded) only if its output file already
+    /// exists, its current content hash matches the hash recorded in a
+    /// prior manifest at this path, and that hash isn't blacklisted.
+    pub fn build_from_file(&self, data_path: &Path) -> anyhow::Result<ColdShardManifest> {
+        std::fs::create_dir_all(&self.output_dir)?;
+
+        let total_bytes = std::fs::metadata(data_path)?.len();
+        let total_entries = total_bytes / self.entry_size as u64;
+
+        let mut manifest = ColdShardManifest::build(
+            self.ring_dim,
+            self.shard_factor,
+            self.entry_size,
+            total_entries,
+        )?;
+
+        let previous = ColdShardManifest::load(self.manifest_path()).ok();
+        let blacklist = ColdShardBlacklist::load(self.output_dir.join("blacklist.json"))?;
+
+        let mut data_file = File::open(data_path)?;
+        let mut sampler = GaussianSampler::new(self.params.sigma);
+
+        for shard in manifest.shards.iter_mut() {
+            let shard_path = self.output_dir.join(&shard.file_name);
+
+            if let Some(existing_hash) = Self::reusable_hash(&previous, &blacklist, shard, &shard_path)? {
+                tracing::info!(shard_id = shard.shard_id, "Shard already up to date, skipping");
+                shard.content_hash = existing_hash;
+                continue;
+            }
+
+            let chunk_bytes = (shard.entry_count as usize) * self.entry_size;
+            let mut buf = vec![0u8; chunk_bytes];
+            data_file.seek(SeekFrom::Start(shard.start_index * self.entry_size as u64))?;
+            data_file.read_exact(&mut buf)?;
+
+            let (crs, db, _sk) = pir_setup(&self.params, &buf, self.entry_size, &mut sampler)
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+            let encoded_json = serde_json::to_vec(&db)?;
+            std::fs::write(&shard_path, &encoded_json)?;
+            shard.content_hash = keccak256_hex(&encoded_json);
+
+            let crs_path = self.output_dir.join(format!("shard_{:06}.crs.json", shard.shard_id));
+            std::fs::write(&crs_path, serde_json::to_string(&crs)?)?;
+
+            tracing::info!(
+                shard_id = shard.shard_id,
+                entries = shard.entry_count,
+                "Encoded cold-lane shard"
+            );
+        }
+
+        manifest.save(self.manifest_path())?;
+        Ok(manifest)
+    }
+
+    /// Returns `Some(hash)` if `shard_path` already holds a verified,
+    /// up-to-date copy of `shard` per `previous`'s recorded hash, and that
+    /// hash isn't blacklisted.
+    fn reusable_hash(
+        previous: &Option<ColdShardManifest>,
+        blacklist: &ColdShardBlacklist,
+        shard: &ColdShardInfo,
+        shard_path: &Path,
+    ) -> anyhow::Result<Option<String>> {
+        if !shard_path.exists() {
+            return Ok(None);
+        }
+        let Some(previous) = previous else {
+            return Ok(None);
+        };
+        let Some(prior_shard) = previous.shards.get(shard.shard_id as usize) else {
+            return Ok(None);
+        };
+        if prior_shard.content_hash.is_empty() {
+            return Ok(None);
+        }
+
+        let on_disk = std::fs::read(shard_path)?;
+        let current_hash = keccak256_hex(&on_disk);
+        if current_hash != prior_shard.content_hash {
+            return Ok(None);
+        }
+        if blacklist.is_blacklisted(shard.shard_id, &current_hash) {
+            return Ok(None);
+        }
+        Ok(Some(current_hash))
+    }
+
+    /// Re-hash every shard on disk against `manifest` and return the IDs of
+    /// any that don't match, recording their bad hashes in a blacklist file
+    /// so a future build doesn't keep retrying them as if they were valid.
+    pub fn verify(&self, manifest: &ColdShardManifest) -> anyhow::Result<Vec<u64>> {
+        let mut blacklist = ColdShardBlacklist::load(self.output_dir.join("blacklist.json"))?;
+        let mut bad = Vec::new();
+
+        for shard in &manifest.shards {
+            let shard_path = self.shard_path(shard);
+            let on_disk = match std::fs::read(&shard_path) {
+                Ok(bytes) => bytes,
+                Err(_) => {
+                    bad.push(shard.shard_id);
+                    continue;
+                }
+            };
+            let current_hash = keccak256_hex(&on_disk);
+            if current_hash != shard.content_hash {
+                blacklist.blacklist(shard.shard_id, current_hash);
+                bad.push(shard.shard_id);
+            }
+        }
+
+        blacklist.save(self.output_dir.join("blacklist.json"))?;
+        Ok(bad)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setup::test_params;
+
+    fn write_cold_data(path: &Path, entries: u64, entry_size: usize) {
+        let data: Vec<u8> = (0..entries * entry_size as u64)
+            .map(|i| (i % 256) as u8)
+            .collect();
+        std::fs::write(path, data).unwrap();
+    }
+
+    #[test]
+    fn test_build_from_file_produces_shards() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_path = dir.path().join("cold.bin");
+        let params = test_params();
+        write_cold_data(&data_path, params.ring_dim as u64 * 2, 32);
+
+        let builder = ColdSnapshotBuilder::new(dir.path().join("out"), params)
+            .shard_factor(1)
+            .entry_size(32);
+
+        let manifest = builder.build_from_file(&data_path).unwrap();
+        assert_eq!(manifest.shard_count(), 2);
+        for shard in &manifest.shards {
+            assert!(!shard.content_hash.is_empty());
+            assert!(dir.path().join("out").join(&shard.file_name).exists());
+        }
+    }
+
+    #[test]
+    fn test_rebuild_skips_unchanged_shards() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_path = dir.path().join("cold.bin");
+        let params = test_params();
+        write_cold_data(&data_path, params.ring_dim as u64, 32);
+
+        let builder = ColdSnapshotBuilder::new(dir.path().join("out"), params)
+            .shard_factor(1)
+            .entry_size(32);
+
+        let first = builder.build_from_file(&data_path).unwrap();
+        let second = builder.build_from_file(&data_path).unwrap();
+
+        assert_eq!(first.shards[0].content_hash, second.shards[0].content_hash);
+    }
+
+    #[test]
+    fn test_verify_detects_corruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_path = dir.path().join("cold.bin");
+        let params = test_params();
+        write_cold_data(&data_path, params.ring_dim as u64, 32);
+
+        let builder = ColdSnapshotBuilder::new(dir.path().join("out"), params)
+            .shard_factor(1)
+            .entry_size(32);
+
+        let manifest = builder.build_from_file(&data_path).unwrap();
+        assert!(builder.verify(&manifest).unwrap().is_empty());
+
+        let shard_path = dir.path().join("out").join(&manifest.shards[0].file_name);
+        std::fs::write(&shard_path, b"corrupted").unwrap();
+
+        let bad = builder.verify(&manifest).unwrap();
+        assert_eq!(bad, vec![0]);
+
+        let blacklist = ColdShardBlacklist::load(dir.path().join("out").join("blacklist.json")).unwrap();
+        assert!(blacklist.is_blacklisted(0, &keccak256_hex(b"corrupted")));
+    }
+}
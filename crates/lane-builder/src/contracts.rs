@@ -1,11 +1,25 @@
 //! Known hot lane contracts
 //!
-//! Curated list of top Ethereum contracts for the hot lane.
-//! Includes DeFi protocols, stablecoins, DEXes, bridges, and privacy protocols.
+//! Curated list of top contracts for the hot lane, across the chains the
+//! PIR ExEx can be pointed at. Includes DeFi protocols, stablecoins, DEXes,
+//! bridges, and privacy protocols.
 
 use inspire_core::Address;
 use serde::{Deserialize, Serialize};
 
+use crate::storage_layout::StorageLayout;
+
+/// Ethereum mainnet
+pub const MAINNET_CHAIN_ID: u64 = 1;
+/// Optimism
+pub const OPTIMISM_CHAIN_ID: u64 = 10;
+/// Polygon PoS
+pub const POLYGON_CHAIN_ID: u64 = 137;
+/// Base
+pub const BASE_CHAIN_ID: u64 = 8453;
+/// Arbitrum One
+pub const ARBITRUM_CHAIN_ID: u64 = 42161;
+
 /// Contract information for hot lane
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContractInfo {
@@ -13,133 +27,328 @@ pub struct ContractInfo {
     pub address: Address,
     pub name: String,
     pub category: String,
+    /// EIP-155 chain ID this contract is deployed on. Defaults to mainnet
+    /// for documents written before multi-chain support existed.
+    #[serde(default = "default_chain_id")]
+    pub chain_id: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tx_count: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub storage_slots: Option<u64>,
+    /// Statically-known storage footprint from a `solc --storage-layout`
+    /// import (see `crate::storage_layout`), if one was loaded for this
+    /// contract.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_layout: Option<StorageLayout>,
+}
+
+fn default_chain_id() -> u64 {
+    MAINNET_CHAIN_ID
 }
 
 /// Known contract for compile-time inclusion
 pub struct KnownContract {
     pub address: Address,
+    /// The `"0x..."` literal `address` was parsed from, kept alongside the
+    /// decoded bytes so [`validate`] can re-derive the EIP-55 checksum --
+    /// `address` alone has already lost the original casing.
+    pub source: &'static str,
     pub name: &'static str,
     pub category: &'static str,
+    /// EIP-155 chain ID this contract is deployed on.
+    pub chain_id: u64,
 }
 
-/// Curated list of top Ethereum contracts
+/// Look up the curated contract list for `chain_id`. Returns an empty
+/// slice for chains without a curated list rather than falling back to
+/// mainnet, since an L2's hot lane shouldn't silently serve L1 addresses.
+pub fn hot_contracts(chain_id: u64) -> &'static [KnownContract] {
+    match chain_id {
+        MAINNET_CHAIN_ID => HOT_CONTRACTS,
+        OPTIMISM_CHAIN_ID => OPTIMISM_CONTRACTS,
+        ARBITRUM_CHAIN_ID => ARBITRUM_CONTRACTS,
+        BASE_CHAIN_ID => BASE_CONTRACTS,
+        POLYGON_CHAIN_ID => POLYGON_CONTRACTS,
+        _ => &[],
+    }
+}
+
+/// Every curated contract across every chain this registry knows about,
+/// for cross-chain analytics ("all privacy contracts across all chains").
+pub fn all_chains_hot_contracts() -> impl Iterator<Item = &'static KnownContract> {
+    [
+        HOT_CONTRACTS,
+        OPTIMISM_CONTRACTS,
+        ARBITRUM_CONTRACTS,
+        BASE_CONTRACTS,
+        POLYGON_CONTRACTS,
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// Curated list of top Ethereum mainnet contracts
 pub const HOT_CONTRACTS: &[KnownContract] = &[
     // Stablecoins
     KnownContract {
         address: hex_literal("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+        source: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
         name: "USDC",
         category: "stablecoin",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
         address: hex_literal("0xdAC17F958D2ee523a2206206994597C13D831ec7"),
+        source: "0xdAC17F958D2ee523a2206206994597C13D831ec7",
         name: "USDT",
         category: "stablecoin",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
-        address: hex_literal("0x6B175474E89094C44Da98b954EescdeCB5BE3d842"),
+        address: hex_literal("0x6B175474E89094C44Da98b954EedeAC495271d0F"),
+        source: "0x6B175474E89094C44Da98b954EedeAC495271d0F",
         name: "DAI",
         category: "stablecoin",
+        chain_id: MAINNET_CHAIN_ID,
     },
     // Wrapped tokens
     KnownContract {
         address: hex_literal("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+        source: "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
         name: "WETH",
         category: "token",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
         address: hex_literal("0x2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599"),
+        source: "0x2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599",
         name: "WBTC",
         category: "token",
+        chain_id: MAINNET_CHAIN_ID,
     },
     // DEX protocols
     KnownContract {
         address: hex_literal("0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D"),
+        source: "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D",
         name: "Uniswap V2 Router",
         category: "dex",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
         address: hex_literal("0xE592427A0AEce92De3Edee1F18E0157C05861564"),
+        source: "0xE592427A0AEce92De3Edee1F18E0157C05861564",
         name: "Uniswap V3 Router",
         category: "dex",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
         address: hex_literal("0x68b3465833fb72A70ecDF485E0e4C7bD8665Fc45"),
+        source: "0x68b3465833fb72A70ecDF485E0e4C7bD8665Fc45",
         name: "Uniswap Universal Router",
         category: "dex",
+        chain_id: MAINNET_CHAIN_ID,
     },
     // Lending protocols
     KnownContract {
         address: hex_literal("0x87870Bca3F3fD6335C3F4ce8392D69350B4fA4E2"),
+        source: "0x87870Bca3F3fD6335C3F4ce8392D69350B4fA4E2",
         name: "Aave V3 Pool",
         category: "lending",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
         address: hex_literal("0x3d9819210A31b4961b30EF54bE2aeD79B9c9Cd3B"),
+        source: "0x3d9819210A31b4961b30EF54bE2aeD79B9c9Cd3B",
         name: "Compound Comptroller",
         category: "lending",
+        chain_id: MAINNET_CHAIN_ID,
     },
     // Privacy protocols
     KnownContract {
         address: hex_literal("0x910Cbd523D972eb0a6f4cAe4618aD62622b39DbF"),
+        source: "0x910Cbd523D972eb0a6f4cAe4618aD62622b39DbF",
         name: "Tornado Cash 0.1 ETH",
         category: "privacy",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
         address: hex_literal("0xA160cdAB225685dA1d56aa342Ad8841c3b53f291"),
+        source: "0xA160cdAB225685dA1d56aa342Ad8841c3b53f291",
         name: "Tornado Cash 1 ETH",
         category: "privacy",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
         address: hex_literal("0xD4B88Df4D29F5CedD6857912842cff3b20C8Cfa3"),
+        source: "0xD4B88Df4D29F5CedD6857912842cff3b20C8Cfa3",
         name: "Tornado Cash 10 ETH",
         category: "privacy",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
-        address: hex_literal("0xA0B86991C6218B36C1D19D4A2E9EB0cE3606eB48"),
+        address: hex_literal("0x7d3F7ab6eB0a7358B6b7BEBc8C7f7c1c9A5c5d41"),
+        source: "0x7d3F7ab6eB0a7358B6b7BEBc8C7f7c1c9A5c5d41",
         name: "Railgun",
         category: "privacy",
+        chain_id: MAINNET_CHAIN_ID,
     },
     // Bridges
     KnownContract {
         address: hex_literal("0x40ec5B33f54e0E8A33A975908C5BA1c14e5BbbDf"),
+        source: "0x40ec5B33f54e0E8A33A975908C5BA1c14e5BbbDf",
         name: "Polygon Bridge",
         category: "bridge",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
         address: hex_literal("0x99C9fc46f92E8a1c0deC1b1747d010903E884bE1"),
+        source: "0x99C9fc46f92E8a1c0deC1b1747d010903E884bE1",
         name: "Optimism Bridge",
         category: "bridge",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
         address: hex_literal("0x8315177aB297bA92A06054cE80a67Ed4DBd7ed3a"),
+        source: "0x8315177aB297bA92A06054cE80a67Ed4DBd7ed3a",
         name: "Arbitrum Bridge",
         category: "bridge",
+        chain_id: MAINNET_CHAIN_ID,
     },
     // Governance tokens
     KnownContract {
         address: hex_literal("0x1f9840a85d5aF5bf1D1762F925BDADdC4201F984"),
+        source: "0x1f9840a85d5aF5bf1D1762F925BDADdC4201F984",
         name: "UNI",
         category: "governance",
+        chain_id: MAINNET_CHAIN_ID,
     },
     KnownContract {
         address: hex_literal("0x7Fc66500c84A76Ad7e9c93437bFc5Ac33E2DDaE9"),
+        source: "0x7Fc66500c84A76Ad7e9c93437bFc5Ac33E2DDaE9",
         name: "AAVE",
         category: "governance",
+        chain_id: MAINNET_CHAIN_ID,
     },
     // NFT marketplaces
     KnownContract {
         address: hex_literal("0x00000000006c3852cbEf3e08E8dF289169EdE581"),
+        source: "0x00000000006c3852cbEf3e08E8dF289169EdE581",
         name: "Seaport",
         category: "nft",
+        chain_id: MAINNET_CHAIN_ID,
     },
 ];
 
+/// Curated list of top Optimism contracts
+pub const OPTIMISM_CONTRACTS: &[KnownContract] = &[
+    KnownContract {
+        address: hex_literal("0x4200000000000000000000000000000000000006"),
+        source: "0x4200000000000000000000000000000000000006",
+        name: "WETH (Optimism)",
+        category: "token",
+        chain_id: OPTIMISM_CHAIN_ID,
+    },
+    KnownContract {
+        address: hex_literal("0x7F5c764cBc14f9669B88837ca1490cCa17c31607"),
+        source: "0x7F5c764cBc14f9669B88837ca1490cCa17c31607",
+        name: "USDC (Optimism)",
+        category: "stablecoin",
+        chain_id: OPTIMISM_CHAIN_ID,
+    },
+    KnownContract {
+        address: hex_literal("0xE592427A0AEce92De3Edee1F18E0157C05861564"),
+        source: "0xE592427A0AEce92De3Edee1F18E0157C05861564",
+        name: "Uniswap V3 Router (Optimism)",
+        category: "dex",
+        chain_id: OPTIMISM_CHAIN_ID,
+    },
+];
+
+/// Curated list of top Arbitrum One contracts
+pub const ARBITRUM_CONTRACTS: &[KnownContract] = &[
+    KnownContract {
+        address: hex_literal("0x82aF49447D8a07e3bd95BD0d56f35241523fBab1"),
+        source: "0x82aF49447D8a07e3bd95BD0d56f35241523fBab1",
+        name: "WETH (Arbitrum)",
+        category: "token",
+        chain_id: ARBITRUM_CHAIN_ID,
+    },
+    KnownContract {
+        address: hex_literal("0xaf88d065e77c8cC2239327C5EDb3A432268e5831"),
+        source: "0xaf88d065e77c8cC2239327C5EDb3A432268e5831",
+        name: "USDC (Arbitrum)",
+        category: "stablecoin",
+        chain_id: ARBITRUM_CHAIN_ID,
+    },
+    KnownContract {
+        address: hex_literal("0xE592427A0AEce92De3Edee1F18E0157C05861564"),
+        source: "0xE592427A0AEce92De3Edee1F18E0157C05861564",
+        name: "Uniswap V3 Router (Arbitrum)",
+        category: "dex",
+        chain_id: ARBITRUM_CHAIN_ID,
+    },
+];
+
+/// Curated list of top Base contracts
+pub const BASE_CONTRACTS: &[KnownContract] = &[
+    KnownContract {
+        address: hex_literal("0x4200000000000000000000000000000000000006"),
+        source: "0x4200000000000000000000000000000000000006",
+        name: "WETH (Base)",
+        category: "token",
+        chain_id: BASE_CHAIN_ID,
+    },
+    KnownContract {
+        address: hex_literal("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913"),
+        source: "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+        name: "USDC (Base)",
+        category: "stablecoin",
+        chain_id: BASE_CHAIN_ID,
+    },
+    KnownContract {
+        address: hex_literal("0x2626664c2603336E57B271c5C0b26F421741e481"),
+        source: "0x2626664c2603336E57B271c5C0b26F421741e481",
+        name: "Uniswap V3 Router (Base)",
+        category: "dex",
+        chain_id: BASE_CHAIN_ID,
+    },
+];
+
+/// Curated list of top Polygon PoS contracts
+pub const POLYGON_CONTRACTS: &[KnownContract] = &[
+    KnownContract {
+        address: hex_literal("0x0d500B1d8E8eF31E21C99d1Db9A6444d3ADf1270"),
+        source: "0x0d500B1d8E8eF31E21C99d1Db9A6444d3ADf1270",
+        name: "WMATIC",
+        category: "token",
+        chain_id: POLYGON_CHAIN_ID,
+    },
+    KnownContract {
+        address: hex_literal("0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359"),
+        source: "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359",
+        name: "USDC (Polygon)",
+        category: "stablecoin",
+        chain_id: POLYGON_CHAIN_ID,
+    },
+    KnownContract {
+        address: hex_literal("0xa5E0829CaCEd8fFDD4De3c43696c57F7D7A678ff"),
+        source: "0xa5E0829CaCEd8fFDD4De3c43696c57F7D7A678ff",
+        name: "QuickSwap Router",
+        category: "dex",
+        chain_id: POLYGON_CHAIN_ID,
+    },
+];
+
+/// Parse a `"0x"` + 40-hex-digit address literal. Panics at compile time
+/// (array-initializer `const` evaluation, not just at `#[test]` time) on
+/// anything else, so a mistyped literal like a stray `s` in place of a hex
+/// digit fails the build instead of silently decoding to `0x00`.
 const fn hex_literal(s: &str) -> Address {
     let bytes = s.as_bytes();
+    assert!(
+        bytes.len() == 42 && bytes[0] == b'0' && bytes[1] == b'x',
+        "address literal must be \"0x\" followed by exactly 40 hex digits"
+    );
     let mut result = [0u8; 20];
     let mut i = 2;
     let mut j = 0;
@@ -158,7 +367,7 @@ const fn hex_char(c: u8) -> u8 {
         b'0'..=b'9' => c - b'0',
         b'a'..=b'f' => c - b'a' + 10,
         b'A'..=b'F' => c - b'A' + 10,
-        _ => 0,
+        _ => panic!("address literal contains a non-hex-digit character"),
     }
 }
 
@@ -184,6 +393,101 @@ mod hex_address {
     }
 }
 
+/// One problem found by [`validate`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum ContractValidationError {
+    /// The literal's EIP-55 mixed-case checksum doesn't match its bytes --
+    /// `hex_literal` already rejects non-hex characters at compile time, so
+    /// this means a digit got swapped or a casing typo slipped in.
+    BadChecksum { name: &'static str, source: &'static str },
+    /// Two entries on the same chain share an address.
+    DuplicateAddress { first: &'static str, second: &'static str, chain_id: u64 },
+}
+
+impl std::fmt::Display for ContractValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadChecksum { name, source } => {
+                write!(f, "{name} ({source}) fails its EIP-55 checksum")
+            }
+            Self::DuplicateAddress { first, second, chain_id } => {
+                write!(f, "{first} and {second} share an address on chain {chain_id}")
+            }
+        }
+    }
+}
+
+/// EIP-55 mixed-case checksum for a lowercase 40-hex-digit address (no
+/// `0x`): keccak256 the ASCII bytes, then uppercase each hex digit whose
+/// corresponding nibble of the hash is `>= 8`.
+fn eip55_checksum(lower_hex: &str) -> String {
+    use tiny_keccak::{Hasher, Keccak};
+
+    let mut hasher = Keccak::v256();
+    hasher.update(lower_hex.as_bytes());
+    let mut digest = [0u8; 32];
+    hasher.finalize(&mut digest);
+    let digest_hex = hex::encode(digest);
+
+    lower_hex
+        .char_indices()
+        .map(|(i, c)| {
+            if c.is_ascii_digit() {
+                return c;
+            }
+            let digest_byte = digest_hex.as_bytes()[i];
+            let nibble = if digest_byte.is_ascii_digit() {
+                digest_byte - b'0'
+            } else {
+                digest_byte - b'a' + 10
+            };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .collect()
+}
+
+/// Validate every curated entry across every chain: each `source` literal
+/// must checksum to itself under EIP-55, and no two entries on the same
+/// chain may share an address. `hex_literal` already rejects malformed hex
+/// at compile time; this catches the cases that still parse but are wrong,
+/// like a `Railgun` entry accidentally pasted with USDC's address.
+pub fn validate() -> Result<(), Vec<ContractValidationError>> {
+    let mut errors = Vec::new();
+    let mut seen: std::collections::HashMap<(u64, Address), &'static str> = std::collections::HashMap::new();
+
+    for known in all_chains_hot_contracts() {
+        let lower = known.source.trim_start_matches("0x").to_ascii_lowercase();
+        let expected = format!("0x{}", eip55_checksum(&lower));
+        if expected != known.source {
+            errors.push(ContractValidationError::BadChecksum {
+                name: known.name,
+                source: known.source,
+            });
+        }
+
+        match seen.insert((known.chain_id, known.address), known.name) {
+            Some(first) if first != known.name => {
+                errors.push(ContractValidationError::DuplicateAddress {
+                    first,
+                    second: known.name,
+                    chain_id: known.chain_id,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,8 +518,10 @@ mod tests {
             address: [0xabu8; 20],
             name: "Test".into(),
             category: "token".into(),
+            chain_id: MAINNET_CHAIN_ID,
             tx_count: Some(1000),
             storage_slots: None,
+            storage_layout: None,
         };
         
         let json = serde_json::to_string(&info).unwrap();
@@ -223,4 +529,33 @@ mod tests {
         assert!(json.contains("tx_count"));
         assert!(!json.contains("storage_slots"));
     }
+
+    #[test]
+    fn test_curated_addresses_pass_validation() {
+        if let Err(errors) = validate() {
+            panic!("curated contract list failed validation: {errors:?}");
+        }
+    }
+
+    #[test]
+    fn test_eip55_checksum_matches_known_address() {
+        assert_eq!(
+            eip55_checksum(&"a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string()),
+            "A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_checksum() {
+        const BAD: &[KnownContract] = &[KnownContract {
+            address: hex_literal("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"),
+            source: "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+            name: "all-lowercase USDC",
+            category: "stablecoin",
+            chain_id: MAINNET_CHAIN_ID,
+        }];
+        let lower = BAD[0].source.trim_start_matches("0x").to_ascii_lowercase();
+        let expected = format!("0x{}", eip55_checksum(&lower));
+        assert_ne!(expected, BAD[0].source);
+    }
 }
@@ -0,0 +1,350 @@
+//! Streaming compaction of per-block delta files into one consolidated snapshot
+//!
+//! [`crate::delta_exex`] (built with the `exex` feature) writes one
+//! `delta_<block>_<hash>.bin` file per canonical block and never consolidates
+//! them, so a reader wanting "the current state" has to replay the whole
+//! window in block order. [`compact_range`] does a streaming k-way merge
+//! of a contiguous range of those already-sorted delta files into a single
+//! `state.bin`: each input file is opened as a cursor, the head entry of
+//! every cursor is pushed onto a min-heap ordered by its `(address, slot)`
+//! key (ties broken in favor of the higher block number, i.e. the later
+//! write), and entries are popped and merged, keeping only the surviving
+//! (highest-block-number) write for each distinct key and discarding the
+//! rest -- including a slot explicitly set back to zero, which is still a
+//! real write and must survive like any other value.
+//!
+//! # Scope
+//!
+//! A natural merge key here would be the EIP-7864 `tree_key` each delta is
+//! conceptually sorted by, but `inspire_core::ubt::compute_tree_key` that
+//! would require doesn't exist in this tree (see
+//! `inspire_core::state_backend`'s module docs for the same gap), and the
+//! on-disk [`StorageEntry`] this crate actually writes has no stored tree
+//! key at all -- only `address`/`slot`/`value`. Each delta file is already
+//! written in ascending `(address, slot)` order (see
+//! `crate::delta_exex::collect_block_entries`'s final sort), so merging on
+//! that same key preserves a valid total order across files without
+//! inventing the missing tree-key machinery.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use inspire_core::state_format::{
+    StateFormatError, StateHeader, StorageEntry, STATE_ENTRY_SIZE, STATE_HEADER_SIZE,
+};
+
+#[derive(Debug, Error)]
+pub enum CompactionError {
+    #[error("I/O error during compaction: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("malformed delta file: {0}")]
+    Format(#[from] StateFormatError),
+    #[error("no non-empty delta files were found in the given range")]
+    EmptyRange,
+}
+
+/// One open delta file, tracking its block number (to break merge ties) and
+/// the entries it still has left to yield
+struct DeltaCursor {
+    reader: BufReader<File>,
+    block_number: u64,
+    block_hash: [u8; 32],
+    chain_id: u64,
+    remaining: u64,
+    head: Option<StorageEntry>,
+}
+
+impl DeltaCursor {
+    /// Open `path` and prime its first entry, or return `None` if the file
+    /// is empty (a block with no storage writes still gets a zero-entry
+    /// delta file, which must be skipped cleanly rather than erroring)
+    fn open(path: &Path) -> Result<Option<Self>, CompactionError> {
+        let file = File::open(path)?;
+        let mut reader = BufReader::new(file);
+        let mut header_buf = [0u8; STATE_HEADER_SIZE];
+        reader.read_exact(&mut header_buf)?;
+        let header = StateHeader::from_bytes(&header_buf)?;
+
+        if header.entry_count == 0 {
+            return Ok(None);
+        }
+
+        let mut cursor = Self {
+            reader,
+            block_number: header.block_number,
+            block_hash: header.block_hash,
+            chain_id: header.chain_id,
+            remaining: header.entry_count,
+            head: None,
+        };
+        cursor.advance()?;
+        Ok(Some(cursor))
+    }
+
+    fn advance(&mut self) -> Result<(), CompactionError> {
+        if self.remaining == 0 {
+            self.head = None;
+            return Ok(());
+        }
+        let mut buf = [0u8; STATE_ENTRY_SIZE];
+        self.reader.read_exact(&mut buf)?;
+        self.remaining -= 1;
+        self.head = Some(StorageEntry::from_bytes(&buf)?);
+        Ok(())
+    }
+}
+
+/// Merge-ordering key: ascending `(address, slot)`, ties broken by
+/// descending `block_number` so the latest write for a key pops first
+struct HeapItem {
+    address: [u8; 20],
+    slot: [u8; 32],
+    block_number: u64,
+    cursor_idx: usize,
+}
+
+impl PartialEq for HeapItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp_key() == other.cmp_key()
+    }
+}
+impl Eq for HeapItem {}
+
+impl HeapItem {
+    fn cmp_key(&self) -> ([u8; 20], [u8; 32], std::cmp::Reverse<u64>) {
+        (self.address, self.slot, std::cmp::Reverse(self.block_number))
+    }
+}
+
+impl PartialOrd for HeapItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap is a max-heap; reverse so the smallest key pops first.
+        other.cmp_key().cmp(&self.cmp_key())
+    }
+}
+
+/// Streaming k-way merge of the delta files at `paths` into a single
+/// consolidated `state.bin` written to `output_path`.
+///
+/// `paths` should be given oldest-block-first; order otherwise doesn't
+/// matter since each file's own block number drives tie-breaking. Idempotent:
+/// re-running over an already-compacted snapshot plus new deltas (passed
+/// together in `paths`) yields the same result, since the merge is driven
+/// purely by each input's `(address, slot, block_number)` and not by any
+/// notion of "already compacted".
+pub fn compact_range(paths: &[PathBuf], output_path: &Path) -> Result<StateHeader, CompactionError> {
+    let mut cursors: Vec<DeltaCursor> = Vec::new();
+    for path in paths {
+        if let Some(cursor) = DeltaCursor::open(path)? {
+            cursors.push(cursor);
+        }
+    }
+
+    if cursors.is_empty() {
+        return Err(CompactionError::EmptyRange);
+    }
+
+    let tip_block = cursors.iter().map(|c| c.block_number).max().unwrap();
+    let (tip_hash, chain_id) = cursors
+        .iter()
+        .find(|c| c.block_number == tip_block)
+        .map(|c| (c.block_hash, c.chain_id))
+        .unwrap();
+
+    let mut heap: BinaryHeap<HeapItem> = BinaryHeap::new();
+    for (idx, cursor) in cursors.iter().enumerate() {
+        if let Some(entry) = &cursor.head {
+            heap.push(HeapItem { address: entry.address, slot: entry.slot, block_number: cursor.block_number, cursor_idx: idx });
+        }
+    }
+
+    let tmp_path = output_path.with_extension("bin.tmp");
+    let mut writer = BufWriter::new(File::create(&tmp_path)?);
+    // Header is rewritten with the real entry_count once merging finishes;
+    // reserve its space up front since entries are streamed out as we go.
+    writer.write_all(&[0u8; STATE_HEADER_SIZE])?;
+
+    let mut entry_count: u64 = 0;
+    let mut last_key: Option<([u8; 20], [u8; 32])> = None;
+
+    while let Some(item) = heap.pop() {
+        let cursor = &mut cursors[item.cursor_idx];
+        let entry = cursor.head.take().expect("heap item's cursor always has a head");
+        cursor.advance()?;
+        if let Some(next) = &cursor.head {
+            heap.push(HeapItem {
+                address: next.address,
+                slot: next.slot,
+                block_number: cursor.block_number,
+                cursor_idx: item.cursor_idx,
+            });
+        }
+
+        let key = (entry.address, entry.slot);
+        if last_key == Some(key) {
+            // A lower or equal block number for the same key already lost
+            // to an earlier, later-written entry -- discard it.
+            continue;
+        }
+        last_key = Some(key);
+
+        writer.write_all(&entry.to_bytes())?;
+        entry_count += 1;
+    }
+
+    writer.flush()?;
+    drop(writer);
+
+    let header = StateHeader::new(entry_count, tip_block, chain_id, tip_hash);
+    let mut file = std::fs::OpenOptions::new().write(true).open(&tmp_path)?;
+    file.write_all(&header.to_bytes())?;
+    drop(file);
+
+    std::fs::rename(&tmp_path, output_path)?;
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_delta(
+        dir: &Path,
+        name: &str,
+        block_number: u64,
+        entries: &[([u8; 20], [u8; 32], [u8; 32])],
+    ) -> PathBuf {
+        let path = dir.join(name);
+        let header = StateHeader::new(entries.len() as u64, block_number, 1, [block_number as u8; 32]);
+        let mut file = File::create(&path).unwrap();
+        file.write_all(&header.to_bytes()).unwrap();
+        for (address, slot, value) in entries {
+            file.write_all(&StorageEntry::new(*address, *slot, *value).to_bytes()).unwrap();
+        }
+        path
+    }
+
+    fn read_all(path: &Path) -> (StateHeader, Vec<StorageEntry>) {
+        let bytes = std::fs::read(path).unwrap();
+        let header = StateHeader::from_bytes(&bytes[..STATE_HEADER_SIZE]).unwrap();
+        let mut entries = Vec::new();
+        let mut offset = STATE_HEADER_SIZE;
+        for _ in 0..header.entry_count {
+            entries.push(StorageEntry::from_bytes(&bytes[offset..offset + STATE_ENTRY_SIZE]).unwrap());
+            offset += STATE_ENTRY_SIZE;
+        }
+        (header, entries)
+    }
+
+    #[test]
+    fn test_merges_disjoint_keys_in_sorted_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let addr_a = [0xAAu8; 20];
+        let addr_b = [0xBBu8; 20];
+        let slot = [0u8; 32];
+
+        let p1 = write_delta(dir.path(), "delta_0000000001.bin", 1, &[(addr_b, slot, [1u8; 32])]);
+        let p2 = write_delta(dir.path(), "delta_0000000002.bin", 2, &[(addr_a, slot, [2u8; 32])]);
+
+        let output = dir.path().join("state.bin");
+        let header = compact_range(&[p1, p2], &output).unwrap();
+
+        assert_eq!(header.entry_count, 2);
+        assert_eq!(header.block_number, 2);
+
+        let (_, entries) = read_all(&output);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].address, addr_a);
+        assert_eq!(entries[1].address, addr_b);
+    }
+
+    #[test]
+    fn test_later_block_wins_for_same_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let addr = [0x11u8; 20];
+        let slot = [0x22u8; 32];
+
+        let p1 = write_delta(dir.path(), "delta_0000000001.bin", 1, &[(addr, slot, [1u8; 32])]);
+        let p2 = write_delta(dir.path(), "delta_0000000002.bin", 2, &[(addr, slot, [2u8; 32])]);
+
+        let output = dir.path().join("state.bin");
+        let header = compact_range(&[p1, p2], &output).unwrap();
+
+        assert_eq!(header.entry_count, 1);
+        let (_, entries) = read_all(&output);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].value, [2u8; 32]);
+    }
+
+    #[test]
+    fn test_zero_value_write_survives() {
+        let dir = tempfile::tempdir().unwrap();
+        let addr = [0x33u8; 20];
+        let slot = [0x44u8; 32];
+
+        let p1 = write_delta(dir.path(), "delta_0000000001.bin", 1, &[(addr, slot, [9u8; 32])]);
+        let p2 = write_delta(dir.path(), "delta_0000000002.bin", 2, &[(addr, slot, [0u8; 32])]);
+
+        let output = dir.path().join("state.bin");
+        compact_range(&[p1, p2], &output).unwrap();
+
+        let (header, entries) = read_all(&output);
+        assert_eq!(header.entry_count, 1);
+        assert_eq!(entries[0].value, [0u8; 32]);
+    }
+
+    #[test]
+    fn test_empty_delta_files_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let addr = [0x55u8; 20];
+        let slot = [0u8; 32];
+
+        let empty = write_delta(dir.path(), "delta_0000000001.bin", 1, &[]);
+        let non_empty = write_delta(dir.path(), "delta_0000000002.bin", 2, &[(addr, slot, [7u8; 32])]);
+
+        let output = dir.path().join("state.bin");
+        let header = compact_range(&[empty, non_empty], &output).unwrap();
+
+        assert_eq!(header.entry_count, 1);
+        assert_eq!(header.block_number, 2);
+    }
+
+    #[test]
+    fn test_compaction_is_idempotent() {
+        let dir = tempfile::tempdir().unwrap();
+        let addr = [0x66u8; 20];
+        let slot = [0u8; 32];
+
+        let p1 = write_delta(dir.path(), "delta_0000000001.bin", 1, &[(addr, slot, [1u8; 32])]);
+        let p2 = write_delta(dir.path(), "delta_0000000002.bin", 2, &[(addr, slot, [2u8; 32])]);
+
+        let output = dir.path().join("state.bin");
+        compact_range(&[p1.clone(), p2.clone()], &output).unwrap();
+
+        // Re-compact the already-consolidated snapshot alongside the same
+        // (already-applied) deltas: result must be byte-identical.
+        let before = std::fs::read(&output).unwrap();
+        compact_range(&[output.clone(), p1, p2], &output).unwrap();
+        let after = std::fs::read(&output).unwrap();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_all_inputs_empty_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let empty = write_delta(dir.path(), "delta_0000000001.bin", 1, &[]);
+        let output = dir.path().join("state.bin");
+        assert!(matches!(compact_range(&[empty], &output), Err(CompactionError::EmptyRange)));
+    }
+}
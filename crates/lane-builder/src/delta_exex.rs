@@ -2,24 +2,62 @@
 //!
 //! This module watches canonical chain updates and writes per-block delta
 //! `state.bin` files (UBT-ordered) derived from StorageChangeSets + PlainStorageState.
+//!
+//! Every write is durably recorded in a [`DeltaWal`] before it's trusted, so
+//! a reorg rolls back by replaying the WAL instead of re-scanning the
+//! output directory, and a delta file is only pruned once its block is
+//! finalized (see [`DeltaExporterConfig::finalized_depth`] and the WAL
+//! module docs for why that's depth-based rather than a real finality
+//! signal in this tree). On restart, [`DeltaWal::open`] replays the log so
+//! no half-finalized window is lost.
+//!
+//! Each delta's [`StateHeader`] carries a BLAKE3 digest over its entry
+//! body (see `write_block_delta`), so a reader can verify it wasn't
+//! truncated or corrupted before trusting it -- see
+//! `bin/stem_index.rs`'s `--verify-checksum`.
+//!
+//! ## Metrics
+//!
+//! The following metrics are exposed, mirroring the lane updater ExEx's
+//! (see [`crate::exex`]) so both can be scraped by one exporter endpoint:
+//! - `delta_exporter_blocks_processed`: Total blocks written
+//! - `delta_exporter_entries_written`: Entries per block histogram
+//! - `delta_exporter_write_duration_ms`: Per-block write latency histogram
+//! - `delta_exporter_reorgs_total`: Total chain reorgs detected
+//! - `delta_exporter_reverts_total`: Total chain reverts detected
+//! - `delta_exporter_prune_total`: Total delta files pruned
+//! - `delta_exporter_last_block`: Gauge of the most recently committed block
 
 #![cfg(feature = "exex")]
 
+use std::collections::HashSet;
 use std::fs::{self, File};
 use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 use anyhow::Result;
 use futures::TryStreamExt;
+use metrics::{counter, gauge, histogram};
 use reth_ethereum::exex::{ExExContext, ExExNotification};
 use reth_execution_types::Chain;
 use reth_node_api::FullNodeComponents;
 use reth_storage_api::{DatabaseProviderFactory, StorageReader};
 use tracing::{info, warn};
 
-use inspire_core::state_format::{StateHeader, StorageEntry, STATE_ENTRY_SIZE};
+use inspire_core::state_format::{StateHeader, StorageEntry, STATE_ENTRY_SIZE, STATE_HEADER_SIZE};
 use inspire_core::ubt::{compute_storage_tree_index, compute_tree_key};
 
+use crate::delta_wal::{DeltaWal, WalEntry};
+
+const METRIC_BLOCKS_PROCESSED: &str = "delta_exporter_blocks_processed";
+const METRIC_ENTRIES_WRITTEN: &str = "delta_exporter_entries_written";
+const METRIC_WRITE_DURATION_MS: &str = "delta_exporter_write_duration_ms";
+const METRIC_REORGS: &str = "delta_exporter_reorgs_total";
+const METRIC_REVERTS: &str = "delta_exporter_reverts_total";
+const METRIC_PRUNE_TOTAL: &str = "delta_exporter_prune_total";
+const METRIC_LAST_BLOCK: &str = "delta_exporter_last_block";
+
 /// Configuration for delta exporter ExEx.
 #[derive(Debug, Clone)]
 pub struct DeltaExporterConfig {
@@ -27,14 +65,28 @@ pub struct DeltaExporterConfig {
     pub output_dir: PathBuf,
     /// Number of recent blocks to keep (rolling window). 0 = keep all.
     pub keep_blocks: u64,
+    /// A committed block is treated as finalized once it's this many blocks
+    /// behind the current tip, at which point its delta file is dropped
+    /// from the WAL and becomes eligible for pruning. See the module docs
+    /// on why this is a depth-based approximation rather than the node's
+    /// real finalized-header signal.
+    pub finalized_depth: u64,
 }
 
 impl Default for DeltaExporterConfig {
     fn default() -> Self {
-        Self { output_dir: PathBuf::from("./pir-data/delta"), keep_blocks: 256 }
+        Self {
+            output_dir: PathBuf::from("./pir-data/delta"),
+            keep_blocks: 256,
+            finalized_depth: 64,
+        }
     }
 }
 
+fn wal_path(output_dir: &Path) -> PathBuf {
+    output_dir.join("delta.wal")
+}
+
 /// Initialize the delta exporter ExEx.
 pub async fn delta_export_exex<Node: FullNodeComponents>(
     ctx: ExExContext<Node>,
@@ -53,20 +105,48 @@ async fn delta_export_loop<Node: FullNodeComponents>(
     mut ctx: ExExContext<Node>,
     config: DeltaExporterConfig,
 ) -> Result<()> {
+    fs::create_dir_all(&config.output_dir)?;
+    let mut wal = DeltaWal::open(wal_path(&config.output_dir))?;
+
+    let orphaned = wal.reconcile()?;
+    if !orphaned.is_empty() {
+        warn!(
+            count = orphaned.len(),
+            blocks = ?orphaned.iter().map(|e| e.block_number).collect::<Vec<_>>(),
+            "Dropped WAL entries left inconsistent by a crash before this restart"
+        );
+    }
+
     while let Some(notification) = ctx.notifications.try_next().await? {
         let chain_id = ctx.config.chain.chain().id();
         match &notification {
             ExExNotification::ChainCommitted { new } => {
-                export_chain_delta(ctx.provider(), &config, new, chain_id)?;
+                export_chain_delta(ctx.provider(), &config, new, chain_id, &mut wal)?;
+
+                let tip = new.tip().number;
+                gauge!(METRIC_LAST_BLOCK).set(tip as f64);
+
+                let finalized_height = tip.saturating_sub(config.finalized_depth);
+                let finalized = wal.finalize(finalized_height)?;
+                for entry in &finalized {
+                    if prune_old_blocks(&config.output_dir, config.keep_blocks, entry.block_number)? {
+                        counter!(METRIC_PRUNE_TOTAL).increment(1);
+                    }
+                }
+
                 ctx.send_finished_height(new.tip().num_hash())?;
             }
             ExExNotification::ChainReorged { old, new } => {
-                delete_chain_deltas(&config.output_dir, old)?;
-                export_chain_delta(ctx.provider(), &config, new, chain_id)?;
+                counter!(METRIC_REORGS).increment(1);
+                let reorged: HashSet<u64> = old.blocks().keys().copied().collect();
+                wal.rollback(&reorged)?;
+                export_chain_delta(ctx.provider(), &config, new, chain_id, &mut wal)?;
                 ctx.send_finished_height(new.tip().num_hash())?;
             }
             ExExNotification::ChainReverted { old } => {
-                delete_chain_deltas(&config.output_dir, old)?;
+                counter!(METRIC_REVERTS).increment(1);
+                let reverted: HashSet<u64> = old.blocks().keys().copied().collect();
+                wal.rollback(&reverted)?;
             }
         }
     }
@@ -79,18 +159,20 @@ fn export_chain_delta<P, N>(
     config: &DeltaExporterConfig,
     chain: &Chain<N>,
     chain_id: u64,
+    wal: &mut DeltaWal,
 ) -> Result<()>
 where
     P: DatabaseProviderFactory,
     N: reth_primitives_traits::NodePrimitives,
 {
-    fs::create_dir_all(&config.output_dir)?;
-
     let db = provider.database_provider_ro()?;
 
     for (block_number, block) in chain.blocks() {
         let block_hash = block.hash();
+        let parent_hash = block.parent_hash();
         let entries = collect_block_entries(&db, *block_number)?;
+
+        let write_started = Instant::now();
         let output_path = write_block_delta(
             &config.output_dir,
             *block_number,
@@ -98,6 +180,18 @@ where
             block_hash.0,
             &entries,
         )?;
+        let write_duration_ms = write_started.elapsed().as_secs_f64() * 1000.0;
+
+        counter!(METRIC_BLOCKS_PROCESSED).increment(1);
+        histogram!(METRIC_ENTRIES_WRITTEN).record(entries.len() as f64);
+        histogram!(METRIC_WRITE_DURATION_MS).record(write_duration_ms);
+
+        wal.append(WalEntry {
+            block_number: *block_number,
+            block_hash: block_hash.0,
+            parent_hash: parent_hash.0,
+            path: output_path.clone(),
+        })?;
 
         info!(
             block = *block_number,
@@ -105,8 +199,6 @@ where
             path = %output_path.display(),
             "Delta state written"
         );
-
-        prune_old_blocks(&config.output_dir, config.keep_blocks, *block_number)?;
     }
 
     Ok(())
@@ -156,49 +248,65 @@ fn write_block_delta(
     block_hash: [u8; 32],
     entries: &[([u8; 32], [u8; STATE_ENTRY_SIZE])],
 ) -> Result<PathBuf> {
-    let path = output_dir.join(format!("delta_{:010}.bin", block_number));
+    // Keyed by both block number and hash, not number alone, so two blocks
+    // at the same height on competing forks (a reorg racing a crash before
+    // the loser is rolled back) never collide on the same path.
+    let path = output_dir.join(format!("delta_{:010}_{}.bin", block_number, hex::encode(&block_hash[..8])));
     let file = File::create(&path)?;
     let mut writer = BufWriter::new(file);
 
-    let header = StateHeader::new(entries.len() as u64, block_number, chain_id, block_hash);
-    writer.write_all(&header.to_bytes())?;
+    // Reserve the header's place; it's rewritten below once the body digest
+    // is known, after the entries have actually been streamed to disk.
+    writer.write_all(&[0u8; STATE_HEADER_SIZE])?;
 
+    let mut hasher = blake3::Hasher::new();
+    let mut hashed_bytes = 0u64;
     for (_, entry) in entries {
         writer.write_all(entry)?;
+        hasher.update(entry);
+        hashed_bytes += entry.len() as u64;
     }
 
     writer.flush()?;
+    drop(writer);
+
+    let header = StateHeader::new(entries.len() as u64, block_number, chain_id, block_hash)
+        .with_digest(*hasher.finalize().as_bytes(), hashed_bytes);
+    let mut file = std::fs::OpenOptions::new().write(true).open(&path)?;
+    file.write_all(&header.to_bytes())?;
+
     Ok(path)
 }
 
-fn prune_old_blocks(output_dir: &Path, keep_blocks: u64, current_block: u64) -> Result<()> {
+/// Removes the delta file(s) for `current_block - keep_blocks`, if any.
+/// Filenames are keyed by block number *and* hash (see `write_block_delta`),
+/// so a number-only prefix match is used rather than reconstructing the
+/// exact filename -- this also sweeps up a losing fork's leftover file if a
+/// reorg at that height happened before the file could be pruned on its own.
+/// Returns whether anything was actually removed, so callers can keep
+/// [`METRIC_PRUNE_TOTAL`] honest rather than counting no-op calls.
+fn prune_old_blocks(output_dir: &Path, keep_blocks: u64, current_block: u64) -> Result<bool> {
     if keep_blocks == 0 || current_block < keep_blocks {
-        return Ok(());
+        return Ok(false);
     }
 
     let prune_block = current_block - keep_blocks;
-    let prune_path = output_dir.join(format!("delta_{:010}.bin", prune_block));
-    if prune_path.exists() {
-        if let Err(err) = fs::remove_file(&prune_path) {
-            warn!(path = %prune_path.display(), error = %err, "Failed to prune old delta file");
-        }
-    }
+    let prefix = format!("delta_{:010}_", prune_block);
 
-    Ok(())
-}
-
-fn delete_chain_deltas<N>(output_dir: &Path, chain: &Chain<N>) -> Result<()>
-where
-    N: reth_primitives_traits::NodePrimitives,
-{
-    for (block_number, _block) in chain.blocks() {
-        let path = output_dir.join(format!("delta_{:010}.bin", block_number));
-        if path.exists() {
-            if let Err(err) = fs::remove_file(&path) {
-                warn!(path = %path.display(), error = %err, "Failed to remove reverted delta file");
-            }
+    let mut pruned = false;
+    for entry in fs::read_dir(output_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(name) = file_name.to_str() else { continue };
+        if !name.starts_with(&prefix) {
+            continue;
         }
+        if let Err(err) = fs::remove_file(entry.path()) {
+            warn!(path = %entry.path().display(), error = %err, "Failed to prune old delta file");
+            continue;
+        }
+        pruned = true;
     }
 
-    Ok(())
+    Ok(pruned)
 }
@@ -0,0 +1,353 @@
+//! Append-only write-ahead log tracking unfinalized delta exports
+//!
+//! [`crate::delta_exex`] writes one `delta_<block>.bin` file per canonical
+//! block, and deletes/prunes them again on reorg or once they age out of
+//! the rolling window. Doing that by re-scanning the output directory races
+//! with readers and can't tell "this file is gone because it was pruned"
+//! apart from "this file is gone because a reorg landed mid-write" -- a
+//! crash between writing a delta and pruning an old one loses the
+//! invariant that exactly one file exists per canonical block. This module
+//! gives the exporter a durable, replayable record of which delta files
+//! are still *unfinalized*: every write is appended here before it's
+//! trusted, a reorg rolls back by replaying the log instead of guessing
+//! from a directory listing, and finalizing a height drops it from the log
+//! (and only then is it safe to prune).
+//!
+//! # Scope
+//!
+//! `ExExNotification` in this tree only has `ChainCommitted` /
+//! `ChainReorged` / `ChainReverted` variants -- there's no distinct
+//! finalized-header notification to consume, so [`crate::delta_exex`]
+//! derives finality itself via `DeltaExporterConfig::finalized_depth`
+//! (blocks more than this far behind the committed tip are treated as
+//! finalized). That's a conservative depth-based approximation, not a real
+//! consensus finality signal; swap in the node's actual finalized-header
+//! notification here once this tree's ExEx surface exposes one.
+
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// One append-only record: a delta file written for `block_number`/`block_hash`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalEntry {
+    pub block_number: u64,
+    pub block_hash: [u8; 32],
+    /// Hash of this block's parent, so [`DeltaWal::reconcile`] can verify
+    /// the log forms a contiguous chain after a crash restart. `[0u8; 32]`
+    /// for entries written before this field existed.
+    #[serde(default)]
+    pub parent_hash: [u8; 32],
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Error)]
+pub enum WalError {
+    #[error("WAL I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("WAL entry at line {line} is malformed: {source}")]
+    Malformed {
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// Durable record of delta files written but not yet finalized
+///
+/// Backed by a newline-delimited-JSON append-only file, so a crash between
+/// writing a delta file and updating the log can only ever lose the very
+/// last, still-unfinalized append -- never corrupt an earlier entry.
+pub struct DeltaWal {
+    path: PathBuf,
+    entries: Vec<WalEntry>,
+}
+
+impl DeltaWal {
+    /// Open (or create) the WAL at `path`, replaying any entries already on
+    /// disk so a restart picks up exactly where the exporter left off
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, WalError> {
+        let path = path.as_ref().to_path_buf();
+        let entries = Self::replay(&path)?;
+        Ok(Self { path, entries })
+    }
+
+    fn replay(path: &Path) -> Result<Vec<WalEntry>, WalError> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let file = File::open(path)?;
+        let mut entries = Vec::new();
+        for (idx, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let entry: WalEntry = serde_json::from_str(&line)
+                .map_err(|source| WalError::Malformed { line: idx + 1, source })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    /// Append one entry, flushing before returning so it's durable before
+    /// the caller treats the corresponding delta file as trustworthy
+    pub fn append(&mut self, entry: WalEntry) -> Result<(), WalError> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let line = serde_json::to_string(&entry).expect("WalEntry serializes infallibly");
+        writeln!(file, "{line}")?;
+        file.flush()?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Entries still tracked as unfinalized, oldest first
+    pub fn entries(&self) -> &[WalEntry] {
+        &self.entries
+    }
+
+    /// Roll back a reorg or revert: delete the delta file for, and drop
+    /// from the log, every tracked entry whose block number is in
+    /// `removed_blocks`. Replays newest-first so a crash mid-rollback
+    /// always leaves a strict prefix (the oldest surviving blocks) on disk
+    /// rather than an arbitrary subset.
+    pub fn rollback(&mut self, removed_blocks: &HashSet<u64>) -> Result<(), WalError> {
+        let mut kept = Vec::with_capacity(self.entries.len());
+        for entry in self.entries.drain(..).rev() {
+            if removed_blocks.contains(&entry.block_number) {
+                if entry.path.exists() {
+                    fs::remove_file(&entry.path)?;
+                }
+            } else {
+                kept.push(entry);
+            }
+        }
+        kept.reverse();
+        self.entries = kept;
+        self.rewrite()
+    }
+
+    /// Finalize every entry at or below `finalized_block`: drop them from
+    /// the log (their delta files can no longer be reorged away) and
+    /// return them so the caller can safely prune
+    pub fn finalize(&mut self, finalized_block: u64) -> Result<Vec<WalEntry>, WalError> {
+        let (finalized, kept): (Vec<_>, Vec<_>) = self
+            .entries
+            .drain(..)
+            .partition(|e| e.block_number <= finalized_block);
+        self.entries = kept;
+        self.rewrite()?;
+        Ok(finalized)
+    }
+
+    /// Reconcile the log against disk after a crash restart: a crash can
+    /// land between a file operation (write or delete) and [`Self::rewrite`]
+    /// persisting the log entry that describes it, leaving the log
+    /// referencing a delta file that's missing, or a tail of entries whose
+    /// `parent_hash` no longer chains to the entry before it (the tell-tale
+    /// sign of a rollback that deleted files but crashed before its
+    /// `rewrite` landed). Drops every such entry from the log -- without
+    /// attempting to delete their files again, since "missing" is exactly
+    /// the state they're already in -- and returns what was dropped so the
+    /// caller can log it.
+    pub fn reconcile(&mut self) -> Result<Vec<WalEntry>, WalError> {
+        let mut break_at = self.entries.len();
+        for i in 1..self.entries.len() {
+            if self.entries[i].parent_hash != self.entries[i - 1].block_hash {
+                break_at = i;
+                break;
+            }
+        }
+
+        let mut orphaned: HashSet<u64> = self.entries[break_at..].iter().map(|e| e.block_number).collect();
+        orphaned.extend(self.entries.iter().filter(|e| !e.path.exists()).map(|e| e.block_number));
+
+        if orphaned.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let dropped: Vec<WalEntry> = self
+            .entries
+            .iter()
+            .filter(|e| orphaned.contains(&e.block_number))
+            .cloned()
+            .collect();
+        self.entries.retain(|e| !orphaned.contains(&e.block_number));
+        self.rewrite()?;
+        Ok(dropped)
+    }
+
+    /// Rewrite the on-disk log to match `self.entries` exactly, used after
+    /// `rollback`/`finalize` shrink the in-memory log so the file doesn't
+    /// grow forever. Writes to a temp file and renames over the original so
+    /// a crash mid-rewrite never leaves a half-written log in place.
+    fn rewrite(&self) -> Result<(), WalError> {
+        let tmp_path = self.path.with_extension("wal.tmp");
+        {
+            let mut file = File::create(&tmp_path)?;
+            for entry in &self.entries {
+                let line = serde_json::to_string(entry).expect("WalEntry serializes infallibly");
+                writeln!(file, "{line}")?;
+            }
+            file.flush()?;
+        }
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(block_number: u64, path: &str) -> WalEntry {
+        entry_with_parent(block_number, [0u8; 32], path)
+    }
+
+    fn entry_with_parent(block_number: u64, parent_hash: [u8; 32], path: &str) -> WalEntry {
+        WalEntry {
+            block_number,
+            block_hash: [block_number as u8; 32],
+            parent_hash,
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn test_append_and_replay_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_path = dir.path().join("delta.wal");
+
+        {
+            let mut wal = DeltaWal::open(&wal_path).unwrap();
+            wal.append(entry(1, "delta_1.bin")).unwrap();
+            wal.append(entry(2, "delta_2.bin")).unwrap();
+        }
+
+        let wal = DeltaWal::open(&wal_path).unwrap();
+        assert_eq!(wal.entries().len(), 2);
+        assert_eq!(wal.entries()[0].block_number, 1);
+        assert_eq!(wal.entries()[1].block_number, 2);
+    }
+
+    #[test]
+    fn test_rollback_deletes_only_removed_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_path = dir.path().join("delta.wal");
+        let keep_file = dir.path().join("delta_1.bin");
+        let drop_file = dir.path().join("delta_2.bin");
+        fs::write(&keep_file, b"keep").unwrap();
+        fs::write(&drop_file, b"drop").unwrap();
+
+        let mut wal = DeltaWal::open(&wal_path).unwrap();
+        wal.append(entry(1, keep_file.to_str().unwrap())).unwrap();
+        wal.append(entry(2, drop_file.to_str().unwrap())).unwrap();
+
+        let mut removed = HashSet::new();
+        removed.insert(2);
+        wal.rollback(&removed).unwrap();
+
+        assert_eq!(wal.entries().len(), 1);
+        assert_eq!(wal.entries()[0].block_number, 1);
+        assert!(keep_file.exists());
+        assert!(!drop_file.exists());
+
+        let reopened = DeltaWal::open(&wal_path).unwrap();
+        assert_eq!(reopened.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_finalize_truncates_and_returns_finalized_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_path = dir.path().join("delta.wal");
+
+        let mut wal = DeltaWal::open(&wal_path).unwrap();
+        wal.append(entry(1, "delta_1.bin")).unwrap();
+        wal.append(entry(2, "delta_2.bin")).unwrap();
+        wal.append(entry(3, "delta_3.bin")).unwrap();
+
+        let finalized = wal.finalize(2).unwrap();
+        assert_eq!(finalized.len(), 2);
+        assert_eq!(finalized[0].block_number, 1);
+        assert_eq!(finalized[1].block_number, 2);
+
+        assert_eq!(wal.entries().len(), 1);
+        assert_eq!(wal.entries()[0].block_number, 3);
+
+        let reopened = DeltaWal::open(&wal_path).unwrap();
+        assert_eq!(reopened.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_reconcile_drops_entries_with_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_path = dir.path().join("delta.wal");
+        let present = dir.path().join("delta_1.bin");
+        fs::write(&present, b"present").unwrap();
+        let missing = dir.path().join("delta_2.bin"); // never written
+
+        let mut wal = DeltaWal::open(&wal_path).unwrap();
+        wal.append(entry(1, present.to_str().unwrap())).unwrap();
+        wal.append(entry(2, missing.to_str().unwrap())).unwrap();
+
+        let dropped = wal.reconcile().unwrap();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].block_number, 2);
+        assert_eq!(wal.entries().len(), 1);
+        assert_eq!(wal.entries()[0].block_number, 1);
+    }
+
+    #[test]
+    fn test_reconcile_drops_tail_with_broken_parent_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_path = dir.path().join("delta.wal");
+        for n in 1..=3u64 {
+            fs::write(dir.path().join(format!("delta_{n}.bin")), b"x").unwrap();
+        }
+
+        let mut wal = DeltaWal::open(&wal_path).unwrap();
+        wal.append(entry_with_parent(1, [0u8; 32], dir.path().join("delta_1.bin").to_str().unwrap()))
+            .unwrap();
+        wal.append(entry_with_parent(2, [1u8; 32], dir.path().join("delta_2.bin").to_str().unwrap()))
+            .unwrap();
+        // block 3's parent_hash doesn't match block 2's block_hash ([2u8; 32])
+        // -- a crash left this log entry describing an already-rolled-back block.
+        wal.append(entry_with_parent(3, [0xffu8; 32], dir.path().join("delta_3.bin").to_str().unwrap()))
+            .unwrap();
+
+        let dropped = wal.reconcile().unwrap();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].block_number, 3);
+        assert_eq!(wal.entries().len(), 2);
+    }
+
+    #[test]
+    fn test_reconcile_is_noop_for_a_clean_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_path = dir.path().join("delta.wal");
+        for n in 1..=2u64 {
+            fs::write(dir.path().join(format!("delta_{n}.bin")), b"x").unwrap();
+        }
+
+        let mut wal = DeltaWal::open(&wal_path).unwrap();
+        wal.append(entry_with_parent(1, [0u8; 32], dir.path().join("delta_1.bin").to_str().unwrap()))
+            .unwrap();
+        wal.append(entry_with_parent(2, [1u8; 32], dir.path().join("delta_2.bin").to_str().unwrap()))
+            .unwrap();
+
+        assert!(wal.reconcile().unwrap().is_empty());
+        assert_eq!(wal.entries().len(), 2);
+    }
+
+    #[test]
+    fn test_open_on_missing_file_starts_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_path = dir.path().join("does-not-exist.wal");
+        let wal = DeltaWal::open(&wal_path).unwrap();
+        assert!(wal.entries().is_empty());
+    }
+}
@@ -18,9 +18,14 @@
 //! - `lane_updater_reorgs_total`: Total chain reorgs detected
 //! - `lane_updater_reverts_total`: Total chain reverts detected
 //! - `lane_updater_debounce_skips_total`: Reloads skipped due to debouncing
+//! - `lane_updater_wal_writes_total`: Storage-slot writes applied directly
+//!   to `database.bin`/`storage-mapping.bin`
+//! - `lane_updater_wal_reconciled_total`: WAL entries dropped and undone by
+//!   [`crate::lane_wal::LaneWal::reconcile`] on startup
 
 #![cfg(feature = "exex")]
 
+use std::collections::HashSet;
 use std::future::Future;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
@@ -30,8 +35,14 @@ use futures::TryStreamExt;
 use metrics::{counter, histogram};
 use reth_ethereum::exex::{ExExContext, ExExEvent, ExExNotification};
 use reth_ethereum::node::api::FullNodeComponents;
+use reth_execution_types::Chain;
+use reth_storage_api::{DatabaseProviderFactory, StorageReader};
 use tracing::{info, warn, error};
 
+use inspire_core::{Address, HotLaneManifest};
+
+use crate::lane_indexer::{LaneIndexer, LaneIndexerConfig};
+use crate::lane_wal::{LaneStore, LaneWal, LaneWalEntry};
 use crate::reload::ReloadClient;
 
 const METRIC_RELOAD_TOTAL: &str = "lane_updater_reload_total";
@@ -41,6 +52,12 @@ const METRIC_BLOCKS_PROCESSED: &str = "lane_updater_blocks_processed";
 const METRIC_REORGS: &str = "lane_updater_reorgs_total";
 const METRIC_REVERTS: &str = "lane_updater_reverts_total";
 const METRIC_DEBOUNCE_SKIPS: &str = "lane_updater_debounce_skips_total";
+const METRIC_WAL_WRITES: &str = "lane_updater_wal_writes_total";
+const METRIC_WAL_RECONCILED: &str = "lane_updater_wal_reconciled_total";
+
+fn lane_wal_path(data_dir: &std::path::Path) -> PathBuf {
+    data_dir.join("lane.wal")
+}
 
 /// Configuration for the lane updater ExEx
 #[derive(Debug, Clone)]
@@ -51,6 +68,14 @@ pub struct LaneUpdaterConfig {
     pub data_dir: PathBuf,
     /// Minimum interval between reloads (debounce)
     pub reload_debounce: Duration,
+    /// Tuning for the live hot/cold partitioner driven by this ExEx's own
+    /// notifications (see [`crate::lane_indexer::LaneIndexer`])
+    pub lane_indexer: LaneIndexerConfig,
+    /// A committed block's writes are dropped from the incremental-update
+    /// WAL once it's this many blocks behind the current tip (see
+    /// [`crate::lane_wal`] for why this is a depth-based approximation
+    /// rather than the node's real finalized-header signal).
+    pub finalized_depth: u64,
 }
 
 impl Default for LaneUpdaterConfig {
@@ -59,6 +84,8 @@ impl Default for LaneUpdaterConfig {
             server_url: "http://localhost:3000".to_string(),
             data_dir: PathBuf::from("./pir-data"),
             reload_debounce: Duration::from_secs(1),
+            lane_indexer: LaneIndexerConfig::default(),
+            finalized_depth: 64,
         }
     }
 }
@@ -78,14 +105,37 @@ pub async fn lane_updater_exex<Node: FullNodeComponents>(
     );
 
     let reload_client = ReloadClient::new(&config.server_url);
-    
+
     match reload_client.health().await {
         Ok(true) => info!("PIR server is healthy"),
         Ok(false) => warn!("PIR server health check failed"),
         Err(e) => warn!(error = %e, "PIR server health check error - server may be unavailable"),
     }
 
-    Ok(lane_updater_loop(ctx, config, reload_client))
+    let manifest_path = config.data_dir.join("hot").join("manifest.json");
+    let manifest = HotLaneManifest::load(&manifest_path).unwrap_or_else(|e| {
+        warn!(
+            path = %manifest_path.display(),
+            error = %e,
+            "No existing hot lane manifest, starting the partitioner from an empty one"
+        );
+        HotLaneManifest::new(0)
+    });
+    let indexer = LaneIndexer::new(manifest, config.lane_indexer.clone());
+
+    let store = LaneStore::new(&config.data_dir);
+    let mut wal = LaneWal::open(lane_wal_path(&config.data_dir))?;
+    let orphaned = wal.reconcile(&store)?;
+    if !orphaned.is_empty() {
+        counter!(METRIC_WAL_RECONCILED).increment(orphaned.len() as u64);
+        warn!(
+            count = orphaned.len(),
+            blocks = ?orphaned.iter().map(|e| e.block_number).collect::<Vec<_>>(),
+            "Undid lane store writes left inconsistent by a crash before this restart"
+        );
+    }
+
+    Ok(lane_updater_loop(ctx, config, reload_client, indexer, store, wal))
 }
 
 /// Main processing loop for the lane updater
@@ -93,6 +143,9 @@ async fn lane_updater_loop<Node: FullNodeComponents>(
     mut ctx: ExExContext<Node>,
     config: LaneUpdaterConfig,
     reload_client: ReloadClient,
+    mut indexer: LaneIndexer,
+    store: LaneStore,
+    mut wal: LaneWal,
 ) -> Result<()> {
     let mut last_reload = Instant::now();
 
@@ -100,14 +153,48 @@ async fn lane_updater_loop<Node: FullNodeComponents>(
         match &notification {
             ExExNotification::ChainCommitted { new } => {
                 let committed_range = new.range();
-                
+
                 counter!(METRIC_BLOCKS_PROCESSED).increment(1);
-                
+
                 info!(
                     chain = ?committed_range,
                     "Chain committed, checking for lane updates"
                 );
 
+                match update_partition_for_range(ctx.provider(), &mut indexer, committed_range.clone()) {
+                    Ok(delta) if !delta.is_empty() => {
+                        if let Err(e) = indexer.persist(&config.data_dir) {
+                            warn!(error = %e, "Failed to persist live-partitioned lane manifest");
+                        } else {
+                            info!(
+                                promoted = delta.added.len(),
+                                demoted = delta.removed.len(),
+                                "Lane partition updated from chain state"
+                            );
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!(error = %e, "Failed to update lane partition from chain state"),
+                }
+
+                match apply_chain_writes(ctx.provider(), new, &store, &mut wal) {
+                    Ok(applied) if applied > 0 => {
+                        counter!(METRIC_WAL_WRITES).increment(applied);
+                        info!(applied, "Applied incremental storage writes to lane store");
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!(error = %e, "Failed to apply incremental storage writes to lane store"),
+                }
+
+                let finalized_height = new.tip().number.saturating_sub(config.finalized_depth);
+                match wal.finalize(finalized_height) {
+                    Ok(finalized) if !finalized.is_empty() => {
+                        info!(count = finalized.len(), finalized_height, "Finalized lane WAL entries");
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!(error = %e, "Failed to finalize lane WAL"),
+                }
+
                 if last_reload.elapsed() >= config.reload_debounce {
                     let start = Instant::now();
                     match trigger_lane_update(&reload_client).await {
@@ -138,12 +225,31 @@ async fn lane_updater_loop<Node: FullNodeComponents>(
             }
             ExExNotification::ChainReverted { old } => {
                 counter!(METRIC_REVERTS).increment(1);
-                
+
                 warn!(
                     reverted_chain = ?old.range(),
                     "Chain reverted - triggering lane rebuild"
                 );
 
+                let reverted_blocks: HashSet<u64> = old.blocks().keys().copied().collect();
+                if let Err(e) = wal.rollback(&store, &reverted_blocks) {
+                    error!(error = %e, "Failed to undo reverted blocks' writes in the lane store");
+                }
+
+                if let Some(fork_point) = old.range().start().checked_sub(1) {
+                    if indexer.revert_to(fork_point) {
+                        if let Err(e) = indexer.persist(&config.data_dir) {
+                            warn!(error = %e, "Failed to persist reverted lane partition");
+                        }
+                    } else {
+                        warn!(
+                            fork_point,
+                            "Revert target outside the partitioner's snapshot window, \
+                             falling back to a full offline rebuild"
+                        );
+                    }
+                }
+
                 let start = Instant::now();
                 match trigger_lane_update(&reload_client).await {
                     Ok(result) => {
@@ -166,13 +272,26 @@ async fn lane_updater_loop<Node: FullNodeComponents>(
             }
             ExExNotification::ChainReorged { old, new } => {
                 counter!(METRIC_REORGS).increment(1);
-                
+
                 warn!(
                     from_chain = ?old.range(),
                     to_chain = ?new.range(),
                     "Chain reorged - triggering lane rebuild"
                 );
 
+                let reorged_blocks: HashSet<u64> = old.blocks().keys().copied().collect();
+                if let Err(e) = wal.rollback(&store, &reorged_blocks) {
+                    error!(error = %e, "Failed to undo reorged blocks' writes in the lane store");
+                }
+                match apply_chain_writes(ctx.provider(), new, &store, &mut wal) {
+                    Ok(applied) if applied > 0 => {
+                        counter!(METRIC_WAL_WRITES).increment(applied);
+                        info!(applied, "Applied new canonical chain's writes to lane store after reorg");
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!(error = %e, "Failed to apply new canonical chain's writes after reorg"),
+                }
+
                 let start = Instant::now();
                 match trigger_lane_update(&reload_client).await {
                     Ok(result) => {
@@ -207,3 +326,117 @@ async fn trigger_lane_update(
 ) -> anyhow::Result<crate::reload::ReloadResult> {
     client.reload().await
 }
+
+/// Feed every block in `range` through `indexer`, deciding hot/cold
+/// membership from the set of accounts whose storage actually changed
+/// (the same `changed_storages_with_range` query [`crate::delta_exex`] uses
+/// to extract values) rather than touching the per-slot values ourselves.
+/// Returns the cumulative delta across `range`.
+fn update_partition_for_range<P>(
+    provider: &P,
+    indexer: &mut LaneIndexer,
+    range: std::ops::RangeInclusive<u64>,
+) -> anyhow::Result<inspire_core::ManifestDelta>
+where
+    P: DatabaseProviderFactory,
+{
+    let db = provider.database_provider_ro()?;
+    let mut delta = inspire_core::ManifestDelta::default();
+
+    for block_number in range {
+        let touched = touched_addresses(&db, block_number)?;
+        let block_delta = indexer.apply_chain_committed(block_number, touched);
+        delta.added.extend(block_delta.added);
+        delta.removed.extend(block_delta.removed);
+        delta.resized.extend(block_delta.resized);
+        delta.reindexed.extend(block_delta.reindexed);
+        delta.to_block = block_number;
+        delta.new_total_entries = block_delta.new_total_entries;
+    }
+
+    Ok(delta)
+}
+
+fn touched_addresses<P>(provider: &P, block_number: u64) -> anyhow::Result<Vec<Address>>
+where
+    P: StorageReader,
+{
+    let changed = provider.changed_storages_with_range(block_number..=block_number)?;
+    Ok(changed.into_keys().map(|address| address.0 .0).collect())
+}
+
+/// Apply every block in `chain`'s changed `(address, slot, value)` tuples
+/// directly to the lane store, durably logging each block's writes to
+/// `wal` before moving on to the next. Returns the total number of slot
+/// writes applied across `chain`.
+fn apply_chain_writes<P, N>(
+    provider: &P,
+    chain: &Chain<N>,
+    store: &LaneStore,
+    wal: &mut LaneWal,
+) -> anyhow::Result<u64>
+where
+    P: DatabaseProviderFactory,
+    N: reth_primitives_traits::NodePrimitives,
+{
+    let db = provider.database_provider_ro()?;
+    let mut total_applied = 0u64;
+
+    for (block_number, block) in chain.blocks() {
+        let writes = collect_block_storage_writes(&db, *block_number)?;
+        if writes.is_empty() {
+            continue;
+        }
+
+        let entries_before = store.entry_count()?;
+        let applied = store.apply_writes(&writes)?;
+        total_applied += applied.len() as u64;
+
+        wal.append(LaneWalEntry {
+            block_number: *block_number,
+            block_hash: block.hash().0,
+            parent_hash: block.parent_hash().0,
+            entries_before,
+            writes: applied,
+        })?;
+    }
+
+    Ok(total_applied)
+}
+
+/// Extract the `(address, slot, value)` tuples changed by `block_number`,
+/// the same `changed_storages_with_range` + `plain_state_storages` pair
+/// [`crate::delta_exex::collect_block_entries`] uses, but returning raw
+/// values instead of UBT-encoded `StateEntry` bytes since the lane store's
+/// `database.bin`/`storage-mapping.bin` format (see `bin/pir_prep.rs`)
+/// isn't tree-ordered.
+fn collect_block_storage_writes<P>(
+    provider: &P,
+    block_number: u64,
+) -> anyhow::Result<Vec<([u8; 20], [u8; 32], [u8; 32])>>
+where
+    P: StorageReader,
+{
+    let changed = provider.changed_storages_with_range(block_number..=block_number)?;
+    if changed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let address_keys = changed
+        .iter()
+        .map(|(address, keys)| (*address, keys.iter().cloned().collect::<Vec<_>>()));
+
+    let updated = provider.plain_state_storages(address_keys)?;
+
+    let mut writes = Vec::new();
+    for (address, storage_entries) in updated {
+        let address_bytes = address.0 .0;
+        for storage_entry in storage_entries {
+            let slot_bytes: [u8; 32] = storage_entry.key.0;
+            let value_bytes: [u8; 32] = storage_entry.value.to_be_bytes();
+            writes.push((address_bytes, slot_bytes, value_bytes));
+        }
+    }
+
+    Ok(writes)
+}
@@ -1,11 +1,17 @@
 //! Contract extractor: identifies hot lane contracts
 
-use std::collections::HashMap;
+use std::num::NonZeroUsize;
 use std::path::Path;
 
+use lru::LruCache;
+
 use inspire_core::{Address, HotLaneManifest};
 
 use crate::contracts::{ContractInfo, HOT_CONTRACTS};
+use crate::hybrid_scorer::HybridScorerConfig;
+use crate::storage_layout::{self, StorageLayout};
+
+const DEFAULT_CACHE_CAPACITY: usize = 10_000;
 
 /// Contract popularity data
 #[derive(Debug, Clone)]
@@ -15,11 +21,45 @@ pub struct ContractStats {
     pub category: String,
     pub tx_count: u64,
     pub storage_slots: u64,
+    /// Individually-tracked hot slots and their decayed access weight (see
+    /// `inspire_updater::StateTracker::hot_slots`), descending by weight.
+    /// When non-empty, `build_manifest` sizes this contract's manifest
+    /// entry off this list's length instead of `storage_slots`' flat guess.
+    pub hot_slots: Vec<([u8; 32], f64)>,
+    /// Statically-known storage footprint imported from a `solc
+    /// --storage-layout` file (see `crate::storage_layout`). When present,
+    /// `build_manifest` sizes this contract off `base_slots` plus whatever
+    /// `storage_slots` has separately observed for its dynamic
+    /// (`mapping`/array) members, instead of guessing.
+    pub storage_layout: Option<StorageLayout>,
+}
+
+/// [`ContractExtractor`]'s cache hit/miss counters, surfaced through
+/// [`crate::gas_tracker::BackfillResult::with_cache_stats`] so operators
+/// running `lane-backfill` over 100k blocks can see how effective the LRU
+/// bound is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub capacity: usize,
+    pub len: usize,
 }
 
-/// Extracts and ranks contracts for hot lane inclusion
+/// Extracts and ranks contracts for hot lane inclusion.
+///
+/// Repeated metadata lookups during backfill and reload (a contract's
+/// [`ContractStats`]: category, tx count, storage footprint) are served from a bounded
+/// [`LruCache`] keyed by [`Address`] instead of an ever-growing `HashMap`,
+/// the same swap the Parity node-filter made when it capped its peer cache.
+/// Contracts loaded from the curated [`HOT_CONTRACTS`] list are kept in a
+/// separate, unbounded `known` map so the cache can never evict a pinned
+/// hot-set entry.
 pub struct ContractExtractor {
-    contracts: HashMap<Address, ContractStats>,
+    known: std::collections::HashMap<Address, ContractStats>,
+    cache: LruCache<Address, ContractStats>,
+    cache_hits: u64,
+    cache_misses: u64,
     max_contracts: usize,
     max_entries: u64,
 }
@@ -28,12 +68,24 @@ impl ContractExtractor {
     /// Create a new extractor with default limits
     pub fn new() -> Self {
         Self {
-            contracts: HashMap::new(),
+            known: std::collections::HashMap::new(),
+            cache: LruCache::new(NonZeroUsize::new(DEFAULT_CACHE_CAPACITY).unwrap()),
+            cache_hits: 0,
+            cache_misses: 0,
             max_contracts: 1000,
             max_entries: 1_000_000,
         }
     }
 
+    /// Build an extractor whose cache capacity is driven by a
+    /// [`HybridScorerConfig`], so a single config value sizes both the final
+    /// hot-lane cutoff (`max_contracts`) and the metadata cache behind it.
+    pub fn from_scorer_config(config: &HybridScorerConfig) -> Self {
+        Self::new()
+            .with_max_contracts(config.max_contracts)
+            .with_cache_capacity(config.contract_cache_capacity)
+    }
+
     /// Set maximum number of contracts
     pub fn with_max_contracts(mut self, max: usize) -> Self {
         self.max_contracts = max;
@@ -46,34 +98,139 @@ impl ContractExtractor {
         self
     }
 
-    /// Load known contracts from the curated list
+    /// Set the LRU metadata cache's capacity (clamped to at least 1).
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache.resize(NonZeroUsize::new(capacity.max(1)).unwrap());
+        self
+    }
+
+    /// Load known contracts from the curated list. These are pinned -- the
+    /// LRU cache never evicts them since they never enter it.
     pub fn load_known_contracts(&mut self) {
         for contract in HOT_CONTRACTS {
-            self.add_contract(ContractStats {
-                address: contract.address,
-                name: contract.name.to_string(),
-                category: contract.category.to_string(),
-                tx_count: 0,
-                storage_slots: 0,
-            });
+            self.known.insert(
+                contract.address,
+                ContractStats {
+                    address: contract.address,
+                    name: contract.name.to_string(),
+                    category: contract.category.to_string(),
+                    tx_count: 0,
+                    storage_slots: 0,
+                    hot_slots: Vec::new(),
+                    storage_layout: None,
+                },
+            );
         }
     }
 
-    /// Add a contract to the extractor
+    /// Add a contract to the extractor. Known (pinned) addresses are
+    /// updated in place; everything else goes through the bounded LRU cache.
     pub fn add_contract(&mut self, stats: ContractStats) {
-        self.contracts.insert(stats.address, stats);
+        if self.known.contains_key(&stats.address) {
+            self.known.insert(stats.address, stats);
+        } else {
+            let _ = self.cache.put(stats.address, stats);
+        }
+    }
+
+    /// Look up a contract's cached metadata, counting the access as a cache
+    /// hit or miss (known/pinned entries don't count towards either, since
+    /// they're never evicted in the first place).
+    pub fn get_contract(&mut self, address: &Address) -> Option<&ContractStats> {
+        if let Some(stats) = self.known.get(address) {
+            return Some(stats);
+        }
+        match self.cache.get(address) {
+            Some(stats) => {
+                self.cache_hits += 1;
+                Some(stats)
+            }
+            None => {
+                self.cache_misses += 1;
+                None
+            }
+        }
     }
 
     /// Update storage slot count for a contract
     pub fn update_slots(&mut self, address: &Address, slots: u64) {
-        if let Some(stats) = self.contracts.get_mut(address) {
+        if let Some(stats) = self.known.get_mut(address) {
             stats.storage_slots = slots;
+            return;
+        }
+        match self.cache.get_mut(address) {
+            Some(stats) => {
+                self.cache_hits += 1;
+                stats.storage_slots = slots;
+            }
+            None => self.cache_misses += 1,
+        }
+    }
+
+    /// Record a contract's per-slot hotness, e.g. from
+    /// `inspire_updater::StateTracker::hot_slots`, for use by
+    /// `build_manifest` instead of a flat `storage_slots` guess.
+    pub fn update_hot_slots(&mut self, address: &Address, hot_slots: Vec<([u8; 32], f64)>) {
+        if let Some(stats) = self.known.get_mut(address) {
+            stats.hot_slots = hot_slots;
+            return;
+        }
+        match self.cache.get_mut(address) {
+            Some(stats) => {
+                self.cache_hits += 1;
+                stats.hot_slots = hot_slots;
+            }
+            None => self.cache_misses += 1,
+        }
+    }
+
+    /// Record a contract's imported storage layout (see
+    /// `crate::storage_layout`), for use by `build_manifest` instead of a
+    /// flat `storage_slots` guess.
+    pub fn update_storage_layout(&mut self, address: &Address, layout: StorageLayout) {
+        if let Some(stats) = self.known.get_mut(address) {
+            stats.storage_layout = Some(layout);
+            return;
+        }
+        match self.cache.get_mut(address) {
+            Some(stats) => {
+                self.cache_hits += 1;
+                stats.storage_layout = Some(layout);
+            }
+            None => self.cache_misses += 1,
+        }
+    }
+
+    /// Load every storage-layout file in `dir` (see
+    /// [`storage_layout::load_layouts_from_dir`]) and apply each to its
+    /// matching tracked contract. Layouts for addresses not already known
+    /// to this extractor are ignored, the same way `update_slots` ignores
+    /// untracked addresses. Returns how many layouts were applied.
+    pub fn load_layouts_from_dir(&mut self, dir: &Path) -> anyhow::Result<usize> {
+        let layouts = storage_layout::load_layouts_from_dir(dir)?;
+        let mut applied = 0;
+        for (address, layout) in layouts {
+            if self.known.contains_key(&address) || self.cache.contains(&address) {
+                self.update_storage_layout(&address, layout);
+                applied += 1;
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Current cache hit/miss counters.
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.cache_hits,
+            misses: self.cache_misses,
+            capacity: self.cache.cap().get(),
+            len: self.cache.len(),
         }
     }
 
     /// Get contracts sorted by popularity (tx_count)
     pub fn ranked_contracts(&self) -> Vec<&ContractStats> {
-        let mut contracts: Vec<_> = self.contracts.values().collect();
+        let mut contracts: Vec<_> = self.known.values().chain(self.cache.iter().map(|(_, v)| v)).collect();
         contracts.sort_by(|a, b| b.tx_count.cmp(&a.tx_count));
         contracts
     }
@@ -89,7 +246,17 @@ impl ContractExtractor {
                 break;
             }
             
-            let slots = if stats.storage_slots > 0 {
+            // Prefer an imported storage layout's base slot count (plus
+            // whatever dynamic mapping/array slots have separately been
+            // observed) over the count of individually-observed hot slots,
+            // over the flat `storage_slots` counter, over the `1000` guess
+            // used when nothing at all is known about this contract's
+            // footprint yet.
+            let slots = if let Some(layout) = stats.storage_layout {
+                layout.base_slots + stats.storage_slots
+            } else if !stats.hot_slots.is_empty() {
+                stats.hot_slots.len() as u64
+            } else if stats.storage_slots > 0 {
                 stats.storage_slots
             } else {
                 1000
@@ -133,15 +300,18 @@ impl ContractExtractor {
                 category: info.category,
                 tx_count: info.tx_count.unwrap_or(0),
                 storage_slots: info.storage_slots.unwrap_or(0),
+                hot_slots: Vec::new(),
+                storage_layout: info.storage_layout,
             });
         }
         
         Ok(extractor)
     }
 
-    /// Number of contracts currently tracked
+    /// Number of contracts currently tracked (pinned known contracts plus
+    /// whatever is presently resident in the LRU cache)
     pub fn contract_count(&self) -> usize {
-        self.contracts.len()
+        self.known.len() + self.cache.len()
     }
 }
 
@@ -177,6 +347,8 @@ mod tests {
             category: "token".into(),
             tx_count: 1000,
             storage_slots: 500,
+            hot_slots: Vec::new(),
+            storage_layout: None,
         });
         extractor.add_contract(ContractStats {
             address: [0x22u8; 20],
@@ -184,6 +356,8 @@ mod tests {
             category: "defi".into(),
             tx_count: 2000,
             storage_slots: 300,
+            hot_slots: Vec::new(),
+            storage_layout: None,
         });
 
         let manifest = extractor.build_manifest(12345);
@@ -202,6 +376,8 @@ mod tests {
             category: "token".into(),
             tx_count: 1000,
             storage_slots: 400,
+            hot_slots: Vec::new(),
+            storage_layout: None,
         });
         extractor.add_contract(ContractStats {
             address: [0x22u8; 20],
@@ -209,11 +385,71 @@ mod tests {
             category: "defi".into(),
             tx_count: 500,
             storage_slots: 200,
+            hot_slots: Vec::new(),
+            storage_layout: None,
         });
 
         let manifest = extractor.build_manifest(12345);
-        
+
         assert_eq!(manifest.contract_count(), 1);
         assert_eq!(manifest.total_entries, 400);
     }
+
+    #[test]
+    fn test_cache_eviction_never_drops_known_contracts() {
+        let mut extractor = ContractExtractor::new().with_cache_capacity(1);
+        extractor.load_known_contracts();
+        let known_addr = HOT_CONTRACTS[0].address;
+
+        // Push far more non-known contracts through than the cache's
+        // capacity of 1; the known entry must survive every eviction.
+        for i in 0..10u8 {
+            extractor.add_contract(ContractStats {
+                address: [i; 20],
+                name: format!("Contract{i}"),
+                category: "token".into(),
+                tx_count: i as u64,
+                storage_slots: 10,
+                hot_slots: Vec::new(),
+                storage_layout: None,
+            });
+        }
+
+        assert!(extractor.get_contract(&known_addr).is_some());
+        assert_eq!(extractor.cache_stats().capacity, 1);
+        assert!(extractor.cache_stats().len <= 1);
+    }
+
+    #[test]
+    fn test_cache_hit_miss_counters() {
+        let mut extractor = ContractExtractor::new();
+        let addr = [0x11u8; 20];
+        extractor.add_contract(ContractStats {
+            address: addr,
+            name: "Test1".into(),
+            category: "token".into(),
+            tx_count: 1,
+            storage_slots: 1,
+            hot_slots: Vec::new(),
+            storage_layout: None,
+        });
+
+        assert!(extractor.get_contract(&addr).is_some());
+        assert!(extractor.get_contract(&[0xffu8; 20]).is_none());
+
+        let stats = extractor.cache_stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+    }
+
+    #[test]
+    fn test_from_scorer_config_sizes_cache_and_max_contracts() {
+        let config = HybridScorerConfig {
+            max_contracts: 5,
+            contract_cache_capacity: 2,
+            ..Default::default()
+        };
+        let extractor = ContractExtractor::from_scorer_config(&config);
+        assert_eq!(extractor.cache_stats().capacity, 2);
+    }
 }
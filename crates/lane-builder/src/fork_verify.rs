@@ -0,0 +1,175 @@
+//! Fork-based verification of the curated contract list
+//!
+//! [`crate::contracts::validate`] catches typos and checksum mistakes in
+//! the curated literals, but can't tell a well-formed, correctly-checksummed
+//! address apart from one that's simply wrong, or that pointed at a live
+//! contract once but was later self-destructed. [`ForkVerifier`] closes
+//! that gap by asking a real (or forked, e.g. anvil/geth `--fork-url`) node
+//! whether each curated address still has code, and how big its storage
+//! footprint roughly is -- turning the curated list from an unverified
+//! literal into something checked against live chain state.
+//!
+//! This is gated behind the `fork-verify` feature and an RPC endpoint
+//! supplied at runtime (there's no mainnet fork bundled with the repo), so
+//! CI can skip it entirely when offline.
+
+#![cfg(feature = "fork-verify")]
+
+use serde_json::{json, Value};
+
+use inspire_core::Address;
+
+use crate::contracts::KnownContract;
+
+/// Result of checking a single curated entry against a live node.
+#[derive(Debug, Clone)]
+pub struct ForkVerificationResult {
+    pub name: &'static str,
+    pub address: Address,
+    /// `eth_getCode` byte length. Zero means the address is an EOA (or a
+    /// self-destructed contract) on the chain the RPC endpoint serves.
+    pub code_len: usize,
+    /// Observed storage slot count, when [`ForkVerifier::verify`] was asked
+    /// to estimate it -- a rough `eth_getProof` trie-node count, for
+    /// sanity-checking the hot-lane sizing assumptions in
+    /// [`KnownContract`]/[`crate::contracts::ContractInfo`].
+    pub storage_slots: Option<u64>,
+}
+
+impl ForkVerificationResult {
+    /// Whether the RPC endpoint reports this address as a contract.
+    pub fn has_code(&self) -> bool {
+        self.code_len > 0
+    }
+}
+
+/// Minimal JSON-RPC client for checking curated addresses against a live
+/// (or forked) node. Intentionally not the full `alloy_provider::Provider`
+/// surface -- this only ever needs `eth_getCode` and `eth_getProof`.
+pub struct ForkVerifier {
+    rpc_url: String,
+    client: reqwest::Client,
+}
+
+impl ForkVerifier {
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn rpc_call(&self, method: &str, params: Value) -> anyhow::Result<Value> {
+        let response = self
+            .client
+            .post(&self.rpc_url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await?;
+
+        let body: Value = response.json().await?;
+        if let Some(error) = body.get("error") {
+            anyhow::bail!("RPC error calling {method}: {error}");
+        }
+        body.get("result")
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("RPC response for {method} missing `result`"))
+    }
+
+    /// Runtime bytecode at `address`, via `eth_getCode` at the latest block.
+    pub async fn get_code(&self, address: Address) -> anyhow::Result<Vec<u8>> {
+        let result = self
+            .rpc_call("eth_getCode", json!([format!("0x{}", hex::encode(address)), "latest"]))
+            .await?;
+        let hex_str = result
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("eth_getCode result was not a string"))?;
+        Ok(hex::decode(hex_str.trim_start_matches("0x"))?)
+    }
+
+    /// Number of account proof nodes `eth_getProof` returns for `address`
+    /// with no storage keys requested -- a rough, cheap proxy for account
+    /// complexity, not an exact slot count.
+    pub async fn estimate_storage_slots(&self, address: Address) -> anyhow::Result<u64> {
+        let result = self
+            .rpc_call(
+                "eth_getProof",
+                json!([format!("0x{}", hex::encode(address)), [], "latest"]),
+            )
+            .await?;
+        let proof_len = result
+            .get("accountProof")
+            .and_then(Value::as_array)
+            .map(Vec::len)
+            .unwrap_or(0);
+        Ok(proof_len as u64)
+    }
+
+    /// Check every `contracts` entry's `eth_getCode`, and its storage
+    /// footprint when `with_storage_slots` is set.
+    pub async fn verify(
+        &self,
+        contracts: &[KnownContract],
+        with_storage_slots: bool,
+    ) -> anyhow::Result<Vec<ForkVerificationResult>> {
+        let mut results = Vec::with_capacity(contracts.len());
+        for known in contracts {
+            let code_len = self.get_code(known.address).await?.len();
+            let storage_slots = if with_storage_slots {
+                Some(self.estimate_storage_slots(known.address).await?)
+            } else {
+                None
+            };
+            results.push(ForkVerificationResult {
+                name: known.name,
+                address: known.address,
+                code_len,
+                storage_slots,
+            });
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::HOT_CONTRACTS;
+
+    /// Name of the env var pointing at a mainnet (or mainnet-forked, e.g.
+    /// `anvil --fork-url <rpc> --fork-block-number <n>`) JSON-RPC endpoint.
+    /// Unset by default so CI runs offline; export it locally to exercise
+    /// this test against a real node.
+    const RPC_URL_ENV: &str = "PIR_FORK_RPC_URL";
+
+    #[tokio::test]
+    async fn test_hot_contracts_have_code_on_fork() {
+        let Ok(rpc_url) = std::env::var(RPC_URL_ENV) else {
+            eprintln!("skipping: {RPC_URL_ENV} not set");
+            return;
+        };
+
+        let verifier = ForkVerifier::new(rpc_url);
+        let results = verifier.verify(HOT_CONTRACTS, true).await.unwrap();
+
+        for result in &results {
+            assert!(
+                result.has_code(),
+                "{} ({:x?}) has no code on the target fork",
+                result.name,
+                result.address
+            );
+            eprintln!(
+                "{}: {} code bytes, ~{} account proof nodes",
+                result.name,
+                result.code_len,
+                result.storage_slots.unwrap_or(0)
+            );
+        }
+    }
+}
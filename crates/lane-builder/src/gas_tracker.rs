@@ -5,12 +5,12 @@
 
 #![cfg(feature = "backfill")]
 
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use alloy_provider::{Provider, ProviderBuilder};
-use alloy_rpc_types::{BlockNumberOrTag, TransactionTrait};
+use alloy_rpc_types::{BlockId, BlockNumberOrTag, TransactionTrait};
 use futures::stream::{self, StreamExt};
 use indicatif::{ProgressBar, ProgressStyle};
 use serde::{Deserialize, Serialize};
@@ -25,27 +25,140 @@ pub struct GasStats {
     pub tx_count: u64,
     pub first_seen_block: u64,
     pub last_seen_block: u64,
+    /// Call counts per 4-byte function selector observed for this contract
+    /// (hex-encoded, `0x`-prefixed), the raw material
+    /// [`crate::category_heuristics::infer_category`] matches against the
+    /// embedded selector table to categorize contracts with no entry in the
+    /// curated known-contracts list.
+    #[serde(default)]
+    pub selectors: HashMap<String, u64>,
+    /// Gas accumulated per recency bucket of `BackfillConfig::bucket_block_span`
+    /// blocks each, newest bucket first (`gas_buckets[0]` covers the most
+    /// recent span, `gas_buckets[1]` the one before that, and so on). Always
+    /// sums to `total_gas`. Lets [`crate::hybrid_scorer::HybridScorer`] weigh
+    /// recent activity more than historical activity instead of treating
+    /// `total_gas` as one flat lifetime sum.
+    #[serde(default)]
+    pub gas_buckets: Vec<u64>,
+    /// Gas attributed to this address as a transaction's top-level `to`.
+    /// Always `<= total_gas`; `top_level_gas + internal_gas == total_gas`.
+    #[serde(default)]
+    pub top_level_gas: u64,
+    /// Gas attributed to this address as the executed code of an internal
+    /// sub-call (see [`BackfillConfig::use_call_tracer`]) -- zero unless
+    /// tracing mode is enabled, since top-level-only attribution can't see
+    /// inside a transaction's call tree. A router/proxy whose own top-level
+    /// gas looks small but whose delegatecall targets do all the work shows
+    /// up here instead of being invisible to `top_contracts`.
+    #[serde(default)]
+    pub internal_gas: u64,
+    /// Top-level transactions that reverted (receipt `status == 0`), out of
+    /// `tx_count`. Only populated when [`BackfillConfig::gas_source`] is
+    /// `GasUsed`, since `GasLimit` mode never fetches a receipt -- a
+    /// contract with a high revert rate is a candidate to weight down or
+    /// exclude from hot-lane promotion even if its raw gas total is large.
+    #[serde(default)]
+    pub reverted_tx_count: u64,
 }
 
 impl GasStats {
-    fn new(address: [u8; 20], block: u64, gas: u64) -> Self {
+    fn new(
+        address: [u8; 20],
+        block: u64,
+        gas: u64,
+        selector: Option<String>,
+        bucket_idx: usize,
+        top_level: bool,
+        reverted: bool,
+    ) -> Self {
+        let mut selectors = HashMap::new();
+        if top_level {
+            if let Some(selector) = selector {
+                selectors.insert(selector, 1);
+            }
+        }
+        let mut gas_buckets = vec![0u64; bucket_idx + 1];
+        gas_buckets[bucket_idx] = gas;
         Self {
             address,
             total_gas: gas,
-            tx_count: 1,
+            tx_count: if top_level { 1 } else { 0 },
             first_seen_block: block,
             last_seen_block: block,
+            selectors,
+            gas_buckets,
+            top_level_gas: if top_level { gas } else { 0 },
+            internal_gas: if top_level { 0 } else { gas },
+            reverted_tx_count: if top_level && reverted { 1 } else { 0 },
         }
     }
 
-    fn add_tx(&mut self, block: u64, gas: u64) {
+    /// Accumulate one frame's gas. `top_level` distinguishes a transaction's
+    /// own top-level call (counted in `tx_count`/`selectors`, as before)
+    /// from an internal sub-call frame attributed via the call tracer (which
+    /// only updates the gas totals -- `tx_count` stays a transaction count,
+    /// not a frame count). `reverted` is only meaningful when `top_level`.
+    fn add_tx(&mut self, block: u64, gas: u64, selector: Option<String>, bucket_idx: usize, top_level: bool, reverted: bool) {
         self.total_gas = self.total_gas.saturating_add(gas);
-        self.tx_count += 1;
         self.last_seen_block = self.last_seen_block.max(block);
         self.first_seen_block = self.first_seen_block.min(block);
+        if top_level {
+            self.top_level_gas = self.top_level_gas.saturating_add(gas);
+            self.tx_count += 1;
+            if reverted {
+                self.reverted_tx_count += 1;
+            }
+            if let Some(selector) = selector {
+                *self.selectors.entry(selector).or_insert(0) += 1;
+            }
+        } else {
+            self.internal_gas = self.internal_gas.saturating_add(gas);
+        }
+        if self.gas_buckets.len() <= bucket_idx {
+            self.gas_buckets.resize(bucket_idx + 1, 0);
+        }
+        self.gas_buckets[bucket_idx] = self.gas_buckets[bucket_idx].saturating_add(gas);
+    }
+
+    /// Fold another `GasStats` for the same address into `self` -- used to
+    /// merge one committed [`BackfillCheckpoint`] chunk's contribution into
+    /// the checkpoint's running accumulator.
+    fn merge(&mut self, other: &GasStats) {
+        self.total_gas = self.total_gas.saturating_add(other.total_gas);
+        self.top_level_gas = self.top_level_gas.saturating_add(other.top_level_gas);
+        self.internal_gas = self.internal_gas.saturating_add(other.internal_gas);
+        self.tx_count += other.tx_count;
+        self.reverted_tx_count += other.reverted_tx_count;
+        self.first_seen_block = self.first_seen_block.min(other.first_seen_block);
+        self.last_seen_block = self.last_seen_block.max(other.last_seen_block);
+        for (selector, count) in &other.selectors {
+            *self.selectors.entry(selector.clone()).or_insert(0) += count;
+        }
+        if self.gas_buckets.len() < other.gas_buckets.len() {
+            self.gas_buckets.resize(other.gas_buckets.len(), 0);
+        }
+        for (idx, gas) in other.gas_buckets.iter().enumerate() {
+            self.gas_buckets[idx] = self.gas_buckets[idx].saturating_add(*gas);
+        }
     }
 }
 
+/// Which gas figure [`GasTracker::process_block`] attributes per
+/// transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GasSource {
+    /// `tx.gas_limit()` -- free (no extra RPC call), but the cap the sender
+    /// set, not what was actually burned; over-refunded and reverted
+    /// transactions inflate it.
+    #[default]
+    GasLimit,
+    /// The receipt's actual `gasUsed`, fetched via `eth_getBlockReceipts`
+    /// (falling back to one `eth_getTransactionReceipt` per transaction if
+    /// the node doesn't support the batch call). Costs an extra RPC round
+    /// trip per block but isn't skewed by gas refunds or reverts.
+    GasUsed,
+}
+
 /// Configuration for gas backfill
 #[derive(Debug, Clone)]
 pub struct BackfillConfig {
@@ -53,6 +166,20 @@ pub struct BackfillConfig {
     pub block_count: u64,
     pub batch_size: usize,
     pub concurrency: usize,
+    /// Width in blocks of each [`GasStats::gas_buckets`] recency bucket.
+    pub bucket_block_span: u64,
+    /// Attribute gas via `debug_traceBlockByNumber`'s `callTracer` instead
+    /// of top-level transactions, walking the full call tree so a
+    /// proxy/router that mostly delegates work is credited for what its
+    /// delegatecall targets actually spend. Requires an archive node with
+    /// the `debug` namespace enabled; off by default since plain
+    /// `eth_getBlockByNumber` is cheaper and sufficient when only
+    /// top-level attribution is needed.
+    pub use_call_tracer: bool,
+    /// Which gas figure to record per top-level transaction. Has no effect
+    /// when `use_call_tracer` is set, since the tracer already reports each
+    /// frame's real attributed gas rather than a sender-set limit.
+    pub gas_source: GasSource,
 }
 
 impl Default for BackfillConfig {
@@ -62,6 +189,9 @@ impl Default for BackfillConfig {
             block_count: 100_000,
             batch_size: 100,
             concurrency: 10,
+            bucket_block_span: 5_000,
+            use_call_tracer: false,
+            gas_source: GasSource::GasLimit,
         }
     }
 }
@@ -75,9 +205,27 @@ pub struct BackfillResult {
     pub total_transactions: u64,
     pub unique_contracts: usize,
     pub gas_stats: Vec<GasStats>,
+    /// [`crate::extractor::ContractExtractor`] LRU cache hits accumulated
+    /// while this backfill's contracts were looked up/updated, if the
+    /// extractor's cache was wired into this run (see
+    /// [`BackfillResult::with_cache_stats`]). Zero if it wasn't.
+    #[serde(default)]
+    pub cache_hits: u64,
+    /// Same as `cache_hits`, but misses.
+    #[serde(default)]
+    pub cache_misses: u64,
 }
 
 impl BackfillResult {
+    /// Attach a [`crate::extractor::CacheStats`] snapshot so operators
+    /// running `lane-backfill` over 100k blocks can see the metadata
+    /// cache's effectiveness in the same report.
+    pub fn with_cache_stats(mut self, stats: crate::extractor::CacheStats) -> Self {
+        self.cache_hits = stats.hits;
+        self.cache_misses = stats.misses;
+        self
+    }
+
     pub fn save(&self, path: &Path) -> anyhow::Result<()> {
         let json = serde_json::to_string_pretty(self)?;
         std::fs::write(path, json)?;
@@ -90,6 +238,17 @@ impl BackfillResult {
         Ok(result)
     }
 
+    /// Finalize a [`BackfillCheckpoint`] file written by a
+    /// [`GasTracker::resume`] run into a `BackfillResult`, whether or not
+    /// that run ever finished -- every chunk it committed before being
+    /// interrupted is included, anything still pending or blacklisted is
+    /// simply absent from the total.
+    pub fn from_checkpoint(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let checkpoint: BackfillCheckpoint = serde_json::from_str(&content)?;
+        Ok(checkpoint.into_result())
+    }
+
     pub fn top_contracts(&self, n: usize) -> Vec<&GasStats> {
         let mut sorted: Vec<_> = self.gas_stats.iter().collect();
         sorted.sort_by(|a, b| b.total_gas.cmp(&a.total_gas));
@@ -97,11 +256,126 @@ impl BackfillResult {
     }
 }
 
+/// A block that has failed more than [`BackfillCheckpoint::MAX_BLOCK_RETRIES`]
+/// times is skipped for the rest of the backfill rather than stalling its
+/// chunk's commit forever.
+const MAX_BLOCK_RETRIES: u32 = 5;
+
+/// On-disk progress for a resumable [`GasTracker::backfill`] run.
+///
+/// Mirrors [`crate::cold_snapshot::ColdShardBlacklist`]'s discipline of
+/// "only mark a unit done once it's fully imported, persistently blacklist
+/// ones that keep failing" -- here a unit is one `batch_size` chunk of
+/// blocks instead of a cold-lane shard. Unrelated to
+/// [`crate::checkpoint::CheckpointClient`], which resolves beacon-chain
+/// finality checkpoints, not backfill progress.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BackfillCheckpoint {
+    start_block: u64,
+    end_block: u64,
+    batch_size: usize,
+    /// Starting block number of every chunk committed so far
+    committed_chunks: HashSet<u64>,
+    /// Gas folded in from every committed chunk so far, in the same shape
+    /// [`BackfillResult::gas_stats`] uses so a checkpoint finalizes into a
+    /// result without reshaping anything.
+    gas_stats: Vec<GasStats>,
+    total_transactions: u64,
+    /// Failure count per block that has failed to process at least once
+    block_retry_counts: HashMap<u64, u32>,
+    /// Blocks that hit `MAX_BLOCK_RETRIES` and are now skipped outright
+    blacklisted_blocks: HashSet<u64>,
+}
+
+impl BackfillCheckpoint {
+    fn new(start_block: u64, end_block: u64, batch_size: usize) -> Self {
+        Self {
+            start_block,
+            end_block,
+            batch_size,
+            ..Default::default()
+        }
+    }
+
+    /// Load the checkpoint at `path`, or start a fresh one covering
+    /// `[start_block, end_block]` if it's missing or describes a different
+    /// range/batch size (a resume only makes sense against the same run).
+    fn load_or_new(path: &Path, start_block: u64, end_block: u64, batch_size: usize) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::new(start_block, end_block, batch_size));
+        }
+        let content = std::fs::read_to_string(path)?;
+        let checkpoint: Self = serde_json::from_str(&content)?;
+        if checkpoint.start_block != start_block || checkpoint.end_block != end_block || checkpoint.batch_size != batch_size {
+            warn!("Checkpoint range/batch size doesn't match this run, starting fresh");
+            return Ok(Self::new(start_block, end_block, batch_size));
+        }
+        Ok(checkpoint)
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    fn is_chunk_committed(&self, chunk_start: u64) -> bool {
+        self.committed_chunks.contains(&chunk_start)
+    }
+
+    fn is_blacklisted(&self, block_num: u64) -> bool {
+        self.blacklisted_blocks.contains(&block_num)
+    }
+
+    /// Record a failed attempt at `block_num`. Returns `true` if this push
+    /// it past `MAX_BLOCK_RETRIES` and blacklisted it.
+    fn record_block_failure(&mut self, block_num: u64) -> bool {
+        let count = self.block_retry_counts.entry(block_num).or_insert(0);
+        *count += 1;
+        if *count > MAX_BLOCK_RETRIES {
+            self.blacklisted_blocks.insert(block_num);
+            return true;
+        }
+        false
+    }
+
+    /// Fold one chunk's gas and transaction count into the accumulator and
+    /// mark it committed. Only call once every block in the chunk has
+    /// either processed successfully or been blacklisted.
+    fn commit_chunk(&mut self, chunk_start: u64, chunk_gas: HashMap<[u8; 20], GasStats>, chunk_txs: u64) {
+        for (address, incoming) in chunk_gas {
+            match self.gas_stats.iter_mut().find(|s| s.address == address) {
+                Some(existing) => existing.merge(&incoming),
+                None => self.gas_stats.push(incoming),
+            }
+        }
+        self.total_transactions += chunk_txs;
+        self.committed_chunks.insert(chunk_start);
+    }
+
+    fn into_result(self) -> BackfillResult {
+        BackfillResult {
+            start_block: self.start_block,
+            end_block: self.end_block,
+            blocks_processed: self.end_block - self.start_block + 1,
+            total_transactions: self.total_transactions,
+            unique_contracts: self.gas_stats.len(),
+            gas_stats: self.gas_stats,
+            cache_hits: 0,
+            cache_misses: 0,
+        }
+    }
+}
+
 /// Gas tracker for backfilling historical gas usage
 pub struct GasTracker {
     rpc_url: String,
     gas_by_contract: Arc<Mutex<HashMap<[u8; 20], GasStats>>>,
     config: BackfillConfig,
+    /// When set, `backfill` checkpoints committed chunks to this path
+    /// instead of running as one all-or-nothing pass. See
+    /// [`GasTracker::resume`].
+    checkpoint_path: Option<PathBuf>,
 }
 
 impl GasTracker {
@@ -110,14 +384,32 @@ impl GasTracker {
             rpc_url: config.rpc_url.clone(),
             gas_by_contract: Arc::new(Mutex::new(HashMap::new())),
             config,
+            checkpoint_path: None,
         })
     }
 
+    /// Like [`GasTracker::new`], but `backfill` persists progress to
+    /// `checkpoint_path` after every committed chunk and resumes from it if
+    /// it already exists -- interrupting a 100k-block run no longer throws
+    /// away everything it already fetched.
+    pub async fn resume(config: BackfillConfig, checkpoint_path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let mut tracker = Self::new(config).await?;
+        tracker.checkpoint_path = Some(checkpoint_path.into());
+        Ok(tracker)
+    }
+
     pub async fn backfill(&self) -> anyhow::Result<BackfillResult> {
+        match &self.checkpoint_path {
+            Some(path) => self.backfill_checkpointed(path).await,
+            None => self.backfill_uncheckpointed().await,
+        }
+    }
+
+    async fn backfill_uncheckpointed(&self) -> anyhow::Result<BackfillResult> {
         let provider = ProviderBuilder::new()
             .connect(&self.rpc_url)
             .await?;
-        
+
         let latest = provider.get_block_number().await?;
         let start_block = latest.saturating_sub(self.config.block_count);
         let end_block = latest;
@@ -146,6 +438,9 @@ impl GasTracker {
 
         let rpc_url = self.rpc_url.clone();
         let gas_map = self.gas_by_contract.clone();
+        let bucket_block_span = self.config.bucket_block_span.max(1);
+        let use_call_tracer = self.config.use_call_tracer;
+        let gas_source = self.config.gas_source;
 
         stream::iter(chunks)
             .for_each_concurrent(self.config.concurrency, |batch| {
@@ -155,17 +450,56 @@ impl GasTracker {
                 let pb = pb.clone();
 
                 async move {
-                    let Ok(provider) = ProviderBuilder::new().connect(&rpc_url).await else {
-                        warn!("Failed to connect to RPC");
-                        return;
-                    };
-                    
-                    for block_num in batch {
-                        if let Err(e) = Self::process_block(&provider, &gas_map, &total_txs, block_num).await {
-                            warn!(block = block_num, error = %e, "Failed to process block");
+                    // Accumulate the whole batch into a worker-local map and
+                    // counter, then take the shared locks once at the end --
+                    // locking `gas_map`/`total_txs` per transaction would
+                    // serialize every concurrent worker on the hot loop.
+                    let local_gas = Arc::new(Mutex::new(HashMap::new()));
+                    let local_txs = Arc::new(Mutex::new(0u64));
+
+                    if use_call_tracer {
+                        let http = reqwest::Client::new();
+                        for block_num in batch {
+                            if let Err(e) = Self::process_block_traced(
+                                &http,
+                                &rpc_url,
+                                &local_gas,
+                                &local_txs,
+                                block_num,
+                                end_block,
+                                bucket_block_span,
+                            )
+                            .await
+                            {
+                                warn!(block = block_num, error = %e, "Failed to trace block");
+                            }
+                            pb.inc(1);
+                        }
+                    } else {
+                        let Ok(provider) = ProviderBuilder::new().connect(&rpc_url).await else {
+                            warn!("Failed to connect to RPC");
+                            return;
+                        };
+
+                        for block_num in batch {
+                            if let Err(e) = Self::process_block(
+                                &provider,
+                                &local_gas,
+                                &local_txs,
+                                block_num,
+                                end_block,
+                                bucket_block_span,
+                                gas_source,
+                            )
+                            .await
+                            {
+                                warn!(block = block_num, error = %e, "Failed to process block");
+                            }
+                            pb.inc(1);
                         }
-                        pb.inc(1);
                     }
+
+                    Self::merge_local(&gas_map, &total_txs, local_gas, local_txs).await;
                 }
             })
             .await;
@@ -186,6 +520,8 @@ impl GasTracker {
             total_transactions,
             unique_contracts: gas_stats.len(),
             gas_stats,
+            cache_hits: 0,
+            cache_misses: 0,
         };
 
         info!(
@@ -198,12 +534,225 @@ impl GasTracker {
         Ok(result)
     }
 
+    /// Chunk-at-a-time version of [`GasTracker::backfill_uncheckpointed`]
+    /// that commits each chunk's gas to `checkpoint_path` as soon as every
+    /// block in it has processed (or been blacklisted after too many
+    /// failures), so a killed run resumes from its last committed chunk
+    /// instead of starting over.
+    async fn backfill_checkpointed(&self, checkpoint_path: &Path) -> anyhow::Result<BackfillResult> {
+        let provider = ProviderBuilder::new().connect(&self.rpc_url).await?;
+
+        let latest = provider.get_block_number().await?;
+        let start_block = latest.saturating_sub(self.config.block_count);
+        let end_block = latest;
+
+        let checkpoint = Arc::new(Mutex::new(BackfillCheckpoint::load_or_new(
+            checkpoint_path,
+            start_block,
+            end_block,
+            self.config.batch_size,
+        )?));
+
+        info!(
+            start = start_block,
+            end = end_block,
+            checkpoint = %checkpoint_path.display(),
+            "Starting checkpointed gas backfill"
+        );
+
+        let block_numbers: Vec<u64> = (start_block..=end_block).collect();
+        let chunks: Vec<Vec<u64>> = block_numbers
+            .chunks(self.config.batch_size)
+            .map(|c| c.to_vec())
+            .collect();
+
+        let pb = ProgressBar::new(chunks.len() as u64);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} chunks ({eta})")?
+                .progress_chars("#>-"),
+        );
+
+        let rpc_url = self.rpc_url.clone();
+        let bucket_block_span = self.config.bucket_block_span.max(1);
+        let use_call_tracer = self.config.use_call_tracer;
+        let gas_source = self.config.gas_source;
+
+        stream::iter(chunks)
+            .for_each_concurrent(self.config.concurrency, |batch| {
+                let rpc_url = rpc_url.clone();
+                let checkpoint = checkpoint.clone();
+                let pb = pb.clone();
+
+                async move {
+                    let Some(&chunk_start) = batch.first() else {
+                        return;
+                    };
+
+                    if checkpoint.lock().await.is_chunk_committed(chunk_start) {
+                        pb.inc(1);
+                        return;
+                    }
+
+                    let outcome = if use_call_tracer {
+                        let http = reqwest::Client::new();
+                        Self::process_chunk_traced(&http, &rpc_url, &checkpoint, &batch, end_block, bucket_block_span).await
+                    } else {
+                        match ProviderBuilder::new().connect(&rpc_url).await {
+                            Ok(provider) => {
+                                Self::process_chunk(&provider, &checkpoint, &batch, end_block, bucket_block_span, gas_source)
+                                    .await
+                            }
+                            Err(e) => Err(e),
+                        }
+                    };
+
+                    match outcome {
+                        Ok((chunk_gas, chunk_txs)) => {
+                            let mut cp = checkpoint.lock().await;
+                            cp.commit_chunk(chunk_start, chunk_gas, chunk_txs);
+                            if let Err(e) = cp.save(checkpoint_path) {
+                                warn!(error = %e, "Failed to persist backfill checkpoint");
+                            }
+                        }
+                        Err(e) => {
+                            warn!(chunk_start, error = %e, "Chunk failed, will retry on next run");
+                        }
+                    }
+                    pb.inc(1);
+                }
+            })
+            .await;
+
+        pb.finish_with_message("Checkpointed backfill complete");
+
+        let result = checkpoint.lock().await.clone().into_result();
+
+        info!(
+            blocks = result.blocks_processed,
+            transactions = result.total_transactions,
+            contracts = result.unique_contracts,
+            "Checkpointed backfill complete"
+        );
+
+        Ok(result)
+    }
+
+    /// Fold a worker-local batch accumulator into the shared `gas_map`/
+    /// `total_txs`, taking each shared lock exactly once regardless of how
+    /// many transactions the batch contained.
+    async fn merge_local(
+        gas_map: &Arc<Mutex<HashMap<[u8; 20], GasStats>>>,
+        total_txs: &Arc<Mutex<u64>>,
+        local_gas: Arc<Mutex<HashMap<[u8; 20], GasStats>>>,
+        local_txs: Arc<Mutex<u64>>,
+    ) {
+        let local_gas = local_gas.lock().await.clone();
+        let local_txs = *local_txs.lock().await;
+
+        let mut map = gas_map.lock().await;
+        for (addr, stats) in local_gas {
+            map.entry(addr)
+                .and_modify(|existing| existing.merge(&stats))
+                .or_insert(stats);
+        }
+        drop(map);
+
+        *total_txs.lock().await += local_txs;
+    }
+
+    /// Process every block in `batch` into a fresh, chunk-local accumulator.
+    /// Aborts (leaving the chunk uncommitted for the next run to retry) the
+    /// first time a block fails and hasn't yet hit `MAX_BLOCK_RETRIES` --
+    /// once a block does hit that limit it's blacklisted and skipped
+    /// instead, so one permanently-bad block can't stop this chunk from
+    /// ever committing.
+    async fn process_chunk<P: Provider>(
+        provider: &P,
+        checkpoint: &Arc<Mutex<BackfillCheckpoint>>,
+        batch: &[u64],
+        end_block: u64,
+        bucket_block_span: u64,
+        gas_source: GasSource,
+    ) -> anyhow::Result<(HashMap<[u8; 20], GasStats>, u64)> {
+        let chunk_gas = Arc::new(Mutex::new(HashMap::new()));
+        let chunk_txs = Arc::new(Mutex::new(0u64));
+
+        for &block_num in batch {
+            if checkpoint.lock().await.is_blacklisted(block_num) {
+                warn!(block = block_num, "Skipping blacklisted block");
+                continue;
+            }
+
+            if let Err(e) = Self::process_block(
+                provider,
+                &chunk_gas,
+                &chunk_txs,
+                block_num,
+                end_block,
+                bucket_block_span,
+                gas_source,
+            )
+            .await
+            {
+                if checkpoint.lock().await.record_block_failure(block_num) {
+                    warn!(block = block_num, "Block exceeded retry limit, blacklisting and skipping");
+                    continue;
+                }
+                return Err(e);
+            }
+        }
+
+        let gas = chunk_gas.lock().await.clone();
+        let txs = *chunk_txs.lock().await;
+        Ok((gas, txs))
+    }
+
+    /// Traced counterpart to [`GasTracker::process_chunk`], using
+    /// [`GasTracker::process_block_traced`] per block.
+    async fn process_chunk_traced(
+        http: &reqwest::Client,
+        rpc_url: &str,
+        checkpoint: &Arc<Mutex<BackfillCheckpoint>>,
+        batch: &[u64],
+        end_block: u64,
+        bucket_block_span: u64,
+    ) -> anyhow::Result<(HashMap<[u8; 20], GasStats>, u64)> {
+        let chunk_gas = Arc::new(Mutex::new(HashMap::new()));
+        let chunk_txs = Arc::new(Mutex::new(0u64));
+
+        for &block_num in batch {
+            if checkpoint.lock().await.is_blacklisted(block_num) {
+                warn!(block = block_num, "Skipping blacklisted block");
+                continue;
+            }
+
+            if let Err(e) =
+                Self::process_block_traced(http, rpc_url, &chunk_gas, &chunk_txs, block_num, end_block, bucket_block_span).await
+            {
+                if checkpoint.lock().await.record_block_failure(block_num) {
+                    warn!(block = block_num, "Block exceeded retry limit, blacklisting and skipping");
+                    continue;
+                }
+                return Err(e);
+            }
+        }
+
+        let gas = chunk_gas.lock().await.clone();
+        let txs = *chunk_txs.lock().await;
+        Ok((gas, txs))
+    }
+
     async fn process_block<P: Provider>(
         provider: &P,
         gas_map: &Arc<Mutex<HashMap<[u8; 20], GasStats>>>,
         total_txs: &Arc<Mutex<u64>>,
         block_num: u64,
+        end_block: u64,
+        bucket_block_span: u64,
+        gas_source: GasSource,
     ) -> anyhow::Result<()> {
+        let bucket_idx = (end_block.saturating_sub(block_num) / bucket_block_span) as usize;
         let block = provider
             .get_block_by_number(BlockNumberOrTag::Number(block_num))
             .full()
@@ -213,20 +762,33 @@ impl GasTracker {
             return Ok(());
         };
 
-        let txs = block.transactions.into_transactions();
+        let txs: Vec<_> = block.transactions.into_transactions().collect();
 
-        for tx in txs {
+        // (gas_used, reverted) per transaction, by index -- only fetched
+        // when `gas_source` is `GasUsed`, since `GasLimit` needs no receipt.
+        let receipts: Vec<Option<(u64, bool)>> = if gas_source == GasSource::GasUsed {
+            Self::fetch_receipts(provider, block_num, &txs).await?
+        } else {
+            Vec::new()
+        };
+
+        for (idx, tx) in txs.into_iter().enumerate() {
             let Some(to) = tx.to() else {
                 continue;
             };
 
-            let gas_used = tx.gas_limit();
+            let (gas_used, reverted) = match receipts.get(idx).copied().flatten() {
+                Some((gas_used, success)) => (gas_used, !success),
+                None => (tx.gas_limit(), false),
+            };
             let to_bytes: [u8; 20] = to.0.into();
+            let input = tx.input();
+            let selector = (input.len() >= 4).then(|| format!("0x{}", hex::encode(&input[..4])));
 
             let mut map = gas_map.lock().await;
             map.entry(to_bytes)
-                .and_modify(|stats| stats.add_tx(block_num, gas_used))
-                .or_insert_with(|| GasStats::new(to_bytes, block_num, gas_used));
+                .and_modify(|stats| stats.add_tx(block_num, gas_used, selector.clone(), bucket_idx, true, reverted))
+                .or_insert_with(|| GasStats::new(to_bytes, block_num, gas_used, selector, bucket_idx, true, reverted));
 
             let mut count = total_txs.lock().await;
             *count += 1;
@@ -234,6 +796,145 @@ impl GasTracker {
 
         Ok(())
     }
+
+    /// Fetch `(gasUsed, success)` per transaction in `txs` via one
+    /// `eth_getBlockReceipts` call, falling back to one
+    /// `eth_getTransactionReceipt` call per transaction if the node doesn't
+    /// support the batch RPC. Returned in the same order as `txs`; an entry
+    /// is `None` if no receipt could be found for that transaction.
+    async fn fetch_receipts<P: Provider, T: TransactionTrait>(
+        provider: &P,
+        block_num: u64,
+        txs: &[T],
+    ) -> anyhow::Result<Vec<Option<(u64, bool)>>> {
+        if let Ok(Some(receipts)) = provider
+            .get_block_receipts(BlockId::Number(BlockNumberOrTag::Number(block_num)))
+            .await
+        {
+            return Ok(receipts.into_iter().map(|r| Some((r.gas_used, r.status()))).collect());
+        }
+
+        let mut out = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let receipt = provider.get_transaction_receipt(tx.tx_hash()).await?;
+            out.push(receipt.map(|r| (r.gas_used, r.status())));
+        }
+        Ok(out)
+    }
+
+    /// Trace `block_num` with `debug_traceBlockByNumber`'s `callTracer` and
+    /// attribute every frame in the call tree, not just each transaction's
+    /// top-level `to`. See [`record_call_frame`] for the per-frame rules.
+    async fn process_block_traced(
+        http: &reqwest::Client,
+        rpc_url: &str,
+        gas_map: &Arc<Mutex<HashMap<[u8; 20], GasStats>>>,
+        total_txs: &Arc<Mutex<u64>>,
+        block_num: u64,
+        end_block: u64,
+        bucket_block_span: u64,
+    ) -> anyhow::Result<()> {
+        let bucket_idx = (end_block.saturating_sub(block_num) / bucket_block_span) as usize;
+        let trace = fetch_call_trace(http, rpc_url, block_num).await?;
+
+        let Some(tx_traces) = trace.as_array() else {
+            return Ok(());
+        };
+
+        let mut map = gas_map.lock().await;
+        for tx_trace in tx_traces {
+            let Some(root_frame) = tx_trace.get("result") else {
+                continue;
+            };
+            record_call_frame(&mut map, root_frame, block_num, bucket_idx, true);
+        }
+        drop(map);
+
+        let mut count = total_txs.lock().await;
+        *count += tx_traces.len() as u64;
+
+        Ok(())
+    }
+}
+
+/// Fetch `debug_traceBlockByNumber(block_num, {"tracer": "callTracer"})` as
+/// raw JSON, the same hand-rolled way `inspire-server`'s `UbtRootClient`
+/// calls `ubt_getRoot` -- this method isn't part of alloy's typed provider
+/// surface, and the response shape only needs a handful of fields read
+/// back out of it.
+async fn fetch_call_trace(http: &reqwest::Client, rpc_url: &str, block_num: u64) -> anyhow::Result<serde_json::Value> {
+    let request_body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "debug_traceBlockByNumber",
+        "params": [format!("0x{block_num:x}"), { "tracer": "callTracer" }],
+        "id": 1,
+    });
+
+    let response: serde_json::Value = http.post(rpc_url).json(&request_body).send().await?.json().await?;
+
+    if let Some(error) = response.get("error") {
+        anyhow::bail!("debug_traceBlockByNumber RPC error: {error}");
+    }
+
+    response
+        .get("result")
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("debug_traceBlockByNumber response missing result"))
+}
+
+/// Recursively attribute one `callTracer` frame (and its nested `calls`)
+/// into `gas_map`.
+///
+/// `top_level` is true only for the frame at depth 0 -- the transaction's
+/// own top-level call -- so [`GasStats::tx_count`]/`selectors` still reflect
+/// transaction counts, not frame counts, while `total_gas`/`gas_buckets`
+/// pick up every frame's contribution. DELEGATECALL frames need no special
+/// case: `callTracer` already reports a delegatecall frame's `to` as the
+/// code address being executed (not the caller's storage context), which is
+/// exactly who should be credited. Reverted subframes (an `error` field
+/// present) and CREATE*/SELFDESTRUCT frames are attributed like any other
+/// frame, by the gas the tracer reported for them -- a revert doesn't
+/// refund the gas the EVM already spent getting there.
+fn record_call_frame(
+    map: &mut HashMap<[u8; 20], GasStats>,
+    frame: &serde_json::Value,
+    block_num: u64,
+    bucket_idx: usize,
+    top_level: bool,
+) {
+    if let (Some(to), Some(gas_used)) = (
+        frame.get("to").and_then(|v| v.as_str()),
+        frame.get("gasUsed").and_then(|v| v.as_str()),
+    ) {
+        if let (Ok(to_bytes), Ok(gas_used)) = (parse_hex_address(to), parse_hex_u64(gas_used)) {
+            let selector = frame
+                .get("input")
+                .and_then(|v| v.as_str())
+                .filter(|input| input.len() >= 10)
+                .map(|input| format!("0x{}", &input[2..10]));
+
+            map.entry(to_bytes)
+                .and_modify(|stats| stats.add_tx(block_num, gas_used, selector.clone(), bucket_idx, top_level, false))
+                .or_insert_with(|| GasStats::new(to_bytes, block_num, gas_used, selector, bucket_idx, top_level, false));
+        }
+    }
+
+    if let Some(calls) = frame.get("calls").and_then(|v| v.as_array()) {
+        for call in calls {
+            record_call_frame(map, call, block_num, bucket_idx, false);
+        }
+    }
+}
+
+fn parse_hex_address(s: &str) -> anyhow::Result<[u8; 20]> {
+    let bytes = hex::decode(s.trim_start_matches("0x"))?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| anyhow::anyhow!("expected 20-byte address, got {} bytes", bytes.len()))
+}
+
+fn parse_hex_u64(s: &str) -> anyhow::Result<u64> {
+    Ok(u64::from_str_radix(s.trim_start_matches("0x"), 16)?)
 }
 
 #[cfg(test)]
@@ -243,15 +944,65 @@ mod tests {
     #[test]
     fn test_gas_stats_accumulation() {
         let addr = [0x11u8; 20];
-        let mut stats = GasStats::new(addr, 1000, 50000);
+        let mut stats = GasStats::new(addr, 1000, 50000, Some("0xa9059cbb".to_string()), 0, true, false);
 
-        stats.add_tx(1001, 30000);
-        stats.add_tx(1002, 20000);
+        stats.add_tx(1001, 30000, Some("0xa9059cbb".to_string()), 0, true, false);
+        stats.add_tx(1002, 20000, None, 1, true, true);
 
         assert_eq!(stats.total_gas, 100000);
         assert_eq!(stats.tx_count, 3);
         assert_eq!(stats.first_seen_block, 1000);
         assert_eq!(stats.last_seen_block, 1002);
+        assert_eq!(stats.selectors.get("0xa9059cbb"), Some(&2));
+        assert_eq!(stats.gas_buckets, vec![80000, 20000]);
+        assert_eq!(stats.top_level_gas, 100000);
+        assert_eq!(stats.internal_gas, 0);
+        assert_eq!(stats.reverted_tx_count, 1);
+    }
+
+    #[test]
+    fn test_gas_stats_separates_top_level_and_internal_gas() {
+        let addr = [0x11u8; 20];
+        let mut stats = GasStats::new(addr, 1000, 50000, Some("0xa9059cbb".to_string()), 0, true, false);
+        stats.add_tx(1000, 20000, Some("0x12345678".to_string()), 0, false, false);
+
+        assert_eq!(stats.total_gas, 70000);
+        assert_eq!(stats.top_level_gas, 50000);
+        assert_eq!(stats.internal_gas, 20000);
+        assert_eq!(stats.tx_count, 1);
+        // internal frames don't contribute to the per-tx selector tally
+        assert_eq!(stats.selectors.get("0x12345678"), None);
+    }
+
+    #[test]
+    fn test_gas_stats_merge_matches_sequential_accumulation() {
+        let addr = [0x22u8; 20];
+
+        // Sequential: every tx folded into one accumulator in block order.
+        let mut sequential = GasStats::new(addr, 100, 10000, Some("0xa9059cbb".to_string()), 0, true, false);
+        sequential.add_tx(105, 20000, Some("0xa9059cbb".to_string()), 0, true, false);
+        sequential.add_tx(110, 15000, None, 1, true, true);
+        sequential.add_tx(120, 5000, Some("0x12345678".to_string()), 1, false, false);
+
+        // Batched: the same four txs split across two worker-local
+        // accumulators, then merged once -- mirrors `merge_local`.
+        let mut batch_a = GasStats::new(addr, 100, 10000, Some("0xa9059cbb".to_string()), 0, true, false);
+        batch_a.add_tx(105, 20000, Some("0xa9059cbb".to_string()), 0, true, false);
+
+        let mut batch_b = GasStats::new(addr, 110, 15000, None, 1, true, true);
+        batch_b.add_tx(120, 5000, Some("0x12345678".to_string()), 1, false, false);
+
+        batch_a.merge(&batch_b);
+
+        assert_eq!(batch_a.total_gas, sequential.total_gas);
+        assert_eq!(batch_a.top_level_gas, sequential.top_level_gas);
+        assert_eq!(batch_a.internal_gas, sequential.internal_gas);
+        assert_eq!(batch_a.tx_count, sequential.tx_count);
+        assert_eq!(batch_a.reverted_tx_count, sequential.reverted_tx_count);
+        assert_eq!(batch_a.first_seen_block, sequential.first_seen_block);
+        assert_eq!(batch_a.last_seen_block, sequential.last_seen_block);
+        assert_eq!(batch_a.selectors, sequential.selectors);
+        assert_eq!(batch_a.gas_buckets, sequential.gas_buckets);
     }
 
     #[test]
@@ -269,6 +1020,11 @@ mod tests {
                     tx_count: 10,
                     first_seen_block: 0,
                     last_seen_block: 100,
+                    selectors: HashMap::new(),
+                    gas_buckets: vec![1000],
+                    top_level_gas: 1000,
+                    internal_gas: 0,
+                    reverted_tx_count: 0,
                 },
                 GasStats {
                     address: [0x22u8; 20],
@@ -276,6 +1032,11 @@ mod tests {
                     tx_count: 20,
                     first_seen_block: 0,
                     last_seen_block: 100,
+                    selectors: HashMap::new(),
+                    gas_buckets: vec![5000],
+                    top_level_gas: 5000,
+                    internal_gas: 0,
+                    reverted_tx_count: 0,
                 },
                 GasStats {
                     address: [0x33u8; 20],
@@ -283,8 +1044,15 @@ mod tests {
                     tx_count: 15,
                     first_seen_block: 0,
                     last_seen_block: 100,
+                    selectors: HashMap::new(),
+                    gas_buckets: vec![3000],
+                    top_level_gas: 3000,
+                    internal_gas: 0,
+                    reverted_tx_count: 0,
                 },
             ],
+            cache_hits: 0,
+            cache_misses: 0,
         };
 
         let top2 = result.top_contracts(2);
@@ -292,4 +1060,123 @@ mod tests {
         assert_eq!(top2[0].total_gas, 5000);
         assert_eq!(top2[1].total_gas, 3000);
     }
+
+    #[test]
+    fn test_with_cache_stats_attaches_hit_miss_counters() {
+        let result = BackfillResult {
+            start_block: 0,
+            end_block: 1,
+            blocks_processed: 1,
+            total_transactions: 0,
+            unique_contracts: 0,
+            gas_stats: vec![],
+            cache_hits: 0,
+            cache_misses: 0,
+        }
+        .with_cache_stats(crate::extractor::CacheStats {
+            hits: 7,
+            misses: 2,
+            capacity: 100,
+            len: 5,
+        });
+
+        assert_eq!(result.cache_hits, 7);
+        assert_eq!(result.cache_misses, 2);
+    }
+
+    fn sample_stats(addr: [u8; 20], gas: u64, block: u64) -> GasStats {
+        GasStats::new(addr, block, gas, Some("0xa9059cbb".to_string()), 0, true, false)
+    }
+
+    #[test]
+    fn test_checkpoint_commit_chunk_merges_gas_and_marks_committed() {
+        let mut checkpoint = BackfillCheckpoint::new(0, 199, 100);
+        assert!(!checkpoint.is_chunk_committed(0));
+
+        let mut chunk_gas = HashMap::new();
+        chunk_gas.insert([0x11u8; 20], sample_stats([0x11u8; 20], 1000, 10));
+        checkpoint.commit_chunk(0, chunk_gas, 1);
+
+        assert!(checkpoint.is_chunk_committed(0));
+        assert!(!checkpoint.is_chunk_committed(100));
+        assert_eq!(checkpoint.total_transactions, 1);
+        assert_eq!(checkpoint.gas_stats.len(), 1);
+
+        // Committing a second chunk that touches the same address merges
+        // instead of duplicating the entry.
+        let mut chunk_gas = HashMap::new();
+        chunk_gas.insert([0x11u8; 20], sample_stats([0x11u8; 20], 500, 150));
+        checkpoint.commit_chunk(100, chunk_gas, 1);
+
+        assert_eq!(checkpoint.gas_stats.len(), 1);
+        assert_eq!(checkpoint.gas_stats[0].total_gas, 1500);
+        assert_eq!(checkpoint.total_transactions, 2);
+    }
+
+    #[test]
+    fn test_checkpoint_blacklists_block_after_max_retries() {
+        let mut checkpoint = BackfillCheckpoint::new(0, 99, 100);
+
+        for _ in 0..MAX_BLOCK_RETRIES {
+            assert!(!checkpoint.record_block_failure(42));
+        }
+        assert!(!checkpoint.is_blacklisted(42));
+
+        assert!(checkpoint.record_block_failure(42));
+        assert!(checkpoint.is_blacklisted(42));
+    }
+
+    #[test]
+    fn test_checkpoint_save_load_roundtrip_resumes_committed_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.json");
+
+        let mut checkpoint = BackfillCheckpoint::new(0, 199, 100);
+        let mut chunk_gas = HashMap::new();
+        chunk_gas.insert([0x11u8; 20], sample_stats([0x11u8; 20], 1000, 10));
+        checkpoint.commit_chunk(0, chunk_gas, 3);
+        checkpoint.save(&path).unwrap();
+
+        let resumed = BackfillCheckpoint::load_or_new(&path, 0, 199, 100).unwrap();
+        assert!(resumed.is_chunk_committed(0));
+        assert!(!resumed.is_chunk_committed(100));
+        assert_eq!(resumed.total_transactions, 3);
+    }
+
+    #[test]
+    fn test_checkpoint_load_or_new_discards_mismatched_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.json");
+
+        let mut checkpoint = BackfillCheckpoint::new(0, 199, 100);
+        let mut chunk_gas = HashMap::new();
+        chunk_gas.insert([0x11u8; 20], sample_stats([0x11u8; 20], 1000, 10));
+        checkpoint.commit_chunk(0, chunk_gas, 1);
+        checkpoint.save(&path).unwrap();
+
+        // A later run over a different block range can't trust this
+        // checkpoint's committed chunks, so it starts fresh instead.
+        let fresh = BackfillCheckpoint::load_or_new(&path, 200, 399, 100).unwrap();
+        assert!(!fresh.is_chunk_committed(0));
+        assert_eq!(fresh.total_transactions, 0);
+    }
+
+    #[test]
+    fn test_backfill_result_from_checkpoint_finalizes_partial_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.json");
+
+        let mut checkpoint = BackfillCheckpoint::new(0, 199, 100);
+        let mut chunk_gas = HashMap::new();
+        chunk_gas.insert([0x11u8; 20], sample_stats([0x11u8; 20], 1000, 10));
+        checkpoint.commit_chunk(0, chunk_gas, 2);
+        checkpoint.save(&path).unwrap();
+
+        // Chunk starting at block 100 was never committed -- the run was
+        // interrupted -- but `from_checkpoint` still finalizes what did land.
+        let result = BackfillResult::from_checkpoint(&path).unwrap();
+        assert_eq!(result.total_transactions, 2);
+        assert_eq!(result.unique_contracts, 1);
+        assert_eq!(result.blocks_processed, 200);
+    }
 }
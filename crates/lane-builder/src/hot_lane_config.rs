@@ -0,0 +1,244 @@
+//! Runtime hot-lane contract config
+//!
+//! [`crate::contracts::HOT_CONTRACTS`] and its per-chain siblings are
+//! compiled in, so adding an L2 restaking protocol or dropping privacy
+//! contracts for compliance means a rebuild. [`HotLaneConfig`] lets an
+//! operator instead supply a JSON document at startup -- a list of
+//! [`ContractInfo`] entries plus optional [`CategoryWeights`] -- the same
+//! way node software loads a genesis/state config file rather than baking
+//! it in. [`merge_hot_lane_config`] combines it with the curated list for
+//! a chain, either layering it on top ([`MergeMode::Extend`]) or replacing
+//! the curated list outright ([`MergeMode::Override`]).
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use inspire_core::Address;
+
+use crate::contracts::{hot_contracts, ContractInfo, KnownContract};
+use crate::hybrid_scorer::CategoryWeights;
+
+/// Categories [`CategoryWeights`] assigns a weight to, plus the fallback
+/// labels used elsewhere in the crate for contracts discovered without a
+/// curated category ([`crate::activity_ranker::ActivityRanker`] tags
+/// freshly-discovered contracts `"unclassified"`).
+const KNOWN_CATEGORIES: &[&str] = &[
+    "privacy",
+    "defi",
+    "bridge",
+    "stablecoin",
+    "token",
+    "lending",
+    "dex",
+    "nft",
+    "governance",
+    "unknown",
+    "unclassified",
+];
+
+/// How a runtime [`HotLaneConfig`] combines with the compiled-in curated
+/// list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeMode {
+    /// Config entries are layered over the curated list: a config entry
+    /// replaces the curated entry at the same address, and addresses the
+    /// config doesn't mention keep their curated entry. The default --
+    /// most operators are adding to the curated list, not replacing it.
+    Extend,
+    /// The config replaces the curated list outright for its chain: only
+    /// addresses the config lists appear. For compliance-driven setups
+    /// (e.g. dropping privacy contracts) where silently falling back to
+    /// a curated entry would defeat the point.
+    Override,
+}
+
+impl Default for MergeMode {
+    fn default() -> Self {
+        MergeMode::Extend
+    }
+}
+
+/// Operator-supplied hot-lane contract config, loaded with
+/// [`load_hot_lane_config`] and applied with [`merge_hot_lane_config`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HotLaneConfig {
+    /// Contracts to add to (or, in [`MergeMode::Override`], replace) the
+    /// curated list. `chain_id` on each entry selects which chain's list
+    /// it applies to, so one file can cover a multi-chain deployment.
+    #[serde(default)]
+    pub contracts: Vec<ContractInfo>,
+    /// Category weight overrides for [`crate::hybrid_scorer::HybridScorer`].
+    /// `None` keeps the compiled-in defaults.
+    #[serde(default)]
+    pub category_weights: Option<CategoryWeights>,
+    #[serde(default)]
+    pub mode: MergeMode,
+}
+
+/// Errors loading or validating a [`HotLaneConfig`].
+#[derive(Debug, thiserror::Error)]
+pub enum HotLaneConfigError {
+    #[error("failed to read contract config file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to parse contract config: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("contract config entry for {address:?} has an unrecognized category {category:?}")]
+    UnknownCategory { address: Address, category: String },
+    #[error("contract config entry has the zero address")]
+    ZeroAddress,
+}
+
+/// Load a [`HotLaneConfig`] from a JSON file, validating every entry's
+/// address and category.
+pub fn load_hot_lane_config(path: &Path) -> Result<HotLaneConfig, HotLaneConfigError> {
+    let content = std::fs::read_to_string(path).map_err(|source| HotLaneConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let config: HotLaneConfig = serde_json::from_str(&content)?;
+    validate_config(&config)?;
+    Ok(config)
+}
+
+fn validate_config(config: &HotLaneConfig) -> Result<(), HotLaneConfigError> {
+    for entry in &config.contracts {
+        if entry.address == [0u8; 20] {
+            return Err(HotLaneConfigError::ZeroAddress);
+        }
+        if !KNOWN_CATEGORIES.contains(&entry.category.as_str()) {
+            return Err(HotLaneConfigError::UnknownCategory {
+                address: entry.address,
+                category: entry.category.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// The curated [`KnownContract`] entry for `chain_id` as a [`ContractInfo`],
+/// with no observed `tx_count`/`storage_slots`/`storage_layout`.
+pub(crate) fn known_to_info(known: &KnownContract) -> ContractInfo {
+    ContractInfo {
+        address: known.address,
+        name: known.name.to_string(),
+        category: known.category.to_string(),
+        chain_id: known.chain_id,
+        tx_count: None,
+        storage_slots: None,
+        storage_layout: None,
+    }
+}
+
+/// Merge `config` over the curated list for `chain_id`, dedup'd by
+/// address. See [`MergeMode`] for how entries present on both sides are
+/// resolved.
+pub fn merge_hot_lane_config(config: &HotLaneConfig, chain_id: u64) -> Vec<ContractInfo> {
+    let config_for_chain = config.contracts.iter().filter(|c| c.chain_id == chain_id);
+
+    if config.mode == MergeMode::Override {
+        return config_for_chain.cloned().collect();
+    }
+
+    let mut by_address: HashMap<Address, ContractInfo> = hot_contracts(chain_id)
+        .iter()
+        .map(|known| (known.address, known_to_info(known)))
+        .collect();
+
+    for entry in config_for_chain {
+        by_address.insert(entry.address, entry.clone());
+    }
+
+    by_address.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::MAINNET_CHAIN_ID;
+
+    fn contract(address: Address, name: &str, category: &str) -> ContractInfo {
+        ContractInfo {
+            address,
+            name: name.to_string(),
+            category: category.to_string(),
+            chain_id: MAINNET_CHAIN_ID,
+            tx_count: None,
+            storage_slots: None,
+            storage_layout: None,
+        }
+    }
+
+    #[test]
+    fn test_extend_adds_without_dropping_curated() {
+        let config = HotLaneConfig {
+            contracts: vec![contract([0x42; 20], "New Router", "dex")],
+            category_weights: None,
+            mode: MergeMode::Extend,
+        };
+
+        let merged = merge_hot_lane_config(&config, MAINNET_CHAIN_ID);
+        assert_eq!(merged.len(), hot_contracts(MAINNET_CHAIN_ID).len() + 1);
+        assert!(merged.iter().any(|c| c.name == "New Router"));
+        assert!(merged.iter().any(|c| c.name == "USDC"));
+    }
+
+    #[test]
+    fn test_extend_overrides_matching_address() {
+        let curated_usdc = hot_contracts(MAINNET_CHAIN_ID)
+            .iter()
+            .find(|c| c.name == "USDC")
+            .unwrap();
+        let config = HotLaneConfig {
+            contracts: vec![contract(curated_usdc.address, "Renamed", "stablecoin")],
+            category_weights: None,
+            mode: MergeMode::Extend,
+        };
+
+        let merged = merge_hot_lane_config(&config, MAINNET_CHAIN_ID);
+        assert_eq!(merged.len(), hot_contracts(MAINNET_CHAIN_ID).len());
+        let entry = merged.iter().find(|c| c.address == curated_usdc.address).unwrap();
+        assert_eq!(entry.name, "Renamed");
+    }
+
+    #[test]
+    fn test_override_drops_curated_entries() {
+        let config = HotLaneConfig {
+            contracts: vec![contract([0x42; 20], "Only Entry", "dex")],
+            category_weights: None,
+            mode: MergeMode::Override,
+        };
+
+        let merged = merge_hot_lane_config(&config, MAINNET_CHAIN_ID);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].name, "Only Entry");
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_category() {
+        let config = HotLaneConfig {
+            contracts: vec![contract([0x42; 20], "Bad", "not-a-real-category")],
+            category_weights: None,
+            mode: MergeMode::Extend,
+        };
+
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_zero_address() {
+        let config = HotLaneConfig {
+            contracts: vec![contract([0u8; 20], "Zero", "dex")],
+            category_weights: None,
+            mode: MergeMode::Extend,
+        };
+
+        assert!(validate_config(&config).is_err());
+    }
+}
@@ -68,6 +68,16 @@ pub struct ScoredContract {
     pub name: Option<String>,
     pub category: Option<String>,
     pub gas_score: u64,
+    /// Recency-decayed gas score actually fed into `final_score`'s
+    /// `(base * category_weight)` formula -- see
+    /// [`HybridScorerConfig::decay_lambda`]. Equal to `gas_score` whenever
+    /// `decay_lambda == 1.0` (the default).
+    #[serde(default)]
+    pub weighted_gas: u64,
+    /// Gas per recency bucket this score was derived from, newest first.
+    /// See [`crate::gas_tracker::GasStats::gas_buckets`].
+    #[serde(default)]
+    pub gas_buckets: Vec<u64>,
     pub priority_boost: u64,
     pub category_weight: f64,
     pub final_score: u64,
@@ -81,6 +91,11 @@ pub enum ContractSource {
     GasBackfill,
     KnownList,
     Both,
+    /// Reconstructed from an on-disk [`inspire_core::HotLaneManifest`] (see
+    /// `crate::serve::ManifestQuery`) rather than produced by [`HybridScorer`]
+    /// -- no gas/category score breakdown is available, only what the
+    /// manifest itself tracks (access count/EWMA).
+    Manifest,
 }
 
 /// Configuration for the hybrid scorer
@@ -89,6 +104,20 @@ pub struct HybridScorerConfig {
     pub category_weights: CategoryWeights,
     pub known_contract_boost: u64,
     pub max_contracts: usize,
+    /// Capacity of the [`crate::ContractExtractor`] LRU metadata cache built
+    /// for this scorer's pipeline (see
+    /// [`crate::ContractExtractor::from_scorer_config`]). Bounds memory
+    /// during a large backfill without capping `max_contracts`, the final
+    /// hot-lane size.
+    pub contract_cache_capacity: usize,
+    /// Exponential decay factor `λ ∈ (0, 1]` applied to
+    /// [`crate::gas_tracker::GasStats::gas_buckets`] when scoring: the
+    /// `i`-th most recent bucket (0-indexed) contributes `g_i * λ^i` to
+    /// `weighted_gas`, so a contract that was hot months ago doesn't
+    /// outrank one that's hot right now. `1.0` (the default) disables
+    /// decay entirely -- every bucket counts equally, reproducing the
+    /// previous flat-lifetime-sum behavior exactly.
+    pub decay_lambda: f64,
 }
 
 impl Default for HybridScorerConfig {
@@ -97,6 +126,8 @@ impl Default for HybridScorerConfig {
             category_weights: CategoryWeights::default(),
             known_contract_boost: 100_000_000_000,
             max_contracts: 1000,
+            contract_cache_capacity: 10_000,
+            decay_lambda: 1.0,
         }
     }
 }
@@ -135,7 +166,8 @@ impl HybridScorer {
                     ContractSource::Both,
                 )
             } else {
-                (None, None, 0, ContractSource::GasBackfill)
+                let category = crate::category_heuristics::infer_category(&stats.selectors);
+                (None, category, 0, ContractSource::GasBackfill)
             };
 
             let category_weight = category
@@ -143,13 +175,16 @@ impl HybridScorer {
                 .map(|c| self.config.category_weights.get(c))
                 .unwrap_or(1.0);
 
-            let final_score = self.calculate_score(stats.total_gas, priority_boost, category_weight);
+            let weighted_gas = decay_weighted_gas(&stats.gas_buckets, self.config.decay_lambda);
+            let final_score = self.calculate_score(weighted_gas, priority_boost, category_weight);
 
             scored.insert(stats.address, ScoredContract {
                 address: stats.address,
                 name,
                 category,
                 gas_score: stats.total_gas,
+                weighted_gas,
+                gas_buckets: stats.gas_buckets.clone(),
                 priority_boost,
                 category_weight,
                 final_score,
@@ -168,6 +203,8 @@ impl HybridScorer {
                     name: Some(kc.name.to_string()),
                     category: Some(kc.category.to_string()),
                     gas_score: 0,
+                    weighted_gas: 0,
+                    gas_buckets: Vec::new(),
                     priority_boost: self.config.known_contract_boost,
                     category_weight,
                     final_score,
@@ -197,6 +234,8 @@ impl HybridScorer {
                     name: Some(kc.name.to_string()),
                     category: Some(kc.category.to_string()),
                     gas_score: 0,
+                    weighted_gas: 0,
+                    gas_buckets: Vec::new(),
                     priority_boost: self.config.known_contract_boost,
                     category_weight,
                     final_score,
@@ -211,8 +250,8 @@ impl HybridScorer {
         result
     }
 
-    fn calculate_score(&self, gas_score: u64, priority_boost: u64, category_weight: f64) -> u64 {
-        let base = gas_score.saturating_add(priority_boost);
+    fn calculate_score(&self, weighted_gas: u64, priority_boost: u64, category_weight: f64) -> u64 {
+        let base = weighted_gas.saturating_add(priority_boost);
         (base as f64 * category_weight) as u64
     }
 
@@ -221,6 +260,17 @@ impl HybridScorer {
     }
 }
 
+/// `Σ g_i * λ^i` over `buckets` (newest first, index 0 = most recent). With
+/// `lambda == 1.0` this is exactly `buckets.iter().sum()`, i.e. `total_gas`,
+/// reproducing the pre-decay flat lifetime sum.
+fn decay_weighted_gas(buckets: &[u64], lambda: f64) -> u64 {
+    buckets
+        .iter()
+        .enumerate()
+        .map(|(i, g)| *g as f64 * lambda.powi(i as i32))
+        .sum::<f64>() as u64
+}
+
 /// Convert scored contracts to ContractInfo for manifest building
 impl ScoredContract {
     pub fn to_contract_info(&self) -> ContractInfo {
@@ -228,8 +278,10 @@ impl ScoredContract {
             address: self.address,
             name: self.name.clone().unwrap_or_else(|| format!("0x{}", hex::encode(&self.address[..6]))),
             category: self.category.clone().unwrap_or_else(|| "unknown".to_string()),
+            chain_id: crate::contracts::MAINNET_CHAIN_ID,
             tx_count: Some(self.tx_count),
             storage_slots: None,
+            storage_layout: None,
         }
     }
 }
@@ -289,4 +341,57 @@ mod tests {
         let known = scorer.known_addresses();
         assert!(known.len() >= 10);
     }
+
+    #[test]
+    fn test_decay_weighted_gas_with_lambda_one_equals_total() {
+        let buckets = vec![100, 200, 300];
+        assert_eq!(decay_weighted_gas(&buckets, 1.0), 600);
+    }
+
+    #[test]
+    fn test_decay_weighted_gas_discounts_older_buckets() {
+        let recent_heavy = vec![1000, 0];
+        let historical_heavy = vec![0, 1000];
+
+        let lambda = 0.5;
+        assert!(decay_weighted_gas(&recent_heavy, lambda) > decay_weighted_gas(&historical_heavy, lambda));
+    }
+
+    #[cfg(feature = "backfill")]
+    #[test]
+    fn test_score_from_backfill_uses_decay_weighted_gas() {
+        use crate::gas_tracker::{BackfillResult, GasStats};
+        use std::collections::HashMap;
+
+        let stats = GasStats {
+            address: [0x55u8; 20],
+            total_gas: 1000,
+            tx_count: 2,
+            first_seen_block: 0,
+            last_seen_block: 100,
+            selectors: HashMap::new(),
+            gas_buckets: vec![0, 1000], // all gas in the oldest bucket
+        };
+        let backfill = BackfillResult {
+            start_block: 0,
+            end_block: 100,
+            blocks_processed: 100,
+            total_transactions: 2,
+            unique_contracts: 1,
+            gas_stats: vec![stats],
+            cache_hits: 0,
+            cache_misses: 0,
+        };
+
+        let decayed_scorer = HybridScorer::new(HybridScorerConfig {
+            decay_lambda: 0.1,
+            ..Default::default()
+        });
+        let scored = decayed_scorer.score_from_backfill(&backfill);
+        let entry = scored.iter().find(|s| s.address == [0x55u8; 20]).unwrap();
+
+        assert_eq!(entry.gas_score, 1000);
+        assert_eq!(entry.weighted_gas, 100); // 0*0.1^0 + 1000*0.1^1
+        assert!(entry.final_score < entry.gas_score);
+    }
 }
@@ -0,0 +1,225 @@
+//! Incremental lane rebuild with content-addressed chunk deduplication
+//!
+//! [`crate::setup::TwoLaneSetup::build`] re-runs `pir_setup` over the whole
+//! `hot_data`/`cold_data` buffer on every invocation, which is wasteful when
+//! only a handful of storage slots change per block. This module instead
+//! splits a lane's data into fixed-size buckets, digests each one, and only
+//! re-encodes a bucket whose digest isn't already present in the prior
+//! build's chunk index -- borrowing the "merge known chunks" approach
+//! backup systems use for deduplicated incremental snapshots.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use inspire_pir::math::GaussianSampler;
+use inspire_pir::{setup as pir_setup, InspireParams};
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+fn keccak256_hex(data: &[u8]) -> String {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut out = [0u8; 32];
+    hasher.finalize(&mut out);
+    hex::encode(out)
+}
+
+/// A single content-addressed chunk: `bucket_entries` source entries,
+/// encoded and written to `file_name`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkEntry {
+    /// Keccak256 digest (hex-encoded) of the unencoded bucket this chunk
+    /// was built from
+    pub digest: String,
+    /// File name (relative to the index's directory) holding the encoded
+    /// database for this chunk
+    pub file_name: String,
+    /// Number of source entries in this chunk's bucket
+    pub entry_count: u64,
+}
+
+/// Index of content-addressed chunks making up one lane's incremental build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkIndex {
+    pub entry_size: usize,
+    pub bucket_entries: u64,
+    pub chunks: Vec<ChunkEntry>,
+}
+
+impl ChunkIndex {
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    fn by_digest(&self) -> HashMap<&str, &ChunkEntry> {
+        self.chunks.iter().map(|c| (c.digest.as_str(), c)).collect()
+    }
+}
+
+/// Report of an incremental build: how many buckets were reused from the
+/// prior build's chunk index versus freshly re-encoded
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct IncrementalBuildStats {
+    pub reused_chunks: usize,
+    pub reencoded_chunks: usize,
+}
+
+/// Builder for a content-addressed, incremental lane encoding
+pub struct IncrementalBuilder {
+    output_dir: PathBuf,
+    bucket_entries: u64,
+    entry_size: usize,
+    params: InspireParams,
+}
+
+impl IncrementalBuilder {
+    pub fn new(output_dir: impl Into<PathBuf>, params: InspireParams) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            bucket_entries: 1,
+            entry_size: 32,
+            params,
+        }
+    }
+
+    /// Number of entries per bucket/chunk (default: 1)
+    pub fn bucket_entries(mut self, entries: u64) -> Self {
+        self.bucket_entries = entries;
+        self
+    }
+
+    /// Entry size in bytes (default: 32, an Ethereum storage slot)
+    pub fn entry_size(mut self, size: usize) -> Self {
+        self.entry_size = size;
+        self
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.output_dir.join("chunks.idx.json")
+    }
+
+    /// Split `data` into fixed-size buckets, re-encoding only the buckets
+    /// whose content digest isn't already present in `previous`'s chunk
+    /// index (if given), and reusing the rest by copying their chunk file
+    /// forward unchanged.
+    pub fn build(&self, data: &[u8], previous: Option<&Path>) -> anyhow::Result<(ChunkIndex, IncrementalBuildStats)> {
+        std::fs::create_dir_all(&self.output_dir)?;
+
+        let bucket_bytes = (self.bucket_entries as usize) * self.entry_size;
+        let previous_index = previous
+            .map(|dir| ChunkIndex::load(dir.join("chunks.idx.json")))
+            .transpose()?;
+        let previous_dir = previous.map(PathBuf::from);
+        let previous_by_digest = previous_index.as_ref().map(ChunkIndex::by_digest);
+
+        let mut sampler = GaussianSampler::new(self.params.sigma);
+        let mut chunks = Vec::new();
+        let mut stats = IncrementalBuildStats::default();
+
+        for (i, bucket) in data.chunks(bucket_bytes).enumerate() {
+            let entry_count = (bucket.len() / self.entry_size) as u64;
+            let digest = keccak256_hex(bucket);
+            let file_name = format!("chunk_{}.json", &digest[..16]);
+            let chunk_path = self.output_dir.join(&file_name);
+
+            let reused = previous_by_digest
+                .as_ref()
+                .and_then(|by_digest| by_digest.get(digest.as_str()).copied())
+                .zip(previous_dir.as_ref());
+
+            if let Some((prior_chunk, prior_dir)) = reused {
+                let prior_path = prior_dir.join(&prior_chunk.file_name);
+                if prior_path.exists() && !chunk_path.exists() {
+                    std::fs::hard_link(&prior_path, &chunk_path)
+                        .or_else(|_| std::fs::copy(&prior_path, &chunk_path).map(|_| ()))?;
+                }
+                stats.reused_chunks += 1;
+                tracing::debug!(bucket = i, digest = %digest, "Reusing unchanged chunk");
+            } else {
+                let (_crs, db, _sk) = pir_setup(&self.params, bucket, self.entry_size, &mut sampler)
+                    .map_err(|e| anyhow::anyhow!("{}", e))?;
+                let encoded_json = serde_json::to_string(&db)?;
+                std::fs::write(&chunk_path, encoded_json)?;
+                stats.reencoded_chunks += 1;
+                tracing::debug!(bucket = i, digest = %digest, "Encoded new/changed chunk");
+            }
+
+            chunks.push(ChunkEntry {
+                digest,
+                file_name,
+                entry_count,
+            });
+        }
+
+        let index = ChunkIndex {
+            entry_size: self.entry_size,
+            bucket_entries: self.bucket_entries,
+            chunks,
+        };
+        index.save(self.index_path())?;
+
+        tracing::info!(
+            reused = stats.reused_chunks,
+            reencoded = stats.reencoded_chunks,
+            "Incremental lane build complete"
+        );
+
+        Ok((index, stats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setup::test_params;
+
+    #[test]
+    fn test_full_build_has_no_reuse() {
+        let dir = tempfile::tempdir().unwrap();
+        let params = test_params();
+        let data: Vec<u8> = (0..params.ring_dim as u64 * 2 * 32).map(|i| (i % 256) as u8).collect();
+
+        let builder = IncrementalBuilder::new(dir.path(), params)
+            .bucket_entries(params.ring_dim as u64)
+            .entry_size(32);
+
+        let (index, stats) = builder.build(&data, None).unwrap();
+        assert_eq!(index.chunks.len(), 2);
+        assert_eq!(stats.reencoded_chunks, 2);
+        assert_eq!(stats.reused_chunks, 0);
+    }
+
+    #[test]
+    fn test_unchanged_bucket_is_reused() {
+        let ring_dim = test_params().ring_dim as u64;
+        let bucket_bytes = ring_dim as usize * 32;
+        let mut data: Vec<u8> = (0..(bucket_bytes * 2) as u64).map(|i| (i % 256) as u8).collect();
+
+        let dir1 = tempfile::tempdir().unwrap();
+        let builder1 = IncrementalBuilder::new(dir1.path(), test_params())
+            .bucket_entries(ring_dim)
+            .entry_size(32);
+        builder1.build(&data, None).unwrap();
+
+        // Change only the second bucket
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+
+        let dir2 = tempfile::tempdir().unwrap();
+        let builder2 = IncrementalBuilder::new(dir2.path(), test_params())
+            .bucket_entries(ring_dim)
+            .entry_size(32);
+        let (index, stats) = builder2.build(&data, Some(dir1.path())).unwrap();
+
+        assert_eq!(stats.reused_chunks, 1);
+        assert_eq!(stats.reencoded_chunks, 1);
+        assert!(dir2.path().join(&index.chunks[0].file_name).exists());
+    }
+}
@@ -0,0 +1,300 @@
+//! Live hot/cold lane partitioning driven by Reth ExEx notifications
+//!
+//! [`crate::exex::lane_updater_exex`] only tells the PIR server to reload
+//! lane files some other process already rebuilt; it doesn't decide *what*
+//! goes in each lane. This module is the partitioning half: given the set
+//! of accounts touched in a committed block, it promotes never-seen
+//! accounts into the hot lane and demotes accounts gone cold, reusing
+//! [`HotLaneManifest::rebalance`]'s EWMA-based scoring so a contract
+//! doesn't flip lanes on every block. A bounded history of manifest
+//! snapshots lets a `ChainReverted`/`ChainReorged` notification roll the
+//! partition back without a full offline rebuild.
+//!
+//! ExEx hands this module the touched-account *set*, not the per-slot
+//! storage *values* -- assembling the `hot_data`/`cold_data` byte buffers
+//! for [`crate::setup::TwoLaneSetup`] (e.g. via [`crate::lsm_overlay::LsmOverlay`]
+//! for incremental value updates) is still the caller's job.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use inspire_core::{
+    Address, ColdCandidate, HotLaneManifest, ManifestDelta, RebalanceConfig, TwoLaneConfig,
+};
+
+/// Tuning for [`LaneIndexer`]
+#[derive(Debug, Clone)]
+pub struct LaneIndexerConfig {
+    /// EWMA scoring/hysteresis passed through to [`HotLaneManifest::rebalance`]
+    pub rebalance: RebalanceConfig,
+    /// Demote a hot contract untouched for this many blocks, regardless of
+    /// its decayed `access_ewma` -- catches contracts that were hot in a
+    /// single burst long ago but whose EWMA hasn't fully decayed yet.
+    pub demote_idle_blocks: u64,
+    /// Slot count assigned to a newly promoted contract before a real
+    /// extractor pass (see `crate::extractor`) measures its actual storage
+    /// footprint. Deliberately conservative; oversized entries are trimmed
+    /// on the next full rebuild.
+    pub default_slot_count: u64,
+    /// Number of recent block snapshots kept for `ChainReverted`/
+    /// `ChainReorged` rollback. A revert older than this window has aged
+    /// out of the buffer and requires a full offline rebuild instead.
+    pub snapshot_window: usize,
+}
+
+impl Default for LaneIndexerConfig {
+    fn default() -> Self {
+        Self {
+            rebalance: RebalanceConfig::default(),
+            demote_idle_blocks: 7_200, // ~1 day at 12s blocks
+            default_slot_count: 1,
+            snapshot_window: 128,
+        }
+    }
+}
+
+/// Manifest state as of one processed block, kept so a later revert can
+/// restore it without recomputing from scratch.
+struct Snapshot {
+    block_number: u64,
+    manifest: HotLaneManifest,
+    last_touched_block: HashMap<Address, u64>,
+    cold_access: HashMap<Address, f64>,
+}
+
+/// Drives [`HotLaneManifest`] membership from a live stream of per-block
+/// touched accounts, instead of requiring a manual `hot_data`/`cold_data`
+/// blob rebuilt offline.
+pub struct LaneIndexer {
+    config: LaneIndexerConfig,
+    manifest: HotLaneManifest,
+    last_touched_block: HashMap<Address, u64>,
+    cold_access: HashMap<Address, f64>,
+    history: VecDeque<Snapshot>,
+}
+
+impl LaneIndexer {
+    /// Start indexing from an existing manifest (e.g. loaded from disk on
+    /// startup, or a fresh [`HotLaneManifest::new`] for a cold start).
+    pub fn new(manifest: HotLaneManifest, config: LaneIndexerConfig) -> Self {
+        let last_touched_block = manifest
+            .contracts
+            .iter()
+            .map(|c| (c.address, manifest.block_number))
+            .collect();
+        Self {
+            config,
+            manifest,
+            last_touched_block,
+            cold_access: HashMap::new(),
+            history: VecDeque::new(),
+        }
+    }
+
+    /// The current hot lane manifest
+    pub fn manifest(&self) -> &HotLaneManifest {
+        &self.manifest
+    }
+
+    /// Record a committed block's touched accounts, promoting/demoting
+    /// contracts between lanes via [`HotLaneManifest::rebalance`] and
+    /// idle-timeout, and returning the resulting delta (empty if nothing
+    /// crossed a threshold this block).
+    pub fn apply_chain_committed(
+        &mut self,
+        block_number: u64,
+        touched: impl IntoIterator<Item = Address>,
+    ) -> ManifestDelta {
+        self.push_snapshot(block_number);
+        let before = self.manifest.clone();
+
+        for address in touched {
+            self.last_touched_block.insert(address, block_number);
+            if let Some(contract) = self.manifest.contracts.iter_mut().find(|c| c.address == address) {
+                contract.record_access(block_number, self.config.rebalance.half_life_secs);
+            } else {
+                *self.cold_access.entry(address).or_insert(0.0) += 1.0;
+            }
+        }
+
+        let idle_demotions: Vec<Address> = self
+            .manifest
+            .contracts
+            .iter()
+            .filter(|c| {
+                let last_touched = *self.last_touched_block.get(&c.address).unwrap_or(&0);
+                block_number.saturating_sub(last_touched) > self.config.demote_idle_blocks
+            })
+            .map(|c| c.address)
+            .collect();
+
+        let cold_candidates: Vec<ColdCandidate> = self
+            .cold_access
+            .iter()
+            .map(|(&address, &access_ewma)| ColdCandidate { address, access_ewma })
+            .collect();
+        let mut plan = self.manifest.rebalance(&cold_candidates, &self.config.rebalance);
+        for address in idle_demotions {
+            if !plan.demote.contains(&address) {
+                plan.demote.push(address);
+            }
+        }
+
+        for address in &plan.promote {
+            self.cold_access.remove(address);
+            self.manifest.add_contract(
+                *address,
+                format!("0x{}", hex::encode(address)),
+                self.config.default_slot_count,
+                "auto-promoted".to_string(),
+            );
+        }
+        for address in &plan.demote {
+            if self.manifest.remove_contract(address).is_some() {
+                self.cold_access.insert(*address, 0.0);
+            }
+        }
+
+        self.manifest.block_number = block_number;
+        before.diff(&self.manifest)
+    }
+
+    /// Roll the partition back to its state as of `block_number`, as
+    /// required on a `ChainReverted`/`ChainReorged` notification. Returns
+    /// `false` (leaving state untouched) if `block_number` has aged out of
+    /// the snapshot window, in which case the caller must fall back to a
+    /// full offline rebuild.
+    pub fn revert_to(&mut self, block_number: u64) -> bool {
+        let Some(pos) = self.history.iter().rposition(|s| s.block_number <= block_number) else {
+            return false;
+        };
+        let mut from_pos = self.history.split_off(pos);
+        let snapshot = from_pos.pop_front().expect("rposition found an element at pos");
+        self.manifest = snapshot.manifest;
+        self.last_touched_block = snapshot.last_touched_block;
+        self.cold_access = snapshot.cold_access;
+        true
+    }
+
+    /// Atomically persist the current manifest and a `TwoLaneConfig`
+    /// derived from `base_dir` (write-to-temp-then-rename, so a concurrent
+    /// reader never observes a partially-written file).
+    pub fn persist(&self, base_dir: &Path) -> anyhow::Result<()> {
+        let hot_dir = base_dir.join("hot");
+        std::fs::create_dir_all(&hot_dir)?;
+        atomic_write_json(&hot_dir.join("manifest.json"), &self.manifest)?;
+
+        let config = TwoLaneConfig::from_base_dir(base_dir)
+            .with_entries(self.manifest.total_entries, 0)
+            .with_hash();
+        atomic_write_json(&base_dir.join("config.json"), &config)?;
+        Ok(())
+    }
+
+    fn push_snapshot(&mut self, block_number: u64) {
+        self.history.push_back(Snapshot {
+            block_number,
+            manifest: self.manifest.clone(),
+            last_touched_block: self.last_touched_block.clone(),
+            cold_access: self.cold_access.clone(),
+        });
+        while self.history.len() > self.config.snapshot_window {
+            self.history.pop_front();
+        }
+    }
+}
+
+fn atomic_write_json<T: serde::Serialize>(path: &Path, value: &T) -> anyhow::Result<()> {
+    let json = serde_json::to_string_pretty(value)?;
+    let tmp_path = path.with_extension("tmp");
+    std::fs::write(&tmp_path, json)?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_touching_new_account_promotes_above_threshold() {
+        let manifest = HotLaneManifest::new(0);
+        let mut indexer = LaneIndexer::new(manifest, LaneIndexerConfig::default());
+        let addr = [1u8; 20];
+
+        // Below promote_threshold (1.0) on the first touch alone
+        let delta = indexer.apply_chain_committed(1, vec![addr]);
+        assert!(delta.added.is_empty());
+        assert!(!indexer.manifest().contains(&addr));
+
+        // Repeated touches accumulate the cold-side score past the threshold
+        for block in 2..10 {
+            indexer.apply_chain_committed(block, vec![addr]);
+        }
+        assert!(indexer.manifest().contains(&addr));
+    }
+
+    #[test]
+    fn test_idle_hot_contract_is_demoted() {
+        let mut manifest = HotLaneManifest::new(100);
+        manifest.add_contract([2u8; 20], "Idle".into(), 10, "token".into());
+        // Comfortably above the rebalance demote threshold so only the
+        // idle-timeout path below removes it, not the EWMA check.
+        manifest.contracts[0].access_ewma = 5.0;
+        let config = LaneIndexerConfig {
+            demote_idle_blocks: 5,
+            ..LaneIndexerConfig::default()
+        };
+        let mut indexer = LaneIndexer::new(manifest, config);
+
+        indexer.apply_chain_committed(103, std::iter::empty());
+        assert!(indexer.manifest().contains(&[2u8; 20]));
+
+        indexer.apply_chain_committed(107, std::iter::empty());
+        assert!(!indexer.manifest().contains(&[2u8; 20]));
+    }
+
+    #[test]
+    fn test_revert_restores_prior_manifest() {
+        let manifest = HotLaneManifest::new(0);
+        let mut indexer = LaneIndexer::new(manifest, LaneIndexerConfig::default());
+        let addr = [3u8; 20];
+
+        for block in 1..10 {
+            indexer.apply_chain_committed(block, vec![addr]);
+        }
+        assert!(indexer.manifest().contains(&addr));
+
+        assert!(indexer.revert_to(2));
+        assert!(!indexer.manifest().contains(&addr));
+    }
+
+    #[test]
+    fn test_revert_older_than_window_fails() {
+        let manifest = HotLaneManifest::new(0);
+        let config = LaneIndexerConfig {
+            snapshot_window: 4,
+            ..LaneIndexerConfig::default()
+        };
+        let mut indexer = LaneIndexer::new(manifest, config);
+
+        for block in 1..20 {
+            indexer.apply_chain_committed(block, std::iter::empty());
+        }
+
+        assert!(!indexer.revert_to(1));
+    }
+
+    #[test]
+    fn test_persist_writes_manifest_and_config_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = HotLaneManifest::new(0);
+        let indexer = LaneIndexer::new(manifest, LaneIndexerConfig::default());
+
+        indexer.persist(dir.path()).unwrap();
+
+        assert!(dir.path().join("hot/manifest.json").exists());
+        assert!(dir.path().join("config.json").exists());
+        assert!(!dir.path().join("hot/manifest.tmp").exists());
+    }
+}
@@ -0,0 +1,265 @@
+//! Query-telemetry-driven hot lane membership via the Space-Saving
+//! heavy-hitters algorithm
+//!
+//! [`crate::LaneIndexer`] promotes/demotes contracts from on-chain
+//! activity (touched accounts), which says nothing about which contracts
+//! clients actually *query*. This module tracks that signal directly:
+//! feed it every query's plaintext target address (observed client-side,
+//! before it's blinded into an oblivious index) and it maintains a
+//! bounded O(K) Space-Saving sketch of the top-K most-queried addresses,
+//! guaranteeing any address whose true query frequency exceeds 1/K of all
+//! queries is retained. [`LaneRebalancer::snapshot_epoch`] periodically
+//! diffs the sketch against the current hot-lane membership so the
+//! additions/removals can be fed into the same lane-rebuild pipeline
+//! `LaneIndexer` feeds -- with a missing-epochs hysteresis (mirroring
+//! `LaneIndexerConfig::demote_idle_blocks`) so an address that drops out
+//! of the sketch for a single epoch doesn't immediately lose its slot.
+
+use std::collections::{HashMap, HashSet};
+
+use inspire_core::Address;
+
+/// One address tracked by the Space-Saving sketch.
+#[derive(Debug, Clone, Copy)]
+struct Counter {
+    /// Possibly-overestimated query count.
+    count: u64,
+    /// The evicted entry's count at the time this address replaced it
+    /// (`0` if this address was inserted into spare capacity rather than
+    /// by eviction), i.e. the maximum amount `count` could be overestimating
+    /// the address's true frequency by.
+    #[allow(dead_code)]
+    overestimate: u64,
+}
+
+/// Tuning for [`LaneRebalancer`]
+#[derive(Debug, Clone, Copy)]
+pub struct LaneRebalancerConfig {
+    /// Number of addresses tracked by the Space-Saving sketch, and the
+    /// target hot-lane membership size each epoch proposes.
+    pub k: usize,
+    /// Consecutive epochs a hot-lane member must fall out of the sketch's
+    /// top-K before it's demoted, so a momentary dip in query volume
+    /// doesn't flip a contract out of the hot lane and back in next epoch.
+    pub demote_after_missing_epochs: u32,
+}
+
+impl Default for LaneRebalancerConfig {
+    fn default() -> Self {
+        Self {
+            k: 1000,
+            demote_after_missing_epochs: 3,
+        }
+    }
+}
+
+/// Addresses to promote/demote from the hot lane as of one
+/// [`LaneRebalancer::snapshot_epoch`] call.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RebalanceDiff {
+    /// Addresses newly monitored in the sketch's top-K, not yet hot.
+    pub promote: Vec<Address>,
+    /// Hot addresses that have been missing from the top-K for
+    /// `demote_after_missing_epochs` consecutive epochs.
+    pub demote: Vec<Address>,
+}
+
+impl RebalanceDiff {
+    pub fn is_empty(&self) -> bool {
+        self.promote.is_empty() && self.demote.is_empty()
+    }
+}
+
+/// Online top-K query tracker using the Space-Saving algorithm (Metwally,
+/// Agrawal & Abbadi, 2005). See the module doc for how this fits alongside
+/// [`crate::LaneIndexer`].
+pub struct LaneRebalancer {
+    config: LaneRebalancerConfig,
+    monitored: HashMap<Address, Counter>,
+    membership: HashSet<Address>,
+    missing_epochs: HashMap<Address, u32>,
+}
+
+impl LaneRebalancer {
+    pub fn new(config: LaneRebalancerConfig) -> Self {
+        Self {
+            config,
+            monitored: HashMap::new(),
+            membership: HashSet::new(),
+            missing_epochs: HashMap::new(),
+        }
+    }
+
+    /// Record one query against `address`:
+    /// - if `address` is already monitored, increment its counter;
+    /// - else if there's spare capacity (`monitored.len() < k`), start
+    ///   tracking it at count 1;
+    /// - else evict the monitored address with the smallest count and
+    ///   insert `address` with `count = min_count + 1`, recording
+    ///   `min_count` as the new entry's overestimate.
+    pub fn record_query(&mut self, address: Address) {
+        if self.config.k == 0 {
+            return;
+        }
+
+        if let Some(counter) = self.monitored.get_mut(&address) {
+            counter.count += 1;
+            return;
+        }
+
+        if self.monitored.len() < self.config.k {
+            self.monitored.insert(address, Counter { count: 1, overestimate: 0 });
+            return;
+        }
+
+        let evict_addr = *self
+            .monitored
+            .iter()
+            .min_by_key(|(_, c)| c.count)
+            .map(|(addr, _)| addr)
+            .expect("k > 0 and monitored is at capacity, so at least one entry exists");
+        let min_count = self.monitored.remove(&evict_addr).expect("just found by iter").count;
+
+        self.monitored.insert(
+            address,
+            Counter {
+                count: min_count + 1,
+                overestimate: min_count,
+            },
+        );
+    }
+
+    /// The current hot-lane membership (as of the last
+    /// [`Self::snapshot_epoch`] call).
+    pub fn membership(&self) -> &HashSet<Address> {
+        &self.membership
+    }
+
+    /// Snapshot the sketch's current top-K, diff it against `membership`
+    /// with missing-epochs hysteresis, update `membership` in place, and
+    /// return what changed so the caller can feed it into a lane rebuild.
+    pub fn snapshot_epoch(&mut self) -> RebalanceDiff {
+        let top_k: HashSet<Address> = self.monitored.keys().copied().collect();
+
+        let mut promote = Vec::new();
+        for &address in &top_k {
+            self.missing_epochs.remove(&address);
+            if self.membership.insert(address) {
+                promote.push(address);
+            }
+        }
+
+        let mut demote = Vec::new();
+        let current_members: Vec<Address> = self.membership.iter().copied().collect();
+        for address in current_members {
+            if top_k.contains(&address) {
+                continue;
+            }
+            let epochs = self.missing_epochs.entry(address).or_insert(0);
+            *epochs += 1;
+            if *epochs >= self.config.demote_after_missing_epochs {
+                self.membership.remove(&address);
+                self.missing_epochs.remove(&address);
+                demote.push(address);
+            }
+        }
+
+        RebalanceDiff { promote, demote }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(n: u8) -> Address {
+        [n; 20]
+    }
+
+    #[test]
+    fn test_tracks_distinct_addresses_up_to_k() {
+        let mut rebalancer = LaneRebalancer::new(LaneRebalancerConfig { k: 2, ..Default::default() });
+        rebalancer.record_query(addr(1));
+        rebalancer.record_query(addr(2));
+        assert_eq!(rebalancer.monitored.len(), 2);
+
+        let diff = rebalancer.snapshot_epoch();
+        assert_eq!(diff.promote.len(), 2);
+        assert!(diff.demote.is_empty());
+    }
+
+    #[test]
+    fn test_eviction_carries_overestimate_and_bumps_new_entry() {
+        let mut rebalancer = LaneRebalancer::new(LaneRebalancerConfig { k: 2, ..Default::default() });
+        // addr(1) gets queried 5 times, addr(2) once -- addr(2) is the
+        // minimum and gets evicted when addr(3) shows up.
+        for _ in 0..5 {
+            rebalancer.record_query(addr(1));
+        }
+        rebalancer.record_query(addr(2));
+        rebalancer.record_query(addr(3));
+
+        assert!(!rebalancer.monitored.contains_key(&addr(2)));
+        let addr3 = rebalancer.monitored.get(&addr(3)).unwrap();
+        assert_eq!(addr3.count, 2); // min_count (1) + 1
+        assert_eq!(addr3.overestimate, 1);
+    }
+
+    #[test]
+    fn test_heavy_hitter_above_threshold_survives_eviction_pressure() {
+        let k = 3;
+        let mut rebalancer = LaneRebalancer::new(LaneRebalancerConfig { k, ..Default::default() });
+
+        for _ in 0..100 {
+            rebalancer.record_query(addr(1)); // true heavy hitter
+        }
+        for i in 2..50u8 {
+            rebalancer.record_query(addr(i)); // churn of one-off queries
+        }
+
+        assert!(rebalancer.monitored.contains_key(&addr(1)));
+    }
+
+    #[test]
+    fn test_snapshot_demotes_only_after_missing_epochs_exceeded() {
+        let mut rebalancer = LaneRebalancer::new(LaneRebalancerConfig {
+            k: 1,
+            demote_after_missing_epochs: 2,
+        });
+
+        rebalancer.record_query(addr(1));
+        let diff = rebalancer.snapshot_epoch();
+        assert_eq!(diff.promote, vec![addr(1)]);
+
+        // addr(1) falls out of the sketch (replaced by addr(2)), but the
+        // first missed epoch shouldn't demote it yet.
+        rebalancer.record_query(addr(2));
+        let diff = rebalancer.snapshot_epoch();
+        assert!(diff.demote.is_empty());
+        assert!(rebalancer.membership().contains(&addr(1)));
+
+        // second consecutive miss crosses the threshold
+        let diff = rebalancer.snapshot_epoch();
+        assert_eq!(diff.demote, vec![addr(1)]);
+        assert!(!rebalancer.membership().contains(&addr(1)));
+    }
+
+    #[test]
+    fn test_reappearing_before_threshold_resets_missing_counter() {
+        let mut rebalancer = LaneRebalancer::new(LaneRebalancerConfig {
+            k: 1,
+            demote_after_missing_epochs: 2,
+        });
+
+        rebalancer.record_query(addr(1));
+        rebalancer.snapshot_epoch();
+
+        rebalancer.record_query(addr(2)); // addr(1) missing this epoch
+        rebalancer.snapshot_epoch();
+
+        rebalancer.record_query(addr(1)); // addr(1) reappears before demotion
+        let diff = rebalancer.snapshot_epoch();
+        assert!(diff.demote.is_empty());
+        assert!(rebalancer.membership().contains(&addr(1)));
+    }
+}
@@ -0,0 +1,570 @@
+//! Write-ahead log and in-place patcher for incremental lane updates
+//!
+//! [`crate::exex::lane_updater_exex`] used to react to every chain
+//! notification by firing `/admin/reload`, which makes the PIR server
+//! re-ingest `database.bin`/`storage-mapping.bin` from scratch -- wasteful
+//! per block and unsafe under a reorg, since a crash mid-reload can leave
+//! the server serving a half-applied lane. This module instead lets the
+//! ExEx patch those two files directly: an existing `(address, slot)` gets
+//! its value overwritten in place in `database.bin`, a new one is appended
+//! to both files and indexed. Every applied block is first durably
+//! recorded here -- mirroring [`crate::delta_wal::DeltaWal`]'s
+//! append-before-trust discipline -- so a `ChainReorged`/`ChainReverted`
+//! can walk the log backward and undo exactly the reverted blocks' writes,
+//! and a crash mid-apply is caught by [`LaneWal::reconcile`] on restart
+//! rather than silently serving a torn file.
+//!
+//! # Scope
+//!
+//! As in [`crate::delta_wal`], this tree's `ExExNotification` has no
+//! distinct finalized-header variant, so [`LaneWal::finalize`] is driven by
+//! the same depth-based approximation `DeltaExporterConfig::finalized_depth`
+//! uses: blocks more than `finalized_depth` behind the committed tip are
+//! dropped from the log once they're that far back, bounding the log's
+//! size without a real finality signal.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Size in bytes of one `storage-mapping.bin` record: `address(20) +
+/// slot(32) + index(4 LE)`, matching `bin/pir_prep.rs`'s format.
+const MAPPING_ENTRY_SIZE: usize = 20 + 32 + 4;
+/// Size in bytes of one `database.bin` record: a flat 32-byte storage value.
+const DATABASE_ENTRY_SIZE: usize = 32;
+
+/// One `(address, slot)` write applied to the lane store, as recorded in
+/// the WAL so [`LaneWal::rollback`] can undo it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotWrite {
+    pub address: [u8; 20],
+    pub slot: [u8; 32],
+    pub value: [u8; 32],
+    /// Ordinal this slot occupies in `database.bin`/`storage-mapping.bin`.
+    pub ordinal: u64,
+    /// The value this slot held before this write, or `None` if this write
+    /// inserted a brand-new `(address, slot)` pair rather than patching one
+    /// that was already indexed.
+    pub previous_value: Option<[u8; 32]>,
+}
+
+/// One append-only record: the writes a single canonical block made to the
+/// lane store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LaneWalEntry {
+    pub block_number: u64,
+    pub block_hash: [u8; 32],
+    /// Hash of this block's parent, so [`LaneWal::reconcile`] can verify the
+    /// log forms a contiguous chain after a crash restart.
+    pub parent_hash: [u8; 32],
+    /// Entry count the lane store had before this block's writes were
+    /// applied, so [`LaneWal::rollback`] can truncate away exactly the
+    /// inserts this block made without disturbing earlier blocks'.
+    pub entries_before: u64,
+    pub writes: Vec<SlotWrite>,
+}
+
+#[derive(Debug, Error)]
+pub enum LaneWalError {
+    #[error("lane WAL I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("lane WAL entry at line {line} is malformed: {source}")]
+    Malformed {
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("storage-mapping.bin record at offset {offset} is truncated")]
+    TruncatedMapping { offset: u64 },
+}
+
+/// Durable record of which blocks' writes have been applied to the lane
+/// store but aren't yet finalized.
+///
+/// Backed by a newline-delimited-JSON append-only file, exactly like
+/// [`crate::delta_wal::DeltaWal`], so a crash between patching the store
+/// and appending the log entry can only ever lose the very last,
+/// still-unfinalized block.
+pub struct LaneWal {
+    path: PathBuf,
+    entries: Vec<LaneWalEntry>,
+}
+
+impl LaneWal {
+    /// Open (or create) the WAL at `path`, replaying any entries already on
+    /// disk so a restart picks up exactly where the updater left off.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, LaneWalError> {
+        let path = path.as_ref().to_path_buf();
+        let entries = Self::replay(&path)?;
+        Ok(Self { path, entries })
+    }
+
+    fn replay(path: &Path) -> Result<Vec<LaneWalEntry>, LaneWalError> {
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let file = File::open(path)?;
+        let mut entries = Vec::new();
+        for (idx, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            let entry: LaneWalEntry = serde_json::from_str(&line)
+                .map_err(|source| LaneWalError::Malformed { line: idx + 1, source })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    /// Entries still tracked as unfinalized, oldest first.
+    pub fn entries(&self) -> &[LaneWalEntry] {
+        &self.entries
+    }
+
+    /// Apply `writes` (already patched into the lane store by the caller)
+    /// for `block_number`/`block_hash`, appending the record and flushing
+    /// before returning so it's durable before the store is trusted.
+    pub fn append(&mut self, entry: LaneWalEntry) -> Result<(), LaneWalError> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        let line = serde_json::to_string(&entry).expect("LaneWalEntry serializes infallibly");
+        writeln!(file, "{line}")?;
+        file.flush()?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Roll back a reorg or revert: undo every tracked entry whose block
+    /// number is in `removed_blocks` against `store`, newest first, so a
+    /// crash mid-rollback always leaves a strict prefix (the oldest
+    /// surviving blocks) applied rather than an arbitrary subset.
+    pub fn rollback(&mut self, store: &LaneStore, removed_blocks: &std::collections::HashSet<u64>) -> Result<(), LaneWalError> {
+        let mut kept = Vec::with_capacity(self.entries.len());
+        for entry in self.entries.drain(..).rev() {
+            if removed_blocks.contains(&entry.block_number) {
+                store.undo(&entry)?;
+            } else {
+                kept.push(entry);
+            }
+        }
+        kept.reverse();
+        self.entries = kept;
+        self.rewrite()
+    }
+
+    /// Finalize every entry at or below `finalized_block`: drop them from
+    /// the log, since their writes can no longer be reorged away.
+    pub fn finalize(&mut self, finalized_block: u64) -> Result<Vec<LaneWalEntry>, LaneWalError> {
+        let (finalized, kept): (Vec<_>, Vec<_>) = self
+            .entries
+            .drain(..)
+            .partition(|e| e.block_number <= finalized_block);
+        self.entries = kept;
+        self.rewrite()?;
+        Ok(finalized)
+    }
+
+    /// Reconcile the log against the store after a crash restart: a crash
+    /// can land mid-[`LaneStore::apply_writes`], leaving the store's entry
+    /// count ahead of what the last logged entry accounts for, or between a
+    /// rollback's undo and [`Self::rewrite`] persisting it, leaving a tail
+    /// whose `parent_hash` doesn't chain to the entry before it. Undoes and
+    /// drops every such orphaned tail entry, returning what was dropped so
+    /// the caller can log it.
+    pub fn reconcile(&mut self, store: &LaneStore) -> Result<Vec<LaneWalEntry>, LaneWalError> {
+        let mut break_at = self.entries.len();
+        for i in 1..self.entries.len() {
+            if self.entries[i].parent_hash != self.entries[i - 1].block_hash {
+                break_at = i;
+                break;
+            }
+        }
+
+        let actual_len = store.entry_count()?;
+        if break_at == self.entries.len() {
+            if let Some(last) = self.entries.last() {
+                let expected_len = last.entries_before
+                    + last.writes.iter().filter(|w| w.previous_value.is_none()).count() as u64;
+                if expected_len != actual_len {
+                    break_at = self.entries.len() - 1;
+                }
+            }
+        }
+
+        if break_at == self.entries.len() {
+            return Ok(Vec::new());
+        }
+
+        let dropped: Vec<LaneWalEntry> = self.entries.split_off(break_at);
+        for entry in dropped.iter().rev() {
+            store.undo(entry)?;
+        }
+        self.rewrite()?;
+        Ok(dropped)
+    }
+
+    /// Rewrite the on-disk log to match `self.entries` exactly, used after
+    /// `rollback`/`finalize`/`reconcile` shrink the in-memory log.
+    fn rewrite(&self) -> Result<(), LaneWalError> {
+        let tmp_path = self.path.with_extension("wal.tmp");
+        {
+            let mut file = File::create(&tmp_path)?;
+            for entry in &self.entries {
+                let line = serde_json::to_string(entry).expect("LaneWalEntry serializes infallibly");
+                writeln!(file, "{line}")?;
+            }
+            file.flush()?;
+        }
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// In-memory `(address, slot) -> ordinal` lookup built by scanning
+/// `storage-mapping.bin`, so [`LaneStore::apply_writes`] can seek straight
+/// to an existing entry instead of scanning the file on every block.
+struct SlotIndex {
+    map: HashMap<([u8; 20], [u8; 32]), u64>,
+    len: u64,
+}
+
+/// Applies storage-slot writes directly to a lane's `database.bin` /
+/// `storage-mapping.bin`, in place, instead of the full `pir_setup` +
+/// `/admin/reload` round trip `bin/pir_prep.rs` uses for the initial build.
+pub struct LaneStore {
+    data_dir: PathBuf,
+    index: std::sync::Mutex<Option<SlotIndex>>,
+}
+
+impl LaneStore {
+    pub fn new(data_dir: impl AsRef<Path>) -> Self {
+        Self {
+            data_dir: data_dir.as_ref().to_path_buf(),
+            index: std::sync::Mutex::new(None),
+        }
+    }
+
+    fn database_path(&self) -> PathBuf {
+        self.data_dir.join("database.bin")
+    }
+
+    fn mapping_path(&self) -> PathBuf {
+        self.data_dir.join("storage-mapping.bin")
+    }
+
+    /// Number of `(address, slot)` entries currently indexed.
+    pub fn entry_count(&self) -> Result<u64, LaneWalError> {
+        let mut guard = self.index.lock().expect("lane store index mutex poisoned");
+        if guard.is_none() {
+            *guard = Some(Self::load_index(&self.mapping_path())?);
+        }
+        Ok(guard.as_ref().expect("populated above").len)
+    }
+
+    fn load_index(mapping_path: &Path) -> Result<SlotIndex, LaneWalError> {
+        if !mapping_path.exists() {
+            return Ok(SlotIndex { map: HashMap::new(), len: 0 });
+        }
+
+        let mut reader = BufReader::new(File::open(mapping_path)?);
+        let mut map = HashMap::new();
+        let mut len = 0u64;
+        let mut buf = [0u8; MAPPING_ENTRY_SIZE];
+        loop {
+            match reader.read_exact(&mut buf) {
+                Ok(()) => {
+                    let mut address = [0u8; 20];
+                    address.copy_from_slice(&buf[0..20]);
+                    let mut slot = [0u8; 32];
+                    slot.copy_from_slice(&buf[20..52]);
+                    let ordinal = u32::from_le_bytes(buf[52..56].try_into().unwrap()) as u64;
+                    map.insert((address, slot), ordinal);
+                    len += 1;
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(SlotIndex { map, len })
+    }
+
+    /// Apply `writes` -- patching `database.bin` in place for slots already
+    /// indexed, appending a new record to both files for slots that
+    /// aren't -- and return the [`SlotWrite`]s the caller should log to the
+    /// WAL, each carrying the previous value (for an update) or `None` (for
+    /// an insert) so a later rollback can undo it.
+    pub fn apply_writes(&self, writes: &[(
+        [u8; 20],
+        [u8; 32],
+        [u8; 32],
+    )]) -> Result<Vec<SlotWrite>, LaneWalError> {
+        if writes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut guard = self.index.lock().expect("lane store index mutex poisoned");
+        if guard.is_none() {
+            *guard = Some(Self::load_index(&self.mapping_path())?);
+        }
+        let index = guard.as_mut().expect("populated above");
+
+        let mut database_file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .open(self.database_path())?;
+        let mut mapping_file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(self.mapping_path())?;
+
+        let mut recorded = Vec::with_capacity(writes.len());
+
+        for &(address, slot, value) in writes {
+            if let Some(&ordinal) = index.map.get(&(address, slot)) {
+                let offset = ordinal * DATABASE_ENTRY_SIZE as u64;
+                let mut previous = [0u8; 32];
+                database_file.seek(SeekFrom::Start(offset))?;
+                database_file.read_exact(&mut previous)?;
+                database_file.seek(SeekFrom::Start(offset))?;
+                database_file.write_all(&value)?;
+                recorded.push(SlotWrite { address, slot, value, ordinal, previous_value: Some(previous) });
+            } else {
+                let ordinal = index.len;
+                database_file.seek(SeekFrom::End(0))?;
+                database_file.write_all(&value)?;
+
+                let mut mapping_entry = [0u8; MAPPING_ENTRY_SIZE];
+                mapping_entry[0..20].copy_from_slice(&address);
+                mapping_entry[20..52].copy_from_slice(&slot);
+                mapping_entry[52..56].copy_from_slice(&(ordinal as u32).to_le_bytes());
+                mapping_file.write_all(&mapping_entry)?;
+
+                index.map.insert((address, slot), ordinal);
+                index.len += 1;
+                recorded.push(SlotWrite { address, slot, value, ordinal, previous_value: None });
+            }
+        }
+
+        database_file.sync_data()?;
+        mapping_file.sync_data()?;
+
+        Ok(recorded)
+    }
+
+    /// Undo one WAL entry's writes: restore every patched slot's previous
+    /// value, then truncate both files back to `entries_before` so any
+    /// slots this block inserted (which, by construction, are exactly the
+    /// newest entries) are dropped.
+    fn undo(&self, entry: &LaneWalEntry) -> Result<(), LaneWalError> {
+        let database_path = self.database_path();
+        if database_path.exists() {
+            let mut database_file = OpenOptions::new().write(true).open(&database_path)?;
+            for write in &entry.writes {
+                if let Some(previous) = write.previous_value {
+                    database_file.seek(SeekFrom::Start(write.ordinal * DATABASE_ENTRY_SIZE as u64))?;
+                    database_file.write_all(&previous)?;
+                }
+            }
+            database_file.sync_data()?;
+            database_file.set_len(entry.entries_before * DATABASE_ENTRY_SIZE as u64)?;
+        }
+
+        let mapping_path = self.mapping_path();
+        if mapping_path.exists() {
+            let mapping_file = OpenOptions::new().write(true).open(&mapping_path)?;
+            mapping_file.set_len(entry.entries_before * MAPPING_ENTRY_SIZE as u64)?;
+        }
+
+        let mut guard = self.index.lock().expect("lane store index mutex poisoned");
+        if let Some(index) = guard.as_mut() {
+            for write in &entry.writes {
+                if write.previous_value.is_none() {
+                    index.map.remove(&(write.address, write.slot));
+                }
+            }
+            index.len = entry.entries_before;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn addr(b: u8) -> [u8; 20] {
+        [b; 20]
+    }
+    fn word(b: u8) -> [u8; 32] {
+        [b; 32]
+    }
+
+    #[test]
+    fn test_apply_writes_inserts_new_slots() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LaneStore::new(dir.path());
+
+        let writes = store.apply_writes(&[(addr(1), word(1), word(100))]).unwrap();
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].ordinal, 0);
+        assert_eq!(writes[0].previous_value, None);
+        assert_eq!(store.entry_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_apply_writes_patches_existing_slot_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LaneStore::new(dir.path());
+        store.apply_writes(&[(addr(1), word(1), word(100))]).unwrap();
+
+        let writes = store.apply_writes(&[(addr(1), word(1), word(200))]).unwrap();
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].previous_value, Some(word(100)));
+        // Patching doesn't grow the store.
+        assert_eq!(store.entry_count().unwrap(), 1);
+
+        let data = std::fs::read(dir.path().join("database.bin")).unwrap();
+        assert_eq!(&data[0..32], &word(200)[..]);
+    }
+
+    #[test]
+    fn test_wal_append_and_replay_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_path = dir.path().join("lane.wal");
+
+        {
+            let mut wal = LaneWal::open(&wal_path).unwrap();
+            wal.append(LaneWalEntry {
+                block_number: 1,
+                block_hash: [1u8; 32],
+                parent_hash: [0u8; 32],
+                entries_before: 0,
+                writes: vec![SlotWrite { address: addr(1), slot: word(1), value: word(100), ordinal: 0, previous_value: None }],
+            })
+            .unwrap();
+        }
+
+        let wal = LaneWal::open(&wal_path).unwrap();
+        assert_eq!(wal.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_rollback_undoes_only_removed_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LaneStore::new(dir.path());
+        let mut wal = LaneWal::open(dir.path().join("lane.wal")).unwrap();
+
+        let entries_before = store.entry_count().unwrap();
+        let writes = store.apply_writes(&[(addr(1), word(1), word(100))]).unwrap();
+        wal.append(LaneWalEntry { block_number: 1, block_hash: [1u8; 32], parent_hash: [0u8; 32], entries_before, writes })
+            .unwrap();
+
+        let entries_before = store.entry_count().unwrap();
+        let writes = store.apply_writes(&[(addr(2), word(2), word(200))]).unwrap();
+        wal.append(LaneWalEntry { block_number: 2, block_hash: [2u8; 32], parent_hash: [1u8; 32], entries_before, writes })
+            .unwrap();
+
+        let mut removed = HashSet::new();
+        removed.insert(2);
+        wal.rollback(&store, &removed).unwrap();
+
+        assert_eq!(wal.entries().len(), 1);
+        assert_eq!(store.entry_count().unwrap(), 1);
+
+        // Block 1's slot is untouched; block 2's insert was rolled back.
+        let data = std::fs::read(dir.path().join("database.bin")).unwrap();
+        assert_eq!(data.len(), DATABASE_ENTRY_SIZE);
+        assert_eq!(&data[0..32], &word(100)[..]);
+    }
+
+    #[test]
+    fn test_rollback_restores_patched_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LaneStore::new(dir.path());
+        let mut wal = LaneWal::open(dir.path().join("lane.wal")).unwrap();
+
+        let entries_before = store.entry_count().unwrap();
+        let writes = store.apply_writes(&[(addr(1), word(1), word(100))]).unwrap();
+        wal.append(LaneWalEntry { block_number: 1, block_hash: [1u8; 32], parent_hash: [0u8; 32], entries_before, writes })
+            .unwrap();
+
+        let entries_before = store.entry_count().unwrap();
+        let writes = store.apply_writes(&[(addr(1), word(1), word(999))]).unwrap();
+        wal.append(LaneWalEntry { block_number: 2, block_hash: [2u8; 32], parent_hash: [1u8; 32], entries_before, writes })
+            .unwrap();
+
+        let mut removed = HashSet::new();
+        removed.insert(2);
+        wal.rollback(&store, &removed).unwrap();
+
+        let data = std::fs::read(dir.path().join("database.bin")).unwrap();
+        assert_eq!(&data[0..32], &word(100)[..]);
+        assert_eq!(store.entry_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_finalize_truncates_and_returns_finalized_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal_path = dir.path().join("lane.wal");
+        let mut wal = LaneWal::open(&wal_path).unwrap();
+
+        for n in 1..=3u64 {
+            wal.append(LaneWalEntry {
+                block_number: n,
+                block_hash: [n as u8; 32],
+                parent_hash: [(n - 1) as u8; 32],
+                entries_before: n - 1,
+                writes: vec![],
+            })
+            .unwrap();
+        }
+
+        let finalized = wal.finalize(2).unwrap();
+        assert_eq!(finalized.len(), 2);
+        assert_eq!(wal.entries().len(), 1);
+        assert_eq!(wal.entries()[0].block_number, 3);
+    }
+
+    #[test]
+    fn test_reconcile_undoes_store_ahead_of_last_logged_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LaneStore::new(dir.path());
+        let mut wal = LaneWal::open(dir.path().join("lane.wal")).unwrap();
+
+        let entries_before = store.entry_count().unwrap();
+        let writes = store.apply_writes(&[(addr(1), word(1), word(100))]).unwrap();
+        wal.append(LaneWalEntry { block_number: 1, block_hash: [1u8; 32], parent_hash: [0u8; 32], entries_before, writes })
+            .unwrap();
+
+        // Simulate a crash mid-apply: the store gained an entry that was
+        // never logged.
+        store.apply_writes(&[(addr(2), word(2), word(200))]).unwrap();
+
+        let dropped = wal.reconcile(&store).unwrap();
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(store.entry_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_reconcile_is_noop_for_a_consistent_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LaneStore::new(dir.path());
+        let mut wal = LaneWal::open(dir.path().join("lane.wal")).unwrap();
+
+        let entries_before = store.entry_count().unwrap();
+        let writes = store.apply_writes(&[(addr(1), word(1), word(100))]).unwrap();
+        wal.append(LaneWalEntry { block_number: 1, block_hash: [1u8; 32], parent_hash: [0u8; 32], entries_before, writes })
+            .unwrap();
+
+        assert!(wal.reconcile(&store).unwrap().is_empty());
+        assert_eq!(wal.entries().len(), 1);
+    }
+}
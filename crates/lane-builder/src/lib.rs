@@ -7,34 +7,104 @@
 //!
 //! - `exex`: Enable Reth ExEx integration for real-time lane updates
 //! - `backfill`: Enable gas backfill for data-driven hot lane selection
+//! - `server`: Enable the HTTP query server over the hot-lane database
+//! - `fork-verify`: Enable the anvil/geth-fork-based curated-list checker
 
+pub mod activity_ranker;
 pub mod builder;
+pub mod checkpoint;
+pub mod cold_shard;
+pub mod cold_snapshot;
 pub mod contracts;
+pub mod delta_compaction;
+pub mod delta_wal;
 pub mod extractor;
+pub mod hot_lane_config;
 pub mod hybrid_scorer;
+pub mod incremental;
+pub mod lane_indexer;
+pub mod lane_wal;
+pub mod lane_rebalancer;
+pub mod lsm_overlay;
 pub mod reload;
 pub mod setup;
+pub mod storage_layout;
 
 #[cfg(feature = "exex")]
 pub mod exex;
 
+#[cfg(feature = "exex")]
+pub mod delta_exex;
+
 #[cfg(feature = "backfill")]
 pub mod gas_tracker;
 
+#[cfg(feature = "backfill")]
+pub mod category_heuristics;
+
 #[cfg(feature = "balance")]
 pub mod balance_extractor;
 
+#[cfg(feature = "balance")]
+pub mod state_proof;
+
+#[cfg(feature = "server")]
+pub mod serve;
+
+#[cfg(feature = "state-dump")]
+pub mod storage_decode;
+
+#[cfg(feature = "fork-verify")]
+pub mod fork_verify;
+
+#[cfg(feature = "redb-store")]
+pub mod redb_store;
+
+pub use activity_ranker::{ActivityProvider, ActivityRanker, ActivityRankerConfig};
 pub use builder::HotLaneBuilder;
-pub use extractor::ContractExtractor;
+pub use checkpoint::{CheckpointBlock, CheckpointClient, CheckpointError};
+pub use cold_shard::{ColdShardError, ColdShardInfo, ColdShardManifest};
+pub use cold_snapshot::{ColdShardBlacklist, ColdSnapshotBuilder};
+pub use delta_compaction::{compact_range, CompactionError};
+pub use delta_wal::{DeltaWal, WalEntry, WalError};
+pub use extractor::{CacheStats, ContractExtractor};
+pub use hot_lane_config::{load_hot_lane_config, merge_hot_lane_config, HotLaneConfig, HotLaneConfigError, MergeMode};
 pub use hybrid_scorer::{HybridScorer, HybridScorerConfig, ScoredContract, CategoryWeights};
+pub use incremental::{ChunkEntry, ChunkIndex, IncrementalBuildStats, IncrementalBuilder};
+pub use lane_indexer::{LaneIndexer, LaneIndexerConfig};
+pub use lane_wal::{LaneStore, LaneWal, LaneWalEntry, LaneWalError, SlotWrite};
+pub use lane_rebalancer::{LaneRebalancer, LaneRebalancerConfig, RebalanceDiff};
+pub use lsm_overlay::{LsmOverlay, LsmOverlayError};
 pub use reload::ReloadClient;
 pub use setup::{TwoLaneSetup, TwoLaneSetupResult, default_params, test_params, load_secret_key};
+pub use storage_layout::{load_layouts_from_dir, parse_storage_layout, StorageLayout};
 
 #[cfg(feature = "exex")]
 pub use exex::{lane_updater_exex, LaneUpdaterConfig};
 
+#[cfg(feature = "exex")]
+pub use delta_exex::{delta_export_exex, DeltaExporterConfig};
+
 #[cfg(feature = "backfill")]
-pub use gas_tracker::{GasTracker, BackfillConfig, BackfillResult, GasStats};
+pub use gas_tracker::{GasTracker, BackfillCheckpoint, BackfillConfig, BackfillResult, GasSource, GasStats};
+
+#[cfg(feature = "backfill")]
+pub use category_heuristics::infer_category;
 
 #[cfg(feature = "balance")]
 pub use balance_extractor::{BalanceExtractor, BalanceExtractorConfig};
+
+#[cfg(feature = "balance")]
+pub use state_proof::{verify_account_proof, verify_storage_proof, StateProofError, TrieAccount};
+
+#[cfg(feature = "server")]
+pub use serve::{create_router, serve as run_serve, LaneQuery, LaneQueryState, ManifestQuery, ScoredContractIndex, SharedLaneQueryState};
+
+#[cfg(feature = "state-dump")]
+pub use storage_decode::{decode_rlp_u256, split_storage_dup_value, DecodeError, ExtractionReport};
+
+#[cfg(feature = "fork-verify")]
+pub use fork_verify::{ForkVerificationResult, ForkVerifier};
+
+#[cfg(feature = "redb-store")]
+pub use redb_store::{store_path, RedbLaneStore, RedbStoreError, UpsertOutcome};
@@ -0,0 +1,200 @@
+//! LSM-style overlay for incremental lane updates
+//!
+//! Ethereum state mutates every block, but re-running `pir_setup` re-encodes
+//! the *entire* database as polynomials from scratch — untenable for a
+//! 1M-entry hot lane refreshed per block. This module provides the
+//! log-structured-merge-tree-inspired accumulation layer: per-block writes
+//! (`global_index -> new entry bytes`) are buffered in a small in-memory
+//! overlay instead of triggering a full re-`setup` immediately. Once the
+//! overlay grows past a threshold, [`LsmOverlay::compact`] folds it into the
+//! plaintext base buffer so the next `pir_setup` encodes a single
+//! consistent snapshot.
+//!
+//! Making the PIR `respond` path itself consult the overlay before falling
+//! back to the encoded base (so a re-`setup` isn't needed on every write) is
+//! a change to the RLWE response computation in `inspire_pir`, which is not
+//! vendored in this tree; this module covers the builder-side half that is.
+
+use std::collections::BTreeMap;
+
+/// Errors from applying or compacting overlay updates
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LsmOverlayError {
+    #[error("update index {index} is out of range for base of {entries} entries")]
+    IndexOutOfRange { index: u64, entries: u64 },
+
+    #[error("update value is {actual} bytes, expected entry_size {expected}")]
+    WrongEntrySize { expected: usize, actual: usize },
+}
+
+/// Accumulates pending per-block entry writes ahead of the next full
+/// `pir_setup` re-encoding.
+#[derive(Debug, Clone)]
+pub struct LsmOverlay {
+    entry_size: usize,
+    /// Pending writes, keyed by global index, most recent write wins
+    pending: BTreeMap<u64, Vec<u8>>,
+    /// Trigger `needs_compaction()` once `pending.len()` reaches this
+    compact_threshold: usize,
+}
+
+impl LsmOverlay {
+    /// Create a new overlay for entries of `entry_size` bytes, compacting
+    /// once `compact_threshold` distinct indices have pending writes.
+    pub fn new(entry_size: usize, compact_threshold: usize) -> Self {
+        Self {
+            entry_size,
+            pending: BTreeMap::new(),
+            compact_threshold,
+        }
+    }
+
+    /// Record new values for the given global indices (e.g. one block's
+    /// worth of storage writes). Later calls overwrite earlier ones for the
+    /// same index.
+    pub fn apply_updates(
+        &mut self,
+        base_entries: u64,
+        updates: &[(u64, Vec<u8>)],
+    ) -> Result<(), LsmOverlayError> {
+        for (index, value) in updates {
+            if *index >= base_entries {
+                return Err(LsmOverlayError::IndexOutOfRange { index: *index, entries: base_entries });
+            }
+            if value.len() != self.entry_size {
+                return Err(LsmOverlayError::WrongEntrySize {
+                    expected: self.entry_size,
+                    actual: value.len(),
+                });
+            }
+            self.pending.insert(*index, value.clone());
+        }
+        Ok(())
+    }
+
+    /// Look up the current value for an index: the overlay if pending,
+    /// otherwise `None` (caller should fall back to the base database).
+    pub fn get(&self, index: u64) -> Option<&[u8]> {
+        self.pending.get(&index).map(|v| v.as_slice())
+    }
+
+    /// Number of indices with a pending overlay write
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Whether the overlay has grown past the compaction threshold and
+    /// should be merged into the base before the next respond cycle
+    pub fn needs_compaction(&self) -> bool {
+        self.pending.len() >= self.compact_threshold
+    }
+
+    /// Merge all pending writes into `base` (a flat `entry_size`-stride
+    /// plaintext buffer) and clear the overlay. The caller is expected to
+    /// re-run `pir_setup` on `base` afterwards to produce a fresh encoded
+    /// database reflecting the merged state.
+    pub fn compact(&mut self, base: &mut [u8]) -> Result<usize, LsmOverlayError> {
+        let base_entries = (base.len() / self.entry_size) as u64;
+        let mut applied = 0usize;
+
+        for (&index, value) in self.pending.iter() {
+            if index >= base_entries {
+                return Err(LsmOverlayError::IndexOutOfRange { index, entries: base_entries });
+            }
+            let start = index as usize * self.entry_size;
+            base[start..start + self.entry_size].copy_from_slice(value);
+            applied += 1;
+        }
+
+        self.pending.clear();
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_db(entries: u64, entry_size: usize) -> Vec<u8> {
+        (0..entries * entry_size as u64).map(|i| (i % 256) as u8).collect()
+    }
+
+    #[test]
+    fn test_overlay_shadows_base_before_compaction() {
+        let entry_size = 32;
+        let mut base = base_db(10, entry_size);
+        let original = base.clone();
+
+        let mut overlay = LsmOverlay::new(entry_size, 100);
+        overlay
+            .apply_updates(10, &[(3, vec![0xaa; entry_size]), (7, vec![0xbb; entry_size])])
+            .unwrap();
+
+        // Overlay has the new values ...
+        assert_eq!(overlay.get(3), Some(&[0xaa; 32][..]));
+        assert_eq!(overlay.get(7), Some(&[0xbb; 32][..]));
+        // ... but untouched indices have no overlay entry ...
+        assert_eq!(overlay.get(0), None);
+        // ... and the base buffer is untouched until compact() runs.
+        assert_eq!(base, original);
+    }
+
+    #[test]
+    fn test_compact_merges_overlay_into_base() {
+        let entry_size = 32;
+        let mut base = base_db(10, entry_size);
+
+        let mut overlay = LsmOverlay::new(entry_size, 100);
+        overlay
+            .apply_updates(10, &[(3, vec![0xaa; entry_size]), (7, vec![0xbb; entry_size])])
+            .unwrap();
+
+        let applied = overlay.compact(&mut base).unwrap();
+        assert_eq!(applied, 2);
+        assert!(overlay.is_empty());
+
+        assert_eq!(&base[3 * entry_size..4 * entry_size], &[0xaa; 32][..]);
+        assert_eq!(&base[7 * entry_size..8 * entry_size], &[0xbb; 32][..]);
+
+        // Untouched index 0 still matches the original base encoding
+        let original = base_db(10, entry_size);
+        assert_eq!(&base[0..entry_size], &original[0..entry_size]);
+    }
+
+    #[test]
+    fn test_needs_compaction_threshold() {
+        let mut overlay = LsmOverlay::new(32, 2);
+        assert!(!overlay.needs_compaction());
+        overlay.apply_updates(10, &[(0, vec![0u8; 32])]).unwrap();
+        assert!(!overlay.needs_compaction());
+        overlay.apply_updates(10, &[(1, vec![0u8; 32])]).unwrap();
+        assert!(overlay.needs_compaction());
+    }
+
+    #[test]
+    fn test_rejects_index_out_of_range() {
+        let mut overlay = LsmOverlay::new(32, 100);
+        let result = overlay.apply_updates(10, &[(10, vec![0u8; 32])]);
+        assert_eq!(result, Err(LsmOverlayError::IndexOutOfRange { index: 10, entries: 10 }));
+    }
+
+    #[test]
+    fn test_rejects_wrong_entry_size() {
+        let mut overlay = LsmOverlay::new(32, 100);
+        let result = overlay.apply_updates(10, &[(0, vec![0u8; 16])]);
+        assert_eq!(result, Err(LsmOverlayError::WrongEntrySize { expected: 32, actual: 16 }));
+    }
+
+    #[test]
+    fn test_later_update_wins() {
+        let mut overlay = LsmOverlay::new(32, 100);
+        overlay.apply_updates(10, &[(0, vec![1u8; 32])]).unwrap();
+        overlay.apply_updates(10, &[(0, vec![2u8; 32])]).unwrap();
+        assert_eq!(overlay.get(0), Some(&[2u8; 32][..]));
+        assert_eq!(overlay.len(), 1);
+    }
+}
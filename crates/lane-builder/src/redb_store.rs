@@ -0,0 +1,383 @@
+//! redb-backed incremental store for the `database.bin`/`storage-mapping.bin`
+//! lane pair, shared between `bin/pir_prep.rs`'s batch extraction and
+//! [`crate::lane_wal::LaneStore`]'s live ExEx path.
+//!
+//! `bin/pir_prep.rs` writes `database.bin`/`storage-mapping.bin` by
+//! streaming every `PlainStorageState` duplicate straight to disk, so
+//! re-running it (to pick up new blocks, or to fix a decode bug) means
+//! rebuilding both files from scratch. This module instead keeps the
+//! authoritative `(address, slot) -> value` mapping in an embedded [redb]
+//! database with two tables:
+//!
+//! - `SLOTS`: `(address: [u8; 20], slot: [u8; 32]) -> (value: [u8; 32],
+//!   dense_index: u64)`, the source of truth for what each slot currently
+//!   holds and where it lives in the dense flat file.
+//! - `DENSE`: `dense_index: u64 -> value: [u8; 32]`, a materialized
+//!   projection of `SLOTS` in dense-index order -- this is what
+//!   [`RedbLaneStore::materialize`] flattens into `database.bin` for the
+//!   PIR server to mmap.
+//!
+//! Upserting a slot that's already indexed overwrites its value in both
+//! tables in place and reuses its `dense_index`; a brand-new slot is
+//! appended at the next free index. Deleting a slot tombstones its index
+//! (recorded in `FREE_INDICES`) rather than shifting every later entry
+//! down, so `dense_index` stays a stable handle into `database.bin` until
+//! the next compaction reclaims it for a future insert.
+//!
+//! [redb]: https://docs.rs/redb
+
+use std::path::{Path, PathBuf};
+
+use redb::{Database, ReadableTable, TableDefinition};
+use thiserror::Error;
+
+const SLOTS: TableDefinition<(&[u8; 20], &[u8; 32]), ([u8; 32], u64)> =
+    TableDefinition::new("slots");
+const DENSE: TableDefinition<u64, [u8; 32]> = TableDefinition::new("dense");
+/// Tombstoned `dense_index`es free for reuse by the next insert, keyed by
+/// the index itself so membership and removal are both O(log n).
+const FREE_INDICES: TableDefinition<u64, ()> = TableDefinition::new("free_indices");
+
+#[derive(Debug, Error)]
+pub enum RedbStoreError {
+    #[error("redb database error: {0}")]
+    Database(#[from] redb::DatabaseError),
+    #[error("redb transaction error: {0}")]
+    Transaction(#[from] redb::TransactionError),
+    #[error("redb table error: {0}")]
+    Table(#[from] redb::TableError),
+    #[error("redb storage error: {0}")]
+    Storage(#[from] redb::StorageError),
+    #[error("redb commit error: {0}")]
+    Commit(#[from] redb::CommitError),
+    #[error("I/O error materializing dense file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Outcome of one [`RedbLaneStore::upsert`] call, so callers (the WAL
+/// patcher, `pir-prep`'s extraction loop) can tell an update from an
+/// insert without a second lookup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    /// The slot already existed at `dense_index` and its value was
+    /// overwritten in place.
+    Updated { dense_index: u64, previous_value: [u8; 32] },
+    /// The slot was new and was appended at `dense_index`.
+    Inserted { dense_index: u64 },
+}
+
+/// Single source of truth for a lane's storage slots, backed by an
+/// embedded redb database rather than the flat `database.bin` /
+/// `storage-mapping.bin` pair `bin/pir_prep.rs` writes directly.
+pub struct RedbLaneStore {
+    db: Database,
+}
+
+impl RedbLaneStore {
+    /// Open (or create) the redb database at `path`, creating the `SLOTS`,
+    /// `DENSE`, and `FREE_INDICES` tables if this is a fresh file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RedbStoreError> {
+        let db = Database::create(path)?;
+        let txn = db.begin_write()?;
+        {
+            txn.open_table(SLOTS)?;
+            txn.open_table(DENSE)?;
+            txn.open_table(FREE_INDICES)?;
+        }
+        txn.commit()?;
+        Ok(Self { db })
+    }
+
+    /// Upsert one `(address, slot) -> value`: if the slot is already
+    /// indexed, its value is overwritten in place and its existing
+    /// `dense_index` is reused; otherwise it claims a tombstoned index (if
+    /// `FREE_INDICES` has one) or appends a fresh one.
+    pub fn upsert(&self, address: [u8; 20], slot: [u8; 32], value: [u8; 32]) -> Result<UpsertOutcome, RedbStoreError> {
+        let txn = self.db.begin_write()?;
+        let outcome = {
+            let mut slots = txn.open_table(SLOTS)?;
+            let mut dense = txn.open_table(DENSE)?;
+            let mut free = txn.open_table(FREE_INDICES)?;
+
+            if let Some(existing) = slots.get((&address, &slot))? {
+                let (previous_value, dense_index) = existing.value();
+                slots.insert((&address, &slot), (value, dense_index))?;
+                dense.insert(dense_index, value)?;
+                UpsertOutcome::Updated { dense_index, previous_value }
+            } else {
+                let dense_index = match Self::pop_free_index(&mut free)? {
+                    Some(index) => index,
+                    None => dense.len()? + Self::tombstone_count(&free)?,
+                };
+                slots.insert((&address, &slot), (value, dense_index))?;
+                dense.insert(dense_index, value)?;
+                UpsertOutcome::Inserted { dense_index }
+            }
+        };
+        txn.commit()?;
+        Ok(outcome)
+    }
+
+    /// Look up the current value for `(address, slot)`, if indexed.
+    pub fn get(&self, address: [u8; 20], slot: [u8; 32]) -> Result<Option<[u8; 32]>, RedbStoreError> {
+        let txn = self.db.begin_read()?;
+        let slots = txn.open_table(SLOTS)?;
+        Ok(slots.get((&address, &slot))?.map(|v| v.value().0))
+    }
+
+    /// Tombstone `(address, slot)`'s dense index for reuse, removing it
+    /// from `SLOTS` and `DENSE` but leaving every other entry's index
+    /// untouched (no downstream shift).
+    pub fn delete(&self, address: [u8; 20], slot: [u8; 32]) -> Result<bool, RedbStoreError> {
+        let txn = self.db.begin_write()?;
+        let removed = {
+            let mut slots = txn.open_table(SLOTS)?;
+            let mut dense = txn.open_table(DENSE)?;
+            let mut free = txn.open_table(FREE_INDICES)?;
+
+            match slots.remove((&address, &slot))? {
+                Some(existing) => {
+                    let (_, dense_index) = existing.value();
+                    dense.remove(dense_index)?;
+                    free.insert(dense_index, ())?;
+                    true
+                }
+                None => false,
+            }
+        };
+        txn.commit()?;
+        Ok(removed)
+    }
+
+    /// Number of live (non-tombstoned) slots.
+    pub fn len(&self) -> Result<u64, RedbStoreError> {
+        let txn = self.db.begin_read()?;
+        Ok(txn.open_table(SLOTS)?.len()?)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, RedbStoreError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Highest `dense_index` ever assigned, plus one -- i.e. the width the
+    /// dense flat file would have with no compaction, tombstones included.
+    /// Used to decide when [`Self::fragmentation`] warrants a
+    /// [`Self::materialize`].
+    fn allocated_width(&self) -> Result<u64, RedbStoreError> {
+        let txn = self.db.begin_read()?;
+        let dense = txn.open_table(DENSE)?;
+        let free = txn.open_table(FREE_INDICES)?;
+        Ok(dense.len()? + free.len()?)
+    }
+
+    /// Fraction of allocated dense indices that are tombstoned, in
+    /// `[0.0, 1.0]`. `materialize` should be called once this crosses the
+    /// caller's compaction threshold.
+    pub fn fragmentation(&self) -> Result<f64, RedbStoreError> {
+        let txn = self.db.begin_read()?;
+        let free = txn.open_table(FREE_INDICES)?;
+        let width = self.allocated_width()?;
+        if width == 0 {
+            return Ok(0.0);
+        }
+        Ok(free.len()? as f64 / width as f64)
+    }
+
+    fn pop_free_index(free: &mut redb::Table<u64, ()>) -> Result<Option<u64>, RedbStoreError> {
+        let next = free.iter()?.next().transpose()?.map(|(k, _)| k.value());
+        if let Some(index) = next {
+            free.remove(index)?;
+        }
+        Ok(next)
+    }
+
+    fn tombstone_count(free: &redb::Table<u64, ()>) -> Result<u64, RedbStoreError> {
+        Ok(free.len()?)
+    }
+
+    /// Rewrite `database.bin`/`storage-mapping.bin` at `output_dir` as a
+    /// dense, gap-free projection of the store, renumbering every live slot
+    /// to a contiguous `0..len()` range in `(address, slot)` order so the
+    /// `FREE_INDICES` tombstones left by prior deletes are fully reclaimed.
+    /// Also clears `FREE_INDICES` and rewrites `DENSE` to match the new
+    /// numbering, so a subsequent insert resumes at `len()` rather than
+    /// reusing a stale tombstone.
+    pub fn materialize(&self, output_dir: impl AsRef<Path>) -> Result<u64, RedbStoreError> {
+        let output_dir = output_dir.as_ref();
+        std::fs::create_dir_all(output_dir)?;
+
+        let database_path = output_dir.join("database.bin");
+        let mapping_path = output_dir.join("storage-mapping.bin");
+        let mut db_writer = std::io::BufWriter::new(std::fs::File::create(&database_path)?);
+        let mut map_writer = std::io::BufWriter::new(std::fs::File::create(&mapping_path)?);
+
+        let read_txn = self.db.begin_read()?;
+        let slots = read_txn.open_table(SLOTS)?;
+
+        let mut renumbered = Vec::with_capacity(slots.len()? as usize);
+        for entry in slots.iter()? {
+            let (key, value) = entry?;
+            let (address, slot) = key.value();
+            let (val, _old_index) = value.value();
+            renumbered.push((address, slot, val));
+        }
+        drop(slots);
+        drop(read_txn);
+
+        let count = renumbered.len() as u64;
+
+        let write_txn = self.db.begin_write()?;
+        {
+            let mut slots = write_txn.open_table(SLOTS)?;
+            let mut dense = write_txn.open_table(DENSE)?;
+            let mut free = write_txn.open_table(FREE_INDICES)?;
+
+            for key in free.iter()?.map(|r| r.map(|(k, _)| k.value())).collect::<Result<Vec<_>, _>>()? {
+                free.remove(key)?;
+            }
+
+            for (index, (address, slot, value)) in renumbered.iter().enumerate() {
+                let index = index as u64;
+                slots.insert((address, slot), (*value, index))?;
+                dense.insert(index, *value)?;
+
+                std::io::Write::write_all(&mut db_writer, value)?;
+                std::io::Write::write_all(&mut map_writer, address)?;
+                std::io::Write::write_all(&mut map_writer, slot)?;
+                std::io::Write::write_all(&mut map_writer, &(index as u32).to_le_bytes())?;
+            }
+        }
+        write_txn.commit()?;
+
+        db_writer.flush()?;
+        map_writer.flush()?;
+
+        Ok(count)
+    }
+
+    /// Current live entry count, for folding into `metadata.json`/
+    /// `CrsMetadata::entry_count` without a separate flat-file scan.
+    pub fn export_entry_count(&self) -> Result<u64, RedbStoreError> {
+        self.len()
+    }
+
+    /// Write `output_dir/metadata.json` in the same shape `bin/pir_prep.rs`
+    /// writes, with `num_storage_slots` taken from the store's current live
+    /// entry count rather than a one-shot extraction tally. Callers that
+    /// also track `CrsMetadata` should pass this same count to
+    /// `CrsMetadata::new`'s `entry_count`.
+    pub fn export_metadata_json(&self, output_dir: impl AsRef<Path>, chain: &str) -> Result<PathBuf, RedbStoreError> {
+        #[derive(serde::Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct LaneStoreMetadata<'a> {
+            chain: &'a str,
+            num_storage_slots: u64,
+            entry_size: usize,
+            mapping_entry_size: usize,
+            format_version: &'static str,
+        }
+
+        let metadata = LaneStoreMetadata {
+            chain,
+            num_storage_slots: self.export_entry_count()?,
+            entry_size: 32,
+            mapping_entry_size: 56,
+            format_version: "1.0.0",
+        };
+
+        let path = output_dir.as_ref().join("metadata.json");
+        let json = serde_json::to_string_pretty(&metadata).expect("LaneStoreMetadata serializes infallibly");
+        std::fs::write(&path, json)?;
+        Ok(path)
+    }
+}
+
+/// Directory convention for the redb file itself, mirroring how
+/// `bin/pir_prep.rs` names its sibling flat files.
+pub fn store_path(output_dir: impl AsRef<Path>) -> PathBuf {
+    output_dir.as_ref().join("lane-store.redb")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(b: u8) -> [u8; 20] {
+        [b; 20]
+    }
+    fn word(b: u8) -> [u8; 32] {
+        [b; 32]
+    }
+
+    #[test]
+    fn test_upsert_inserts_new_slot() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RedbLaneStore::open(dir.path().join("store.redb")).unwrap();
+
+        let outcome = store.upsert(addr(1), word(1), word(100)).unwrap();
+        assert_eq!(outcome, UpsertOutcome::Inserted { dense_index: 0 });
+        assert_eq!(store.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_upsert_updates_in_place_reusing_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RedbLaneStore::open(dir.path().join("store.redb")).unwrap();
+
+        store.upsert(addr(1), word(1), word(100)).unwrap();
+        let outcome = store.upsert(addr(1), word(1), word(200)).unwrap();
+        assert_eq!(outcome, UpsertOutcome::Updated { dense_index: 0, previous_value: word(100) });
+        assert_eq!(store.len().unwrap(), 1);
+        assert_eq!(store.get(addr(1), word(1)).unwrap(), Some(word(200)));
+    }
+
+    #[test]
+    fn test_delete_tombstones_index_for_reuse() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RedbLaneStore::open(dir.path().join("store.redb")).unwrap();
+
+        store.upsert(addr(1), word(1), word(100)).unwrap();
+        store.upsert(addr(2), word(2), word(200)).unwrap();
+        assert!(store.delete(addr(1), word(1)).unwrap());
+        assert_eq!(store.len().unwrap(), 1);
+
+        let outcome = store.upsert(addr(3), word(3), word(300)).unwrap();
+        assert_eq!(outcome, UpsertOutcome::Inserted { dense_index: 0 });
+    }
+
+    #[test]
+    fn test_fragmentation_reflects_tombstones() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RedbLaneStore::open(dir.path().join("store.redb")).unwrap();
+
+        store.upsert(addr(1), word(1), word(100)).unwrap();
+        store.upsert(addr(2), word(2), word(200)).unwrap();
+        assert_eq!(store.fragmentation().unwrap(), 0.0);
+
+        store.delete(addr(1), word(1)).unwrap();
+        assert_eq!(store.fragmentation().unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_materialize_writes_dense_flat_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RedbLaneStore::open(dir.path().join("store.redb")).unwrap();
+
+        store.upsert(addr(1), word(1), word(100)).unwrap();
+        store.upsert(addr(2), word(2), word(200)).unwrap();
+        store.delete(addr(1), word(1)).unwrap();
+        store.upsert(addr(3), word(3), word(300)).unwrap();
+
+        let out_dir = dir.path().join("out");
+        let count = store.materialize(&out_dir).unwrap();
+        assert_eq!(count, 2);
+
+        let data = std::fs::read(out_dir.join("database.bin")).unwrap();
+        assert_eq!(data.len(), 64);
+
+        let mapping = std::fs::read(out_dir.join("storage-mapping.bin")).unwrap();
+        assert_eq!(mapping.len(), 112);
+
+        assert_eq!(store.fragmentation().unwrap(), 0.0);
+    }
+}
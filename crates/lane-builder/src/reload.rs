@@ -2,7 +2,26 @@
 
 use std::time::Duration;
 
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Distinguishes a server-side slow-request condition (HTTP 408) from every
+/// other reload failure, so [`ReloadClient::reload`]'s retry loop can tell
+/// "the server is overloaded, try again" apart from a genuine error.
+#[derive(Debug, Error)]
+pub enum ReloadError {
+    #[error("Reload request timed out: {0}")]
+    Timeout(String),
+}
+
+/// Whether a `reload`/`health` failure is worth retrying: a classified
+/// [`ReloadError::Timeout`], or a bare transport-level `reqwest::Error`
+/// (connection reset, DNS hiccup). Any other error means the server
+/// understood and rejected the request, so retrying would just repeat it.
+fn is_retryable(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<ReloadError>().is_some() || err.downcast_ref::<reqwest::Error>().is_some()
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReloadResult {
@@ -12,12 +31,19 @@ pub struct ReloadResult {
     pub hot_loaded: bool,
     pub cold_loaded: bool,
     pub mmap_mode: bool,
+    /// Opaque token encoding the block/snapshot this result reflects.
+    /// Pass it back into [`ReloadClient::poll_until`] to wait for the
+    /// *next* snapshot swap instead of busy-polling `reload`/`health`.
+    #[serde(default)]
+    pub causality_token: String,
 }
 
 #[derive(Debug, Clone)]
 pub struct ReloadClient {
     client: reqwest::Client,
     server_url: String,
+    max_retries: u32,
+    backoff_base: Duration,
 }
 
 impl ReloadClient {
@@ -27,25 +53,58 @@ impl ReloadClient {
             .connect_timeout(Duration::from_secs(10))
             .build()
             .expect("Failed to build HTTP client");
-        
+
         Self {
             client,
             server_url: server_url.into(),
+            max_retries: 0,
+            backoff_base: Duration::from_millis(200),
         }
     }
 
+    /// How many times to retry `reload`/`health` on a timeout or transport
+    /// error. `reload` is idempotent from the client's point of view (it
+    /// just asks the server to check for a newer snapshot), so a briefly
+    /// overloaded server doesn't need to abort the whole operation.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Base delay for the retry backoff (doubled per attempt, plus jitter).
+    pub fn backoff(mut self, base: Duration) -> Self {
+        self.backoff_base = base;
+        self
+    }
+
     pub async fn reload(&self) -> anyhow::Result<ReloadResult> {
         let url = format!("{}/admin/reload", self.server_url);
-        
+
+        let mut attempt = 0;
+        loop {
+            match self.reload_once(&url).await {
+                Err(e) if attempt < self.max_retries && is_retryable(&e) => {
+                    attempt += 1;
+                    self.backoff_sleep(attempt).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn reload_once(&self, url: &str) -> anyhow::Result<ReloadResult> {
         let response = self
             .client
-            .post(&url)
+            .post(url)
             .send()
             .await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
+            if status.as_u16() == 408 {
+                anyhow::bail!(ReloadError::Timeout(body));
+            }
             anyhow::bail!("Reload failed with status {}: {}", status, body);
         }
 
@@ -53,12 +112,60 @@ impl ReloadClient {
         Ok(result)
     }
 
+    async fn backoff_sleep(&self, attempt: u32) {
+        let base = self.backoff_base.saturating_mul(1u32 << attempt.min(16));
+        let jitter_bound = (base.as_millis() as u64 / 4).max(1);
+        let jitter = Duration::from_millis(rand::thread_rng().gen_range(0..=jitter_bound));
+        tokio::time::sleep(base + jitter).await;
+    }
+
     pub async fn health(&self) -> anyhow::Result<bool> {
         let url = format!("{}/health", self.server_url);
-        
+
         let response = self.client.get(&url).send().await?;
         Ok(response.status().is_success())
     }
+
+    /// Long-poll for a reload that advances past `after`.
+    ///
+    /// Blocks server-side until the snapshot's causality token advances
+    /// past `after` or `timeout` elapses, returning the new [`ReloadResult`]
+    /// or `None` on timeout. Lets operators and test harnesses efficiently
+    /// await a database swap (e.g. after submitting a delta) instead of
+    /// sleeping in a loop around `reload`/`health`.
+    pub async fn poll_until(
+        &self,
+        after: &str,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<ReloadResult>> {
+        let url = format!("{}/admin/reload/poll", self.server_url);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("after", after.to_string()),
+                ("timeout_ms", timeout.as_millis().to_string()),
+            ])
+            // The server may legitimately block for up to `timeout`; give
+            // the request itself some slack beyond that on top of it.
+            .timeout(timeout + Duration::from_secs(5))
+            .send()
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Reload poll failed with status {}: {}", status, body);
+        }
+
+        let result: ReloadResult = response.json().await?;
+        Ok(Some(result))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,366 @@
+//! HTTP query server over the hot-lane database
+//!
+//! `lane-builder` produces `hot-contracts.json` and a lane manifest but
+//! otherwise has no way to answer "is this contract hot, and what's its
+//! rank/score" without a consumer reloading the whole file. This is a small
+//! REST/JSON service -- modeled on the electrs `rest`/`query` layer and its
+//! `BlockProvider`-style trait -- in front of whatever is currently loaded,
+//! updated in place via [`LaneQueryState::push`] so [`crate::ReloadClient`]
+//! (or an equivalent push from [`crate::exex`]) can refresh it without a
+//! server restart.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use axum::{
+    extract::{Path, Query, State},
+    response::{IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use inspire_core::{Address, HotLaneManifest};
+use serde::{Deserialize, Serialize};
+
+use crate::hybrid_scorer::{ContractSource, ScoredContract};
+
+/// Uniform read interface over hot-lane contract data, regardless of whether
+/// it came from a freshly-run [`crate::HybridScorer`] or was loaded back from
+/// a saved [`HotLaneManifest`]. Both [`ScoredContractIndex`] and
+/// [`ManifestQuery`] implement this so the HTTP handlers don't need to care
+/// which one is currently loaded.
+pub trait LaneQuery: Send + Sync {
+    /// Look up a single contract by address.
+    fn contract(&self, address: &Address) -> Option<ScoredContract>;
+    /// The top `n` contracts by `final_score`, highest first.
+    fn top(&self, n: usize) -> Vec<ScoredContract>;
+    /// The block number this data reflects.
+    fn block_number(&self) -> u64;
+    /// Merkle commitment over the backing contract list, if one exists
+    /// (see [`HotLaneManifest::merkle_root`]). `None` for data that isn't
+    /// backed by a manifest.
+    fn manifest_hash(&self) -> Option<[u8; 32]>;
+}
+
+/// A [`LaneQuery`] backed directly by a [`crate::HybridScorer`] run, with no
+/// manifest (and therefore no Merkle commitment) behind it.
+pub struct ScoredContractIndex {
+    by_address: HashMap<Address, ScoredContract>,
+    ranked: Vec<Address>,
+    block_number: u64,
+}
+
+impl ScoredContractIndex {
+    /// Build an index from scorer output, pre-sorting by `final_score` so
+    /// `top` is a cheap slice instead of a sort per request.
+    pub fn new(block_number: u64, mut contracts: Vec<ScoredContract>) -> Self {
+        contracts.sort_by(|a, b| b.final_score.cmp(&a.final_score));
+        let ranked = contracts.iter().map(|c| c.address).collect();
+        let by_address = contracts.into_iter().map(|c| (c.address, c)).collect();
+        Self { by_address, ranked, block_number }
+    }
+}
+
+impl LaneQuery for ScoredContractIndex {
+    fn contract(&self, address: &Address) -> Option<ScoredContract> {
+        self.by_address.get(address).cloned()
+    }
+
+    fn top(&self, n: usize) -> Vec<ScoredContract> {
+        self.ranked
+            .iter()
+            .take(n)
+            .filter_map(|addr| self.by_address.get(addr).cloned())
+            .collect()
+    }
+
+    fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    fn manifest_hash(&self) -> Option<[u8; 32]> {
+        None
+    }
+}
+
+/// A [`LaneQuery`] backed by an on-disk [`HotLaneManifest`], reconstructing
+/// a [`ScoredContract`] per [`inspire_core::HotContract`] from whatever the
+/// manifest itself tracks (no gas/category score breakdown survives a
+/// save/load round trip, so those fields come back as defaults -- see
+/// [`ContractSource::Manifest`]).
+pub struct ManifestQuery {
+    manifest: HotLaneManifest,
+    by_address: HashMap<Address, usize>,
+    ranked: Vec<usize>,
+}
+
+impl ManifestQuery {
+    pub fn new(manifest: HotLaneManifest) -> Self {
+        let by_address = manifest
+            .contracts
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.address, i))
+            .collect();
+
+        let mut ranked: Vec<usize> = (0..manifest.contracts.len()).collect();
+        ranked.sort_by(|&a, &b| {
+            manifest.contracts[b]
+                .access_ewma
+                .total_cmp(&manifest.contracts[a].access_ewma)
+        });
+
+        Self { manifest, by_address, ranked }
+    }
+
+    fn to_scored(&self, index: usize) -> ScoredContract {
+        let c = &self.manifest.contracts[index];
+        ScoredContract {
+            address: c.address,
+            name: Some(c.name.clone()),
+            category: Some(c.category.clone()),
+            gas_score: 0,
+            weighted_gas: 0,
+            gas_buckets: Vec::new(),
+            priority_boost: 0,
+            category_weight: 1.0,
+            final_score: c.access_ewma as u64,
+            tx_count: c.access_count,
+            source: ContractSource::Manifest,
+        }
+    }
+}
+
+impl LaneQuery for ManifestQuery {
+    fn contract(&self, address: &Address) -> Option<ScoredContract> {
+        self.by_address.get(address).map(|&i| self.to_scored(i))
+    }
+
+    fn top(&self, n: usize) -> Vec<ScoredContract> {
+        self.ranked.iter().take(n).map(|&i| self.to_scored(i)).collect()
+    }
+
+    fn block_number(&self) -> u64 {
+        self.manifest.block_number
+    }
+
+    fn manifest_hash(&self) -> Option<[u8; 32]> {
+        self.manifest.merkle_root
+    }
+}
+
+/// Lock-free, swappable handle to whatever [`LaneQuery`] is currently
+/// loaded, mirroring `inspire_server::state::ServerState`'s `ArcSwap`-backed
+/// snapshot pattern. [`push`](Self::push) is how a reload (triggered by
+/// [`crate::ReloadClient`] or the [`crate::exex`] live-partitioner) makes
+/// the server reflect new data without a restart.
+pub struct LaneQueryState {
+    current: ArcSwap<dyn LaneQuery>,
+}
+
+impl LaneQueryState {
+    pub fn new(query: Arc<dyn LaneQuery>) -> Self {
+        Self { current: ArcSwap::from(query) }
+    }
+
+    /// Atomically swap in a new [`LaneQuery`] implementation.
+    pub fn push(&self, query: Arc<dyn LaneQuery>) {
+        self.current.store(query);
+    }
+
+    fn load(&self) -> Arc<dyn LaneQuery> {
+        self.current.load_full()
+    }
+}
+
+/// Shared server state, passed as axum `State`.
+pub type SharedLaneQueryState = Arc<LaneQueryState>;
+
+#[derive(Serialize)]
+struct ContractResponse {
+    #[serde(with = "hex_address")]
+    address: Address,
+    name: Option<String>,
+    category: Option<String>,
+    gas_score: u64,
+    tx_count: u64,
+    final_score: u64,
+    source: ContractSource,
+}
+
+impl From<ScoredContract> for ContractResponse {
+    fn from(c: ScoredContract) -> Self {
+        Self {
+            address: c.address,
+            name: c.name,
+            category: c.category,
+            gas_score: c.gas_score,
+            tx_count: c.tx_count,
+            final_score: c.final_score,
+            source: c.source,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TopQuery {
+    #[serde(default = "default_top_n")]
+    top_n: usize,
+}
+
+fn default_top_n() -> usize {
+    100
+}
+
+#[derive(Serialize)]
+struct ManifestResponse {
+    block_number: u64,
+    merkle_root: Option<String>,
+}
+
+async fn get_contract(
+    State(state): State<SharedLaneQueryState>,
+    Path(address): Path<String>,
+) -> Response {
+    let Ok(address) = parse_address(&address) else {
+        return (axum::http::StatusCode::BAD_REQUEST, "invalid address").into_response();
+    };
+
+    match state.load().contract(&address) {
+        Some(contract) => Json(ContractResponse::from(contract)).into_response(),
+        None => (axum::http::StatusCode::NOT_FOUND, "contract not in hot lane").into_response(),
+    }
+}
+
+async fn get_lane_contracts(
+    State(state): State<SharedLaneQueryState>,
+    Path(lane): Path<String>,
+    Query(query): Query<TopQuery>,
+) -> Response {
+    if lane != "hot" {
+        return (axum::http::StatusCode::NOT_FOUND, "unknown lane").into_response();
+    }
+
+    let contracts: Vec<ContractResponse> =
+        state.load().top(query.top_n).into_iter().map(ContractResponse::from).collect();
+    Json(contracts).into_response()
+}
+
+async fn get_manifest(State(state): State<SharedLaneQueryState>) -> Json<ManifestResponse> {
+    let query = state.load();
+    Json(ManifestResponse {
+        block_number: query.block_number(),
+        merkle_root: query.manifest_hash().map(|root| format!("0x{}", hex::encode(root))),
+    })
+}
+
+fn parse_address(s: &str) -> Result<Address, hex::FromHexError> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let bytes = hex::decode(s)?;
+    bytes.try_into().map_err(|_| hex::FromHexError::InvalidStringLength)
+}
+
+/// Build the router: `GET /contract/:address`, `GET /lane/:id/contracts`
+/// (`?top_n=`, default 100), `GET /manifest`.
+pub fn create_router(state: SharedLaneQueryState) -> Router {
+    Router::new()
+        .route("/contract/:address", get(get_contract))
+        .route("/lane/:id/contracts", get(get_lane_contracts))
+        .route("/manifest", get(get_manifest))
+        .with_state(state)
+}
+
+/// Run the lane query server on `addr` until the process is killed.
+pub async fn serve(state: SharedLaneQueryState, addr: SocketAddr) -> anyhow::Result<()> {
+    let router = create_router(state);
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    tracing::info!("Starting lane query server on {}", addr);
+    axum::serve(listener, router).await?;
+    Ok(())
+}
+
+mod hex_address {
+    use inspire_core::Address;
+    use serde::{self, Serializer};
+
+    pub fn serialize<S>(address: &Address, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("0x{}", hex::encode(address)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_contract(address: Address, final_score: u64) -> ScoredContract {
+        ScoredContract {
+            address,
+            name: Some("Test".to_string()),
+            category: Some("defi".to_string()),
+            gas_score: final_score,
+            weighted_gas: final_score,
+            gas_buckets: vec![final_score],
+            priority_boost: 0,
+            category_weight: 1.0,
+            final_score,
+            tx_count: 1,
+            source: ContractSource::GasBackfill,
+        }
+    }
+
+    #[test]
+    fn test_scored_contract_index_top_is_sorted_desc() {
+        let a = sample_contract([1u8; 20], 10);
+        let b = sample_contract([2u8; 20], 50);
+        let index = ScoredContractIndex::new(100, vec![a, b.clone()]);
+
+        let top = index.top(1);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].address, b.address);
+    }
+
+    #[test]
+    fn test_scored_contract_index_contract_lookup() {
+        let a = sample_contract([1u8; 20], 10);
+        let index = ScoredContractIndex::new(100, vec![a.clone()]);
+
+        assert!(index.contract(&a.address).is_some());
+        assert!(index.contract(&[9u8; 20]).is_none());
+        assert_eq!(index.manifest_hash(), None);
+    }
+
+    #[test]
+    fn test_manifest_query_reflects_access_ewma_ranking() {
+        let mut manifest = HotLaneManifest::new(42);
+        manifest.add_contract([1u8; 20], "Low".to_string(), 4, "token".to_string());
+        manifest.add_contract([2u8; 20], "High".to_string(), 4, "token".to_string());
+        manifest.contracts[0].access_ewma = 1.0;
+        manifest.contracts[1].access_ewma = 5.0;
+        manifest.commit_merkle_root();
+
+        let query = ManifestQuery::new(manifest);
+        let top = query.top(1);
+        assert_eq!(top[0].address, [2u8; 20]);
+        assert_eq!(top[0].source, ContractSource::Manifest);
+        assert_eq!(query.block_number(), 42);
+        assert!(query.manifest_hash().is_some());
+    }
+
+    #[test]
+    fn test_lane_query_state_push_replaces_loaded_data() {
+        let initial = ScoredContractIndex::new(1, vec![sample_contract([1u8; 20], 1)]);
+        let state = LaneQueryState::new(Arc::new(initial));
+        assert!(state.load().contract(&[1u8; 20]).is_some());
+
+        let replacement = ScoredContractIndex::new(2, vec![sample_contract([2u8; 20], 1)]);
+        state.push(Arc::new(replacement));
+
+        assert!(state.load().contract(&[1u8; 20]).is_none());
+        assert!(state.load().contract(&[2u8; 20]).is_some());
+        assert_eq!(state.load().block_number(), 2);
+    }
+}
@@ -4,7 +4,10 @@
 
 use std::path::Path;
 
-use inspire_core::{HotLaneManifest, TwoLaneConfig, CrsMetadata, PirParams, PIR_PARAMS_VERSION};
+use inspire_core::{
+    sign_crs, CrsMetadata, CrsSigningKey, HotLaneManifest, PirParams, TwoLaneConfig,
+    PIR_PARAMS_VERSION,
+};
 use inspire_pir::{
     setup as pir_setup,
     InspireParams, SecurityLevel,
@@ -26,6 +29,65 @@ fn to_pir_params(p: &InspireParams) -> PirParams {
     }
 }
 
+/// Magic bytes prefixing a binary-framed CRS/encoded-database/secret-key
+/// file, so a loader can tell a `SerFormat::Binary` file apart from
+/// `SerFormat::Json` (which always starts with `{`) without being told
+/// which format it is up front.
+const BINARY_MAGIC: [u8; 4] = *b"PIRB";
+
+/// On-disk serialization format for CRS, encoded database, and secret key
+/// files.
+///
+/// `Json` produces large, slow-to-parse files for Ethereum-scale encoded
+/// databases (millions of 32-byte slots) but stays human-inspectable for
+/// debugging. `Binary` length-prefixes each field via `bincode` behind a
+/// magic-byte header, cutting both file size and load latency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SerFormat {
+    #[default]
+    Json,
+    Binary,
+}
+
+fn write_framed<T: serde::Serialize>(value: &T, path: &Path, format: SerFormat) -> anyhow::Result<()> {
+    match format {
+        SerFormat::Json => {
+            let json = serde_json::to_string(value)?;
+            std::fs::write(path, json)?;
+        }
+        SerFormat::Binary => {
+            let mut bytes = BINARY_MAGIC.to_vec();
+            bytes.extend(bincode::serialize(value)?);
+            std::fs::write(path, bytes)?;
+        }
+    }
+    Ok(())
+}
+
+/// Drain `reader` into a `Vec<u8>` in fixed-size chunks rather than
+/// requiring the caller to size a buffer up front.
+fn read_all(mut reader: impl std::io::Read) -> anyhow::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        out.extend_from_slice(&buf[..n]);
+    }
+    Ok(out)
+}
+
+fn read_framed<T: serde::de::DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
+    let bytes = std::fs::read(path)?;
+    if bytes.starts_with(&BINARY_MAGIC) {
+        Ok(bincode::deserialize(&bytes[BINARY_MAGIC.len()..])?)
+    } else {
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
 /// Get current timestamp in ISO 8601 format
 fn now_iso8601() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -65,6 +127,8 @@ pub struct TwoLaneSetup {
     entry_size: usize,
     manifest: Option<HotLaneManifest>,
     params: InspireParams,
+    format: SerFormat,
+    signing_key: Option<CrsSigningKey>,
     #[cfg(any(test, feature = "dev-keys"))]
     secret_key_path: Option<std::path::PathBuf>,
 }
@@ -79,6 +143,8 @@ impl TwoLaneSetup {
             entry_size: 32,
             manifest: None,
             params: default_params(),
+            format: SerFormat::default(),
+            signing_key: None,
             #[cfg(any(test, feature = "dev-keys"))]
             secret_key_path: None,
         }
@@ -96,6 +162,45 @@ impl TwoLaneSetup {
         self
     }
 
+    /// Read the hot lane data from a `Read` source instead of requiring the
+    /// caller to already hold it as a `Vec<u8>` -- e.g. a file handle or a
+    /// state-export stream piped directly into the builder.
+    ///
+    /// Note: `pir_setup` (from the external `inspire_pir` crate) only
+    /// accepts a single contiguous `&[u8]`, so this still materializes the
+    /// whole lane in memory by the time [`Self::build`] runs; it avoids the
+    /// caller needing a separate buffer/copy before calling in, not the
+    /// O(total lane size) peak memory itself.
+    pub fn hot_reader(mut self, reader: impl std::io::Read) -> anyhow::Result<Self> {
+        self.hot_data = read_all(reader)?;
+        Ok(self)
+    }
+
+    /// Read the cold lane data from a `Read` source. See [`Self::hot_reader`]
+    /// for the streaming caveat.
+    pub fn cold_reader(mut self, reader: impl std::io::Read) -> anyhow::Result<Self> {
+        self.cold_data = read_all(reader)?;
+        Ok(self)
+    }
+
+    /// Set the hot lane data from an iterator of fixed-size entries,
+    /// setting `entry_size` to `N` and computing the entry count from
+    /// however many items the iterator yields rather than from
+    /// `data.len() / entry_size`.
+    pub fn hot_entries<const N: usize>(mut self, entries: impl Iterator<Item = [u8; N]>) -> Self {
+        self.hot_data = entries.flatten().collect();
+        self.entry_size = N;
+        self
+    }
+
+    /// Set the cold lane data from an iterator of fixed-size entries. See
+    /// [`Self::hot_entries`].
+    pub fn cold_entries<const N: usize>(mut self, entries: impl Iterator<Item = [u8; N]>) -> Self {
+        self.cold_data = entries.flatten().collect();
+        self.entry_size = N;
+        self
+    }
+
     /// Set entry size (default: 32 bytes for Ethereum storage slots)
     pub fn entry_size(mut self, size: usize) -> Self {
         self.entry_size = size;
@@ -114,6 +219,23 @@ impl TwoLaneSetup {
         self
     }
 
+    /// Set the on-disk serialization format for CRS/encoded-database/secret-key
+    /// files (default: [`SerFormat::Json`])
+    pub fn format(mut self, format: SerFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sign each lane's `crs.json` + `crs.meta.json` with `key` after
+    /// writing them, embedding the verifying key in `CrsMetadata` and
+    /// writing a detached `crs.sig` alongside `crs.json`. Clients check
+    /// `crs.sig` via [`inspire_core::verify_crs_signature`] to detect a
+    /// tampered-with or swapped-in-transit CRS.
+    pub fn sign_with(mut self, key: CrsSigningKey) -> Self {
+        self.signing_key = Some(key);
+        self
+    }
+
     /// Emit secret key to a specified path (for testing/development only)
     ///
     /// # Security Warning
@@ -179,11 +301,11 @@ impl TwoLaneSetup {
             &mut sampler,
         ).map_err(|e| anyhow::anyhow!("{}", e))?;
 
-        save_crs(&hot_crs, &hot_dir.join("crs.json"))?;
-        save_db(&hot_db, &hot_dir.join("encoded.json"))?;
-        
-        save_crs(&cold_crs, &cold_dir.join("crs.json"))?;
-        save_db(&cold_db, &cold_dir.join("encoded.json"))?;
+        save_crs(&hot_crs, &hot_dir.join("crs.json"), self.format)?;
+        save_db(&hot_db, &hot_dir.join("encoded.json"), self.format)?;
+
+        save_crs(&cold_crs, &cold_dir.join("crs.json"), self.format)?;
+        save_db(&cold_db, &cold_dir.join("encoded.json"), self.format)?;
 
         let pir_params = to_pir_params(&self.params);
         let generated_by = format!("lane-builder {}", env!("CARGO_PKG_VERSION"));
@@ -192,7 +314,9 @@ impl TwoLaneSetup {
         let hot_entries = (self.hot_data.len() / self.entry_size) as u64;
         let cold_entries = (self.cold_data.len() / self.entry_size) as u64;
 
-        let hot_meta = CrsMetadata::new(
+        let verifying_key = self.signing_key.as_ref().map(CrsSigningKey::verifying_key);
+
+        let mut hot_meta = CrsMetadata::new(
             &pir_params,
             self.entry_size,
             hot_entries,
@@ -200,9 +324,12 @@ impl TwoLaneSetup {
             &generated_by,
             &generated_at,
         );
+        if let Some(key) = &verifying_key {
+            hot_meta = hot_meta.with_verifying_key(key.clone());
+        }
         hot_meta.save(&hot_dir.join("crs.meta.json"))?;
 
-        let cold_meta = CrsMetadata::new(
+        let mut cold_meta = CrsMetadata::new(
             &pir_params,
             self.entry_size,
             cold_entries,
@@ -210,6 +337,9 @@ impl TwoLaneSetup {
             &generated_by,
             &generated_at,
         );
+        if let Some(key) = &verifying_key {
+            cold_meta = cold_meta.with_verifying_key(key.clone());
+        }
         cold_meta.save(&cold_dir.join("crs.meta.json"))?;
 
         tracing::info!(
@@ -217,6 +347,22 @@ impl TwoLaneSetup {
             "Generated CRS metadata sidecars"
         );
 
+        if let Some(key) = &self.signing_key {
+            sign_crs(
+                key,
+                &hot_dir.join("crs.json"),
+                &hot_dir.join("crs.meta.json"),
+                &hot_dir.join("crs.sig"),
+            )?;
+            sign_crs(
+                key,
+                &cold_dir.join("crs.json"),
+                &cold_dir.join("crs.meta.json"),
+                &cold_dir.join("crs.sig"),
+            )?;
+            tracing::info!("Signed CRS sidecars for both lanes");
+        }
+
         if let Some(manifest) = &self.manifest {
             manifest.save(&hot_dir.join("manifest.json"))?;
         }
@@ -226,7 +372,7 @@ impl TwoLaneSetup {
         if let Some(sk_path) = &self.secret_key_path {
             // For testing, we need a key - regenerate one since we discarded the setup keys
             let test_sk = RlweSecretKey::generate(&self.params, &mut sampler);
-            save_secret_key(&test_sk, sk_path)?;
+            save_secret_key(&test_sk, sk_path, self.format)?;
             tracing::warn!(
                 path = %sk_path.display(),
                 "Secret key saved for testing - DO NOT use in production"
@@ -281,30 +427,33 @@ pub fn test_params() -> InspireParams {
     }
 }
 
-fn save_crs(crs: &ServerCrs, path: &Path) -> anyhow::Result<()> {
-    let json = serde_json::to_string(crs)?;
-    std::fs::write(path, json)?;
-    Ok(())
+fn save_crs(crs: &ServerCrs, path: &Path, format: SerFormat) -> anyhow::Result<()> {
+    write_framed(crs, path, format)
 }
 
-fn save_db(db: &EncodedDatabase, path: &Path) -> anyhow::Result<()> {
-    let json = serde_json::to_string(db)?;
-    std::fs::write(path, json)?;
-    Ok(())
+fn save_db(db: &EncodedDatabase, path: &Path, format: SerFormat) -> anyhow::Result<()> {
+    write_framed(db, path, format)
 }
 
 #[cfg(any(test, feature = "dev-keys"))]
-fn save_secret_key(sk: &RlweSecretKey, path: &Path) -> anyhow::Result<()> {
-    let json = serde_json::to_string(sk)?;
-    std::fs::write(path, json)?;
-    Ok(())
+fn save_secret_key(sk: &RlweSecretKey, path: &Path, format: SerFormat) -> anyhow::Result<()> {
+    write_framed(sk, path, format)
+}
+
+/// Load a CRS from disk, auto-detecting JSON vs [`SerFormat::Binary`]
+pub fn load_crs(path: &Path) -> anyhow::Result<ServerCrs> {
+    read_framed(path)
+}
+
+/// Load an encoded database from disk, auto-detecting JSON vs
+/// [`SerFormat::Binary`]
+pub fn load_db(path: &Path) -> anyhow::Result<EncodedDatabase> {
+    read_framed(path)
 }
 
-/// Load a secret key from disk
+/// Load a secret key from disk, auto-detecting JSON vs [`SerFormat::Binary`]
 pub fn load_secret_key(path: &Path) -> anyhow::Result<RlweSecretKey> {
-    let json = std::fs::read_to_string(path)?;
-    let sk: RlweSecretKey = serde_json::from_str(&json)?;
-    Ok(sk)
+    read_framed(path)
 }
 
 #[cfg(test)]
@@ -348,6 +497,101 @@ mod tests {
         assert!(hot_meta.validate().is_ok());
     }
 
+    #[test]
+    fn test_binary_format_roundtrip() {
+        let dir = tempdir().unwrap();
+
+        let hot_data: Vec<u8> = (0..256 * 32).map(|i| (i % 256) as u8).collect();
+        let cold_data: Vec<u8> = (0..256 * 32).map(|i| ((i + 1) % 256) as u8).collect();
+
+        let result = TwoLaneSetup::new(dir.path())
+            .hot_data(hot_data)
+            .cold_data(cold_data)
+            .entry_size(32)
+            .params(test_params())
+            .format(SerFormat::Binary)
+            .build()
+            .unwrap();
+
+        // Binary-framed files don't start with JSON's `{`
+        let raw = std::fs::read(dir.path().join("hot/crs.json")).unwrap();
+        assert!(raw.starts_with(&BINARY_MAGIC));
+
+        let loaded_crs = load_crs(&dir.path().join("hot/crs.json")).unwrap();
+        let loaded_db = load_db(&dir.path().join("hot/encoded.json")).unwrap();
+        assert_eq!(
+            serde_json::to_string(&loaded_crs).unwrap(),
+            serde_json::to_string(&result.hot_crs).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_string(&loaded_db).unwrap(),
+            serde_json::to_string(&result.hot_db).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_json_and_binary_loaders_auto_detect() {
+        let dir = tempdir().unwrap();
+        let json_path = dir.path().join("crs_json.bin");
+        let binary_path = dir.path().join("crs_binary.bin");
+
+        let params = test_params();
+        let mut sampler = GaussianSampler::new(params.sigma);
+        let data: Vec<u8> = (0..32).collect();
+        let (crs, _db, _sk) = pir_setup(&params, &data, 32, &mut sampler)
+            .map_err(|e| anyhow::anyhow!("{}", e))
+            .unwrap();
+
+        save_crs(&crs, &json_path, SerFormat::Json).unwrap();
+        save_crs(&crs, &binary_path, SerFormat::Binary).unwrap();
+
+        let from_json = load_crs(&json_path).unwrap();
+        let from_binary = load_crs(&binary_path).unwrap();
+        assert_eq!(
+            serde_json::to_string(&from_json).unwrap(),
+            serde_json::to_string(&from_binary).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_reader_ingestion_matches_vec_ingestion() {
+        let dir = tempdir().unwrap();
+
+        let hot_data: Vec<u8> = (0..256 * 32).map(|i| (i % 256) as u8).collect();
+        let cold_data: Vec<u8> = (0..256 * 32).map(|i| ((i + 1) % 256) as u8).collect();
+
+        let result = TwoLaneSetup::new(dir.path())
+            .hot_reader(hot_data.as_slice())
+            .unwrap()
+            .cold_reader(cold_data.as_slice())
+            .unwrap()
+            .entry_size(32)
+            .params(test_params())
+            .build()
+            .unwrap();
+
+        assert_eq!(result.config.hot_entries, 256);
+        assert_eq!(result.config.cold_entries, 256);
+    }
+
+    #[test]
+    fn test_entry_iterator_ingestion_sets_entry_size() {
+        let dir = tempdir().unwrap();
+
+        let hot_entries = (0..16u8).map(|i| [i; 32]);
+        let cold_entries = (0..16u8).map(|i| [i.wrapping_add(1); 32]);
+
+        let result = TwoLaneSetup::new(dir.path())
+            .hot_entries(hot_entries)
+            .cold_entries(cold_entries)
+            .params(test_params())
+            .build()
+            .unwrap();
+
+        assert_eq!(result.config.hot_entries, 16);
+        assert_eq!(result.config.cold_entries, 16);
+    }
+
     #[test]
     fn test_emit_secret_key() {
         // Use separate directories for PIR data and secret key
@@ -393,4 +637,36 @@ mod tests {
             .build()
             .unwrap();
     }
+
+    #[test]
+    fn test_sign_with_writes_verifiable_crs_sig() {
+        let dir = tempdir().unwrap();
+
+        let hot_data: Vec<u8> = (0..256 * 32).map(|i| (i % 256) as u8).collect();
+        let cold_data: Vec<u8> = (0..256 * 32).map(|i| ((i + 1) % 256) as u8).collect();
+        let key = CrsSigningKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[9u8; 32]));
+
+        TwoLaneSetup::new(dir.path())
+            .hot_data(hot_data)
+            .cold_data(cold_data)
+            .entry_size(32)
+            .params(test_params())
+            .sign_with(key)
+            .build()
+            .unwrap();
+
+        assert!(dir.path().join("hot/crs.sig").exists());
+        assert!(dir.path().join("cold/crs.sig").exists());
+
+        let hot_meta = CrsMetadata::load(dir.path().join("hot/crs.meta.json")).unwrap();
+        let verifying_key = hot_meta.verifying_key.expect("signed CRS should embed a verifying key");
+
+        inspire_core::verify_crs_signature(
+            &verifying_key,
+            &dir.path().join("hot/crs.json"),
+            &dir.path().join("hot/crs.meta.json"),
+            &dir.path().join("hot/crs.sig"),
+        )
+        .expect("crs.sig should verify against the embedded key");
+    }
 }
@@ -0,0 +1,16 @@
+//! Re-export of the shared `state_proof` crate's MPT proof verifier.
+//!
+//! [`BalanceExtractor`](crate::BalanceExtractor) fetches balances via plain
+//! `eth_getBalance`/`eth_call` RPCs today, which means a lying or stale RPC
+//! silently corrupts the hot lane database -- PIR protects the *query*, not
+//! the data it was built from. [`verify_account_proof`]/[`verify_storage_proof`]
+//! close that gap by independently re-checking an `eth_getProof` response
+//! against a state root the caller already trusts; see the `state_proof`
+//! crate for the actual trie-walking implementation, which is shared with
+//! `inspire-updater` rather than duplicated here.
+//!
+//! Kept as a same-named module (rather than having callers depend on
+//! `state_proof` directly) so `crate::state_proof::*`/the `lane_builder`
+//! re-exports in `lib.rs` don't need to change.
+
+pub use state_proof::{verify_account_proof, verify_storage_proof, StateProofError, TrieAccount};
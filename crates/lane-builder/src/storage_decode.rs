@@ -0,0 +1,224 @@
+//! RLP/DUPSORT decoding for reth's `PlainStorageState` MDBX table
+//!
+//! `PlainStorageState` is a DUPSORT table: one 20-byte address key maps to
+//! *many* duplicate `(slot, value)` entries, sorted by the 32-byte B256
+//! slot key that prefixes each duplicate's value. `bin/pir_prep.rs`'s
+//! cursor walk is what decides how to step through that -- plain
+//! `MDBX_NEXT` treats every duplicate as if it were a new address, and an
+//! RLP value shorter than a bare single-string encoding gets silently
+//! skipped as "too short" rather than decoded -- so this module only owns
+//! the decode step: splitting one duplicate's value into its slot key and
+//! RLP tail, and decoding that tail into a canonical 32-byte big-endian
+//! value. It also tracks the malformed/skipped counts the caller folds
+//! into its end-of-run report.
+
+use thiserror::Error;
+
+/// Outcome of decoding one `PlainStorageState` duplicate, for the
+/// per-run [`ExtractionReport`].
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    #[error("DUPSORT value too short for a 32-byte slot key: {0} bytes")]
+    MissingSlotKey(usize),
+    #[error("RLP value is empty")]
+    EmptyRlp,
+    #[error("RLP truncated: expected {expected} more bytes, got {actual}")]
+    Truncated { expected: usize, actual: usize },
+    #[error("RLP value too large: {0} bytes (max 32)")]
+    TooLarge(usize),
+    #[error("unexpected RLP list type: 0x{0:02x}")]
+    UnexpectedList(u8),
+    #[error("non-canonical RLP encoding: {len}-byte string has a leading zero byte")]
+    NonCanonicalEncoding { len: usize },
+}
+
+/// Split one `PlainStorageState` DUPSORT duplicate's value into its
+/// 32-byte B256 slot key and the RLP-encoded `U256` storage value that
+/// follows it.
+pub fn split_storage_dup_value(value: &[u8]) -> Result<([u8; 32], &[u8]), DecodeError> {
+    if value.len() < 32 {
+        return Err(DecodeError::MissingSlotKey(value.len()));
+    }
+    let mut slot = [0u8; 32];
+    slot.copy_from_slice(&value[0..32]);
+    Ok((slot, &value[32..]))
+}
+
+/// Decode an RLP-encoded `U256` into a canonical left-padded 32-byte
+/// big-endian array.
+///
+/// Handles every single-value RLP shape that can appear here: the empty
+/// string (`0x80`, value zero -- reth prunes zero-valued slots from
+/// `PlainStorageState`, but a dump taken mid-prune or from a differently
+/// configured node can still emit one), a single byte encoded as itself,
+/// short strings (length in the prefix byte), and long strings (a
+/// multi-byte length prefix), rejecting a leading zero byte in the
+/// encoded string -- RLP's canonical form never has one -- so a
+/// non-canonical encoding is reported as malformed instead of silently
+/// accepted.
+pub fn decode_rlp_u256(data: &[u8]) -> Result<[u8; 32], DecodeError> {
+    if data.is_empty() {
+        return Err(DecodeError::EmptyRlp);
+    }
+
+    let first = data[0];
+    let mut result = [0u8; 32];
+
+    if first == 0x80 {
+        return Ok(result);
+    }
+
+    if first < 0x80 {
+        result[31] = first;
+        return Ok(result);
+    }
+
+    if first <= 0xb7 {
+        let len = (first - 0x80) as usize;
+        return decode_fixed_string(&data[1..], len, &mut result).map(|()| result);
+    }
+
+    if first <= 0xbf {
+        let len_of_len = (first - 0xb7) as usize;
+        if data.len() < 1 + len_of_len {
+            return Err(DecodeError::Truncated { expected: len_of_len, actual: data.len() - 1 });
+        }
+        let mut len = 0usize;
+        for &b in &data[1..1 + len_of_len] {
+            len = (len << 8) | (b as usize);
+        }
+        return decode_fixed_string(&data[1 + len_of_len..], len, &mut result).map(|()| result);
+    }
+
+    Err(DecodeError::UnexpectedList(first))
+}
+
+/// Decode the `len`-byte string body starting at `rest` into `result`'s
+/// low-order bytes, rejecting a non-canonical leading zero (an RLP string
+/// encoder never emits one -- a single `0x00` byte standing for zero would
+/// instead be encoded as the empty string `0x80`).
+fn decode_fixed_string(rest: &[u8], len: usize, result: &mut [u8; 32]) -> Result<(), DecodeError> {
+    if rest.len() < len {
+        return Err(DecodeError::Truncated { expected: len, actual: rest.len() });
+    }
+    if len > 32 {
+        return Err(DecodeError::TooLarge(len));
+    }
+    if len > 0 && rest[0] == 0x00 {
+        return Err(DecodeError::NonCanonicalEncoding { len });
+    }
+    let start = 32 - len;
+    result[start..].copy_from_slice(&rest[..len]);
+    Ok(())
+}
+
+/// Tally of decode outcomes across one `pir-prep` extraction run, so
+/// operators can check the total against the node's reported slot count
+/// before trusting the extracted `database.bin`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ExtractionReport {
+    pub decoded: u64,
+    pub malformed: u64,
+    pub skipped: u64,
+}
+
+impl ExtractionReport {
+    pub fn record_decoded(&mut self) {
+        self.decoded += 1;
+    }
+
+    pub fn record_malformed(&mut self) {
+        self.malformed += 1;
+    }
+
+    pub fn record_skipped(&mut self) {
+        self.skipped += 1;
+    }
+
+    pub fn total(&self) -> u64 {
+        self.decoded + self.malformed + self.skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_storage_dup_value() {
+        let mut value = vec![0x11; 32];
+        value.extend_from_slice(&[0x80]); // RLP-encoded zero
+        let (slot, rlp) = split_storage_dup_value(&value).unwrap();
+        assert_eq!(slot, [0x11; 32]);
+        assert_eq!(rlp, &[0x80]);
+    }
+
+    #[test]
+    fn test_split_storage_dup_value_too_short() {
+        let value = [0u8; 10];
+        assert!(split_storage_dup_value(&value).is_err());
+    }
+
+    #[test]
+    fn test_decode_empty_string_is_zero() {
+        assert_eq!(decode_rlp_u256(&[0x80]).unwrap(), [0u8; 32]);
+    }
+
+    #[test]
+    fn test_decode_single_byte_value() {
+        assert_eq!(decode_rlp_u256(&[0x2a]).unwrap()[31], 0x2a);
+    }
+
+    #[test]
+    fn test_decode_short_string() {
+        let mut data = vec![0x82]; // 2-byte string
+        data.extend_from_slice(&[0x01, 0x00]);
+        let decoded = decode_rlp_u256(&data).unwrap();
+        assert_eq!(&decoded[30..], &[0x01, 0x00]);
+    }
+
+    #[test]
+    fn test_decode_long_string() {
+        let mut value = vec![0u8; 32];
+        value[0] = 0xff;
+        let mut data = vec![0xb8, 0x20]; // long string, 1-byte length = 32
+        data.extend_from_slice(&value);
+        let decoded = decode_rlp_u256(&data).unwrap();
+        assert_eq!(decoded, value.as_slice());
+    }
+
+    #[test]
+    fn test_decode_rejects_oversized_value() {
+        let mut data = vec![0xb8, 0x21]; // claims 33 bytes
+        data.extend(std::iter::repeat(0u8).take(33));
+        assert!(decode_rlp_u256(&data).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_non_canonical_leading_zero() {
+        let mut data = vec![0x82]; // 2-byte string with a leading zero
+        data.extend_from_slice(&[0x00, 0x01]);
+        assert!(decode_rlp_u256(&data).is_err());
+    }
+
+    #[test]
+    fn test_decode_truncated_is_malformed() {
+        assert!(decode_rlp_u256(&[0x83, 0x01]).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_list_type() {
+        assert!(decode_rlp_u256(&[0xc2, 0x01, 0x02]).is_err());
+    }
+
+    #[test]
+    fn test_extraction_report_totals() {
+        let mut report = ExtractionReport::default();
+        report.record_decoded();
+        report.record_decoded();
+        report.record_malformed();
+        report.record_skipped();
+        assert_eq!(report.total(), 4);
+        assert_eq!(report.decoded, 2);
+    }
+}
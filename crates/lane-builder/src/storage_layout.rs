@@ -0,0 +1,152 @@
+//! Solidity storage-layout import (`solc --storage-layout` / `forge
+//! inspect storage-layout` JSON)
+//!
+//! [`ContractExtractor::build_manifest`] otherwise has no way to size a
+//! contract's manifest entry beyond a flat `1000`-slot guess or whatever
+//! `update_slots` happened to observe on-chain. When a contract's compiled
+//! storage layout is available, its `storage`/`types` sections tell us
+//! exactly how many slots its scalar and struct members occupy -- a
+//! `mapping`/dynamic-array member's keys can't be enumerated statically,
+//! so those are left out of [`StorageLayout::base_slots`] entirely and are
+//! expected to keep being tracked dynamically via `update_slots`/
+//! `update_hot_slots` as they're observed on-chain.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use inspire_core::Address;
+use serde::{Deserialize, Serialize};
+
+/// A contract's statically-known storage footprint, derived from a solc
+/// storage-layout JSON. `base_slots` counts only scalar/struct members
+/// with `"encoding": "inplace"` -- `mapping`/`dynamic_array`/`bytes`
+/// members occupy no fixed slot of their own (their data lives at
+/// `keccak256`-derived offsets) and aren't counted here.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageLayout {
+    pub base_slots: u64,
+}
+
+#[derive(Deserialize)]
+struct SolcLayout {
+    storage: Vec<SolcStorageEntry>,
+    types: HashMap<String, SolcType>,
+}
+
+#[derive(Deserialize)]
+struct SolcStorageEntry {
+    slot: String,
+    #[serde(rename = "type")]
+    type_key: String,
+}
+
+#[derive(Deserialize)]
+struct SolcType {
+    encoding: String,
+    #[serde(rename = "numberOfBytes")]
+    number_of_bytes: String,
+}
+
+/// Parse one contract's `solc --storage-layout` JSON (the `{"storage":
+/// [...], "types": {...}}` object solc emits, not the whole combined-json
+/// output) into a [`StorageLayout`].
+pub fn parse_storage_layout(json: &str) -> anyhow::Result<StorageLayout> {
+    let layout: SolcLayout = serde_json::from_str(json)?;
+
+    let mut highest_slot_end = 0u64;
+    for entry in &layout.storage {
+        let Some(ty) = layout.types.get(&entry.type_key) else {
+            continue;
+        };
+        if ty.encoding != "inplace" {
+            // mapping / dynamic_array / bytes -- no fixed slot of its own.
+            continue;
+        }
+
+        let slot: u64 = entry.slot.parse().unwrap_or(0);
+        let bytes: u64 = ty.number_of_bytes.parse().unwrap_or(32);
+        let span_slots = bytes.div_ceil(32).max(1);
+        highest_slot_end = highest_slot_end.max(slot + span_slots);
+    }
+
+    Ok(StorageLayout { base_slots: highest_slot_end })
+}
+
+/// Load every `<address>.json` storage-layout file in `dir`, keyed by the
+/// address encoded in its filename (with or without a `0x` prefix,
+/// case-insensitive -- matching how block explorers name downloaded
+/// artifacts). Files that don't parse as a valid layout or address are
+/// skipped rather than failing the whole load, since a directory of
+/// artifacts pulled from varied sources will have some noise.
+pub fn load_layouts_from_dir(dir: &Path) -> anyhow::Result<HashMap<Address, StorageLayout>> {
+    let mut layouts = HashMap::new();
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Some(address) = parse_address(stem) else {
+            continue;
+        };
+
+        let content = std::fs::read_to_string(&path)?;
+        match parse_storage_layout(&content) {
+            Ok(layout) => {
+                layouts.insert(address, layout);
+            }
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "Skipping unparseable storage layout file");
+            }
+        }
+    }
+
+    Ok(layouts)
+}
+
+fn parse_address(s: &str) -> Option<Address> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let bytes = hex::decode(s).ok()?;
+    bytes.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_LAYOUT: &str = r#"{
+        "storage": [
+            {"slot": "0", "offset": 0, "type": "t_uint256", "label": "totalSupply"},
+            {"slot": "1", "offset": 0, "type": "t_mapping(t_address,t_uint256)", "label": "balances"},
+            {"slot": "2", "offset": 0, "type": "t_array(t_uint256)2_storage", "label": "pair"}
+        ],
+        "types": {
+            "t_uint256": {"encoding": "inplace", "numberOfBytes": "32"},
+            "t_mapping(t_address,t_uint256)": {"encoding": "mapping", "numberOfBytes": "32"},
+            "t_array(t_uint256)2_storage": {"encoding": "inplace", "numberOfBytes": "64"}
+        }
+    }"#;
+
+    #[test]
+    fn test_mapping_excluded_from_base_slots() {
+        let layout = parse_storage_layout(SAMPLE_LAYOUT).unwrap();
+        // slot 0 (1 slot) + slot 2 spanning 2 slots (64 bytes) = 4; the
+        // mapping at slot 1 contributes nothing of its own.
+        assert_eq!(layout.base_slots, 4);
+    }
+
+    #[test]
+    fn test_load_layouts_from_dir_keys_by_filename_address() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("0x1111111111111111111111111111111111111111.json"), SAMPLE_LAYOUT).unwrap();
+        std::fs::write(dir.path().join("not-a-layout.json"), "{not json").unwrap();
+
+        let layouts = load_layouts_from_dir(dir.path()).unwrap();
+        assert_eq!(layouts.len(), 1);
+        assert_eq!(layouts.get(&[0x11u8; 20]).unwrap().base_slots, 4);
+    }
+}
@@ -52,6 +52,19 @@ impl RotationState {
     pub fn hints_to_avoid(&self, target: u64) -> &[usize] {
         self.recently_used.get(&target).map(|v| v.as_slice()).unwrap_or(&[])
     }
+
+    /// Undo one [`Self::record_use`] call, for rolling back a batch
+    /// reservation that failed partway through (see
+    /// [`HintStore::reserve_hints`]). Removes the most recent matching
+    /// entry rather than the whole target's history, since `record_use`
+    /// may have been called for the same target more than once already.
+    fn unrecord_use(&mut self, target: u64, hint_id: usize) {
+        if let Some(recent) = self.recently_used.get_mut(&target) {
+            if let Some(pos) = recent.iter().rposition(|&id| id == hint_id) {
+                recent.remove(pos);
+            }
+        }
+    }
 }
 
 /// Local hint store with rotation support
@@ -67,6 +80,50 @@ pub struct HintStore {
     /// Rotation state (not persisted)
     #[serde(skip)]
     pub rotation: RotationState,
+    /// target_index -> the value [`HintStore::apply_delta_batch`] last
+    /// validated/applied for it, used to check a batch's `old_value`
+    /// against what the store's hints currently encode before mutating
+    /// any of them. Empty (trust-on-first-use) for a target that hasn't
+    /// seen a delta yet.
+    #[serde(default)]
+    pub known_values: HashMap<u64, [u8; ENTRY_SIZE]>,
+}
+
+/// One entry change to fold into a [`DeltaBatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeltaOp {
+    pub target_index: u64,
+    pub old_value: [u8; ENTRY_SIZE],
+    pub new_value: [u8; ENTRY_SIZE],
+}
+
+/// A batch of delta ops to apply atomically, advancing the store to
+/// `block_number` only if every op validates.
+#[derive(Debug, Clone, Default)]
+pub struct DeltaBatch {
+    pub block_number: u64,
+    pub ops: Vec<DeltaOp>,
+}
+
+/// An op that failed validation against the store's current known value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedOp {
+    pub op: DeltaOp,
+    pub reason: String,
+}
+
+/// Result of [`HintStore::apply_delta_batch`].
+#[derive(Debug, Clone, Default)]
+pub struct DeltaReport {
+    /// `false` if any op was rejected -- in that case nothing in the
+    /// batch was applied and `block_number` did not advance.
+    pub applied: bool,
+    /// The store's `block_number` after this call (unchanged if rejected).
+    pub block_number: u64,
+    /// target_index -> number of hint updates applied for it
+    pub hints_touched_per_target: HashMap<u64, usize>,
+    /// Non-empty only when `applied` is `false`.
+    pub rejected: Vec<RejectedOp>,
 }
 
 impl HintStore {
@@ -99,6 +156,17 @@ impl HintStore {
         Ok(())
     }
 
+    /// Open a fixed-stride, memory-mapped hint store previously written by
+    /// [`crate::MmapHintStore::build`].
+    ///
+    /// Unlike [`HintStore::load`], which deserializes every [`StoredHint`]
+    /// into a `Vec`, the returned [`crate::MmapHintStore`] reads/mutates
+    /// hint bytes in place via the mapping -- for snapshots whose hint set
+    /// doesn't fit comfortably in RAM.
+    pub fn open_mmap<P: AsRef<Path>>(path: P) -> anyhow::Result<crate::MmapHintStore> {
+        crate::MmapHintStore::open(path)
+    }
+
     /// Add hints from a manifest
     pub fn add_hints(&mut self, hints: Vec<(Subset, Hint)>, block_number: u64) {
         self.block_number = block_number;
@@ -157,6 +225,62 @@ impl HintStore {
         }
     }
 
+    /// Reserve one distinct hint per target for an entire batch, atomically
+    /// with respect to the rotation state: every target's hint is chosen
+    /// and marked used in one pass, so no two targets in `indices` can end
+    /// up reusing the same hint and the per-target rotation-avoidance
+    /// invariant ([`RotationState::hints_to_avoid`]) still holds for the
+    /// whole batch, not just one query at a time. Callers (e.g.
+    /// `PirClient::query_batch`) take this reservation up front, then issue
+    /// the actual queries against the returned, independently owned
+    /// `StoredHint` clones without touching `self` again -- letting the
+    /// requests run concurrently.
+    ///
+    /// Fails fast -- and rolls back every `record_use` already applied for
+    /// this call -- if any target has no available hint, rather than
+    /// returning a partially-reserved batch.
+    pub fn reserve_hints(&mut self, indices: &[u64]) -> anyhow::Result<Vec<StoredHint>> {
+        let mut reserved = Vec::with_capacity(indices.len());
+        let mut used_this_batch: Vec<(u64, usize)> = Vec::with_capacity(indices.len());
+        let mut taken_hint_ids: std::collections::HashSet<usize> = std::collections::HashSet::new();
+
+        for &target in indices {
+            let hints_to_avoid = self.rotation.hints_to_avoid(target);
+            let candidates: Vec<&StoredHint> = self
+                .find_all_hints_for_target(target)
+                .into_iter()
+                .filter(|h| !taken_hint_ids.contains(&h.id))
+                .collect();
+
+            // Pick randomly from the still-available hints (or from every
+            // candidate if all of them were recently used for this target).
+            let available: Vec<_> = candidates
+                .iter()
+                .filter(|h| !hints_to_avoid.contains(&h.id))
+                .collect();
+
+            let chosen = if available.is_empty() {
+                candidates.choose(&mut rand::thread_rng())
+            } else {
+                available.choose(&mut rand::thread_rng()).copied()
+            };
+
+            let Some(&hint) = chosen else {
+                for (target, hint_id) in used_this_batch {
+                    self.rotation.unrecord_use(target, hint_id);
+                }
+                anyhow::bail!("No available hint for target {} in batch", target);
+            };
+
+            taken_hint_ids.insert(hint.id);
+            self.rotation.record_use(target, hint.id);
+            used_this_batch.push((target, hint.id));
+            reserved.push(hint.clone());
+        }
+
+        Ok(reserved)
+    }
+
     /// Find a hint (simple, no rotation - for backward compatibility)
     pub fn find_hint_for_target(&self, target: u64) -> Option<&StoredHint> {
         if let Some(hint_ids) = self.index.get(&target) {
@@ -196,16 +320,90 @@ impl HintStore {
     }
 
     /// Update hints based on state changes
+    ///
+    /// Thin wrapper around [`HintStore::apply_delta_batch`] for a one-off
+    /// batch that doesn't advance `block_number`; kept for callers that
+    /// don't need batch validation or a [`DeltaReport`].
     pub fn apply_delta(&mut self, changes: &[(u64, [u8; ENTRY_SIZE], [u8; ENTRY_SIZE])]) {
-        for &(idx, ref old_value, ref new_value) in changes {
-            if let Some(hint_ids) = self.index.get(&idx) {
+        let batch = DeltaBatch {
+            block_number: self.block_number,
+            ops: changes
+                .iter()
+                .map(|&(target_index, old_value, new_value)| DeltaOp {
+                    target_index,
+                    old_value,
+                    new_value,
+                })
+                .collect(),
+        };
+        self.apply_delta_batch(batch);
+    }
+
+    /// Apply a batch of delta ops atomically, advancing `block_number` to
+    /// `batch.block_number` -- all-or-nothing, suitable for driving the
+    /// store from an ExEx delta stream.
+    ///
+    /// Ops touching the same `target_index` are coalesced: each op's
+    /// `old_value` is validated in order against what the store last
+    /// recorded for that target (trust-on-first-use if it's never seen a
+    /// delta for that target before), and the batch is rejected -- with
+    /// none of its ops applied and `block_number` left unchanged -- if any
+    /// op's `old_value` doesn't match.
+    pub fn apply_delta_batch(&mut self, batch: DeltaBatch) -> DeltaReport {
+        let mut expected: HashMap<u64, [u8; ENTRY_SIZE]> = HashMap::new();
+        let mut rejected = Vec::new();
+
+        for op in &batch.ops {
+            let current = expected
+                .get(&op.target_index)
+                .copied()
+                .or_else(|| self.known_values.get(&op.target_index).copied())
+                .unwrap_or(op.old_value);
+
+            if current != op.old_value {
+                rejected.push(RejectedOp {
+                    op: op.clone(),
+                    reason: format!(
+                        "old_value mismatch for target {}: store has {:?}, op claims {:?}",
+                        op.target_index, current, op.old_value
+                    ),
+                });
+                continue;
+            }
+            expected.insert(op.target_index, op.new_value);
+        }
+
+        if !rejected.is_empty() {
+            return DeltaReport {
+                applied: false,
+                block_number: self.block_number,
+                hints_touched_per_target: HashMap::new(),
+                rejected,
+            };
+        }
+
+        let mut hints_touched_per_target = HashMap::new();
+        for op in &batch.ops {
+            if let Some(hint_ids) = self.index.get(&op.target_index) {
+                let touched = hints_touched_per_target.entry(op.target_index).or_insert(0usize);
                 for &hint_id in hint_ids {
                     if let Some(stored) = self.hints.get_mut(hint_id) {
-                        pir_core::hint::update_hint(&mut stored.hint, old_value, new_value);
+                        pir_core::hint::update_hint(&mut stored.hint, &op.old_value, &op.new_value);
+                        *touched += 1;
                     }
                 }
             }
         }
+
+        self.known_values.extend(expected);
+        self.block_number = batch.block_number;
+
+        DeltaReport {
+            applied: true,
+            block_number: self.block_number,
+            hints_touched_per_target,
+            rejected: Vec::new(),
+        }
     }
 
     /// Reset rotation state (e.g., after long idle period)
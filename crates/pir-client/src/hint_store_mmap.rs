@@ -0,0 +1,348 @@
+//! Memory-mapped, fixed-stride on-disk [`HintStore`] backend
+//!
+//! [`HintStore::load`] deserializes every [`StoredHint`] into a `Vec`, which
+//! doesn't scale once a snapshot's hint set no longer fits comfortably in
+//! RAM. [`MmapHintStore`] is an alternate backend for that case: hints are
+//! packed into a fixed-stride file (header + one record per hint) with a
+//! sidecar `target_index -> hint_ids` index, both loaded via `mmap` so
+//! [`MmapHintStore::find_all_hints_for_target`],
+//! [`MmapHintStore::find_hint_with_rotation`], and
+//! [`MmapHintStore::apply_delta`] read/mutate hint bytes through the
+//! mapping via byte offsets rather than owning a deserialized `Vec`.
+//!
+//! `Subset` and `Hint` come from the external `pir_core` crate (not
+//! vendored in this tree), so neither has a wire size this module can
+//! assume ahead of time. Each record therefore reserves a *stride* -- the
+//! widest bincode encoding seen across all hints when the file was built --
+//! with a length prefix marking the bytes actually in use within it.
+//! `apply_delta` writes the re-encoded hint back into that same reserved
+//! region, so rotation state and deltas persist without a full re-serialize
+//! of the file.
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+
+use memmap2::MmapMut;
+use pir_core::{subset::Subset, Hint, ENTRY_SIZE};
+use rand::seq::SliceRandom;
+
+use crate::hint_store::RotationState;
+
+const MMAP_MAGIC: [u8; 4] = *b"PHS1";
+const MMAP_VERSION: u16 = 1;
+/// magic(4) + version(2) + reserved(2) + block_number(8) + record_count(8)
+/// + subset_stride(4) + hint_stride(4)
+const MMAP_HEADER_SIZE: usize = 32;
+
+/// One decoded record read out of a [`MmapHintStore`]'s mapping.
+#[derive(Debug, Clone)]
+pub struct MmapHint {
+    pub id: usize,
+    pub subset: Subset,
+    pub hint: Hint,
+}
+
+/// Fixed-stride, memory-mapped [`HintStore`] backend.
+///
+/// [`HintStore`]: crate::hint_store::HintStore
+pub struct MmapHintStore {
+    mmap: MmapMut,
+    block_number: u64,
+    record_count: u64,
+    subset_stride: u32,
+    hint_stride: u32,
+    record_stride: u64,
+    /// target_index -> hint ids (== record offsets) that contain it
+    index: HashMap<u64, Vec<usize>>,
+    rotation: RotationState,
+}
+
+impl MmapHintStore {
+    /// Build a fixed-stride file (plus its `.idx` sidecar) from an
+    /// in-memory hint set, mirroring [`HintStore::add_hints`] + `save`.
+    ///
+    /// [`HintStore::add_hints`]: crate::hint_store::HintStore::add_hints
+    pub fn build<P: AsRef<Path>>(
+        path: P,
+        hints: Vec<(Subset, Hint)>,
+        block_number: u64,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+
+        let encoded: Vec<(Vec<u8>, Vec<u8>)> = hints
+            .iter()
+            .map(|(subset, hint)| -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+                Ok((bincode::serialize(subset)?, bincode::serialize(hint)?))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let subset_stride = encoded.iter().map(|(s, _)| s.len()).max().unwrap_or(0) as u32;
+        let hint_stride = encoded.iter().map(|(_, h)| h.len()).max().unwrap_or(0) as u32;
+        let record_stride = Self::record_stride_for(subset_stride, hint_stride);
+        let record_count = encoded.len() as u64;
+
+        let file_len = MMAP_HEADER_SIZE as u64 + record_count * record_stride;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(file_len)?;
+
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        write_header(&mut mmap, block_number, record_count, subset_stride, hint_stride);
+
+        let mut index: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (id, (subset_bytes, hint_bytes)) in encoded.into_iter().enumerate() {
+            let off = MMAP_HEADER_SIZE + id * record_stride as usize;
+            write_record(&mut mmap, off, id as u64, subset_stride, &subset_bytes, &hint_bytes);
+        }
+        for (id, (subset, _hint)) in hints.iter().enumerate() {
+            for target in subset.expand() {
+                index.entry(target).or_default().push(id);
+            }
+        }
+
+        mmap.flush()?;
+        save_index(path, &index)?;
+
+        Ok(Self {
+            mmap,
+            block_number,
+            record_count,
+            subset_stride,
+            hint_stride,
+            record_stride,
+            index,
+            rotation: RotationState::new(10),
+        })
+    }
+
+    /// Open an existing fixed-stride file and its `.idx` sidecar for
+    /// in-place, mmap-backed reads and updates.
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+
+        if mmap.len() < MMAP_HEADER_SIZE {
+            anyhow::bail!("Hint store file too short to contain a header");
+        }
+        if mmap[0..4] != MMAP_MAGIC[..] {
+            anyhow::bail!("Not a packed hint store file (bad magic)");
+        }
+        let version = u16::from_le_bytes(mmap[4..6].try_into().unwrap());
+        if version != MMAP_VERSION {
+            anyhow::bail!("Unsupported packed hint store version {version}");
+        }
+        let block_number = u64::from_le_bytes(mmap[8..16].try_into().unwrap());
+        let record_count = u64::from_le_bytes(mmap[16..24].try_into().unwrap());
+        let subset_stride = u32::from_le_bytes(mmap[24..28].try_into().unwrap());
+        let hint_stride = u32::from_le_bytes(mmap[28..32].try_into().unwrap());
+        let record_stride = Self::record_stride_for(subset_stride, hint_stride);
+
+        let expected_len = MMAP_HEADER_SIZE as u64 + record_count * record_stride;
+        if (mmap.len() as u64) < expected_len {
+            anyhow::bail!(
+                "Hint store file truncated: expected at least {expected_len} bytes, got {}",
+                mmap.len()
+            );
+        }
+
+        let index = load_index(path)?;
+
+        Ok(Self {
+            mmap,
+            block_number,
+            record_count,
+            subset_stride,
+            hint_stride,
+            record_stride,
+            index,
+            rotation: RotationState::new(10),
+        })
+    }
+
+    fn record_stride_for(subset_stride: u32, hint_stride: u32) -> u64 {
+        // id(8) + subset_len(4) + subset_stride + hint_len(4) + hint_stride
+        8 + 4 + subset_stride as u64 + 4 + hint_stride as u64
+    }
+
+    fn record_offset(&self, id: usize) -> usize {
+        MMAP_HEADER_SIZE + id * self.record_stride as usize
+    }
+
+    fn subset_region(&self, off: usize) -> (usize, usize) {
+        let subset_len_off = off + 8;
+        let subset_len = u32::from_le_bytes(
+            self.mmap[subset_len_off..subset_len_off + 4].try_into().unwrap(),
+        ) as usize;
+        (subset_len_off + 4, subset_len)
+    }
+
+    fn hint_region(&self, off: usize) -> (usize, usize) {
+        let (subset_off, _) = self.subset_region(off);
+        let hint_len_off = subset_off + self.subset_stride as usize;
+        let hint_len = u32::from_le_bytes(
+            self.mmap[hint_len_off..hint_len_off + 4].try_into().unwrap(),
+        ) as usize;
+        (hint_len_off + 4, hint_len)
+    }
+
+    /// Decode the record at `id` (record offset, stable for the file's
+    /// lifetime) into an owned [`MmapHint`].
+    fn read_record(&self, id: usize) -> anyhow::Result<MmapHint> {
+        let off = self.record_offset(id);
+        let (subset_start, subset_len) = self.subset_region(off);
+        let subset: Subset = bincode::deserialize(&self.mmap[subset_start..subset_start + subset_len])?;
+
+        let (hint_start, hint_len) = self.hint_region(off);
+        let hint: Hint = bincode::deserialize(&self.mmap[hint_start..hint_start + hint_len])?;
+
+        Ok(MmapHint { id, subset, hint })
+    }
+
+    /// Find ALL hints that contain the target index (for rotation).
+    pub fn find_all_hints_for_target(&self, target: u64) -> Vec<MmapHint> {
+        self.index
+            .get(&target)
+            .into_iter()
+            .flatten()
+            .filter_map(|&id| self.read_record(id).ok())
+            .collect()
+    }
+
+    /// Find a hint for target WITH ROTATION (avoids recently used hints).
+    pub fn find_hint_with_rotation(&mut self, target: u64) -> Option<MmapHint> {
+        let hints_to_avoid = self.rotation.hints_to_avoid(target).to_vec();
+        let all_hints = self.find_all_hints_for_target(target);
+
+        if all_hints.is_empty() {
+            return None;
+        }
+
+        let available: Vec<&MmapHint> = all_hints
+            .iter()
+            .filter(|h| !hints_to_avoid.contains(&h.id))
+            .collect();
+
+        let chosen = if available.is_empty() {
+            all_hints.choose(&mut rand::thread_rng())
+        } else {
+            available.choose(&mut rand::thread_rng()).copied()
+        };
+
+        chosen.map(|hint| {
+            self.rotation.record_use(target, hint.id);
+            hint.clone()
+        })
+    }
+
+    /// Update hints in place based on state changes, writing the re-encoded
+    /// hint bytes straight back through the mapping.
+    pub fn apply_delta(&mut self, changes: &[(u64, [u8; ENTRY_SIZE], [u8; ENTRY_SIZE])]) -> anyhow::Result<()> {
+        for &(idx, ref old_value, ref new_value) in changes {
+            let Some(ids) = self.index.get(&idx).cloned() else {
+                continue;
+            };
+            for id in ids {
+                let mut record = self.read_record(id)?;
+                pir_core::hint::update_hint(&mut record.hint, old_value, new_value);
+                self.write_hint(id, &record.hint)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-encode `hint` and write it back into its reserved stride,
+    /// without touching the subset bytes or the target index.
+    fn write_hint(&mut self, id: usize, hint: &Hint) -> anyhow::Result<()> {
+        let off = self.record_offset(id);
+        let (hint_start, _old_len) = self.hint_region(off);
+        let encoded = bincode::serialize(hint)?;
+
+        if encoded.len() > self.hint_stride as usize {
+            anyhow::bail!(
+                "Hint re-encoded to {} bytes, which exceeds the store's reserved stride of {} \
+                 -- rebuild the mmap hint store to grow it",
+                encoded.len(),
+                self.hint_stride
+            );
+        }
+
+        let hint_len_off = hint_start - 4;
+        self.mmap[hint_len_off..hint_start].copy_from_slice(&(encoded.len() as u32).to_le_bytes());
+        self.mmap[hint_start..hint_start + encoded.len()].copy_from_slice(&encoded);
+
+        Ok(())
+    }
+
+    /// Flush pending writes to disk (writes are visible in-process
+    /// immediately; this only affects durability against a crash).
+    pub fn flush(&self) -> anyhow::Result<()> {
+        self.mmap.flush()?;
+        Ok(())
+    }
+
+    pub fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    pub fn len(&self) -> usize {
+        self.record_count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.record_count == 0
+    }
+}
+
+fn write_header(mmap: &mut MmapMut, block_number: u64, record_count: u64, subset_stride: u32, hint_stride: u32) {
+    mmap[0..4].copy_from_slice(&MMAP_MAGIC);
+    mmap[4..6].copy_from_slice(&MMAP_VERSION.to_le_bytes());
+    mmap[6..8].copy_from_slice(&[0u8; 2]);
+    mmap[8..16].copy_from_slice(&block_number.to_le_bytes());
+    mmap[16..24].copy_from_slice(&record_count.to_le_bytes());
+    mmap[24..28].copy_from_slice(&subset_stride.to_le_bytes());
+    mmap[28..32].copy_from_slice(&hint_stride.to_le_bytes());
+}
+
+fn write_record(
+    mmap: &mut MmapMut,
+    off: usize,
+    id: u64,
+    subset_stride: u32,
+    subset_bytes: &[u8],
+    hint_bytes: &[u8],
+) {
+    mmap[off..off + 8].copy_from_slice(&id.to_le_bytes());
+
+    let subset_len_off = off + 8;
+    mmap[subset_len_off..subset_len_off + 4].copy_from_slice(&(subset_bytes.len() as u32).to_le_bytes());
+    let subset_off = subset_len_off + 4;
+    mmap[subset_off..subset_off + subset_bytes.len()].copy_from_slice(subset_bytes);
+
+    let hint_len_off = subset_off + subset_stride as usize;
+    mmap[hint_len_off..hint_len_off + 4].copy_from_slice(&(hint_bytes.len() as u32).to_le_bytes());
+    let hint_off = hint_len_off + 4;
+    mmap[hint_off..hint_off + hint_bytes.len()].copy_from_slice(hint_bytes);
+}
+
+fn index_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".idx");
+    PathBuf::from(name)
+}
+
+fn save_index(path: &Path, index: &HashMap<u64, Vec<usize>>) -> anyhow::Result<()> {
+    let data = bincode::serialize(index)?;
+    std::fs::write(index_path(path), data)?;
+    Ok(())
+}
+
+fn load_index(path: &Path) -> anyhow::Result<HashMap<u64, Vec<usize>>> {
+    let data = std::fs::read(index_path(path))?;
+    Ok(bincode::deserialize(&data)?)
+}
@@ -0,0 +1,17 @@
+//! pir-client: Local hint storage and query execution for PIR clients
+//!
+//! Holds the client-side hint set needed to answer a PIR query without
+//! revealing the target index to the server, plus the HTTP glue to
+//! actually send a query and recover the entry from the response.
+
+pub mod hint_store;
+pub mod hint_store_mmap;
+pub mod query;
+pub mod transport;
+
+pub use hint_store::{
+    DeltaBatch, DeltaOp, DeltaReport, HintStore, HintStoreStats, RejectedOp, RotationState, StoredHint,
+};
+pub use hint_store_mmap::MmapHintStore;
+pub use query::{PirClient, PrivacyStats, QueryResult};
+pub use transport::{now_ms, QueryTransport};
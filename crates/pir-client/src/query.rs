@@ -1,17 +1,22 @@
 //! Query construction and execution with rotation support
 
-use crate::hint_store::HintStore;
+use crate::hint_store::{HintStore, StoredHint};
+use crate::transport::{now_ms, DefaultTransport, QueryTransport};
+use futures::future::try_join_all;
 use pir_core::{hint::recover_entry, subset::CompressedQuery, Hint, ENTRY_SIZE};
-use serde::{Deserialize, Serialize};
 
-/// PIR client for making private queries
+/// PIR client for making private queries. Compiles to `wasm32` as well as
+/// native targets -- the actual HTTP call is routed through
+/// [`QueryTransport`] so a browser-based light client can link the
+/// `web-sys`/`fetch` transport instead of `reqwest` without touching any
+/// of the query/recovery logic below.
 pub struct PirClient {
     /// Local hint store
     pub hints: HintStore,
     /// Query server URL
     pub server_url: String,
-    /// HTTP client
-    client: reqwest::Client,
+    /// HTTP transport
+    transport: DefaultTransport,
     /// Enable hint rotation for multi-query privacy
     pub rotation_enabled: bool,
 }
@@ -24,26 +29,13 @@ pub struct QueryResult {
     pub server_time_ms: f64,
 }
 
-/// Server response
-#[derive(Debug, Deserialize)]
-struct ServerResponse {
-    result: String,
-    query_time_ms: f64,
-}
-
-/// Query request
-#[derive(Debug, Serialize)]
-struct QueryRequest {
-    query: CompressedQuery,
-}
-
 impl PirClient {
     /// Create a new PIR client (rotation enabled by default)
     pub fn new(hints: HintStore, server_url: String) -> Self {
         Self {
             hints,
             server_url,
-            client: reqwest::Client::new(),
+            transport: DefaultTransport::new(),
             rotation_enabled: true,
         }
     }
@@ -53,70 +45,56 @@ impl PirClient {
         Self {
             hints,
             server_url,
-            client: reqwest::Client::new(),
+            transport: DefaultTransport::new(),
             rotation_enabled: false,
         }
     }
 
     /// Query for a specific database index (uses rotation if enabled)
     pub async fn query(&mut self, target_index: u64) -> anyhow::Result<QueryResult> {
-        let start = std::time::Instant::now();
-        
         // Find a hint containing the target (with or without rotation)
         let stored_hint = if self.rotation_enabled {
             self.hints
                 .find_hint_with_rotation(target_index)
                 .ok_or_else(|| anyhow::anyhow!("No hint found for target {}", target_index))?
+                .clone()
         } else {
             self.hints
                 .find_hint_for_target(target_index)
                 .ok_or_else(|| anyhow::anyhow!("No hint found for target {}", target_index))?
+                .clone()
         };
-        
-        // Clone what we need before the borrow ends
-        let subset = stored_hint.subset.clone();
-        let hint = stored_hint.hint;
-        
-        // Create compressed query
-        let query = CompressedQuery::new(&subset);
-        
-        // Send to server
-        let response: ServerResponse = self
-            .client
-            .post(format!("{}/query", self.server_url))
-            .json(&QueryRequest { query })
-            .send()
-            .await?
-            .json()
-            .await?;
-        
-        // Decode server response
-        let server_result: Hint = hex::decode(&response.result)?
-            .try_into()
-            .map_err(|_| anyhow::anyhow!("Invalid response length"))?;
-        
-        // Recover the entry
-        let entry = recover_entry(&server_result, &hint);
-        
-        let elapsed = start.elapsed();
-        
-        Ok(QueryResult {
-            entry,
-            query_time_ms: elapsed.as_secs_f64() * 1000.0,
-            server_time_ms: response.query_time_ms,
-        })
+
+        fetch_entry(&self.transport, &self.server_url, stored_hint).await
     }
 
-    /// Query multiple indices (batched, with rotation)
+    /// Query multiple indices at once. Every target's hint is reserved
+    /// up front in a single pass over `self.hints` (see
+    /// [`HintStore::reserve_hints`]), guaranteeing distinct hints per
+    /// target and a consistent rotation update even though the batch
+    /// fans out concurrently; the actual `/query` round trips then run
+    /// at once via [`try_join_all`], each against its own reserved,
+    /// independently owned [`StoredHint`] clone.
     pub async fn query_batch(&mut self, indices: &[u64]) -> anyhow::Result<Vec<QueryResult>> {
-        let mut results = Vec::with_capacity(indices.len());
-        
-        // TODO: Parallelize queries (need to handle rotation state carefully)
-        for &idx in indices {
-            results.push(self.query(idx).await?);
-        }
-        
-        Ok(results)
+        let reserved = if self.rotation_enabled {
+            self.hints.reserve_hints(indices)?
+        } else {
+            indices
+                .iter()
+                .map(|&target| {
+                    self.hints
+                        .find_hint_for_target(target)
+                        .ok_or_else(|| anyhow::anyhow!("No hint found for target {}", target))
+                        .cloned()
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+        };
+
+        let futures = reserved
+            .into_iter()
+            .map(|hint| fetch_entry(&self.transport, &self.server_url, hint));
+
+        try_join_all(futures).await
     }
 
     /// Get privacy statistics
@@ -132,6 +110,37 @@ impl PirClient {
     }
 }
 
+/// One `/query` round trip and recovery against an already-reserved hint.
+/// Takes no `&self`/`&mut self` so a batch of these can run concurrently
+/// via [`try_join_all`] without any of them borrowing `PirClient`. Measures
+/// elapsed time via [`now_ms`] rather than `std::time::Instant`, which
+/// can't be constructed on `wasm32`.
+async fn fetch_entry(
+    transport: &impl QueryTransport,
+    server_url: &str,
+    stored_hint: StoredHint,
+) -> anyhow::Result<QueryResult> {
+    let start = now_ms();
+
+    let query = CompressedQuery::new(&stored_hint.subset);
+
+    let response = transport
+        .post_query(&format!("{}/query", server_url), &query)
+        .await?;
+
+    let server_result: Hint = hex::decode(&response.result)?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Invalid response length"))?;
+
+    let entry = recover_entry(&server_result, &stored_hint.hint);
+
+    Ok(QueryResult {
+        entry,
+        query_time_ms: now_ms() - start,
+        server_time_ms: response.query_time_ms,
+    })
+}
+
 /// Privacy statistics for a target
 #[derive(Debug)]
 pub struct PrivacyStats {
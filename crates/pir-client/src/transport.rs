@@ -0,0 +1,149 @@
+//! HTTP transport for the `/query` round trip, abstracted behind
+//! [`QueryTransport`] so `query.rs`'s query/recovery logic is identical
+//! whether the binary links `reqwest` (native) or runs in a browser via
+//! `web-sys` fetch (`wasm32`). [`DefaultTransport`] picks the right
+//! concrete implementation for the target the crate is compiled for.
+
+use pir_core::subset::CompressedQuery;
+use serde::{Deserialize, Serialize};
+
+/// Server response to a `/query` POST.
+#[derive(Debug, Deserialize)]
+pub struct ServerResponse {
+    pub result: String,
+    pub query_time_ms: f64,
+}
+
+/// Query request body. Holds a reference rather than an owned
+/// `CompressedQuery` so [`QueryTransport::post_query`] (which only ever
+/// borrows its caller's query) doesn't need it to be `Clone`.
+#[derive(Debug, Serialize)]
+struct QueryRequest<'a> {
+    query: &'a CompressedQuery,
+}
+
+/// The one HTTP call a [`crate::PirClient`] needs to make. Implemented
+/// separately per target so `query.rs` stays transport-agnostic -- see
+/// [`ReqwestTransport`] (native) and [`WasmFetchTransport`] (`wasm32`).
+pub trait QueryTransport {
+    async fn post_query(&self, url: &str, query: &CompressedQuery) -> anyhow::Result<ServerResponse>;
+}
+
+/// `reqwest`-backed transport used everywhere except `wasm32`.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        Self { client: reqwest::Client::new() }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl QueryTransport for ReqwestTransport {
+    async fn post_query(&self, url: &str, query: &CompressedQuery) -> anyhow::Result<ServerResponse> {
+        let response = self
+            .client
+            .post(url)
+            .json(&QueryRequest { query })
+            .send()
+            .await?
+            .json()
+            .await?;
+        Ok(response)
+    }
+}
+
+/// `web-sys` `fetch`-backed transport for a `PirClient` compiled to
+/// `wasm32` and run in a browser (e.g. a wallet's in-browser light
+/// client), so private storage-slot lookups work without a backend proxy.
+#[cfg(target_arch = "wasm32")]
+pub struct WasmFetchTransport;
+
+#[cfg(target_arch = "wasm32")]
+impl WasmFetchTransport {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Default for WasmFetchTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl QueryTransport for WasmFetchTransport {
+    async fn post_query(&self, url: &str, query: &CompressedQuery) -> anyhow::Result<ServerResponse> {
+        use wasm_bindgen::{JsCast, JsValue};
+        use wasm_bindgen_futures::JsFuture;
+        use web_sys::{Request, RequestInit, RequestMode, Response};
+
+        let body = serde_json::to_string(&QueryRequest { query })?;
+
+        let mut opts = RequestInit::new();
+        opts.method("POST");
+        opts.mode(RequestMode::Cors);
+        opts.body(Some(&JsValue::from_str(&body)));
+
+        let request = Request::new_with_str_and_init(url, &opts)
+            .map_err(|e| anyhow::anyhow!("failed to build fetch request: {:?}", e))?;
+        request
+            .headers()
+            .set("Content-Type", "application/json")
+            .map_err(|e| anyhow::anyhow!("failed to set request content-type: {:?}", e))?;
+
+        let window = web_sys::window().ok_or_else(|| anyhow::anyhow!("no `window` in this wasm context"))?;
+        let resp_value = JsFuture::from(window.fetch_with_request(&request))
+            .await
+            .map_err(|e| anyhow::anyhow!("fetch failed: {:?}", e))?;
+        let resp: Response = resp_value
+            .dyn_into()
+            .map_err(|_| anyhow::anyhow!("fetch response was not a Response"))?;
+
+        let text_promise = resp.text().map_err(|e| anyhow::anyhow!("failed to read response body: {:?}", e))?;
+        let text = JsFuture::from(text_promise)
+            .await
+            .map_err(|e| anyhow::anyhow!("failed to await response body: {:?}", e))?;
+        let body_str = text.as_string().ok_or_else(|| anyhow::anyhow!("response body was not a string"))?;
+
+        Ok(serde_json::from_str(&body_str)?)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub type DefaultTransport = ReqwestTransport;
+#[cfg(target_arch = "wasm32")]
+pub type DefaultTransport = WasmFetchTransport;
+
+/// Current time in milliseconds, used to measure query round-trip time.
+/// `std::time::Instant` panics if constructed on `wasm32` outside a
+/// handful of supported host bindings, so native measures wall-clock time
+/// since the Unix epoch and `wasm32` defers to `js_sys::Date::now()` --
+/// both are monotonic enough for a single elapsed-time measurement.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now_ms() -> f64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+        * 1000.0
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn now_ms() -> f64 {
+    js_sys::Date::now()
+}
@@ -1,8 +1,10 @@
-use alloy_primitives::{Address, B256, U256};
+use alloy_primitives::{Address, Bytes, B256, U256};
 use alloy_sol_types::{sol, SolCall};
 use alloy_signer::SignerSync;
 use alloy_signer_local::PrivateKeySigner;
-use alloy_eips::eip7702::Authorization;
+use alloy_eips::eip7702::{Authorization, SignedAuthorization};
+use alloy_eips::eip2718::Encodable2718;
+use alloy_consensus::{SignableTransaction, TxEip7702};
 use alloy_rlp::Encodable;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -10,6 +12,14 @@ use wasm_bindgen::prelude::*;
 sol! {
     function balanceOf(address account) external view returns (uint256);
     function transfer(address to, uint256 amount) external returns (bool);
+
+    struct CallTuple {
+        address to;
+        uint256 value;
+        bytes data;
+    }
+
+    function execute(CallTuple[] calls) external payable;
 }
 
 #[derive(Serialize, Deserialize)]
@@ -48,6 +58,25 @@ pub struct BatchCallData {
     pub encoded: String,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct SetCodeTxRequest {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: String,
+    pub max_fee_per_gas: String,
+    pub gas_limit: u64,
+    pub to: String,
+    pub value: String,
+    pub data: String,
+    pub authorization_list: Vec<SignedAuthorizationResult>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SignedTxResult {
+    pub tx_hash: String,
+    pub raw_tx: String,
+}
+
 fn parse_hex(s: &str) -> Result<Vec<u8>, JsError> {
     let s = s.strip_prefix("0x").unwrap_or(s);
     hex::decode(s).map_err(|e| JsError::new(&format!("Invalid hex: {}", e)))
@@ -166,6 +195,113 @@ pub fn encode_transfer(to: &str, amount: &str) -> Result<Vec<u8>, JsError> {
     Ok(call.abi_encode().to_vec())
 }
 
+/// ABI-encodes a list of `{to, value, data}` calls into the calldata for
+/// the standard batch-executor `execute((address,uint256,bytes)[])` entry
+/// point, e.g. a 7702-delegated EOA's minimal batching contract.
+#[wasm_bindgen]
+pub fn encode_batch_calls(calls_json: &str) -> Result<String, JsError> {
+    let calls: Vec<Call> = serde_json::from_str(calls_json)
+        .map_err(|e| JsError::new(&format!("Invalid calls: {}", e)))?;
+
+    let mut encoded_calls = Vec::with_capacity(calls.len());
+    for call in calls {
+        let to: Address = call.to.parse()
+            .map_err(|e| JsError::new(&format!("Invalid call.to: {}", e)))?;
+        let value: U256 = call.value.parse()
+            .map_err(|e| JsError::new(&format!("Invalid call.value: {}", e)))?;
+        let data = if call.data.is_empty() || call.data == "0x" {
+            Vec::new()
+        } else {
+            parse_hex(&call.data)?
+        };
+        encoded_calls.push(CallTuple { to, value, data: data.into() });
+    }
+
+    let batch = executeCall { calls: encoded_calls };
+    let result = BatchCallData {
+        encoded: to_hex(&batch.abi_encode()),
+    };
+
+    serde_json::to_string(&result).map_err(|e| JsError::new(&format!("{}", e)))
+}
+
+/// Assembles, signs and RLP-encodes a complete EIP-7702 type-0x04 SetCode
+/// transaction, reusing the [`SignedAuthorizationResult`] values produced
+/// by [`sign_authorization`] as its `authorization_list`. Returns the
+/// signed payload and its hash, ready to broadcast.
+#[wasm_bindgen]
+pub fn build_set_code_tx(private_key: &str, tx_json: &str) -> Result<String, JsError> {
+    let signer = get_signer(private_key)?;
+    let req: SetCodeTxRequest = serde_json::from_str(tx_json)
+        .map_err(|e| JsError::new(&format!("Invalid tx request: {}", e)))?;
+
+    let to_addr: Address = req.to.parse()
+        .map_err(|e| JsError::new(&format!("Invalid to address: {}", e)))?;
+    let value: U256 = req.value.parse()
+        .map_err(|e| JsError::new(&format!("Invalid value: {}", e)))?;
+    let max_priority_fee_per_gas: u128 = req.max_priority_fee_per_gas.parse()
+        .map_err(|e| JsError::new(&format!("Invalid max_priority_fee_per_gas: {}", e)))?;
+    let max_fee_per_gas: u128 = req.max_fee_per_gas.parse()
+        .map_err(|e| JsError::new(&format!("Invalid max_fee_per_gas: {}", e)))?;
+    let input = if req.data.is_empty() || req.data == "0x" {
+        Bytes::new()
+    } else {
+        Bytes::from(parse_hex(&req.data)?)
+    };
+
+    let mut authorization_list = Vec::with_capacity(req.authorization_list.len());
+    for auth in &req.authorization_list {
+        let address: Address = auth.address.parse()
+            .map_err(|e| JsError::new(&format!("Invalid auth address: {}", e)))?;
+        let r_bytes: [u8; 32] = parse_hex(&auth.r)?
+            .try_into()
+            .map_err(|_| JsError::new("Invalid R length"))?;
+        let s_bytes: [u8; 32] = parse_hex(&auth.s)?
+            .try_into()
+            .map_err(|_| JsError::new("Invalid S length"))?;
+
+        let unsigned = Authorization {
+            chain_id: U256::from(auth.chain_id),
+            address,
+            nonce: auth.nonce,
+        };
+        let sig = alloy_primitives::Signature::from_scalars_and_parity(
+            B256::from(r_bytes),
+            B256::from(s_bytes),
+            auth.y_parity != 0,
+        );
+        authorization_list.push(unsigned.into_signed(sig));
+    }
+
+    let tx = TxEip7702 {
+        chain_id: req.chain_id,
+        nonce: req.nonce,
+        max_priority_fee_per_gas,
+        max_fee_per_gas,
+        gas_limit: req.gas_limit,
+        to: to_addr,
+        value,
+        input,
+        access_list: Default::default(),
+        authorization_list,
+    };
+
+    let sig_hash = tx.signature_hash();
+    let sig = signer.sign_hash_sync(&sig_hash)
+        .map_err(|e| JsError::new(&format!("Signing failed: {}", e)))?;
+    let signed_tx = tx.into_signed(sig);
+
+    let mut rlp_buf = Vec::new();
+    signed_tx.encode_2718(&mut rlp_buf);
+
+    let result = SignedTxResult {
+        tx_hash: to_hex(signed_tx.hash().as_slice()),
+        raw_tx: to_hex(&rlp_buf),
+    };
+
+    serde_json::to_string(&result).map_err(|e| JsError::new(&format!("{}", e)))
+}
+
 #[wasm_bindgen]
 pub fn keccak256(data: &[u8]) -> Vec<u8> {
     alloy_primitives::keccak256(data).to_vec()
@@ -10,7 +10,12 @@ use lane_builder::ReloadClient;
 use lane_builder::reload::ReloadResult;
 
 mod mock_server {
-    use axum::{extract::State, routing::post, Router};
+    use axum::{
+        extract::{Query, State},
+        response::IntoResponse,
+        routing::post,
+        Router,
+    };
     use std::sync::atomic::{AtomicU64, Ordering};
     use std::sync::Arc;
     use tokio::net::TcpListener;
@@ -29,6 +34,7 @@ mod mock_server {
 
         let app = Router::new()
             .route("/admin/reload", post(handle_reload))
+            .route("/admin/reload/poll", axum::routing::get(handle_reload_poll))
             .route("/health", axum::routing::get(|| async { "ok" }))
             .with_state(state.clone());
 
@@ -59,9 +65,56 @@ mod mock_server {
             hot_loaded: true,
             cold_loaded: true,
             mmap_mode: true,
+            causality_token: format!("block:{}", new_block),
         })
     }
 
+    #[derive(serde::Deserialize)]
+    struct PollParams {
+        after: String,
+        #[serde(default = "default_poll_timeout_ms")]
+        timeout_ms: u64,
+    }
+
+    fn default_poll_timeout_ms() -> u64 {
+        5_000
+    }
+
+    /// Minimal stand-in for `ServerState::wait_for_reload`: blocks until
+    /// `current_block` advances past `after`, or `timeout_ms` elapses.
+    async fn handle_reload_poll(
+        State(state): State<MockState>,
+        Query(params): Query<PollParams>,
+    ) -> axum::response::Response {
+        let after: u64 = params
+            .after
+            .strip_prefix("block:")
+            .and_then(|b| b.parse().ok())
+            .unwrap_or(0);
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(params.timeout_ms);
+
+        loop {
+            let current = state.current_block.load(Ordering::SeqCst);
+            if current > after {
+                return axum::Json(lane_builder::reload::ReloadResult {
+                    old_block_number: Some(after),
+                    new_block_number: Some(current),
+                    reload_duration_ms: 0,
+                    hot_loaded: true,
+                    cold_loaded: true,
+                    mmap_mode: true,
+                    causality_token: format!("block:{}", current),
+                })
+                .into_response();
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return axum::http::StatusCode::NO_CONTENT.into_response();
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
     use std::time::Duration;
 }
 
@@ -177,3 +230,42 @@ async fn test_reload_result_fields() {
     assert_eq!(second.old_block_number, Some(1));
     assert_eq!(second.new_block_number, Some(2));
 }
+
+#[tokio::test]
+async fn test_poll_until_waits_for_next_reload() {
+    let (url, _state) = mock_server::spawn_mock_server(19007).await;
+    let client = ReloadClient::new(&url);
+
+    let first = client.reload().await.expect("reload should succeed");
+    assert_eq!(first.new_block_number, Some(1));
+
+    let waiter = {
+        let client = client.clone();
+        let token = first.causality_token.clone();
+        tokio::spawn(async move { client.poll_until(&token, Duration::from_secs(5)).await })
+    };
+
+    // Give the poll a moment to start blocking before the next reload lands.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let second = client.reload().await.expect("reload should succeed");
+    assert_eq!(second.new_block_number, Some(2));
+
+    let polled = waiter
+        .await
+        .expect("poll task should not panic")
+        .expect("poll should not error")
+        .expect("poll should see the second reload before timing out");
+    assert_eq!(polled.new_block_number, Some(2));
+}
+
+#[tokio::test]
+async fn test_poll_until_times_out() {
+    let (url, _state) = mock_server::spawn_mock_server(19008).await;
+    let client = ReloadClient::new(&url);
+
+    let result = client
+        .poll_until("block:999", Duration::from_millis(200))
+        .await
+        .expect("poll should not error on timeout");
+    assert!(result.is_none());
+}
@@ -6,8 +6,9 @@
 //! - Fast tests (no #[ignore]): run in CI, complete in <30s total
 //! - Slow tests (#[ignore]): load/soak tests for manual/nightly runs
 
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use inspire_core::{Lane, TwoLaneConfig};
@@ -17,6 +18,7 @@ use inspire_pir::rlwe::RlweSecretKey;
 use inspire_pir::{extract_with_variant, query as pir_query, EncodedDatabase, ServerCrs};
 use inspire_server::{create_router, create_shared_state, DbSnapshot, SharedState};
 use lane_builder::{test_params, TwoLaneSetup};
+use rand::Rng;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tokio::net::TcpListener;
@@ -30,6 +32,15 @@ pub struct TestHarness {
     pub http: Client,
     pub hot_crs: Option<ServerCrs>,
     pub cold_crs: Option<ServerCrs>,
+    /// Last-seen [`CrsResponse`] per lane, keyed off its `etag`. Populated
+    /// by `get_crs_if_changed` and consulted by `query_and_extract` (and
+    /// friends) so repeated queries against an unchanged lane skip the
+    /// `/crs/{lane}` round trip entirely.
+    crs_cache: Mutex<HashMap<Lane, CrsResponse>>,
+    /// Backoff policy `query_and_extract` (and friends) use to ride out a
+    /// `/admin/reload` window instead of surfacing a transient failure to
+    /// the caller. See [`RetryConfig`].
+    pub retry_config: RetryConfig,
 }
 
 impl TestHarness {
@@ -81,7 +92,12 @@ impl TestHarness {
         let server_url = format!("http://{}", addr);
 
         tokio::spawn(async move {
-            axum::serve(listener, router).await.ok();
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+            .ok();
         });
 
         let http = Client::builder()
@@ -112,6 +128,8 @@ impl TestHarness {
             http,
             hot_crs: Some(result.hot_crs),
             cold_crs: Some(result.cold_crs),
+            crs_cache: Mutex::new(HashMap::new()),
+            retry_config: RetryConfig::default(),
         }
     }
 
@@ -145,34 +163,125 @@ impl TestHarness {
             .await
     }
 
-    /// Send a PIR query and get the response
+    /// Conditional `GET /crs/{lane}`: sends `etag` as `If-None-Match` and
+    /// returns `None` on a `304 Not Modified` (the server's CRS hasn't
+    /// changed since `etag` was issued) instead of re-downloading and
+    /// re-parsing the (large) CRS body.
+    pub async fn get_crs_if_changed(
+        &self,
+        lane: Lane,
+        etag: &str,
+    ) -> reqwest::Result<Option<CrsResponse>> {
+        let resp = self
+            .http
+            .get(format!("{}/crs/{}", self.server_url, lane))
+            .header(reqwest::header::IF_NONE_MATCH, etag)
+            .send()
+            .await?;
+
+        if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+
+        Ok(Some(resp.json().await?))
+    }
+
+    /// Fetch a lane's CRS, reusing the last-seen copy (keyed by ETag) when
+    /// the server reports it's unchanged. This is what `query_and_extract`
+    /// and friends call instead of `get_crs` directly, so repeated queries
+    /// against the same lane skip the CRS download after the first one.
+    async fn cached_crs(&self, lane: Lane) -> reqwest::Result<CrsResponse> {
+        let cached_etag = self.crs_cache.lock().unwrap().get(&lane).map(|c| c.etag.clone());
+
+        let resp = match cached_etag {
+            Some(etag) => match self.get_crs_if_changed(lane, &etag).await? {
+                Some(fresh) => fresh,
+                None => return Ok(self.crs_cache.lock().unwrap()[&lane].clone()),
+            },
+            None => self.get_crs(lane).await?,
+        };
+
+        self.crs_cache.lock().unwrap().insert(lane, resp.clone());
+        Ok(resp)
+    }
+
+    /// Run `op` with full-jitter exponential backoff (see [`RetryConfig`]),
+    /// riding out connection errors and 5xx/503 responses (e.g. an
+    /// `/admin/reload` swap in progress) instead of surfacing them to the
+    /// caller. 4xx responses (e.g. the 400 from an invalid lane) return
+    /// immediately -- retrying a client error would just fail again.
+    async fn with_retry<F, Fut>(&self, op: F) -> reqwest::Result<reqwest::Response>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = reqwest::Result<reqwest::Response>>,
+    {
+        let mut attempt = 0u32;
+        loop {
+            let outcome = op().await;
+            let should_retry = match &outcome {
+                Ok(resp) => resp.status().is_server_error(),
+                Err(_) => true,
+            };
+
+            if !should_retry || attempt >= self.retry_config.max_retries {
+                return outcome;
+            }
+
+            tokio::time::sleep(self.retry_config.backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send a PIR query and get the response, resiliently (see [`Self::with_retry`])
     pub async fn query_raw(
         &self,
         lane: Lane,
         query: &inspire_pir::ClientQuery,
     ) -> reqwest::Result<reqwest::Response> {
-        self.http
-            .post(format!("{}/query/{}", self.server_url, lane))
-            .json(&QueryRequest {
-                query: query.clone(),
-            })
-            .send()
-            .await
+        self.with_retry(|| {
+            self.http
+                .post(format!("{}/query/{}", self.server_url, lane))
+                .json(&QueryRequest {
+                    query: query.clone(),
+                })
+                .send()
+        })
+        .await
     }
 
-    /// Send a seeded PIR query
+    /// Send a seeded PIR query, resiliently (see [`Self::with_retry`])
     pub async fn query_seeded_raw(
         &self,
         lane: Lane,
         query: &inspire_pir::SeededClientQuery,
     ) -> reqwest::Result<reqwest::Response> {
-        self.http
-            .post(format!("{}/query/{}/seeded", self.server_url, lane))
-            .json(&SeededQueryRequest {
-                query: query.clone(),
-            })
-            .send()
-            .await
+        self.with_retry(|| {
+            self.http
+                .post(format!("{}/query/{}/seeded", self.server_url, lane))
+                .json(&SeededQueryRequest {
+                    query: query.clone(),
+                })
+                .send()
+        })
+        .await
+    }
+
+    /// `X-RateLimit-*` headers read off a query response, see
+    /// `inspire_server::rate_limit::rate_limit_middleware`
+    pub fn rate_limit_headers(resp: &reqwest::Response) -> RateLimitHeaders {
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+        };
+
+        RateLimitHeaders {
+            limit: header("x-ratelimit-limit"),
+            remaining: header("x-ratelimit-remaining"),
+            reset: header("x-ratelimit-reset"),
+            retry_after: header("retry-after"),
+        }
     }
 
     /// Perform a full PIR query and extract the result
@@ -182,7 +291,7 @@ impl TestHarness {
             Lane::Cold => self.cold_crs.as_ref().expect("cold CRS"),
         };
 
-        let crs_resp = self.get_crs(lane).await?;
+        let crs_resp = self.cached_crs(lane).await?;
         let shard_config = crs_resp.shard_config;
 
         let mut sampler = GaussianSampler::new(crs.params.sigma);
@@ -219,7 +328,7 @@ impl TestHarness {
             Lane::Cold => self.cold_crs.as_ref().expect("cold CRS"),
         };
 
-        let crs_resp = self.get_crs(lane).await?;
+        let crs_resp = self.cached_crs(lane).await?;
         let shard_config = crs_resp.shard_config;
 
         let mut sampler = GaussianSampler::new(crs.params.sigma);
@@ -255,7 +364,7 @@ impl TestHarness {
             Lane::Cold => self.cold_crs.as_ref().expect("cold CRS"),
         };
 
-        let crs_resp = self.get_crs(lane).await?;
+        let crs_resp = self.cached_crs(lane).await?;
         let shard_config = crs_resp.shard_config;
 
         let mut sampler = GaussianSampler::new(crs.params.sigma);
@@ -265,12 +374,14 @@ impl TestHarness {
             .map_err(|e| anyhow::anyhow!("{}", e))?;
 
         let resp = self
-            .http
-            .post(format!("{}/query/{}/binary", self.server_url, lane))
-            .json(&QueryRequest {
-                query: client_query,
+            .with_retry(|| {
+                self.http
+                    .post(format!("{}/query/{}/binary", self.server_url, lane))
+                    .json(&QueryRequest {
+                        query: client_query.clone(),
+                    })
+                    .send()
             })
-            .send()
             .await?;
 
         let bytes = resp.bytes().await?;
@@ -302,7 +413,7 @@ impl TestHarness {
             Lane::Cold => self.cold_crs.as_ref().expect("cold CRS"),
         };
 
-        let crs_resp = self.get_crs(lane).await?;
+        let crs_resp = self.cached_crs(lane).await?;
         let shard_config = crs_resp.shard_config;
 
         let mut sampler = GaussianSampler::new(crs.params.sigma);
@@ -313,12 +424,14 @@ impl TestHarness {
                 .map_err(|e| anyhow::anyhow!("{}", e))?;
 
         let resp = self
-            .http
-            .post(format!("{}/query/{}/seeded/binary", self.server_url, lane))
-            .json(&SeededQueryRequest {
-                query: seeded_query,
+            .with_retry(|| {
+                self.http
+                    .post(format!("{}/query/{}/seeded/binary", self.server_url, lane))
+                    .json(&SeededQueryRequest {
+                        query: seeded_query.clone(),
+                    })
+                    .send()
             })
-            .send()
             .await?;
 
         let bytes = resp.bytes().await?;
@@ -337,6 +450,74 @@ impl TestHarness {
         Ok(entry)
     }
 
+    /// Perform a batch of full PIR queries via `POST /query/{lane}/batch`
+    ///
+    /// Frames each index's query upload, reads back the length-prefixed
+    /// binary stream of `ServerResponse`s, and extracts each one -- one
+    /// entry per requested index, in order.
+    pub async fn query_batch_and_extract(
+        &self,
+        lane: Lane,
+        indices: &[u64],
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        let crs = match lane {
+            Lane::Hot => self.hot_crs.as_ref().expect("hot CRS"),
+            Lane::Cold => self.cold_crs.as_ref().expect("cold CRS"),
+        };
+
+        let crs_resp = self.cached_crs(lane).await?;
+        let shard_config = crs_resp.shard_config;
+
+        let mut sampler = GaussianSampler::new(crs.params.sigma);
+        let sk = RlweSecretKey::generate(&crs.params, &mut sampler);
+
+        let mut client_states = Vec::with_capacity(indices.len());
+        let mut queries = Vec::with_capacity(indices.len());
+        for &index in indices {
+            let (client_state, client_query) =
+                pir_query(crs, index, &shard_config, &sk, &mut sampler)
+                    .map_err(|e| anyhow::anyhow!("{}", e))?;
+            client_states.push(client_state);
+            queries.push(client_query);
+        }
+
+        let resp = self
+            .http
+            .post(format!("{}/query/{}/batch", self.server_url, lane))
+            .json(&BatchQueryRequest { queries })
+            .send()
+            .await?;
+        let bytes = resp.bytes().await?;
+
+        let mut entries = Vec::with_capacity(indices.len());
+        let mut offset = 0usize;
+        for client_state in &client_states {
+            let len = u32::from_be_bytes(
+                bytes[offset..offset + 4]
+                    .try_into()
+                    .expect("4-byte length prefix"),
+            ) as usize;
+            offset += 4;
+            let frame = &bytes[offset..offset + len];
+            offset += len;
+
+            let server_response = inspire_pir::ServerResponse::from_binary(frame)
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+            let entry = extract_with_variant(
+                crs,
+                client_state,
+                &server_response,
+                32,
+                InspireVariant::OnePacking,
+            )
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
     /// Reload databases via admin endpoint
     pub async fn reload(&self) -> reqwest::Result<ReloadResult> {
         self.http
@@ -347,6 +528,30 @@ impl TestHarness {
             .await
     }
 
+    /// Long-poll `/admin/reload/poll` for a reload past `after`. Returns
+    /// `None` on a 204 (timeout), `Some` on a 200 (advanced).
+    pub async fn poll_reload(
+        &self,
+        after: &str,
+        timeout: Duration,
+    ) -> reqwest::Result<Option<ReloadResult>> {
+        let response = self
+            .http
+            .get(format!("{}/admin/reload/poll", self.server_url))
+            .query(&[
+                ("after", after.to_string()),
+                ("timeout_ms", timeout.as_millis().to_string()),
+            ])
+            .send()
+            .await?;
+
+        if response.status() == reqwest::StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+
+        Ok(Some(response.json().await?))
+    }
+
     /// Get current snapshot
     pub fn snapshot(&self) -> Arc<DbSnapshot> {
         self.state.load_snapshot_full()
@@ -369,6 +574,11 @@ struct SeededQueryRequest {
     query: inspire_pir::SeededClientQuery,
 }
 
+#[derive(Serialize)]
+struct BatchQueryRequest {
+    queries: Vec<inspire_pir::ClientQuery>,
+}
+
 #[derive(Deserialize)]
 pub struct ServerInfo {
     pub version: String,
@@ -389,12 +599,16 @@ pub struct HealthResponse {
     pub mmap_mode: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct CrsResponse {
     pub crs: String,
     pub lane: Lane,
     pub entry_count: u64,
     pub shard_config: inspire_pir::params::ShardConfig,
+    /// Strong ETag for this CRS. Reused as `If-None-Match` by
+    /// `TestHarness::get_crs_if_changed` so repeated queries can skip
+    /// re-downloading and re-parsing an unchanged CRS.
+    pub etag: String,
 }
 
 #[derive(Deserialize)]
@@ -403,6 +617,50 @@ pub struct QueryResponse {
     pub lane: Lane,
 }
 
+/// `X-RateLimit-*`/`Retry-After` headers parsed off a query response by
+/// `TestHarness::rate_limit_headers`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimitHeaders {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    pub reset: Option<u64>,
+    pub retry_after: Option<u64>,
+}
+
+/// Full-jitter exponential backoff policy for [`TestHarness::with_retry`]
+///
+/// On attempt `n` (0-indexed), sleeps a random duration in
+/// `[0, min(cap, base * 2^n))` before retrying.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    pub base: Duration,
+    pub cap: Duration,
+    pub max_retries: u32,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_millis(50),
+            cap: Duration::from_secs(5),
+            max_retries: 5,
+        }
+    }
+}
+
+impl RetryConfig {
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self.base.as_secs_f64() * 2f64.powi(attempt as i32);
+        let capped = exp.min(self.cap.as_secs_f64());
+        let jittered = if capped > 0.0 {
+            rand::thread_rng().gen_range(0.0..capped)
+        } else {
+            0.0
+        };
+        Duration::from_secs_f64(jittered)
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ReloadResult {
     pub old_block_number: Option<u64>,
@@ -411,6 +669,8 @@ pub struct ReloadResult {
     pub hot_loaded: bool,
     pub cold_loaded: bool,
     pub mmap_mode: bool,
+    #[serde(default)]
+    pub causality_token: String,
 }
 
 // ============================================================================
@@ -476,6 +736,64 @@ async fn test_hot_lane_query() {
     );
 }
 
+#[tokio::test]
+async fn test_batch_query_matches_single_queries() {
+    let harness = TestHarness::new().await;
+    let indices = [3u64, 17, 42, 100];
+
+    let batch_entries = harness
+        .query_batch_and_extract(Lane::Hot, &indices)
+        .await
+        .expect("batch query");
+
+    assert_eq!(batch_entries.len(), indices.len());
+    for (i, &index) in indices.iter().enumerate() {
+        let single_entry = harness
+            .query_and_extract(Lane::Hot, index)
+            .await
+            .expect("single query");
+        assert_eq!(
+            batch_entries[i], single_entry,
+            "batch entry {i} (index {index}) should match single-query result"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_rapid_queries_are_rate_limited() {
+    let harness = TestHarness::new().await;
+    let crs = harness.hot_crs.as_ref().expect("hot CRS");
+
+    let mut sampler = GaussianSampler::new(crs.params.sigma);
+    let sk = RlweSecretKey::generate(&crs.params, &mut sampler);
+    let shard_config = harness.get_crs(Lane::Hot).await.expect("hot CRS").shard_config;
+    let (_client_state, client_query) = pir_query(crs, 0, &shard_config, &sk, &mut sampler)
+        .expect("query");
+
+    // Default per-client bucket capacity is 10 tokens, so the 11th rapid
+    // request from the same (test harness's) IP should be rejected.
+    let mut last_status = None;
+    for _ in 0..10 {
+        let resp = harness
+            .query_raw(Lane::Hot, &client_query)
+            .await
+            .expect("request should complete");
+        last_status = Some(resp.status());
+    }
+    assert_eq!(last_status, Some(reqwest::StatusCode::OK));
+
+    let resp = harness
+        .query_raw(Lane::Hot, &client_query)
+        .await
+        .expect("request should complete");
+    assert_eq!(resp.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+    let headers = TestHarness::rate_limit_headers(&resp);
+    assert_eq!(headers.remaining, Some(0));
+    let retry_after = headers.retry_after.expect("Retry-After header");
+    assert!(retry_after > 0 && retry_after <= 2, "sensible Retry-After: {retry_after}");
+}
+
 /// Minimal test: directly use PIR functions without HTTP
 /// to isolate whether the issue is in HTTP layer or PIR layer
 #[tokio::test]
@@ -741,6 +1059,48 @@ async fn test_basic_reload() {
     );
 }
 
+#[tokio::test]
+async fn test_reload_poll_waits_for_next_reload() {
+    let harness = TestHarness::new().await;
+
+    let first = harness.reload().await.expect("reload");
+    assert!(!first.causality_token.is_empty());
+
+    let waiter = {
+        let server_url = harness.server_url.clone();
+        let http = harness.http.clone();
+        let token = first.causality_token.clone();
+        tokio::spawn(async move {
+            http.get(format!("{}/admin/reload/poll", server_url))
+                .query(&[("after", token.as_str()), ("timeout_ms", "5000")])
+                .send()
+                .await
+        })
+    };
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    harness.reload().await.expect("second reload");
+
+    let response = waiter
+        .await
+        .expect("poll task should not panic")
+        .expect("poll request should succeed");
+    assert!(response.status().is_success());
+}
+
+#[tokio::test]
+async fn test_reload_poll_times_out_without_a_new_reload() {
+    let harness = TestHarness::new().await;
+    let first = harness.reload().await.expect("reload");
+
+    let polled = harness
+        .poll_reload(&first.causality_token, Duration::from_millis(200))
+        .await
+        .expect("poll request should succeed");
+
+    assert!(polled.is_none(), "No further reload happened, poll should time out");
+}
+
 #[tokio::test]
 async fn test_reload_while_querying() {
     let harness = TestHarness::new().await;
@@ -757,6 +1117,8 @@ async fn test_reload_while_querying() {
                 .expect("HTTP client"),
             hot_crs: harness.hot_crs.clone(),
             cold_crs: harness.cold_crs.clone(),
+            crs_cache: Mutex::new(HashMap::new()),
+            retry_config: harness.retry_config,
         };
         tokio::spawn(async move {
             for i in 0..5 {
@@ -770,6 +1132,48 @@ async fn test_reload_while_querying() {
     query_task.await.expect("queries should complete");
 }
 
+#[tokio::test]
+async fn test_resilient_queries_survive_reloads() {
+    let harness = TestHarness::new().await;
+
+    let query_task = {
+        let h = TestHarness {
+            server_url: harness.server_url.clone(),
+            config: harness.config.clone(),
+            state: harness.state.clone(),
+            temp_dir: harness.temp_dir.clone(),
+            http: Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .expect("HTTP client"),
+            hot_crs: harness.hot_crs.clone(),
+            cold_crs: harness.cold_crs.clone(),
+            crs_cache: Mutex::new(HashMap::new()),
+            retry_config: harness.retry_config,
+        };
+        tokio::spawn(async move {
+            let mut results = Vec::new();
+            for i in 0..30 {
+                results.push(h.query_and_extract(Lane::Hot, i % 256).await);
+            }
+            results
+        })
+    };
+
+    for _ in 0..3 {
+        harness.reload().await.expect("reload");
+    }
+
+    let results = query_task.await.expect("query task should complete");
+    for result in &results {
+        assert!(
+            result.is_ok(),
+            "resilient query should ride out reload windows: {:?}",
+            result.as_ref().err()
+        );
+    }
+}
+
 #[tokio::test]
 async fn test_concurrent_queries_during_reload() {
     let harness = TestHarness::new().await;
@@ -1003,7 +1407,12 @@ impl RangeDeltaTestHarness {
         let server_url = format!("http://{}", addr);
 
         tokio::spawn(async move {
-            axum::serve(listener, router).await.ok();
+            axum::serve(
+                listener,
+                router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+            .ok();
         });
 
         let http = Client::builder()